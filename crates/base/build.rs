@@ -13,36 +13,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // this is commented out to shorten build times when proto generated code didn't change
     ////////////////////
 
-    // We add serde support for Protobuf structs that needs to be persisted locally as member of rust structs.
+    // We add serde support for Protobuf structs that needs to be persisted locally as member of rust structs
+    // and for snp api types which are served as json by the provider json/http gateway.
 
     /*
     std::env::set_var("OUT_DIR", "src");
     tonic_build::configure()
-        .type_attribute(
-            "EntityId",
-            "#[derive(serde::Serialize, serde::Deserialize)]",
-        )
-        //.type_attribute("EntityId", "#[serde(default)]")
-        .type_attribute(
-            "PublicKey",
-            "#[derive(serde::Serialize, serde::Deserialize)]",
-        )
-        //.type_attribute("PublicKey", "#[serde(default)]")
-        .type_attribute(
-            "ProviderNetInfo",
-            "#[derive(serde::Serialize, serde::Deserialize)]",
-        )
-        //.type_attribute("ProviderNetInfo", "#[serde(default)]")
-        .type_attribute(
-            "DialupInfo",
-            "#[derive(serde::Serialize, serde::Deserialize)]",
-        )
-        //.type_attribute("DialupInfo", "#[serde(default)]")
-        .type_attribute(
-            "Signature",
-            "#[derive(serde::Serialize, serde::Deserialize)]",
-        )
-        //.type_attribute("Signature", "#[serde(default)]")
+        .type_attribute(".snp", "#[derive(serde::Serialize, serde::Deserialize)]")
         .build_server(true)
         .out_dir("src/snp")
         .format(true)
@@ -61,6 +38,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/snp/server_api/public_service.proto",
                 "proto/snp/client_to_client/channels.proto",
                 "proto/snp/client_to_client/paid_items.proto",
+                "proto/snp/client_to_client/receipts.proto",
+                "proto/snp/client_to_client/attachments.proto",
                 "proto/upsetter/simple_client/simple_client_service.proto",
                 "proto/upsetter/server_admin/server_admin.proto",
             ],
@@ -2,16 +2,34 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use anyhow::Result;
+use crate::signatures::SigningKey;
+use anyhow::{bail, Result};
 
 /// A signed message with verifier public key for verification in the message
 pub trait Signed {
-    fn sign(&mut self, signer: &ed25519_dalek::Keypair) -> Result<()>;
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()>;
     fn verify_signature(&self) -> Result<()>;
 }
 
-/// Signed message with external verifier
+/// Signed message with external verifier. The verifier public key is provided as raw data of its signature scheme.
 pub trait SignedWithExternalVerifier {
-    fn sign(&mut self, signer: &ed25519_dalek::Keypair) -> Result<()>;
-    fn verify_signature(&self, signer: &ed25519_dalek::PublicKey) -> Result<()>;
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()>;
+    fn verify_signature(&self, signer: &[u8]) -> Result<()>;
+}
+
+/// Data designed for a specific SNP network. Data from other networks must not be mixed with a network's data
+pub trait NetworkScoped {
+    fn get_net_id(&self) -> u32;
+
+    /// Verify the data is designed for the network with the provided id
+    fn verify_net_id(&self, net_id: u32) -> Result<()> {
+        if self.get_net_id() != net_id {
+            bail!(
+                "data is for network {} but expected network {}",
+                self.get_net_id(),
+                net_id
+            )
+        }
+        Ok(())
+    }
 }
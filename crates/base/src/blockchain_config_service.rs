@@ -23,6 +23,8 @@ pub const GRPC_HOST_CONFIG_KEY: &str = "grpc_host"; // grpc api service host
 pub const GRPC_SERVER_PORT_CONFIG_KEY: &str = "grpc_server_port"; // grpc api service port
 pub const NET_ID_CONFIG_KEY: &str = "net_id";
 pub const START_GRPC_SERVICE_CONFIG_KEY: &str = "start_grpc_service";
+pub const TLS_CERT_FILE_CONFIG_KEY: &str = "tls_cert_file"; // pem cert chain. grpc server uses tls when cert and key are set
+pub const TLS_KEY_FILE_CONFIG_KEY: &str = "tls_key_file"; // pem private key
 
 pub struct BlockchainConfigService {
     config: Config,
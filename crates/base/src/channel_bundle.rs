@@ -2,9 +2,11 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::snp::snp_core_types::ChannelBundle;
-use anyhow::{anyhow, Result};
-use ed25519_dalek::Signer;
+use crate::signatures::{sign_message, SigningKey};
+use crate::snp::snp_core_types::{ChannelBundle, PricingModel};
+use crate::snp::snp_payments::Amount;
+use anyhow::{anyhow, bail, Result};
+use byteorder::{BigEndian, ByteOrder};
 
 impl ChannelBundle {
     pub fn get_channel_id(&self) -> Result<Vec<u8>> {
@@ -19,29 +21,32 @@ impl ChannelBundle {
             .clone())
     }
 
+    /// Returns the monthly subscription fee of a paid channel. None for a free channel
+    pub fn get_monthly_fee(&self) -> Option<&Amount> {
+        if self.pricing_model != PricingModel::MonthlyFee as i32 {
+            return None;
+        }
+
+        self.subscription_fee.as_ref().filter(|fee| fee.value > 0)
+    }
+
+    /// Returns the id of payments for a channel's subscription so a payment can't be used for another channel
+    pub fn get_payment_id(&self) -> Result<u64> {
+        let channel_id = self.get_channel_id()?;
+        if channel_id.len() < 8 {
+            bail!("invalid channel id")
+        }
+        Ok(BigEndian::read_u64(&channel_id[..8]))
+    }
+
     /// Sign a channel bundle by channel id and by channel creator
     pub fn sign(
         &mut self,
-        client_pair: &ed25519_dalek::Keypair,
-        channel_pair: &ed25519_dalek::Keypair,
+        client_signer: &dyn SigningKey,
+        channel_signer: &dyn SigningKey,
     ) -> Result<()> {
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: channel_pair.sign(&buf).as_ref().to_vec(),
-        });
-
-        let mut buf1 = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf1)?;
-
-        self.creator_signature = Some(Signature {
-            scheme_id: 0,
-            signature: client_pair.sign(&buf1).as_ref().to_vec(),
-        });
+        self.signature = Some(sign_message(self, channel_signer)?);
+        self.creator_signature = Some(sign_message(self, client_signer)?);
         Ok(())
     }
 }
@@ -4,6 +4,28 @@
 
 use crate::snp::snp_core_types::ChannelSubscriber;
 use anyhow::anyhow;
+use std::time::Duration;
+
+/// A paid channel subscription is paid for a month at a time
+pub const SUBSCRIPTION_PERIOD: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Time after a subscription fee is due in which a subscriber still gets updates
+pub const PAYMENT_GRACE_PERIOD: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+
+/// Time before a subscription fee is due in which a subscriber is reminded to renew its subscription
+pub const PAYMENT_REMINDER_LEAD: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+
+/// Returns true when a subscription paid through paid_through (unix nanos) should be renewed at time_stamp.
+/// A subscription which was never paid for has paid_through 0
+pub fn is_renewal_due(paid_through: u64, time_stamp: u64) -> bool {
+    time_stamp >= paid_through.saturating_sub(PAYMENT_REMINDER_LEAD.as_nanos() as u64)
+}
+
+/// Returns the time a subscription paid through paid_through is paid through after it is renewed at time_stamp.
+/// A lapsed subscription starts a new period when it is renewed
+pub fn renewed_paid_through(paid_through: u64, time_stamp: u64) -> u64 {
+    paid_through.max(time_stamp) + SUBSCRIPTION_PERIOD.as_nanos() as u64
+}
 
 impl ChannelSubscriber {
     pub fn get_subscriber_id(&self) -> anyhow::Result<&Vec<u8>> {
@@ -31,4 +53,92 @@ impl ChannelSubscriber {
 
         id == an_id
     }
+
+    /// Returns true when a subscriber should get channel updates at time_stamp - it subscribed to a free
+    /// channel or it paid for its subscription up to a grace period before time_stamp
+    pub fn is_paid_up(&self, time_stamp: u64) -> bool {
+        self.time_next_payment_due == 0
+            || time_stamp
+                <= self
+                    .time_next_payment_due
+                    .saturating_add(PAYMENT_GRACE_PERIOD.as_nanos() as u64)
+    }
+
+    /// Returns true when a paid subscriber should be reminded at time_stamp that its subscription fee is due.
+    /// A subscriber is reminded once for each due payment
+    pub fn needs_payment_reminder(&self, time_stamp: u64) -> bool {
+        if self.time_next_payment_due == 0 {
+            return false;
+        }
+
+        let reminder_time = self
+            .time_next_payment_due
+            .saturating_sub(PAYMENT_REMINDER_LEAD.as_nanos() as u64);
+
+        time_stamp >= reminder_time && self.time_reminder_sent < reminder_time
+    }
+
+    /// Extend a paid subscription by a subscription period after a subscriber paid its fee at time_stamp.
+    /// A lapsed subscription starts a new period when it is renewed
+    pub fn renew(&mut self, time_stamp: u64) {
+        self.time_next_payment_due = renewed_paid_through(self.time_next_payment_due, time_stamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paid_subscription() {
+        let day = Duration::from_secs(24 * 60 * 60).as_nanos() as u64;
+        let now = 1000 * day;
+
+        // free channel subscribers are always paid up and are never reminded
+        let mut subscriber = ChannelSubscriber::default();
+        assert!(subscriber.is_paid_up(now));
+        assert!(!subscriber.needs_payment_reminder(now));
+
+        subscriber.renew(now);
+        let due = now + 30 * day;
+        assert_eq!(subscriber.time_next_payment_due, due);
+
+        assert!(!subscriber.needs_payment_reminder(due - 4 * day));
+        assert!(subscriber.needs_payment_reminder(due - 2 * day));
+        subscriber.time_reminder_sent = due - 2 * day;
+        assert!(!subscriber.needs_payment_reminder(due + day));
+
+        assert!(subscriber.is_paid_up(due + 3 * day));
+        assert!(!subscriber.is_paid_up(due + 4 * day));
+
+        // renewing before the payment is due extends the current period
+        subscriber.renew(due - day);
+        assert_eq!(subscriber.time_next_payment_due, due + 30 * day);
+        assert!(subscriber.needs_payment_reminder(due + 28 * day));
+
+        // renewing a lapsed subscription starts a new period
+        let later = due + 100 * day;
+        subscriber.renew(later);
+        assert_eq!(subscriber.time_next_payment_due, later + 30 * day);
+    }
+
+    #[test]
+    fn test_renewal_due() {
+        let day = Duration::from_secs(24 * 60 * 60).as_nanos() as u64;
+        let now = 1000 * day;
+
+        // a subscription which was never paid for is due
+        assert!(is_renewal_due(0, now));
+
+        // a subscription is renewed once per period - it's only due again a reminder lead before it ends
+        let paid_through = renewed_paid_through(0, now);
+        assert_eq!(paid_through, now + 30 * day);
+        assert!(!is_renewal_due(paid_through, now));
+        assert!(!is_renewal_due(paid_through, now + 26 * day));
+        assert!(is_renewal_due(paid_through, now + 27 * day));
+
+        let paid_through = renewed_paid_through(paid_through, now + 27 * day);
+        assert_eq!(paid_through, now + 60 * day);
+        assert!(!is_renewal_due(paid_through, now + 27 * day));
+    }
 }
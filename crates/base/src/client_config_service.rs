@@ -4,6 +4,7 @@
 
 use crate::server_config_service::{
     DB_NAME_CONFIG_KEY, DROP_DB_CONFIG_KEY, GRPC_HOST_CONFIG_KEY, GRPC_SERVER_PORT_CONFIG_KEY,
+    MESSAGE_PADDING_CONFIG_KEY, NET_ID_CONFIG_KEY,
 };
 use anyhow::{anyhow, Result};
 use config::{Config, Environment};
@@ -11,6 +12,12 @@ use log::*;
 use xactor::*;
 
 pub const CLIENT_NAME_CONFIG_KEY: &str = "client_name";
+pub const X2DH_HYBRID_CONFIG_KEY: &str = "x2dh_hybrid"; // publish a hybrid x2dh pre-key with an ml-kem key. Peers must support hybrid x2dh
+pub const COVER_TRAFFIC_INTERVAL_CONFIG_KEY: &str = "cover_traffic_interval"; // millis between cover traffic messages to provider. 0 to disable
+pub const IDENTITY_AUDIT_INTERVAL_CONFIG_KEY: &str = "identity_audit_interval"; // millis between audits of our identity in the bundles transparency log. 0 to disable
+pub const MESSAGES_PURGE_INTERVAL_CONFIG_KEY: &str = "messages_purge_interval"; // millis between purges of expired self-destructing messages from the client db. 0 to disable
+pub const SUBSCRIPTIONS_CHECK_INTERVAL_CONFIG_KEY: &str = "subscriptions_check_interval"; // millis between checks for paid channel subscribers we need to remind to renew. 0 to disable
+pub const AUTO_RENEW_SUBSCRIPTIONS_CONFIG_KEY: &str = "auto_renew_subscriptions"; // pay the fees of our paid channels subscriptions when they are due and the channel creator reminds us
 
 pub struct ClientConfigService {
     config: Config,
@@ -31,7 +38,24 @@ impl Default for ClientConfigService {
         let mut config = Config::default();
 
         config
-            .set_default(DROP_DB_CONFIG_KEY, true)
+            .set_default(NET_ID_CONFIG_KEY, 0)
+            .unwrap()
+            .set_default(MESSAGE_PADDING_CONFIG_KEY, false)
+            .unwrap()
+            .set_default(COVER_TRAFFIC_INTERVAL_CONFIG_KEY, 0)
+            .unwrap()
+            .set_default(IDENTITY_AUDIT_INTERVAL_CONFIG_KEY, 0)
+            .unwrap()
+            .set_default(MESSAGES_PURGE_INTERVAL_CONFIG_KEY, 10_000)
+            .unwrap()
+            .set_default(SUBSCRIPTIONS_CHECK_INTERVAL_CONFIG_KEY, 3_600_000)
+            .unwrap()
+            .set_default(AUTO_RENEW_SUBSCRIPTIONS_CONFIG_KEY, false)
+            .unwrap()
+            .set_default(X2DH_HYBRID_CONFIG_KEY, false)
+            .unwrap()
+            // clients keep their identity, contacts and dr sessions across restarts
+            .set_default(DROP_DB_CONFIG_KEY, false)
             .unwrap()
             .set_default(GRPC_SERVER_PORT_CONFIG_KEY, 8081)
             .unwrap()
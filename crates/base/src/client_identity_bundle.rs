@@ -2,68 +2,58 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::api_types_extensions::Signed;
+use crate::api_types_extensions::{NetworkScoped, Signed};
+use crate::protocol_version::X2DH_VERSION;
+use crate::signatures::{sign_message, verify_message, SigningKey};
 use crate::snp::snp_core_types::{
     ClientIdentityBundle, EntityId, PreKey, ProviderIdentityBundle, PublicKey,
 };
 use crate::snp::snp_payments::Address;
 use anyhow::{anyhow, Result};
 use chrono::Utc;
-use ed25519_dalek::ed25519::signature::Signature;
-use ed25519_dalek::{Keypair, Signer, Verifier};
+use ed25519_dalek::Keypair;
 use x25519_dalek::StaticSecret;
 
+impl NetworkScoped for ClientIdentityBundle {
+    fn get_net_id(&self) -> u32 {
+        self.net_id
+    }
+
+    /// Verify the bundle and its provider bundle are for the network
+    fn verify_net_id(&self, net_id: u32) -> Result<()> {
+        if self.net_id != net_id {
+            return Err(anyhow!(
+                "client bundle is for network {} but expected network {}",
+                self.net_id,
+                net_id
+            ));
+        }
+        match self.provider_bundle.as_ref() {
+            Some(provider_bundle) => provider_bundle.verify_net_id(net_id),
+            None => Ok(()),
+        }
+    }
+}
+
 impl Signed for ClientIdentityBundle {
-    fn sign(&mut self, signer: &ed25519_dalek::Keypair) -> Result<()> {
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: signer.sign(&buf).as_ref().to_vec(),
-        });
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
     /// Verify the client public id signature on the bundle
     fn verify_signature(&self) -> Result<()> {
-        if self.client_id.is_none() {
-            return Err(anyhow!("missing client id"));
-        }
+        let pub_key = self.get_client_id_public_key()?;
 
-        let pub_key_data = self
-            .client_id
-            .as_ref()
-            .unwrap()
-            .public_key
+        let signature = self
+            .signature
             .as_ref()
-            .ok_or_else(|| anyhow!("missing public key"))?;
+            .ok_or_else(|| anyhow!("missing message signature"))?;
 
-        let pub_key = pub_key_data.as_pub_key()?;
-
-        if self.signature.is_none() {
-            return Err(anyhow!("missing message signature"));
-        }
-
-        let signature_data = self.signature.as_ref().unwrap().clone();
-
-        // create message binary data for signature verification
         let mut data = self.clone();
         data.signature = None; // remove signature from message before verification
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature_data.signature.as_slice())?;
-
-        if pub_key.verify(&buf, &signature).is_err() {
-            return Err(anyhow!("failed to verify client signature"));
-        };
-
-        Ok(())
+        verify_message(&data, pub_key.key.as_slice(), signature)
+            .map_err(|e| anyhow!("failed to verify client signature: {}", e))
     }
 }
 
@@ -129,14 +119,16 @@ impl ClientIdentityBundle {
             address: Some(payment_address.clone()),
             provider_bundle: Some(provider.clone()),
             pre_key: Some(PreKey {
-                x2dh_version: "".to_string(),
+                x2dh_version: X2DH_VERSION.into(),
                 key: Some(client_pre_key_public),
                 key_id: 0,
+                kem_key: vec![],
             }),
             one_time_keys: vec![],
             profile_image: None,
             signature: None,
-            net_id: 0,
+            // clients are on their provider's network
+            net_id: provider.net_id,
         };
 
         client_bundle.sign(key_pair)?;
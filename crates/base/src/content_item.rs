@@ -3,13 +3,13 @@
 //
 
 use crate::api_types_extensions::{Signed, SignedWithExternalVerifier};
+use crate::signatures::{sign_message, verify_message, SigningKey};
 use crate::snp::snp_core_types::{
     ChannelContentItem, CompressionCodec, ContentItem, EntityId, MediaItem, MimeType,
 };
+use crate::time_utils::expiration_time;
 use anyhow::{anyhow, bail, Result};
 use chrono::Utc;
-use ed25519_dalek::ed25519::signature::Signature;
-use ed25519_dalek::{Signer, Verifier};
 use rand_core::{OsRng, RngCore};
 
 impl ContentItem {
@@ -22,6 +22,13 @@ impl ContentItem {
         Ok(String::from_utf8(self.media_item[0].content.clone())?)
     }
 
+    /// Returns the time a self-destructing item received at received_time_stamp expires at. None if it doesn't expire.
+    /// Its ttl counts from its creation, or from its reception when its creation time is later, so the clock
+    /// of its author can't extend its life
+    pub fn expiration_time(&self, received_time_stamp: u64) -> Option<u64> {
+        expiration_time(self.created.min(received_time_stamp), self.ttl)
+    }
+
     /// Creates a new simple 1:1 text message w/o a channel
     pub fn new_one_to_one_text_message(
         text: String,
@@ -50,6 +57,18 @@ impl ContentItem {
         }
     }
 
+    /// Creates a new 1:1 message with media items such as images and files attached to its text
+    pub fn new_one_to_one_media_message(
+        text: String,
+        attachments: Vec<MediaItem>,
+        author: EntityId,
+        reply_to: u64,
+    ) -> ContentItem {
+        let mut item = ContentItem::new_one_to_one_text_message(text, author, reply_to);
+        item.media_item.extend(attachments);
+        item
+    }
+
     /// Creates a new simple text status update
     pub fn new_channel_text_message(
         text: String,
@@ -80,17 +99,8 @@ impl ContentItem {
 
 impl Signed for ContentItem {
     /// Sign content by author
-    fn sign(&mut self, author_key_pair: &ed25519_dalek::Keypair) -> Result<()> {
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: author_key_pair.sign(&buf).as_ref().to_vec(),
-        });
-
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
@@ -98,28 +108,17 @@ impl Signed for ContentItem {
         let signature = self
             .signature
             .as_ref()
-            .ok_or_else(|| anyhow!("missing author signature"))?
-            .clone();
+            .ok_or_else(|| anyhow!("missing author signature"))?;
 
         let mut data = self.clone();
         data.signature = None;
 
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature.signature.as_slice())?;
-
         let author = self
             .author
             .as_ref()
             .ok_or_else(|| anyhow!("missing author"))?;
 
-        let author_pub_key = ed25519_dalek::PublicKey::from_bytes(author.get_id()?.as_slice())?;
-
-        Ok(author_pub_key.verify(&buf, &signature)?)
+        verify_message(&data, author.get_id()?.as_slice(), signature)
     }
 }
 
@@ -134,22 +133,13 @@ impl ChannelContentItem {
 
 impl SignedWithExternalVerifier for ChannelContentItem {
     /// Sign a content by content item creator
-    fn sign(&mut self, creator_key_pair: &ed25519_dalek::Keypair) -> Result<()> {
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: creator_key_pair.sign(&buf).as_ref().to_vec(),
-        });
-
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
     /// Caller needs to provide channel creator id which is external to content item
-    fn verify_signature(&self, signer: &ed25519_dalek::PublicKey) -> Result<()> {
+    fn verify_signature(&self, signer: &[u8]) -> Result<()> {
         let item = self
             .content_item
             .as_ref()
@@ -160,25 +150,11 @@ impl SignedWithExternalVerifier for ChannelContentItem {
         let signature = self
             .signature
             .as_ref()
-            .ok_or_else(|| anyhow!("missing channel creator signature"))?
-            .clone();
+            .ok_or_else(|| anyhow!("missing channel creator signature"))?;
 
         let mut data = self.clone();
         data.signature = None;
-
-        let channel_signature =
-            ed25519_dalek::Signature::from_bytes(signature.signature.as_slice())?;
-
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        if signer.verify(&buf, &channel_signature).is_err() {
-            return Err(anyhow!("failed to verify channel creator signature"));
-        };
-
-        Ok(())
+        verify_message(&data, signer, signature)
+            .map_err(|e| anyhow!("failed to verify channel creator signature: {}", e))
     }
 }
@@ -5,17 +5,31 @@
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-use crate::snp::snp_core_types::DialupInfo;
+use crate::api_types_extensions::NetworkScoped;
+use crate::grpc_tls::client_tls_config;
+use crate::server_config_service::EndpointConfig;
+use crate::snp::snp_core_types::{ApiEndPoint, DialupInfo};
+use anyhow::{bail, Result};
+use tonic::transport::{Channel, Endpoint};
 
 impl Display for DialupInfo {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "name: {}, ", self.name)?;
         write!(f, "address: {}:{}, ", self.ip_address, self.port)?;
         write!(f, "api version: {}, ", self.api_version)?;
+        if !self.min_api_version.is_empty() {
+            write!(f, "min api version: {}, ", self.min_api_version)?;
+        }
         write!(f, "net_id: {}", self.net_id)
     }
 }
 
+impl NetworkScoped for DialupInfo {
+    fn get_net_id(&self) -> u32 {
+        self.net_id
+    }
+}
+
 impl DialupInfo {
     pub fn new() -> Self {
         DialupInfo {
@@ -25,6 +39,191 @@ impl DialupInfo {
             port: 0,
             net_id: 0,
             name: "".to_string(),
+            min_api_version: "".to_string(),
+        }
+    }
+
+    /// Create the dialup info of a configured endpoint. api_version and min_api_version are used when the
+    /// endpoint doesn't set them.
+    pub fn from_endpoint_config(
+        endpoint: &EndpointConfig,
+        api_version: &str,
+        min_api_version: &str,
+        net_id: u32,
+        name: &str,
+    ) -> Result<Self> {
+        Ok(DialupInfo {
+            end_point: DialupInfo::parse_end_point(&endpoint.end_point)? as i32,
+            api_version: endpoint
+                .api_version
+                .clone()
+                .unwrap_or_else(|| api_version.into()),
+            ip_address: endpoint.host.clone(),
+            port: endpoint.port,
+            net_id,
+            name: name.into(),
+            min_api_version: endpoint
+                .min_api_version
+                .clone()
+                .unwrap_or_else(|| min_api_version.into()),
+        })
+    }
+
+    /// Parse an endpoint type name as used in config files. e.g. grpc_web2s
+    pub fn parse_end_point(name: &str) -> Result<ApiEndPoint> {
+        match name.to_lowercase().as_str() {
+            "grpc_web2" => Ok(ApiEndPoint::GrpcWeb2),
+            "grpc_web2s" => Ok(ApiEndPoint::GrpcWeb2s),
+            "json_http" => Ok(ApiEndPoint::JsonHttp),
+            "json_https" => Ok(ApiEndPoint::JsonHttps),
+            _ => bail!("unknown endpoint type {}", name),
         }
     }
+
+    /// Returns true if the endpoint is a grpc endpoint
+    pub fn is_grpc(&self) -> bool {
+        self.end_point == ApiEndPoint::GrpcWeb2 as i32
+            || self.end_point == ApiEndPoint::GrpcWeb2s as i32
+    }
+
+    /// Returns true if the endpoint is a json/http gateway endpoint
+    pub fn is_json_http(&self) -> bool {
+        self.end_point == ApiEndPoint::JsonHttp as i32
+            || self.end_point == ApiEndPoint::JsonHttps as i32
+    }
+
+    /// Returns true if connections to the endpoint are secured by TLS
+    pub fn is_tls(&self) -> bool {
+        self.end_point == ApiEndPoint::GrpcWeb2s as i32
+            || self.end_point == ApiEndPoint::JsonHttps as i32
+    }
+
+    /// Returns the endpoint url. e.g. https://[::1]:8080
+    pub fn url(&self) -> String {
+        let scheme = if self.is_tls() { "https" } else { "http" };
+        format!("{}://{}:{}", scheme, self.ip_address, self.port)
+    }
+
+    /// Returns the best grpc endpoint from a list of endpoints advertised by a peer.
+    /// TLS secured endpoints are preferred over insecure ones. Endpoints with an unspecified type
+    /// are treated as insecure grpc endpoints for backward compatibility.
+    pub fn best_grpc_endpoint(endpoints: &[DialupInfo]) -> Option<&DialupInfo> {
+        endpoints
+            .iter()
+            .find(|e| e.end_point == ApiEndPoint::GrpcWeb2s as i32)
+            .or_else(|| {
+                endpoints.iter().find(|e| {
+                    e.end_point == ApiEndPoint::GrpcWeb2 as i32
+                        || e.end_point == ApiEndPoint::Unspecified as i32
+                })
+            })
+    }
+
+    /// Connect to a grpc endpoint. ca_cert_file is a pem file with a CA certificate to trust in addition to the
+    /// system's trusted roots - used to dial endpoints which use self-signed certificates.
+    pub async fn connect(&self, ca_cert_file: Option<String>) -> Result<Channel> {
+        let mut endpoint = Endpoint::from_shared(self.url())?;
+        if self.is_tls() {
+            endpoint = endpoint.tls_config(client_tls_config(ca_cert_file).await?)?;
+        }
+        Ok(endpoint.connect().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(end_point: ApiEndPoint, port: u32) -> DialupInfo {
+        DialupInfo {
+            end_point: end_point as i32,
+            api_version: "0.1.0".into(),
+            ip_address: "localhost".into(),
+            port,
+            net_id: 0,
+            name: "test".into(),
+            min_api_version: "".into(),
+        }
+    }
+
+    #[test]
+    fn test_best_grpc_endpoint() {
+        let insecure = endpoint(ApiEndPoint::GrpcWeb2, 8080);
+        let secure = endpoint(ApiEndPoint::GrpcWeb2s, 8081);
+        let json = endpoint(ApiEndPoint::JsonHttps, 8082);
+
+        let endpoints = vec![json.clone(), insecure.clone(), secure.clone()];
+        assert_eq!(DialupInfo::best_grpc_endpoint(&endpoints), Some(&secure));
+
+        let endpoints = vec![json.clone(), insecure.clone()];
+        assert_eq!(DialupInfo::best_grpc_endpoint(&endpoints), Some(&insecure));
+
+        assert_eq!(DialupInfo::best_grpc_endpoint(&[json]), None);
+    }
+
+    #[test]
+    fn test_from_endpoint_config() {
+        let config = EndpointConfig {
+            end_point: "json_https".into(),
+            host: "provider.example.com".into(),
+            port: 443,
+            api_version: None,
+            min_api_version: None,
+        };
+
+        let info =
+            DialupInfo::from_endpoint_config(&config, "0.1.0", "0.1.0", 1, "provider").unwrap();
+        assert_eq!(info.end_point, ApiEndPoint::JsonHttps as i32);
+        assert_eq!(info.api_version, "0.1.0");
+        assert_eq!(info.url(), "https://provider.example.com:443");
+        assert_eq!(info.net_id, 1);
+
+        let config = EndpointConfig {
+            end_point: "GRPC_WEB2S".into(),
+            api_version: Some("0.2.0".into()),
+            ..config
+        };
+        let info =
+            DialupInfo::from_endpoint_config(&config, "0.1.0", "0.1.0", 1, "provider").unwrap();
+        assert_eq!(info.end_point, ApiEndPoint::GrpcWeb2s as i32);
+        assert_eq!(info.api_version, "0.2.0");
+        assert_eq!(info.min_api_version, "0.1.0");
+
+        let config = EndpointConfig {
+            end_point: "ftp".into(),
+            ..config
+        };
+        assert!(
+            DialupInfo::from_endpoint_config(&config, "0.1.0", "0.1.0", 1, "provider").is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_net_id() {
+        let mut info = endpoint(ApiEndPoint::GrpcWeb2, 8080);
+        assert!(info.verify_net_id(0).is_ok());
+
+        info.net_id = 1;
+        assert!(info.verify_net_id(0).is_err());
+        assert!(info.verify_net_id(1).is_ok());
+
+        let bundle = crate::snp::snp_core_types::ProviderIdentityBundle {
+            net_id: 1,
+            dial_up_info: vec![info.clone(), endpoint(ApiEndPoint::GrpcWeb2, 8081)],
+            ..Default::default()
+        };
+        assert!(bundle.verify_net_id(1).is_err());
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(
+            endpoint(ApiEndPoint::GrpcWeb2, 8080).url(),
+            "http://localhost:8080"
+        );
+        assert_eq!(
+            endpoint(ApiEndPoint::JsonHttps, 8082).url(),
+            "https://localhost:8082"
+        );
+    }
 }
@@ -3,48 +3,88 @@
 //
 
 use crate::api_types_extensions::Signed;
-use crate::snp::snp_core_types::{GroupMemberBundle, GroupMembersBundle};
-use anyhow::{anyhow, Result};
-use ed25519_dalek::ed25519::signature::Signature;
-use ed25519_dalek::{Signer, Verifier};
+use crate::signatures::{sign_message, verify_message, SigningKey};
+use crate::snp::snp_core_types::{GroupAdminChange, GroupMemberBundle, GroupMembersBundle};
+use anyhow::{anyhow, bail, Result};
 
 impl GroupMembersBundle {
     pub fn get_member(&self, user_id: &[u8]) -> Option<GroupMemberBundle> {
-        match self
-            .members
+        self.members
             .iter()
-            .position(|sub| sub.get_member_id().unwrap().as_slice() == user_id)
-        {
-            Some(idx) => Some(self.members[idx].clone()),
-            None => None,
+            .find(|sub| sub.get_member_id().is_ok_and(|id| id.as_slice() == user_id))
+            .cloned()
+    }
+
+    /// Returns true iff user is the group's creator or one of its co-admins.
+    /// Malformed admin ids don't match any user
+    pub fn is_admin(&self, user_id: &[u8]) -> bool {
+        self.is_creator(user_id)
+            || self
+                .admins
+                .iter()
+                .any(|a| a.get_id().is_ok_and(|id| id.as_slice() == user_id))
+    }
+
+    /// Returns true iff user is the group's creator. A missing or malformed creator id doesn't match any user
+    pub fn is_creator(&self, user_id: &[u8]) -> bool {
+        self.creator_id
+            .as_ref()
+            .is_some_and(|c| c.get_id().is_ok_and(|id| id.as_slice() == user_id))
+    }
+
+    /// Verify the bundle was signed by the group and by the group creator and that its last change
+    /// was signed by one of the group's admins
+    pub fn verify(&self) -> Result<()> {
+        let group_signature = self
+            .group_signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group signature"))?;
+        let creator_signature = self
+            .creator_signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing creator signature"))?;
+
+        let group_id = self
+            .group_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group id"))?
+            .get_id()?;
+        let creator_id = self
+            .creator_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing creator id"))?
+            .get_id()?;
+
+        let mut data = self.clone();
+        data.creator_signature = None;
+        verify_message(&data, creator_id, creator_signature)?;
+        data.group_signature = None;
+        verify_message(&data, group_id, group_signature)?;
+
+        if let Some(change) = self.last_change.as_ref() {
+            if change.channel_id != *group_id {
+                bail!("change is for another group")
+            }
+            change.verify_signature()?;
+            let admin_id = change.get_admin_id()?;
+            if !self.is_admin(admin_id) {
+                bail!("change was not made by a group admin")
+            }
         }
+
+        Ok(())
     }
 
+    /// Sign the bundle by the group and by the group creator
     pub fn sign(
         &mut self,
-        creator_key_pair: &ed25519_dalek::Keypair,
-        group_key_pair: &ed25519_dalek::Keypair,
+        creator_signer: &dyn SigningKey,
+        group_signer: &dyn SigningKey,
     ) -> Result<()> {
         self.group_signature = None;
         self.creator_signature = None;
-
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-
-        use crate::snp::snp_core_types::Signature;
-        self.group_signature = Some(Signature {
-            scheme_id: 0,
-            signature: group_key_pair.sign(&buf).as_ref().to_vec(),
-        });
-
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-        self.creator_signature = Some(Signature {
-            scheme_id: 0,
-            signature: creator_key_pair.sign(&buf).as_ref().to_vec(),
-        });
-
+        self.group_signature = Some(sign_message(self, group_signer)?);
+        self.creator_signature = Some(sign_message(self, creator_signer)?);
         Ok(())
     }
 }
@@ -63,19 +103,26 @@ impl GroupMemberBundle {
     }
 }
 
-impl Signed for GroupMemberBundle {
-    fn sign(&mut self, member_key_pair: &ed25519_dalek::Keypair) -> Result<()> {
-        self.signature = None;
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
+impl GroupAdminChange {
+    pub fn get_admin_id(&self) -> Result<&Vec<u8>> {
+        self.admin_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing admin id"))?
+            .get_id()
+    }
 
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: member_key_pair.sign(&buf).as_ref().to_vec(),
-        });
+    pub fn get_user_id(&self) -> Result<&Vec<u8>> {
+        self.user_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing user id"))?
+            .get_id()
+    }
+}
 
+impl Signed for GroupAdminChange {
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = None;
+        self.signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
@@ -83,26 +130,130 @@ impl Signed for GroupMemberBundle {
         let signature = self
             .signature
             .as_ref()
-            .ok_or_else(|| anyhow!("missing author signature"))?
-            .clone();
+            .ok_or_else(|| anyhow!("missing admin signature"))?;
 
         let mut data = self.clone();
         data.signature = None;
+        verify_message(&data, self.get_admin_id()?, signature)
+    }
+}
 
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
+impl Signed for GroupMemberBundle {
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = None;
+        self.signature = Some(sign_message(self, signer)?);
+        Ok(())
+    }
+
+    fn verify_signature(&self) -> Result<()> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing author signature"))?;
 
-        let signature = ed25519_dalek::Signature::from_bytes(signature.signature.as_slice())?;
+        let mut data = self.clone();
+        data.signature = None;
 
         let signer = self
             .user_id
             .as_ref()
             .ok_or_else(|| anyhow!("missing user id"))?;
 
-        let signer_pub_key = ed25519_dalek::PublicKey::from_bytes(signer.get_id()?.as_slice())?;
-        Ok(signer_pub_key.verify(&buf, &signature)?)
+        verify_message(&data, signer.get_id()?.as_slice(), signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snp::snp_core_types::{EntityId, GroupAdminAction, PublicKey};
+    use rand_core::OsRng;
+
+    fn entity(key_pair: &ed25519_dalek::Keypair) -> EntityId {
+        EntityId {
+            public_key: Some(PublicKey {
+                key: key_pair.public.as_ref().to_vec(),
+            }),
+            nickname: "".into(),
+        }
+    }
+
+    #[test]
+    fn test_verify_members_bundle() {
+        let group = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let creator = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let co_admin = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let member = ed25519_dalek::Keypair::generate(&mut OsRng);
+
+        let mut bundle = GroupMembersBundle {
+            created: 1,
+            group_id: Some(entity(&group)),
+            creator_id: Some(entity(&creator)),
+            members: vec![],
+            group_signature: None,
+            creator_signature: None,
+            admins: vec![entity(&co_admin)],
+            last_change: None,
+        };
+        bundle.sign(&creator, &group).unwrap();
+        bundle.verify().unwrap();
+        assert!(bundle.is_admin(creator.public.as_ref()));
+        assert!(bundle.is_admin(co_admin.public.as_ref()));
+        assert!(!bundle.is_admin(member.public.as_ref()));
+
+        bundle.created = 2;
+        assert!(bundle.verify().is_err());
+
+        // a change signed by a co-admin
+        let mut change = GroupAdminChange {
+            time_stamp: 2,
+            channel_id: group.public.as_ref().to_vec(),
+            admin_id: Some(entity(&co_admin)),
+            action: GroupAdminAction::RemoveMember as i32,
+            user_id: Some(entity(&member)),
+            signature: None,
+        };
+        change.sign(&co_admin).unwrap();
+        bundle.last_change = Some(change.clone());
+        bundle.sign(&creator, &group).unwrap();
+        bundle.verify().unwrap();
+
+        // only admins may change the group's membership
+        change.admin_id = Some(entity(&member));
+        change.sign(&member).unwrap();
+        bundle.last_change = Some(change);
+        bundle.sign(&creator, &group).unwrap();
+        assert!(bundle.verify().is_err());
+    }
+
+    #[test]
+    fn test_malformed_ids() {
+        let creator = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let malformed = EntityId {
+            public_key: None,
+            nickname: "".into(),
+        };
+
+        let mut bundle = GroupMembersBundle {
+            created: 1,
+            group_id: None,
+            creator_id: Some(malformed.clone()),
+            members: vec![GroupMemberBundle {
+                user_id: Some(malformed.clone()),
+                ..Default::default()
+            }],
+            group_signature: None,
+            creator_signature: None,
+            admins: vec![malformed],
+            last_change: None,
+        };
+
+        assert!(!bundle.is_creator(creator.public.as_ref()));
+        assert!(!bundle.is_admin(creator.public.as_ref()));
+        assert!(bundle.get_member(creator.public.as_ref()).is_none());
+
+        bundle.creator_id = None;
+        assert!(!bundle.is_creator(creator.public.as_ref()));
+        assert!(bundle.verify().is_err());
     }
 }
@@ -0,0 +1,40 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+//! TLS configuration for grpc servers and for grpc clients dialing TLS secured endpoints
+
+use anyhow::{anyhow, Result};
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+
+/// Reads a pem certificate chain file and a pem private key file
+pub async fn read_tls_files(cert_file: &str, key_file: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+    let cert = tokio::fs::read(cert_file)
+        .await
+        .map_err(|e| anyhow!("failed to read tls cert file {}: {:?}", cert_file, e))?;
+    let key = tokio::fs::read(key_file)
+        .await
+        .map_err(|e| anyhow!("failed to read tls key file {}: {:?}", key_file, e))?;
+    Ok((cert, key))
+}
+
+/// Returns a server TLS config from a pem certificate chain file and a pem private key file
+pub async fn server_tls_config(cert_file: &str, key_file: &str) -> Result<ServerTlsConfig> {
+    let (cert, key) = read_tls_files(cert_file, key_file).await?;
+    Ok(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))
+}
+
+/// Returns a client TLS config which trusts the system's root certificates and
+/// the CA certificate in ca_cert_file when provided
+pub async fn client_tls_config(ca_cert_file: Option<String>) -> Result<ClientTlsConfig> {
+    let config = ClientTlsConfig::new();
+    match ca_cert_file {
+        Some(file) => {
+            let ca_cert = tokio::fs::read(&file)
+                .await
+                .map_err(|e| anyhow!("failed to read tls ca cert file {}: {:?}", file, e))?;
+            Ok(config.ca_certificate(Certificate::from_pem(ca_cert)))
+        }
+        None => Ok(config),
+    }
+}
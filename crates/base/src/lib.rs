@@ -18,24 +18,33 @@ mod dialup_info;
 pub mod entity_id;
 mod forward_message_payload;
 pub mod group_members_bundle;
+pub mod grpc_tls;
 pub mod hex_utils;
 pub mod key_pair;
 pub mod logging_service;
+pub mod media_item;
 mod message;
 pub mod message_type;
 mod new_session_reqeust;
 pub mod payment_types_extensions;
+pub mod protocol_version;
 pub mod provider_identity_bundle;
 pub mod provider_net_info;
 pub mod provider_private_identity_bundle;
 pub mod provider_signed_client_identity_bundle;
 pub mod public_key;
+pub mod request_rejection;
+pub mod safety_number;
+mod sealed_sender_content;
+pub mod secp256k1;
 pub mod server_config_service;
 pub mod service_terms_bundle;
+pub mod signatures;
 pub mod snp;
 pub mod store_data_request;
 pub mod test_helpers;
 pub mod time_utils;
 pub mod transaction;
+pub mod transparency_log;
 pub mod typed_message;
 pub mod typed_msgs_dispatcher;
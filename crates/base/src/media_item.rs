@@ -0,0 +1,103 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::snp::snp_core_types::{CompressionCodec, MediaItem, MimeType};
+use anyhow::{anyhow, bail, Result};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+impl MediaItem {
+    /// Creates a new media item with content compressed with a compression codec
+    pub fn new(
+        id: u32,
+        name: String,
+        mime_type: MimeType,
+        compression: CompressionCodec,
+        content: &[u8],
+    ) -> Result<MediaItem> {
+        Ok(MediaItem {
+            id,
+            name,
+            mime_type: mime_type as i32,
+            compression: compression as i32,
+            content: compress(compression, content)?,
+        })
+    }
+
+    /// Returns the item's decompressed content. Fails when it is larger than max_size
+    /// so a small compressed item can't be used to exhaust the receiver's memory
+    pub fn get_content(&self, max_size: usize) -> Result<Vec<u8>> {
+        let compression = CompressionCodec::from_i32(self.compression)
+            .ok_or_else(|| anyhow!("unknown compression codec"))?;
+        decompress(compression, &self.content, max_size)
+    }
+}
+
+/// Compress data with a compression codec
+pub fn compress(compression: CompressionCodec, data: &[u8]) -> Result<Vec<u8>> {
+    match compression {
+        CompressionCodec::None => Ok(data.to_vec()),
+        CompressionCodec::Zlib030 => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?)
+        }
+        CompressionCodec::Rar010 => bail!("rar compression is not supported"),
+    }
+}
+
+/// Decompress data compressed with a compression codec. Fails when decompressed data is larger than max_size
+pub fn decompress(compression: CompressionCodec, data: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    let res = match compression {
+        CompressionCodec::None => data.to_vec(),
+        CompressionCodec::Zlib030 => {
+            let mut res = vec![];
+            ZlibDecoder::new(data)
+                .take(max_size as u64 + 1)
+                .read_to_end(&mut res)
+                .map_err(|e| anyhow!("invalid zlib data: {:?}", e))?;
+            res
+        }
+        CompressionCodec::Rar010 => bail!("rar compression is not supported"),
+    };
+
+    if res.len() > max_size {
+        bail!("content is larger than {} bytes", max_size)
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zlib_media_item() {
+        let content = "a picture is worth a thousand words. ".repeat(1000);
+        let item = MediaItem::new(
+            1,
+            "words.txt".into(),
+            MimeType::TextUtf8,
+            CompressionCodec::Zlib030,
+            content.as_bytes(),
+        )
+        .unwrap();
+
+        assert!(item.content.len() < content.len());
+        assert_eq!(item.get_content(content.len()).unwrap(), content.as_bytes());
+
+        // decompressing content larger than the max size fails
+        assert!(item.get_content(content.len() - 1).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_codecs() {
+        assert!(compress(CompressionCodec::Rar010, b"data").is_err());
+        assert!(decompress(CompressionCodec::Rar010, b"data", 1024).is_err());
+        assert!(decompress(CompressionCodec::Zlib030, b"not zlib data", 1024).is_err());
+    }
+}
@@ -54,6 +54,21 @@ impl fmt::Display for MessageType {
 
             MessageType::PingNodeRequest => write!(f, "A ping request for node to return its net info"),
             MessageType::PingNodeResponse => write!(f, "A ping response, includes signed node net info"),
+            MessageType::CoverTrafficRequest => write!(f, "Cover traffic message to provider, discarded by the provider"),
+            MessageType::CoverTrafficResponse => write!(f, "An empty response to a cover traffic message"),
+
+            MessageType::MessageReceipts => write!(f, "Delivered or read receipts of 1:1 messages"),
+            MessageType::AttachmentChunk => write!(f, "A chunk of a 1:1 message with attachments"),
+            MessageType::ChannelPaymentReminder => write!(f, "Paid channel subscription fee reminder"),
+            MessageType::ChannelSubscriptionRenewal => write!(f, "Paid channel subscription renewal"),
+            MessageType::GroupSenderKey => write!(f, "Group sender key"),
+            MessageType::FanOutMessageRequest => write!(f, "FanOutMessage request"),
+            MessageType::FanOutMessageResponse => write!(f, "FanOutMessage response"),
+            MessageType::GroupAdminRequest => write!(f, "Group admin request"),
+            MessageType::GroupAdminResponse => write!(f, "Group admin response"),
+            MessageType::GroupMembersUpdate => write!(f, "Group members update"),
+            MessageType::ChannelHistoryRequest => write!(f, "Channel history request"),
+            MessageType::ChannelHistoryResponse => write!(f, "Channel history response"),
 
         }
     }
@@ -2,10 +2,27 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use anyhow::anyhow;
+use crate::api_types_extensions::NetworkScoped;
+use anyhow::{anyhow, bail};
+use log::*;
+use sha2::{Digest, Sha256};
 use std::convert::TryInto;
 
-use crate::snp::snp_server_api::NewSessionRequest;
+use crate::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use crate::snp::snp_server_api::{
+    NewSessionRequest, NewSessionResponse, RejectionReason, RequestRejection,
+};
+use tonic::transport::Channel;
+use tonic::Status;
+
+/// Max proof of work difficulty senders agree to solve for a new session request
+pub const MAX_POW_DIFFICULTY: u32 = 28;
+
+impl NetworkScoped for NewSessionRequest {
+    fn get_net_id(&self) -> u32 {
+        self.net_id
+    }
+}
 
 impl NewSessionRequest {
     pub fn get_receiver(&self) -> anyhow::Result<ed25519_dalek::PublicKey> {
@@ -50,4 +67,138 @@ impl NewSessionRequest {
 
         Ok(eka)
     }
+
+    /// Returns the sender's ml-kem ciphertext of a hybrid x2dh request
+    pub fn get_kem_ct(&self) -> Option<Vec<u8>> {
+        if self.sender_kem_ciphertext.is_empty() {
+            None
+        } else {
+            Some(self.sender_kem_ciphertext.clone())
+        }
+    }
+
+    /// Send the request to a provider. When the provider requires a proof of work, the proof is solved
+    /// and the request is sent again.
+    pub async fn send(
+        mut self,
+        client: &mut ProviderCoreServiceClient<Channel>,
+    ) -> Result<NewSessionResponse, Status> {
+        let status = match client.new_session(tonic::Request::new(self.clone())).await {
+            Ok(resp) => return Ok(resp.into_inner()),
+            Err(status) => status,
+        };
+
+        let difficulty = match RequestRejection::from_status(&status) {
+            Some(rejection)
+                if rejection.reason == RejectionReason::ProofOfWorkRequired as i32
+                    && rejection.pow_difficulty <= MAX_POW_DIFFICULTY =>
+            {
+                rejection.pow_difficulty
+            }
+            _ => return Err(status),
+        };
+
+        debug!(
+            "solving new session proof of work. difficulty: {}",
+            difficulty
+        );
+        self = tokio::task::spawn_blocking(move || self.solve_pow(difficulty).map(|_| self))
+            .await
+            .map_err(|e| Status::internal(format!("failed to solve proof of work: {:?}", e)))?
+            .map_err(|e| Status::internal(format!("failed to solve proof of work: {:?}", e)))?;
+
+        Ok(client
+            .new_session(tonic::Request::new(self))
+            .await?
+            .into_inner())
+    }
+
+    /// Find and set a proof of work nonce with at least difficulty leading zero bits.
+    /// The proof of work is over all request data but the signature and the nonce, so it may be
+    /// provided after the request was signed.
+    pub fn solve_pow(&mut self, difficulty: u32) -> anyhow::Result<()> {
+        let prefix = self.pow_prefix()?;
+        let mut nonce: u64 = 0;
+        while pow_leading_zeros(&prefix, nonce) < difficulty {
+            nonce = nonce
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("failed to find a proof of work"))?;
+        }
+        self.pow_nonce = nonce;
+        Ok(())
+    }
+
+    /// Verify the request's proof of work has at least difficulty leading zero bits
+    pub fn verify_pow(&self, difficulty: u32) -> anyhow::Result<()> {
+        if pow_leading_zeros(&self.pow_prefix()?, self.pow_nonce) < difficulty {
+            bail!("insufficient proof of work")
+        }
+        Ok(())
+    }
+
+    fn pow_prefix(&self) -> anyhow::Result<Vec<u8>> {
+        use prost::Message;
+        let mut data = self.clone();
+        data.sender_signature = None;
+        data.pow_nonce = 0;
+        let mut buf = Vec::with_capacity(data.encoded_len());
+        data.encode(&mut buf)?;
+        Ok(Sha256::digest(&buf).to_vec())
+    }
+}
+
+/// Returns the number of leading zero bits of the hash of a pow prefix and a nonce
+fn pow_leading_zeros(prefix: &[u8], nonce: u64) -> u32 {
+    let hash = Sha256::new()
+        .chain(prefix)
+        .chain(nonce.to_le_bytes())
+        .finalize();
+
+    let mut zeros = 0;
+    for byte in hash.iter() {
+        zeros += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    zeros
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types_extensions::SignedWithExternalVerifier;
+    use crate::snp::snp_core_types::PublicKey;
+    use ed25519_dalek::Keypair;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_pow() {
+        let key_pair = Keypair::generate(&mut OsRng);
+        let mut req = NewSessionRequest {
+            time_stamp: 1,
+            receiver: None,
+            sender_ephemeral_key: Some(PublicKey { key: vec![1; 32] }),
+            receiver_bundle_id: 2,
+            receiver_one_time_prekey_id: 0,
+            message: None,
+            sender_signature: None,
+            net_id: 0,
+            protocol_version: "0.1.0".into(),
+            pow_nonce: 0,
+            sender_kem_ciphertext: vec![],
+        };
+        req.sign(&key_pair).unwrap();
+
+        // pow may be solved for a signed request
+        req.solve_pow(12).unwrap();
+        req.verify_pow(12).unwrap();
+        req.verify_signature(key_pair.public.as_bytes()).unwrap();
+
+        // pow is bound to the request's data
+        let mut other = req.clone();
+        other.receiver_bundle_id = 3;
+        assert!(other.verify_pow(12).is_err() || other.verify_pow(13).is_err());
+        assert!(req.verify_pow(64).is_err());
+    }
 }
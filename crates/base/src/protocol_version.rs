@@ -0,0 +1,320 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::snp::snp_core_types::{DialupInfo, PreKey};
+use crate::snp::snp_server_api::{RejectionReason, RequestRejection};
+use anyhow::{bail, Result};
+use semver::Version;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use tonic::Status;
+
+/// Snp protocol semantic version implemented by this build
+pub const SNP_PROTOCOL_VERSION: &str = "0.2.0";
+
+/// Oldest Snp protocol version this build can talk to
+pub const MIN_SNP_PROTOCOL_VERSION: &str = "0.1.0";
+
+/// X2dh protocol semantic version of classic pre-keys created by this build
+pub const X2DH_VERSION: &str = "0.1.0";
+
+/// X2dh protocol semantic version of hybrid pre-keys which include an ml-kem encapsulation key
+pub const X2DH_HYBRID_VERSION: &str = "0.2.0";
+
+/// Oldest x2dh protocol version of pre-keys this build can use
+pub const MIN_X2DH_VERSION: &str = "0.1.0";
+
+/// Version assumed for peers and pre-keys which predate version negotiation and don't specify a version
+const LEGACY_VERSION: &str = "0.1.0";
+
+/// Parse a semantic version. An empty version is treated as the legacy version.
+pub fn parse_version(version: &str) -> Result<Version> {
+    let version = if version.is_empty() {
+        LEGACY_VERSION
+    } else {
+        version
+    };
+    Ok(Version::parse(version)?)
+}
+
+/// An inclusive range of supported protocol versions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    pub min: Version,
+    pub max: Version,
+}
+
+impl Display for VersionRange {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} - {}", self.min, self.max)
+    }
+}
+
+impl VersionRange {
+    pub fn new(min: &str, max: &str) -> Result<Self> {
+        let range = VersionRange {
+            min: parse_version(min)?,
+            max: parse_version(max)?,
+        };
+        if range.min > range.max {
+            bail!("invalid version range: {}", range)
+        }
+        Ok(range)
+    }
+
+    /// Snp protocol versions supported by this build
+    pub fn snp_protocol() -> Self {
+        VersionRange::new(MIN_SNP_PROTOCOL_VERSION, SNP_PROTOCOL_VERSION).unwrap()
+    }
+
+    /// X2dh protocol versions supported by this build
+    pub fn x2dh() -> Self {
+        VersionRange::new(MIN_X2DH_VERSION, X2DH_HYBRID_VERSION).unwrap()
+    }
+
+    /// Versions supported by a peer's endpoint
+    pub fn from_dialup_info(info: &DialupInfo) -> Result<Self> {
+        let min = if info.min_api_version.is_empty() {
+            &info.api_version
+        } else {
+            &info.min_api_version
+        };
+        VersionRange::new(min, &info.api_version)
+    }
+
+    pub fn contains(&self, version: &Version) -> bool {
+        *version >= self.min && *version <= self.max
+    }
+
+    /// Returns the newest version supported by both ranges
+    pub fn negotiate(&self, other: &VersionRange) -> Result<Version, IncompatibleVersionError> {
+        let version = std::cmp::min(&self.max, &other.max);
+        if self.contains(version) && other.contains(version) {
+            Ok(version.clone())
+        } else {
+            Err(IncompatibleVersionError {
+                version: other.to_string(),
+                supported: self.clone(),
+            })
+        }
+    }
+
+    /// Negotiate a version with a peer which implements a version, and supports older versions
+    /// down to our min version. Returns the newest version supported by both.
+    pub fn negotiate_with(&self, version: &str) -> Result<Version, IncompatibleVersionError> {
+        let incompatible = || IncompatibleVersionError {
+            version: version.into(),
+            supported: self.clone(),
+        };
+        let version = parse_version(version).map_err(|_| incompatible())?;
+        let negotiated = std::cmp::min(version, self.max.clone());
+        if self.contains(&negotiated) {
+            Ok(negotiated)
+        } else {
+            Err(incompatible())
+        }
+    }
+
+    /// Returns an error if a version is not in the range
+    pub fn verify(&self, version: &str) -> Result<Version, IncompatibleVersionError> {
+        match parse_version(version) {
+            Ok(v) if self.contains(&v) => Ok(v),
+            _ => Err(IncompatibleVersionError {
+                version: version.into(),
+                supported: self.clone(),
+            }),
+        }
+    }
+}
+
+/// An error returned when a peer's protocol version is not supported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncompatibleVersionError {
+    pub version: String,
+    pub supported: VersionRange,
+}
+
+impl Display for IncompatibleVersionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "incompatible protocol version {}. supported versions: {}",
+            self.version, self.supported
+        )
+    }
+}
+
+impl std::error::Error for IncompatibleVersionError {}
+
+impl IncompatibleVersionError {
+    /// Returns a grpc status carrying a typed rejection with the supported versions
+    pub fn into_status(self) -> Status {
+        let message = self.to_string();
+        RequestRejection::incompatible_protocol_version(&self.supported).into_status(message)
+    }
+
+    /// Returns the error carried by a provider's rejection, if it rejected the caller's protocol version
+    pub fn from_rejection(rejection: &RequestRejection, version: &str) -> Option<Self> {
+        if rejection.reason != RejectionReason::IncompatibleProtocolVersion as i32 {
+            return None;
+        }
+        VersionRange::new(
+            &rejection.min_protocol_version,
+            &rejection.max_protocol_version,
+        )
+        .ok()
+        .map(|supported| IncompatibleVersionError {
+            version: version.into(),
+            supported,
+        })
+    }
+}
+
+impl PreKey {
+    /// Returns an error if the pre-key was created with an x2dh version this build can't use
+    pub fn verify_x2dh_version(&self) -> Result<Version, IncompatibleVersionError> {
+        VersionRange::x2dh().verify(&self.x2dh_version)
+    }
+
+    /// Returns the ml-kem encapsulation key of a hybrid pre-key, or None for a classic pre-key.
+    /// Returns an error if the pre-key's version is not supported or if a hybrid pre-key is missing its kem key.
+    pub fn get_kem_key(&self) -> Result<Option<Vec<u8>>> {
+        let version = self.verify_x2dh_version()?;
+        if version < parse_version(X2DH_HYBRID_VERSION)? {
+            return Ok(None);
+        }
+        if self.kem_key.is_empty() {
+            bail!("hybrid pre-key is missing its kem key")
+        }
+        Ok(Some(self.kem_key.clone()))
+    }
+}
+
+/// Protocol features which are gated by the negotiated protocol version.
+/// A feature is used with a peer only when the version negotiated with it supports the feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolFeature {
+    SealedSender,
+}
+
+impl ProtocolFeature {
+    /// Returns the first protocol version which includes the feature
+    pub fn min_version(&self) -> Version {
+        match self {
+            ProtocolFeature::SealedSender => Version::new(0, 2, 0),
+        }
+    }
+
+    /// Returns true if the feature may be used in a session with a negotiated protocol version.
+    /// Features newer than this build's protocol version are never enabled.
+    pub fn is_enabled(&self, negotiated: &Version) -> bool {
+        let min_version = self.min_version();
+        *negotiated >= min_version && VersionRange::snp_protocol().max >= min_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        let ours = VersionRange::new("0.1.0", "0.3.0").unwrap();
+
+        // newest common version is used
+        let theirs = VersionRange::new("0.1.0", "0.2.1").unwrap();
+        assert_eq!(ours.negotiate(&theirs).unwrap(), Version::new(0, 2, 1));
+        assert_eq!(theirs.negotiate(&ours).unwrap(), Version::new(0, 2, 1));
+
+        let theirs = VersionRange::new("0.4.0", "0.5.0").unwrap();
+        assert!(ours.negotiate(&theirs).is_err());
+
+        // peers implementing newer versions fall back to ours
+        assert_eq!(ours.negotiate_with("0.5.0").unwrap(), Version::new(0, 3, 0));
+        assert_eq!(ours.negotiate_with("0.2.0").unwrap(), Version::new(0, 2, 0));
+        assert_eq!(ours.negotiate_with("").unwrap(), Version::new(0, 1, 0));
+
+        let ours = VersionRange::new("0.2.0", "0.3.0").unwrap();
+        let err = ours.negotiate_with("0.1.5").unwrap_err();
+        assert_eq!(err.supported, ours);
+        assert!(ours.negotiate_with("not a version").is_err());
+        assert!(VersionRange::new("0.3.0", "0.2.0").is_err());
+    }
+
+    #[test]
+    fn test_dialup_info_range() {
+        let mut info = DialupInfo {
+            api_version: "0.2.0".into(),
+            ..Default::default()
+        };
+        let range = VersionRange::from_dialup_info(&info).unwrap();
+        assert_eq!(range.min, Version::new(0, 2, 0));
+
+        info.min_api_version = "0.1.0".into();
+        let range = VersionRange::from_dialup_info(&info).unwrap();
+        assert_eq!(range.min, Version::new(0, 1, 0));
+        assert_eq!(range.max, Version::new(0, 2, 0));
+    }
+
+    #[test]
+    fn test_rejection_round_trip() {
+        let err = VersionRange::snp_protocol().verify("9.0.0").unwrap_err();
+        let status = err.clone().into_status();
+        let rejection = RequestRejection::from_status(&status).unwrap();
+        assert_eq!(
+            IncompatibleVersionError::from_rejection(&rejection, "9.0.0"),
+            Some(err)
+        );
+    }
+
+    #[test]
+    fn test_pre_key_version() {
+        let mut pre_key = PreKey {
+            x2dh_version: X2DH_VERSION.into(),
+            ..Default::default()
+        };
+        assert!(pre_key.verify_x2dh_version().is_ok());
+
+        // pre-keys which predate versioning use the legacy version
+        pre_key.x2dh_version = "".into();
+        assert!(pre_key.verify_x2dh_version().is_ok());
+
+        pre_key.x2dh_version = "99.0.0".into();
+        assert!(pre_key.verify_x2dh_version().is_err());
+    }
+
+    #[test]
+    fn test_pre_key_kem_key() {
+        let mut pre_key = PreKey {
+            x2dh_version: X2DH_VERSION.into(),
+            kem_key: vec![1, 2, 3],
+            ..Default::default()
+        };
+        // classic pre-keys use classic x2dh
+        assert_eq!(pre_key.get_kem_key().unwrap(), None);
+
+        pre_key.x2dh_version = X2DH_HYBRID_VERSION.into();
+        assert_eq!(pre_key.get_kem_key().unwrap(), Some(vec![1, 2, 3]));
+
+        // hybrid pre-keys may not be downgraded to classic x2dh
+        pre_key.kem_key = vec![];
+        assert!(pre_key.get_kem_key().is_err());
+    }
+
+    #[test]
+    fn test_features() {
+        let legacy = parse_version("").unwrap();
+        assert!(!ProtocolFeature::SealedSender.is_enabled(&legacy));
+
+        // peers which negotiated a version which includes the feature may use it
+        let ours = VersionRange::snp_protocol();
+        let negotiated = ours.negotiate_with(SNP_PROTOCOL_VERSION).unwrap();
+        assert!(ProtocolFeature::SealedSender.is_enabled(&negotiated));
+
+        // peers which only implement 0.1.0 negotiate it and may not use 0.2.0 features
+        let negotiated = ours.negotiate_with("0.1.0").unwrap();
+        assert_eq!(negotiated, Version::new(0, 1, 0));
+        assert!(!ProtocolFeature::SealedSender.is_enabled(&negotiated));
+    }
+}
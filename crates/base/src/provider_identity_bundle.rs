@@ -2,66 +2,51 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::api_types_extensions::Signed;
+use crate::api_types_extensions::{NetworkScoped, Signed};
+use crate::signatures::{sign_message, verify_message, SigningKey};
 use crate::snp::snp_core_types::{ProviderIdentityBundle, PublicKey};
 use anyhow::{anyhow, Result};
-use ed25519_dalek::ed25519::signature::Signature;
-use ed25519_dalek::{Signer, Verifier};
 
-impl Signed for ProviderIdentityBundle {
-    fn sign(&mut self, signer: &ed25519_dalek::Keypair) -> Result<()> {
-        use prost::Message;
+impl NetworkScoped for ProviderIdentityBundle {
+    fn get_net_id(&self) -> u32 {
+        self.net_id
+    }
 
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-        let signature = signer.sign(&buf);
-        use crate::snp::snp_core_types::Signature;
-        self.provider_signature = Some(Signature {
-            scheme_id: 0,
-            signature: signature.to_bytes().to_vec(),
-        });
+    /// Verify the bundle and all its advertised endpoints are for the network
+    fn verify_net_id(&self, net_id: u32) -> Result<()> {
+        if self.net_id != net_id {
+            return Err(anyhow!(
+                "provider bundle is for network {} but expected network {}",
+                self.net_id,
+                net_id
+            ));
+        }
+        for dialup_info in self.dial_up_info.iter() {
+            dialup_info.verify_net_id(net_id)?;
+        }
+        Ok(())
+    }
+}
 
+impl Signed for ProviderIdentityBundle {
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.provider_signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
     /// Verify the provider public signature on the bundle
     fn verify_signature(&self) -> Result<()> {
-        if self.provider_id.is_none() {
-            return Err(anyhow!("missing client id"));
-        }
+        let pub_key = self.get_provider_id_public_key()?;
 
-        let pub_key_data = self
-            .provider_id
+        let signature = self
+            .provider_signature
             .as_ref()
-            .unwrap()
-            .public_key
-            .as_ref()
-            .ok_or_else(|| anyhow!("missing public key"))?;
-
-        let pub_key = pub_key_data.as_pub_key()?;
-
-        if self.provider_signature.is_none() {
-            return Err(anyhow!("missing message signature"));
-        }
-
-        let signature_data = self.provider_signature.as_ref().unwrap().clone();
+            .ok_or_else(|| anyhow!("missing message signature"))?;
 
-        // create message binary data for signature verification
         let mut data = self.clone();
         data.provider_signature = None; // remove signature from message before verification
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature_data.signature.as_slice())?;
-
-        if pub_key.verify(&buf, &signature).is_err() {
-            return Err(anyhow!("failed to verify provider signature"));
-        };
-
-        Ok(())
+        verify_message(&data, pub_key.key.as_slice(), signature)
+            .map_err(|e| anyhow!("failed to verify provider signature: {}", e))
     }
 }
 impl ProviderIdentityBundle {
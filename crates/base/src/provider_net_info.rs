@@ -3,10 +3,9 @@
 //
 
 use crate::api_types_extensions::Signed;
+use crate::signatures::{sign_message, verify_message, SigningKey};
 use crate::snp::snp_core_types::{DialupInfo, ProviderNetInfo};
 use anyhow::{anyhow, Result};
-use ed25519_dalek::ed25519::signature::Signature;
-use ed25519_dalek::{Signer, Verifier};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
@@ -23,9 +22,9 @@ impl ProviderNetInfo {
             .as_ref())
     }
 
+    /// Returns the best grpc endpoint advertised by the provider
     pub fn get_dialup_info(&self) -> Result<&DialupInfo> {
-        self.dial_up_info
-            .as_ref()
+        DialupInfo::best_grpc_endpoint(&self.dial_up_info)
             .ok_or_else(|| anyhow!("missing dialup info"))
     }
 }
@@ -38,18 +37,9 @@ impl Display for ProviderNetInfo {
 }
 
 impl Signed for ProviderNetInfo {
-    fn sign(&mut self, member_key_pair: &ed25519_dalek::Keypair) -> Result<()> {
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
         self.signature = None;
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: member_key_pair.sign(&buf).as_ref().to_vec(),
-        });
-
+        self.signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
@@ -57,26 +47,16 @@ impl Signed for ProviderNetInfo {
         let signature = self
             .signature
             .as_ref()
-            .ok_or_else(|| anyhow!("missing author signature"))?
-            .clone();
+            .ok_or_else(|| anyhow!("missing author signature"))?;
 
         let mut data = self.clone();
         data.signature = None;
 
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature.signature.as_slice())?;
-
         let signer = self
             .provider_id
             .as_ref()
             .ok_or_else(|| anyhow!("missing user id"))?;
 
-        let signer_pub_key = ed25519_dalek::PublicKey::from_bytes(signer.get_id()?.as_slice())?;
-        Ok(signer_pub_key.verify(&buf, &signature)?)
+        verify_message(&data, signer.get_id()?.as_slice(), signature)
     }
 }
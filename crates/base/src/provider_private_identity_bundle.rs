@@ -3,6 +3,7 @@
 //
 
 use crate::api_types_extensions::Signed;
+use crate::protocol_version::X2DH_VERSION;
 use crate::snp::snp_core_types::{
     DialupInfo, EntityId, KeyPair, PreKey, PrivateKey, PrivateProviderIdentityBundle,
     ProviderIdentityBundle, PublicKey,
@@ -28,18 +29,19 @@ impl PrivateProviderIdentityBundle {
         PrivateProviderIdentityBundle::new_for_id(
             &key_pair,
             &pre_key_private,
-            dialup_info,
+            std::slice::from_ref(dialup_info),
             nickname,
             payment_address,
             net_id,
         )
     }
 
-    /// Create a new identity bundle using the provided information
+    /// Create a new identity bundle using the provided information.
+    /// dialup_info are all the api endpoints the provider advertises.
     pub fn new_for_id(
         key_pair: &Keypair,
         pre_key_private: &StaticSecret,
-        dialup_info: &DialupInfo,
+        dialup_info: &[DialupInfo],
         nickname: String,
         payment_address: &Address,
         net_id: u32,
@@ -79,11 +81,12 @@ impl PrivateProviderIdentityBundle {
             }),
             address: Some(payment_address.clone()),
 
-            dial_up_info: vec![dialup_info.clone()],
+            dial_up_info: dialup_info.to_vec(),
             pre_key: Some(PreKey {
-                x2dh_version: "0.1.0".to_string(),
+                x2dh_version: X2DH_VERSION.into(),
                 key: Some(pre_key_pair.clone().public_key.unwrap()),
                 key_id: 0, // unused here - same as bundle id
+                kem_key: vec![],
             }),
             one_time_keys: vec![], // empty for now
             profile_image: None,
@@ -3,10 +3,9 @@
 //
 
 use crate::api_types_extensions::Signed;
+use crate::signatures::{sign_message, verify_message, SigningKey};
 use crate::snp::snp_core_types::{EntityId, ProviderSignedClientIdentityBundle};
 use anyhow::{anyhow, Result};
-use ed25519_dalek::ed25519::signature::Signature;
-use ed25519_dalek::{Signer, Verifier};
 
 impl ProviderSignedClientIdentityBundle {
     // Get the public client id raw data
@@ -33,16 +32,8 @@ impl ProviderSignedClientIdentityBundle {
 }
 
 impl Signed for ProviderSignedClientIdentityBundle {
-    fn sign(&mut self, signer: &ed25519_dalek::Keypair) -> Result<()> {
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: signer.sign(&buf).as_ref().to_vec(),
-        });
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
@@ -62,37 +53,16 @@ impl Signed for ProviderSignedClientIdentityBundle {
         client_bundle.verify_signature()?;
         provider_bundle.verify_signature()?;
 
-        let provider_key_data = provider_bundle
-            .provider_id
-            .as_ref()
-            .unwrap()
-            .public_key
-            .as_ref()
-            .ok_or_else(|| anyhow!("missing public key"))?;
-
-        let provider_pub_key = provider_key_data.as_pub_key()?;
-
-        if self.signature.is_none() {
-            return Err(anyhow!("missing message signature"));
-        }
+        let provider_pub_key = provider_bundle.get_provider_id_public_key()?;
 
-        let signature_data = self.signature.as_ref().unwrap().clone();
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing message signature"))?;
 
-        // create message binary data for signature verification
         let mut data = self.clone();
         data.signature = None; // remove signature from message before verification
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature_data.signature.as_slice())?;
-
-        if provider_pub_key.verify(&buf, &signature).is_err() {
-            return Err(anyhow!("failed to verify provider signature"));
-        };
-
-        Ok(())
+        verify_message(&data, provider_pub_key.key.as_slice(), signature)
+            .map_err(|e| anyhow!("failed to verify provider signature: {}", e))
     }
 }
@@ -0,0 +1,140 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::protocol_version::VersionRange;
+use crate::snp::snp_server_api::{RejectionReason, RequestRejection};
+use bytes::Bytes;
+use prost::Message;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use tonic::{Code, Status};
+
+impl RequestRejection {
+    pub fn client_rate_limited(retry_after_ms: u64) -> Self {
+        RequestRejection {
+            reason: RejectionReason::ClientRateLimited as i32,
+            retry_after_ms,
+            pow_difficulty: 0,
+            min_protocol_version: "".into(),
+            max_protocol_version: "".into(),
+        }
+    }
+
+    pub fn ip_rate_limited(retry_after_ms: u64) -> Self {
+        RequestRejection {
+            reason: RejectionReason::IpRateLimited as i32,
+            retry_after_ms,
+            pow_difficulty: 0,
+            min_protocol_version: "".into(),
+            max_protocol_version: "".into(),
+        }
+    }
+
+    pub fn proof_of_work_required(pow_difficulty: u32) -> Self {
+        RequestRejection {
+            reason: RejectionReason::ProofOfWorkRequired as i32,
+            retry_after_ms: 0,
+            pow_difficulty,
+            min_protocol_version: "".into(),
+            max_protocol_version: "".into(),
+        }
+    }
+
+    pub fn incompatible_protocol_version(supported: &VersionRange) -> Self {
+        RequestRejection {
+            reason: RejectionReason::IncompatibleProtocolVersion as i32,
+            retry_after_ms: 0,
+            pow_difficulty: 0,
+            min_protocol_version: supported.min.to_string(),
+            max_protocol_version: supported.max.to_string(),
+        }
+    }
+
+    /// Returns a grpc status carrying this rejection in its details
+    pub fn into_status(self, message: impl Into<String>) -> Status {
+        let code = match RejectionReason::from_i32(self.reason) {
+            Some(RejectionReason::ProofOfWorkRequired)
+            | Some(RejectionReason::IncompatibleProtocolVersion) => Code::FailedPrecondition,
+            _ => Code::ResourceExhausted,
+        };
+        Status::with_details(code, message, Bytes::from(self.encode_to_vec()))
+    }
+
+    /// Returns the rejection carried by a grpc status, if any
+    pub fn from_status(status: &Status) -> Option<RequestRejection> {
+        match status.code() {
+            Code::FailedPrecondition | Code::ResourceExhausted => {}
+            _ => return None,
+        }
+        RequestRejection::decode(status.details())
+            .ok()
+            .filter(|r| r.reason != RejectionReason::Unspecified as i32)
+    }
+}
+
+/// An error returned to callers when a provider rejected their request.
+/// Use anyhow's downcast to get the rejection details from an error.
+#[derive(Debug, Clone)]
+pub struct RequestRejectedError {
+    pub rejection: RequestRejection,
+    pub message: String,
+}
+
+impl RequestRejectedError {
+    /// Returns a rejection error if the status carries a rejection
+    pub fn from_status(status: &Status) -> Option<Self> {
+        RequestRejection::from_status(status).map(|rejection| RequestRejectedError {
+            rejection,
+            message: status.message().into(),
+        })
+    }
+}
+
+impl Display for RequestRejectedError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "request rejected by provider: {} (reason: {:?}, retry after: {}ms, pow difficulty: {})",
+            self.message,
+            RejectionReason::from_i32(self.rejection.reason).unwrap_or(RejectionReason::Unspecified),
+            self.rejection.retry_after_ms,
+            self.rejection.pow_difficulty
+        )
+    }
+}
+
+impl std::error::Error for RequestRejectedError {}
+
+/// Returns an error for a provider's error response. Rejections are returned as RequestRejectedError.
+pub fn status_error(status: Status) -> anyhow::Error {
+    match RequestRejectedError::from_status(&status) {
+        Some(err) => err.into(),
+        None => anyhow::anyhow!("got an error response: {:?}", status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        let rejection = RequestRejection::client_rate_limited(1500);
+        let status = rejection.clone().into_status("too many messages");
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        assert_eq!(RequestRejection::from_status(&status), Some(rejection));
+
+        let status = RequestRejection::proof_of_work_required(16).into_status("pow required");
+        assert_eq!(status.code(), Code::FailedPrecondition);
+        let err = RequestRejectedError::from_status(&status).unwrap();
+        assert_eq!(err.rejection.pow_difficulty, 16);
+        assert_eq!(err.message, "pow required");
+
+        let err = status_error(status);
+        assert!(err.downcast_ref::<RequestRejectedError>().is_some());
+
+        assert!(RequestRejection::from_status(&Status::internal("error")).is_none());
+        assert!(RequestRejection::from_status(&Status::resource_exhausted("error")).is_none());
+    }
+}
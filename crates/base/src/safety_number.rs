@@ -0,0 +1,73 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use sha2::{Digest, Sha512};
+
+/// Fingerprint format version. Hashed into fingerprints so a new format doesn't produce colliding numbers.
+const FINGERPRINT_VERSION: u16 = 0;
+
+/// Hash iterations for a fingerprint. Makes searching for a key with a colliding fingerprint expensive.
+const FINGERPRINT_ITERATIONS: usize = 5200;
+
+/// Number of 5 digits chunks in a fingerprint
+const FINGERPRINT_CHUNKS: usize = 6;
+
+/// Returns a stable 30 digits fingerprint of an identity public key
+pub fn fingerprint(public_key: &[u8]) -> String {
+    let mut hash = Sha512::new()
+        .chain(FINGERPRINT_VERSION.to_be_bytes())
+        .chain(public_key)
+        .finalize();
+
+    for _ in 0..FINGERPRINT_ITERATIONS {
+        hash = Sha512::new().chain(hash).chain(public_key).finalize();
+    }
+
+    // each 5 bytes chunk of the hash is encoded as 5 digits
+    hash.chunks(5)
+        .take(FINGERPRINT_CHUNKS)
+        .map(|chunk| {
+            let value = chunk.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            format!("{:05}", value % 100000)
+        })
+        .collect()
+}
+
+/// Returns the 60 digits safety number of two identities.
+/// Both parties compute the same number so users can compare it out-of-band to verify each other's identity.
+pub fn safety_number(local_public_key: &[u8], remote_public_key: &[u8]) -> String {
+    let local = fingerprint(local_public_key);
+    let remote = fingerprint(remote_public_key);
+    if local <= remote {
+        local + &remote
+    } else {
+        remote + &local
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safety_number() {
+        let alice = [1u8; 32];
+        let bob = [2u8; 32];
+
+        let number = safety_number(&alice, &bob);
+        assert_eq!(number.len(), 60);
+        assert!(number.chars().all(|c| c.is_ascii_digit()));
+
+        // same number for both parties
+        assert_eq!(number, safety_number(&bob, &alice));
+        assert!(number.contains(&fingerprint(&alice)));
+        assert!(number.contains(&fingerprint(&bob)));
+
+        // a changed identity key changes the number
+        assert_ne!(number, safety_number(&alice, &[3u8; 32]));
+
+        // fingerprints are stable
+        assert_eq!(fingerprint(&alice), "653481277809870204720547886502");
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::api_types_extensions::Signed;
+use crate::signatures::{sign_message, verify_message, SigningKey};
+use crate::snp::snp_server_api::SealedSenderContent;
+use anyhow::anyhow;
+
+impl Signed for SealedSenderContent {
+    /// Sign the content as its sender
+    fn sign(&mut self, signer: &dyn SigningKey) -> anyhow::Result<()> {
+        self.signature = None;
+        self.signature = Some(sign_message(self, signer)?);
+        Ok(())
+    }
+
+    /// Verify sender's signature on the content
+    fn verify_signature(&self) -> anyhow::Result<()> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing signature"))?;
+
+        let sender_pub_key = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing sender"))?
+            .get_id()?;
+
+        let mut data = self.clone();
+        data.signature = None;
+        verify_message(&data, sender_pub_key.as_slice(), signature)
+            .map_err(|_| anyhow!("failed to verify signature of sender on sealed content"))
+    }
+}
+
+impl SealedSenderContent {
+    /// Get the sender public key from the content
+    pub fn get_sender_pub_key(&self) -> anyhow::Result<ed25519_dalek::PublicKey> {
+        self.sender
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing sender"))?
+            .public_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing public key"))?
+            .as_pub_key()
+    }
+
+    /// Get the receiver public key from the content
+    pub fn get_receiver_pub_key(&self) -> anyhow::Result<ed25519_dalek::PublicKey> {
+        self.receiver
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing receiver"))?
+            .public_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing public key"))?
+            .as_pub_key()
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+//! ECDSA over secp256k1 as used by Ethereum wallets.
+//! Signatures are 65 bytes r || s || v with a low s and deterministic RFC 6979 nonces.
+//! Curve arithmetic, signing and verification are provided by the k256 crate.
+
+use anyhow::{anyhow, bail, Result};
+use k256::ecdsa::{Signature, VerifyingKey};
+use k256::elliptic_curve::rand_core::OsRng;
+use tiny_keccak::{Hasher, Keccak};
+
+pub const SECRET_KEY_LEN: usize = 32;
+pub const PUBLIC_KEY_LEN: usize = 33;
+pub const SIGNATURE_LEN: usize = 65;
+
+/// A secp256k1 signing key
+#[derive(Clone)]
+pub struct SecretKey(k256::ecdsa::SigningKey);
+
+impl SecretKey {
+    /// Create a new random secret key
+    pub fn generate() -> Self {
+        SecretKey(k256::ecdsa::SigningKey::random(&mut OsRng))
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() != SECRET_KEY_LEN {
+            bail!("invalid secp256k1 secret key length")
+        }
+        let key = k256::ecdsa::SigningKey::from_slice(data)
+            .map_err(|_| anyhow!("invalid secp256k1 secret key"))?;
+        Ok(SecretKey(key))
+    }
+
+    pub fn to_bytes(&self) -> [u8; SECRET_KEY_LEN] {
+        self.0.to_bytes().into()
+    }
+
+    /// Returns the compressed SEC1 public key
+    pub fn public_key(&self) -> Vec<u8> {
+        self.0
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Sign data the way Ethereum wallets sign personal messages (EIP-191)
+    pub fn sign(&self, data: &[u8]) -> Vec<u8> {
+        self.sign_digest(&personal_message_hash(data))
+    }
+
+    /// Sign a 32 bytes message digest. Returns r || s || v where v is 27 or 28
+    pub fn sign_digest(&self, digest: &[u8; 32]) -> Vec<u8> {
+        // k256 signs with a low s and adjusts the recovery id accordingly.
+        // Signing a 32 bytes prehash with a valid key can't fail.
+        let (signature, recovery_id) = self
+            .0
+            .sign_prehash_recoverable(digest)
+            .expect("failed to sign secp256k1 digest");
+
+        let mut data = Vec::with_capacity(SIGNATURE_LEN);
+        data.extend_from_slice(&signature.to_bytes());
+        data.push(27 + recovery_id.to_byte());
+        data
+    }
+}
+
+/// Verify an EIP-191 personal message signature of data by a public key
+pub fn verify(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<()> {
+    verify_digest(public_key, &personal_message_hash(data), signature)
+}
+
+/// Verify a signature of a 32 bytes digest by a public key. Signatures with a high s are rejected.
+pub fn verify_digest(public_key: &[u8], digest: &[u8; 32], signature: &[u8]) -> Result<()> {
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+
+    if signature.len() != SIGNATURE_LEN {
+        bail!("invalid secp256k1 signature length")
+    }
+    let verifying_key = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|_| anyhow!("invalid secp256k1 public key"))?;
+    let signature = Signature::from_slice(&signature[..64])
+        .map_err(|_| anyhow!("invalid secp256k1 signature"))?;
+    if signature.normalize_s().is_some() {
+        bail!("invalid secp256k1 signature")
+    }
+
+    verifying_key
+        .verify_prehash(digest, &signature)
+        .map_err(|_| anyhow!("failed to verify secp256k1 signature"))
+}
+
+/// Returns the keccak256 hash of data
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak::v256();
+    keccak.update(data);
+    let mut out = [0u8; 32];
+    keccak.finalize(&mut out);
+    out
+}
+
+/// Returns the Ethereum address of a public key - the last 20 bytes of its uncompressed keccak256 hash
+pub fn ethereum_address(public_key: &[u8]) -> Result<Vec<u8>> {
+    let verifying_key = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|_| anyhow!("invalid secp256k1 public key"))?;
+    let point = verifying_key.to_encoded_point(false);
+    // skip the uncompressed point tag
+    Ok(keccak256(&point.as_bytes()[1..])[12..].to_vec())
+}
+
+/// EIP-191 hash of a personal message
+fn personal_message_hash(data: &[u8]) -> [u8; 32] {
+    let mut message = format!("\x19Ethereum Signed Message:\n{}", data.len()).into_bytes();
+    message.extend_from_slice(data);
+    keccak256(&message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify() {
+        let key = SecretKey::generate();
+        let public_key = key.public_key();
+        assert_eq!(public_key.len(), PUBLIC_KEY_LEN);
+
+        let signature = key.sign(b"hello");
+        assert_eq!(signature.len(), SIGNATURE_LEN);
+        verify(&public_key, b"hello", &signature).unwrap();
+        assert!(verify(&public_key, b"hello!", &signature).is_err());
+        assert!(verify(&SecretKey::generate().public_key(), b"hello", &signature).is_err());
+
+        // signatures are deterministic
+        assert_eq!(key.sign(b"hello"), signature);
+
+        // high s signatures are rejected
+        let low_s = Signature::from_slice(&signature[..64]).unwrap();
+        let high_s = Signature::from_scalars(low_s.r(), -*low_s.s()).unwrap();
+        let mut high_s_signature = high_s.to_bytes().to_vec();
+        high_s_signature.push(signature[64]);
+        assert!(verify(&public_key, b"hello", &high_s_signature).is_err());
+    }
+
+    /// EIP-155 example transaction signature
+    #[test]
+    fn test_known_signature() {
+        let key = SecretKey::from_bytes(&[0x46; 32]).unwrap();
+        assert_eq!(
+            hex::encode(ethereum_address(&key.public_key()).unwrap()),
+            "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+        );
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(
+            &hex::decode("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")
+                .unwrap(),
+        );
+        let signature = key.sign_digest(&digest);
+        assert_eq!(
+            hex::encode(&signature),
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276\
+             67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83\
+             1b"
+        );
+        verify_digest(&key.public_key(), &digest, &signature).unwrap();
+    }
+
+    /// web3 personal message signature example
+    #[test]
+    fn test_known_personal_message_signature() {
+        let key = SecretKey::from_bytes(
+            &hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(ethereum_address(&key.public_key()).unwrap()),
+            "2c7536e3605d9c16a7a3d7b1898e529396a65c23"
+        );
+
+        let signature = key.sign(b"Some data");
+        assert_eq!(
+            hex::encode(&signature),
+            "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd\
+             6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a029\
+             1c"
+        );
+        verify(&key.public_key(), b"Some data", &signature).unwrap();
+    }
+}
@@ -3,8 +3,10 @@
 //
 
 use anyhow::{anyhow, Result};
-use config::{Config, Environment};
+use config::{Config, ConfigError, Environment};
 use log::*;
+use serde::Deserialize;
+use std::collections::HashMap;
 use xactor::*;
 
 pub const DEFAULT_GRPC_SERVER_PORT: i64 = 9080;
@@ -12,6 +14,11 @@ pub const DEFAULT_GRPC_ADMIN_PORT: i64 = 9888;
 pub const DEFAULT_START_ADMIN_SERVICE: bool = true;
 pub const DEFAULT_START_GRPC_SERVICE: bool = true;
 pub const DEFAULT_DROP_DB_ON_EXIT: bool = true;
+pub const DEFAULT_HANDOVER_GRACE_PERIOD_SECS: i64 = 60 * 60 * 24 * 7;
+pub const DEFAULT_CLIENT_MESSAGES_PER_MINUTE: i64 = 600;
+pub const DEFAULT_IP_REQUESTS_PER_MINUTE: i64 = 1200;
+pub const DEFAULT_FAN_OUT_MAX_RECEIVERS: i64 = 100;
+pub const DEFAULT_MAX_FILE_SIZE: i64 = 4 * 1024 * 1024;
 
 /// ConfigService for servers
 
@@ -21,9 +28,27 @@ pub const PEER_NAME_CONFIG_KEY: &str = "peer_name";
 pub const GRPC_HOST_CONFIG_KEY: &str = "grpc_host"; // grpc api service host
 pub const GRPC_SERVER_PORT_CONFIG_KEY: &str = "grpc_server_port"; // grpc api service port
 pub const NET_ID_CONFIG_KEY: &str = "net_id";
+pub const MESSAGE_PADDING_CONFIG_KEY: &str = "message_padding"; // pad encrypted messages to size buckets. Must be the same for all nodes of a network
+pub const GRPC_ADMIN_HOST_CONFIG_KEY: &str = "grpc_admin_host"; // grpc admin service host. Defaults to localhost
 pub const GRPC_ADMIN_PORT_CONFIG_KEY: &str = "grpc_admin_port";
+pub const ADMIN_KEY_CONFIG_KEY: &str = "admin_key"; // when set, admin service callers must provide it
 pub const START_GRPC_SERVER_ADMIN_SERVICE_CONFIG_KEY: &str = "start_grpc_admin_service";
 pub const START_GRPC_SERVICE_CONFIG_KEY: &str = "start_grpc_service";
+pub const PUBLIC_HOST_CONFIG_KEY: &str = "public_host"; // host advertised in dialup info. Defaults to grpc_host
+pub const PUBLIC_PORT_CONFIG_KEY: &str = "public_port"; // grpc port advertised in dialup info. Defaults to grpc_server_port
+pub const API_VERSION_CONFIG_KEY: &str = "api_version"; // api version advertised in dialup info
+pub const MIN_API_VERSION_CONFIG_KEY: &str = "min_api_version"; // oldest protocol version accepted from callers and advertised in dialup info
+pub const ENDPOINTS_CONFIG_KEY: &str = "endpoints"; // advertised endpoints. When set, replaces the endpoints derived from host and ports
+pub const TLS_CERT_FILE_CONFIG_KEY: &str = "tls_cert_file"; // pem cert chain. grpc servers use tls when cert and key are set
+pub const TLS_KEY_FILE_CONFIG_KEY: &str = "tls_key_file"; // pem private key
+pub const TLS_CA_CERT_FILE_CONFIG_KEY: &str = "tls_ca_cert_file"; // extra pem CA cert to trust when dialing tls endpoints
+pub const JSON_HTTP_PORT_CONFIG_KEY: &str = "json_http_port"; // json/http gateway port. Gateway is disabled when not set
+pub const HANDOVER_GRACE_PERIOD_CONFIG_KEY: &str = "handover_grace_period"; // secs to keep handling messages for a client that moved to another provider
+pub const CLIENT_MESSAGES_PER_MINUTE_CONFIG_KEY: &str = "client_messages_per_minute"; // max requests per client. Offered in service terms. 0 for no limit
+pub const IP_REQUESTS_PER_MINUTE_CONFIG_KEY: &str = "ip_requests_per_minute"; // max requests per remote ip address. 0 for no limit
+pub const NEW_SESSION_POW_DIFFICULTY_CONFIG_KEY: &str = "new_session_pow_difficulty"; // required leading zero bits of new session requests proof of work. 0 to disable
+pub const FAN_OUT_MAX_RECEIVERS_CONFIG_KEY: &str = "fan_out_max_receivers"; // max receivers per group message fan out request. Offered in service terms. 0 for no limit
+pub const MAX_FILE_SIZE_CONFIG_KEY: &str = "max_file_size"; // max size in bytes of a message routed to a served client. Offered in service terms. 0 for no limit
 
 pub struct ServerConfigService {
     config: Config,
@@ -48,6 +73,8 @@ impl Default for ServerConfigService {
         config
             .set_default(NET_ID_CONFIG_KEY, 0)
             .unwrap()
+            .set_default(MESSAGE_PADDING_CONFIG_KEY, false)
+            .unwrap()
             .set_default(DROP_DB_CONFIG_KEY, DEFAULT_DROP_DB_ON_EXIT)
             .unwrap()
             .set_default(START_GRPC_SERVICE_CONFIG_KEY, DEFAULT_START_GRPC_SERVICE)
@@ -63,6 +90,32 @@ impl Default for ServerConfigService {
             .unwrap()
             .set_default(GRPC_HOST_CONFIG_KEY, "[::1]")
             .unwrap()
+            .set_default(GRPC_ADMIN_HOST_CONFIG_KEY, "[::1]")
+            .unwrap()
+            .set_default(
+                HANDOVER_GRACE_PERIOD_CONFIG_KEY,
+                DEFAULT_HANDOVER_GRACE_PERIOD_SECS,
+            )
+            .unwrap()
+            .set_default(
+                CLIENT_MESSAGES_PER_MINUTE_CONFIG_KEY,
+                DEFAULT_CLIENT_MESSAGES_PER_MINUTE,
+            )
+            .unwrap()
+            .set_default(
+                IP_REQUESTS_PER_MINUTE_CONFIG_KEY,
+                DEFAULT_IP_REQUESTS_PER_MINUTE,
+            )
+            .unwrap()
+            .set_default(NEW_SESSION_POW_DIFFICULTY_CONFIG_KEY, 0)
+            .unwrap()
+            .set_default(
+                FAN_OUT_MAX_RECEIVERS_CONFIG_KEY,
+                DEFAULT_FAN_OUT_MAX_RECEIVERS,
+            )
+            .unwrap()
+            .set_default(MAX_FILE_SIZE_CONFIG_KEY, DEFAULT_MAX_FILE_SIZE)
+            .unwrap()
             // we always want to have a peer name - even a generic one
             .set_default(PEER_NAME_CONFIG_KEY, "my_peer")
             .unwrap()
@@ -99,6 +152,18 @@ impl ServerConfigService {
         Ok(res)
     }
 
+    // helper
+    pub async fn get_endpoints() -> Result<Option<Vec<EndpointConfig>>> {
+        let config = ServerConfigService::from_registry().await?;
+        config.call(GetEndpoints).await?
+    }
+
+    // helper
+    pub async fn get_all() -> Result<HashMap<String, String>> {
+        let config = ServerConfigService::from_registry().await?;
+        config.call(GetAllValues).await?
+    }
+
     pub async fn set(key: String, value: String) -> Result<()> {
         let config = ServerConfigService::from_registry().await?;
         config.call(SetValue { key, value }).await?
@@ -117,6 +182,38 @@ impl ServerConfigService {
     }
 }
 
+/// An api endpoint a provider advertises in its dialup info, configured in the endpoints list.
+/// e.g. { "end_point": "grpc_web2s", "host": "provider.example.com", "port": 443 }
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EndpointConfig {
+    pub end_point: String, // grpc_web2, grpc_web2s, json_http or json_https
+    pub host: String,
+    pub port: u32,
+    #[serde(default)]
+    pub api_version: Option<String>, // defaults to the provider's api version
+    #[serde(default)]
+    pub min_api_version: Option<String>, // defaults to the provider's min api version
+}
+
+#[message(result = "Result<Option<Vec<EndpointConfig>>>")]
+pub struct GetEndpoints;
+
+/// Returns the configured endpoints list or None when no endpoints are configured
+#[async_trait::async_trait]
+impl Handler<GetEndpoints> for ServerConfigService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: GetEndpoints,
+    ) -> Result<Option<Vec<EndpointConfig>>> {
+        match self.config.get::<Vec<EndpointConfig>>(ENDPOINTS_CONFIG_KEY) {
+            Ok(endpoints) => Ok(Some(endpoints)),
+            Err(ConfigError::NotFound(_)) => Ok(None),
+            Err(e) => Err(anyhow!("invalid endpoints config: {:?}", e)),
+        }
+    }
+}
+
 #[message(result = "Result<()>")]
 pub struct SetConfigFile {
     pub config_file: String,
@@ -139,6 +236,28 @@ impl Handler<SetConfigFile> for ServerConfigService {
     }
 }
 
+#[message(result = "Result<HashMap<String, String>>")]
+pub struct GetAllValues;
+
+/// Returns all config values
+#[async_trait::async_trait]
+impl Handler<GetAllValues> for ServerConfigService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: GetAllValues,
+    ) -> Result<HashMap<String, String>> {
+        let table = self
+            .config
+            .cache
+            .clone()
+            .into_table()
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        Ok(table.into_iter().map(|(k, v)| (k, v.to_string())).collect())
+    }
+}
+
 #[message(result = "Option<bool>")]
 pub struct GetBool(pub String);
 
@@ -0,0 +1,182 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::secp256k1;
+use crate::snp::snp_core_types::Signature;
+use anyhow::{anyhow, bail, Result};
+use ed25519_dalek::Verifier;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// Supported signature schemes. A scheme id is stored with each signature so verifiers know how to verify it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureScheme {
+    Ed25519 = 0,
+    /// Ethereum style secp256k1 ECDSA over EIP-191 personal messages so an Ethereum wallet can sign
+    Secp256k1 = 1,
+}
+
+impl Display for SignatureScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureScheme::Ed25519 => write!(f, "ed25519"),
+            SignatureScheme::Secp256k1 => write!(f, "secp256k1"),
+        }
+    }
+}
+
+impl SignatureScheme {
+    /// Returns the scheme with the provided id or an error for an unknown scheme
+    pub fn from_id(scheme_id: u32) -> Result<SignatureScheme> {
+        match scheme_id {
+            0 => Ok(SignatureScheme::Ed25519),
+            1 => Ok(SignatureScheme::Secp256k1),
+            _ => bail!("unsupported signature scheme {}", scheme_id),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Verify a signature of data by a public key using this scheme
+    pub fn verify(&self, public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<()> {
+        match self {
+            SignatureScheme::Ed25519 => {
+                let public_key = ed25519_dalek::PublicKey::from_bytes(public_key)?;
+                let signature = ed25519_dalek::Signature::from_bytes(signature)?;
+                Ok(public_key.verify(data, &signature)?)
+            }
+            SignatureScheme::Secp256k1 => secp256k1::verify(public_key, data, signature),
+        }
+    }
+}
+
+/// A private key which can sign data using one of the supported signature schemes
+pub trait SigningKey {
+    fn scheme(&self) -> SignatureScheme;
+
+    /// Public key data of the signer as expected by the scheme's verifier
+    fn public_key(&self) -> Vec<u8>;
+
+    fn sign_data(&self, data: &[u8]) -> Vec<u8>;
+}
+
+impl SigningKey for ed25519_dalek::Keypair {
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::Ed25519
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.public.as_bytes().to_vec()
+    }
+
+    fn sign_data(&self, data: &[u8]) -> Vec<u8> {
+        use ed25519_dalek::Signer;
+        self.sign(data).to_bytes().to_vec()
+    }
+}
+
+impl SigningKey for secp256k1::SecretKey {
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::Secp256k1
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        secp256k1::SecretKey::public_key(self)
+    }
+
+    fn sign_data(&self, data: &[u8]) -> Vec<u8> {
+        self.sign(data)
+    }
+}
+
+/// Sign a message's binary data. Callers should clear the message signature fields before signing.
+pub fn sign_message<M: prost::Message>(message: &M, signer: &dyn SigningKey) -> Result<Signature> {
+    let mut buf = Vec::with_capacity(message.encoded_len());
+    message.encode(&mut buf)?;
+    Ok(Signature {
+        scheme_id: signer.scheme().id(),
+        signature: signer.sign_data(&buf),
+    })
+}
+
+/// Verify a signature of a message's binary data by a public key.
+/// The message must have its signature fields cleared as they were when it was signed.
+pub fn verify_message<M: prost::Message>(
+    message: &M,
+    public_key: &[u8],
+    signature: &Signature,
+) -> Result<()> {
+    let scheme = SignatureScheme::from_id(signature.scheme_id)?;
+    let mut buf = Vec::with_capacity(message.encoded_len());
+    if message.encode(&mut buf).is_err() {
+        return Err(anyhow!("failed to encode source data to binary data"));
+    };
+
+    scheme.verify(public_key, &buf, signature.signature.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types_extensions::Signed;
+    use crate::snp::snp_core_types::{EntityId, ProviderIdentityBundle, PublicKey};
+    use rand_core::OsRng;
+
+    fn new_bundle(signer: &dyn SigningKey) -> ProviderIdentityBundle {
+        ProviderIdentityBundle {
+            time_stamp: 0,
+            provider_id: Some(EntityId {
+                public_key: Some(PublicKey {
+                    key: signer.public_key(),
+                }),
+                nickname: "provider".into(),
+            }),
+            address: None,
+            dial_up_info: vec![],
+            provider_signature: None,
+            net_id: 0,
+            pre_key: None,
+            one_time_keys: vec![],
+            profile_image: None,
+            current_bond_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_sign_verify_schemes() {
+        let ed25519_key = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let secp256k1_key = secp256k1::SecretKey::generate();
+        let signers: [&dyn SigningKey; 2] = [&ed25519_key, &secp256k1_key];
+
+        for signer in signers {
+            let mut bundle = new_bundle(signer);
+            bundle.sign(signer).unwrap();
+            assert_eq!(
+                bundle.provider_signature.as_ref().unwrap().scheme_id,
+                signer.scheme().id()
+            );
+            bundle.verify_signature().unwrap();
+
+            bundle.time_stamp = 1;
+            assert!(bundle.verify_signature().is_err());
+        }
+    }
+
+    #[test]
+    fn test_unknown_scheme_rejected() {
+        let key_pair = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let mut bundle = new_bundle(&key_pair);
+        bundle.sign(&key_pair).unwrap();
+        bundle.provider_signature.as_mut().unwrap().scheme_id = 7;
+
+        let err = bundle.verify_signature().unwrap_err();
+        assert!(err.to_string().contains("unsupported signature scheme 7"));
+
+        // a signature must be verified with the scheme it was created with
+        bundle.provider_signature.as_mut().unwrap().scheme_id = SignatureScheme::Secp256k1.id();
+        assert!(bundle.verify_signature().is_err());
+    }
+}
@@ -2,6 +2,7 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use snp_blockchain as blockchain;
 use snp_core_types as core_types;
 use snp_payments as payments;
 
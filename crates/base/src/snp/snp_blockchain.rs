@@ -1,4 +1,4 @@
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Account {
     #[prost(message, optional, tag = "1")]
     pub address: ::core::option::Option<super::payments::Address>,
@@ -7,7 +7,7 @@ pub struct Account {
     #[prost(message, repeated, tag = "3")]
     pub balances: ::prost::alloc::vec::Vec<super::payments::Amount>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Block {
     /// incremental id
     #[prost(uint64, tag = "1")]
@@ -22,7 +22,7 @@ pub struct Block {
     pub signature: ::prost::alloc::vec::Vec<u8>,
 }
 /// Transaction fee
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct TransactionFee {
     /// fee amount
     #[prost(message, optional, tag = "1")]
@@ -35,7 +35,7 @@ pub struct TransactionFee {
 /// When sender pays the fee he provides the fee amount and signs it as part of the tx signature and the fee_signature is empty.
 /// When another party pays the transaction fee, the sender signs all fields besides the fee and the fee payer provides the fee info and signs it and the other tx fields.
 /// The 3rd party tx fee feature is designed so providers can pay the transaction fees for transactions related to provided service for their users.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Transaction {
     /// sender pub key
     #[prost(bytes = "vec", tag = "1")]
@@ -58,6 +58,12 @@ pub struct Transaction {
     /// signature of tx fee payer on all other fields in case sender doesn't pay the fee. Empty otherwise
     #[prost(bytes = "vec", tag = "10")]
     pub fee_signature: ::prost::alloc::vec::Vec<u8>,
+    /// signature scheme of the sender signature (snp.core_types.Signature scheme id). 0 is ed25519
+    #[prost(uint32, tag = "11")]
+    pub signature_scheme_id: u32,
+    /// signature scheme of the fee payer signature
+    #[prost(uint32, tag = "12")]
+    pub fee_signature_scheme_id: u32,
     /// Transaction data
     #[prost(oneof = "transaction::Data", tags = "5, 6, 7")]
     pub data: ::core::option::Option<transaction::Data>,
@@ -65,7 +71,7 @@ pub struct Transaction {
 /// Nested message and enum types in `Transaction`.
 pub mod transaction {
     /// Transaction data
-    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Oneof)]
     pub enum Data {
         #[prost(message, tag = "5")]
         PaymentTransaction(super::PaymentTransactionData),
@@ -76,7 +82,7 @@ pub mod transaction {
     }
 }
 /// a blockchain transaction - can be a user-to-user payment or a user-to-provider payment
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PaymentTransactionData {
     /// receiver account
     #[prost(message, optional, tag = "1")]
@@ -89,20 +95,20 @@ pub struct PaymentTransactionData {
     pub id: u64,
 }
 /// Provider identity bundle
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ProviderBundleTransactionData {
     #[prost(message, optional, tag = "1")]
     pub provider_bundle: ::core::option::Option<super::core_types::ProviderIdentityBundle>,
 }
 /// Users client identity bundle signed by a provider
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ClientBundleTransactionData {
     #[prost(message, optional, tag = "1")]
     pub client_bundle:
         ::core::option::Option<super::core_types::ProviderSignedClientIdentityBundle>,
 }
 /// Information about a transaction obtainable from pool or from ledger
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct TransactionInfo {
     /// this is a h ash of binary Transaction data - implied from transaction
     #[prost(message, optional, tag = "1")]
@@ -119,66 +125,67 @@ pub struct TransactionInfo {
     #[prost(uint64, tag = "5")]
     pub block_id: u64,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SubmitTransactionRequest {
     #[prost(message, optional, tag = "1")]
     pub transaction: ::core::option::Option<Transaction>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SubmitTransactionResponse {
     /// computed by node - just hash of transaction binary data
     #[prost(message, optional, tag = "1")]
     pub id: ::core::option::Option<super::payments::TransactionId>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetProviderIdentityBundleRequest {
     #[prost(message, optional, tag = "1")]
     pub entity_id: ::core::option::Option<super::core_types::EntityId>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetProviderIdentityBundleResponse {
     #[prost(message, optional, tag = "1")]
     pub provider_bundle: ::core::option::Option<super::core_types::ProviderIdentityBundle>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientIdentityBundleRequest {
+    /// client id. Clients may be looked up by their registered nickname using an entity id w/o a public key
     #[prost(message, optional, tag = "1")]
     pub entity_id: ::core::option::Option<super::core_types::EntityId>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientIdentityBundleResponse {
     #[prost(message, optional, tag = "1")]
     pub client_bundle:
         ::core::option::Option<super::core_types::ProviderSignedClientIdentityBundle>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientsRequest {}
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientsResponse {
     #[prost(message, repeated, tag = "1")]
     pub clients_bundles:
         ::prost::alloc::vec::Vec<super::core_types::ProviderSignedClientIdentityBundle>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetProvidersRequest {}
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetProvidersResponse {
     #[prost(message, repeated, tag = "1")]
     pub providers_bundles: ::prost::alloc::vec::Vec<super::core_types::ProviderIdentityBundle>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetCurrentBlockRequest {}
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetBlockRequest {
     #[prost(uint64, tag = "1")]
     pub block_id: u64,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetBlockResponse {
     #[prost(message, optional, tag = "1")]
     pub block: ::core::option::Option<Block>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SetBalanceRequest {
     /// address
     #[prost(message, optional, tag = "1")]
@@ -187,29 +194,29 @@ pub struct SetBalanceRequest {
     #[prost(message, optional, tag = "2")]
     pub amount: ::core::option::Option<super::payments::Amount>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SetBalanceResponse {}
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetTransactionRequest {
     #[prost(message, optional, tag = "1")]
     pub id: ::core::option::Option<super::payments::TransactionId>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetTransactionResponse {
     #[prost(message, optional, tag = "1")]
     pub transaction_info: ::core::option::Option<TransactionInfo>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetAccountRequest {
     #[prost(message, optional, tag = "1")]
     pub address: ::core::option::Option<super::payments::Address>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetAccountResponse {
     #[prost(message, optional, tag = "1")]
     pub account: ::core::option::Option<Account>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetBlocksCountByEntityRequest {
     #[prost(message, optional, tag = "1")]
     pub entity_id: ::core::option::Option<super::core_types::EntityId>,
@@ -217,14 +224,113 @@ pub struct GetBlocksCountByEntityRequest {
     #[prost(uint32, tag = "2")]
     pub max_count: u32,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetBlocksCountByEntityResponse {
     #[prost(uint64, tag = "1")]
     pub blocks_count: u64,
     #[prost(message, repeated, tag = "2")]
     pub blocks: ::prost::alloc::vec::Vec<Block>,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+/// An identity bundle version in the identity bundles transparency log.
+/// A leaf of the log's Merkle tree is an entry's binary data.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct TransparencyLogEntry {
+    /// entry index in the log
+    #[prost(uint64, tag = "1")]
+    pub index: u64,
+    /// client or provider which published the bundle
+    #[prost(message, optional, tag = "2")]
+    pub entity_id: ::core::option::Option<super::core_types::EntityId>,
+    /// set provider bundle or set client bundle
+    #[prost(enumeration = "TransactionType", tag = "3")]
+    pub transaction_type: i32,
+    /// sha256 hash of the binary data of the bundle signed by the entity
+    #[prost(bytes = "vec", tag = "4")]
+    pub bundle_hash: ::prost::alloc::vec::Vec<u8>,
+    /// bundle creation time
+    #[prost(uint64, tag = "5")]
+    pub bundle_time_stamp: u64,
+    /// block of the bundle transaction
+    #[prost(uint64, tag = "6")]
+    pub block_id: u64,
+}
+/// A tree head of the transparency log (RFC 6962)
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct TransparencyLogHead {
+    /// number of entries in the tree
+    #[prost(uint64, tag = "1")]
+    pub tree_size: u64,
+    /// Merkle tree root hash
+    #[prost(bytes = "vec", tag = "2")]
+    pub root_hash: ::prost::alloc::vec::Vec<u8>,
+    /// id of the log which signed this tree head
+    #[prost(message, optional, tag = "3")]
+    pub log_id: ::core::option::Option<super::core_types::EntityId>,
+    /// log signature on all other fields
+    #[prost(message, optional, tag = "4")]
+    pub signature: ::core::option::Option<super::core_types::Signature>,
+}
+/// A log entry with a proof of its inclusion in a tree head
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct TransparencyLogEntryProof {
+    #[prost(message, optional, tag = "1")]
+    pub entry: ::core::option::Option<TransparencyLogEntry>,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub inclusion_proof: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetTransparencyLogHeadRequest {}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetTransparencyLogHeadResponse {
+    #[prost(message, optional, tag = "1")]
+    pub head: ::core::option::Option<TransparencyLogHead>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetTransparencyLogConsistencyProofRequest {
+    #[prost(uint64, tag = "1")]
+    pub first_tree_size: u64,
+    /// must not be larger than the current tree size
+    #[prost(uint64, tag = "2")]
+    pub second_tree_size: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetTransparencyLogConsistencyProofResponse {
+    #[prost(message, optional, tag = "1")]
+    pub first_head: ::core::option::Option<TransparencyLogHead>,
+    #[prost(message, optional, tag = "2")]
+    pub second_head: ::core::option::Option<TransparencyLogHead>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub proof: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetIdentityBundlesHistoryRequest {
+    #[prost(message, optional, tag = "1")]
+    pub entity_id: ::core::option::Option<super::core_types::EntityId>,
+}
+/// An entity's history is not verifiably complete - a log may omit some of the entity's entries from it.
+/// Signed tree heads commit the log to all entries so an omission found by scanning the log can be proven.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GetIdentityBundlesHistoryResponse {
+    /// entries inclusion proofs are in this tree head
+    #[prost(message, optional, tag = "1")]
+    pub head: ::core::option::Option<TransparencyLogHead>,
+    /// oldest first
+    #[prost(message, repeated, tag = "2")]
+    pub entries: ::prost::alloc::vec::Vec<TransparencyLogEntryProof>,
+}
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum TransactionState {
     Unknown = 0,
@@ -241,8 +347,22 @@ pub enum TransactionState {
     Final = 9,
     /// not found in ledger
     Unrecognized = 10,
+    /// tx or its data is for another network
+    RejectedWrongNetwork = 11,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum TransactionType {
     Unknown = 0,
@@ -484,6 +604,59 @@ pub mod blockchain_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        #[doc = " Returns the current tree head of the append-only log of all published identity bundles"]
+        pub async fn get_transparency_log_head(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTransparencyLogHeadRequest>,
+        ) -> Result<tonic::Response<super::GetTransparencyLogHeadResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/snp.blockchain.BlockchainService/GetTransparencyLogHead",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Returns a proof that a tree head is a prefix of a newer tree head of the log"]
+        pub async fn get_transparency_log_consistency_proof(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTransparencyLogConsistencyProofRequest>,
+        ) -> Result<tonic::Response<super::GetTransparencyLogConsistencyProofResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/snp.blockchain.BlockchainService/GetTransparencyLogConsistencyProof",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Returns all log entries of an entity's identity bundles with their inclusion proofs in the current tree head"]
+        pub async fn get_identity_bundles_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIdentityBundlesHistoryRequest>,
+        ) -> Result<tonic::Response<super::GetIdentityBundlesHistoryResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/snp.blockchain.BlockchainService/GetIdentityBundlesHistory",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         #[doc = " Returns recent created blocks count by an entity - PoUW"]
         pub async fn get_validated_blocks_count_by_entity(
             &mut self,
@@ -577,6 +750,21 @@ pub mod blockchain_service_server {
             &self,
             request: tonic::Request<super::GetProvidersRequest>,
         ) -> Result<tonic::Response<super::GetProvidersResponse>, tonic::Status>;
+        #[doc = " Returns the current tree head of the append-only log of all published identity bundles"]
+        async fn get_transparency_log_head(
+            &self,
+            request: tonic::Request<super::GetTransparencyLogHeadRequest>,
+        ) -> Result<tonic::Response<super::GetTransparencyLogHeadResponse>, tonic::Status>;
+        #[doc = " Returns a proof that a tree head is a prefix of a newer tree head of the log"]
+        async fn get_transparency_log_consistency_proof(
+            &self,
+            request: tonic::Request<super::GetTransparencyLogConsistencyProofRequest>,
+        ) -> Result<tonic::Response<super::GetTransparencyLogConsistencyProofResponse>, tonic::Status>;
+        #[doc = " Returns all log entries of an entity's identity bundles with their inclusion proofs in the current tree head"]
+        async fn get_identity_bundles_history(
+            &self,
+            request: tonic::Request<super::GetIdentityBundlesHistoryRequest>,
+        ) -> Result<tonic::Response<super::GetIdentityBundlesHistoryResponse>, tonic::Status>;
         #[doc = " Returns recent created blocks count by an entity - PoUW"]
         async fn get_validated_blocks_count_by_entity(
             &self,
@@ -976,6 +1164,117 @@ pub mod blockchain_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/snp.blockchain.BlockchainService/GetTransparencyLogHead" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTransparencyLogHeadSvc<T: BlockchainService>(pub Arc<T>);
+                    impl<T: BlockchainService>
+                        tonic::server::UnaryService<super::GetTransparencyLogHeadRequest>
+                        for GetTransparencyLogHeadSvc<T>
+                    {
+                        type Response = super::GetTransparencyLogHeadResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTransparencyLogHeadRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).get_transparency_log_head(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTransparencyLogHeadSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/snp.blockchain.BlockchainService/GetTransparencyLogConsistencyProof" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTransparencyLogConsistencyProofSvc<T: BlockchainService>(pub Arc<T>);
+                    impl<T: BlockchainService>
+                        tonic::server::UnaryService<
+                            super::GetTransparencyLogConsistencyProofRequest,
+                        > for GetTransparencyLogConsistencyProofSvc<T>
+                    {
+                        type Response = super::GetTransparencyLogConsistencyProofResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::GetTransparencyLogConsistencyProofRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner)
+                                    .get_transparency_log_consistency_proof(request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTransparencyLogConsistencyProofSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/snp.blockchain.BlockchainService/GetIdentityBundlesHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIdentityBundlesHistorySvc<T: BlockchainService>(pub Arc<T>);
+                    impl<T: BlockchainService>
+                        tonic::server::UnaryService<super::GetIdentityBundlesHistoryRequest>
+                        for GetIdentityBundlesHistorySvc<T>
+                    {
+                        type Response = super::GetIdentityBundlesHistoryResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetIdentityBundlesHistoryRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut =
+                                async move { (*inner).get_identity_bundles_history(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIdentityBundlesHistorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/snp.blockchain.BlockchainService/GetValidatedBlocksCountByEntity" => {
                     #[allow(non_camel_case_types)]
                     struct GetValidatedBlocksCountByEntitySvc<T: BlockchainService>(pub Arc<T>);
@@ -6,13 +6,13 @@
 /////////////
 
 /// Request to subscribe to a channel or to join a group
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelSubscriptionRequest {
     #[prost(message, optional, tag = "1")]
     pub subscription_request_data:
         ::core::option::Option<super::core_types::ChannelSubscriptionRequestData>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelSubscriptionResponse {
     #[prost(bytes = "vec", tag = "1")]
     pub channel_id: ::prost::alloc::vec::Vec<u8>,
@@ -25,8 +25,30 @@ pub struct ChannelSubscriptionResponse {
 }
 /////////////
 
+/// Reminder from a paid status updates channel creator to a subscriber that its subscription fee is due.
+/// Subscriber stops getting updates when it doesn't renew its subscription in a grace period after it is due
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ChannelPaymentReminder {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub subscription_fee: ::core::option::Option<super::payments::Amount>,
+    #[prost(uint64, tag = "3")]
+    pub time_payment_due: u64,
+}
+/// Subscriber's renewal of a paid status updates channel subscription. Sent to channel creator
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ChannelSubscriptionRenewal {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    /// subscription fee payment tx id
+    #[prost(message, optional, tag = "2")]
+    pub tx_id: ::core::option::Option<super::payments::TransactionId>,
+}
+/////////////
+
 /// Request to unsubscribe from a status update channel or to leave a group
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct CancelChannelSubscription {
     #[prost(uint64, tag = "1")]
     pub time_stamp: u64,
@@ -38,7 +60,7 @@ pub struct CancelChannelSubscription {
     #[prost(string, tag = "4")]
     pub message: ::prost::alloc::string::String,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct CancelChannelSubscriptionResponse {
     #[prost(bytes = "vec", tag = "1")]
     pub channel_id: ::prost::alloc::vec::Vec<u8>,
@@ -47,21 +69,97 @@ pub struct CancelChannelSubscriptionResponse {
 
 /// New channel message
 /// Sent from channel creator to all status updates subscribers (or for a group, to group members)
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct NewChannelMessage {
     #[prost(message, optional, tag = "1")]
     pub content_item: ::core::option::Option<super::core_types::ChannelContentItem>,
 }
+/////////////
+
+/// A group sender key. Sent by a group's publisher to each group member over their pairwise DR session.
+/// Members use it to decrypt the publisher's group messages which it encrypts once for all members.
+/// Publisher sends members a new key when the group's membership changes
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GroupSenderKey {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    /// random key id. Identifies the key of a group message
+    #[prost(bytes = "vec", tag = "2")]
+    pub key_id: ::prost::alloc::vec::Vec<u8>,
+    /// symmetric ratchet chain key
+    #[prost(bytes = "vec", tag = "3")]
+    pub chain_key: ::prost::alloc::vec::Vec<u8>,
+    /// ratchet iteration of chain_key
+    #[prost(uint32, tag = "4")]
+    pub iteration: u32,
+    /// ed25519 public key of the publisher's group messages signing key
+    #[prost(bytes = "vec", tag = "5")]
+    pub signing_key: ::prost::alloc::vec::Vec<u8>,
+}
+/////////////
+
+/// A request from a group co-admin to the group creator to make a membership change
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GroupAdminRequest {
+    #[prost(message, optional, tag = "1")]
+    pub change: ::core::option::Option<super::core_types::GroupAdminChange>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GroupAdminResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bool, tag = "2")]
+    pub accepted: bool,
+    /// rejection reason
+    #[prost(string, tag = "3")]
+    pub message: ::prost::alloc::string::String,
+}
+/// An updated group members bundle. Sent by the group creator to the group's members and to removed members
+/// when the group's membership changes
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GroupMembersUpdate {
+    #[prost(message, optional, tag = "1")]
+    pub members: ::core::option::Option<super::core_types::GroupMembersBundle>,
+}
+///////////////
+
+/// A request from a status updates subscriber or a group member to the channel's creator for content published
+/// to the channel before the requester subscribed or joined. Pages are returned newest items first
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ChannelHistoryRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    /// only items created before this time stamp. 0 for the latest items
+    #[prost(uint64, tag = "2")]
+    pub before: u64,
+    /// max items in the page. Creator may return fewer
+    #[prost(uint32, tag = "3")]
+    pub max_items: u32,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ChannelHistoryResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    /// each item is signed by the channel's creator
+    #[prost(message, repeated, tag = "2")]
+    pub content_items: ::prost::alloc::vec::Vec<super::core_types::ChannelContentItem>,
+    /// there are older items before the page's last item
+    #[prost(bool, tag = "3")]
+    pub has_more: bool,
+    /// rejection reason, e.g. requester isn't a paid up subscriber
+    #[prost(string, tag = "4")]
+    pub error: ::prost::alloc::string::String,
+}
 ///////////////
 
 /// A request from a client to post a message to a channel. Sent from author to channel owner.
 /// Message is a reply to a status message or a group message in a group that client is member of.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct NewChannelMessageRequest {
     #[prost(message, optional, tag = "1")]
     pub content_item: ::core::option::Option<super::core_types::ContentItem>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct NewChannelMessageResponse {}
 //////////////
 //
@@ -71,21 +169,21 @@ pub struct NewChannelMessageResponse {}
 /////////////
 
 /// Request to list all current content items for sale by a client
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ListPaidItemsRequest {}
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ListPaidItemsResponse {
     #[prost(message, repeated, tag = "1")]
     pub content_items: ::prost::alloc::vec::Vec<super::core_types::ContentItem>,
 }
 /// Request to return meta data about a content items
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetItemDataRequest {
     #[prost(uint64, tag = "1")]
     pub item_id: u64,
 }
 /// Response - a content item with an empty MediaItems is returned with all content meta-data
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetItemDataResponse {
     #[prost(message, optional, tag = "1")]
     pub item: ::core::option::Option<super::core_types::ContentItem>,
@@ -93,14 +191,14 @@ pub struct GetItemDataResponse {
 /////////////
 
 /// Request to unsubscribe from a status update channel or to leave a group
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct BuyItemRequest {
     #[prost(uint64, tag = "1")]
     pub item_id: u64,
     #[prost(message, optional, tag = "2")]
     pub transaction_id: ::core::option::Option<super::payments::TransactionId>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct BuyItemResponse {
     #[prost(enumeration = "BuyItemResult", tag = "1")]
     pub result: i32,
@@ -109,10 +207,91 @@ pub struct BuyItemResponse {
     #[prost(message, optional, tag = "3")]
     pub item: ::core::option::Option<super::core_types::ContentItem>,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum BuyItemResult {
     InvalidTransaction = 0,
     ItemNotFound = 1,
     Success = 2,
 }
+/// A batch of receipts of messages sent by the receipts receiver
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct MessageReceipts {
+    #[prost(enumeration = "ReceiptType", tag = "1")]
+    pub receipt_type: i32,
+    /// ids of the received messages content items
+    #[prost(uint64, repeated, tag = "2")]
+    pub message_ids: ::prost::alloc::vec::Vec<u64>,
+    /// time messages were delivered or read
+    #[prost(uint64, tag = "3")]
+    pub time_stamp: u64,
+}
+//////////////
+//
+// SNP - Receipts client-to-client network protocol
+// Receipts a client sends to another client about 1:1 messages it received from it
+//
+/////////////
+
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum ReceiptType {
+    /// messages reached the receiver's client
+    Delivered = 0,
+    /// receiver's user read the messages
+    Read = 1,
+}
+//////////////
+//
+// SNP - Attachments client-to-client network protocol
+// 1:1 messages with media items which may be larger than the max file size supported by providers are split to
+// chunks which are sent over the DR session between the clients and reassembled by the receiver
+//
+/////////////
+
+/// A chunk of a signed and encoded 1:1 message ContentItem
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct AttachmentChunk {
+    /// unique id of the transfer of the content item by its sender
+    #[prost(uint64, tag = "1")]
+    pub transfer_id: u64,
+    /// zero based index of this chunk
+    #[prost(uint32, tag = "2")]
+    pub chunk_index: u32,
+    /// number of chunks in the transfer
+    #[prost(uint32, tag = "3")]
+    pub chunks_count: u32,
+    /// chunk data
+    #[prost(bytes = "vec", tag = "4")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    /// sha256 of data
+    #[prost(bytes = "vec", tag = "5")]
+    pub chunk_hash: ::prost::alloc::vec::Vec<u8>,
+    /// sha256 of the encoded content item
+    #[prost(bytes = "vec", tag = "6")]
+    pub content_hash: ::prost::alloc::vec::Vec<u8>,
+}
@@ -10,12 +10,12 @@ pub struct PublicKey {
     #[prost(bytes = "vec", tag = "1")]
     pub key: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PrivateKey {
     #[prost(bytes = "vec", tag = "1")]
     pub key: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct KeyPair {
     #[prost(message, optional, tag = "1")]
     pub private_key: ::core::option::Option<PrivateKey>,
@@ -23,7 +23,7 @@ pub struct KeyPair {
     pub public_key: ::core::option::Option<PublicKey>,
 }
 /// an x2dh or x3dh pre-key
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PreKey {
     /// x2dh protocol semantic version
     #[prost(string, tag = "1")]
@@ -34,8 +34,11 @@ pub struct PreKey {
     /// unique key id. This is the id of the bundle which published this prekey
     #[prost(uint64, tag = "3")]
     pub key_id: u64,
+    /// ml-kem-768 encapsulation key of hybrid pre-keys. Required by hybrid x2dh versions
+    #[prost(bytes = "vec", tag = "4")]
+    pub kem_key: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PreKeypair {
     /// x2dh protocol semantic version
     #[prost(string, tag = "1")]
@@ -57,7 +60,7 @@ pub struct EntityId {
     #[prost(string, tag = "2")]
     pub nickname: ::prost::alloc::string::String,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PrivateEntityId {
     #[prost(message, optional, tag = "1")]
     pub key_pair: ::core::option::Option<KeyPair>,
@@ -71,7 +74,7 @@ pub struct Signature {
     #[prost(bytes = "vec", tag = "2")]
     pub signature: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ContentItem {
     /// unique item id
     #[prost(uint64, tag = "1")]
@@ -85,7 +88,7 @@ pub struct ContentItem {
     /// item's author
     #[prost(message, optional, tag = "4")]
     pub author: ::core::option::Option<EntityId>,
-    /// optional expiration for self-destructing messages
+    /// optional expiration for self-destructing messages. Seconds from creation, 0 for none
     #[prost(uint64, tag = "5")]
     pub ttl: u64,
     /// price for a paid content item. 0 otherwise
@@ -104,7 +107,7 @@ pub struct ContentItem {
     #[prost(message, optional, tag = "10")]
     pub signature: ::core::option::Option<Signature>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct MediaItem {
     /// content unique id, for reference from other content
     #[prost(uint32, tag = "1")]
@@ -121,7 +124,7 @@ pub struct MediaItem {
     #[prost(bytes = "vec", tag = "5")]
     pub content: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ServiceTermsBundle {
     /// Provider public id
     #[prost(message, optional, tag = "1")]
@@ -134,7 +137,7 @@ pub struct ServiceTermsBundle {
     pub signature: ::core::option::Option<Signature>,
 }
 /// A set of pre-keys for an entity (provider or client)
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct EntityPreKeys {
     #[prost(message, optional, tag = "1")]
     pub entity_id: ::core::option::Option<EntityId>,
@@ -144,7 +147,19 @@ pub struct EntityPreKeys {
     pub one_time_keys: ::prost::alloc::vec::Vec<PublicKey>,
 }
 /// Supported media items' media types
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum MimeType {
     TextUtf8 = 0,
@@ -157,7 +172,19 @@ pub enum MimeType {
     AudioM4a = 7,
 }
 /// A compression codec. Specifies the codec name and min required implementation version
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum CompressionCodec {
     None = 0,
@@ -173,7 +200,7 @@ pub enum CompressionCodec {
 /// Public service provider identity bundle.
 /// See X2dh protocol for additional details.
 /// Only include public data
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ProviderIdentityBundle {
     /// unique publishing time per provider
     #[prost(uint64, tag = "1")]
@@ -209,7 +236,7 @@ pub struct ProviderIdentityBundle {
 /// Provider identity bundle with private data corresponding to the public data.
 /// Private data includes private keys for public keys shared via the public bundle
 /// todo: move this to upsetter package - this is a server implementation data object not an SNP type.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PrivateProviderIdentityBundle {
     #[prost(message, optional, tag = "1")]
     pub public_bundle: ::core::option::Option<ProviderIdentityBundle>,
@@ -224,7 +251,7 @@ pub struct PrivateProviderIdentityBundle {
 }
 /// Client published bundle specifying current provider and x2dh pre-keys
 /// Client represents a pseudo-anon identity that has its private key.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ClientIdentityBundle {
     #[prost(uint64, tag = "1")]
     pub time_stamp: u64,
@@ -254,7 +281,7 @@ pub struct ClientIdentityBundle {
     pub net_id: u32,
 }
 /// Provider client service data - not API specific - move to data objects package
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ClientServiceData {
     #[prost(uint64, tag = "1")]
     pub service_started: u64,
@@ -262,9 +289,24 @@ pub struct ClientServiceData {
     pub service_ended: u64,
     #[prost(message, optional, tag = "3")]
     pub client_identity_bundle: ::core::option::Option<ClientIdentityBundle>,
+    /// client bundle with its new provider. Set when client moved to another provider
+    #[prost(message, optional, tag = "4")]
+    pub next_client_bundle: ::core::option::Option<ClientIdentityBundle>,
+    /// provider admin suspended service to the client
+    #[prost(bool, tag = "5")]
+    pub service_suspended: bool,
+    /// terms client is served with
+    #[prost(message, optional, tag = "6")]
+    pub service_terms: ::core::option::Option<super::payments::ServiceTerms>,
+    /// token which authorizes delivery of sealed-sender messages to the client
+    #[prost(bytes = "vec", tag = "7")]
+    pub delivery_token: ::prost::alloc::vec::Vec<u8>,
+    /// Snp protocol semantic version negotiated with the client. Gates protocol features in messages to the client
+    #[prost(string, tag = "8")]
+    pub protocol_version: ::prost::alloc::string::String,
 }
 /// Provider published client bundle - includes provider signature on the data
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ProviderSignedClientIdentityBundle {
     #[prost(message, optional, tag = "1")]
     pub client_bundle: ::core::option::Option<ClientIdentityBundle>,
@@ -293,6 +335,9 @@ pub struct DialupInfo {
     /// provider chosen name
     #[prost(string, tag = "6")]
     pub name: ::prost::alloc::string::String,
+    /// oldest api semantic version supported by the endpoint. api_version when empty
+    #[prost(string, tag = "7")]
+    pub min_api_version: ::prost::alloc::string::String,
 }
 /// Provider info includes public key and dialup info
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
@@ -300,16 +345,28 @@ pub struct ProviderNetInfo {
     /// provider id
     #[prost(message, optional, tag = "1")]
     pub provider_id: ::core::option::Option<EntityId>,
-    /// provider dialup info
-    #[prost(message, optional, tag = "2")]
-    pub dial_up_info: ::core::option::Option<DialupInfo>,
+    /// provider advertised api endpoints
+    #[prost(message, repeated, tag = "2")]
+    pub dial_up_info: ::prost::alloc::vec::Vec<DialupInfo>,
     /// data must be signed by provider
     #[prost(message, optional, tag = "3")]
     pub signature: ::core::option::Option<Signature>,
 }
 /// basic types
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum ApiEndPoint {
     Unspecified = 0,
@@ -329,7 +386,7 @@ pub enum ApiEndPoint {
 
 /// Request to subscribe to a channel or to a group.
 /// Note that there's no need to sign as app-level protocol messages are always delivered as a signed TypedMessage
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelSubscriptionRequestData {
     #[prost(uint64, tag = "1")]
     pub time_stamp: u64,
@@ -350,7 +407,7 @@ pub struct ChannelSubscriptionRequestData {
 /// A signed immutable bundle describing a status updates channel or a group channel.
 /// Designed to be made available for subscribers who have obtained channel_id.
 /// Signed by channel id and user to ensure that no-one else can create a channel with the same channel id
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelBundle {
     /// Channel public key id
     #[prost(message, optional, tag = "1")]
@@ -391,7 +448,7 @@ pub struct ChannelBundle {
 }
 /// Private data stored on publisher's client with mutable channel's or group's state.
 /// Channel can be public or private, status update channel or a group channel
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelData {
     /// current channel bundle and its data
     #[prost(message, optional, tag = "1")]
@@ -422,27 +479,36 @@ pub struct ChannelData {
     /// channel id private key corresponding to channel_id so creator can sign with channel id.
     #[prost(bytes = "vec", tag = "10")]
     pub channel_key_pair: ::prost::alloc::vec::Vec<u8>,
+    /// Users banned by a group admin. Their requests to join the group are rejected.
+    #[prost(message, repeated, tag = "11")]
+    pub banned_members: ::prost::alloc::vec::Vec<EntityId>,
+    /// when true, requests to join the group are pending until a group admin approves them.
+    #[prost(bool, tag = "12")]
+    pub approve_members: bool,
 }
 /// Data maintained by channel subscriber (or group member) client
 ///
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelClientData {
     #[prost(message, repeated, tag = "1")]
     pub content_items: ::prost::alloc::vec::Vec<ChannelContentItem>,
 }
 /// A channel member
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelSubscriber {
     #[prost(message, optional, tag = "1")]
     pub user_id: ::core::option::Option<EntityId>,
     #[prost(uint64, tag = "2")]
     pub date_subscribed: u64,
-    /// add receipts here for past payments
+    /// 0 when channel is free
     #[prost(uint64, tag = "3")]
     pub time_next_payment_due: u64,
+    /// last time creator reminded subscriber to renew its subscription
+    #[prost(uint64, tag = "4")]
+    pub time_reminder_sent: u64,
 }
 /// A ChannelContent Item is a content item signed by the channel's creator
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ChannelContentItem {
     #[prost(message, optional, tag = "1")]
     pub content_item: ::core::option::Option<ContentItem>,
@@ -453,7 +519,7 @@ pub struct ChannelContentItem {
 ////////////// Groups specific data
 
 /// A group member bundle
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GroupMemberBundle {
     #[prost(message, optional, tag = "1")]
     pub user_id: ::core::option::Option<EntityId>,
@@ -465,7 +531,7 @@ pub struct GroupMemberBundle {
 }
 /// Group members bundle is shared by group creator with group members and
 /// is updated with membership changes
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GroupMembersBundle {
     /// signature timestamp
     #[prost(uint64, tag = "1")]
@@ -485,17 +551,98 @@ pub struct GroupMembersBundle {
     /// channel's user's client signature on all other fields (proves identity of owner's client)
     #[prost(message, optional, tag = "6")]
     pub creator_signature: ::core::option::Option<Signature>,
+    /// group co-admins appointed by the creator. They may change the group's membership
+    #[prost(message, repeated, tag = "7")]
+    pub admins: ::prost::alloc::vec::Vec<EntityId>,
+    /// the admin signed change that resulted in this bundle. Empty for joins and leaves
+    #[prost(message, optional, tag = "8")]
+    pub last_change: ::core::option::Option<GroupAdminChange>,
+}
+/// A change to a group's membership made by the group's creator or by a co-admin
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GroupAdminChange {
+    #[prost(uint64, tag = "1")]
+    pub time_stamp: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    /// creator or co-admin who made the change
+    #[prost(message, optional, tag = "3")]
+    pub admin_id: ::core::option::Option<EntityId>,
+    #[prost(enumeration = "GroupAdminAction", tag = "4")]
+    pub action: i32,
+    /// member or requester the change applies to
+    #[prost(message, optional, tag = "5")]
+    pub user_id: ::core::option::Option<EntityId>,
+    /// admin's signature on all other fields
+    #[prost(message, optional, tag = "6")]
+    pub signature: ::core::option::Option<Signature>,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum PricingModel {
     Free = 0,
     MonthlyFee = 1,
 }
 /// Supported types of channel
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum ChannelType {
     StatusFeed = 0,
     Group = 1,
 }
+/// Group administration actions. Co-admins may take all actions besides adding and removing co-admins
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum GroupAdminAction {
+    RemoveMember = 0,
+    /// remove member and reject its requests to join the group
+    BanMember = 1,
+    UnbanMember = 2,
+    /// creator only
+    AddAdmin = 3,
+    /// creator only
+    RemoveAdmin = 4,
+    /// approve a pending request to join the group
+    ApproveMember = 5,
+    /// deny a pending request to join the group
+    DenyMember = 6,
+    /// member may post to the group but not reply to posts
+    BlockReplier = 7,
+    UnblockReplier = 8,
+}
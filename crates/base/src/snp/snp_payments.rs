@@ -1,19 +1,19 @@
 /// An address is an account id
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Address {
     /// 20 bytes unique address derived from public key
     #[prost(bytes = "vec", tag = "1")]
     pub data: ::prost::alloc::vec::Vec<u8>,
 }
 /// A non-negative coin amount
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Amount {
     #[prost(uint64, tag = "1")]
     pub value: u64,
     #[prost(enumeration = "CoinType", tag = "2")]
     pub coin_type: i32,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ServiceTerms {
     /// unique referencable contract id.
     #[prost(uint64, tag = "1")]
@@ -68,8 +68,19 @@ pub struct ServiceTerms {
     /// provider's blockchain account to receive transactions
     #[prost(message, optional, tag = "17")]
     pub payable_account: ::core::option::Option<Address>,
+    /// spam controls
+    ///
+    /// max messages a client may send the provider per minute. 0 for no limit
+    #[prost(uint32, tag = "18")]
+    pub max_messages_per_minute: u32,
+    /// leading zero bits required in new session requests proof of work. 0 when not required
+    #[prost(uint32, tag = "19")]
+    pub new_session_pow_difficulty: u32,
+    /// max receivers of a group message fan out request. 0 for no limit
+    #[prost(uint32, tag = "20")]
+    pub max_fan_out_receivers: u32,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Payment {
     /// time of payment issued
     #[prost(uint64, tag = "1")]
@@ -90,12 +101,12 @@ pub struct Payment {
     #[prost(bytes = "vec", tag = "6")]
     pub signature: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct TransactionId {
     #[prost(bytes = "vec", tag = "1")]
     pub id: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Bill {
     /// bill generation time
     #[prost(uint64, tag = "1")]
@@ -113,7 +124,7 @@ pub struct Bill {
     #[prost(bytes = "vec", tag = "5")]
     pub signature: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct BillSection {
     /// user's service terms
     #[prost(message, optional, tag = "1")]
@@ -125,7 +136,19 @@ pub struct BillSection {
 //// Basic Cryptocurrency and Payments Types
 
 /// Supported built-in coin types
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum CoinType {
     /// $SNP
@@ -135,7 +158,19 @@ pub enum CoinType {
 }
 // TODO: think about user adding contract id when doing a transaction to start a service with provider. evidence he agrees to the terms.
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum PricingModel {
     PayPerUsage = 0,
@@ -147,12 +182,12 @@ pub enum PricingModel {
 ///////////////////////////
 
 /// A request to pay provider by a serviced user
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PaymentRequest {
     #[prost(message, optional, tag = "1")]
     pub payment: ::core::option::Option<Payment>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PaymentResponse {
     #[prost(enumeration = "payment_response::Result", tag = "1")]
     pub result: i32,
@@ -165,7 +200,19 @@ pub struct PaymentResponse {
 }
 /// Nested message and enum types in `PaymentResponse`.
 pub mod payment_response {
-    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[derive(
+        serde::Serialize,
+        serde::Deserialize,
+        Clone,
+        Copy,
+        Debug,
+        PartialEq,
+        Eq,
+        Hash,
+        PartialOrd,
+        Ord,
+        ::prost::Enumeration,
+    )]
     #[repr(i32)]
     pub enum Result {
         Accepted = 0,
@@ -177,10 +224,10 @@ pub mod payment_response {
 ////////////////////////////
 
 /// Request to get the current service contract for the user
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ServiceContractRequest {}
 /// Current user service prices
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ServiceContractResponse {
     /// current user balance
     #[prost(message, optional, tag = "1")]
@@ -192,10 +239,10 @@ pub struct ServiceContractResponse {
 /////////////////////
 
 /// Request client bill. Currently from service start time
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetBillRequest {}
 /// Response includes a newly generated bill that covers all user payments, current balance and service contract
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetBillResponse {
     #[prost(message, optional, tag = "1")]
     pub bill: ::core::option::Option<Bill>,
@@ -209,7 +256,7 @@ pub struct GetBillResponse {
 /// A wallet account has a derivation index in wallet and a public key. Account address derived from pub key.
 /// Account data is stored in wallets. AccountState is stored on the blockchain.
 /// Private key computable from account's wallet seed and derivation_index
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Account {
     /// time stamp
     #[prost(uint64, tag = "1")]
@@ -224,7 +271,7 @@ pub struct Account {
     #[prost(bytes = "vec", tag = "4")]
     pub pub_key: ::prost::alloc::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Seed {
     #[prost(enumeration = "SeedModel", tag = "1")]
     pub model: i32,
@@ -237,7 +284,7 @@ pub struct Seed {
 /// A wallet has a hot seed or seed on an associated hardware wallet
 /// Wallet is a hierarchical deterministic wallet.
 /// See https://en.bitcoin.it/wiki/Deterministic_wallet
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Wallet {
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
@@ -251,7 +298,7 @@ pub struct Wallet {
 }
 /// Safe contains wallets owned by a single entity - stored in a `Safe file` with seed data secured by AES, KDF and user's password
 /// Contains one or more wallets.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Safe {
     /// for now we special-case 3 accounts but safe can contain additional wallets and multiple wallets of each type
     #[prost(string, tag = "1")]
@@ -272,7 +319,19 @@ pub struct Safe {
     pub additional_wallets: ::prost::alloc::vec::Vec<Wallet>,
 }
 /// Specifies where the seed is stored
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum SeedModel {
     /// ledger hardware wallet
@@ -280,7 +339,19 @@ pub enum SeedModel {
     /// in wallet file
     Hot = 1,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum SeedType {
     /// 128 bit
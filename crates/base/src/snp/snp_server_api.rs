@@ -7,7 +7,7 @@
 /// A DR session header - see DR protocol def of HEADER(dh,pn,n)
 ///
 /// DR session header
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct DrSessionHeader {
     /// Unique session id - first created by session initiator. Used by other party to retrieve the session from storage when it needs to use it.
     #[prost(uint64, tag = "1")]
@@ -23,7 +23,7 @@ pub struct DrSessionHeader {
     pub count: u32,
 }
 /// A simple message has a DR header and an encrypted TypedMessage, encrypted using DR. See DR protocol for more info.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct Message {
     /// DR protocol unencrypted header
     #[prost(message, optional, tag = "1")]
@@ -35,7 +35,7 @@ pub struct Message {
 /// Typed message is a self-described typed message designated to a specific receiver authenticated by a sender.
 /// It enables dynamic decoding of a proto-encoded messages to a specific runtime type which is needed as protobuf 3
 /// does not support self-describing messages.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct TypedMessage {
     /// msg creation time signed by sender (to avoid replay later on)
     #[prost(uint64, tag = "1")]
@@ -57,6 +57,12 @@ pub struct TypedMessage {
     /// Message sender signature on all other fields - authenticating the msg
     #[prost(message, optional, tag = "6")]
     pub signature: ::core::option::Option<super::core_types::Signature>,
+    /// Sender's delivery token. Receiver uses it to send sealed-sender messages to sender (optional)
+    #[prost(bytes = "vec", tag = "7")]
+    pub sender_delivery_token: ::prost::alloc::vec::Vec<u8>,
+    /// Snp protocol semantic version implemented by sender. Receiver uses it to gate protocol features in messages to sender
+    #[prost(string, tag = "8")]
+    pub sender_protocol_version: ::prost::alloc::string::String,
 }
 /// A 2-party DR session request using the X2DH protocol. Can be sent by Alice to Bob.
 /// Can also be sent as an inner message sent from Alice to Bob designated to Charlie.
@@ -65,7 +71,7 @@ pub struct TypedMessage {
 /// extract the caller id from the enc payload, verify signature on internal message
 /// and only then associate DR session with the public long term id of the caller.
 /// this is done so we don't leak sender public id in this clear-text network message.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct NewSessionRequest {
     /// request time signed by sender (to avoid replays at much later time)
     #[prost(uint64, tag = "1")]
@@ -94,27 +100,88 @@ pub struct NewSessionRequest {
     /// Snp protocol semantic version number implemented by caller
     #[prost(string, tag = "9")]
     pub protocol_version: ::prost::alloc::string::String,
+    /// proof of work nonce. Required when provider terms set a new session pow difficulty. Not signed.
+    #[prost(uint64, tag = "10")]
+    pub pow_nonce: u64,
+    /// Alice's ml-kem ciphertext for receiver's hybrid pre-key. see X2DH protocol.
+    #[prost(bytes = "vec", tag = "11")]
+    pub sender_kem_ciphertext: ::prost::alloc::vec::Vec<u8>,
 }
 /// A DDMessage is a NewSessionRequest or a Message.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct DrMessage {
-    #[prost(oneof = "dr_message::Data", tags = "1, 2")]
+    #[prost(oneof = "dr_message::Data", tags = "1, 2, 3, 4")]
     pub data: ::core::option::Option<dr_message::Data>,
 }
 /// Nested message and enum types in `DRMessage`.
 pub mod dr_message {
-    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Oneof)]
     pub enum Data {
         #[prost(message, tag = "1")]
         NewSessionRequest(super::NewSessionRequest),
         #[prost(message, tag = "2")]
         Message(super::Message),
+        #[prost(message, tag = "3")]
+        SealedSenderMessage(super::SealedSenderMessage),
+        #[prost(message, tag = "4")]
+        GroupMessage(super::GroupMessage),
     }
 }
+/// A sealed-sender message hides the identity of the sender of a DRMessage from providers.
+/// The sender's identity and signature are encrypted to the receiver using an eph-dh with the receiver's pre-key.
+/// Providers deliver it when it is authorized by a delivery token issued by the receiver instead of by the sender's identity.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct SealedSenderMessage {
+    /// Sender's x25519 ephemeral key for the eph-dh with the receiver's pre-key
+    #[prost(message, optional, tag = "1")]
+    pub sender_ephemeral_key: ::core::option::Option<super::core_types::PublicKey>,
+    /// Receiver's bundle id used by sender. Identifies the pre-key.
+    #[prost(uint64, tag = "2")]
+    pub receiver_bundle_id: u64,
+    /// encrypted SealedSenderContent
+    #[prost(bytes = "vec", tag = "3")]
+    pub enc_content: ::prost::alloc::vec::Vec<u8>,
+}
+/// The content of a sealed-sender message. Only the receiver can decrypt it.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct SealedSenderContent {
+    /// content creation time signed by sender
+    #[prost(uint64, tag = "1")]
+    pub time_stamp: u64,
+    /// Sender's long term id
+    #[prost(message, optional, tag = "2")]
+    pub sender: ::core::option::Option<super::core_types::EntityId>,
+    /// Receiver's long term id - prevents re-sealing content to other receivers
+    #[prost(message, optional, tag = "3")]
+    pub receiver: ::core::option::Option<super::core_types::EntityId>,
+    /// NewSessionRequest or Message from sender
+    #[prost(message, optional, tag = "4")]
+    pub dr_message: ::core::option::Option<DrMessage>,
+    /// Sender signature on all other fields
+    #[prost(message, optional, tag = "5")]
+    pub signature: ::core::option::Option<super::core_types::Signature>,
+}
+/// A group message encrypted once by its sender with a sender key it distributed to group members over their pairwise
+/// DR sessions. The key id is a random id of the sender key so providers don't learn the message's group or sender.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct GroupMessage {
+    /// id of the sender key
+    #[prost(bytes = "vec", tag = "1")]
+    pub key_id: ::prost::alloc::vec::Vec<u8>,
+    /// sender key ratchet iteration of the message key
+    #[prost(uint32, tag = "2")]
+    pub iteration: u32,
+    /// a TypedMessage encrypted with the message key
+    #[prost(bytes = "vec", tag = "3")]
+    pub enc_typed_msg: ::prost::alloc::vec::Vec<u8>,
+    /// signature with the sender key's signing key on all other fields
+    #[prost(bytes = "vec", tag = "4")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+}
 /// Metadata about a DRMessage designated to a client that is stored
 /// on provider for client delivery
 /// Note that provider doesn't have by design any additional message meta-data
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ClientMessageMetadata {
     /// unique id created by provider who stores a message to client by this id. Also used in receipt
     #[prost(uint64, tag = "1")]
@@ -128,33 +195,39 @@ pub struct ClientMessageMetadata {
     /// message byte size
     #[prost(uint64, tag = "4")]
     pub size: u64,
-    /// how long will server hold this message for client before deleting it
+    /// how long will server hold this message for client before deleting it. Seconds from received_date, 0 for no limit
     #[prost(uint64, tag = "5")]
     pub ttl: u64,
+    /// position of the message in the client's messages stream. Increases with each message stored for the client
+    #[prost(uint64, tag = "6")]
+    pub cursor: u64,
 }
 /// A list of messages metadata
 /// Sent from provider to its client so client can decide which messages to request
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ClientMessagesMetadata {
     #[prost(message, repeated, tag = "1")]
     pub messages_metadata: ::prost::alloc::vec::Vec<ClientMessageMetadata>,
+    /// cursor of the last message stored for the client
+    #[prost(uint64, tag = "2")]
+    pub last_cursor: u64,
 }
 /// Receiver returns a response message in the new DR session between the parties based on the message that the sender sent
 /// or an error status if it failed or refused to create new DR session between the parties.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct NewSessionResponse {
     #[prost(message, optional, tag = "1")]
     pub message: ::core::option::Option<Message>,
 }
 /// A message request between two parties using an existing DR session between them
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct MessageRequest {
     /// message enc in Dr session between sender and receiver
     #[prost(message, optional, tag = "1")]
     pub message: ::core::option::Option<Message>,
 }
 /// A response to a MessageRequest
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct MessageResponse {
     /// the response message, encoded in the DR session between the parties
     #[prost(message, optional, tag = "1")]
@@ -163,32 +236,53 @@ pub struct MessageResponse {
 /// A request by a client to subscribe to messages designated to him that reached his provider
 /// Note that for now we don't let clients start a new DR session by sending a DRMessage
 /// so they always have to send a Message (and not a NewSessionRequest) here
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SubscribeToClientMessagesRequest {
     #[prost(message, optional, tag = "1")]
     pub dr_message: ::core::option::Option<Message>,
 }
-/// empty message
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SubscribeToClientMessagesRequestPayload {}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeToClientMessagesRequestPayload {
+    /// metadata of pending messages with a greater cursor is replayed on the stream. 0 to replay all pending messages
+    #[prost(uint64, tag = "1")]
+    pub resume_cursor: u64,
+}
+/// A provider rejection of a request. Provided in the details of the rejection grpc status
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct RequestRejection {
+    #[prost(enumeration = "RejectionReason", tag = "1")]
+    pub reason: i32,
+    /// when rate limited - time until the sender may send another request
+    #[prost(uint64, tag = "2")]
+    pub retry_after_ms: u64,
+    /// when proof of work is required - the required proof of work difficulty
+    #[prost(uint32, tag = "3")]
+    pub pow_difficulty: u32,
+    /// when protocol version is incompatible - oldest version supported by the provider
+    #[prost(string, tag = "4")]
+    pub min_protocol_version: ::prost::alloc::string::String,
+    /// when protocol version is incompatible - newest version supported by the provider
+    #[prost(string, tag = "5")]
+    pub max_protocol_version: ::prost::alloc::string::String,
+}
 /// A request to get the current provider identity bundle
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetIdentityBundleRequest {
     /// Snp protocol semantic version number implemented by caller
     #[prost(string, tag = "1")]
     pub protocol_version: ::prost::alloc::string::String,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetIdentityBundleResponse {
     #[prost(message, optional, tag = "1")]
     pub bundle: ::core::option::Option<super::core_types::ProviderIdentityBundle>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetTermsOfServiceRequest {
     #[prost(string, tag = "1")]
     pub promo_code: ::prost::alloc::string::String,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetTermsOfServiceResponse {
     #[prost(message, optional, tag = "1")]
     pub terms: ::core::option::Option<super::core_types::ServiceTermsBundle>,
@@ -207,7 +301,7 @@ pub struct GetTermsOfServiceResponse {
 /// Forward_message is encrypted with eph-dh to the other service provider (SB).
 /// This message is implemented by providers that accept routing messages for their clients.
 /// See the basic user-to-user messaging flow for more info.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RouteMessageRequest {
     /// the message for the destination service provider (SB)
     #[prost(message, optional, tag = "1")]
@@ -217,23 +311,39 @@ pub struct RouteMessageRequest {
     pub dialup_info: ::core::option::Option<super::core_types::DialupInfo>,
 }
 /// empty as it only includes status
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RouteMessageResponse {}
+/// A client A requests its service provider (SA) to fan out a group message to other clients.
+/// A encrypts the message once and SA forwards it to each receiver via the receiver's provider.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct FanOutMessageRequest {
+    #[prost(message, optional, tag = "1")]
+    pub group_message: ::core::option::Option<GroupMessage>,
+    /// receivers' bundles with their providers' bundles
+    #[prost(message, repeated, tag = "2")]
+    pub receivers: ::prost::alloc::vec::Vec<super::core_types::ClientIdentityBundle>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct FanOutMessageResponse {
+    /// receivers SA failed to forward the message to
+    #[prost(message, repeated, tag = "1")]
+    pub failed_receivers: ::prost::alloc::vec::Vec<super::core_types::EntityId>,
+}
 ////////////////////////////////
 
 /// A request to a provider to send a message to one of its serviced clients.
 /// Clients use this to send a message to another client serviced by the same provider
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SendMessageToServicedClientRequest {
     /// new session request or message to serviced client
     #[prost(message, optional, tag = "1")]
     pub dr_message: ::core::option::Option<DrMessage>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SendMessageToServicedClientResponse {}
 /// A request from client to deliver messages designated to it from its provider
 /// Includes a receipt for te messages delivery price
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct DeliverClientMessagesRequest {
     /// payment include item ids
     #[prost(message, optional, tag = "1")]
@@ -241,7 +351,7 @@ pub struct DeliverClientMessagesRequest {
 }
 /// A response from a server to deliver messages to a client. Includes the receipt id of the client's payment
 /// and the full messages content it has pending for delivery to the client.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct DeliverClientMessagesResponse {
     #[prost(uint64, tag = "1")]
     pub receipt_id: u64,
@@ -257,7 +367,7 @@ pub struct DeliverClientMessagesResponse {
 /// the message was sent - it should be a one time key
 /// >>> there is no DR session created between sender and receiver only a 1 time key to decrypt the payload
 /// The enc/dec key is obtained by doing DH with receiver public pre-key and sender ephemeral key
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ForwardMessageRequest {
     /// Provider receiver id - long term public key
     #[prost(message, optional, tag = "1")]
@@ -274,24 +384,42 @@ pub struct ForwardMessageRequest {
 }
 /// Payload is a NewSessionRequest or a Message request
 /// to another entity that the ForwardMessage receiver
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ForwardMessagePayload {
     /// we need this because Message doesn't have receiver id in it and provider needs it.
     #[prost(message, optional, tag = "1")]
     pub receiver: ::core::option::Option<super::core_types::EntityId>,
     #[prost(message, optional, tag = "2")]
     pub dr_message: ::core::option::Option<DrMessage>,
+    /// Receiver's delivery token. Required to deliver a SealedSenderMessage.
+    #[prost(bytes = "vec", tag = "3")]
+    pub delivery_token: ::prost::alloc::vec::Vec<u8>,
+    /// Optional expiry hint - time (unix nanos) after which the message should be dropped if not delivered. 0 for none.
+    #[prost(uint64, tag = "4")]
+    pub expires: u64,
 }
 /// The response just indicates a status to the sender who forwarded the message to the receiver
 /// It is protected with the channel the sender rand the receiver have. e.g. a DR session.
 /// So the response can be a DR-protected response... e.g. a Message with this as its internal
 /// typed message
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ForwardMessageResponse {}
 /// MessageType specifies the run-time type of a TypedMessage
 /// Used for dynamic decoding of messages to a runtime typed object in code.
 /// New protocol messages type ids need to be added here
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
 #[repr(i32)]
 pub enum MessageType {
     //
@@ -375,6 +503,57 @@ pub enum MessageType {
     PingNodeRequest = 35,
     /// A ping response including new node dialup info (for follow-up requests)
     PingNodeResponse = 36,
+    /// Cover traffic sent by a client to its provider to mask its real messaging patterns. Provider discards it
+    CoverTrafficRequest = 37,
+    /// An empty response to a cover traffic request
+    CoverTrafficResponse = 38,
+    /// Delivered or read receipts sent by a client to the sender of 1:1 messages it received
+    MessageReceipts = 39,
+    /// A chunk of a 1:1 message with attachments sent by a client to another client
+    AttachmentChunk = 40,
+    /// A reminder from a paid channel creator to a subscriber that its subscription fee is due
+    ChannelPaymentReminder = 41,
+    /// A subscriber's paid channel subscription renewal with its payment
+    ChannelSubscriptionRenewal = 42,
+    /// A group sender key sent by a group publisher to a group member over their pairwise DR session
+    GroupSenderKey = 43,
+    /// A request from a client to its provider to fan out a group message to other clients via their providers
+    FanOutMessageRequest = 44,
+    FanOutMessageResponse = 45,
+    /// A membership change request from a group co-admin to the group creator
+    GroupAdminRequest = 46,
+    GroupAdminResponse = 47,
+    /// An updated group members bundle sent by a group creator to group members
+    GroupMembersUpdate = 48,
+    /// A request from a channel subscriber or a group member to the channel's creator for a page of the channel's content
+    ChannelHistoryRequest = 49,
+    ChannelHistoryResponse = 50,
+}
+/// The reason a provider rejected a request
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum RejectionReason {
+    Unspecified = 0,
+    /// sender sent too many requests
+    ClientRateLimited = 1,
+    /// too many requests were sent from the caller's ip address
+    IpRateLimited = 2,
+    /// new session request must include a valid proof of work
+    ProofOfWorkRequired = 3,
+    /// caller's protocol version is not supported by the provider
+    IncompatibleProtocolVersion = 4,
 }
 #[doc = r" Generated client implementations."]
 pub mod provider_core_service_client {
@@ -842,24 +1021,24 @@ pub mod provider_core_service_server {
 
 /// Client requests to stop being serviced by this provider.
 /// Client may optionally send a new identity bundle with its new provider
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct StopServiceRequest {
     /// optional
     #[prost(message, optional, tag = "1")]
     pub client_bundle: ::core::option::Option<super::core_types::ClientIdentityBundle>,
 }
 /// Stop service response
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct StopServiceResponse {}
 ///////////////////////
 // Current service status
 //
 
 /// Client request to receive its current service status
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ServiceStatusRequest {}
 /// return open invoices
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ServiceStatusResponse {}
 // todo: add GetOpenInvoices() - returns all client charges that he needs to pay such as monthly fee or monthly data storage fee
 
@@ -869,12 +1048,12 @@ pub struct ServiceStatusResponse {}
 
 /// Update client published pre-keys. Clients can refresh their pre-keys at any time.
 /// Provider should sign if he's serving this client and store the updated signed bundle on the network's decentralized storage (Kad based)
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SetBundleRequest {
     #[prost(message, optional, tag = "1")]
     pub bundle: ::core::option::Option<super::core_types::ClientIdentityBundle>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SetBundleResponse {
     #[prost(message, optional, tag = "1")]
     pub bundle: ::core::option::Option<super::core_types::ProviderSignedClientIdentityBundle>,
@@ -884,7 +1063,7 @@ pub struct SetBundleResponse {
 
 //////////
 
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ClientDataItem {
     /// provider storage item id
     #[prost(uint64, tag = "1")]
@@ -912,7 +1091,7 @@ pub struct ClientDataItem {
     pub download_price: u64,
 }
 /// A request to store a data file on the provider. The file must be an archive of one or more user encrypted data files
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct StoreClientDataRequest {
     #[prost(message, optional, tag = "1")]
     pub data_item: ::core::option::Option<ClientDataItem>,
@@ -920,35 +1099,35 @@ pub struct StoreClientDataRequest {
     #[prost(message, optional, tag = "2")]
     pub payment: ::core::option::Option<super::payments::Payment>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct StoreClientDataResponse {
     #[prost(uint64, tag = "1")]
     pub storage_item_id: u64,
 }
 //////////
 
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ListClientDataItemsRequest {}
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ListClientDataItemsResponse {
     #[prost(message, repeated, tag = "1")]
     pub items: ::prost::alloc::vec::Vec<ClientDataItem>,
 }
 //////////
 
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientDataItemInfoRequest {
     #[prost(uint64, tag = "1")]
     pub storage_item_id: u64,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientDataItemInfoResponse {
     #[prost(message, optional, tag = "1")]
     pub item: ::core::option::Option<ClientDataItem>,
 }
 //////////
 
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct DownloadClientDataRequest {
     #[prost(uint64, tag = "1")]
     pub storage_item_id: u64,
@@ -956,7 +1135,7 @@ pub struct DownloadClientDataRequest {
     #[prost(message, optional, tag = "2")]
     pub payment: ::core::option::Option<super::payments::Payment>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct DownloadClientDataResponse {
     /// encrypted uplaoded client data. e.g binary/zip mime compressed with zip. Uncompressed data is user-encrypted arbitrary data file.
     #[prost(message, optional, tag = "1")]
@@ -973,7 +1152,7 @@ pub struct DownloadClientDataResponse {
 /// This should start the free trial period if it exists.
 /// By calling this method the client agrees to the provider terms of service
 /// as provided in provider bundle
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct StartServiceRequest {
     #[prost(message, optional, tag = "1")]
     pub bundle: ::core::option::Option<super::core_types::ClientIdentityBundle>,
@@ -985,16 +1164,19 @@ pub struct StartServiceRequest {
     /// fixed monthly fee or pay-per-usage
     #[prost(enumeration = "super::payments::PricingModel", tag = "4")]
     pub contract_options: i32,
+    /// Client's delivery token. Sealed-sender messages to the client must present it.
+    #[prost(bytes = "vec", tag = "5")]
+    pub delivery_token: ::prost::alloc::vec::Vec<u8>,
 }
 /// Returns a signed client id bundle if accepted this client request
 /// or an error status code
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct StartServiceResponse {
     #[prost(message, optional, tag = "1")]
     pub bundle: ::core::option::Option<super::core_types::ProviderSignedClientIdentityBundle>,
 }
 /// Request to get the most recent client bundle of a client provided by this provider
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientPreKeysRequest {
     #[prost(message, optional, tag = "1")]
     pub client_id: ::core::option::Option<super::core_types::EntityId>,
@@ -1003,7 +1185,7 @@ pub struct GetClientPreKeysRequest {
 /// the provider asserts that it is its service provider.
 /// The provider id is signed by the client in the response envelope so client all asserts that
 /// it is provided by the provider
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GetClientPreKeysResponse {
     #[prost(message, optional, tag = "1")]
     pub client_bundle:
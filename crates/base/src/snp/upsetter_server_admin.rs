@@ -1,5 +1,62 @@
+/// Summary of a serviced client data
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetClientsResponse {}
+pub struct ClientInfo {
+    #[prost(message, optional, tag = "1")]
+    pub service_data: ::core::option::Option<super::super::snp::core_types::ClientServiceData>,
+    /// number of messages pending delivery to the client
+    #[prost(uint32, tag = "2")]
+    pub pending_messages: u32,
+    /// client's account balances. Empty when no blockchain service is set
+    #[prost(message, repeated, tag = "3")]
+    pub balances: ::prost::alloc::vec::Vec<super::super::snp::payments::Amount>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetClientsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub clients: ::prost::alloc::vec::Vec<ClientInfo>,
+}
+/// A dr session with another provider
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PeerSession {
+    #[prost(message, optional, tag = "1")]
+    pub provider_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(message, optional, tag = "2")]
+    pub dialup_info: ::core::option::Option<super::super::snp::core_types::DialupInfo>,
+    #[prost(uint64, tag = "3")]
+    pub session_id: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPeersSessionsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub sessions: ::prost::alloc::vec::Vec<PeerSession>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EvictClientRequest {
+    #[prost(message, optional, tag = "1")]
+    pub client_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuspendClientRequest {
+    #[prost(message, optional, tag = "1")]
+    pub client_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    /// false to resume service
+    #[prost(bool, tag = "2")]
+    pub suspend: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RotatePreKeyResponse {
+    /// provider bundle with the new pre-key
+    #[prost(message, optional, tag = "1")]
+    pub provider_bundle:
+        ::core::option::Option<super::super::snp::core_types::ProviderIdentityBundle>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetConfigResponse {
+    /// secret values are masked
+    #[prost(map = "string, string", tag = "1")]
+    pub values:
+        ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
+}
 #[doc = r" Generated client implementations."]
 pub mod server_admin_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -94,6 +151,91 @@ pub mod server_admin_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        #[doc = " return all dr sessions this server has with other providers"]
+        pub async fn get_peers_sessions(
+            &mut self,
+            request: impl tonic::IntoRequest<()>,
+        ) -> Result<tonic::Response<super::GetPeersSessionsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.server_admin.ServerAdminService/GetPeersSessions",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " stop serving a client and delete all of its data, including messages pending delivery to it"]
+        pub async fn evict_client(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EvictClientRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.server_admin.ServerAdminService/EvictClient",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " suspend or resume service to a client"]
+        pub async fn suspend_client(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SuspendClientRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.server_admin.ServerAdminService/SuspendClient",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " create a new provider pre-key and publish the updated provider bundle"]
+        pub async fn rotate_pre_key(
+            &mut self,
+            request: impl tonic::IntoRequest<()>,
+        ) -> Result<tonic::Response<super::RotatePreKeyResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.server_admin.ServerAdminService/RotatePreKey",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " return the server's current configuration"]
+        pub async fn get_config(
+            &mut self,
+            request: impl tonic::IntoRequest<()>,
+        ) -> Result<tonic::Response<super::GetConfigResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.server_admin.ServerAdminService/GetConfig",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
     }
 }
 #[doc = r" Generated server implementations."]
@@ -113,6 +255,31 @@ pub mod server_admin_service_server {
             &self,
             request: tonic::Request<()>,
         ) -> Result<tonic::Response<super::GetClientsResponse>, tonic::Status>;
+        #[doc = " return all dr sessions this server has with other providers"]
+        async fn get_peers_sessions(
+            &self,
+            request: tonic::Request<()>,
+        ) -> Result<tonic::Response<super::GetPeersSessionsResponse>, tonic::Status>;
+        #[doc = " stop serving a client and delete all of its data, including messages pending delivery to it"]
+        async fn evict_client(
+            &self,
+            request: tonic::Request<super::EvictClientRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " suspend or resume service to a client"]
+        async fn suspend_client(
+            &self,
+            request: tonic::Request<super::SuspendClientRequest>,
+        ) -> Result<tonic::Response<()>, tonic::Status>;
+        #[doc = " create a new provider pre-key and publish the updated provider bundle"]
+        async fn rotate_pre_key(
+            &self,
+            request: tonic::Request<()>,
+        ) -> Result<tonic::Response<super::RotatePreKeyResponse>, tonic::Status>;
+        #[doc = " return the server's current configuration"]
+        async fn get_config(
+            &self,
+            request: tonic::Request<()>,
+        ) -> Result<tonic::Response<super::GetConfigResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct ServerAdminServiceServer<T: ServerAdminService> {
@@ -228,6 +395,158 @@ pub mod server_admin_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/upsetter.server_admin.ServerAdminService/GetPeersSessions" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPeersSessionsSvc<T: ServerAdminService>(pub Arc<T>);
+                    impl<T: ServerAdminService> tonic::server::UnaryService<()> for GetPeersSessionsSvc<T> {
+                        type Response = super::GetPeersSessionsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(&mut self, request: tonic::Request<()>) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_peers_sessions(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetPeersSessionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/upsetter.server_admin.ServerAdminService/EvictClient" => {
+                    #[allow(non_camel_case_types)]
+                    struct EvictClientSvc<T: ServerAdminService>(pub Arc<T>);
+                    impl<T: ServerAdminService>
+                        tonic::server::UnaryService<super::EvictClientRequest>
+                        for EvictClientSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::EvictClientRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).evict_client(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = EvictClientSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/upsetter.server_admin.ServerAdminService/SuspendClient" => {
+                    #[allow(non_camel_case_types)]
+                    struct SuspendClientSvc<T: ServerAdminService>(pub Arc<T>);
+                    impl<T: ServerAdminService>
+                        tonic::server::UnaryService<super::SuspendClientRequest>
+                        for SuspendClientSvc<T>
+                    {
+                        type Response = ();
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SuspendClientRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).suspend_client(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SuspendClientSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/upsetter.server_admin.ServerAdminService/RotatePreKey" => {
+                    #[allow(non_camel_case_types)]
+                    struct RotatePreKeySvc<T: ServerAdminService>(pub Arc<T>);
+                    impl<T: ServerAdminService> tonic::server::UnaryService<()> for RotatePreKeySvc<T> {
+                        type Response = super::RotatePreKeyResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(&mut self, request: tonic::Request<()>) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).rotate_pre_key(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RotatePreKeySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/upsetter.server_admin.ServerAdminService/GetConfig" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetConfigSvc<T: ServerAdminService>(pub Arc<T>);
+                    impl<T: ServerAdminService> tonic::server::UnaryService<()> for GetConfigSvc<T> {
+                        type Response = super::GetConfigResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(&mut self, request: tonic::Request<()>) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_config(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetConfigSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => Box::pin(async move {
                     Ok(http::Response::builder()
                         .status(200)
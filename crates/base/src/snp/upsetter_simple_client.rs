@@ -40,9 +40,24 @@ pub struct UserNewPostResponse {
     pub post_id: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetChannelHistoryRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    /// only items created before this time stamp. 0 for the latest items
+    #[prost(uint64, tag = "2")]
+    pub before: u64,
+    #[prost(uint32, tag = "3")]
+    pub max_items: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetChannelHistoryResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserCreateStatusUpdateChannelRequest {
     #[prost(string, tag = "1")]
     pub channel_name: ::prost::alloc::string::String,
+    /// monthly subscription fee in core coins. 0 for a free channel
+    #[prost(uint64, tag = "2")]
+    pub subscription_fee: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserCreateStatusUpdateChannelResponse {
@@ -64,13 +79,30 @@ pub struct UserSetProviderResponse {
         ::core::option::Option<super::super::snp::core_types::ProviderSignedClientIdentityBundle>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserSwitchProviderRequest {
+    /// the new provider
+    #[prost(message, optional, tag = "1")]
+    pub dialup_info: ::core::option::Option<super::super::snp::core_types::DialupInfo>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserSwitchProviderResponse {
+    /// the client bundle signed by the new provider and published on-chain by the client
+    #[prost(message, optional, tag = "1")]
+    pub client_bundle:
+        ::core::option::Option<super::super::snp::core_types::ProviderSignedClientIdentityBundle>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserSendTextMessageRequest {
+    /// receiver id or only its nickname. Unknown receivers are looked up on the blockchain
     #[prost(message, optional, tag = "1")]
     pub other_client_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
     #[prost(string, tag = "2")]
     pub user_text: ::prost::alloc::string::String,
     #[prost(uint64, tag = "3")]
     pub reply_to: u64,
+    /// optional seconds after which the message self-destructs. 0 for none
+    #[prost(uint64, tag = "4")]
+    pub ttl: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserSendTextMessageResponse {
@@ -78,12 +110,260 @@ pub struct UserSendTextMessageResponse {
     #[prost(uint64, tag = "1")]
     pub message_id: u64,
 }
+/// Large attachments are sent in chunks smaller than the max file size of this client's provider
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserSendAttachmentRequest {
+    /// receiver id or only its nickname. Unknown receivers are looked up on the blockchain
+    #[prost(message, optional, tag = "1")]
+    pub other_client_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    /// optional text sent with the attachment
+    #[prost(string, tag = "2")]
+    pub user_text: ::prost::alloc::string::String,
+    /// attachment file name
+    #[prost(string, tag = "3")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(enumeration = "super::super::snp::core_types::MimeType", tag = "4")]
+    pub mime_type: i32,
+    /// codec to compress the attachment with
+    #[prost(
+        enumeration = "super::super::snp::core_types::CompressionCodec",
+        tag = "5"
+    )]
+    pub compression: i32,
+    /// attachment uncompressed content
+    #[prost(bytes = "vec", tag = "6")]
+    pub content: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "7")]
+    pub reply_to: u64,
+    /// optional seconds after which the message self-destructs. 0 for none
+    #[prost(uint64, tag = "8")]
+    pub ttl: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserSendAttachmentResponse {
+    #[prost(uint64, tag = "1")]
+    pub message_id: u64,
+    /// number of chunks the message was sent in
+    #[prost(uint32, tag = "2")]
+    pub chunks_count: u32,
+}
+///// Contacts verification
+
+/// Contacts are known by the nickname in their identity bundle. A contact's identity key is pinned on first use.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetContactSafetyNumberRequest {
+    /// contact's registered nickname or its 0x prefixed hex identity key
+    #[prost(string, tag = "1")]
+    pub nickname: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetContactSafetyNumberResponse {
+    /// 60 digits number over our identity and the contact's pinned identity
+    #[prost(string, tag = "1")]
+    pub safety_number: ::prost::alloc::string::String,
+    /// the contact's pinned identity. A changed identity is not shown before it is verified
+    #[prost(message, optional, tag = "2")]
+    pub contact_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    /// user verified the contact's pinned identity
+    #[prost(bool, tag = "3")]
+    pub verified: bool,
+    /// contact's identity key changed since it was pinned. Messages are blocked until it is verified
+    #[prost(bool, tag = "4")]
+    pub identity_changed: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserVerifyContactRequest {
+    /// contact's registered nickname or its 0x prefixed hex identity key
+    #[prost(string, tag = "1")]
+    pub nickname: ::prost::alloc::string::String,
+    /// the safety number the user compared with the contact. The contact's number with its new key when it changed
+    #[prost(string, tag = "2")]
+    pub safety_number: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserVerifyContactResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserAuditIdentityRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserAuditIdentityResponse {
+    /// the audited log tree head
+    #[prost(message, optional, tag = "1")]
+    pub head: ::core::option::Option<super::super::snp::blockchain::TransparencyLogHead>,
+    /// number of this client's bundles in the log
+    #[prost(uint32, tag = "2")]
+    pub bundles_count: u32,
+    /// bundles of this client's identity it didn't publish
+    #[prost(message, repeated, tag = "3")]
+    pub unknown_bundles:
+        ::prost::alloc::vec::Vec<super::super::snp::blockchain::TransparencyLogEntry>,
+}
+/// A conversation is identified by the other client's public key for 1:1 messages and by the channel id for channels
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Conversation {
+    #[prost(bytes = "vec", tag = "1")]
+    pub conversation_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(enumeration = "ConversationType", tag = "2")]
+    pub conversation_type: i32,
+    #[prost(uint64, tag = "3")]
+    pub messages_count: u64,
+    /// time the last message was received
+    #[prost(uint64, tag = "4")]
+    pub last_message_time_stamp: u64,
+}
+/// A message received by this client in a conversation
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InboxMessage {
+    #[prost(bytes = "vec", tag = "1")]
+    pub conversation_id: ::prost::alloc::vec::Vec<u8>,
+    /// index of message in its conversation, starting at 0
+    #[prost(uint64, tag = "2")]
+    pub index: u64,
+    #[prost(message, optional, tag = "3")]
+    pub sender_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(uint64, tag = "4")]
+    pub received_time_stamp: u64,
+    #[prost(message, optional, tag = "5")]
+    pub content_item: ::core::option::Option<super::super::snp::core_types::ContentItem>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserListConversationsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserListConversationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub conversations: ::prost::alloc::vec::Vec<Conversation>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetConversationMessagesRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub conversation_id: ::prost::alloc::vec::Vec<u8>,
+    /// index of first message to return
+    #[prost(uint64, tag = "2")]
+    pub from_index: u64,
+    /// 0 for all messages from from_index
+    #[prost(uint32, tag = "3")]
+    pub max_results: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetConversationMessagesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: ::prost::alloc::vec::Vec<InboxMessage>,
+    /// number of messages in the conversation
+    #[prost(uint64, tag = "2")]
+    pub total_count: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeEventsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientEvent {
+    #[prost(uint64, tag = "1")]
+    pub time_stamp: u64,
+    #[prost(oneof = "client_event::Event", tags = "2, 3, 4, 5, 6, 7, 8, 9")]
+    pub event: ::core::option::Option<client_event::Event>,
+}
+/// Nested message and enum types in `ClientEvent`.
+pub mod client_event {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "2")]
+        TextMessageReceived(super::TextMessageReceivedEvent),
+        #[prost(message, tag = "3")]
+        ChannelPostReceived(super::ChannelPostReceivedEvent),
+        #[prost(message, tag = "4")]
+        SubscriptionRequest(super::SubscriptionRequestEvent),
+        #[prost(message, tag = "5")]
+        ItemPurchased(super::ItemPurchasedEvent),
+        #[prost(message, tag = "6")]
+        DeliveryFailure(super::DeliveryFailureEvent),
+        #[prost(message, tag = "7")]
+        MessageStatusChanged(super::MessageStatusChangedEvent),
+        #[prost(message, tag = "8")]
+        GroupMembersUpdated(super::GroupMembersUpdatedEvent),
+        #[prost(message, tag = "9")]
+        ChannelHistoryReceived(super::ChannelHistoryReceivedEvent),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TextMessageReceivedEvent {
+    #[prost(message, optional, tag = "1")]
+    pub message: ::core::option::Option<InboxMessage>,
+}
+/// A status update or a group message received in a channel we are subscribed to
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelPostReceivedEvent {
+    #[prost(message, optional, tag = "1")]
+    pub message: ::core::option::Option<InboxMessage>,
+    #[prost(enumeration = "super::super::snp::core_types::ChannelType", tag = "2")]
+    pub channel_type: i32,
+}
+/// Another client subscribed to a status updates channel or joined a group we created
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscriptionRequestEvent {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub subscriber_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(enumeration = "super::super::snp::core_types::ChannelType", tag = "3")]
+    pub channel_type: i32,
+}
+/// A paid item was bought. We are either its seller or its buyer
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ItemPurchasedEvent {
+    #[prost(message, optional, tag = "1")]
+    pub seller_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(message, optional, tag = "2")]
+    pub buyer_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(uint64, tag = "3")]
+    pub item_id: u64,
+    /// the bought item. Set when we are the buyer
+    #[prost(message, optional, tag = "4")]
+    pub item: ::core::option::Option<super::super::snp::core_types::ContentItem>,
+}
+/// A message we sent couldn't be delivered to its receiver
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeliveryFailureEvent {
+    #[prost(message, optional, tag = "1")]
+    pub receiver_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    /// snp.server_api.MessageType of the undelivered message
+    #[prost(int32, tag = "2")]
+    pub message_type: i32,
+    #[prost(string, tag = "3")]
+    pub error: ::prost::alloc::string::String,
+}
+/// We got an updated members bundle of a group we are a member of
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupMembersUpdatedEvent {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub members: ::core::option::Option<super::super::snp::core_types::GroupMembersBundle>,
+    /// we were removed from the group
+    #[prost(bool, tag = "3")]
+    pub removed: bool,
+}
+/// We got a page of content published to a channel we are subscribed to from the channel's creator
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelHistoryReceivedEvent {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    /// newest first. Verified creator and author signatures
+    #[prost(message, repeated, tag = "2")]
+    pub content_items: ::prost::alloc::vec::Vec<super::super::snp::core_types::ChannelContentItem>,
+    /// there are older items
+    #[prost(bool, tag = "3")]
+    pub has_more: bool,
+    /// creator's rejection reason
+    #[prost(string, tag = "4")]
+    pub error: ::prost::alloc::string::String,
+}
 ///// Groups
 
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserCreateGroupRequest {
     #[prost(string, tag = "1")]
     pub group_name: ::prost::alloc::string::String,
+    /// when true, requests to join the group are pending until an admin approves them
+    #[prost(bool, tag = "2")]
+    pub approve_members: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserJoinGroupResponse {}
@@ -104,6 +384,36 @@ pub struct UserLeaveGroupRequest {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserLeaveGroupResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGroupAdminRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(
+        enumeration = "super::super::snp::core_types::GroupAdminAction",
+        tag = "2"
+    )]
+    pub action: i32,
+    /// member or requester the action applies to
+    #[prost(message, optional, tag = "3")]
+    pub user_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGroupAdminResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetGroupMembersRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub channel_id: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetGroupMembersResponse {
+    #[prost(message, optional, tag = "1")]
+    pub members: ::core::option::Option<super::super::snp::core_types::GroupMembersBundle>,
+    #[prost(message, repeated, tag = "2")]
+    pub pending_requests:
+        ::prost::alloc::vec::Vec<super::super::snp::core_types::ChannelSubscriptionRequestData>,
+    #[prost(message, repeated, tag = "3")]
+    pub blocked_repliers: ::prost::alloc::vec::Vec<super::super::snp::core_types::EntityId>,
+}
 //// Paid content items
 
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -139,6 +449,93 @@ pub struct UserListPaidContentItemsRequest {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserListPaidContentItemsResponse {}
+/// A 1:1 message sent by this client and its delivery status
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SentMessage {
+    #[prost(uint64, tag = "1")]
+    pub message_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub receiver_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(enumeration = "MessageStatus", tag = "3")]
+    pub status: i32,
+    #[prost(uint64, tag = "4")]
+    pub sent_time_stamp: u64,
+    /// 0 until a delivered receipt is received
+    #[prost(uint64, tag = "5")]
+    pub delivered_time_stamp: u64,
+    /// 0 until a read receipt is received
+    #[prost(uint64, tag = "6")]
+    pub read_time_stamp: u64,
+    /// time the message self-destructs at. 0 for never
+    #[prost(uint64, tag = "7")]
+    pub expires_time_stamp: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserMarkMessagesReadRequest {
+    /// sender of the messages
+    #[prost(message, optional, tag = "1")]
+    pub other_client_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(uint64, repeated, tag = "2")]
+    pub message_ids: ::prost::alloc::vec::Vec<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserMarkMessagesReadResponse {
+    /// false when read receipts to the sender are off
+    #[prost(bool, tag = "1")]
+    pub receipt_sent: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetMessagesStatusRequest {
+    #[prost(uint64, repeated, tag = "1")]
+    pub message_ids: ::prost::alloc::vec::Vec<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserGetMessagesStatusResponse {
+    /// unknown message ids are omitted
+    #[prost(message, repeated, tag = "1")]
+    pub messages: ::prost::alloc::vec::Vec<SentMessage>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserSetReadReceiptsRequest {
+    #[prost(message, optional, tag = "1")]
+    pub contact_id: ::core::option::Option<super::super::snp::core_types::EntityId>,
+    #[prost(bool, tag = "2")]
+    pub enabled: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserSetReadReceiptsResponse {}
+/// Status of a message this client sent changed after it got a receipt for it
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MessageStatusChangedEvent {
+    #[prost(message, optional, tag = "1")]
+    pub message: ::core::option::Option<SentMessage>,
+}
+///// Inbox and events
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ConversationType {
+    /// 1:1 text messages with another client
+    Direct = 0,
+    /// status updates channel we are subscribed to
+    StatusFeed = 1,
+    /// group we are a member of
+    Group = 2,
+}
+/////////////////
+
+///// Receipts
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MessageStatus {
+    /// message was sent to the receiver's provider
+    Sent = 0,
+    /// receiver's client got the message
+    Delivered = 1,
+    /// receiver's user read the message
+    Read = 2,
+}
 #[doc = r" Generated client implementations."]
 pub mod simple_client_user_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -221,6 +618,23 @@ pub mod simple_client_user_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        #[doc = " Move this client from its current provider to another provider"]
+        pub async fn user_switch_provider(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserSwitchProviderRequest>,
+        ) -> Result<tonic::Response<super::UserSwitchProviderResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserSwitchProvider",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         #[doc = " Set other client bundle (so we can chat with him)"]
         pub async fn user_add_other_client_bundle(
             &mut self,
@@ -258,6 +672,181 @@ pub mod simple_client_user_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        #[doc = " Send a 1:1 message with a file or an image attachment to another client on behalf of user"]
+        pub async fn user_send_attachment(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserSendAttachmentRequest>,
+        ) -> Result<tonic::Response<super::UserSendAttachmentResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserSendAttachment",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Get the safety number of this client and a contact so users can compare it out-of-band"]
+        pub async fn user_get_contact_safety_number(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserGetContactSafetyNumberRequest>,
+        ) -> Result<tonic::Response<super::UserGetContactSafetyNumberResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserGetContactSafetyNumber",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Mark a contact as verified after the user compared safety numbers. Unblocks a contact whose identity key changed"]
+        pub async fn user_verify_contact(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserVerifyContactRequest>,
+        ) -> Result<tonic::Response<super::UserVerifyContactResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserVerifyContact",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Audit the history of this client's identity bundles in the blockchain transparency log"]
+        pub async fn user_audit_identity(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserAuditIdentityRequest>,
+        ) -> Result<tonic::Response<super::UserAuditIdentityResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserAuditIdentity",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " List the conversations this client received messages in, most recently active first"]
+        pub async fn user_list_conversations(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserListConversationsRequest>,
+        ) -> Result<tonic::Response<super::UserListConversationsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserListConversations",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Get a page of the messages received in a conversation, oldest first"]
+        pub async fn user_get_conversation_messages(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserGetConversationMessagesRequest>,
+        ) -> Result<tonic::Response<super::UserGetConversationMessagesResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserGetConversationMessages",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Stream events of this client such as received messages and delivery failures as they happen"]
+        pub async fn subscribe_events(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SubscribeEventsRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::ClientEvent>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/SubscribeEvents",
+            );
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        #[doc = " Mark 1:1 messages received from another client as read. Sends it a read receipt unless read receipts to it are off"]
+        pub async fn user_mark_messages_read(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserMarkMessagesReadRequest>,
+        ) -> Result<tonic::Response<super::UserMarkMessagesReadResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserMarkMessagesRead",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Get the delivery status of 1:1 messages this client sent"]
+        pub async fn user_get_messages_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserGetMessagesStatusRequest>,
+        ) -> Result<tonic::Response<super::UserGetMessagesStatusResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserGetMessagesStatus",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Turn on or off sending read receipts to a contact. Read receipts are on by default"]
+        pub async fn user_set_read_receipts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserSetReadReceiptsRequest>,
+        ) -> Result<tonic::Response<super::UserSetReadReceiptsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserSetReadReceipts",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         #[doc = " Create a new status update channel and return its id and bundle so we can share it with other clients so"]
         #[doc = " they may subscribe"]
         pub async fn user_create_status_update_channel(
@@ -328,6 +917,24 @@ pub mod simple_client_user_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        #[doc = " Request a page of content published to a channel we are subscribed to or a group we are a member of from its"]
+        #[doc = " creator. The page is published in a channel history received event"]
+        pub async fn user_get_channel_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserGetChannelHistoryRequest>,
+        ) -> Result<tonic::Response<super::UserGetChannelHistoryResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserGetChannelHistory",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         #[doc = " Create a new group channel and return its id and bundle so we can share it with other clients so"]
         #[doc = " they may subscribe"]
         pub async fn user_create_group(
@@ -380,6 +987,41 @@ pub mod simple_client_user_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        #[doc = " Change a group's membership as its creator or as a co-admin - remove or ban members, appoint co-admins,"]
+        #[doc = " approve or deny requests to join the group and block members from replying"]
+        pub async fn user_group_admin(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserGroupAdminRequest>,
+        ) -> Result<tonic::Response<super::UserGroupAdminResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserGroupAdmin",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Get a group's members bundle and, for a group we created, its pending requests to join it"]
+        pub async fn user_get_group_members(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserGetGroupMembersRequest>,
+        ) -> Result<tonic::Response<super::UserGetGroupMembersResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/upsetter.simple_client.SimpleClientUserService/UserGetGroupMembers",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         #[doc = " Create a new paid content item"]
         pub async fn user_create_paid_item(
             &mut self,
@@ -462,6 +1104,11 @@ pub mod simple_client_user_service_server {
             &self,
             request: tonic::Request<super::UserSetProviderRequest>,
         ) -> Result<tonic::Response<super::UserSetProviderResponse>, tonic::Status>;
+        #[doc = " Move this client from its current provider to another provider"]
+        async fn user_switch_provider(
+            &self,
+            request: tonic::Request<super::UserSwitchProviderRequest>,
+        ) -> Result<tonic::Response<super::UserSwitchProviderResponse>, tonic::Status>;
         #[doc = " Set other client bundle (so we can chat with him)"]
         async fn user_add_other_client_bundle(
             &self,
@@ -474,6 +1121,61 @@ pub mod simple_client_user_service_server {
             &self,
             request: tonic::Request<super::UserSendTextMessageRequest>,
         ) -> Result<tonic::Response<super::UserSendTextMessageResponse>, tonic::Status>;
+        #[doc = " Send a 1:1 message with a file or an image attachment to another client on behalf of user"]
+        async fn user_send_attachment(
+            &self,
+            request: tonic::Request<super::UserSendAttachmentRequest>,
+        ) -> Result<tonic::Response<super::UserSendAttachmentResponse>, tonic::Status>;
+        #[doc = " Get the safety number of this client and a contact so users can compare it out-of-band"]
+        async fn user_get_contact_safety_number(
+            &self,
+            request: tonic::Request<super::UserGetContactSafetyNumberRequest>,
+        ) -> Result<tonic::Response<super::UserGetContactSafetyNumberResponse>, tonic::Status>;
+        #[doc = " Mark a contact as verified after the user compared safety numbers. Unblocks a contact whose identity key changed"]
+        async fn user_verify_contact(
+            &self,
+            request: tonic::Request<super::UserVerifyContactRequest>,
+        ) -> Result<tonic::Response<super::UserVerifyContactResponse>, tonic::Status>;
+        #[doc = " Audit the history of this client's identity bundles in the blockchain transparency log"]
+        async fn user_audit_identity(
+            &self,
+            request: tonic::Request<super::UserAuditIdentityRequest>,
+        ) -> Result<tonic::Response<super::UserAuditIdentityResponse>, tonic::Status>;
+        #[doc = " List the conversations this client received messages in, most recently active first"]
+        async fn user_list_conversations(
+            &self,
+            request: tonic::Request<super::UserListConversationsRequest>,
+        ) -> Result<tonic::Response<super::UserListConversationsResponse>, tonic::Status>;
+        #[doc = " Get a page of the messages received in a conversation, oldest first"]
+        async fn user_get_conversation_messages(
+            &self,
+            request: tonic::Request<super::UserGetConversationMessagesRequest>,
+        ) -> Result<tonic::Response<super::UserGetConversationMessagesResponse>, tonic::Status>;
+        #[doc = "Server streaming response type for the SubscribeEvents method."]
+        type SubscribeEventsStream: futures_core::Stream<Item = Result<super::ClientEvent, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        #[doc = " Stream events of this client such as received messages and delivery failures as they happen"]
+        async fn subscribe_events(
+            &self,
+            request: tonic::Request<super::SubscribeEventsRequest>,
+        ) -> Result<tonic::Response<Self::SubscribeEventsStream>, tonic::Status>;
+        #[doc = " Mark 1:1 messages received from another client as read. Sends it a read receipt unless read receipts to it are off"]
+        async fn user_mark_messages_read(
+            &self,
+            request: tonic::Request<super::UserMarkMessagesReadRequest>,
+        ) -> Result<tonic::Response<super::UserMarkMessagesReadResponse>, tonic::Status>;
+        #[doc = " Get the delivery status of 1:1 messages this client sent"]
+        async fn user_get_messages_status(
+            &self,
+            request: tonic::Request<super::UserGetMessagesStatusRequest>,
+        ) -> Result<tonic::Response<super::UserGetMessagesStatusResponse>, tonic::Status>;
+        #[doc = " Turn on or off sending read receipts to a contact. Read receipts are on by default"]
+        async fn user_set_read_receipts(
+            &self,
+            request: tonic::Request<super::UserSetReadReceiptsRequest>,
+        ) -> Result<tonic::Response<super::UserSetReadReceiptsResponse>, tonic::Status>;
         #[doc = " Create a new status update channel and return its id and bundle so we can share it with other clients so"]
         #[doc = " they may subscribe"]
         async fn user_create_status_update_channel(
@@ -495,6 +1197,12 @@ pub mod simple_client_user_service_server {
             &self,
             request: tonic::Request<super::UserNewPostRequest>,
         ) -> Result<tonic::Response<super::UserNewPostResponse>, tonic::Status>;
+        #[doc = " Request a page of content published to a channel we are subscribed to or a group we are a member of from its"]
+        #[doc = " creator. The page is published in a channel history received event"]
+        async fn user_get_channel_history(
+            &self,
+            request: tonic::Request<super::UserGetChannelHistoryRequest>,
+        ) -> Result<tonic::Response<super::UserGetChannelHistoryResponse>, tonic::Status>;
         #[doc = " Create a new group channel and return its id and bundle so we can share it with other clients so"]
         #[doc = " they may subscribe"]
         async fn user_create_group(
@@ -511,6 +1219,17 @@ pub mod simple_client_user_service_server {
             &self,
             request: tonic::Request<super::UserLeaveGroupRequest>,
         ) -> Result<tonic::Response<super::UserLeaveGroupResponse>, tonic::Status>;
+        #[doc = " Change a group's membership as its creator or as a co-admin - remove or ban members, appoint co-admins,"]
+        #[doc = " approve or deny requests to join the group and block members from replying"]
+        async fn user_group_admin(
+            &self,
+            request: tonic::Request<super::UserGroupAdminRequest>,
+        ) -> Result<tonic::Response<super::UserGroupAdminResponse>, tonic::Status>;
+        #[doc = " Get a group's members bundle and, for a group we created, its pending requests to join it"]
+        async fn user_get_group_members(
+            &self,
+            request: tonic::Request<super::UserGetGroupMembersRequest>,
+        ) -> Result<tonic::Response<super::UserGetGroupMembersResponse>, tonic::Status>;
         #[doc = " Create a new paid content item"]
         async fn user_create_paid_item(
             &self,
@@ -584,7 +1303,7 @@ pub mod simple_client_user_service_server {
         }
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             let inner = self.inner.clone();
-            match req . uri () . path () { "/upsetter.simple_client.SimpleClientUserService/UserSetProvider" => { # [allow (non_camel_case_types)] struct UserSetProviderSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSetProviderRequest > for UserSetProviderSvc < T > { type Response = super :: UserSetProviderResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSetProviderRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_set_provider (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSetProviderSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserAddOtherClientBundle" => { # [allow (non_camel_case_types)] struct UserAddOtherClientBundleSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: super :: super :: snp :: core_types :: ProviderSignedClientIdentityBundle > for UserAddOtherClientBundleSvc < T > { type Response = super :: UserAddOtherClientBundleResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: super :: super :: snp :: core_types :: ProviderSignedClientIdentityBundle >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_add_other_client_bundle (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserAddOtherClientBundleSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserSendTextMessage" => { # [allow (non_camel_case_types)] struct UserSendTextMessageSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSendTextMessageRequest > for UserSendTextMessageSvc < T > { type Response = super :: UserSendTextMessageResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSendTextMessageRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_send_text_message (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSendTextMessageSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserCreateStatusUpdateChannel" => { # [allow (non_camel_case_types)] struct UserCreateStatusUpdateChannelSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserCreateStatusUpdateChannelRequest > for UserCreateStatusUpdateChannelSvc < T > { type Response = super :: UserCreateStatusUpdateChannelResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserCreateStatusUpdateChannelRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_create_status_update_channel (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserCreateStatusUpdateChannelSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserSubscribeToStatusUpdates" => { # [allow (non_camel_case_types)] struct UserSubscribeToStatusUpdatesSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSubscribeRequest > for UserSubscribeToStatusUpdatesSvc < T > { type Response = super :: UserSubscribeResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSubscribeRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_subscribe_to_status_updates (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSubscribeToStatusUpdatesSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserUnsubscribeFromStatusUpdates" => { # [allow (non_camel_case_types)] struct UserUnsubscribeFromStatusUpdatesSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserUnsubscribeRequest > for UserUnsubscribeFromStatusUpdatesSvc < T > { type Response = super :: UserUnsubscribeResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserUnsubscribeRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_unsubscribe_from_status_updates (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserUnsubscribeFromStatusUpdatesSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserNewPost" => { # [allow (non_camel_case_types)] struct UserNewPostSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserNewPostRequest > for UserNewPostSvc < T > { type Response = super :: UserNewPostResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserNewPostRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_new_post (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserNewPostSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserCreateGroup" => { # [allow (non_camel_case_types)] struct UserCreateGroupSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserCreateGroupRequest > for UserCreateGroupSvc < T > { type Response = super :: UserCreateGroupResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserCreateGroupRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_create_group (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserCreateGroupSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserJoinGroup" => { # [allow (non_camel_case_types)] struct UserJoinGroupSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserJoinGroupRequest > for UserJoinGroupSvc < T > { type Response = super :: UserJoinGroupResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserJoinGroupRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_join_group (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserJoinGroupSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserLeaveGroup" => { # [allow (non_camel_case_types)] struct UserLeaveGroupSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserLeaveGroupRequest > for UserLeaveGroupSvc < T > { type Response = super :: UserLeaveGroupResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserLeaveGroupRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_leave_group (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserLeaveGroupSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserCreatePaidItem" => { # [allow (non_camel_case_types)] struct UserCreatePaidItemSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserCreatePaidItemRequest > for UserCreatePaidItemSvc < T > { type Response = super :: UserCreatePaidItemResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserCreatePaidItemRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_create_paid_item (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserCreatePaidItemSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserBuyPaidItem" => { # [allow (non_camel_case_types)] struct UserBuyPaidItemSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserBuyPaidItemRequest > for UserBuyPaidItemSvc < T > { type Response = super :: UserBuyPaidItemResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserBuyPaidItemRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_buy_paid_item (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserBuyPaidItemSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserListPaidContentItems" => { # [allow (non_camel_case_types)] struct UserListPaidContentItemsSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserListPaidContentItemsRequest > for UserListPaidContentItemsSvc < T > { type Response = super :: UserListPaidContentItemsResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserListPaidContentItemsRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_list_paid_content_items (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserListPaidContentItemsSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/SetBlockchainService" => { # [allow (non_camel_case_types)] struct SetBlockchainServiceSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: SetBlockchainServiceRequest > for SetBlockchainServiceSvc < T > { type Response = () ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: SetBlockchainServiceRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . set_blockchain_service (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = SetBlockchainServiceSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } _ => Box :: pin (async move { Ok (http :: Response :: builder () . status (200) . header ("grpc-status" , "12") . header ("content-type" , "application/grpc") . body (empty_body ()) . unwrap ()) }) , }
+            match req . uri () . path () { "/upsetter.simple_client.SimpleClientUserService/UserSetProvider" => { # [allow (non_camel_case_types)] struct UserSetProviderSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSetProviderRequest > for UserSetProviderSvc < T > { type Response = super :: UserSetProviderResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSetProviderRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_set_provider (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSetProviderSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserSwitchProvider" => { # [allow (non_camel_case_types)] struct UserSwitchProviderSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSwitchProviderRequest > for UserSwitchProviderSvc < T > { type Response = super :: UserSwitchProviderResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSwitchProviderRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_switch_provider (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSwitchProviderSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserAddOtherClientBundle" => { # [allow (non_camel_case_types)] struct UserAddOtherClientBundleSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: super :: super :: snp :: core_types :: ProviderSignedClientIdentityBundle > for UserAddOtherClientBundleSvc < T > { type Response = super :: UserAddOtherClientBundleResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: super :: super :: snp :: core_types :: ProviderSignedClientIdentityBundle >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_add_other_client_bundle (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserAddOtherClientBundleSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserSendTextMessage" => { # [allow (non_camel_case_types)] struct UserSendTextMessageSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSendTextMessageRequest > for UserSendTextMessageSvc < T > { type Response = super :: UserSendTextMessageResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSendTextMessageRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_send_text_message (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSendTextMessageSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserSendAttachment" => { # [allow (non_camel_case_types)] struct UserSendAttachmentSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSendAttachmentRequest > for UserSendAttachmentSvc < T > { type Response = super :: UserSendAttachmentResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSendAttachmentRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_send_attachment (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSendAttachmentSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserGetContactSafetyNumber" => { # [allow (non_camel_case_types)] struct UserGetContactSafetyNumberSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserGetContactSafetyNumberRequest > for UserGetContactSafetyNumberSvc < T > { type Response = super :: UserGetContactSafetyNumberResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserGetContactSafetyNumberRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_get_contact_safety_number (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserGetContactSafetyNumberSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserVerifyContact" => { # [allow (non_camel_case_types)] struct UserVerifyContactSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserVerifyContactRequest > for UserVerifyContactSvc < T > { type Response = super :: UserVerifyContactResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserVerifyContactRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_verify_contact (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserVerifyContactSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserAuditIdentity" => { # [allow (non_camel_case_types)] struct UserAuditIdentitySvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserAuditIdentityRequest > for UserAuditIdentitySvc < T > { type Response = super :: UserAuditIdentityResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserAuditIdentityRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_audit_identity (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserAuditIdentitySvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserListConversations" => { # [allow (non_camel_case_types)] struct UserListConversationsSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserListConversationsRequest > for UserListConversationsSvc < T > { type Response = super :: UserListConversationsResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserListConversationsRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_list_conversations (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserListConversationsSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserGetConversationMessages" => { # [allow (non_camel_case_types)] struct UserGetConversationMessagesSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserGetConversationMessagesRequest > for UserGetConversationMessagesSvc < T > { type Response = super :: UserGetConversationMessagesResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserGetConversationMessagesRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_get_conversation_messages (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserGetConversationMessagesSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/SubscribeEvents" => { # [allow (non_camel_case_types)] struct SubscribeEventsSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: ServerStreamingService < super :: SubscribeEventsRequest > for SubscribeEventsSvc < T > { type Response = super :: ClientEvent ; type ResponseStream = T :: SubscribeEventsStream ; type Future = BoxFuture < tonic :: Response < Self :: ResponseStream > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: SubscribeEventsRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . subscribe_events (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = SubscribeEventsSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . server_streaming (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserMarkMessagesRead" => { # [allow (non_camel_case_types)] struct UserMarkMessagesReadSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserMarkMessagesReadRequest > for UserMarkMessagesReadSvc < T > { type Response = super :: UserMarkMessagesReadResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserMarkMessagesReadRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_mark_messages_read (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserMarkMessagesReadSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserGetMessagesStatus" => { # [allow (non_camel_case_types)] struct UserGetMessagesStatusSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserGetMessagesStatusRequest > for UserGetMessagesStatusSvc < T > { type Response = super :: UserGetMessagesStatusResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserGetMessagesStatusRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_get_messages_status (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserGetMessagesStatusSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserSetReadReceipts" => { # [allow (non_camel_case_types)] struct UserSetReadReceiptsSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSetReadReceiptsRequest > for UserSetReadReceiptsSvc < T > { type Response = super :: UserSetReadReceiptsResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSetReadReceiptsRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_set_read_receipts (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSetReadReceiptsSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserCreateStatusUpdateChannel" => { # [allow (non_camel_case_types)] struct UserCreateStatusUpdateChannelSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserCreateStatusUpdateChannelRequest > for UserCreateStatusUpdateChannelSvc < T > { type Response = super :: UserCreateStatusUpdateChannelResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserCreateStatusUpdateChannelRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_create_status_update_channel (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserCreateStatusUpdateChannelSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserSubscribeToStatusUpdates" => { # [allow (non_camel_case_types)] struct UserSubscribeToStatusUpdatesSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserSubscribeRequest > for UserSubscribeToStatusUpdatesSvc < T > { type Response = super :: UserSubscribeResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserSubscribeRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_subscribe_to_status_updates (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserSubscribeToStatusUpdatesSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserUnsubscribeFromStatusUpdates" => { # [allow (non_camel_case_types)] struct UserUnsubscribeFromStatusUpdatesSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserUnsubscribeRequest > for UserUnsubscribeFromStatusUpdatesSvc < T > { type Response = super :: UserUnsubscribeResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserUnsubscribeRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_unsubscribe_from_status_updates (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserUnsubscribeFromStatusUpdatesSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserNewPost" => { # [allow (non_camel_case_types)] struct UserNewPostSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserNewPostRequest > for UserNewPostSvc < T > { type Response = super :: UserNewPostResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserNewPostRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_new_post (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserNewPostSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserGetChannelHistory" => { # [allow (non_camel_case_types)] struct UserGetChannelHistorySvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserGetChannelHistoryRequest > for UserGetChannelHistorySvc < T > { type Response = super :: UserGetChannelHistoryResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserGetChannelHistoryRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_get_channel_history (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserGetChannelHistorySvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserCreateGroup" => { # [allow (non_camel_case_types)] struct UserCreateGroupSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserCreateGroupRequest > for UserCreateGroupSvc < T > { type Response = super :: UserCreateGroupResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserCreateGroupRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_create_group (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserCreateGroupSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserJoinGroup" => { # [allow (non_camel_case_types)] struct UserJoinGroupSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserJoinGroupRequest > for UserJoinGroupSvc < T > { type Response = super :: UserJoinGroupResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserJoinGroupRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_join_group (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserJoinGroupSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserLeaveGroup" => { # [allow (non_camel_case_types)] struct UserLeaveGroupSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserLeaveGroupRequest > for UserLeaveGroupSvc < T > { type Response = super :: UserLeaveGroupResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserLeaveGroupRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_leave_group (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserLeaveGroupSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserGroupAdmin" => { # [allow (non_camel_case_types)] struct UserGroupAdminSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserGroupAdminRequest > for UserGroupAdminSvc < T > { type Response = super :: UserGroupAdminResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserGroupAdminRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_group_admin (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserGroupAdminSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserGetGroupMembers" => { # [allow (non_camel_case_types)] struct UserGetGroupMembersSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserGetGroupMembersRequest > for UserGetGroupMembersSvc < T > { type Response = super :: UserGetGroupMembersResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserGetGroupMembersRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_get_group_members (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserGetGroupMembersSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserCreatePaidItem" => { # [allow (non_camel_case_types)] struct UserCreatePaidItemSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserCreatePaidItemRequest > for UserCreatePaidItemSvc < T > { type Response = super :: UserCreatePaidItemResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserCreatePaidItemRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_create_paid_item (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserCreatePaidItemSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserBuyPaidItem" => { # [allow (non_camel_case_types)] struct UserBuyPaidItemSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserBuyPaidItemRequest > for UserBuyPaidItemSvc < T > { type Response = super :: UserBuyPaidItemResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserBuyPaidItemRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_buy_paid_item (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserBuyPaidItemSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/UserListPaidContentItems" => { # [allow (non_camel_case_types)] struct UserListPaidContentItemsSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: UserListPaidContentItemsRequest > for UserListPaidContentItemsSvc < T > { type Response = super :: UserListPaidContentItemsResponse ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: UserListPaidContentItemsRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . user_list_paid_content_items (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = UserListPaidContentItemsSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } "/upsetter.simple_client.SimpleClientUserService/SetBlockchainService" => { # [allow (non_camel_case_types)] struct SetBlockchainServiceSvc < T : SimpleClientUserService > (pub Arc < T >) ; impl < T : SimpleClientUserService > tonic :: server :: UnaryService < super :: SetBlockchainServiceRequest > for SetBlockchainServiceSvc < T > { type Response = () ; type Future = BoxFuture < tonic :: Response < Self :: Response > , tonic :: Status > ; fn call (& mut self , request : tonic :: Request < super :: SetBlockchainServiceRequest >) -> Self :: Future { let inner = self . 0 . clone () ; let fut = async move { (* inner) . set_blockchain_service (request) . await } ; Box :: pin (fut) } } let accept_compression_encodings = self . accept_compression_encodings ; let send_compression_encodings = self . send_compression_encodings ; let inner = self . inner . clone () ; let fut = async move { let inner = inner . 0 ; let method = SetBlockchainServiceSvc (inner) ; let codec = tonic :: codec :: ProstCodec :: default () ; let mut grpc = tonic :: server :: Grpc :: new (codec) . apply_compression_config (accept_compression_encodings , send_compression_encodings) ; let res = grpc . unary (method , req) . await ; Ok (res) } ; Box :: pin (fut) } _ => Box :: pin (async move { Ok (http :: Response :: builder () . status (200) . header ("grpc-status" , "12") . header ("content-type" , "application/grpc") . body (empty_body ()) . unwrap ()) }) , }
         }
     }
     impl<T: SimpleClientUserService> Clone for SimpleClientUserServiceServer<T> {
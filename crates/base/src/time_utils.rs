@@ -15,3 +15,28 @@ pub fn local_date(time_stamp_nano: u64) -> DateTime<Local> {
 
     Local.from_local_datetime(&naive).unwrap()
 }
+
+/// Returns the current unix epoch time in nanos
+pub fn now() -> u64 {
+    Utc::now().timestamp_nanos() as u64
+}
+
+/// Returns the time a ttl in seconds from a unix epoch timestamp in nanos ends at. None for no ttl
+pub fn expiration_time(time_stamp_nano: u64, ttl: u64) -> Option<u64> {
+    match ttl {
+        0 => None,
+        ttl => Some(time_stamp_nano.saturating_add(ttl.saturating_mul(A_BILLY))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiration_time() {
+        assert_eq!(expiration_time(5, 0), None);
+        assert_eq!(expiration_time(5, 2), Some(2 * A_BILLY + 5));
+        assert_eq!(expiration_time(u64::MAX, 2), Some(u64::MAX));
+    }
+}
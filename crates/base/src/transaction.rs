@@ -2,29 +2,62 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::api_types_extensions::Signed;
+use crate::api_types_extensions::{NetworkScoped, Signed};
+use crate::signatures::{sign_message, verify_message, SigningKey};
+use crate::snp::snp_blockchain::transaction::Data;
 use crate::snp::snp_blockchain::Transaction;
+use crate::snp::snp_core_types::Signature;
 use anyhow::{anyhow, bail, Result};
-use ed25519_dalek::ed25519::signature::Signature;
-use ed25519_dalek::{Keypair, Signer, Verifier};
 use orion::hazardous::hash::sha2::sha512::Sha512;
 
-impl Signed for Transaction {
-    /// Sign the transaction using the provided keypair
-    fn sign(&mut self, signer: &Keypair) -> Result<()> {
-        self.signature = vec![];
+impl NetworkScoped for Transaction {
+    fn get_net_id(&self) -> u32 {
+        self.net_id
+    }
 
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
+    /// Verify the transaction and the identity bundles it includes are for the network
+    fn verify_net_id(&self, net_id: u32) -> Result<()> {
+        if self.net_id != net_id {
+            bail!(
+                "transaction is for network {} but expected network {}",
+                self.net_id,
+                net_id
+            )
+        }
 
-        self.signature = signer.sign(&buf).as_ref().to_vec();
+        match self.data.as_ref() {
+            Some(Data::ProviderBundle(data)) => match data.provider_bundle.as_ref() {
+                Some(bundle) => bundle.verify_net_id(net_id),
+                None => Ok(()),
+            },
+            Some(Data::ClientBundle(data)) => match data
+                .client_bundle
+                .as_ref()
+                .and_then(|b| b.client_bundle.as_ref())
+            {
+                Some(bundle) => bundle.verify_net_id(net_id),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Signed for Transaction {
+    /// Sign the transaction using the provided signer
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = vec![];
+        self.signature_scheme_id = signer.scheme().id();
+        self.signature = sign_message(self, signer)?.signature;
         Ok(())
     }
 
     /// Verify transaction is properly signed by its sender_pub_key
     fn verify_signature(&self) -> Result<()> {
-        let signature = self.signature.clone();
+        let signature = Signature {
+            scheme_id: self.signature_scheme_id,
+            signature: self.signature.clone(),
+        };
         let mut data = self.clone();
         data.signature = vec![];
 
@@ -32,20 +65,13 @@ impl Signed for Transaction {
             // Fee was paid by another party. Remove the parts signed by the fee_signature as it was not signed by sender.
             data.fee = None;
             data.fee_signature = vec![];
+            data.fee_signature_scheme_id = 0;
         }
 
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature.as_slice())?;
-        let signer_pub_key = ed25519_dalek::PublicKey::from_bytes(self.sender_pub_key.as_ref())?;
-
-        Ok(signer_pub_key.verify(&buf, &signature)?)
+        verify_message(&data, self.sender_pub_key.as_slice(), &signature)
     }
 }
+
 impl Transaction {
     /// Returns true if fee was paid by a 3rd party and false when it was paid by tx sender
     pub fn third_party_fee_payer(&self) -> bool {
@@ -74,6 +100,14 @@ impl Transaction {
         Ok(())
     }
 
+    /// Sign the transaction as the 3rd party fee payer. Fee payer signs all other transaction fields.
+    pub fn sign_fee(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.fee_signature = vec![];
+        self.fee_signature_scheme_id = signer.scheme().id();
+        self.fee_signature = sign_message(self, signer)?.signature;
+        Ok(())
+    }
+
     /// Verify transactions' fee signature if tx fee was not paid by tx sender
     pub fn verify_fee_signature(&self) -> Result<()> {
         if self.fee_signature.is_empty() {
@@ -85,20 +119,14 @@ impl Transaction {
             .as_ref()
             .ok_or_else(|| anyhow!("missing expected fee data"))?;
 
-        let signature = self.fee_signature.clone();
+        let signature = Signature {
+            scheme_id: self.fee_signature_scheme_id,
+            signature: self.fee_signature.clone(),
+        };
         let mut data = self.clone();
         data.fee_signature = vec![];
 
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature.as_slice())?;
-        let signer_pub_key = ed25519_dalek::PublicKey::from_bytes(fee.payer_public_key.as_ref())?;
-
-        Ok(signer_pub_key.verify(&buf, &signature)?)
+        verify_message(&data, fee.payer_public_key.as_slice(), &signature)
     }
 
     pub fn get_sender_address(&self) -> Vec<u8> {
@@ -0,0 +1,479 @@
+// Copyright (c) 2021, Subnet Authors.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+//! Merkle tree of an append-only log as specified in RFC 6962 (Certificate Transparency).
+//! Used by the identity bundles transparency log. Proofs have the RFC's layout so any CT verifier can check them.
+//!
+//! A log stores the root hashes of the tree's complete subtrees so it can compute tree heads and proofs
+//! without reading all of its leaves. A complete subtree is identified by its level and its index in the level -
+//! subtree (level, index) has the 2^level leaves starting at leaf index * 2^level.
+
+use crate::api_types_extensions::Signed;
+use crate::signatures::{sign_message, verify_message, SigningKey};
+use crate::snp::snp_blockchain::{TransparencyLogEntry, TransparencyLogHead};
+use anyhow::{anyhow, bail, Result};
+use sha2::{Digest, Sha256};
+
+/// Hash of a log entry's data
+pub fn leaf_hash(data: &[u8]) -> Vec<u8> {
+    Sha256::new().chain([0u8]).chain(data).finalize().to_vec()
+}
+
+/// Hash of an internal tree node
+pub fn node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    Sha256::new()
+        .chain([1u8])
+        .chain(left)
+        .chain(right)
+        .finalize()
+        .to_vec()
+}
+
+/// Largest power of 2 smaller than n. n must be greater than 1
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+/// Root hash of a tree with the provided leaves hashes
+pub fn root_hash(leaves: &[Vec<u8>]) -> Vec<u8> {
+    match leaves.len() {
+        0 => Sha256::digest(b"").to_vec(),
+        1 => leaves[0].clone(),
+        n => {
+            let k = split_point(n);
+            node_hash(&root_hash(&leaves[..k]), &root_hash(&leaves[k..]))
+        }
+    }
+}
+
+/// Returns the complete subtrees (level, index) whose root hashes make up the root hash of the size leaves
+/// starting at leaf start, largest first. Ranges of a tree's proofs are aligned so they are made of complete subtrees
+pub fn subtrees(start: u64, size: u64) -> Vec<(u32, u64)> {
+    let mut subtrees = vec![];
+    let (mut start, mut size) = (start, size);
+    while size > 0 {
+        let level = 63 - size.leading_zeros();
+        subtrees.push((level, start >> level));
+        start += 1 << level;
+        size -= 1 << level;
+    }
+    subtrees
+}
+
+/// Root hash of leaves from the root hashes of the complete subtrees returned by subtrees() for them
+pub fn subtrees_root_hash(hashes: &[Vec<u8>]) -> Vec<u8> {
+    match hashes.split_last() {
+        None => Sha256::digest(b"").to_vec(),
+        Some((last, rest)) => rest
+            .iter()
+            .rev()
+            .fold(last.clone(), |right, left| node_hash(left, &right)),
+    }
+}
+
+/// Returns the leaves ranges (start, size) whose root hashes make up a proof that the leaf at index is
+/// included in a tree of tree_size leaves
+pub fn inclusion_proof_ranges(tree_size: u64, index: u64) -> Result<Vec<(u64, u64)>> {
+    if index >= tree_size {
+        bail!("leaf {} is not in a tree of size {}", index, tree_size)
+    }
+
+    fn path(start: u64, n: u64, index: u64) -> Vec<(u64, u64)> {
+        if n == 1 {
+            return vec![];
+        }
+        let k = split_point(n as usize) as u64;
+        if index < k {
+            let mut proof = path(start, k, index);
+            proof.push((start + k, n - k));
+            proof
+        } else {
+            let mut proof = path(start + k, n - k, index - k);
+            proof.push((start, k));
+            proof
+        }
+    }
+
+    Ok(path(0, tree_size, index))
+}
+
+/// Returns the leaves ranges (start, size) whose root hashes make up a proof that the tree with the first
+/// old_size leaves is a prefix of a tree of tree_size leaves
+pub fn consistency_proof_ranges(tree_size: u64, old_size: u64) -> Result<Vec<(u64, u64)>> {
+    if old_size > tree_size {
+        bail!(
+            "tree of size {} is larger than tree of size {}",
+            old_size,
+            tree_size
+        )
+    }
+
+    if old_size == 0 || old_size == tree_size {
+        return Ok(vec![]);
+    }
+
+    fn sub_proof(start: u64, n: u64, m: u64, complete_subtree: bool) -> Vec<(u64, u64)> {
+        if m == n {
+            return if complete_subtree {
+                vec![]
+            } else {
+                vec![(start, n)]
+            };
+        }
+        let k = split_point(n as usize) as u64;
+        if m <= k {
+            let mut proof = sub_proof(start, k, m, complete_subtree);
+            proof.push((start + k, n - k));
+            proof
+        } else {
+            let mut proof = sub_proof(start + k, n - k, m - k, false);
+            proof.push((start, k));
+            proof
+        }
+    }
+
+    Ok(sub_proof(0, tree_size, old_size, true))
+}
+
+/// Returns the root hashes of leaves ranges
+fn ranges_root_hashes(leaves: &[Vec<u8>], ranges: Vec<(u64, u64)>) -> Vec<Vec<u8>> {
+    ranges
+        .into_iter()
+        .map(|(start, size)| root_hash(&leaves[start as usize..(start + size) as usize]))
+        .collect()
+}
+
+/// Returns a proof that the leaf at index is included in the tree of the provided leaves
+pub fn inclusion_proof(leaves: &[Vec<u8>], index: usize) -> Result<Vec<Vec<u8>>> {
+    let ranges = inclusion_proof_ranges(leaves.len() as u64, index as u64)?;
+    Ok(ranges_root_hashes(leaves, ranges))
+}
+
+/// Returns a proof that the tree with the first old_size leaves is a prefix of the tree of the provided leaves
+pub fn consistency_proof(leaves: &[Vec<u8>], old_size: usize) -> Result<Vec<Vec<u8>>> {
+    let ranges = consistency_proof_ranges(leaves.len() as u64, old_size as u64)?;
+    Ok(ranges_root_hashes(leaves, ranges))
+}
+
+/// Verify a proof that a leaf is included at index in a tree with the provided size and root hash
+pub fn verify_inclusion(
+    leaf_hash: &[u8],
+    index: u64,
+    tree_size: u64,
+    proof: &[Vec<u8>],
+    root: &[u8],
+) -> Result<()> {
+    if index >= tree_size {
+        bail!("leaf {} is not in a tree of size {}", index, tree_size)
+    }
+
+    let mut f_n = index;
+    let mut s_n = tree_size - 1;
+    let mut r = leaf_hash.to_vec();
+
+    for p in proof {
+        if s_n == 0 {
+            bail!("inclusion proof is too long")
+        }
+        if f_n & 1 == 1 || f_n == s_n {
+            r = node_hash(p, &r);
+            while f_n & 1 == 0 && f_n != 0 {
+                f_n >>= 1;
+                s_n >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    if s_n != 0 || r != root {
+        bail!("invalid inclusion proof")
+    }
+    Ok(())
+}
+
+/// Verify a proof that the tree with old_size leaves and old_root is a prefix of the tree with new_size leaves and new_root
+pub fn verify_consistency(
+    old_size: u64,
+    old_root: &[u8],
+    new_size: u64,
+    new_root: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<()> {
+    if old_size > new_size {
+        bail!(
+            "tree of size {} is larger than tree of size {}",
+            old_size,
+            new_size
+        )
+    }
+
+    if old_size == new_size {
+        if !proof.is_empty() || old_root != new_root {
+            bail!("different root hashes for the same tree size")
+        }
+        return Ok(());
+    }
+
+    // every tree is an extension of the empty tree
+    if old_size == 0 {
+        if !proof.is_empty() {
+            bail!("invalid consistency proof")
+        }
+        return Ok(());
+    }
+
+    if proof.is_empty() {
+        bail!("missing consistency proof")
+    }
+
+    // the old tree root is omitted from the proof when the old tree is a complete subtree
+    let mut proof = proof.to_vec();
+    if old_size.is_power_of_two() {
+        proof.insert(0, old_root.to_vec());
+    }
+
+    let mut f_n = old_size - 1;
+    let mut s_n = new_size - 1;
+    while f_n & 1 == 1 {
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    let mut f_r = proof[0].clone();
+    let mut s_r = proof[0].clone();
+
+    for c in &proof[1..] {
+        if s_n == 0 {
+            bail!("consistency proof is too long")
+        }
+        if f_n & 1 == 1 || f_n == s_n {
+            f_r = node_hash(c, &f_r);
+            s_r = node_hash(c, &s_r);
+            while f_n & 1 == 0 && f_n != 0 {
+                f_n >>= 1;
+                s_n >>= 1;
+            }
+        } else {
+            s_r = node_hash(&s_r, c);
+        }
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    if s_n != 0 || f_r != old_root || s_r != new_root {
+        bail!("invalid consistency proof")
+    }
+    Ok(())
+}
+
+/// Returns the hash of an identity bundle as committed to in transparency log entries
+pub fn bundle_hash<M: prost::Message>(bundle: &M) -> Vec<u8> {
+    Sha256::digest(&bundle.encode_to_vec()).to_vec()
+}
+
+impl TransparencyLogEntry {
+    /// Hash of this entry as a leaf in the log's tree
+    pub fn leaf_hash(&self) -> Vec<u8> {
+        use prost::Message;
+        leaf_hash(&self.encode_to_vec())
+    }
+}
+
+impl TransparencyLogHead {
+    /// Returns the public key of the log which signed this tree head
+    pub fn get_log_key(&self) -> Result<&[u8]> {
+        Ok(self
+            .log_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing log id"))?
+            .public_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing log public key"))?
+            .key
+            .as_ref())
+    }
+}
+
+/// A log signs its tree heads so a log which shows different trees to different auditors can be proven to
+/// have done so
+impl Signed for TransparencyLogHead {
+    fn sign(&mut self, signer: &dyn SigningKey) -> Result<()> {
+        self.signature = None;
+        self.signature = Some(sign_message(self, signer)?);
+        Ok(())
+    }
+
+    fn verify_signature(&self) -> Result<()> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing log signature"))?;
+
+        let mut data = self.clone();
+        data.signature = None;
+
+        verify_message(&data, self.get_log_key()?, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_leaves(count: usize) -> Vec<Vec<u8>> {
+        (0..count).map(|i| leaf_hash(&i.to_be_bytes())).collect()
+    }
+
+    #[test]
+    fn test_known_root_hashes() {
+        // RFC 6962 reference implementation test vectors
+        let data: Vec<Vec<u8>> = vec![
+            vec![],
+            vec![0x00],
+            vec![0x10],
+            vec![0x20, 0x21],
+            vec![0x30, 0x31],
+            vec![0x40, 0x41, 0x42, 0x43],
+            (0x50..0x58).collect(),
+            (0x60..0x70).collect(),
+        ];
+        let leaves: Vec<Vec<u8>> = data.iter().map(|d| leaf_hash(d)).collect();
+
+        assert_eq!(
+            hex::encode(root_hash(&[])),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(root_hash(&leaves[..3])),
+            "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77"
+        );
+        assert_eq!(
+            hex::encode(root_hash(&leaves[..7])),
+            "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c"
+        );
+        assert_eq!(
+            hex::encode(root_hash(&leaves)),
+            "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328"
+        );
+    }
+
+    #[test]
+    fn test_subtrees_root_hashes() {
+        let leaves = test_leaves(17);
+        let subtree_hash = |(level, index): (u32, u64)| {
+            let start = (index << level) as usize;
+            root_hash(&leaves[start..start + (1 << level)])
+        };
+
+        for size in 0..=leaves.len() {
+            let hashes: Vec<Vec<u8>> = subtrees(0, size as u64)
+                .into_iter()
+                .map(subtree_hash)
+                .collect();
+            assert_eq!(subtrees_root_hash(&hashes), root_hash(&leaves[..size]));
+        }
+
+        // ranges of proofs are made of complete subtrees of the tree
+        for size in 1..=leaves.len() as u64 {
+            let ranges = (0..size)
+                .flat_map(|index| inclusion_proof_ranges(size, index).unwrap())
+                .chain(
+                    (0..=size)
+                        .flat_map(|old_size| consistency_proof_ranges(size, old_size).unwrap()),
+                );
+            for (start, range_size) in ranges {
+                let hashes: Vec<Vec<u8>> = subtrees(start, range_size)
+                    .into_iter()
+                    .map(subtree_hash)
+                    .collect();
+                assert_eq!(
+                    subtrees_root_hash(&hashes),
+                    root_hash(&leaves[start as usize..(start + range_size) as usize])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        for size in 1..=17 {
+            let leaves = test_leaves(size);
+            let root = root_hash(&leaves);
+            for index in 0..size {
+                let proof = inclusion_proof(&leaves, index).unwrap();
+                verify_inclusion(&leaves[index], index as u64, size as u64, &proof, &root).unwrap();
+
+                // proof doesn't verify for another leaf or index
+                let other = (index + 1) % size;
+                if other != index {
+                    assert!(verify_inclusion(
+                        &leaves[other],
+                        index as u64,
+                        size as u64,
+                        &proof,
+                        &root
+                    )
+                    .is_err());
+                    assert!(verify_inclusion(
+                        &leaves[index],
+                        other as u64,
+                        size as u64,
+                        &proof,
+                        &root
+                    )
+                    .is_err());
+                }
+            }
+        }
+        assert!(inclusion_proof(&test_leaves(3), 3).is_err());
+    }
+
+    #[test]
+    fn test_consistency_proofs() {
+        let leaves = test_leaves(17);
+        for new_size in 1..=leaves.len() {
+            let new_root = root_hash(&leaves[..new_size]);
+            for old_size in 0..=new_size {
+                let old_root = root_hash(&leaves[..old_size]);
+                let proof = consistency_proof(&leaves[..new_size], old_size).unwrap();
+                verify_consistency(
+                    old_size as u64,
+                    &old_root,
+                    new_size as u64,
+                    &new_root,
+                    &proof,
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_forked_log_rejected() {
+        let leaves = test_leaves(8);
+        let old_root = root_hash(&leaves[..5]);
+
+        // a log which replaced an entry of the old tree can't prove it is consistent with it
+        let mut forked = leaves.clone();
+        forked[2] = leaf_hash(b"swapped bundle");
+        let forked_root = root_hash(&forked);
+
+        for proof in [
+            consistency_proof(&forked, 5).unwrap(),
+            consistency_proof(&leaves, 5).unwrap(),
+        ] {
+            assert!(verify_consistency(5, &old_root, 8, &forked_root, &proof).is_err());
+        }
+
+        // a shrinking log is never consistent
+        assert!(verify_consistency(8, &root_hash(&leaves), 5, &old_root, &[]).is_err());
+    }
+}
@@ -3,57 +3,38 @@
 //
 
 use crate::api_types_extensions::{Signed, SignedWithExternalVerifier};
+use crate::signatures::{sign_message, verify_message, SigningKey};
 use crate::snp::snp_server_api::{NewSessionRequest, TypedMessage};
 use anyhow::anyhow;
-use ed25519_dalek::ed25519::signature::Signature;
 
 impl Signed for TypedMessage {
     /// Sign the message
-    fn sign(&mut self, signer: &ed25519_dalek::Keypair) -> anyhow::Result<()> {
-        use ed25519_dalek::Signer;
-        use prost::Message;
-        // Sign and add signature to typed_msg as alice
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-        let typed_message_signature = signer.sign(&buf);
-        use crate::snp::snp_core_types::Signature;
-        self.signature = Some(Signature {
-            scheme_id: 0,
-            signature: typed_message_signature.to_bytes().to_vec(),
-        });
-
+    fn sign(&mut self, signer: &dyn SigningKey) -> anyhow::Result<()> {
+        self.signature = Some(sign_message(self, signer)?);
         Ok(())
     }
 
     /// Verify sender's signature on the message
     fn verify_signature(&self) -> anyhow::Result<()> {
-        let signature_data = self
+        let signature = self
             .signature
             .as_ref()
             .ok_or_else(|| anyhow!("missing signature"))?;
 
-        // create message binary data for signature verification
-        let mut data = self.clone();
+        let sender_pub_key = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing sender"))?
+            .get_id()?;
 
+        let mut data = self.clone();
         data.signature = None; // remove signature from message before verification
-        use ed25519_dalek::Verifier;
-        use prost::Message;
-        let mut buf = Vec::with_capacity(data.encoded_len());
-        if data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let signature = ed25519_dalek::Signature::from_bytes(signature_data.signature.as_slice())?;
-
-        let ika = self.get_ika()?;
-
-        if ika.verify(&buf, &signature).is_err() {
-            return Err(anyhow!(
-                "failed to verify signature of sender on message content"
-            ));
-        };
-
-        Ok(())
+        verify_message(&data, sender_pub_key.as_slice(), signature).map_err(|e| {
+            anyhow!(
+                "failed to verify signature of sender on message content: {}",
+                e
+            )
+        })
     }
 }
 
@@ -81,48 +62,30 @@ impl TypedMessage {
 }
 
 impl SignedWithExternalVerifier for NewSessionRequest {
-    fn sign(&mut self, signer: &ed25519_dalek::Keypair) -> anyhow::Result<()> {
-        use ed25519_dalek::Signer;
-        use prost::Message;
-        let mut buf = Vec::with_capacity(self.encoded_len());
-        self.encode(&mut buf)?;
-        let signature = signer.sign(&buf);
-        use crate::snp::snp_core_types::Signature;
-        self.sender_signature = Some(Signature {
-            scheme_id: 0,
-            signature: signature.to_bytes().to_vec(),
-        });
-
+    fn sign(&mut self, signer: &dyn SigningKey) -> anyhow::Result<()> {
+        // pow nonce is not signed so a pow can be provided for a signed request
+        let mut data = self.clone();
+        data.sender_signature = None;
+        data.pow_nonce = 0;
+        self.sender_signature = Some(sign_message(&data, signer)?);
         Ok(())
     }
 
     // verify sender's signature
-    fn verify_signature(&self, pub_key: &ed25519_dalek::PublicKey) -> anyhow::Result<()> {
-        if self.sender_signature.is_none() {
-            return Err(anyhow!("missing message signature"));
-        }
-
-        let ika_signature_data = self.sender_signature.as_ref().unwrap().clone();
-
-        // create message binary data for signature verification
-        let mut mut_req_data = self.clone();
-        mut_req_data.sender_signature = None; // remove signature from message before verification
-        use ed25519_dalek::Verifier;
-        use prost::Message;
-        let mut buf = Vec::with_capacity(mut_req_data.encoded_len());
-        if mut_req_data.encode(&mut buf).is_err() {
-            return Err(anyhow!("failed to encode source data to binary data"));
-        };
-
-        let ika_signature =
-            ed25519_dalek::Signature::from_bytes(ika_signature_data.signature.as_slice())?;
-
-        if pub_key.verify(&buf, &ika_signature).is_err() {
-            return Err(anyhow!(
-                "failed to verify signature of sender on message content"
-            ));
-        };
+    fn verify_signature(&self, pub_key: &[u8]) -> anyhow::Result<()> {
+        let signature = self
+            .sender_signature
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing message signature"))?;
 
-        Ok(())
+        let mut data = self.clone();
+        data.sender_signature = None; // remove signature from message before verification
+        data.pow_nonce = 0; // pow nonce is not signed
+        verify_message(&data, pub_key, signature).map_err(|e| {
+            anyhow!(
+                "failed to verify signature of sender on message content: {}",
+                e
+            )
+        })
     }
 }
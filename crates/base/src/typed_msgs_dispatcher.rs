@@ -9,6 +9,7 @@ use log::*;
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use xactor::*;
 
 // TypedMessageHandler is an actor message that includes an input TypedMessage
@@ -40,18 +41,20 @@ impl Message for Unsubscribe {
     type Result = Result<()>;
 }
 
-// TypeMessagesAdapter actor messages - sends a TypedMessage to the adapter
-pub struct Publish(pub TypedMessage);
+// GetSubscriber actor message - returns the subscriber of a message type
+pub struct GetSubscriber {
+    pub message_type: i32,
+}
 
-impl Message for Publish {
-    type Result = Result<TypedMessage>;
+impl Message for GetSubscriber {
+    type Result = Result<Arc<Caller<TypedMessageHandler>>>;
 }
 
 /// TypedMessagesDispatcher is a TypedMessages broker that enables only one subscriber
 /// to subscribe on a message type identified by an int enum.
 /// When a client publishes a message, he gets a response from the subscriber that can be down-casted by the client to a specific prost::Message type.
 pub struct TypedMessagesDispatcher {
-    subscribes: HashMap<i32, Caller<TypedMessageHandler>, BuildHasherDefault<FnvHasher>>,
+    subscribes: HashMap<i32, Arc<Caller<TypedMessageHandler>>, BuildHasherDefault<FnvHasher>>,
     mark: PhantomData<TypedMessage>,
 }
 
@@ -74,11 +77,28 @@ impl Actor for TypedMessagesDispatcher {
 
 impl Service for TypedMessagesDispatcher {}
 
+impl TypedMessagesDispatcher {
+    /// Publish a message to its type subscriber and return the subscriber's response.
+    /// The subscriber is called outside of the dispatcher so a subscriber waiting on a remote node which is publishing
+    /// a message to this node doesn't block it.
+    pub async fn publish(msg: TypedMessage) -> Result<TypedMessage> {
+        let subscriber = TypedMessagesDispatcher::from_registry()
+            .await?
+            .call(GetSubscriber {
+                message_type: msg.msg_type,
+            })
+            .await??;
+
+        subscriber.call(TypedMessageHandler(msg)).await?
+    }
+}
+
 #[async_trait::async_trait]
 impl Handler<Subscribe> for TypedMessagesDispatcher {
     async fn handle(&mut self, _ctx: &mut Context<Self>, msg: Subscribe) -> Result<()> {
         // todo: return error if there's already a subscriber for this message
-        self.subscribes.insert(msg.message_type, msg.subscriber);
+        self.subscribes
+            .insert(msg.message_type, Arc::new(msg.subscriber));
         debug!(
             "added subscriber to message type {}",
             MessageType::from_i32(msg.message_type).unwrap()
@@ -100,16 +120,17 @@ impl Handler<Unsubscribe> for TypedMessagesDispatcher {
     }
 }
 
-/// Handle Publish actor message - search for the subscriber for this message,
-/// Pass the message to the subscriber and return the result to the caller.
+/// Handle GetSubscriber actor message - search for the subscriber for a message type
 #[async_trait::async_trait]
-impl Handler<Publish> for TypedMessagesDispatcher {
-    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: Publish) -> Result<TypedMessage> {
-        let subscriber = self
-            .subscribes
-            .get(&(msg.0.msg_type as i32))
-            .ok_or_else(|| anyhow!("no subscriber to published message"))?;
-
-        subscriber.call(TypedMessageHandler(msg.0)).await?
+impl Handler<GetSubscriber> for TypedMessagesDispatcher {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetSubscriber,
+    ) -> Result<Arc<Caller<TypedMessageHandler>>> {
+        self.subscribes
+            .get(&msg.message_type)
+            .cloned()
+            .ok_or_else(|| anyhow!("no subscriber to published message"))
     }
 }
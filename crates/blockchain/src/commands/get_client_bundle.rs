@@ -3,12 +3,12 @@
 //
 
 use crate::service::SimpleBlockchainService;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use base::snp::snp_blockchain::{GetClientIdentityBundleRequest, GetClientIdentityBundleResponse};
 use xactor::*;
 
 impl SimpleBlockchainService {
-    /// write me
+    /// Returns the current bundle of a client by its id or by its registered nickname
     pub(crate) async fn get_client_bundle(
         request: GetClientIdentityBundleRequest,
     ) -> Result<GetClientIdentityBundleResponse> {
@@ -24,7 +24,7 @@ struct GetClientIdentityBundleMessage {
     request: GetClientIdentityBundleRequest,
 }
 
-/// write me
+/// Clients are looked up by nickname when the request's entity has no public key
 #[async_trait::async_trait]
 impl Handler<GetClientIdentityBundleMessage> for SimpleBlockchainService {
     async fn handle(
@@ -32,17 +32,31 @@ impl Handler<GetClientIdentityBundleMessage> for SimpleBlockchainService {
         _ctx: &mut Context<Self>,
         msg: GetClientIdentityBundleMessage,
     ) -> Result<GetClientIdentityBundleResponse> {
-        let id = msg
+        let entity_id = msg
             .request
             .entity_id
             .as_ref()
-            .ok_or_else(|| anyhow!("missing provider id"))?
-            .public_key
-            .as_ref()
-            .ok_or_else(|| anyhow!("missing provider public key"))?;
+            .ok_or_else(|| anyhow!("missing client id"))?;
+
+        let key = match entity_id.public_key.as_ref() {
+            Some(public_key) if !public_key.key.is_empty() => public_key.key.clone(),
+            _ if !entity_id.nickname.is_empty() => {
+                match SimpleBlockchainService::read_client_id_by_nickname(&entity_id.nickname)
+                    .await?
+                {
+                    Some(key) => key,
+                    None => {
+                        return Ok(GetClientIdentityBundleResponse {
+                            client_bundle: None,
+                        })
+                    }
+                }
+            }
+            _ => bail!("missing client public key or nickname"),
+        };
 
         Ok(GetClientIdentityBundleResponse {
-            client_bundle: SimpleBlockchainService::read_client_bundle(id.key.as_ref()).await?,
+            client_bundle: SimpleBlockchainService::read_client_bundle(&key).await?,
         })
     }
 }
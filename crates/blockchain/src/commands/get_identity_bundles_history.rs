@@ -0,0 +1,67 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::service::SimpleBlockchainService;
+use anyhow::{anyhow, Result};
+use base::snp::snp_blockchain::{
+    GetIdentityBundlesHistoryRequest, GetIdentityBundlesHistoryResponse, TransparencyLogEntryProof,
+};
+use base::transparency_log::inclusion_proof_ranges;
+use xactor::*;
+
+impl SimpleBlockchainService {
+    /// Returns an entity's identity bundles entries in the transparency log with their inclusion proofs
+    pub(crate) async fn get_identity_bundles_history(
+        request: GetIdentityBundlesHistoryRequest,
+    ) -> Result<GetIdentityBundlesHistoryResponse> {
+        SimpleBlockchainService::from_registry()
+            .await?
+            .call(GetIdentityBundlesHistoryMessage { request })
+            .await?
+    }
+}
+
+#[message(result = "Result<GetIdentityBundlesHistoryResponse>")]
+struct GetIdentityBundlesHistoryMessage {
+    request: GetIdentityBundlesHistoryRequest,
+}
+
+#[async_trait::async_trait]
+impl Handler<GetIdentityBundlesHistoryMessage> for SimpleBlockchainService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetIdentityBundlesHistoryMessage,
+    ) -> Result<GetIdentityBundlesHistoryResponse> {
+        let id = msg
+            .request
+            .entity_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing entity id"))?
+            .public_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing entity public key"))?;
+
+        let head = SimpleBlockchainService::current_log_head().await?;
+
+        let mut entries = vec![];
+        for index in SimpleBlockchainService::read_entity_log_indexes(id.key.as_ref()).await? {
+            let mut inclusion_proof = vec![];
+            for (start, size) in inclusion_proof_ranges(head.tree_size, index)? {
+                inclusion_proof
+                    .push(SimpleBlockchainService::read_log_tree_hash(start, size).await?);
+            }
+
+            entries.push(TransparencyLogEntryProof {
+                entry: Some(SimpleBlockchainService::read_transparency_log_entry(index).await?),
+                inclusion_proof,
+            });
+        }
+
+        Ok(GetIdentityBundlesHistoryResponse {
+            head: Some(head),
+            entries,
+        })
+    }
+}
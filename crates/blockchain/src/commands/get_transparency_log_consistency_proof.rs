@@ -0,0 +1,62 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::service::SimpleBlockchainService;
+use anyhow::{bail, Result};
+use base::snp::snp_blockchain::{
+    GetTransparencyLogConsistencyProofRequest, GetTransparencyLogConsistencyProofResponse,
+};
+use base::transparency_log::consistency_proof_ranges;
+use xactor::*;
+
+impl SimpleBlockchainService {
+    /// Returns a proof that an older tree head of the transparency log is a prefix of a newer one
+    pub(crate) async fn get_transparency_log_consistency_proof(
+        request: GetTransparencyLogConsistencyProofRequest,
+    ) -> Result<GetTransparencyLogConsistencyProofResponse> {
+        SimpleBlockchainService::from_registry()
+            .await?
+            .call(GetTransparencyLogConsistencyProofMessage { request })
+            .await?
+    }
+}
+
+#[message(result = "Result<GetTransparencyLogConsistencyProofResponse>")]
+struct GetTransparencyLogConsistencyProofMessage {
+    request: GetTransparencyLogConsistencyProofRequest,
+}
+
+#[async_trait::async_trait]
+impl Handler<GetTransparencyLogConsistencyProofMessage> for SimpleBlockchainService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetTransparencyLogConsistencyProofMessage,
+    ) -> Result<GetTransparencyLogConsistencyProofResponse> {
+        let first_size = msg.request.first_tree_size;
+        let second_size = msg.request.second_tree_size;
+
+        let head = SimpleBlockchainService::current_log_head().await?;
+        if second_size > head.tree_size {
+            bail!("log has only {} entries", head.tree_size)
+        }
+
+        let mut proof = vec![];
+        for (start, size) in consistency_proof_ranges(second_size, first_size)? {
+            proof.push(SimpleBlockchainService::read_log_tree_hash(start, size).await?);
+        }
+
+        let second_head = if second_size == head.tree_size {
+            head
+        } else {
+            SimpleBlockchainService::signed_log_head(second_size).await?
+        };
+
+        Ok(GetTransparencyLogConsistencyProofResponse {
+            first_head: Some(SimpleBlockchainService::signed_log_head(first_size).await?),
+            second_head: Some(second_head),
+            proof,
+        })
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::service::SimpleBlockchainService;
+use anyhow::Result;
+use base::snp::snp_blockchain::{GetTransparencyLogHeadRequest, GetTransparencyLogHeadResponse};
+use xactor::*;
+
+impl SimpleBlockchainService {
+    /// Returns the current signed tree head of the identity bundles transparency log
+    pub(crate) async fn get_transparency_log_head(
+        request: GetTransparencyLogHeadRequest,
+    ) -> Result<GetTransparencyLogHeadResponse> {
+        SimpleBlockchainService::from_registry()
+            .await?
+            .call(GetTransparencyLogHeadMessage { _request: request })
+            .await?
+    }
+}
+
+#[message(result = "Result<GetTransparencyLogHeadResponse>")]
+struct GetTransparencyLogHeadMessage {
+    _request: GetTransparencyLogHeadRequest,
+}
+
+#[async_trait::async_trait]
+impl Handler<GetTransparencyLogHeadMessage> for SimpleBlockchainService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: GetTransparencyLogHeadMessage,
+    ) -> Result<GetTransparencyLogHeadResponse> {
+        Ok(GetTransparencyLogHeadResponse {
+            head: Some(SimpleBlockchainService::current_log_head().await?),
+        })
+    }
+}
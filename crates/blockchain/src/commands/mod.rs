@@ -6,8 +6,11 @@ pub(crate) mod get_account;
 pub(crate) mod get_block;
 pub(crate) mod get_client_bundle;
 pub(crate) mod get_clients;
+pub(crate) mod get_identity_bundles_history;
 pub(crate) mod get_provider_bundle;
 pub(crate) mod get_providers;
 pub(crate) mod get_transaction;
+pub(crate) mod get_transparency_log_consistency_proof;
+pub(crate) mod get_transparency_log_head;
 pub(crate) mod set_balance;
 pub(crate) mod submit_tx;
@@ -4,7 +4,7 @@
 
 use crate::service::SimpleBlockchainService;
 use anyhow::Result;
-use base::api_types_extensions::Signed;
+use base::api_types_extensions::{NetworkScoped, Signed};
 use base::hex_utils::hex_string;
 use base::snp::snp_blockchain::transaction::Data::{
     ClientBundle, PaymentTransaction, ProviderBundle,
@@ -14,6 +14,7 @@ use base::snp::snp_blockchain::{
     TransactionState, TransactionType,
 };
 use base::snp::snp_payments::{Amount, TransactionId};
+use common::network_salt::net_id;
 use xactor::*;
 
 impl SimpleBlockchainService {
@@ -65,6 +66,11 @@ impl Handler<SubmitTransactionMessage> for SimpleBlockchainService {
 
         let tx = req.transaction.as_ref().unwrap();
 
+        if let Err(e) = tx.verify_net_id(net_id()) {
+            info!("rejecting tx: {}", e);
+            return Err(TransactionState::RejectedWrongNetwork);
+        }
+
         if tx.validate_fee().is_err() {
             return Err(TransactionState::RejectedInvalidData);
         }
@@ -239,6 +245,12 @@ impl Handler<SubmitTransactionMessage> for SimpleBlockchainService {
             return Err(TransactionState::RejectedInternalError);
         }
 
+        // identity bundles are appended to the bundles transparency log
+        if let Err(e) = SimpleBlockchainService::log_identity_bundle(data, block_id).await {
+            error!("failed to append bundle to transparency log: {:?}", e);
+            return Err(TransactionState::RejectedInternalError);
+        }
+
         if SimpleBlockchainService::write_current_block_id(block_id)
             .await
             .is_err()
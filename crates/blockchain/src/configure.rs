@@ -3,13 +3,15 @@
 //
 
 use crate::consts::{
-    ACCOUNTS_CF, BLOCKCHAIN_CF, BLOCKS_CF, CLIENTS_BUNDLES_CF, PROVIDERS_BUNDLES_CF,
-    SEALER_BLOCKS_CF, SYSTEM_COL_FAMILY, TRANSACTIONS_CF, VALIDATOR_BLOCKS_CF,
+    ACCOUNTS_CF, BLOCKCHAIN_CF, BLOCKS_CF, CLIENTS_BUNDLES_CF, CLIENTS_NICKNAMES_CF,
+    ENTITIES_LOG_ENTRIES_CF, PROVIDERS_BUNDLES_CF, SEALER_BLOCKS_CF, SYSTEM_COL_FAMILY,
+    TRANSACTIONS_CF, TRANSPARENCY_LOG_CF, TRANSPARENCY_LOG_TREE_CF, VALIDATOR_BLOCKS_CF,
 };
 use crate::service::SimpleBlockchainService;
 use anyhow::Result;
-use base::blockchain_config_service::BlockchainConfigService;
+use base::blockchain_config_service::{BlockchainConfigService, NET_ID_CONFIG_KEY};
 use base::server_config_service::{DB_NAME_CONFIG_KEY, DROP_DB_CONFIG_KEY};
+use common::network_salt;
 use db::db_service::DatabaseService;
 use rocksdb::{ColumnFamilyDescriptor, Options};
 use xactor::*;
@@ -29,10 +31,16 @@ impl Handler<Configure> for SimpleBlockchainService {
             .await?
             .unwrap();
 
+        let net_id = BlockchainConfigService::get_u64(NET_ID_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default() as u32;
+        network_salt::set_net_id(net_id);
+
         // todo: merge any config params into the config
 
         info!("db name: {}", db_name);
         info!("drop db on exit: {}", drop_on_exit);
+        info!("network id: {}", net_id);
 
         // configure the database service
         DatabaseService::config_db(db::db_service::Configure {
@@ -47,11 +55,18 @@ impl Handler<Configure> for SimpleBlockchainService {
                 ColumnFamilyDescriptor::new(ACCOUNTS_CF, Options::default()),
                 ColumnFamilyDescriptor::new(PROVIDERS_BUNDLES_CF, Options::default()),
                 ColumnFamilyDescriptor::new(CLIENTS_BUNDLES_CF, Options::default()),
+                ColumnFamilyDescriptor::new(CLIENTS_NICKNAMES_CF, Options::default()),
+                ColumnFamilyDescriptor::new(TRANSPARENCY_LOG_CF, Options::default()),
+                ColumnFamilyDescriptor::new(TRANSPARENCY_LOG_TREE_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ENTITIES_LOG_ENTRIES_CF, Options::default()),
                 ColumnFamilyDescriptor::new(SYSTEM_COL_FAMILY, Options::default()),
             ],
         })
         .await?;
 
+        // logs stored before their tree was persisted are indexed once
+        SimpleBlockchainService::index_transparency_log().await?;
+
         info!("config done");
         Ok(())
     }
@@ -12,6 +12,12 @@ pub(crate) const BLOCKCHAIN_CF: &str = "blockchain";
 
 pub(crate) const CURRENT_BLOCK_KEY: &str = "curr_block";
 
+pub(crate) const TRANSPARENCY_LOG_SIZE_KEY: &str = "transparency_log_size";
+
+pub(crate) const TRANSPARENCY_LOG_HEAD_KEY: &str = "transparency_log_head";
+
+pub(crate) const TRANSPARENCY_LOG_SIGNING_KEY: &str = "transparency_log_signing_key";
+
 // stores txs (tx_id -> TransactionInfo)
 pub(crate) const TRANSACTIONS_CF: &str = "txs";
 
@@ -30,5 +36,17 @@ pub(crate) const PROVIDERS_BUNDLES_CF: &str = "providers_bundles";
 // providers bundles (user_id -> bundle)
 pub(crate) const CLIENTS_BUNDLES_CF: &str = "users_bundles";
 
+// registered clients nicknames (nickname -> user_id)
+pub(crate) const CLIENTS_NICKNAMES_CF: &str = "users_nicknames";
+
+// identity bundles transparency log (entry_index -> TransparencyLogEntry)
+pub(crate) const TRANSPARENCY_LOG_CF: &str = "transparency_log";
+
+// identity bundles transparency log tree ((level, index) -> complete subtree root hash)
+pub(crate) const TRANSPARENCY_LOG_TREE_CF: &str = "transparency_log_tree";
+
+// entities log entries (entity_id -> entries indexes)
+pub(crate) const ENTITIES_LOG_ENTRIES_CF: &str = "transparency_log_by_entity";
+
 // system settings
 pub(crate) const SYSTEM_COL_FAMILY: &str = "system";
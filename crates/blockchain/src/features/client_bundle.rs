@@ -2,7 +2,7 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::consts::CLIENTS_BUNDLES_CF;
+use crate::consts::{CLIENTS_BUNDLES_CF, CLIENTS_NICKNAMES_CF};
 use crate::service::SimpleBlockchainService;
 use anyhow::{anyhow, bail, Result};
 use base::api_types_extensions::Signed;
@@ -53,18 +53,41 @@ impl SimpleBlockchainService {
         }
 
         let provider_payment_address = provider_bundle.address.as_ref().unwrap();
+        let client_payment_address = client_bundle.address.as_ref().unwrap();
+        let sender_address = &sender_account.address.as_ref().unwrap().data;
 
-        // check that tx sender payment account is the provided published coin account
-        // only providers can publish service bundles for their clients
-        // Providers must publish their client bundles to the blockchain for clients to be able to receive messages and providers earn inome
+        // check that tx sender payment account is the provider or the client published coin account.
+        // Providers must publish their client bundles to the blockchain for clients to be able to receive messages and providers earn inome.
+        // Clients publish their provider signed bundle when they move to a new provider.
 
-        if provider_payment_address.data != sender_account.address.as_ref().unwrap().data {
+        if provider_payment_address.data != *sender_address
+            && client_payment_address.data != *sender_address
+        {
             return Err(TransactionState::RejectedInvalidData);
         }
 
-        // todo: check that if there's an already client bundle stored in global state.
-        // the time-stamp of the new one is greater than the one before so only newer updates are accepted
-        // also check that very old bundles can't be published. e.g. created more than few months ago
+        // an older bundle may not replace a client bundle stored in global state
+        match SimpleBlockchainService::read_client_bundle(
+            client_bundle
+                .get_client_id_public_key()
+                .unwrap()
+                .key
+                .as_ref(),
+        )
+        .await
+        {
+            Ok(Some(curr_bundle)) => {
+                if let Some(curr_client_bundle) = curr_bundle.client_bundle.as_ref() {
+                    if curr_client_bundle.time_stamp > client_bundle.time_stamp {
+                        return Err(TransactionState::RejectedInvalidData);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(_) => return Err(TransactionState::RejectedInternalError),
+        }
+
+        // todo: check that very old bundles can't be published. e.g. created more than few months ago
 
         if SimpleBlockchainService::store_client_bundle(bundle)
             .await
@@ -134,6 +157,46 @@ impl SimpleBlockchainService {
             )
         })?;
 
+        let nickname = &client_bundle.client_id.as_ref().unwrap().nickname;
+        if !nickname.is_empty() {
+            SimpleBlockchainService::register_client_nickname(nickname, &client_pub_key.key)
+                .await?;
+        }
+
         Ok(())
     }
+
+    /// Returns the id of the client which registered a nickname
+    pub(crate) async fn read_client_id_by_nickname(nickname: &str) -> Result<Option<Vec<u8>>> {
+        Ok(DatabaseService::read(ReadItem {
+            key: Bytes::from(nickname.as_bytes().to_vec()),
+            cf: CLIENTS_NICKNAMES_CF,
+        })
+        .await?
+        .map(|data| data.0.to_vec()))
+    }
+
+    /// Register a client's nickname. A nickname belongs to the first client which published a bundle with it
+    async fn register_client_nickname(nickname: &str, client_id: &[u8]) -> Result<()> {
+        if let Some(owner) = SimpleBlockchainService::read_client_id_by_nickname(nickname).await? {
+            if owner != client_id {
+                info!(
+                    "nickname {} is already registered by another client",
+                    nickname
+                );
+            }
+            return Ok(());
+        }
+
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: Bytes::from(nickname.as_bytes().to_vec()),
+                value: Bytes::from(client_id.to_vec()),
+            },
+            cf: CLIENTS_NICKNAMES_CF,
+            ttl: 0,
+        })
+        .await
+        .map_err(|e| anyhow!("internal server error - failed to register nickname: {}", e))
+    }
 }
@@ -9,8 +9,11 @@ use base::snp::snp_blockchain::{
     GetAccountRequest, GetAccountResponse, GetBlockRequest, GetBlockResponse,
     GetBlocksCountByEntityRequest, GetBlocksCountByEntityResponse, GetClientIdentityBundleRequest,
     GetClientIdentityBundleResponse, GetClientsRequest, GetClientsResponse, GetCurrentBlockRequest,
+    GetIdentityBundlesHistoryRequest, GetIdentityBundlesHistoryResponse,
     GetProviderIdentityBundleRequest, GetProviderIdentityBundleResponse, GetProvidersRequest,
-    GetProvidersResponse, GetTransactionRequest, GetTransactionResponse, SetBalanceRequest,
+    GetProvidersResponse, GetTransactionRequest, GetTransactionResponse,
+    GetTransparencyLogConsistencyProofRequest, GetTransparencyLogConsistencyProofResponse,
+    GetTransparencyLogHeadRequest, GetTransparencyLogHeadResponse, SetBalanceRequest,
     SetBalanceResponse, SubmitTransactionRequest, SubmitTransactionResponse,
 };
 use tonic::{Request, Response, Status};
@@ -141,6 +144,56 @@ impl BlockchainService for BlockchainServerGrpc {
         }
     }
 
+    async fn get_transparency_log_head(
+        &self,
+        request: Request<GetTransparencyLogHeadRequest>,
+    ) -> Result<Response<GetTransparencyLogHeadResponse>, Status> {
+        match SimpleBlockchainService::get_transparency_log_head(request.into_inner()).await {
+            Ok(result) => Ok(Response::new(result)),
+            Err(e) => {
+                error!("get transparency log head error: {:?}", e);
+                Err(Status::internal(format!(
+                    "get transparency log head error: {:?}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn get_transparency_log_consistency_proof(
+        &self,
+        request: Request<GetTransparencyLogConsistencyProofRequest>,
+    ) -> Result<Response<GetTransparencyLogConsistencyProofResponse>, Status> {
+        match SimpleBlockchainService::get_transparency_log_consistency_proof(request.into_inner())
+            .await
+        {
+            Ok(result) => Ok(Response::new(result)),
+            Err(e) => {
+                error!("get consistency proof error: {:?}", e);
+                Err(Status::internal(format!(
+                    "get consistency proof error: {:?}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn get_identity_bundles_history(
+        &self,
+        request: Request<GetIdentityBundlesHistoryRequest>,
+    ) -> Result<Response<GetIdentityBundlesHistoryResponse>, Status> {
+        match SimpleBlockchainService::get_identity_bundles_history(request.into_inner()).await {
+            Ok(result) => Ok(Response::new(result)),
+            Err(e) => {
+                error!("get bundles history error: {:?}", e);
+                Err(Status::internal(format!(
+                    "get bundles history error: {:?}",
+                    e
+                )))
+            }
+        }
+    }
+
     async fn get_block(
         &self,
         request: Request<GetBlockRequest>,
@@ -9,3 +9,4 @@ pub(crate) mod grpc_service;
 mod payment_tx;
 mod provider_bundle;
 mod transactions;
+pub(crate) mod transparency_log;
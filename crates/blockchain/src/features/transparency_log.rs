@@ -0,0 +1,356 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::consts::{
+    BLOCKCHAIN_CF, ENTITIES_LOG_ENTRIES_CF, TRANSPARENCY_LOG_CF, TRANSPARENCY_LOG_HEAD_KEY,
+    TRANSPARENCY_LOG_SIGNING_KEY, TRANSPARENCY_LOG_SIZE_KEY, TRANSPARENCY_LOG_TREE_CF,
+};
+use crate::service::SimpleBlockchainService;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::Signed;
+use base::snp::snp_blockchain::transaction::Data;
+use base::snp::snp_blockchain::{TransactionType, TransparencyLogEntry, TransparencyLogHead};
+use base::transparency_log::{bundle_hash, node_hash, subtrees, subtrees_root_hash};
+use byteorder::{ByteOrder, LittleEndian};
+use bytes::Bytes;
+use crypto::utils::{create_key_pair, entity_from_ed25519_pub_key};
+use db::db_service::{DataItem, DatabaseService, ReadItem, WriteItem};
+use ed25519_dalek::Keypair;
+use prost::Message;
+use std::ops::Deref;
+
+/// The identity bundles transparency log is an append-only log of every identity bundle published on the blockchain.
+/// Entities audit their bundles history in the log to detect bundles they didn't publish.
+///
+/// The root hashes of the log tree's complete subtrees are stored as entries are appended, so tree heads and proofs
+/// are computed from O(log n) stored hashes. The log signs each new tree head with its signing key.
+///
+/// An entity's history is not verifiably complete - the log may omit entries from it, e.g. a bundle it swapped.
+/// Auditors which scan all the log's entries find omitted entries, and the signed tree heads which include them
+/// prove the omission.
+impl SimpleBlockchainService {
+    /// Append a bundle published by a bundle transaction to the transparency log.
+    /// Other transactions are ignored
+    pub(crate) async fn log_identity_bundle(data: &Data, block_id: u64) -> Result<()> {
+        let (entity_id, transaction_type, bundle_hash, bundle_time_stamp) = match data {
+            Data::ProviderBundle(tx_data) => {
+                let bundle = tx_data
+                    .provider_bundle
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing provider bundle"))?;
+                (
+                    bundle.provider_id.clone(),
+                    TransactionType::SetProviderBundle,
+                    bundle_hash(bundle),
+                    bundle.time_stamp,
+                )
+            }
+            Data::ClientBundle(tx_data) => {
+                // log the bundle signed by the client and not the provider signed bundle
+                let bundle = tx_data
+                    .client_bundle
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing client bundle"))?
+                    .client_bundle
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing client bundle"))?;
+                (
+                    bundle.client_id.clone(),
+                    TransactionType::SetClientBundle,
+                    bundle_hash(bundle),
+                    bundle.time_stamp,
+                )
+            }
+            Data::PaymentTransaction(_) => return Ok(()),
+        };
+
+        let entity_id = entity_id.ok_or_else(|| anyhow!("missing entity id"))?;
+        let entity_key = entity_id
+            .public_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing public key"))?
+            .key
+            .clone();
+
+        let index = SimpleBlockchainService::read_transparency_log_size().await?;
+        let entry = TransparencyLogEntry {
+            index,
+            entity_id: Some(entity_id),
+            transaction_type: transaction_type as i32,
+            bundle_hash,
+            bundle_time_stamp,
+            block_id,
+        };
+
+        SimpleBlockchainService::store_transparency_log_entry(&entry).await?;
+        SimpleBlockchainService::append_log_tree_leaf(index, entry.leaf_hash()).await?;
+
+        let mut indexes = SimpleBlockchainService::read_entity_log_indexes(&entity_key).await?;
+        indexes.push(index);
+        SimpleBlockchainService::store_entity_log_indexes(&entity_key, &indexes).await?;
+
+        let mut buf = [0; 8];
+        LittleEndian::write_u64(&mut buf, index + 1);
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: Bytes::from(TRANSPARENCY_LOG_SIZE_KEY.as_bytes()),
+                value: Bytes::from(buf.to_vec()),
+            },
+            cf: BLOCKCHAIN_CF,
+            ttl: 0,
+        })
+        .await
+        .map_err(|e| anyhow!("internal server error - failed to store log size: {}", e))?;
+
+        let head = SimpleBlockchainService::signed_log_head(index + 1).await?;
+        SimpleBlockchainService::store_transparency_log_head(&head).await?;
+
+        info!("appended identity bundle to transparency log at {}", index);
+        Ok(())
+    }
+
+    /// Store the tree of log entries which were appended before the tree was persisted, including entries
+    /// appended before the log stored its tree and signed its heads
+    pub(crate) async fn index_transparency_log() -> Result<()> {
+        let size = SimpleBlockchainService::read_transparency_log_size().await?;
+        let indexed_size = SimpleBlockchainService::read_transparency_log_head()
+            .await?
+            .map(|head| head.tree_size)
+            .unwrap_or_default();
+
+        if indexed_size >= size {
+            return Ok(());
+        }
+
+        for index in indexed_size..size {
+            let entry = SimpleBlockchainService::read_transparency_log_entry(index).await?;
+            SimpleBlockchainService::append_log_tree_leaf(index, entry.leaf_hash()).await?;
+        }
+
+        let head = SimpleBlockchainService::signed_log_head(size).await?;
+        SimpleBlockchainService::store_transparency_log_head(&head).await?;
+
+        info!("indexed {} transparency log entries", size - indexed_size);
+        Ok(())
+    }
+
+    /// Returns the current signed tree head of the log
+    pub(crate) async fn current_log_head() -> Result<TransparencyLogHead> {
+        match SimpleBlockchainService::read_transparency_log_head().await? {
+            Some(head) => Ok(head),
+            None => SimpleBlockchainService::signed_log_head(0).await,
+        }
+    }
+
+    /// Returns the signed tree head of the log's tree with the first tree_size entries
+    pub(crate) async fn signed_log_head(tree_size: u64) -> Result<TransparencyLogHead> {
+        let signing_key = SimpleBlockchainService::read_log_signing_key().await?;
+        let mut head = TransparencyLogHead {
+            tree_size,
+            root_hash: SimpleBlockchainService::read_log_tree_hash(0, tree_size).await?,
+            log_id: Some(entity_from_ed25519_pub_key(&signing_key.public, "".into())),
+            signature: None,
+        };
+        head.sign(&signing_key)?;
+        Ok(head)
+    }
+
+    /// Returns the root hash of the size log entries starting at entry start.
+    /// Ranges of log proofs and heads are made of complete subtrees of the log's tree
+    pub(crate) async fn read_log_tree_hash(start: u64, size: u64) -> Result<Vec<u8>> {
+        let mut hashes = vec![];
+        for (level, index) in subtrees(start, size) {
+            hashes.push(SimpleBlockchainService::read_log_tree_node(level, index).await?);
+        }
+        Ok(subtrees_root_hash(&hashes))
+    }
+
+    /// Store a new leaf of the log's tree and the root hashes of the complete subtrees it completes
+    async fn append_log_tree_leaf(index: u64, leaf_hash: Vec<u8>) -> Result<()> {
+        let (mut level, mut index, mut hash) = (0, index, leaf_hash);
+        SimpleBlockchainService::store_log_tree_node(level, index, &hash).await?;
+
+        // a right child completes its parent subtree
+        while index & 1 == 1 {
+            let left = SimpleBlockchainService::read_log_tree_node(level, index - 1).await?;
+            hash = node_hash(&left, &hash);
+            level += 1;
+            index >>= 1;
+            SimpleBlockchainService::store_log_tree_node(level, index, &hash).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn read_log_tree_node(level: u32, index: u64) -> Result<Vec<u8>> {
+        DatabaseService::read(ReadItem {
+            key: log_tree_node_key(level, index),
+            cf: TRANSPARENCY_LOG_TREE_CF,
+        })
+        .await?
+        .map(|data| data.0.to_vec())
+        .ok_or_else(|| anyhow!("missing transparency log tree node {}/{}", level, index))
+    }
+
+    async fn store_log_tree_node(level: u32, index: u64, hash: &[u8]) -> Result<()> {
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: log_tree_node_key(level, index),
+                value: Bytes::from(hash.to_vec()),
+            },
+            cf: TRANSPARENCY_LOG_TREE_CF,
+            ttl: 0,
+        })
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "internal server error - failed to store log tree node: {}",
+                e
+            )
+        })
+    }
+
+    async fn read_transparency_log_head() -> Result<Option<TransparencyLogHead>> {
+        match DatabaseService::read(ReadItem {
+            key: Bytes::from(TRANSPARENCY_LOG_HEAD_KEY.as_bytes()),
+            cf: BLOCKCHAIN_CF,
+        })
+        .await?
+        {
+            Some(data) => Ok(Some(TransparencyLogHead::decode(data.0.as_ref())?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn store_transparency_log_head(head: &TransparencyLogHead) -> Result<()> {
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: Bytes::from(TRANSPARENCY_LOG_HEAD_KEY.as_bytes()),
+                value: Bytes::from(head.encode_to_vec()),
+            },
+            cf: BLOCKCHAIN_CF,
+            ttl: 0,
+        })
+        .await
+        .map_err(|e| anyhow!("internal server error - failed to store log head: {}", e))
+    }
+
+    /// Returns the key the log signs its tree heads with. A new key is created on first use
+    async fn read_log_signing_key() -> Result<Keypair> {
+        if let Some(data) = DatabaseService::read(ReadItem {
+            key: Bytes::from(TRANSPARENCY_LOG_SIGNING_KEY.as_bytes()),
+            cf: BLOCKCHAIN_CF,
+        })
+        .await?
+        {
+            return Ok(Keypair::from_bytes(data.0.as_ref())?);
+        }
+
+        let signing_key = create_key_pair();
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: Bytes::from(TRANSPARENCY_LOG_SIGNING_KEY.as_bytes()),
+                value: Bytes::from(signing_key.to_bytes().to_vec()),
+            },
+            cf: BLOCKCHAIN_CF,
+            ttl: 0,
+        })
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "internal server error - failed to store log signing key: {}",
+                e
+            )
+        })?;
+
+        Ok(signing_key)
+    }
+
+    /// Returns the number of entries in the transparency log
+    pub(crate) async fn read_transparency_log_size() -> Result<u64> {
+        if let Some(data) = DatabaseService::read(ReadItem {
+            key: Bytes::from(TRANSPARENCY_LOG_SIZE_KEY.as_bytes()),
+            cf: BLOCKCHAIN_CF,
+        })
+        .await?
+        {
+            Ok(LittleEndian::read_u64(data.0.deref()))
+        } else {
+            Ok(0)
+        }
+    }
+
+    pub(crate) async fn read_transparency_log_entry(index: u64) -> Result<TransparencyLogEntry> {
+        let mut buf = [0; 8];
+        LittleEndian::write_u64(&mut buf, index);
+        if let Some(data) = DatabaseService::read(ReadItem {
+            key: Bytes::from(buf.to_vec()),
+            cf: TRANSPARENCY_LOG_CF,
+        })
+        .await?
+        {
+            Ok(TransparencyLogEntry::decode(data.0.as_ref())?)
+        } else {
+            bail!("missing transparency log entry {}", index)
+        }
+    }
+
+    async fn store_transparency_log_entry(entry: &TransparencyLogEntry) -> Result<()> {
+        let mut index = [0; 8];
+        LittleEndian::write_u64(&mut index, entry.index);
+
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: Bytes::from(index.to_vec()),
+                value: Bytes::from(entry.encode_to_vec()),
+            },
+            cf: TRANSPARENCY_LOG_CF,
+            ttl: 0,
+        })
+        .await
+        .map_err(|e| anyhow!("internal server error - failed to store log entry: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Returns the indexes of an entity's entries in the log, oldest first
+    pub(crate) async fn read_entity_log_indexes(entity_key: &[u8]) -> Result<Vec<u64>> {
+        if let Some(data) = DatabaseService::read(ReadItem {
+            key: Bytes::from(entity_key.to_vec()),
+            cf: ENTITIES_LOG_ENTRIES_CF,
+        })
+        .await?
+        {
+            Ok(data.0.chunks(8).map(LittleEndian::read_u64).collect())
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    async fn store_entity_log_indexes(entity_key: &[u8], indexes: &[u64]) -> Result<()> {
+        let mut data = vec![0; indexes.len() * 8];
+        LittleEndian::write_u64_into(indexes, &mut data);
+
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: Bytes::from(entity_key.to_vec()),
+                value: Bytes::from(data),
+            },
+            cf: ENTITIES_LOG_ENTRIES_CF,
+            ttl: 0,
+        })
+        .await
+        .map_err(|e| anyhow!("internal server error - failed to store log indexes: {}", e))?;
+
+        Ok(())
+    }
+}
+
+/// Returns the db key of a complete subtree of the log's tree
+fn log_tree_node_key(level: u32, index: u64) -> Bytes {
+    let mut key = [0; 12];
+    LittleEndian::write_u32(&mut key[..4], level);
+    LittleEndian::write_u64(&mut key[4..], index);
+    Bytes::from(key.to_vec())
+}
@@ -5,6 +5,10 @@
 use crate::features::grpc_service::BlockchainServerGrpc;
 use crate::service::SimpleBlockchainService;
 use anyhow::Result;
+use base::blockchain_config_service::{
+    BlockchainConfigService, TLS_CERT_FILE_CONFIG_KEY, TLS_KEY_FILE_CONFIG_KEY,
+};
+use base::grpc_tls::server_tls_config;
 use base::snp::snp_blockchain::blockchain_service_server::BlockchainServiceServer;
 use tonic::transport::Server;
 use xactor::*;
@@ -45,8 +49,16 @@ impl Handler<StartGrpcServer> for SimpleBlockchainService {
             //.allow_origins(vec!["127.0.0.1"]) // e.g. can limit to a reverse proxy that adds ssl!
             .enable(service);
 
+        let mut builder = Server::builder();
+        let cert_file = BlockchainConfigService::get(TLS_CERT_FILE_CONFIG_KEY.into()).await?;
+        let key_file = BlockchainConfigService::get(TLS_KEY_FILE_CONFIG_KEY.into()).await?;
+        if let Some((cert_file, key_file)) = cert_file.zip(key_file) {
+            info!("grpc server using tls");
+            builder = builder.tls_config(server_tls_config(&cert_file, &key_file).await?)?;
+        }
+
         tokio::task::spawn(async move {
-            let res = Server::builder()
+            let res = builder
                 .accept_http1(true)
                 .add_service(service)
                 .add_service(health_service)
@@ -60,7 +60,7 @@ async fn clients_bundles() {
     let provider_bundle_data = PrivateProviderIdentityBundle::new_for_id(
         &provider_key_pair,
         &pre_key_private,
-        &DialupInfo::new(),
+        &[DialupInfo::new()],
         "provider 1".to_string(),
         &provider_payment_address,
         0,
@@ -139,6 +139,8 @@ async fn clients_bundles() {
         signature: vec![],
         data: Some(Data::ClientBundle(bundle_tx_data)),
         fee_signature: vec![], // sender pays fee
+        signature_scheme_id: 0,
+        fee_signature_scheme_id: 0,
     };
 
     tx.sign(&provider_key_pair).unwrap();
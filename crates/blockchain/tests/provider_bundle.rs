@@ -57,7 +57,7 @@ async fn providers_bundles() {
     let bundle = PrivateProviderIdentityBundle::new_for_id(
         &key_pair,
         &pre_key_private,
-        &dialup_info,
+        &[dialup_info],
         "provider1".to_string(),
         &payment_address,
         0,
@@ -97,6 +97,8 @@ async fn providers_bundles() {
         signature: vec![],
         data: Some(Data::ProviderBundle(bundle_data)),
         fee_signature: vec![], // sender pays fee
+        signature_scheme_id: 0,
+        fee_signature_scheme_id: 0,
     };
 
     tx.sign(&key_pair).unwrap();
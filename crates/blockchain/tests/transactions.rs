@@ -116,6 +116,8 @@ async fn coin_transactions() {
         signature: vec![],
         data: Some(Data::PaymentTransaction(payment_data)),
         fee_signature: vec![], // sender pays fee
+        signature_scheme_id: 0,
+        fee_signature_scheme_id: 0,
     };
 
     tx.sign(&keypair1).unwrap();
@@ -171,6 +173,43 @@ async fn coin_transactions() {
     assert_eq!(balance1, amount1 - tx_amount - tx_fee_amount);
     assert_eq!(balance2, amount2 + tx_amount);
 
+    // a tx from another network is rejected
+    let mut tx = Transaction {
+        sender_pub_key: keypair1.public.to_bytes().to_vec(),
+        fee: Some(TransactionFee {
+            amount: Some(Amount {
+                value: tx_fee_amount,
+                coin_type: CoinType::Core as i32,
+            }),
+            payer_public_key: vec![],
+        }),
+        counter: 2,
+        entity_id: None,
+        net_id: 1,
+        signature: vec![],
+        data: Some(Data::PaymentTransaction(PaymentTransactionData {
+            receiver: Some(address2.clone()),
+            coins: Some(Amount {
+                value: tx_amount,
+                coin_type: CoinType::Core as i32,
+            }),
+            id: 0,
+        })),
+        fee_signature: vec![],
+        signature_scheme_id: 0,
+        fee_signature_scheme_id: 0,
+    };
+
+    tx.sign(&keypair1).unwrap();
+
+    let status = client
+        .submit_transaction(SubmitTransactionRequest {
+            transaction: Some(tx),
+        })
+        .await
+        .expect_err("expected tx from another network to be rejected");
+    assert!(status.message().contains("RejectedWrongNetwork"));
+
     test_teardown().await.unwrap();
 }
 
@@ -3,7 +3,7 @@
 //
 
 use crate::simple_client::SimpleClient;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use crypto::utils::entity_from_ed25519_pub_key;
 use xactor::*;
 
@@ -12,6 +12,7 @@ use base::api_types_extensions::Signed;
 use base::snp::snp_core_types::{
     ChannelBundle, ChannelData, ChannelType, GroupMemberBundle, GroupMembersBundle, PricingModel,
 };
+use base::snp::snp_payments::{Amount, CoinType};
 use chrono::prelude::*;
 
 #[message(result = "Result<ChannelBundle>")]
@@ -19,6 +20,8 @@ pub(crate) struct CreateNewChannel {
     pub(crate) name: String,
     pub(crate) channel_type: ChannelType, // status updates, group, etc...
     pub(crate) description: String,
+    pub(crate) subscription_fee: u64, // monthly fee of a paid status updates channel. 0 for a free channel
+    pub(crate) approve_members: bool, // group join requests are pending until a group admin approves them
 }
 
 /// Create a new status update channel or a group by this client and returns its bundle
@@ -30,6 +33,25 @@ impl Handler<CreateNewChannel> for SimpleClient {
         _ctx: &mut Context<Self>,
         msg: CreateNewChannel,
     ) -> Result<ChannelBundle> {
+        if msg.approve_members && msg.channel_type != ChannelType::Group {
+            bail!("only groups can require approving their members")
+        }
+
+        let (pricing_model, payable_address, subscription_fee) = match msg.subscription_fee {
+            0 => (PricingModel::Free, None, None),
+            _ if msg.channel_type != ChannelType::StatusFeed => {
+                bail!("only status update channels can have a subscription fee")
+            }
+            fee => (
+                PricingModel::MonthlyFee,
+                Some(self.get_payment_address()?),
+                Some(Amount {
+                    value: fee,
+                    coin_type: CoinType::Core as i32,
+                }),
+            ),
+        };
+
         let channels_service = ChannelsService::from_registry().await?;
         let channel_id_key_pair = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
         let channel_id = entity_from_ed25519_pub_key(&channel_id_key_pair.public, msg.name);
@@ -45,11 +67,11 @@ impl Handler<CreateNewChannel> for SimpleClient {
             acceptable_content_policy:
                 "Be nice, thoughtful, and kind when replying or I'll kick you out".to_string(),
             logo: None,
-            payable_address: None,
-            subscription_fee: None,
+            payable_address,
+            subscription_fee,
             signature: None,
             creator_signature: None,
-            pricing_model: PricingModel::Free as i32,
+            pricing_model: pricing_model as i32,
         };
 
         bundle
@@ -66,6 +88,8 @@ impl Handler<CreateNewChannel> for SimpleClient {
             subscribers: vec![],
             group_members: None,
             channel_key_pair: channel_id_key_pair.to_bytes().to_vec(),
+            banned_members: vec![],
+            approve_members: msg.approve_members,
         };
 
         if msg.channel_type == ChannelType::Group {
@@ -84,6 +108,8 @@ impl Handler<CreateNewChannel> for SimpleClient {
                 members: vec![membership],
                 group_signature: None,
                 creator_signature: None,
+                admins: vec![],
+                last_change: None,
             };
 
             members_bundle.sign(&self.client_id, &channel_id_key_pair)?;
@@ -0,0 +1,131 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::channels::channels_data_service::{ChannelsService, GetChannel};
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::SignedWithExternalVerifier;
+use base::hex_utils::short_hex_string;
+use base::snp::snp_client_to_client::{ChannelHistoryRequest, ChannelHistoryResponse};
+use base::snp::snp_core_types::{ChannelContentItem, ChannelData, ChannelType};
+use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::time_utils::now;
+use bytes::Bytes;
+use prost::Message;
+use std::cmp::Reverse;
+use xactor::Service;
+
+/// Max number of content items in a channel history page
+const MAX_HISTORY_PAGE_SIZE: usize = 50;
+
+impl SimpleClient {
+    /// Handle a request from a subscriber of a channel we created or from a member of a group we created
+    /// for a page of the channel's content
+    pub(crate) async fn handle_channel_history_request(&mut self, msg: TypedMessage) -> Result<()> {
+        let request = ChannelHistoryRequest::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode channel history request {:?}", e))?;
+
+        let requester = msg
+            .sender
+            .clone()
+            .ok_or_else(|| anyhow!("missing sender"))?;
+
+        let channels_service = ChannelsService::from_registry().await?;
+        let channel_data = channels_service
+            .call(GetChannel(request.channel_id.clone()))
+            .await??
+            .ok_or_else(|| anyhow!("unknown channel"))?;
+
+        let response = match self.channel_history_page(&channel_data, &request, requester.get_id()?)
+        {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("rejected channel history request: {:?}", e);
+                ChannelHistoryResponse {
+                    channel_id: request.channel_id,
+                    content_items: vec![],
+                    has_more: false,
+                    error: format!("{}", e),
+                }
+            }
+        };
+
+        debug!(
+            "sending {} channel history items to {}",
+            response.content_items.len(),
+            short_hex_string(requester.get_id()?)
+        );
+
+        let typed_msg = self.create_typed_message(
+            MessageType::ChannelHistoryResponse,
+            response.encode_to_vec(),
+            requester.get_ed_pub_key()?,
+        )?;
+        self.send_typed_message(typed_msg, Bytes::from(requester.get_id()?.clone()))
+            .await
+    }
+
+    /// Returns the page of a channel's content the requester asked for. Only group members and subscribers who
+    /// paid their subscription fee may get a channel's content. Expired self-destructing items are not returned
+    fn channel_history_page(
+        &self,
+        channel_data: &ChannelData,
+        request: &ChannelHistoryRequest,
+        requester_id: &[u8],
+    ) -> Result<ChannelHistoryResponse> {
+        let time_stamp = now();
+
+        match channel_data.get_bundle()?.channel_type {
+            t if t == ChannelType::StatusFeed as i32 => {
+                let subscriber = channel_data
+                    .get_subscriber(requester_id)?
+                    .ok_or_else(|| anyhow!("not a channel subscriber"))?;
+                if !subscriber.is_paid_up(time_stamp) {
+                    bail!("subscription fee is due")
+                }
+            }
+            t if t == ChannelType::Group as i32 => {
+                channel_data
+                    .group_members
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing group members"))?
+                    .get_member(requester_id)
+                    .ok_or_else(|| anyhow!("not a group member"))?;
+            }
+            _ => bail!("unsupported channel type"),
+        }
+
+        let mut items: Vec<_> = channel_data
+            .content_items
+            .iter()
+            .filter(|item| request.before == 0 || item.created < request.before)
+            .filter(|item| {
+                item.expiration_time(time_stamp)
+                    .is_none_or(|t| t > time_stamp)
+            })
+            .collect();
+
+        // newest items first
+        items.sort_by_key(|item| Reverse(item.created));
+
+        let page_size = match request.max_items as usize {
+            0 => MAX_HISTORY_PAGE_SIZE,
+            max_items => max_items.min(MAX_HISTORY_PAGE_SIZE),
+        };
+
+        let mut content_items = vec![];
+        for item in items.iter().take(page_size) {
+            let mut channel_item = ChannelContentItem::new((*item).clone());
+            channel_item.sign(&self.client_id)?;
+            content_items.push(channel_item);
+        }
+
+        Ok(ChannelHistoryResponse {
+            channel_id: request.channel_id.clone(),
+            content_items,
+            has_more: items.len() > page_size,
+            error: "".into(),
+        })
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::SignedWithExternalVerifier;
+use base::hex_utils::short_hex_string;
+use base::snp::snp_client_to_client::{ChannelHistoryRequest, ChannelHistoryResponse};
+use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::ChannelHistoryReceivedEvent;
+use bytes::Bytes;
+use prost::Message;
+use xactor::*;
+
+#[message(result = "Result<()>")]
+pub(crate) struct GetChannelHistory {
+    pub(crate) channel_id: Vec<u8>,
+    pub(crate) before: u64,
+    pub(crate) max_items: u32,
+}
+
+/// Request a page of the content of a channel we are subscribed to or of a group we are a member of
+/// from the channel's creator
+#[async_trait::async_trait]
+impl Handler<GetChannelHistory> for SimpleClient {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: GetChannelHistory) -> Result<()> {
+        let channel_bundle = self
+            .channels_subscriptions
+            .get(&msg.channel_id)
+            .ok_or_else(|| anyhow!("not subscribed to this channel"))?;
+
+        let creator_id = channel_bundle
+            .creator_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing channel creator id"))?
+            .get_id()?
+            .clone();
+
+        let typed_msg = self.create_typed_message(
+            MessageType::ChannelHistoryRequest,
+            ChannelHistoryRequest {
+                channel_id: msg.channel_id,
+                before: msg.before,
+                max_items: msg.max_items,
+            }
+            .encode_to_vec(),
+            ed25519_dalek::PublicKey::from_bytes(&creator_id)?,
+        )?;
+
+        debug!("sending channel history request to channel creator...");
+        self.send_typed_message(typed_msg, Bytes::from(creator_id))
+            .await
+    }
+}
+
+impl SimpleClient {
+    /// Handle a page of a channel's content sent by the channel's creator.
+    /// Each item must be signed by its author and by the channel's creator
+    pub(crate) async fn handle_channel_history_response(
+        &mut self,
+        msg: TypedMessage,
+    ) -> Result<()> {
+        let response = ChannelHistoryResponse::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode channel history response {:?}", e))?;
+
+        let channel_bundle = self
+            .channels_subscriptions
+            .get(&response.channel_id)
+            .ok_or_else(|| anyhow!("not subscribed to this channel"))?;
+
+        let creator_id = channel_bundle
+            .creator_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing channel creator id"))?
+            .get_id()?;
+
+        if msg.get_ika()?.as_ref() != creator_id.as_slice() {
+            bail!("channel history is not from the channel's creator")
+        }
+
+        for channel_item in response.content_items.iter() {
+            channel_item.verify_signature(creator_id)?;
+            let item = channel_item
+                .content_item
+                .as_ref()
+                .ok_or_else(|| anyhow!("missing content item"))?;
+            if item.channel_id != response.channel_id {
+                bail!("channel history item is from another channel")
+            }
+
+            let author = item
+                .author
+                .as_ref()
+                .ok_or_else(|| anyhow!("missing author"))?;
+            info!(
+                "📜 channel history item from {}: {}. Reply to: {}. Id: {}",
+                short_hex_string(author.get_id()?),
+                item.get_simple_text_content().unwrap_or_default(),
+                item.reply_to,
+                item.id
+            );
+        }
+
+        if !response.error.is_empty() {
+            warn!(
+                "channel {} creator rejected our history request: {}",
+                short_hex_string(&response.channel_id),
+                response.error
+            );
+        }
+
+        self.publish_event(Event::ChannelHistoryReceived(ChannelHistoryReceivedEvent {
+            channel_id: response.channel_id,
+            content_items: response.content_items,
+            has_more: response.has_more,
+            error: response.error,
+        }));
+
+        Ok(())
+    }
+}
@@ -44,6 +44,16 @@ impl SimpleClient {
             .as_ref()
             .ok_or_else(|| anyhow!("missing channel bundle"))?;
 
+        if content_item.reply_to != 0
+            && channel_data
+                .blocked_repliers
+                .iter()
+                .any(|id| id.get_id().unwrap() == author_id_key)
+        {
+            warn!("author is blocked from replying in this channel");
+            return Ok(());
+        }
+
         match channel_bundle.channel_type {
             t if t == ChannelType::StatusFeed as i32 => {
                 if content_item.reply_to == 0 {
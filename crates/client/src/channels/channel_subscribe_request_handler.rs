@@ -2,13 +2,19 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::channels::channels_data_service::{ChannelsService, GetChannel};
+use crate::channels::channels_data_service::{ChannelsService, GetChannel, UpsertChannel};
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, bail, Result};
+use base::hex_utils::short_hex_string;
 use base::snp::snp_client_to_client::{ChannelSubscriptionRequest, ChannelSubscriptionResponse};
-use base::snp::snp_core_types::{ChannelData, ChannelType};
+use base::snp::snp_core_types::{
+    ChannelData, ChannelSubscriptionRequestData, ChannelType, EntityId,
+};
 use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::SubscriptionRequestEvent;
 use bytes::Bytes;
+use prost::Message;
 use xactor::Service;
 
 impl SimpleClient {
@@ -20,11 +26,9 @@ impl SimpleClient {
     ) -> Result<()> {
         //
         // Currently, accept any request from other client to subscribe to status updates or to join a group
-        // In a real product, this client's user will need to review and approve the request or set it to auto approve (public channel)
-        // In addition, for paid channels, client needs to verify the receipt from the request before
-        // adding the requesting client as a subscriber
+        // besides requests to join a group that requires approving its members, which a group admin approves or denies.
+        // Paid status update channels only accept subscribers who paid their first month's subscription fee
         //
-        use prost::Message;
         let request: ChannelSubscriptionRequest =
             ChannelSubscriptionRequest::decode(msg.message.as_slice())
                 .map_err(|e| anyhow!("failed to decode message {:?}", e))?;
@@ -35,12 +39,13 @@ impl SimpleClient {
 
         let channels_service = ChannelsService::from_registry().await?;
         let mut channel_data: ChannelData = channels_service
-            .call(GetChannel(request_data.channel_id))
+            .call(GetChannel(request_data.channel_id.clone()))
             .await??
             .ok_or_else(|| anyhow!("unknown channel"))?;
 
         let subscriber = request_data
             .user
+            .clone()
             .ok_or_else(|| anyhow!("missing subscriber id"))?;
 
         let subscriber_id = Bytes::from(subscriber.get_id()?.clone());
@@ -49,13 +54,64 @@ impl SimpleClient {
             .bundle
             .as_mut()
             .ok_or_else(|| anyhow!("missing channel bundle"))?;
+        let channel_type = channel_bundle.channel_type;
 
-        match channel_bundle.channel_type {
+        match channel_type {
             t if t == ChannelType::StatusFeed as i32 => {
-                self.subscribe_to_status_update(&subscriber, &mut channel_data)
+                let paid = match channel_bundle.get_monthly_fee() {
+                    Some(fee) => {
+                        let (fee, payment_id) = (fee.value, channel_bundle.get_payment_id()?);
+                        if let Err(e) = self
+                            .accept_payment(
+                                request_data.tx_id,
+                                subscriber_id.as_ref(),
+                                fee,
+                                payment_id,
+                            )
+                            .await
+                        {
+                            warn!("rejecting subscription without a valid payment: {}", e);
+                            return self
+                                .send_subscription_response(
+                                    &subscriber,
+                                    channel_data.get_channel_id()?,
+                                    false,
+                                    "This channel requires a paid subscription",
+                                )
+                                .await;
+                        }
+                        true
+                    }
+                    None => false,
+                };
+
+                self.subscribe_to_status_update(&subscriber, &mut channel_data, paid)
                     .await?
             }
             t if t == ChannelType::Group as i32 => {
+                if channel_data
+                    .banned_members
+                    .iter()
+                    .any(|banned| banned.get_id().unwrap().as_slice() == subscriber_id.as_ref())
+                {
+                    warn!("rejecting a request to join the group by a banned user");
+                    return self
+                        .send_subscription_response(
+                            &subscriber,
+                            channel_data.get_channel_id()?,
+                            false,
+                            "You are banned from this group",
+                        )
+                        .await;
+                }
+
+                if channel_data.approve_members {
+                    // the request is pending until a group admin approves or denies it
+                    return self
+                        .add_pending_group_request(&mut channel_data, request_data, subscriber)
+                        .await;
+                }
+
                 let membership = request_data
                     .membership
                     .ok_or_else(|| anyhow!("missing membership data in channel's data"))?;
@@ -66,22 +122,96 @@ impl SimpleClient {
             _ => bail!("unrecognized channel type"),
         }
 
-        // Send subscription confirmation message the to subscriber
-        let resp_msg = ChannelSubscriptionResponse {
+        self.send_subscription_response(
+            &subscriber,
+            channel_data.get_channel_id()?,
+            true,
+            "Welcome aboard!",
+        )
+        .await?;
+
+        self.publish_event(Event::SubscriptionRequest(SubscriptionRequestEvent {
             channel_id: channel_data.get_channel_id()?,
-            subscribed: true,
-            message: "Welcome aboard!".into(),
+            subscriber_id: Some(subscriber),
+            channel_type,
+        }));
+
+        Ok(())
+    }
+
+    /// Keep a request to join a group we created until a group admin approves or denies it
+    async fn add_pending_group_request(
+        &mut self,
+        channel_data: &mut ChannelData,
+        request_data: ChannelSubscriptionRequestData,
+        subscriber: EntityId,
+    ) -> Result<()> {
+        let subscriber_id = subscriber.get_id()?;
+        if channel_data
+            .group_members
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group members"))?
+            .get_member(subscriber_id)
+            .is_some()
+        {
+            bail!("already a group member")
+        }
+
+        request_data
+            .membership
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing membership data in channel's data"))?;
+
+        // a newer request replaces a pending one
+        channel_data.sub_requests.retain(|r| {
+            r.user
+                .as_ref()
+                .is_none_or(|u| u.get_id().unwrap() != subscriber_id)
+        });
+        channel_data.sub_requests.push(request_data);
+
+        let channels_service = ChannelsService::from_registry().await?;
+        channels_service
+            .call(UpsertChannel(channel_data.clone()))
+            .await??;
+
+        info!(
+            "request to join group by {} is pending approval",
+            short_hex_string(subscriber_id)
+        );
+
+        self.publish_event(Event::SubscriptionRequest(SubscriptionRequestEvent {
+            channel_id: channel_data.get_channel_id()?,
+            subscriber_id: Some(subscriber),
+            channel_type: ChannelType::Group as i32,
+        }));
+
+        Ok(())
+    }
+
+    /// Send a subscriber a response to its request to subscribe to a channel or to join a group
+    pub(crate) async fn send_subscription_response(
+        &mut self,
+        subscriber: &EntityId,
+        channel_id: Vec<u8>,
+        subscribed: bool,
+        message: &str,
+    ) -> Result<()> {
+        let resp_msg = ChannelSubscriptionResponse {
+            channel_id,
+            subscribed,
+            message: message.into(),
         };
-        let mut buff = Vec::with_capacity(resp_msg.encoded_len());
-        resp_msg.encode(&mut buff).unwrap();
         let receiver = subscriber.get_ed_pub_key()?;
-        let typed_msg =
-            self.create_typed_message(MessageType::ChannelSubscribeResponse, buff, receiver)?;
+        let typed_msg = self.create_typed_message(
+            MessageType::ChannelSubscribeResponse,
+            resp_msg.encode_to_vec(),
+            receiver,
+        )?;
 
         debug!("sending subscription resp to other client via our provider...");
 
-        self.send_typed_message(typed_msg, subscriber_id).await?;
-
-        Ok(())
+        let subscriber_id = Bytes::from(subscriber.get_id()?.clone());
+        self.send_typed_message(typed_msg, subscriber_id).await
     }
 }
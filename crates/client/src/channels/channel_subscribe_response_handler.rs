@@ -19,17 +19,27 @@ impl SimpleClient {
                 .map_err(|e| anyhow!("failed to decode message {:?}", e))?;
 
         info!(
-            "Got channel subscription confirmation. Channel id: {}. Message: {}",
+            "Got channel subscription response. Channel id: {}. Message: {}",
             short_hex_string(&*response.channel_id),
             response.message
         );
 
         let key: &[u8] = response.channel_id.as_ref();
-        if let Some(channel_data) = self.channels_subscriptions_requests.get(key) {
+
+        if !response.subscribed {
+            warn!("channel creator rejected our subscription request");
+            if self.channels_subscriptions_requests.remove(key).is_some() {
+                SimpleClient::delete_channel_subscription_request(key).await?;
+            }
+            return Ok(());
+        }
+
+        if let Some(channel_data) = self.channels_subscriptions_requests.remove(key) {
             info!("subscribed to channel");
+            SimpleClient::store_channel_subscription(key, &channel_data).await?;
+            SimpleClient::delete_channel_subscription_request(key).await?;
             self.channels_subscriptions
-                .insert(key.to_vec(), channel_data.clone());
-            self.channels_subscriptions_requests.remove(key);
+                .insert(key.to_vec(), channel_data);
         } else {
             warn!("did not find a request to subscribe to this channel by this client")
         }
@@ -10,6 +10,7 @@ use base::snp::snp_client_to_client::ChannelSubscriptionRequest;
 use base::snp::snp_core_types::{
     ChannelBundle, ChannelSubscriptionRequestData, ChannelType, GroupMemberBundle,
 };
+use base::snp::snp_payments::CoinType;
 use base::snp::snp_server_api::MessageType;
 use bytes::Bytes;
 use chrono::prelude::*;
@@ -83,6 +84,25 @@ impl Handler<SubscribeToChannel> for SimpleClient {
 
         let channel_id_bytes = channel_id.get_id()?.to_vec();
 
+        // pay for the first month of a paid status updates channel. creator only accepts us once it sees the payment
+        let tx_id = match msg.channel.get_monthly_fee() {
+            Some(fee) if fee.coin_type == CoinType::Core as i32 => {
+                let payable_address = msg
+                    .channel
+                    .payable_address
+                    .clone()
+                    .ok_or_else(|| anyhow!("missing channel payable address"))?;
+
+                let tx_id = self
+                    .submit_payment(payable_address, fee.value, msg.channel.get_payment_id()?)
+                    .await?;
+                self.record_subscription_payment(&channel_id_bytes).await?;
+                Some(tx_id)
+            }
+            Some(_) => bail!("unsupported subscription fee coin type"),
+            None => None,
+        };
+
         let subscribe_request = ChannelSubscriptionRequest {
             subscription_request_data: Some(ChannelSubscriptionRequestData {
                 time_stamp: Utc::now().timestamp_nanos() as u64,
@@ -90,11 +110,12 @@ impl Handler<SubscribeToChannel> for SimpleClient {
                 user: Some(my_entity),
                 message: message.into(),
                 membership,
-                tx_id: None,
+                tx_id,
             }),
         };
 
         // store this in pending requests store
+        SimpleClient::store_channel_subscription_request(&channel_id_bytes, &msg.channel).await?;
         self.channels_subscriptions_requests
             .insert(channel_id_bytes, msg.channel);
 
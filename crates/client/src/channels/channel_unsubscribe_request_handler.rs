@@ -121,15 +121,15 @@ impl SimpleClient {
             bail!("non member of this group");
         }
 
-        // Sign the new bundle without the removed member - in the future we are going to share the bundle
-        // with other group members so they have an updated list of members which is authenticated
-
-        let group_id_key_pair =
-            ed25519_dalek::Keypair::from_bytes(channel_data.channel_key_pair.as_ref())?;
-
-        members_bundle.sign(&self.client_id, &group_id_key_pair)?;
-        let channels_service = ChannelsService::from_registry().await?;
-        let _ = channels_service.call(UpsertChannel(channel_data)).await??;
+        // a co-admin who left the group is no longer its admin
+        members_bundle
+            .admins
+            .retain(|admin| *admin.get_id().unwrap() != *member_id);
+
+        // Share the new bundle without the removed member with the group members so they have an
+        // updated list of members which is authenticated
+        self.update_group_members(&mut channel_data, None, None)
+            .await?;
 
         info!(
             "removed user {:?} from group",
@@ -90,7 +90,15 @@ impl Handler<UnsubscribeFromChannel> for SimpleClient {
         self.send_typed_message(typed_msg, receiver_id).await?;
 
         // after sending message to channel creator we just remove locally.
+        SimpleClient::delete_channel_subscription(&channel_id_bytes).await?;
         self.channels_subscriptions.remove(&channel_id_bytes);
+        if self
+            .subscriptions_paid_through
+            .remove(&channel_id_bytes)
+            .is_some()
+        {
+            SimpleClient::delete_subscription_paid_through(&channel_id_bytes).await?;
+        }
         debug!("removed channel from channels store");
 
         Ok(())
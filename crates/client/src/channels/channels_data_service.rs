@@ -2,15 +2,19 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use anyhow::Result;
-use base::snp::snp_core_types::ChannelData;
+use crate::consts::CHANNELS_CF;
+use crate::services::client_store::{read_all_items, read_item, write_item};
+use anyhow::{anyhow, Result};
+use base::snp::snp_core_types::{ChannelData, ContentItem};
+use base::time_utils::now;
+use prost::Message;
 use std::collections::HashMap;
 use xactor::*;
 
 /// Channel service manages data for channels created and owned by this client.
 /// Channels handle groups and status updates data.
 /// Only channel or group creator has the channel's data.
-/// Currently uses an in-memory db - should use a persistent db
+/// Channels are persisted in the client db and cached in memory
 #[derive(Debug)]
 pub struct ChannelsService {
     // key is channel_id
@@ -42,6 +46,7 @@ pub(crate) struct UpsertChannel(pub(crate) ChannelData);
 impl Handler<UpsertChannel> for ChannelsService {
     async fn handle(&mut self, _ctx: &mut Context<Self>, msg: UpsertChannel) -> Result<()> {
         let key = msg.0.get_channel_id()?;
+        write_item(CHANNELS_CF, &key, msg.0.encode_to_vec()).await?;
         self.channels.insert(key, msg.0);
         Ok(())
     }
@@ -57,25 +62,92 @@ impl Handler<GetChannel> for ChannelsService {
         _ctx: &mut Context<Self>,
         msg: GetChannel,
     ) -> Result<Option<ChannelData>> {
-        match self.channels.get(&msg.0) {
-            Some(channel) => Ok(Some(channel.clone())),
+        self.get_channel(msg.0).await
+    }
+}
+
+/// Add a content item published to a channel to the channel's content.
+/// Self-destructing items which expired are removed from the channel's content
+#[message(result = "Result<()>")]
+pub(crate) struct AddChannelContentItem(pub(crate) Vec<u8>, pub(crate) ContentItem);
+
+#[async_trait::async_trait]
+impl Handler<AddChannelContentItem> for ChannelsService {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: AddChannelContentItem) -> Result<()> {
+        let mut channel = self
+            .get_channel(msg.0)
+            .await?
+            .ok_or_else(|| anyhow!("unknown channel"))?;
+
+        let time_stamp = now();
+        channel.content_items.retain(|item| {
+            item.expiration_time(time_stamp)
+                .is_none_or(|t| t > time_stamp)
+        });
+        channel.content_items.push(msg.1);
+        channel.last_updated = time_stamp;
+
+        let key = channel.get_channel_id()?;
+        write_item(CHANNELS_CF, &key, channel.encode_to_vec()).await?;
+        self.channels.insert(key, channel);
+        Ok(())
+    }
+}
+
+impl ChannelsService {
+    async fn get_channel(&mut self, channel_id: Vec<u8>) -> Result<Option<ChannelData>> {
+        if let Some(channel) = self.channels.get(&channel_id) {
+            return Ok(Some(channel.clone()));
+        }
+
+        // channels created before the client was restarted are only in the db
+        match read_item(CHANNELS_CF, &channel_id).await? {
+            Some(data) => {
+                let channel = ChannelData::decode(data.as_ref())?;
+                self.channels.insert(channel_id, channel.clone());
+                Ok(Some(channel))
+            }
             None => Ok(None),
         }
     }
 }
 
+/// Returns all channels created by this client
+#[message(result = "Result<Vec<ChannelData>>")]
+pub(crate) struct GetChannels;
+
+#[async_trait::async_trait]
+impl Handler<GetChannels> for ChannelsService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: GetChannels,
+    ) -> Result<Vec<ChannelData>> {
+        let mut channels = vec![];
+        for (key, value) in read_all_items(CHANNELS_CF).await? {
+            let channel = ChannelData::decode(value.as_ref())?;
+            self.channels.insert(key.to_vec(), channel.clone());
+            channels.push(channel);
+        }
+        Ok(channels)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::channels::channels_data_service::ChannelsService;
+    use crate::test_helpers::run_with_test_db;
     use base::snp::snp_core_types::{ChannelBundle, ChannelType, PricingModel};
-    use base::test_helpers::enable_logger;
     use chrono::prelude::*;
     use crypto::utils::entity_from_ed25519_pub_key;
 
-    #[tokio::test]
-    async fn upsert_channel() {
-        enable_logger();
+    #[test]
+    fn upsert_channel() {
+        run_with_test_db(upsert_channel_test());
+    }
+
+    async fn upsert_channel_test() {
         let channels_service = ChannelsService::from_registry().await.unwrap();
         let client_id_key_pair = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
         let channel_id_key_pair = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
@@ -116,6 +188,8 @@ mod tests {
             subscribers: vec![],
             group_members: None,
             channel_key_pair: channel_id_key_pair.to_bytes().to_vec(),
+            banned_members: vec![],
+            approve_members: false,
         };
 
         channels_service
@@ -127,10 +201,52 @@ mod tests {
         let key = channel_id_key_pair.public.as_ref().to_vec();
 
         let _ = channels_service
+            .call(GetChannel(key.clone()))
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        // a restarted service reads the channel from the db
+        let restarted_service = ChannelsService::default().start().await.unwrap();
+        let channel = restarted_service
+            .call(GetChannel(key.clone()))
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            channel.channel_key_pair,
+            channel_id_key_pair.to_bytes().to_vec()
+        );
+
+        // expired self-destructing items are removed from the channel's content when an item is added
+        let author =
+            entity_from_ed25519_pub_key(&client_id_key_pair.public, "DJ Fuzzy Logic".into());
+        let mut expired_item =
+            ContentItem::new_channel_text_message("gone".into(), author.clone(), key.clone(), 0);
+        expired_item.created = now() - 10_000_000_000;
+        expired_item.ttl = 1;
+        let item = ContentItem::new_channel_text_message("hello".into(), author, key.clone(), 0);
+
+        for item in [expired_item, item] {
+            restarted_service
+                .call(AddChannelContentItem(key.clone(), item))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let channel = restarted_service
             .call(GetChannel(key))
             .await
             .unwrap()
             .unwrap()
             .unwrap();
+        assert_eq!(channel.content_items.len(), 1);
+        assert_eq!(
+            channel.content_items[0].get_simple_text_content().unwrap(),
+            "hello"
+        );
     }
 }
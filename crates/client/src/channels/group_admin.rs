@@ -0,0 +1,347 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::channels::channels_data_service::{ChannelsService, GetChannel};
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::Signed;
+use base::hex_utils::short_hex_string;
+use base::snp::snp_client_to_client::{GroupAdminRequest, GroupAdminResponse};
+use base::snp::snp_core_types::{
+    ChannelData, ChannelType, EntityId, GroupAdminAction, GroupAdminChange,
+};
+use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::snp::upsetter_simple_client::UserGetGroupMembersResponse;
+use bytes::Bytes;
+use chrono::prelude::*;
+use prost::Message;
+use xactor::*;
+
+#[message(result = "Result<()>")]
+pub(crate) struct GroupAdmin {
+    pub(crate) channel_id: Vec<u8>,
+    pub(crate) action: GroupAdminAction,
+    pub(crate) user: EntityId,
+}
+
+/// Change a group's membership on behalf of this client's user. The change is applied when we created the group.
+/// Otherwise, it is sent to the group's creator which applies it when we are one of the group's co-admins.
+#[async_trait::async_trait]
+impl Handler<GroupAdmin> for SimpleClient {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: GroupAdmin) -> Result<()> {
+        let mut change = GroupAdminChange {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            channel_id: msg.channel_id.clone(),
+            admin_id: Some(self.get_client_entity()?),
+            action: msg.action as i32,
+            user_id: Some(msg.user),
+            signature: None,
+        };
+        change.sign(&self.client_id)?;
+
+        let channels_service = ChannelsService::from_registry().await?;
+        if let Some(channel_data) = channels_service
+            .call(GetChannel(msg.channel_id.clone()))
+            .await??
+        {
+            return self.apply_group_admin_change(channel_data, change).await;
+        }
+
+        let channel_bundle = self
+            .channels_subscriptions
+            .get(&msg.channel_id)
+            .ok_or_else(|| anyhow!("not a member of this group"))?;
+
+        if channel_bundle.channel_type != ChannelType::Group as i32 {
+            bail!("channel is not a group")
+        }
+
+        let creator_id = channel_bundle
+            .creator_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group creator id"))?
+            .get_id()?
+            .clone();
+
+        if let Some(members) = SimpleClient::read_group_members(&msg.channel_id).await? {
+            if !members.is_admin(self.client_id.public.as_ref()) {
+                bail!("not a group admin")
+            }
+        }
+
+        let typed_msg = self.create_typed_message(
+            MessageType::GroupAdminRequest,
+            GroupAdminRequest {
+                change: Some(change),
+            }
+            .encode_to_vec(),
+            ed25519_dalek::PublicKey::from_bytes(&creator_id)?,
+        )?;
+
+        debug!("sending group admin request to group creator...");
+        self.send_typed_message(typed_msg, Bytes::from(creator_id))
+            .await
+    }
+}
+
+#[message(result = "Result<UserGetGroupMembersResponse>")]
+pub(crate) struct GetGroupMembers(pub(crate) Vec<u8>);
+
+/// Returns a group's members bundle. Pending requests to join the group and its blocked repliers are
+/// only returned for a group we created
+#[async_trait::async_trait]
+impl Handler<GetGroupMembers> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetGroupMembers,
+    ) -> Result<UserGetGroupMembersResponse> {
+        let channels_service = ChannelsService::from_registry().await?;
+        if let Some(channel_data) = channels_service.call(GetChannel(msg.0.clone())).await?? {
+            return Ok(UserGetGroupMembersResponse {
+                members: channel_data.group_members,
+                pending_requests: channel_data.sub_requests,
+                blocked_repliers: channel_data.blocked_repliers,
+            });
+        }
+
+        Ok(UserGetGroupMembersResponse {
+            members: SimpleClient::read_group_members(&msg.0).await?,
+            pending_requests: vec![],
+            blocked_repliers: vec![],
+        })
+    }
+}
+
+impl SimpleClient {
+    /// Handle a membership change request from a co-admin of a group we created
+    pub(crate) async fn handle_group_admin_request(&mut self, msg: TypedMessage) -> Result<()> {
+        let request = GroupAdminRequest::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode group admin request {:?}", e))?;
+        let change = request
+            .change
+            .ok_or_else(|| anyhow!("missing group admin change"))?;
+
+        let admin = msg
+            .sender
+            .clone()
+            .ok_or_else(|| anyhow!("missing sender"))?;
+        if change.get_admin_id()? != admin.get_id()? {
+            bail!("group admin change was not made by its sender")
+        }
+        change.verify_signature()?;
+
+        let channels_service = ChannelsService::from_registry().await?;
+        let channel_data = channels_service
+            .call(GetChannel(change.channel_id.clone()))
+            .await??
+            .ok_or_else(|| anyhow!("unknown group"))?;
+
+        let channel_id = change.channel_id.clone();
+        let response = match self.apply_group_admin_change(channel_data, change).await {
+            Ok(()) => GroupAdminResponse {
+                channel_id,
+                accepted: true,
+                message: "".into(),
+            },
+            Err(e) => {
+                warn!("rejected group admin request: {:?}", e);
+                GroupAdminResponse {
+                    channel_id,
+                    accepted: false,
+                    message: format!("{}", e),
+                }
+            }
+        };
+
+        let typed_msg = self.create_typed_message(
+            MessageType::GroupAdminResponse,
+            response.encode_to_vec(),
+            admin.get_ed_pub_key()?,
+        )?;
+        self.send_typed_message(typed_msg, Bytes::from(admin.get_id()?.clone()))
+            .await
+    }
+
+    /// Handle a group creator's response to our membership change request
+    pub(crate) async fn handle_group_admin_response(&mut self, msg: TypedMessage) -> Result<()> {
+        let response = GroupAdminResponse::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode group admin response {:?}", e))?;
+
+        if response.accepted {
+            info!(
+                "group {} creator accepted our membership change",
+                short_hex_string(&response.channel_id)
+            );
+        } else {
+            warn!(
+                "group {} creator rejected our membership change: {}",
+                short_hex_string(&response.channel_id),
+                response.message
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Apply a membership change made by an admin of a group we created and send the group's members
+    /// the new members bundle which includes the signed change
+    async fn apply_group_admin_change(
+        &mut self,
+        mut channel_data: ChannelData,
+        change: GroupAdminChange,
+    ) -> Result<()> {
+        if channel_data.get_bundle()?.channel_type != ChannelType::Group as i32 {
+            bail!("channel is not a group")
+        }
+
+        let action = GroupAdminAction::from_i32(change.action)
+            .ok_or_else(|| anyhow!("unknown group admin action"))?;
+        let admin_id = change.get_admin_id()?.clone();
+        let user = change
+            .user_id
+            .clone()
+            .ok_or_else(|| anyhow!("missing user id"))?;
+        let user_id = user.get_id()?.clone();
+
+        let members = channel_data
+            .group_members
+            .as_mut()
+            .ok_or_else(|| anyhow!("missing group members"))?;
+
+        if !members.is_admin(&admin_id) {
+            bail!("only group admins may change its membership")
+        }
+
+        if members.is_creator(&user_id) {
+            bail!("the group's creator membership can't be changed")
+        }
+
+        let creator_only = matches!(
+            action,
+            GroupAdminAction::AddAdmin | GroupAdminAction::RemoveAdmin
+        );
+        if creator_only && !members.is_creator(&admin_id) {
+            bail!("only the group's creator may change its admins")
+        }
+
+        // co-admins can't act against each other
+        if members.is_admin(&user_id) && !members.is_creator(&admin_id) {
+            bail!("co-admins can't change other co-admins membership")
+        }
+
+        let is_user = |id: &EntityId| *id.get_id().unwrap() == user_id;
+        let mut removed = None;
+
+        match action {
+            GroupAdminAction::RemoveMember | GroupAdminAction::BanMember => {
+                match members
+                    .members
+                    .iter()
+                    .position(|m| m.get_member_id().unwrap() == user_id)
+                {
+                    Some(idx) => {
+                        members.members.remove(idx);
+                        members.admins.retain(|a| !is_user(a));
+                        removed = Some(&user);
+                    }
+                    None if action == GroupAdminAction::RemoveMember => {
+                        bail!("not a group member")
+                    }
+                    None => {}
+                }
+
+                if action == GroupAdminAction::BanMember {
+                    channel_data
+                        .sub_requests
+                        .retain(|r| !r.user.as_ref().is_some_and(is_user));
+                    if !channel_data.banned_members.iter().any(is_user) {
+                        channel_data.banned_members.push(user.clone());
+                    }
+                }
+            }
+            GroupAdminAction::UnbanMember => {
+                if !channel_data.banned_members.iter().any(is_user) {
+                    bail!("user is not banned")
+                }
+                channel_data.banned_members.retain(|b| !is_user(b));
+            }
+            GroupAdminAction::AddAdmin => {
+                if members.get_member(&user_id).is_none() {
+                    bail!("only group members can be admins")
+                }
+                if members.admins.iter().any(is_user) {
+                    bail!("already a group admin")
+                }
+                members.admins.push(user.clone());
+            }
+            GroupAdminAction::RemoveAdmin => {
+                if !members.admins.iter().any(is_user) {
+                    bail!("not a group admin")
+                }
+                members.admins.retain(|a| !is_user(a));
+            }
+            GroupAdminAction::ApproveMember | GroupAdminAction::DenyMember => {
+                let idx = channel_data
+                    .sub_requests
+                    .iter()
+                    .position(|r| r.user.as_ref().is_some_and(is_user))
+                    .ok_or_else(|| anyhow!("no pending request to join the group"))?;
+                let request = channel_data.sub_requests.remove(idx);
+
+                if action == GroupAdminAction::ApproveMember {
+                    members.members.push(
+                        request
+                            .membership
+                            .ok_or_else(|| anyhow!("missing membership data"))?,
+                    );
+                }
+            }
+            GroupAdminAction::BlockReplier => {
+                if members.get_member(&user_id).is_none() {
+                    bail!("not a group member")
+                }
+                if !channel_data.blocked_repliers.iter().any(is_user) {
+                    channel_data.blocked_repliers.push(user.clone());
+                }
+            }
+            GroupAdminAction::UnblockReplier => {
+                channel_data.blocked_repliers.retain(|b| !is_user(b));
+            }
+        }
+
+        info!(
+            "group admin {} made a membership change: {:?} {}",
+            short_hex_string(&admin_id),
+            action,
+            short_hex_string(&user_id)
+        );
+
+        self.update_group_members(&mut channel_data, Some(change), removed)
+            .await?;
+
+        // let the requester know whether its request to join the group was approved
+        match action {
+            GroupAdminAction::ApproveMember => {
+                self.send_subscription_response(
+                    &user,
+                    channel_data.get_channel_id()?,
+                    true,
+                    "Welcome aboard!",
+                )
+                .await
+            }
+            GroupAdminAction::DenyMember => {
+                self.send_subscription_response(
+                    &user,
+                    channel_data.get_channel_id()?,
+                    false,
+                    "Your request to join the group was denied",
+                )
+                .await
+            }
+            _ => Ok(()),
+        }
+    }
+}
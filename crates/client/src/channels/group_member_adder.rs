@@ -2,17 +2,15 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::channels::channels_data_service::{ChannelsService, UpsertChannel};
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, Result};
 use base::hex_utils::short_hex_string;
 use base::snp::snp_core_types::{ChannelData, EntityId, GroupMemberBundle};
-use xactor::Service;
 
 impl SimpleClient {
-    /// Add user to group
+    /// Add user to group and send the group's members the new members bundle
     pub(crate) async fn add_group_member(
-        &self,
+        &mut self,
         user: &EntityId,
         channel_data: &mut ChannelData,
         membership: GroupMemberBundle,
@@ -26,17 +24,7 @@ impl SimpleClient {
 
         members_bundle.members.push(membership);
 
-        let group_id_key_pair =
-            ed25519_dalek::Keypair::from_bytes(channel_data.channel_key_pair.as_ref())?;
-
-        // sign the new bundle with the new members
-        members_bundle.sign(&self.client_id, &group_id_key_pair)?;
-
-        // save the bundle
-        let channels_service = ChannelsService::from_registry().await?;
-        let _ = channels_service
-            .call(UpsertChannel(channel_data.clone()))
-            .await??;
+        self.update_group_members(channel_data, None, None).await?;
 
         let subscriber_id = user.get_id()?;
 
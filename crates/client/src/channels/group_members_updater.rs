@@ -0,0 +1,172 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::channels::channels_data_service::{ChannelsService, UpsertChannel};
+use crate::consts::GROUP_MEMBERS_CF;
+use crate::services::client_store::{delete_item, read_item, write_item};
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_client_to_client::GroupMembersUpdate;
+use base::snp::snp_core_types::{ChannelData, EntityId, GroupAdminChange, GroupMembersBundle};
+use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::GroupMembersUpdatedEvent;
+use bytes::Bytes;
+use chrono::prelude::*;
+use prost::Message;
+use xactor::Service;
+
+impl SimpleClient {
+    /// Re-sign the members bundle of a group we created after a membership change, store the group
+    /// and send the new bundle to the group's members.
+    /// A removed member is sent the new bundle so it knows it is no longer a member of the group
+    pub(crate) async fn update_group_members(
+        &mut self,
+        channel_data: &mut ChannelData,
+        change: Option<GroupAdminChange>,
+        removed: Option<&EntityId>,
+    ) -> Result<()> {
+        let members_bundle = channel_data
+            .group_members
+            .as_mut()
+            .ok_or_else(|| anyhow!("missing membership bundle"))?;
+
+        members_bundle.created = Utc::now().timestamp_nanos() as u64;
+        members_bundle.last_change = change;
+
+        let group_id_key_pair =
+            ed25519_dalek::Keypair::from_bytes(channel_data.channel_key_pair.as_ref())?;
+        members_bundle.sign(&self.client_id, &group_id_key_pair)?;
+        let members_bundle = members_bundle.clone();
+
+        let channels_service = ChannelsService::from_registry().await?;
+        channels_service
+            .call(UpsertChannel(channel_data.clone()))
+            .await??;
+
+        let mut receivers = vec![];
+        for member in members_bundle.members.iter() {
+            let member_id = member.get_member_id()?;
+            if member_id != self.client_id.public.as_ref() {
+                receivers.push(member_id);
+            }
+        }
+        if let Some(removed) = removed {
+            receivers.push(removed.get_id()?.clone());
+        }
+
+        let update = GroupMembersUpdate {
+            members: Some(members_bundle),
+        }
+        .encode_to_vec();
+
+        for receiver in receivers {
+            let typed_msg = self.create_typed_message(
+                MessageType::GroupMembersUpdate,
+                update.clone(),
+                ed25519_dalek::PublicKey::from_bytes(&receiver)?,
+            )?;
+
+            if let Err(e) = self
+                .send_typed_message(typed_msg, Bytes::from(receiver.clone()))
+                .await
+            {
+                warn!(
+                    "failed to send group members update to {}: {:?}",
+                    short_hex_string(&receiver),
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A group's creator sent us the group's updated members bundle
+    pub(crate) async fn handle_group_members_update(&mut self, msg: TypedMessage) -> Result<()> {
+        let update = GroupMembersUpdate::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode group members update {:?}", e))?;
+        let members_bundle = update
+            .members
+            .ok_or_else(|| anyhow!("missing group members bundle"))?;
+
+        members_bundle.verify()?;
+
+        let channel_id = members_bundle
+            .group_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group id"))?
+            .get_id()?
+            .clone();
+
+        // the update may arrive before the creator's response to our request to join the group
+        let channel_bundle = self
+            .channels_subscriptions
+            .get(&channel_id)
+            .or_else(|| self.channels_subscriptions_requests.get(&channel_id))
+            .ok_or_else(|| anyhow!("not a member of this group"))?;
+
+        let creator_id = channel_bundle
+            .creator_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group creator id"))?
+            .get_id()?;
+
+        if !members_bundle.is_creator(creator_id) || msg.get_ika()?.as_ref() != creator_id {
+            bail!("group members update is not from the group's creator")
+        }
+
+        if let Some(current) = SimpleClient::read_group_members(&channel_id).await? {
+            if current.created >= members_bundle.created {
+                bail!("got an outdated group members bundle")
+            }
+        }
+
+        let removed = members_bundle
+            .get_member(self.client_id.public.as_ref())
+            .is_none()
+            && self.channels_subscriptions.contains_key(&channel_id);
+
+        if removed {
+            info!(
+                "removed from group {}",
+                short_hex_string(channel_id.as_ref())
+            );
+            self.channels_subscriptions.remove(&channel_id);
+            SimpleClient::delete_channel_subscription(&channel_id).await?;
+            delete_item(GROUP_MEMBERS_CF, &channel_id).await?;
+        } else {
+            debug!(
+                "got group {} members bundle with {} members",
+                short_hex_string(channel_id.as_ref()),
+                members_bundle.members.len()
+            );
+            write_item(
+                GROUP_MEMBERS_CF,
+                &channel_id,
+                members_bundle.encode_to_vec(),
+            )
+            .await?;
+        }
+
+        self.publish_event(Event::GroupMembersUpdated(GroupMembersUpdatedEvent {
+            channel_id,
+            members: Some(members_bundle),
+            removed,
+        }));
+
+        Ok(())
+    }
+
+    /// Returns the members bundle of a group we are a member of
+    pub(crate) async fn read_group_members(
+        channel_id: &[u8],
+    ) -> Result<Option<GroupMembersBundle>> {
+        match read_item(GROUP_MEMBERS_CF, channel_id).await? {
+            Some(data) => Ok(Some(GroupMembersBundle::decode(data.as_ref())?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -2,14 +2,16 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use crate::channels::channels_data_service::{AddChannelContentItem, ChannelsService};
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, Result};
 use base::hex_utils::short_hex_string;
 use base::snp::snp_core_types::{ChannelData, ContentItem};
-use bytes::Bytes;
+use xactor::Service;
 
 impl SimpleClient {
-    // Publish a group message from a group member - send it to all group members that should get it
+    // Publish a group message from a group member - send it to all group members that should get it.
+    // The message is encrypted once and our provider forwards it to the members
     pub(crate) async fn publish_group_message(
         &mut self,
         data: &ChannelData,
@@ -42,39 +44,47 @@ impl SimpleClient {
 
         let channel_id = data.get_channel_id()?;
 
-        for member in members_bundle.members.iter() {
-            let id = member
-                .user_id
-                .as_ref()
-                .ok_or_else(|| anyhow!("missing user id"))?;
-
-            let key = id
-                .public_key
-                .as_ref()
-                .ok_or_else(|| anyhow!("missing pub key"))?
-                .as_pub_key()?;
+        // keep the message so members can get it in the group's history
+        ChannelsService::from_registry()
+            .await?
+            .call(AddChannelContentItem(
+                channel_id.clone(),
+                content_item.clone(),
+            ))
+            .await??;
 
-            if key.as_ref() == author_id {
-                continue;
+        // all group members besides us get our sender key. The author already has its message
+        let mut members = vec![];
+        for member in members_bundle.members.iter() {
+            let member_id = member.get_member_id()?;
+            if member_id != channel_owner_id {
+                members.push(member_id);
             }
+        }
+        members.sort();
 
-            if key.as_ref() == channel_owner_id {
-                // skip sending the message to ourselves (as channel owners)
-                continue;
-            }
+        let receivers: Vec<Vec<u8>> = members
+            .iter()
+            .filter(|m| m.as_slice() != author_id)
+            .cloned()
+            .collect();
 
-            debug!(
-                "sending update to member: {:}",
-                short_hex_string(key.as_ref())
-            );
+        if receivers.is_empty() {
+            return Ok(());
+        }
 
-            let message = self
-                .new_channel_message(&key, channel_id.clone().as_ref(), content_item.clone())
-                .await?;
+        debug!("sending group message to {} members", receivers.len());
 
-            let receiver_id = Bytes::from(member.get_member_id()?);
-            self.send_typed_message(message, receiver_id).await?;
-        }
+        // the message is encrypted once with our group sender key for all members
+        let mut key = self.get_group_sender_key(&channel_id, &members).await?;
+        let group_key = ed25519_dalek::PublicKey::from_bytes(&channel_id)?;
+        let message = self
+            .new_channel_message(&group_key, &channel_id, content_item)
+            .await?;
+        let group_message = key.encrypt(message)?;
+        SimpleClient::store_group_sender_key(&key, &members).await?;
+
+        self.send_group_message(group_message, &receivers).await?;
 
         Ok(())
     }
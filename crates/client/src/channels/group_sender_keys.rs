@@ -0,0 +1,157 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::consts::{GROUP_SENDER_KEYS_CF, MEMBERS_SENDER_KEYS_CF};
+use crate::services::client_store::{read_item, write_item};
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_client_to_client::GroupSenderKey;
+use base::snp::snp_core_types::ChannelType;
+use base::snp::snp_server_api::{GroupMessage, MessageType, TypedMessage};
+use bytes::Bytes;
+use common::sender_keys::SenderKey;
+use prost::Message;
+
+impl SimpleClient {
+    /// Returns our sender key for a group we publish messages to.
+    /// A new key is created and sent to the members when the group's membership changed since we sent them the
+    /// current key, so members who left can't decrypt new messages and new members can't decrypt older ones.
+    /// Members ids must be sorted
+    pub(crate) async fn get_group_sender_key(
+        &mut self,
+        channel_id: &[u8],
+        members: &[Vec<u8>],
+    ) -> Result<SenderKey> {
+        if let Some(data) = read_item(GROUP_SENDER_KEYS_CF, channel_id).await? {
+            let (key, key_members): (SenderKey, Vec<Vec<u8>>) =
+                bincode::deserialize(data.as_ref())?;
+            if key_members == members {
+                return Ok(key);
+            }
+        }
+
+        debug!(
+            "creating a new sender key for group {}",
+            short_hex_string(channel_id)
+        );
+
+        let key = SenderKey::new(channel_id);
+        let distribution = key.distribution().encode_to_vec();
+        for member in members.iter() {
+            let typed_msg = self.create_typed_message(
+                MessageType::GroupSenderKey,
+                distribution.clone(),
+                ed25519_dalek::PublicKey::from_bytes(member)?,
+            )?;
+
+            // member won't be able to decrypt our group messages until we send it a new key
+            if let Err(e) = self
+                .send_typed_message(typed_msg, Bytes::from(member.clone()))
+                .await
+            {
+                warn!(
+                    "failed to send group sender key to member {}: {:?}",
+                    short_hex_string(member),
+                    e
+                );
+            }
+        }
+
+        SimpleClient::store_group_sender_key(&key, members).await?;
+        Ok(key)
+    }
+
+    /// Store our sender key for a group and the members we sent it to
+    pub(crate) async fn store_group_sender_key(key: &SenderKey, members: &[Vec<u8>]) -> Result<()> {
+        write_item(
+            GROUP_SENDER_KEYS_CF,
+            &key.channel_id,
+            bincode::serialize(&(key, members))?,
+        )
+        .await
+    }
+
+    /// A group's publisher sent us its sender key. We use it to decrypt the publisher's group messages
+    pub(crate) async fn handle_group_sender_key(&mut self, msg: TypedMessage) -> Result<()> {
+        let publisher_id = msg.get_ika()?.as_ref().to_vec();
+        let distribution = GroupSenderKey::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode group sender key {:?}", e))?;
+
+        // the key may arrive before the group creator's response to our request to join the group
+        let bundle = self
+            .channels_subscriptions
+            .get(&distribution.channel_id)
+            .or_else(|| {
+                self.channels_subscriptions_requests
+                    .get(&distribution.channel_id)
+            })
+            .ok_or_else(|| anyhow!("not a member of this group"))?;
+
+        if bundle.channel_type != ChannelType::Group as i32 {
+            bail!("channel is not a group")
+        }
+
+        if *bundle
+            .creator_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group creator id"))?
+            .get_id()?
+            != publisher_id
+        {
+            bail!("sender key is not from the group's creator")
+        }
+
+        let key = SenderKey::from_distribution(&distribution)?;
+
+        debug!(
+            "got a new sender key for group {}",
+            short_hex_string(&distribution.channel_id)
+        );
+
+        write_item(
+            MEMBERS_SENDER_KEYS_CF,
+            &key.key_id,
+            bincode::serialize(&(publisher_id, &key))?,
+        )
+        .await
+    }
+
+    /// Handle a group message that a group's publisher encrypted once for all members with its sender key
+    pub(crate) async fn handle_group_message(&mut self, message: GroupMessage) -> Result<()> {
+        let data = read_item(MEMBERS_SENDER_KEYS_CF, &message.key_id)
+            .await?
+            .ok_or_else(|| anyhow!("unknown group sender key"))?;
+        let (publisher_id, mut key): (Vec<u8>, SenderKey) = bincode::deserialize(data.as_ref())?;
+
+        let typed_msg = key.decrypt(&message)?;
+
+        // the message must be from the key's publisher to the key's group
+        if typed_msg.get_ika()?.as_ref() != publisher_id.as_slice() {
+            bail!("group message is not from the sender key's publisher")
+        }
+
+        let receiver = typed_msg
+            .receiver
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing group message receiver"))?;
+        if *receiver.get_id()? != key.channel_id {
+            bail!("group message is for another group")
+        }
+
+        if typed_msg.msg_type != MessageType::ChannelMessage as i32 {
+            bail!("unexpected group message type: {}", typed_msg.msg_type)
+        }
+
+        // message keys can only be used once
+        write_item(
+            MEMBERS_SENDER_KEYS_CF,
+            &message.key_id,
+            bincode::serialize(&(publisher_id, &key))?,
+        )
+        .await?;
+
+        self.dispatch_incoming_client_message(typed_msg).await
+    }
+}
@@ -9,11 +9,13 @@ use base::hex_utils::short_hex_string;
 use base::snp::snp_client_to_client::NewChannelMessage;
 use base::snp::snp_core_types::ChannelType;
 use base::snp::snp_server_api::TypedMessage;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::{ChannelPostReceivedEvent, ConversationType};
 
 impl SimpleClient {
     /// Handle an incoming status update or group message sent by channel creator
     pub(crate) async fn handle_new_incoming_channel_message(
-        &self,
+        &mut self,
         msg: TypedMessage,
     ) -> Result<()> {
         use prost::Message;
@@ -27,8 +29,8 @@ impl SimpleClient {
             .public_key
             .as_ref()
             .ok_or_else(|| anyhow!("missing public key"))?
-            .as_pub_key()
-            .map_err(|_| anyhow!("invalid public key"))?;
+            .key
+            .clone();
 
         let content = channel_message
             .content_item
@@ -48,23 +50,40 @@ impl SimpleClient {
             .get(&channel_id)
             .ok_or_else(|| anyhow!("not subscribed to this channel"))?;
 
-        match channel_bundle.channel_type {
-            t if t == ChannelType::Group as i32 => info!(
-                "🎉 👋 incoming group message from, {}: {}. Reply to {}. Id: {}",
-                short_hex_string(sender.as_ref()),
-                item.get_simple_text_content()?,
-                item.reply_to,
-                item.id
-            ),
-            t if t == ChannelType::StatusFeed as i32 => info!(
-                "🎉 👋 incoming status update from, {}: {}. Reply to: {}. Id: {}",
-                short_hex_string(sender.as_ref()),
-                item.get_simple_text_content()?,
-                item.reply_to,
-                item.id
-            ),
+        let channel_type = channel_bundle.channel_type;
+        let conversation_type = match channel_type {
+            t if t == ChannelType::Group as i32 => {
+                info!(
+                    "🎉 👋 incoming group message from, {}: {}. Reply to {}. Id: {}",
+                    short_hex_string(sender.as_ref()),
+                    item.get_simple_text_content()?,
+                    item.reply_to,
+                    item.id
+                );
+                ConversationType::Group
+            }
+            t if t == ChannelType::StatusFeed as i32 => {
+                info!(
+                    "🎉 👋 incoming status update from, {}: {}. Reply to: {}. Id: {}",
+                    short_hex_string(sender.as_ref()),
+                    item.get_simple_text_content()?,
+                    item.reply_to,
+                    item.id
+                );
+                ConversationType::StatusFeed
+            }
             _ => bail!("unsupported channel type"),
-        }
+        };
+
+        let sender_id = self.get_other_client_entity(sender.as_ref());
+        let message =
+            SimpleClient::store_inbox_message(&channel_id, conversation_type, sender_id, item)
+                .await?;
+
+        self.publish_event(Event::ChannelPostReceived(ChannelPostReceivedEvent {
+            message: Some(message),
+            channel_type,
+        }));
 
         Ok(())
     }
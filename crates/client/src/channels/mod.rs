@@ -4,6 +4,8 @@
 
 // Channels package implements client channels
 pub mod channel_creator;
+mod channel_history_request_handler;
+pub(crate) mod channel_history_requester;
 pub(crate) mod channel_msg_publisher;
 pub(crate) mod channel_msg_request_handler;
 mod channel_msg_request_sender;
@@ -14,10 +16,14 @@ pub(crate) mod channel_unsubscribe_request_handler;
 mod channel_unsubscribe_response_handler;
 pub(crate) mod channel_unsubscriber;
 pub(crate) mod channels_data_service;
+pub(crate) mod group_admin;
 pub(crate) mod group_member_adder;
+mod group_members_updater;
 mod group_msg_publisher;
+mod group_sender_keys;
 pub(crate) mod incoming_channel_msg_handler;
 mod new_channel_msg;
 pub(crate) mod new_channel_msg_request;
 mod status_update_channel_publisher;
 pub(crate) mod status_update_subscriber;
+mod subscription_fees;
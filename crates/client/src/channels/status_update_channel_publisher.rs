@@ -2,15 +2,19 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use crate::channels::channels_data_service::{AddChannelContentItem, ChannelsService};
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, Result};
 use base::hex_utils::short_hex_string;
 use base::snp::snp_core_types::{ChannelData, ContentItem};
+use base::time_utils::now;
 use bytes::Bytes;
+use xactor::Service;
 
 impl SimpleClient {
     /// Send a ContentItem to all channel subscribers.
-    /// ContentItem can be a status update from channel creator or a reply to a status update by any subscriber
+    /// ContentItem can be a status update from channel creator or a reply to a status update by any subscriber.
+    /// Subscribers of a paid channel who didn't renew their subscription don't get it
     pub(crate) async fn publish_to_status_update_channel(
         &mut self,
         data: &ChannelData,
@@ -41,6 +45,16 @@ impl SimpleClient {
             );
         }
 
+        // keep the item so subscribers can get it in the channel's history
+        ChannelsService::from_registry()
+            .await?
+            .call(AddChannelContentItem(
+                channel_id.clone(),
+                content_item.clone(),
+            ))
+            .await??;
+
+        let time_stamp = now();
         for subscriber in data.subscribers.iter() {
             let sub_entity = subscriber
                 .user_id
@@ -58,6 +72,14 @@ impl SimpleClient {
                 continue;
             }
 
+            if !subscriber.is_paid_up(time_stamp) {
+                debug!(
+                    "skipping subscriber who didn't renew its subscription: {:}",
+                    short_hex_string(sub_key.as_ref())
+                );
+                continue;
+            }
+
             debug!(
                 "sending update to subscriber: {:}",
                 short_hex_string(sub_key.as_ref())
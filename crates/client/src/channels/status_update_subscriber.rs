@@ -7,34 +7,46 @@ use crate::simple_client::SimpleClient;
 use anyhow::Result;
 use base::hex_utils::short_hex_string;
 use base::snp::snp_core_types::{ChannelData, ChannelSubscriber, EntityId};
-use chrono::prelude::*;
+use base::time_utils::now;
 use xactor::Service;
 
 impl SimpleClient {
-    /// Subscribe a user to a status update channel created by this client
+    /// Subscribe a user to a status update channel created by this client.
+    /// A paid subscriber's subscription is paid for a subscription period. An existing subscriber who paid again
+    /// renews its subscription
     pub(crate) async fn subscribe_to_status_update(
         &self,
         user: &EntityId,
         channel_data: &mut ChannelData,
+        paid: bool,
     ) -> Result<()> {
-        let subscriber = ChannelSubscriber {
-            user_id: Some(user.clone()),
-            date_subscribed: Utc::now().timestamp_nanos() as u64,
-            time_next_payment_due: 0,
-        };
-
         let subscriber_id = user.get_id()?;
 
-        if let Some(_sub) = channel_data
+        match channel_data
             .subscribers
-            .iter()
-            .position(|sub| sub.get_subscriber_id().unwrap() == subscriber_id)
+            .iter_mut()
+            .find(|sub| sub.has_subscriber_id(subscriber_id))
         {
-            warn!("user is already a subscriber to this channel");
-            return Ok(());
-        };
-
-        channel_data.subscribers.push(subscriber);
+            Some(sub) if paid => sub.renew(now()),
+            Some(_) => {
+                warn!("user is already a subscriber to this channel");
+                return Ok(());
+            }
+            None => {
+                let mut subscriber = ChannelSubscriber {
+                    user_id: Some(user.clone()),
+                    date_subscribed: now(),
+                    time_next_payment_due: 0,
+                    time_reminder_sent: 0,
+                };
+
+                if paid {
+                    subscriber.renew(now());
+                }
+
+                channel_data.subscribers.push(subscriber);
+            }
+        }
 
         let channels_service = ChannelsService::from_registry().await?;
         let _ = channels_service
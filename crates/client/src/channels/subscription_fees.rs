@@ -0,0 +1,338 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::channels::channels_data_service::{
+    ChannelsService, GetChannel, GetChannels, UpsertChannel,
+};
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::channel_subscriber::{is_renewal_due, renewed_paid_through};
+use base::client_config_service::{ClientConfigService, AUTO_RENEW_SUBSCRIPTIONS_CONFIG_KEY};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_client_to_client::{ChannelPaymentReminder, ChannelSubscriptionRenewal};
+use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::time_utils::now;
+use bytes::Bytes;
+use prost::Message;
+use std::time::Duration;
+use tokio::time::sleep;
+use xactor::*;
+
+impl SimpleClient {
+    /// Periodically remind subscribers of our paid channels to renew their subscriptions
+    pub(crate) async fn send_payment_reminders_periodically(interval: Duration) {
+        loop {
+            sleep(interval).await;
+
+            let res = match SimpleClient::from_registry().await {
+                Ok(client) => client.call(SendPaymentReminders).await.and_then(|r| r),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = res {
+                warn!("failed to send subscription payment reminders: {:?}", e);
+            }
+        }
+    }
+
+    /// Extend the time our subscription to a paid channel is paid through after we paid its fee
+    pub(crate) async fn record_subscription_payment(&mut self, channel_id: &[u8]) -> Result<()> {
+        let paid_through = renewed_paid_through(
+            self.subscriptions_paid_through
+                .get(channel_id)
+                .cloned()
+                .unwrap_or_default(),
+            now(),
+        );
+
+        SimpleClient::store_subscription_paid_through(channel_id, paid_through).await?;
+        self.subscriptions_paid_through
+            .insert(channel_id.to_vec(), paid_through);
+        Ok(())
+    }
+
+    /// A paid channel's creator reminds us that our subscription fee is due. When auto-renew is on, renew our
+    /// subscription by paying it. We pay at most once per subscription period - reminders for a period we
+    /// already paid for are ignored
+    pub(crate) async fn handle_channel_payment_reminder(
+        &mut self,
+        msg: TypedMessage,
+    ) -> Result<()> {
+        let creator_id = msg.get_ika()?;
+        let reminder = ChannelPaymentReminder::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode payment reminder {:?}", e))?;
+
+        let bundle = self
+            .channels_subscriptions
+            .get(&reminder.channel_id)
+            .ok_or_else(|| anyhow!("not subscribed to channel"))?
+            .clone();
+
+        if bundle
+            .creator_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing creator identity"))?
+            .get_id()?
+            != creator_id.as_ref()
+        {
+            bail!("payment reminder is not from channel creator")
+        }
+
+        let paid_through = self
+            .subscriptions_paid_through
+            .get(&reminder.channel_id)
+            .cloned()
+            .unwrap_or_default();
+        if !is_renewal_due(paid_through, now()) {
+            bail!("subscription is paid through {}", paid_through)
+        }
+
+        if !ClientConfigService::get_bool(AUTO_RENEW_SUBSCRIPTIONS_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default()
+        {
+            info!(
+                "subscription fee of channel {} is due. auto-renew is off",
+                short_hex_string(&reminder.channel_id)
+            );
+            return Ok(());
+        }
+
+        // we pay the fee of the channel we subscribed to and not the one in the reminder
+        let fee = bundle
+            .get_monthly_fee()
+            .ok_or_else(|| anyhow!("channel is free"))?
+            .value;
+
+        let payable_address = bundle
+            .payable_address
+            .clone()
+            .ok_or_else(|| anyhow!("missing channel payable address"))?;
+
+        let tx_id = self
+            .submit_payment(payable_address, fee, bundle.get_payment_id()?)
+            .await?;
+        self.record_subscription_payment(&reminder.channel_id)
+            .await?;
+
+        info!(
+            "renewed subscription to channel {}. paid {} coins",
+            short_hex_string(&reminder.channel_id),
+            fee
+        );
+
+        let renewal = ChannelSubscriptionRenewal {
+            channel_id: reminder.channel_id,
+            tx_id: Some(tx_id),
+        };
+
+        let typed_msg = self.create_typed_message(
+            MessageType::ChannelSubscriptionRenewal,
+            renewal.encode_to_vec(),
+            creator_id,
+        )?;
+        let receiver_id = Bytes::from(creator_id.to_bytes().to_vec());
+        self.send_typed_message(typed_msg, receiver_id).await
+    }
+
+    /// A subscriber of one of our paid channels renews its subscription
+    pub(crate) async fn handle_channel_subscription_renewal(
+        &mut self,
+        msg: TypedMessage,
+    ) -> Result<()> {
+        let subscriber_id = msg.get_ika()?.as_ref().to_vec();
+        let renewal = ChannelSubscriptionRenewal::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode subscription renewal {:?}", e))?;
+
+        let channels_service = ChannelsService::from_registry().await?;
+        let mut channel_data = channels_service
+            .call(GetChannel(renewal.channel_id))
+            .await??
+            .ok_or_else(|| anyhow!("unknown channel"))?;
+
+        let bundle = channel_data
+            .bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing channel bundle"))?;
+
+        let fee = bundle
+            .get_monthly_fee()
+            .ok_or_else(|| anyhow!("channel is free"))?
+            .value;
+        let payment_id = bundle.get_payment_id()?;
+
+        let index = channel_data
+            .subscribers
+            .iter()
+            .position(|sub| sub.has_subscriber_id(&subscriber_id))
+            .ok_or_else(|| anyhow!("not a channel subscriber"))?;
+
+        self.accept_payment(renewal.tx_id, &subscriber_id, fee, payment_id)
+            .await?;
+
+        let subscriber = &mut channel_data.subscribers[index];
+        subscriber.renew(now());
+
+        debug!(
+            "subscriber {} renewed its subscription. next payment due: {}",
+            short_hex_string(&subscriber_id),
+            subscriber.time_next_payment_due
+        );
+
+        channels_service.call(UpsertChannel(channel_data)).await?
+    }
+}
+
+/// Remind subscribers of our paid channels whose subscription fee is due to renew their subscriptions
+#[message(result = "Result<()>")]
+pub(crate) struct SendPaymentReminders;
+
+#[async_trait::async_trait]
+impl Handler<SendPaymentReminders> for SimpleClient {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, _msg: SendPaymentReminders) -> Result<()> {
+        let time_stamp = now();
+        let channels_service = ChannelsService::from_registry().await?;
+
+        for mut channel_data in channels_service.call(GetChannels).await?? {
+            let bundle = channel_data
+                .bundle
+                .clone()
+                .ok_or_else(|| anyhow!("missing channel bundle"))?;
+
+            let fee = match bundle.get_monthly_fee() {
+                Some(fee) => fee.clone(),
+                None => continue,
+            };
+
+            let mut reminded = false;
+            for subscriber in channel_data.subscribers.iter_mut() {
+                if !subscriber.needs_payment_reminder(time_stamp) {
+                    continue;
+                }
+
+                let reminder = ChannelPaymentReminder {
+                    channel_id: bundle.get_channel_id()?,
+                    subscription_fee: Some(fee.clone()),
+                    time_payment_due: subscriber.time_next_payment_due,
+                };
+
+                let receiver = subscriber
+                    .user_id
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing user id"))?
+                    .get_ed_pub_key()?;
+                let typed_msg = self.create_typed_message(
+                    MessageType::ChannelPaymentReminder,
+                    reminder.encode_to_vec(),
+                    receiver,
+                )?;
+                let receiver_id = Bytes::from(subscriber.get_subscriber_id()?.clone());
+
+                if let Err(e) = self.send_typed_message(typed_msg, receiver_id).await {
+                    warn!("failed to send payment reminder: {:?}", e);
+                    continue;
+                }
+
+                subscriber.time_reminder_sent = time_stamp;
+                reminded = true;
+            }
+
+            if reminded {
+                channels_service.call(UpsertChannel(channel_data)).await??;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+    use base::channel_subscriber::SUBSCRIPTION_PERIOD;
+    use base::snp::snp_core_types::{
+        ChannelBundle, ChannelType, EntityId, PricingModel, PublicKey, Signature,
+    };
+    use base::snp::snp_payments::{Amount, CoinType};
+    use crypto::utils::entity_from_ed25519_pub_key;
+
+    fn reminder_message(creator: &ed25519_dalek::PublicKey, channel_id: &[u8]) -> TypedMessage {
+        TypedMessage {
+            time_stamp: 0,
+            msg_type: MessageType::ChannelPaymentReminder as i32,
+            message: ChannelPaymentReminder {
+                channel_id: channel_id.to_vec(),
+                subscription_fee: None,
+                time_payment_due: 0,
+            }
+            .encode_to_vec(),
+            receiver: None,
+            sender: Some(EntityId {
+                public_key: Some(PublicKey {
+                    key: creator.to_bytes().to_vec(),
+                }),
+                nickname: "".into(),
+            }),
+            signature: Some(Signature::default()),
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
+        }
+    }
+
+    #[test]
+    fn test_payment_reminders() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+            let creator = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+            let channel = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+            let channel_id = channel.public.to_bytes().to_vec();
+
+            let mut bundle = ChannelBundle {
+                channel_id: Some(entity_from_ed25519_pub_key(
+                    &channel.public,
+                    "Paid Status Updates".into(),
+                )),
+                creator_id: Some(entity_from_ed25519_pub_key(&creator.public, "".into())),
+                channel_type: ChannelType::StatusFeed as i32,
+                created: now(),
+                description: "".into(),
+                acceptable_content_policy: "".into(),
+                logo: None,
+                payable_address: None,
+                subscription_fee: Some(Amount {
+                    value: 20,
+                    coin_type: CoinType::Core as i32,
+                }),
+                signature: None,
+                creator_signature: None,
+                pricing_model: PricingModel::MonthlyFee as i32,
+            };
+            bundle.sign(&creator, &channel).unwrap();
+            client
+                .channels_subscriptions
+                .insert(channel_id.clone(), bundle);
+
+            // reminders for a period we already paid for are ignored
+            let paid_through = now() + SUBSCRIPTION_PERIOD.as_nanos() as u64;
+            client
+                .subscriptions_paid_through
+                .insert(channel_id.clone(), paid_through);
+            assert!(client
+                .handle_channel_payment_reminder(reminder_message(&creator.public, &channel_id))
+                .await
+                .is_err());
+
+            // a due subscription fee is not paid when auto-renew is off
+            client
+                .subscriptions_paid_through
+                .insert(channel_id.clone(), now());
+            client
+                .handle_channel_payment_reminder(reminder_message(&creator.public, &channel_id))
+                .await
+                .unwrap();
+            assert!(client.subscriptions_paid_through.get(&channel_id).unwrap() < &paid_through);
+        });
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+// store column families
+
+// client identity and provider data (key -> value)
+pub(crate) const CLIENT_CF: &str = "client";
+
+pub(crate) const CLIENT_ID_KEY: &str = "client_id";
+pub(crate) const PRE_KEY_KEY: &str = "pre_key";
+pub(crate) const KEM_PRE_KEY_KEY: &str = "kem_pre_key";
+pub(crate) const DELIVERY_TOKEN_KEY: &str = "delivery_token";
+pub(crate) const CLIENT_BUNDLE_KEY: &str = "client_bundle";
+pub(crate) const PROVIDER_BUNDLE_KEY: &str = "provider_bundle";
+pub(crate) const PROVIDER_PROTOCOL_VERSION_KEY: &str = "provider_protocol_version";
+pub(crate) const PROVIDER_TERMS_KEY: &str = "provider_terms";
+pub(crate) const MESSAGES_CURSOR_KEY: &str = "messages_cursor";
+pub(crate) const TRANSPARENCY_LOG_HEAD_KEY: &str = "transparency_log_head";
+
+// other clients bundles (client_id -> ProviderSignedClientIdentityBundle)
+pub(crate) const OTHER_CLIENTS_CF: &str = "other_clients";
+
+// delivery tokens other clients gave us (client_id -> token)
+pub(crate) const DELIVERY_TOKENS_CF: &str = "delivery_tokens";
+
+// protocol versions negotiated with other clients (client_id -> semantic version)
+pub(crate) const PROTOCOL_VERSIONS_CF: &str = "protocol_versions";
+
+// contacts identities (registered nickname or hex client_id -> ContactIdentity)
+pub(crate) const CONTACTS_CF: &str = "contacts";
+
+// channels we are subscribed to (channel_id -> ChannelBundle)
+pub(crate) const CHANNELS_SUBSCRIPTIONS_CF: &str = "channels_subscriptions";
+
+// time our paid channels subscriptions are paid through (channel_id -> unix nanos)
+pub(crate) const SUBSCRIPTIONS_PAID_THROUGH_CF: &str = "subscriptions_paid_through";
+
+// channels we requested to subscribe to (channel_id -> ChannelBundle)
+pub(crate) const CHANNELS_SUBSCRIPTIONS_REQUESTS_CF: &str = "channels_subscriptions_requests";
+
+// channels we created (channel_id -> ChannelData)
+pub(crate) const CHANNELS_CF: &str = "channels";
+
+// paid content items we published (item_id -> ContentItem)
+pub(crate) const PAID_ITEMS_CF: &str = "paid_items";
+
+// hashes of client bundles we signed (bundle_hash -> empty)
+pub(crate) const PUBLISHED_BUNDLES_CF: &str = "published_bundles";
+
+// conversations we received messages in (conversation_id -> Conversation)
+pub(crate) const CONVERSATIONS_CF: &str = "conversations";
+
+// messages we received ((conversation_id, index) -> InboxMessage)
+pub(crate) const INBOX_CF: &str = "inbox";
+
+// 1:1 messages we sent and their delivery status (message_id -> SentMessage)
+pub(crate) const SENT_MESSAGES_CF: &str = "sent_messages";
+
+// contacts we don't send read receipts to (pub_key -> empty)
+pub(crate) const READ_RECEIPTS_OFF_CF: &str = "read_receipts_off";
+
+// payments to us we accepted so each can only be used once (transaction_id -> payment_id)
+pub(crate) const USED_PAYMENTS_CF: &str = "used_payments";
+
+// sender keys of groups we publish messages to and the members we sent them to (channel_id -> (SenderKey, member_ids))
+pub(crate) const GROUP_SENDER_KEYS_CF: &str = "group_sender_keys";
+
+// sender keys group publishers sent us (key_id -> (publisher_id, SenderKey))
+pub(crate) const MEMBERS_SENDER_KEYS_CF: &str = "members_sender_keys";
+
+// members bundles of groups we are a member of (channel_id -> GroupMembersBundle)
+pub(crate) const GROUP_MEMBERS_CF: &str = "group_members";
@@ -0,0 +1,130 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::Result;
+use base::protocol_version::{parse_version, ProtocolFeature, VersionRange};
+use base::snp::snp_core_types::ClientIdentityBundle;
+
+impl SimpleClient {
+    /// Negotiate a protocol version with another client which implements a version and store it.
+    /// Returns an error if the client's version is not supported.
+    pub(crate) async fn update_client_protocol_version(
+        &mut self,
+        key: &[u8],
+        version: &str,
+    ) -> Result<()> {
+        let negotiated = VersionRange::snp_protocol()
+            .negotiate_with(version)?
+            .to_string();
+
+        if self.other_clients_protocol_versions.get(key) != Some(&negotiated) {
+            SimpleClient::store_protocol_version(key, &negotiated).await?;
+            self.other_clients_protocol_versions
+                .insert(key.to_vec(), negotiated);
+        }
+        Ok(())
+    }
+
+    /// Returns true if a protocol feature may be used in messages to another client.
+    /// Both the version negotiated with the client and the versions supported by its provider must enable it.
+    /// Clients which didn't send us a message yet are assumed to implement the legacy version.
+    pub(crate) fn is_feature_enabled_with_client(
+        &self,
+        feature: ProtocolFeature,
+        bundle: &ClientIdentityBundle,
+    ) -> Result<bool> {
+        let key = &bundle.get_client_id_public_key()?.key;
+        let client_version = parse_version(
+            self.other_clients_protocol_versions
+                .get(key)
+                .map(|v| v.as_str())
+                .unwrap_or_default(),
+        )?;
+        if !feature.is_enabled(&client_version) {
+            return Ok(false);
+        }
+
+        let dial_up_info = match bundle.provider_bundle.as_ref() {
+            Some(provider_bundle) if !provider_bundle.dial_up_info.is_empty() => {
+                &provider_bundle.dial_up_info
+            }
+            _ => return Ok(false),
+        };
+
+        Ok(dial_up_info
+            .iter()
+            .all(|info| match VersionRange::from_dialup_info(info) {
+                Ok(range) => matches!(
+                    VersionRange::snp_protocol().negotiate(&range),
+                    Ok(version) if feature.is_enabled(&version)
+                ),
+                Err(_) => false,
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+    use base::protocol_version::{MIN_SNP_PROTOCOL_VERSION, SNP_PROTOCOL_VERSION};
+    use base::snp::snp_core_types::{DialupInfo, EntityId, ProviderIdentityBundle, PublicKey};
+
+    fn client_bundle(key: &[u8], provider_version: &str) -> ClientIdentityBundle {
+        ClientIdentityBundle {
+            client_id: Some(EntityId {
+                public_key: Some(PublicKey { key: key.to_vec() }),
+                nickname: "".into(),
+            }),
+            provider_bundle: Some(ProviderIdentityBundle {
+                dial_up_info: vec![DialupInfo {
+                    api_version: provider_version.into(),
+                    min_api_version: MIN_SNP_PROTOCOL_VERSION.into(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sealed_sender_gating() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+            let key = vec![7u8; 32];
+            let bundle = client_bundle(&key, SNP_PROTOCOL_VERSION);
+            let sealed_sender = ProtocolFeature::SealedSender;
+
+            // clients we didn't negotiate a version with yet are legacy clients
+            assert!(!client
+                .is_feature_enabled_with_client(sealed_sender, &bundle)
+                .unwrap());
+
+            // a 0.1.0 client is refused 0.2.0 features
+            client
+                .update_client_protocol_version(&key, "0.1.0")
+                .await
+                .unwrap();
+            assert!(!client
+                .is_feature_enabled_with_client(sealed_sender, &bundle)
+                .unwrap());
+
+            client
+                .update_client_protocol_version(&key, SNP_PROTOCOL_VERSION)
+                .await
+                .unwrap();
+            assert!(client
+                .is_feature_enabled_with_client(sealed_sender, &bundle)
+                .unwrap());
+
+            // and so is a client served by a 0.1.0 provider
+            let bundle = client_bundle(&key, "0.1.0");
+            assert!(!client
+                .is_feature_enabled_with_client(sealed_sender, &bundle)
+                .unwrap());
+        });
+    }
+}
@@ -4,12 +4,14 @@
 
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, bail, Result};
-use base::api_types_extensions::{Signed, SignedWithExternalVerifier};
+use base::api_types_extensions::{NetworkScoped, Signed, SignedWithExternalVerifier};
 use base::hex_utils::short_hex_string;
-use base::snp::snp_server_api::{Message, NewSessionRequest};
+use base::protocol_version::VersionRange;
+use base::snp::snp_server_api::dr_message::Data;
+use base::snp::snp_server_api::{Message, NewSessionRequest, SealedSenderMessage};
 use bytes::Bytes;
 use common::dr_service::DRService;
-use common::network_salt;
+use common::network_salt::{net_id, net_salt};
 use common::typed_msg_extensions::TypedMessageExtensions;
 use common::x2dh_service::{ExecuteProtocolAsBob, X2DHService};
 use crypto::x2dh::{ProtocolInputBob, ProtocolOutputBob};
@@ -23,15 +25,20 @@ use xactor::*;
 impl SimpleClient {
     /// Handle a new DR session request + message from another client on the network (not current provider)
     /// Must only be called from SimpleClient Actor handlers
+    /// When the request was in a sealed-sender message, the sealed sender must be the request's sender.
     pub(crate) async fn handle_new_session_req_from_entity(
         &mut self,
         req_data: NewSessionRequest,
+        sealed_sender: Option<ed25519_dalek::PublicKey>,
     ) -> Result<()> {
         debug!("hello :-)");
 
         //
         // In this flow we are Bob (b) and the other party which initiated the protocol is Alice (a).
 
+        req_data.verify_net_id(net_id())?;
+        VersionRange::snp_protocol().negotiate_with(&req_data.protocol_version)?;
+
         // check that caller is using our only client bundle id
         let client_bundle = self
             .client_bundle
@@ -53,11 +60,17 @@ impl SimpleClient {
         let ikb_pair = Keypair::from_bytes(self.client_id.to_bytes().as_ref())
             .map_err(|_| anyhow!("invalid data"))?;
 
+        // x2dh rejects classic requests when we published a hybrid pre-key
         let input_bob = ProtocolInputBob {
             eka,
+            kem_ct: req_data.get_kem_ct(),
             ikb_pair,
             pkb_private: self.pre_key.clone(),
             b_bundle_id: req_data.receiver_bundle_id,
+            kem_pkb_private: self
+                .kem_pre_key
+                .as_ref()
+                .map(|kem_pre_key| kem_pre_key.decapsulation_key.clone()),
         };
 
         // step 3 - call X2DHService to execute x2dh with Alice (obtain AD and shared secret)
@@ -71,7 +84,7 @@ impl SimpleClient {
 
         // step 4 - Start a new DR ratchet with Alice using (shared secret, AD) from X2DH output
         let root_chain_key = ChainKey::from(x2dh_output_bob.shared_secret);
-        let session_key = SessionKey::from(network_salt::NET_SALT.to_vec().as_slice());
+        let session_key = SessionKey::from(net_salt().to_vec().as_slice());
         let session_id = req_data
             .get_dr_session_id()
             .map_err(|e| anyhow!(format!("missing session id: {:?}", e)))?;
@@ -112,9 +125,11 @@ impl SimpleClient {
 
         debug!("Caller public id: {}", short_hex_string(ika.as_ref()));
 
+        verify_sealed_sender(sealed_sender, &ika)?;
+
         // verify the whole request
         req_data
-            .verify_signature(&ika)
+            .verify_signature(ika.as_bytes())
             .map_err(|_| anyhow!("Failed to authenticate message"))?;
 
         typed_message.verify_signature()?;
@@ -129,16 +144,18 @@ impl SimpleClient {
 
     /// Handle a new message in what the sender claims is an existing dr session between him and this client.
     /// Must only be called from SimpleClient Actor handlers
+    /// When the message was in a sealed-sender message, the sealed sender must be the message's sender.
     pub(crate) async fn handle_new_dr_message_from_entity(
         &mut self,
         message: Message,
+        sealed_sender: Option<ed25519_dalek::PublicKey>,
     ) -> Result<()> {
         debug!("hello :-)");
         let header = message
             .header
             .as_ref()
             .ok_or_else(|| anyhow!("missing header"))?;
-        let (mut dr, ika) = DRService::get_dr_session_by_id(header.session_id)
+        let (dr, ika) = DRService::get_dr_session_by_id(header.session_id)
             .await?
             .ok_or_else(|| anyhow!("failed to load dr session"))?;
 
@@ -147,13 +164,16 @@ impl SimpleClient {
             .get_sender_dr_pub_key()
             .map_err(|_| anyhow!("invalid sender dr pub key"))?;
 
-        if header.count == 0 {
-            // Bob performs a full ratchet step with Alice's pub dr key per the protocol if send count is 0
-            dr.ratchet(&mut OsRng, &alice_pub_dr_key, header.prev_count)
-                .map_err(|_| anyhow!("invalid dr data"))?;
-        }
-
-        let bob_receive_key = dr.get_receiving_key(header.count)?;
+        // Bob performs a full ratchet step with Alice's pub dr key per the protocol if it is new.
+        // The updated session is saved once the message was decrypted and authenticated
+        let (dr, bob_receive_key) = dr
+            .get_message_receiving_key(
+                &mut OsRng,
+                &alice_pub_dr_key,
+                header.prev_count,
+                header.count,
+            )
+            .map_err(|_| anyhow!("invalid dr data"))?;
         let typed_message = TypedMessageExtensions::decrypt_msg(
             message.enc_typed_msg.as_slice(),
             &bob_receive_key,
@@ -175,8 +195,55 @@ impl SimpleClient {
             bail!("sender id mismatch between message and stored dr session")
         }
 
+        verify_sealed_sender(sealed_sender, &ika)?;
+
         typed_message.verify_signature()?;
         DRService::save_dr_session(ika, dr).await?;
         Ok(self.dispatch_incoming_client_message(typed_message).await?)
     }
 }
+
+impl SimpleClient {
+    /// Handle a sealed-sender message from another client. Providers which delivered it don't know who sent it.
+    /// Must only be called from SimpleClient Actor handlers
+    pub(crate) async fn handle_sealed_sender_message_from_entity(
+        &mut self,
+        message: SealedSenderMessage,
+    ) -> Result<()> {
+        let (sender, dr_message) = self.unseal_incoming_message(message)?;
+
+        debug!(
+            "Sealed sender public id: {}",
+            short_hex_string(sender.as_ref())
+        );
+
+        match dr_message
+            .data
+            .ok_or_else(|| anyhow!("missing sealed message data"))?
+        {
+            Data::Message(msg) => {
+                self.handle_new_dr_message_from_entity(msg, Some(sender))
+                    .await
+            }
+            Data::NewSessionRequest(msg) => {
+                self.handle_new_session_req_from_entity(msg, Some(sender))
+                    .await
+            }
+            Data::SealedSenderMessage(_) => bail!("unexpected nested sealed-sender message"),
+            Data::GroupMessage(_) => bail!("unexpected group message in sealed-sender message"),
+        }
+    }
+}
+
+/// Verify that the sender of a sealed-sender message is the sender authenticated by the sealed dr message
+fn verify_sealed_sender(
+    sealed_sender: Option<ed25519_dalek::PublicKey>,
+    sender: &ed25519_dalek::PublicKey,
+) -> Result<()> {
+    match sealed_sender {
+        Some(sealed_sender) if sealed_sender != *sender => {
+            bail!("sender id mismatch between sealed-sender message and dr message")
+        }
+        _ => Ok(()),
+    }
+}
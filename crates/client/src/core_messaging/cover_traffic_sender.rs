@@ -0,0 +1,83 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::Result;
+use base::snp::snp_server_api::MessageType;
+use common::message_padding;
+use rand::Rng;
+use std::time::Duration;
+use tokio::time::sleep;
+use xactor::*;
+
+/// Approximate bytes a cover traffic typed message adds to its data: sender, receiver, signature and time stamp
+const COVER_MESSAGE_OVERHEAD: usize = 200;
+
+impl SimpleClient {
+    /// Periodically send cover traffic to our provider at an average interval.
+    /// Each delay is randomized around the interval so cover messages don't stand out from real ones.
+    pub(crate) async fn send_cover_traffic(interval: Duration) {
+        loop {
+            let delay = interval.mul_f64(rand::thread_rng().gen_range(0.5, 1.5));
+            sleep(delay).await;
+
+            let res = match SimpleClient::from_registry().await {
+                Ok(client) => client.call(SendCoverMessage).await.and_then(|r| r),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = res {
+                debug!("failed to send cover traffic message: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Send one cover traffic message with random data to our provider, if we have one
+#[message(result = "Result<()>")]
+pub(crate) struct SendCoverMessage;
+
+#[async_trait::async_trait]
+impl Handler<SendCoverMessage> for SimpleClient {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, _msg: SendCoverMessage) -> Result<()> {
+        if self.provider_net_client.is_none() {
+            return Ok(());
+        }
+
+        // the cover message is padded to a random padding bucket like real messages
+        let len = message_padding::random_bucket_len(&mut rand::thread_rng());
+        let mut data = vec![0u8; len.saturating_sub(COVER_MESSAGE_OVERHEAD)];
+        rand::thread_rng().fill(data.as_mut_slice());
+
+        // provider discards the message and returns an empty response
+        self.send_message_to_provider(MessageType::CoverTrafficRequest, data)
+            .await?;
+
+        debug!("sent cover traffic message to provider");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    /// A cover message's typed message is about as long as its sampled length so it is padded to its bucket
+    #[test]
+    fn test_cover_message_overhead() {
+        let client = SimpleClient::default();
+        let receiver = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+        let typed_msg = client
+            .create_typed_message(
+                MessageType::CoverTrafficRequest,
+                vec![1u8; 1000],
+                receiver.public,
+            )
+            .unwrap();
+        let overhead = typed_msg.encoded_len() - 1000;
+        assert!(overhead <= COVER_MESSAGE_OVERHEAD);
+        assert!(overhead + 8 > COVER_MESSAGE_OVERHEAD);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_server_api::{
+    FanOutMessageRequest, FanOutMessageResponse, GroupMessage, MessageType,
+};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::DeliveryFailureEvent;
+use prost::Message;
+
+impl SimpleClient {
+    /// Send a group message to group members. The message is sent once to our provider which forwards it to each
+    /// member via the member's provider. Publishes a delivery failure event for each member it can't be delivered to
+    pub(crate) async fn send_group_message(
+        &mut self,
+        message: GroupMessage,
+        receivers: &[Vec<u8>],
+    ) -> Result<()> {
+        let mut bundles = vec![];
+        for receiver in receivers.iter() {
+            let res = match self.other_clients.get(receiver) {
+                Some(bundle) => self.check_contact_identity(receiver).and_then(|_| {
+                    bundle
+                        .client_bundle
+                        .clone()
+                        .ok_or_else(|| anyhow!("missing client bundle"))
+                }),
+                None => Err(anyhow!("I don't know about this client")),
+            };
+
+            match res {
+                Ok(bundle) => bundles.push(bundle),
+                Err(e) => {
+                    warn!(
+                        "can't send group message to member {}: {:?}",
+                        short_hex_string(receiver),
+                        e
+                    );
+                    let receiver_id = self.get_other_client_entity(receiver);
+                    self.publish_delivery_failure(receiver_id, format!("{:#}", e));
+                }
+            }
+        }
+
+        if bundles.is_empty() {
+            return Ok(());
+        }
+
+        let req = FanOutMessageRequest {
+            group_message: Some(message),
+            receivers: bundles,
+        };
+
+        debug!("sending fan out message request to our provider...");
+
+        let resp = self
+            .send_message_to_provider(MessageType::FanOutMessageRequest, req.encode_to_vec())
+            .await?;
+
+        if resp.msg_type != MessageType::FanOutMessageResponse as i32 {
+            bail!("unexpected response type")
+        }
+
+        let resp = FanOutMessageResponse::decode(resp.message.as_slice())?;
+        for receiver_id in resp.failed_receivers {
+            self.publish_delivery_failure(
+                receiver_id,
+                "provider failed to forward the group message".into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn publish_delivery_failure(
+        &mut self,
+        receiver_id: base::snp::snp_core_types::EntityId,
+        error: String,
+    ) {
+        self.publish_event(Event::DeliveryFailure(DeliveryFailureEvent {
+            receiver_id: Some(receiver_id),
+            message_type: MessageType::ChannelMessage as i32,
+            error,
+        }));
+    }
+}
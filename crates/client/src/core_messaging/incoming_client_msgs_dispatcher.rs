@@ -13,12 +13,53 @@ impl SimpleClient {
         &mut self,
         msg: TypedMessage,
     ) -> Result<()> {
+        // messages from a contact with a changed identity key are blocked until the user verifies it
+        self.check_contact_identity(msg.get_ika()?.as_ref())?;
+
+        let key = msg.get_ika()?.as_ref().to_vec();
+
+        // protocol features we use in messages to the sender depend on the version it implements
+        self.update_client_protocol_version(&key, &msg.sender_protocol_version)
+            .await?;
+
+        // sender's delivery token lets us send it sealed-sender messages
+        if !msg.sender_delivery_token.is_empty()
+            && self.other_clients_delivery_tokens.get(&key) != Some(&msg.sender_delivery_token)
+        {
+            SimpleClient::store_delivery_token(&key, &msg.sender_delivery_token).await?;
+            self.other_clients_delivery_tokens
+                .insert(key, msg.sender_delivery_token.clone());
+        }
+
         match msg.msg_type {
             t if t == MessageType::TextMessageRequest as i32 => self.handle_text_message(msg).await,
 
+            t if t == MessageType::AttachmentChunk as i32 => {
+                self.handle_attachment_chunk(msg).await
+            }
+
+            t if t == MessageType::MessageReceipts as i32 => {
+                self.handle_message_receipts(msg).await
+            }
+
             t if t == MessageType::ChannelMessage as i32 => {
                 self.handle_new_incoming_channel_message(msg).await
             }
+            t if t == MessageType::GroupSenderKey as i32 => self.handle_group_sender_key(msg).await,
+
+            t if t == MessageType::GroupMembersUpdate as i32 => {
+                self.handle_group_members_update(msg).await
+            }
+
+            // Request from a group co-admin to change the membership of a group created by this client
+            t if t == MessageType::GroupAdminRequest as i32 => {
+                self.handle_group_admin_request(msg).await
+            }
+
+            t if t == MessageType::GroupAdminResponse as i32 => {
+                self.handle_group_admin_response(msg).await
+            }
+
             t if t == MessageType::ChannelSubscribeRequest as i32 => {
                 // handles both status updates and group join requests
                 self.handle_subscribe_to_channel_message(msg).await
@@ -39,12 +80,29 @@ impl SimpleClient {
                 self.handle_unsubscribe_response_message(msg).await
             }
 
+            t if t == MessageType::ChannelPaymentReminder as i32 => {
+                self.handle_channel_payment_reminder(msg).await
+            }
+
+            t if t == MessageType::ChannelSubscriptionRenewal as i32 => {
+                self.handle_channel_subscription_renewal(msg).await
+            }
+
             // Request to a creator client from a provided client to post a message
             // to status update or group
             t if t == MessageType::ChannelMessageRequest as i32 => {
                 self.handle_channel_message_request(msg).await
             }
 
+            // Request from a subscriber or a group member for the content of a channel created by this client
+            t if t == MessageType::ChannelHistoryRequest as i32 => {
+                self.handle_channel_history_request(msg).await
+            }
+
+            t if t == MessageType::ChannelHistoryResponse as i32 => {
+                self.handle_channel_history_response(msg).await
+            }
+
             t if t == MessageType::BuyItemRequest as i32 => self.handle_buy_item_request(msg).await,
 
             t if t == MessageType::ListPaidItemsRequest as i32 => {
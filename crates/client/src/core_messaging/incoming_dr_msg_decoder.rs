@@ -6,8 +6,9 @@ use crate::simple_client::SimpleClient;
 
 use anyhow::{anyhow, Result};
 use base::api_types_extensions::Signed;
-use base::snp::snp_server_api::{Message, TypedMessage};
+use base::snp::snp_server_api::{DrMessage, Message, SealedSenderMessage, TypedMessage};
 use common::dr_service::DRService;
+use common::sealed_sender::unseal_message;
 use common::typed_msg_extensions::TypedMessageExtensions;
 use rand_core::OsRng;
 use std::convert::TryFrom;
@@ -21,7 +22,7 @@ impl SimpleClient {
             .await?
             .ok_or_else(|| anyhow!("didn't find existing dr session with sender"))?;
 
-        let mut dr = dr_session.0;
+        let dr = dr_session.0;
         let sender_pub_key = dr_session.1;
 
         let key_data = dr_header
@@ -31,16 +32,14 @@ impl SimpleClient {
         use crypto::utils::X25519PublicKeyWrapper;
         let bob_dr_key_wrapper = X25519PublicKeyWrapper::try_from(key_data.key.as_slice()).unwrap();
 
-        if dr_header.count == 0 {
-            // we only should ratchet and update with new dr pub key if sending chain key is != 0
-            dr.ratchet(
-                &mut OsRng,
-                &bob_dr_key_wrapper.0.clone(),
-                dr_header.prev_count,
-            )?;
-        }
-
-        let receive_key = dr.get_receiving_key(dr_header.count).unwrap();
+        // we only ratchet when the message has a new dr pub key. The updated session is saved once the
+        // message was decrypted and authenticated
+        let (dr, receive_key) = dr.get_message_receiving_key(
+            &mut OsRng,
+            &bob_dr_key_wrapper.0,
+            dr_header.prev_count,
+            dr_header.count,
+        )?;
         let ad = dr.get_ad().map_err(|_| anyhow!("missing ad"))?;
 
         let typed_message = TypedMessageExtensions::decrypt_msg(
@@ -53,4 +52,18 @@ impl SimpleClient {
         DRService::save_dr_session(sender_pub_key, dr).await?;
         Ok(typed_message)
     }
+
+    /// Open a sealed-sender message designated to this client using our pre-key.
+    /// Returns the authenticated sender id and the sealed dr message.
+    pub(crate) fn unseal_incoming_message(
+        &self,
+        message: SealedSenderMessage,
+    ) -> Result<(ed25519_dalek::PublicKey, DrMessage)> {
+        let content = unseal_message(&message, &self.client_id.public, &self.pre_key)?;
+        let sender = content.get_sender_pub_key()?;
+        let dr_message = content
+            .dr_message
+            .ok_or_else(|| anyhow!("missing sealed dr message"))?;
+        Ok((sender, dr_message))
+    }
 }
@@ -2,7 +2,10 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+mod client_protocol_versions;
 mod client_session_handler;
+mod cover_traffic_sender;
+mod group_msg_sender;
 mod incoming_client_msgs_dispatcher;
 mod incoming_dr_msg_decoder;
 mod msg_to_provider_sender;
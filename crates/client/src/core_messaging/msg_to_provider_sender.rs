@@ -4,7 +4,10 @@
 
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, bail, Result};
+use base::request_rejection::status_error;
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
 use base::snp::snp_server_api::{MessageRequest, MessageType, TypedMessage};
+use tonic::transport::Channel;
 
 impl SimpleClient {
     /// Send a message to client's provider using an existing DR session, and return the response.
@@ -21,13 +24,33 @@ impl SimpleClient {
             .ok_or_else(|| anyhow!("missing provider bundle"))?;
 
         let ikb = provider_bundle.get_provider_id_ed25519_public_key()?;
-        let typed_msg = self.create_typed_message(msg_type, msg_data, ikb)?;
-        let message = self.create_message_to_receiver(ikb, typed_msg).await?;
 
-        let provider_api_service = self
+        let mut provider_api_service = self
             .provider_net_client
-            .as_mut()
-            .ok_or_else(|| anyhow!("missing provider net client"))?;
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing provider net client"))?
+            .clone();
+
+        self.send_message_to_provider_with_client(
+            ikb,
+            &mut provider_api_service,
+            msg_type,
+            msg_data,
+        )
+        .await
+    }
+
+    /// Send a message to a provider we have a DR session with over a provided net client, and return the response.
+    /// Used to talk with a provider which is not (or no longer) this client's current provider
+    pub(crate) async fn send_message_to_provider_with_client(
+        &mut self,
+        ikb: ed25519_dalek::PublicKey,
+        provider_api_service: &mut ProviderCoreServiceClient<Channel>,
+        msg_type: MessageType,
+        msg_data: Vec<u8>,
+    ) -> Result<TypedMessage> {
+        let typed_msg = self.create_typed_message(msg_type, msg_data, ikb)?;
+        let message = self.create_message_to_receiver(ikb, typed_msg).await?;
 
         debug!("sending message to provider...");
 
@@ -36,7 +59,7 @@ impl SimpleClient {
                 message: Some(message),
             }))
             .await
-            .map_err(|e| anyhow!("got an error response: {:?}", e))?
+            .map_err(status_error)?
             .into_inner();
 
         debug!("got provider response...");
@@ -3,13 +3,13 @@
 //
 
 use crate::simple_client::{SimpleClient, SNP_PROTOCOL_VERSION};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use base::api_types_extensions::SignedWithExternalVerifier;
 use base::snp::snp_core_types::{ClientIdentityBundle, EntityId, PublicKey};
 use base::snp::snp_server_api::{DrSessionHeader, NewSessionRequest, TypedMessage};
 use chrono::prelude::*;
 use common::dr_service::DRService;
-use common::network_salt::NET_SALT;
+use common::network_salt::{net_id, net_salt};
 use common::typed_msg_extensions::TypedMessageExtensions;
 use crypto::x2dh;
 use crypto::x2dh::ProtocolInputAlice;
@@ -26,6 +26,12 @@ impl SimpleClient {
         bob_bundle: &ClientIdentityBundle, // recipient client
         message: TypedMessage,             // the message to send in this session
     ) -> Result<NewSessionRequest> {
+        let kem_pkb = bob_bundle
+            .pre_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client pre-key"))?
+            .get_kem_key()?;
+
         let ikb = bob_bundle.get_client_id_ed25519_public_key().unwrap();
         let pkb = bob_bundle.get_client_x25519_pre_key().unwrap();
 
@@ -34,15 +40,16 @@ impl SimpleClient {
             ikb,
             pkb,
             b_bundle_id: bob_bundle.time_stamp,
+            kem_pkb,
         };
 
         // Alice executes x2dh with bob and get the output
-        let output_alice = x2dh::execute_alice(&input_alice);
+        let output_alice = x2dh::execute_alice(&input_alice)?;
 
         //debug!("Alice x2dh output: {:?}", output_alice);
 
         // Alice creates a DR session with bob and using it to get the enc key for her first message with bob
-        let input = SessionKey::from(NET_SALT.as_ref());
+        let input = SessionKey::from(net_salt().as_ref());
         let dr_root_chain_key = ChainKey::from(output_alice.shared_secret.as_ref());
 
         let mut dr = DoubleRatchet::new_with_peer(
@@ -97,8 +104,10 @@ impl SimpleClient {
             message: Some(message),
             sender_signature: None,
             receiver_bundle_id: bob_bundle.time_stamp,
-            net_id: 0,
+            net_id: net_id(),
             protocol_version: SNP_PROTOCOL_VERSION.into(),
+            pow_nonce: 0,
+            sender_kem_ciphertext: output_alice.kem_ct.unwrap_or_default(),
         };
 
         // debug!("new session request: {:?}", new_session_request);
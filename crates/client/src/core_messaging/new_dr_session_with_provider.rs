@@ -6,11 +6,12 @@ use crate::simple_client::{SimpleClient, SNP_PROTOCOL_VERSION};
 use anyhow::{anyhow, Result};
 use base::api_types_extensions::{Signed, SignedWithExternalVerifier};
 use base::hex_utils::short_hex_string;
+use base::request_rejection::status_error;
 use base::snp::snp_core_types::{EntityId, PublicKey};
 use base::snp::snp_server_api::{DrSessionHeader, MessageType, NewSessionRequest, TypedMessage};
 use chrono::prelude::*;
 use common::dr_service::DRService;
-use common::network_salt::NET_SALT;
+use common::network_salt::{net_id, net_salt};
 use common::typed_msg_extensions::TypedMessageExtensions;
 use crypto::utils::X25519PublicKeyWrapper;
 use crypto::x2dh;
@@ -38,21 +39,27 @@ impl SimpleClient {
             .unwrap();
 
         let pkb = provider_bundle.get_provider_x25519_pre_key().unwrap();
+        let kem_pkb = provider_bundle
+            .pre_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing provider pre-key"))?
+            .get_kem_key()?;
 
         // Alice X2DH protocol input
         let input_alice = ProtocolInputAlice {
             ikb,
             pkb,
             b_bundle_id: provider_bundle.time_stamp,
+            kem_pkb,
         };
 
         // Alice executes x2dh with bob and get the output
-        let output_alice = x2dh::execute_alice(&input_alice);
+        let output_alice = x2dh::execute_alice(&input_alice)?;
 
         // debug!("Alice x2dh output: {:?}", output_alice);
 
         // Alice creates a DR session with bob and using it to get the enc key for her first message with bob
-        let input = SessionKey::from(NET_SALT.as_ref());
+        let input = SessionKey::from(net_salt().as_ref());
         let dr_root_chain_key = ChainKey::from(output_alice.shared_secret.as_ref());
 
         let mut alice_dr = DoubleRatchet::new_with_peer(
@@ -122,8 +129,13 @@ impl SimpleClient {
             message: Some(message),
             sender_signature: None,
             receiver_bundle_id: provider_bundle.time_stamp,
-            net_id: 0,
-            protocol_version: SNP_PROTOCOL_VERSION.into(),
+            net_id: net_id(),
+            protocol_version: self
+                .provider_protocol_version
+                .clone()
+                .unwrap_or_else(|| SNP_PROTOCOL_VERSION.into()),
+            pow_nonce: 0,
+            sender_kem_ciphertext: output_alice.kem_ct.unwrap_or_default(),
         };
 
         new_session_request.sign(&self.client_id)?;
@@ -136,11 +148,10 @@ impl SimpleClient {
         // save a clone so we can st;il use mutable alice_dr - we'll save it again later
         DRService::save_dr_session(ikb, alice_dr.clone()).await?;
 
-        let response = provider_api_client
-            .new_session(tonic::Request::new(new_session_request))
+        let response = new_session_request
+            .send(provider_api_client)
             .await
-            .expect("failed to send NewSession request to provider")
-            .into_inner();
+            .map_err(status_error)?;
 
         // Alice decrypts the response message using the dr session with the server bob
         // Validate it is the expected response to the original request message (get service terms)...
@@ -5,13 +5,14 @@
 use crate::simple_client::SimpleClient;
 use anyhow::Result;
 use base::api_types_extensions::Signed;
+use base::protocol_version::SNP_PROTOCOL_VERSION;
 use base::snp::snp_core_types::{EntityId, PublicKey};
 use base::snp::snp_server_api::{MessageType, TypedMessage};
 use chrono::prelude::*;
 
 impl SimpleClient {
     /// Create a new typed message from this client using msg data and type
-    /// Sign the message by this client, and sets its sender, receiver and timestamp
+    /// Sign the message by this client, and sets its sender, receiver, timestamp, our delivery token and our protocol version
     pub(crate) fn create_typed_message(
         &self,
         msg_type: MessageType,
@@ -45,6 +46,8 @@ impl SimpleClient {
             receiver: Some(b_entity),
             sender: Some(alice_entity),
             signature: None,
+            sender_delivery_token: self.delivery_token.clone(),
+            sender_protocol_version: SNP_PROTOCOL_VERSION.into(),
         };
 
         typed_msg.sign(&self.client_id)?;
@@ -16,7 +16,11 @@ use tonic::Streaming;
 use xactor::*;
 
 impl SimpleClient {
-    pub(crate) async fn provider_messages_handler(mut stream: Streaming<DrMessage>) {
+    /// Handle messages incoming on a provider messages stream. Reconnects when the stream ends.
+    pub(crate) async fn provider_messages_handler(
+        mut stream: Streaming<DrMessage>,
+        subscription_id: u64,
+    ) {
         debug!("setup process for receiving new message from provider");
         loop {
             match stream.message().await {
@@ -32,11 +36,20 @@ impl SimpleClient {
                             )
                         }
                     }
-                    None => debug!("expected a dr message"),
+                    None => {
+                        // provider closed the stream. e.g. it stopped serving this client
+                        debug!("provider messages stream ended");
+                        break;
+                    }
                 },
-                Err(e) => error!("error getting dr message from stream: {:?}", e),
+                Err(e) => {
+                    error!("error getting dr message from stream: {:?}", e);
+                    break;
+                }
             }
         }
+
+        SimpleClient::resubscribe_to_provider_messages(subscription_id).await;
     }
 }
 
@@ -51,7 +64,9 @@ impl Handler<IncomingDrMessage> for SimpleClient {
 
         let message = match msg.0.data.ok_or_else(|| anyhow!("missing data"))? {
             Data::Message(msg) => msg,
-            Data::NewSessionRequest(_) => bail!("unexpected message from provider"),
+            Data::NewSessionRequest(_) | Data::SealedSenderMessage(_) | Data::GroupMessage(_) => {
+                bail!("unexpected message from provider")
+            }
         };
 
         let typed_message = SimpleClient::decode_incoming_dr_message(message).await?;
@@ -119,17 +134,36 @@ impl SimpleClient {
 
         debug!("dispatching new message(s) from provider");
 
+        let cursor = meta_data
+            .messages_metadata
+            .iter()
+            .map(|m| m.cursor)
+            .max()
+            .unwrap_or_default();
+
         // Go over the response message and send them to the appropriate handler for processing
         for msg in delivery_resp.messages {
             let data = msg.data.ok_or_else(|| anyhow!("missing message data"))?;
             match data {
-                Data::Message(msg) => self.handle_new_dr_message_from_entity(msg).await?,
+                Data::Message(msg) => self.handle_new_dr_message_from_entity(msg, None).await?,
                 Data::NewSessionRequest(msg) => {
-                    self.handle_new_session_req_from_entity(msg).await?
+                    self.handle_new_session_req_from_entity(msg, None).await?
                 }
+                Data::SealedSenderMessage(msg) => {
+                    self.handle_sealed_sender_message_from_entity(msg).await?
+                }
+                Data::GroupMessage(msg) => self.handle_group_message(msg).await?,
             };
         }
 
+        self.send_delivery_receipts().await;
+
+        // messages up to this cursor won't be replayed when we subscribe again
+        if cursor > self.messages_cursor {
+            self.messages_cursor = cursor;
+            self.store_messages_cursor().await?;
+        }
+
         Ok(())
     }
 }
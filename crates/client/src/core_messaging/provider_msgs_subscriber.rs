@@ -7,12 +7,24 @@ use anyhow::{anyhow, Result};
 use base::snp::snp_server_api::{
     MessageType, SubscribeToClientMessagesRequest, SubscribeToClientMessagesRequestPayload,
 };
+use std::time::Duration;
+use tokio::time::sleep;
+use xactor::*;
+
+/// Delay before the first attempt to reconnect an ended provider messages stream
+const RESUBSCRIBE_MIN_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Max delay between attempts to reconnect an ended provider messages stream
+const RESUBSCRIBE_MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 impl SimpleClient {
-    /// Subscribe to messages this client provider has for us
+    /// Subscribe to messages this client provider has for us.
+    /// Provider replays metadata of messages it stored for us after our messages cursor.
     pub(crate) async fn subscribe_to_provider_messages(&mut self) -> Result<()> {
         // 1. prepare the request for the provider
-        let msg = SubscribeToClientMessagesRequestPayload {};
+        let msg = SubscribeToClientMessagesRequestPayload {
+            resume_cursor: self.messages_cursor,
+        };
         use prost::Message;
         let mut msg_data = Vec::with_capacity(msg.encoded_len());
         msg.encode(&mut msg_data).unwrap();
@@ -37,7 +49,10 @@ impl SimpleClient {
             .as_mut()
             .ok_or_else(|| anyhow!("missing provider net client"))?;
 
-        debug!("Sending subscription request to provider...");
+        debug!(
+            "Sending subscription request to provider. resume cursor: {}",
+            self.messages_cursor
+        );
 
         let req = SubscribeToClientMessagesRequest {
             dr_message: Some(dr_message),
@@ -51,9 +66,74 @@ impl SimpleClient {
 
         debug!("got provider response to subscription request - subscribing to messages...");
 
+        // a previous subscription which ends from now on doesn't reconnect
+        self.messages_subscription_id += 1;
+
         // spawn new task to handle messages incoming on the stream
-        tokio::spawn(SimpleClient::provider_messages_handler(response));
+        tokio::spawn(SimpleClient::provider_messages_handler(
+            response,
+            self.messages_subscription_id,
+        ));
 
         Ok(())
     }
+
+    /// Reconnect an ended provider messages stream with exponential backoff.
+    /// Stops when reconnected or when the subscription was replaced, e.g. when client switched provider.
+    pub(crate) async fn resubscribe_to_provider_messages(subscription_id: u64) {
+        let mut backoff = RESUBSCRIBE_MIN_BACKOFF;
+        loop {
+            sleep(backoff).await;
+
+            let res = match SimpleClient::from_registry().await {
+                Ok(client) => client
+                    .call(ResubscribeToProviderMessages { subscription_id })
+                    .await
+                    .and_then(|r| r),
+                Err(e) => Err(e),
+            };
+
+            match res {
+                Ok(true) => {
+                    info!("reconnected to provider messages stream");
+                    return;
+                }
+                Ok(false) => {
+                    debug!("provider messages subscription was replaced");
+                    return;
+                }
+                Err(e) => warn!(
+                    "failed to reconnect to provider messages stream: {:?}. retrying in {:?}",
+                    e, backoff
+                ),
+            }
+
+            backoff = std::cmp::min(backoff * 2, RESUBSCRIBE_MAX_BACKOFF);
+        }
+    }
+}
+
+/// Subscribe again to provider messages if a subscription is still the current one.
+/// Returns false if the subscription was replaced.
+#[message(result = "Result<bool>")]
+pub(crate) struct ResubscribeToProviderMessages {
+    pub(crate) subscription_id: u64,
+}
+
+#[async_trait::async_trait]
+impl Handler<ResubscribeToProviderMessages> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: ResubscribeToProviderMessages,
+    ) -> Result<bool> {
+        if msg.subscription_id != self.messages_subscription_id
+            || self.provider_net_client.is_none()
+        {
+            return Ok(false);
+        }
+
+        self.subscribe_to_provider_messages().await?;
+        Ok(true)
+    }
 }
@@ -5,23 +5,63 @@
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, bail, Result};
 use base::hex_utils::short_hex_string;
-use base::snp::snp_core_types::{EntityId, PublicKey};
+use base::protocol_version::ProtocolFeature;
+use base::snp::snp_core_types::{
+    ClientIdentityBundle, DialupInfo, EntityId, ProviderSignedClientIdentityBundle, PublicKey,
+};
 use base::snp::snp_server_api::dr_message::Data;
 use base::snp::snp_server_api::{
     DrMessage, ForwardMessagePayload, ForwardMessageRequest, MessageType, RouteMessageRequest,
     TypedMessage,
 };
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::DeliveryFailureEvent;
 use bytes::Bytes;
 use common::aead::AEAD;
 use common::dr_service::DRService;
+use common::sealed_sender::seal_message;
 use rand_core::OsRng;
 
 impl SimpleClient {
-    /// Send a typed message to another client
+    /// Send a typed message to another client. Publishes a delivery failure event when it can't be delivered
     pub(crate) async fn send_typed_message(
         &mut self,
         msg: TypedMessage,
         receiver_id: Bytes,
+    ) -> Result<()> {
+        self.send_expiring_typed_message(msg, receiver_id, 0).await
+    }
+
+    /// Send a typed message to another client with an expiry hint to the receiver's provider.
+    /// The provider drops the message if it wasn't delivered by the expires time (unix nanos). 0 for no expiry
+    pub(crate) async fn send_expiring_typed_message(
+        &mut self,
+        msg: TypedMessage,
+        receiver_id: Bytes,
+        expires: u64,
+    ) -> Result<()> {
+        let message_type = msg.msg_type;
+        let res = self
+            .deliver_typed_message(msg, receiver_id.clone(), expires)
+            .await;
+
+        if let Err(e) = res.as_ref() {
+            let receiver_id = self.get_other_client_entity(receiver_id.as_ref());
+            self.publish_event(Event::DeliveryFailure(DeliveryFailureEvent {
+                receiver_id: Some(receiver_id),
+                message_type,
+                error: format!("{:#}", e),
+            }));
+        }
+
+        res
+    }
+
+    async fn deliver_typed_message(
+        &mut self,
+        msg: TypedMessage,
+        receiver_id: Bytes,
+        expires: u64,
     ) -> Result<()> {
         // In this flow, we are A, SA is our service provider. We send a message to B where SB is its service provider.
         // Note that we assume to B is not server by SA. In the case it does, we need to execute a different simpler flow
@@ -37,8 +77,13 @@ impl SimpleClient {
             .ok_or_else(|| anyhow!("I don't know about this client"))?
             .clone();
 
-        let b_bundle = sb_bundle.client_bundle.as_ref().unwrap();
+        let b_bundle = sb_bundle
+            .client_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client bundle"))?;
         let b_pub_key = b_bundle.get_client_id_public_key().unwrap();
+        self.check_contact_identity(b_pub_key.key.as_ref())?;
+
         let b_entity = EntityId {
             public_key: Some(b_pub_key.clone()),
             nickname: "Bob".to_string(),
@@ -55,17 +100,71 @@ impl SimpleClient {
             }
             None => {
                 debug!("no existing dr session with receiver client - starting a new one...");
-                let new_session_request =
-                    self.new_session_message_to_client(&b_bundle, msg).await?;
+                let new_session_request = self.new_session_message_to_client(b_bundle, msg).await?;
 
                 Data::NewSessionRequest(new_session_request)
             }
         };
 
+        let dr_message = DrMessage { data: Some(data) };
+
+        if let Err(e) = self
+            .route_message_to_client(b_bundle, b_entity.clone(), dr_message.clone(), expires)
+            .await
+        {
+            // Receiver might have moved to another provider. Get its current bundle and retry once via its new provider
+            warn!("failed to route message to receiver: {:?}", e);
+
+            match self.get_newer_client_bundle(b_bundle).await? {
+                Some(new_bundle) => {
+                    info!("receiver moved to another provider - retrying...");
+                    let b_bundle = new_bundle
+                        .client_bundle
+                        .as_ref()
+                        .ok_or_else(|| anyhow!("missing client bundle"))?;
+                    self.route_message_to_client(b_bundle, b_entity, dr_message, expires)
+                        .await?;
+                }
+                None => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Route a dr message to another client via this client's provider and the receiver's provider
+    async fn route_message_to_client(
+        &mut self,
+        b_bundle: &ClientIdentityBundle,
+        b_entity: EntityId,
+        dr_message: DrMessage,
+        expires: u64,
+    ) -> Result<()> {
+        // Seal the message when the receiver gave us a delivery token so SB can't learn who sent it.
+        // Otherwise, this is a first contact and we send it unsealed. Receiver gets our token in the message.
+        // Receivers and providers whose protocol version doesn't support sealed-sender get it unsealed too.
+        let sealed_sender_enabled =
+            self.is_feature_enabled_with_client(ProtocolFeature::SealedSender, b_bundle)?;
+        let (dr_message, delivery_token) = match self
+            .other_clients_delivery_tokens
+            .get(&b_bundle.get_client_id_public_key()?.key)
+        {
+            Some(token) if sealed_sender_enabled => {
+                let sealed_message = seal_message(&self.client_id, b_bundle, dr_message)?;
+                let dr_message = DrMessage {
+                    data: Some(Data::SealedSenderMessage(sealed_message)),
+                };
+                (dr_message, token.clone())
+            }
+            _ => (dr_message, vec![]),
+        };
+
         // The forward request payload we need to send to SB (via SA)
         let forward_message_payload = ForwardMessagePayload {
             receiver: Some(b_entity),
-            dr_message: Some(DrMessage { data: Some(data) }),
+            dr_message: Some(dr_message),
+            delivery_token,
+            expires,
         };
 
         // now we perform an EDH with SB. We use its published pre-key and a new ephemeral key we generate here
@@ -110,13 +209,12 @@ impl SimpleClient {
         // include SB dialup info for now in the request.
         // todo: SA can find SB dialup info by running Kad with SB's public id. Do this once Kad is implemented.
 
-        if spb_bundle.dial_up_info.is_empty() {
-            bail!("missing dialup info from bundle")
-        }
+        let spb_dialup_info = DialupInfo::best_grpc_endpoint(&spb_bundle.dial_up_info)
+            .ok_or_else(|| anyhow!("missing grpc dialup info from bundle"))?;
 
         let route_req = RouteMessageRequest {
             forward_message: Some(forward_req),
-            dialup_info: Some(spb_bundle.dial_up_info[0].clone()),
+            dialup_info: Some(spb_dialup_info.clone()),
         };
 
         let mut buff: Vec<u8> = Vec::with_capacity(route_req.encoded_len());
@@ -139,3 +237,44 @@ impl SimpleClient {
         Ok(())
     }
 }
+
+impl SimpleClient {
+    /// Returns the current bundle of another client published on the blockchain if it is newer than the one we have.
+    /// Rejects a bundle of any other client returned by the blockchain
+    async fn get_newer_client_bundle(
+        &mut self,
+        known_bundle: &ClientIdentityBundle,
+    ) -> Result<Option<ProviderSignedClientIdentityBundle>> {
+        if self.blockchain_service_client.is_none() {
+            return Ok(None);
+        }
+
+        let bundle = match self
+            .get_client_bundle_from_blockchain(known_bundle.get_client_entity()?)
+            .await?
+        {
+            Some(bundle) => bundle,
+            None => return Ok(None),
+        };
+
+        let client_bundle = bundle
+            .client_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client bundle"))?;
+
+        let key = bundle.get_client_id()?;
+        if known_bundle.get_client_id_public_key()?.key != key {
+            bail!("blockchain returned a bundle of another client")
+        }
+
+        if client_bundle.time_stamp <= known_bundle.time_stamp {
+            return Ok(None);
+        }
+
+        self.pin_contact_identity(&bundle).await?;
+        SimpleClient::store_other_client(&key, &bundle).await?;
+        self.other_clients.insert(key, bundle.clone());
+
+        Ok(Some(bundle))
+    }
+}
@@ -10,7 +10,11 @@ extern crate db;
 pub mod simple_client;
 
 mod channels;
+mod consts;
 mod core_messaging;
 mod paid_content;
 mod services;
 mod user_to_user_messaging;
+
+#[cfg(test)]
+mod test_helpers;
@@ -7,6 +7,8 @@ use anyhow::{anyhow, Result};
 use base::hex_utils::short_hex_string;
 use base::snp::snp_client_to_client::{BuyItemRequest, BuyItemResponse, BuyItemResult};
 use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::ItemPurchasedEvent;
 use bytes::Bytes;
 
 impl SimpleClient {
@@ -22,28 +24,25 @@ impl SimpleClient {
             req.item_id,
         );
 
-        let resp_msg = BuyItemResponse {
+        let mut resp_msg = BuyItemResponse {
             result: BuyItemResult::Success as i32,
             receipt_id: 0,
             item: None,
         };
 
-        // todo: verify the transaction...
-        /*
-        match req.receipt {
-            Some(receipt) => {
-                // todo: fully verify the L2 receipt
-                if let Some(item) = self.paid_items.get(&req.item_id) {
-                    resp_msg.item = Some(item.clone());
-                    resp_msg.receipt_id = receipt.id;
-                } else {
-                    resp_msg.result = BuyItemResult::ItemNotFound as i32;
+        match self.paid_items.get(&req.item_id).cloned() {
+            Some(item) => match self
+                .accept_payment(req.transaction_id, sender_id.as_ref(), item.price, item.id)
+                .await
+            {
+                Ok(()) => resp_msg.item = Some(item),
+                Err(e) => {
+                    warn!("rejecting payment for item {}: {}", req.item_id, e);
+                    resp_msg.result = BuyItemResult::InvalidTransaction as i32;
                 }
-            }
-            None => {
-                resp_msg.result = BuyItemResult::InvalidReceipt as i32;
-            }
-        }*/
+            },
+            None => resp_msg.result = BuyItemResult::ItemNotFound as i32,
+        }
 
         use prost::Message;
         let mut buff = Vec::with_capacity(resp_msg.encoded_len());
@@ -53,6 +52,15 @@ impl SimpleClient {
         let receiver_id = Bytes::from(sender_id.to_bytes().to_vec());
         self.send_typed_message(typed_msg, receiver_id).await?;
 
+        if resp_msg.item.is_some() {
+            self.publish_event(Event::ItemPurchased(ItemPurchasedEvent {
+                seller_id: Some(self.get_client_entity()?),
+                buyer_id: Some(self.get_other_client_entity(sender_id.as_ref())),
+                item_id: req.item_id,
+                item: None,
+            }));
+        }
+
         Ok(())
     }
 }
@@ -3,19 +3,28 @@
 //
 
 use crate::simple_client::SimpleClient;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use base::hex_utils::short_hex_string;
-use base::snp::snp_client_to_client::BuyItemResponse;
+use base::snp::snp_client_to_client::{BuyItemResponse, BuyItemResult};
 use base::snp::snp_server_api::TypedMessage;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::ItemPurchasedEvent;
 use prost::Message;
 
 impl SimpleClient {
     /// New content item from another client
-    pub(crate) async fn handle_incoming_item(&self, msg: TypedMessage) -> Result<()> {
+    pub(crate) async fn handle_incoming_item(&mut self, msg: TypedMessage) -> Result<()> {
         let sender_id = msg.get_ika()?;
         let item_resp: BuyItemResponse = BuyItemResponse::decode(msg.message.as_slice())
             .map_err(|e| anyhow!("failed to decode response {:?}", e))?;
 
+        if item_resp.result != BuyItemResult::Success as i32 {
+            bail!(
+                "seller didn't sell us the item: {:?}",
+                BuyItemResult::from_i32(item_resp.result)
+            )
+        }
+
         let item = item_resp
             .item
             .ok_or_else(|| anyhow!("missing item from response"))?;
@@ -27,6 +36,13 @@ impl SimpleClient {
             item.id,
         );
 
+        self.publish_event(Event::ItemPurchased(ItemPurchasedEvent {
+            seller_id: Some(self.get_other_client_entity(sender_id.as_ref())),
+            buyer_id: Some(self.get_client_entity()?),
+            item_id: item.id,
+            item: Some(item),
+        }));
+
         Ok(())
     }
 }
@@ -4,6 +4,7 @@
 
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, bail, Result};
+use base::hex_utils::short_hex_string;
 use base::snp::snp_client_to_client::BuyItemRequest;
 use base::snp::snp_core_types::EntityId;
 use base::snp::snp_server_api::MessageType;
@@ -38,41 +39,26 @@ impl Handler<BuyItem> for SimpleClient {
         let seller_bundle = sb_bundle.client_bundle.as_ref().unwrap();
         let seller_pub_key = seller_bundle.get_client_id_ed25519_public_key()?;
 
-        // we fake a payment and create a receipt for now
-        // todo: make an L2 payment here
-
-        let _seller_wallet_address = seller_bundle
-            .address
-            .as_ref()
-            .ok_or_else(|| anyhow!("missing seller current wallet address"))?;
-
-        let _my_wallet_address = self
-            .client_bundle
-            .as_ref()
-            .ok_or_else(|| anyhow!("missing client bundle"))?
+        let seller_address = seller_bundle
             .address
             .as_ref()
-            .ok_or_else(|| anyhow!("missing address from bundle"))?;
+            .ok_or_else(|| anyhow!("missing seller current wallet address"))?
+            .clone();
 
-        // TODO: create transaction and attach it to message
+        let transaction_id = self
+            .submit_payment(seller_address, msg.price, msg.item_id)
+            .await?;
 
-        /*
-        let receipt = Receipt {
-            id: OsRng.next_u64(),
-            time_stamp: Instant::now().seconds() as u64,
-            from: Some(my_wallet_address.clone()), // client's wallet address with sufficient L2 balance
-            to: Some(seller_wallet_address.clone()), // this needs to be the provider's wallet address as provided in its identity bundle
-            amount: Some(Amount {
-                value: msg.price,
-                coin_type: CoinType::Stable as i32,
-            }),
-            signature: None,
-            item_ids: vec![msg.item_id],
-        };*/
+        info!(
+            "paid {} coins for item {}. transaction id: {}",
+            msg.price,
+            msg.item_id,
+            short_hex_string(&transaction_id.id)
+        );
 
         let buy_item_message = BuyItemRequest {
             item_id: msg.item_id,
-            transaction_id: None,
+            transaction_id: Some(transaction_id),
         };
 
         use prost::Message;
@@ -50,6 +50,7 @@ impl SimpleClient {
         };
 
         item.sign(&self.client_id)?;
+        SimpleClient::store_paid_item(&item).await?;
         self.paid_items.insert(id, item);
         Ok(id)
     }
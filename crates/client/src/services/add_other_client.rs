@@ -3,9 +3,10 @@
 //
 
 use crate::simple_client::SimpleClient;
-use anyhow::Result;
-use base::api_types_extensions::Signed;
+use anyhow::{anyhow, Result};
+use base::api_types_extensions::{NetworkScoped, Signed};
 use base::snp::snp_core_types::ProviderSignedClientIdentityBundle;
+use common::network_salt::net_id;
 use xactor::*;
 
 #[message(result = "Result<()>")]
@@ -15,7 +16,14 @@ pub struct AddOtherClientBundle(pub ProviderSignedClientIdentityBundle);
 impl Handler<AddOtherClientBundle> for SimpleClient {
     async fn handle(&mut self, _ctx: &mut Context<Self>, msg: AddOtherClientBundle) -> Result<()> {
         msg.0.verify_signature()?;
+        msg.0
+            .client_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client bundle"))?
+            .verify_net_id(net_id())?;
+        self.pin_contact_identity(&msg.0).await?;
         let key = msg.0.get_client_id()?;
+        SimpleClient::store_other_client(&key, &msg.0).await?;
         self.other_clients.insert(key, msg.0);
         Ok(())
     }
@@ -0,0 +1,87 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::Result;
+use base::snp::upsetter_simple_client::{client_event, ClientEvent};
+use chrono::prelude::*;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tonic::Status;
+use xactor::*;
+
+/// Max number of events buffered for a subscriber which doesn't consume them
+const EVENTS_BUFFER_SIZE: usize = 256;
+
+pub(crate) type ClientEventsSender = mpsc::Sender<Result<ClientEvent, Status>>;
+
+impl SimpleClient {
+    /// Send an event to all events subscribers. Subscribers whose stream ended are removed
+    pub(crate) fn publish_event(&mut self, event: client_event::Event) {
+        let event = ClientEvent {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            event: Some(event),
+        };
+
+        self.events_subscribers
+            .retain(|subscriber| match subscriber.try_send(Ok(event.clone())) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    warn!("events subscriber is not consuming events - dropping event");
+                    true
+                }
+                Err(TrySendError::Closed(_)) => {
+                    debug!("events subscriber stream ended");
+                    false
+                }
+            });
+    }
+}
+
+/// Subscribe to this client's events. Returns a receiver of all events published from now on
+#[message(result = "Result<mpsc::Receiver<Result<ClientEvent, Status>>>")]
+pub(crate) struct SubscribeEvents;
+
+#[async_trait::async_trait]
+impl Handler<SubscribeEvents> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: SubscribeEvents,
+    ) -> Result<mpsc::Receiver<Result<ClientEvent, Status>>> {
+        let (tx, rx) = mpsc::channel(EVENTS_BUFFER_SIZE);
+        self.events_subscribers.push(tx);
+        Ok(rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::snp::upsetter_simple_client::DeliveryFailureEvent;
+
+    #[tokio::test]
+    async fn test_publish_event() {
+        let mut client = SimpleClient::default();
+        let (tx, mut rx) = mpsc::channel(EVENTS_BUFFER_SIZE);
+        let (closed_tx, closed_rx) = mpsc::channel(EVENTS_BUFFER_SIZE);
+        drop(closed_rx);
+        client.events_subscribers.push(tx);
+        client.events_subscribers.push(closed_tx);
+
+        client.publish_event(client_event::Event::DeliveryFailure(DeliveryFailureEvent {
+            receiver_id: None,
+            message_type: 0,
+            error: "unreachable".into(),
+        }));
+
+        // subscribers which ended are removed
+        assert_eq!(client.events_subscribers.len(), 1);
+
+        match rx.recv().await.unwrap().unwrap().event.unwrap() {
+            client_event::Event::DeliveryFailure(e) => assert_eq!(e.error, "unreachable"),
+            _ => panic!("unexpected event"),
+        }
+    }
+}
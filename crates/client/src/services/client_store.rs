@@ -0,0 +1,503 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::consts::*;
+use crate::services::contact_identities::ContactIdentity;
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, Result};
+use base::client_config_service::ClientConfigService;
+use base::hex_utils::short_hex_string;
+use base::server_config_service::TLS_CA_CERT_FILE_CONFIG_KEY;
+use base::snp::snp_blockchain::TransparencyLogHead;
+use base::snp::snp_core_types::{
+    ChannelBundle, ClientIdentityBundle, ContentItem, ProviderIdentityBundle,
+    ProviderSignedClientIdentityBundle, ServiceTermsBundle,
+};
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use byteorder::{BigEndian, ByteOrder};
+use bytes::Bytes;
+use crypto::ml_kem;
+use db::db_service::{DataItem, DatabaseService, DeleteItem, ReadAllItems, ReadItem, WriteItem};
+use ed25519_dalek::Keypair;
+use prost::Message;
+use rocksdb::{ColumnFamilyDescriptor, Options};
+use std::convert::TryInto;
+use x25519_dalek::StaticSecret;
+use xactor::*;
+
+/// Column families of the client's persistent state
+pub(crate) fn client_col_descriptors() -> Vec<ColumnFamilyDescriptor> {
+    vec![
+        CLIENT_CF,
+        OTHER_CLIENTS_CF,
+        DELIVERY_TOKENS_CF,
+        PROTOCOL_VERSIONS_CF,
+        CONTACTS_CF,
+        CHANNELS_SUBSCRIPTIONS_CF,
+        SUBSCRIPTIONS_PAID_THROUGH_CF,
+        CHANNELS_SUBSCRIPTIONS_REQUESTS_CF,
+        CHANNELS_CF,
+        PAID_ITEMS_CF,
+        PUBLISHED_BUNDLES_CF,
+        CONVERSATIONS_CF,
+        INBOX_CF,
+        SENT_MESSAGES_CF,
+        READ_RECEIPTS_OFF_CF,
+        USED_PAYMENTS_CF,
+        GROUP_SENDER_KEYS_CF,
+        MEMBERS_SENDER_KEYS_CF,
+        GROUP_MEMBERS_CF,
+    ]
+    .into_iter()
+    .map(|cf| ColumnFamilyDescriptor::new(cf, Options::default()))
+    .collect()
+}
+
+pub(crate) async fn write_item(cf: &'static str, key: &[u8], value: Vec<u8>) -> Result<()> {
+    DatabaseService::write(WriteItem {
+        data: DataItem {
+            key: Bytes::from(key.to_vec()),
+            value: Bytes::from(value),
+        },
+        cf,
+        ttl: 0,
+    })
+    .await
+    .map_err(|e| anyhow!("failed to store client data in {}: {}", cf, e))
+}
+
+pub(crate) async fn read_item(cf: &'static str, key: &[u8]) -> Result<Option<Bytes>> {
+    Ok(DatabaseService::read(ReadItem {
+        key: Bytes::from(key.to_vec()),
+        cf,
+    })
+    .await?
+    .map(|data| data.0))
+}
+
+pub(crate) async fn delete_item(cf: &'static str, key: &[u8]) -> Result<()> {
+    DatabaseService::delete(DeleteItem {
+        key: Bytes::from(key.to_vec()),
+        cf,
+    })
+    .await
+}
+
+/// Returns all (key, value) items stored in a column family
+pub(crate) async fn read_all_items(cf: &'static str) -> Result<Vec<(Bytes, Bytes)>> {
+    Ok(DatabaseService::read_all_items(ReadAllItems {
+        from: None,
+        max_results: 0,
+        cf,
+    })
+    .await?
+    .items
+    .into_iter()
+    .map(|(key, value)| (key, value.value))
+    .collect())
+}
+
+async fn read_message<M: Message + Default>(key: &str) -> Result<Option<M>> {
+    match read_item(CLIENT_CF, key.as_bytes()).await? {
+        Some(data) => Ok(Some(M::decode(data.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
+async fn write_message<M: Message>(key: &str, value: &Option<M>) -> Result<()> {
+    match value {
+        Some(value) => write_item(CLIENT_CF, key.as_bytes(), value.encode_to_vec()).await,
+        None => delete_item(CLIENT_CF, key.as_bytes()).await,
+    }
+}
+
+/// Persistent client state. The client db is the source of truth for its identity, its provider,
+/// its contacts, its channels and its paid items. In-memory state is loaded from it when the client starts
+/// and every change is written through to it, so a restarted client resumes with the same identity.
+impl SimpleClient {
+    /// Load our state from the client db. Stores our new identity when the db has none, e.g. on first run
+    pub(crate) async fn load_client_data(&mut self) -> Result<()> {
+        match read_item(CLIENT_CF, CLIENT_ID_KEY.as_bytes()).await? {
+            Some(data) => {
+                self.client_id = Keypair::from_bytes(data.as_ref())
+                    .map_err(|e| anyhow!("invalid stored client id: {:?}", e))?;
+
+                let pre_key: [u8; 32] = read_item(CLIENT_CF, PRE_KEY_KEY.as_bytes())
+                    .await?
+                    .ok_or_else(|| anyhow!("missing stored pre-key"))?
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| anyhow!("invalid stored pre-key"))?;
+                self.pre_key = StaticSecret::from(pre_key);
+
+                if let Some(data) = read_item(CLIENT_CF, DELIVERY_TOKEN_KEY.as_bytes()).await? {
+                    self.delivery_token = data.to_vec();
+                }
+
+                // an ml-kem pre-key we published stays valid even if we no longer create hybrid pre-keys
+                if let Some(data) = read_item(CLIENT_CF, KEM_PRE_KEY_KEY.as_bytes()).await? {
+                    let (encapsulation_key, decapsulation_key): (Vec<u8>, Vec<u8>) =
+                        bincode::deserialize(data.as_ref())?;
+                    self.kem_pre_key = Some(ml_kem::KeyPair {
+                        encapsulation_key,
+                        decapsulation_key,
+                    });
+                } else if self.kem_pre_key.is_some() {
+                    self.store_identity().await?;
+                }
+
+                info!(
+                    "loaded client pub id: {}",
+                    short_hex_string(self.client_id.public.as_ref())
+                );
+            }
+            None => {
+                self.store_identity().await?;
+                info!("stored new client identity");
+                return Ok(());
+            }
+        }
+
+        self.client_bundle = read_message::<ClientIdentityBundle>(CLIENT_BUNDLE_KEY).await?;
+        self.provider_bundle = read_message::<ProviderIdentityBundle>(PROVIDER_BUNDLE_KEY).await?;
+        self.provider_terms = read_message::<ServiceTermsBundle>(PROVIDER_TERMS_KEY).await?;
+        self.transparency_log_head =
+            read_message::<TransparencyLogHead>(TRANSPARENCY_LOG_HEAD_KEY).await?;
+        self.provider_protocol_version =
+            read_item(CLIENT_CF, PROVIDER_PROTOCOL_VERSION_KEY.as_bytes())
+                .await?
+                .map(|data| String::from_utf8_lossy(data.as_ref()).to_string());
+        self.messages_cursor = read_item(CLIENT_CF, MESSAGES_CURSOR_KEY.as_bytes())
+            .await?
+            .map(|data| BigEndian::read_u64(data.as_ref()))
+            .unwrap_or_default();
+
+        for (key, value) in read_all_items(OTHER_CLIENTS_CF).await? {
+            self.other_clients.insert(
+                key.to_vec(),
+                ProviderSignedClientIdentityBundle::decode(value.as_ref())?,
+            );
+        }
+
+        for (key, value) in read_all_items(DELIVERY_TOKENS_CF).await? {
+            self.other_clients_delivery_tokens
+                .insert(key.to_vec(), value.to_vec());
+        }
+
+        for (key, value) in read_all_items(PROTOCOL_VERSIONS_CF).await? {
+            self.other_clients_protocol_versions.insert(
+                key.to_vec(),
+                String::from_utf8_lossy(value.as_ref()).to_string(),
+            );
+        }
+
+        for (key, value) in read_all_items(CONTACTS_CF).await? {
+            self.contacts.insert(
+                String::from_utf8_lossy(key.as_ref()).to_string(),
+                bincode::deserialize::<ContactIdentity>(value.as_ref())?,
+            );
+        }
+
+        for (key, value) in read_all_items(CHANNELS_SUBSCRIPTIONS_CF).await? {
+            self.channels_subscriptions
+                .insert(key.to_vec(), ChannelBundle::decode(value.as_ref())?);
+        }
+
+        for (key, value) in read_all_items(SUBSCRIPTIONS_PAID_THROUGH_CF).await? {
+            self.subscriptions_paid_through
+                .insert(key.to_vec(), BigEndian::read_u64(value.as_ref()));
+        }
+
+        for (key, value) in read_all_items(CHANNELS_SUBSCRIPTIONS_REQUESTS_CF).await? {
+            self.channels_subscriptions_requests
+                .insert(key.to_vec(), ChannelBundle::decode(value.as_ref())?);
+        }
+
+        for (key, value) in read_all_items(PAID_ITEMS_CF).await? {
+            self.paid_items.insert(
+                BigEndian::read_u64(key.as_ref()),
+                ContentItem::decode(value.as_ref())?,
+            );
+        }
+
+        for (key, _) in read_all_items(PUBLISHED_BUNDLES_CF).await? {
+            self.published_bundles.insert(key.to_vec());
+        }
+
+        for (key, _) in read_all_items(READ_RECEIPTS_OFF_CF).await? {
+            self.read_receipts_off.insert(key.to_vec());
+        }
+
+        info!(
+            "loaded client data: {} other clients, {} contacts, {} channels subscriptions, {} paid items",
+            self.other_clients.len(),
+            self.contacts.len(),
+            self.channels_subscriptions.len(),
+            self.paid_items.len()
+        );
+
+        Ok(())
+    }
+
+    /// Store our long term id, pre-keys and delivery token
+    async fn store_identity(&self) -> Result<()> {
+        write_item(
+            CLIENT_CF,
+            CLIENT_ID_KEY.as_bytes(),
+            self.client_id.to_bytes().to_vec(),
+        )
+        .await?;
+        write_item(
+            CLIENT_CF,
+            PRE_KEY_KEY.as_bytes(),
+            self.pre_key.to_bytes().to_vec(),
+        )
+        .await?;
+        if let Some(kem_pre_key) = self.kem_pre_key.as_ref() {
+            write_item(
+                CLIENT_CF,
+                KEM_PRE_KEY_KEY.as_bytes(),
+                bincode::serialize(&(
+                    &kem_pre_key.encapsulation_key,
+                    &kem_pre_key.decapsulation_key,
+                ))?,
+            )
+            .await?;
+        }
+        write_item(
+            CLIENT_CF,
+            DELIVERY_TOKEN_KEY.as_bytes(),
+            self.delivery_token.clone(),
+        )
+        .await
+    }
+
+    /// Store our current provider, its terms and our client bundle with it
+    pub(crate) async fn store_provider_data(&self) -> Result<()> {
+        write_message(PROVIDER_BUNDLE_KEY, &self.provider_bundle).await?;
+        write_message(PROVIDER_TERMS_KEY, &self.provider_terms).await?;
+        write_message(CLIENT_BUNDLE_KEY, &self.client_bundle).await?;
+        match self.provider_protocol_version.as_ref() {
+            Some(version) => {
+                write_item(
+                    CLIENT_CF,
+                    PROVIDER_PROTOCOL_VERSION_KEY.as_bytes(),
+                    version.as_bytes().to_vec(),
+                )
+                .await?
+            }
+            None => delete_item(CLIENT_CF, PROVIDER_PROTOCOL_VERSION_KEY.as_bytes()).await?,
+        }
+        self.store_messages_cursor().await
+    }
+
+    pub(crate) async fn store_messages_cursor(&self) -> Result<()> {
+        let mut buf = [0; 8];
+        BigEndian::write_u64(&mut buf, self.messages_cursor);
+        write_item(CLIENT_CF, MESSAGES_CURSOR_KEY.as_bytes(), buf.to_vec()).await
+    }
+
+    pub(crate) async fn store_transparency_log_head(&self) -> Result<()> {
+        write_message(TRANSPARENCY_LOG_HEAD_KEY, &self.transparency_log_head).await
+    }
+
+    pub(crate) async fn store_published_bundle(bundle_hash: &[u8]) -> Result<()> {
+        write_item(PUBLISHED_BUNDLES_CF, bundle_hash, vec![]).await
+    }
+
+    pub(crate) async fn store_other_client(
+        key: &[u8],
+        bundle: &ProviderSignedClientIdentityBundle,
+    ) -> Result<()> {
+        write_item(OTHER_CLIENTS_CF, key, bundle.encode_to_vec()).await
+    }
+
+    pub(crate) async fn store_delivery_token(key: &[u8], token: &[u8]) -> Result<()> {
+        write_item(DELIVERY_TOKENS_CF, key, token.to_vec()).await
+    }
+
+    pub(crate) async fn store_protocol_version(key: &[u8], version: &str) -> Result<()> {
+        write_item(PROTOCOL_VERSIONS_CF, key, version.as_bytes().to_vec()).await
+    }
+
+    pub(crate) async fn store_contact(contact_id: &str, contact: &ContactIdentity) -> Result<()> {
+        write_item(
+            CONTACTS_CF,
+            contact_id.as_bytes(),
+            bincode::serialize(contact)?,
+        )
+        .await
+    }
+
+    pub(crate) async fn store_channel_subscription(
+        channel_id: &[u8],
+        bundle: &ChannelBundle,
+    ) -> Result<()> {
+        write_item(
+            CHANNELS_SUBSCRIPTIONS_CF,
+            channel_id,
+            bundle.encode_to_vec(),
+        )
+        .await
+    }
+
+    pub(crate) async fn delete_channel_subscription(channel_id: &[u8]) -> Result<()> {
+        delete_item(CHANNELS_SUBSCRIPTIONS_CF, channel_id).await
+    }
+
+    pub(crate) async fn store_subscription_paid_through(
+        channel_id: &[u8],
+        paid_through: u64,
+    ) -> Result<()> {
+        let mut value = [0; 8];
+        BigEndian::write_u64(&mut value, paid_through);
+        write_item(SUBSCRIPTIONS_PAID_THROUGH_CF, channel_id, value.to_vec()).await
+    }
+
+    pub(crate) async fn delete_subscription_paid_through(channel_id: &[u8]) -> Result<()> {
+        delete_item(SUBSCRIPTIONS_PAID_THROUGH_CF, channel_id).await
+    }
+
+    pub(crate) async fn store_channel_subscription_request(
+        channel_id: &[u8],
+        bundle: &ChannelBundle,
+    ) -> Result<()> {
+        write_item(
+            CHANNELS_SUBSCRIPTIONS_REQUESTS_CF,
+            channel_id,
+            bundle.encode_to_vec(),
+        )
+        .await
+    }
+
+    pub(crate) async fn delete_channel_subscription_request(channel_id: &[u8]) -> Result<()> {
+        delete_item(CHANNELS_SUBSCRIPTIONS_REQUESTS_CF, channel_id).await
+    }
+
+    pub(crate) async fn store_paid_item(item: &ContentItem) -> Result<()> {
+        let mut key = [0; 8];
+        BigEndian::write_u64(&mut key, item.id);
+        write_item(PAID_ITEMS_CF, &key, item.encode_to_vec()).await
+    }
+
+    pub(crate) async fn store_read_receipts_setting(key: &[u8], enabled: bool) -> Result<()> {
+        if enabled {
+            delete_item(READ_RECEIPTS_OFF_CF, key).await
+        } else {
+            write_item(READ_RECEIPTS_OFF_CF, key, vec![]).await
+        }
+    }
+
+    pub(crate) async fn is_used_payment(transaction_id: &[u8]) -> Result<bool> {
+        Ok(read_item(USED_PAYMENTS_CF, transaction_id).await?.is_some())
+    }
+
+    pub(crate) async fn store_used_payment(transaction_id: &[u8], payment_id: u64) -> Result<()> {
+        let mut value = [0; 8];
+        BigEndian::write_u64(&mut value, payment_id);
+        write_item(USED_PAYMENTS_CF, transaction_id, value.to_vec()).await
+    }
+}
+
+/// Reconnect to the provider we were served by before the client restarted and resume our messages subscription
+/// from the stored cursor. Our DR session with the provider is persisted by the DR service.
+#[message(result = "Result<()>")]
+pub(crate) struct ResumeProviderService;
+
+#[async_trait::async_trait]
+impl Handler<ResumeProviderService> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: ResumeProviderService,
+    ) -> Result<()> {
+        // a provider set since the client started replaced the stored one
+        if self.provider_net_client.is_some() {
+            return Ok(());
+        }
+
+        let info = self
+            .provider_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("no stored provider"))?
+            .dial_up_info
+            .first()
+            .ok_or_else(|| anyhow!("missing provider dialup info"))?
+            .clone();
+
+        info!("resuming service with provider {}", info.name);
+
+        let ca_cert_file = ClientConfigService::get(TLS_CA_CERT_FILE_CONFIG_KEY.into()).await?;
+        self.provider_net_client = Some(ProviderCoreServiceClient::new(
+            info.connect(ca_cert_file).await?,
+        ));
+
+        self.subscribe_to_provider_messages().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+
+    #[test]
+    fn test_store_and_load_client_data() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+            client.load_client_data().await.unwrap();
+
+            let key = vec![7u8; 32];
+            let mut contact = ContactIdentity::new(&key);
+            contact.verify();
+            SimpleClient::store_contact("alice", &contact)
+                .await
+                .unwrap();
+            SimpleClient::store_delivery_token(&key, &[1, 2, 3])
+                .await
+                .unwrap();
+            SimpleClient::store_protocol_version(&key, "0.1.0")
+                .await
+                .unwrap();
+
+            let item = ContentItem {
+                id: 42,
+                name: "song".into(),
+                ..Default::default()
+            };
+            SimpleClient::store_paid_item(&item).await.unwrap();
+
+            client.messages_cursor = 17;
+            client.store_messages_cursor().await.unwrap();
+
+            // a restarted client loads the stored identity instead of its new one
+            let mut restarted = SimpleClient::default();
+            assert_ne!(
+                restarted.client_id.public.as_bytes(),
+                client.client_id.public.as_bytes()
+            );
+            restarted.load_client_data().await.unwrap();
+
+            assert_eq!(
+                restarted.client_id.to_bytes().to_vec(),
+                client.client_id.to_bytes().to_vec()
+            );
+            assert_eq!(restarted.pre_key.to_bytes(), client.pre_key.to_bytes());
+            assert_eq!(restarted.delivery_token, client.delivery_token);
+            assert_eq!(restarted.messages_cursor, 17);
+            assert_eq!(restarted.paid_items.get(&42).unwrap().name, "song");
+            assert_eq!(
+                restarted.other_clients_delivery_tokens.get(&key).unwrap(),
+                &vec![1, 2, 3]
+            );
+            assert_eq!(
+                restarted.other_clients_protocol_versions.get(&key).unwrap(),
+                "0.1.0"
+            );
+            let contact = restarted.contacts.get("alice").unwrap();
+            assert!(contact.verified);
+            assert_eq!(contact.pinned_key, key);
+        });
+    }
+}
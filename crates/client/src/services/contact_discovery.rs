@@ -0,0 +1,93 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::{NetworkScoped, Signed};
+use base::snp::snp_blockchain::GetClientIdentityBundleRequest;
+use base::snp::snp_core_types::{EntityId, ProviderSignedClientIdentityBundle};
+use common::network_salt::net_id;
+
+impl SimpleClient {
+    /// Returns the id of another client given its id or its nickname.
+    /// Clients we don't know about are looked up on the blockchain and their bundle is added to our other clients.
+    pub(crate) async fn resolve_other_client(&mut self, entity: &EntityId) -> Result<Vec<u8>> {
+        let key = match entity.public_key.as_ref() {
+            Some(public_key) if !public_key.key.is_empty() => Some(public_key.key.clone()),
+            _ => self
+                .contacts
+                .get(&entity.nickname)
+                .map(|contact| contact.pinned_key.clone()),
+        };
+
+        if let Some(key) = key.as_ref() {
+            if self.other_clients.contains_key(key) {
+                return Ok(key.clone());
+            }
+        }
+
+        if key.is_none() && entity.nickname.is_empty() {
+            bail!("missing client id or nickname")
+        }
+
+        let bundle = self
+            .get_client_bundle_from_blockchain(entity.clone())
+            .await?
+            .ok_or_else(|| anyhow!("unknown client"))?;
+
+        let bundle_entity = bundle.get_client_entity()?;
+        let bundle_key = bundle.get_client_id()?;
+        if let Ok(requested_key) = entity.get_id() {
+            if *requested_key != bundle_key {
+                bail!("blockchain returned a bundle of another client")
+            }
+        }
+        if !entity.nickname.is_empty() && entity.nickname != bundle_entity.nickname {
+            bail!("blockchain returned a bundle with another nickname")
+        }
+
+        info!(
+            "discovered client {} on the blockchain",
+            bundle_entity.nickname
+        );
+
+        self.pin_contact_identity(&bundle).await?;
+        SimpleClient::store_other_client(&bundle_key, &bundle).await?;
+        self.other_clients.insert(bundle_key.clone(), bundle);
+
+        Ok(bundle_key)
+    }
+
+    /// Returns a client's current bundle published on the blockchain after verifying it
+    pub(crate) async fn get_client_bundle_from_blockchain(
+        &mut self,
+        entity: EntityId,
+    ) -> Result<Option<ProviderSignedClientIdentityBundle>> {
+        let client = self
+            .blockchain_service_client
+            .as_mut()
+            .ok_or_else(|| anyhow!("missing blockchain service"))?;
+
+        let bundle = match client
+            .get_client_identity_bundle(GetClientIdentityBundleRequest {
+                entity_id: Some(entity),
+            })
+            .await?
+            .into_inner()
+            .client_bundle
+        {
+            Some(bundle) => bundle,
+            None => return Ok(None),
+        };
+
+        bundle.verify_signature()?;
+        bundle
+            .client_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client bundle"))?
+            .verify_net_id(net_id())?;
+
+        Ok(Some(bundle))
+    }
+}
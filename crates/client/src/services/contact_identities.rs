@@ -0,0 +1,305 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::hex_utils::hex_string;
+use base::safety_number::safety_number;
+use base::snp::snp_core_types::{EntityId, ProviderSignedClientIdentityBundle, PublicKey};
+use base::snp::upsetter_simple_client::UserGetContactSafetyNumberResponse;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use xactor::*;
+
+/// Identity of a contact, pinned when it is first seen (trust on first use).
+/// Contacts are known by their nickname when the blockchain registered it to their identity key, and by their
+/// hex identity key otherwise. Self-asserted nicknames are not trusted as anyone may publish a bundle with them.
+/// Pins are keyed by these contact ids, so only contacts with a registered nickname are flagged when their
+/// identity key changes. A new key of a contact without one is pinned as another contact.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ContactIdentity {
+    /// identity key pinned on first use or when the user verified the contact
+    pub(crate) pinned_key: Vec<u8>,
+    /// a different identity key presented by the contact which the user didn't verify yet
+    pub(crate) changed_key: Option<Vec<u8>>,
+    /// user verified the pinned identity key by comparing safety numbers
+    pub(crate) verified: bool,
+}
+
+impl ContactIdentity {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        ContactIdentity {
+            pinned_key: key.to_vec(),
+            changed_key: None,
+            verified: false,
+        }
+    }
+
+    /// Update with an identity key presented by the contact. Returns true when it differs from the pinned key.
+    /// A change is kept until the user re-verifies the contact, even if the contact presents its pinned key again.
+    pub(crate) fn present_key(&mut self, key: &[u8]) -> bool {
+        if key == self.pinned_key.as_slice() {
+            return false;
+        }
+        self.changed_key = Some(key.to_vec());
+        self.verified = false;
+        true
+    }
+
+    /// Returns true when messages with the identity key are blocked until the user re-verifies the contact
+    pub(crate) fn is_blocked(&self, key: &[u8]) -> bool {
+        self.changed_key.as_deref() == Some(key)
+    }
+
+    /// User verified the contact's changed identity key or its pinned key when it didn't change
+    pub(crate) fn verify(&mut self) {
+        if let Some(key) = self.changed_key.take() {
+            self.pinned_key = key;
+        }
+        self.verified = true;
+    }
+}
+
+impl SimpleClient {
+    /// Pin the identity of a contact on first use or flag the contact when its bundle has a different identity key
+    pub(crate) async fn pin_contact_identity(
+        &mut self,
+        bundle: &ProviderSignedClientIdentityBundle,
+    ) -> Result<()> {
+        let entity = bundle.get_client_entity()?;
+        let key = bundle.get_client_id()?;
+        let contact_id = self.get_contact_id(&entity.nickname, &key).await;
+
+        if let Some(contact) = self.pin_contact(&contact_id, &key) {
+            SimpleClient::store_contact(&contact_id, contact).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the id a contact is known by - its nickname when the blockchain registered it to the contact's
+    /// identity key and the contact's hex identity key otherwise
+    async fn get_contact_id(&mut self, nickname: &str, key: &[u8]) -> String {
+        // registered nicknames which look like a hex key may not claim another contact's id
+        if nickname.is_empty()
+            || nickname.starts_with("0x")
+            || self.blockchain_service_client.is_none()
+        {
+            return hex_string(key);
+        }
+
+        let entity = EntityId {
+            public_key: None,
+            nickname: nickname.into(),
+        };
+
+        match self.get_client_bundle_from_blockchain(entity).await {
+            Ok(Some(bundle)) if bundle.get_client_id().ok().as_deref() == Some(key) => {
+                nickname.into()
+            }
+            Ok(_) => hex_string(key),
+            Err(e) => {
+                warn!("failed to get nickname {} owner: {:?}", nickname, e);
+                hex_string(key)
+            }
+        }
+    }
+
+    /// Pin a contact's identity key on first use or flag the contact when the key differs from its pinned key.
+    /// Returns the contact when it was updated
+    fn pin_contact(&mut self, contact_id: &str, key: &[u8]) -> Option<&ContactIdentity> {
+        match self.contacts.entry(contact_id.into()) {
+            Entry::Occupied(entry) => {
+                let contact = entry.into_mut();
+                if !contact.present_key(key) {
+                    return None;
+                }
+                warn!(
+                    "identity key of contact {} changed. Messages with it are blocked until it is verified",
+                    contact_id
+                );
+                Some(contact)
+            }
+            Entry::Vacant(entry) => Some(entry.insert(ContactIdentity::new(key))),
+        }
+    }
+
+    /// Returns an error when the other client is a contact whose new identity key wasn't verified by the user yet
+    pub(crate) fn check_contact_identity(&self, key: &[u8]) -> Result<()> {
+        let nickname = match self.other_clients.get(key) {
+            Some(bundle) => bundle.get_client_entity()?.nickname,
+            None => return Ok(()),
+        };
+
+        // only contacts known by their registered nickname may present a changed key
+        match self.contacts.get(&nickname) {
+            Some(contact) if contact.is_blocked(key) => bail!(
+                "identity key of contact {} changed. Verify the contact's safety number to unblock it",
+                nickname
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a contact's pinned identity and its safety number with our identity.
+    /// A changed identity key is never shown before the user verifies it
+    fn get_contact_safety_number(
+        &self,
+        contact_id: &str,
+    ) -> Result<UserGetContactSafetyNumberResponse> {
+        let contact = self
+            .contacts
+            .get(contact_id)
+            .ok_or_else(|| anyhow!("unknown contact {}", contact_id))?;
+
+        let key = contact.pinned_key.clone();
+        let number = safety_number(self.client_id.public.as_bytes(), &key);
+        let nickname = if contact_id == hex_string(&key) {
+            "".into()
+        } else {
+            contact_id.into()
+        };
+        let entity = EntityId {
+            public_key: Some(PublicKey { key }),
+            nickname,
+        };
+        Ok(UserGetContactSafetyNumberResponse {
+            safety_number: number,
+            contact_id: Some(entity),
+            verified: contact.verified,
+            identity_changed: contact.changed_key.is_some(),
+        })
+    }
+}
+
+#[message(result = "Result<UserGetContactSafetyNumberResponse>")]
+pub(crate) struct GetContactSafetyNumber {
+    pub(crate) nickname: String,
+}
+
+#[async_trait::async_trait]
+impl Handler<GetContactSafetyNumber> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetContactSafetyNumber,
+    ) -> Result<UserGetContactSafetyNumberResponse> {
+        self.get_contact_safety_number(&msg.nickname)
+    }
+}
+
+/// User compared a contact's safety number out-of-band and verified it.
+/// When the contact's identity key changed, the user verifies the safety number the contact computed with its new key
+#[message(result = "Result<()>")]
+pub(crate) struct VerifyContact {
+    pub(crate) nickname: String,
+    pub(crate) safety_number: String,
+}
+
+#[async_trait::async_trait]
+impl Handler<VerifyContact> for SimpleClient {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: VerifyContact) -> Result<()> {
+        let contact = self
+            .contacts
+            .get_mut(&msg.nickname)
+            .ok_or_else(|| anyhow!("unknown contact {}", msg.nickname))?;
+
+        let key = contact.changed_key.as_ref().unwrap_or(&contact.pinned_key);
+        if safety_number(self.client_id.public.as_bytes(), key) != msg.safety_number {
+            bail!("safety number doesn't match the contact's identity")
+        }
+
+        contact.verify();
+        SimpleClient::store_contact(&msg.nickname, contact).await?;
+        info!("contact {} verified", msg.nickname);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+
+    #[test]
+    fn test_contact_identity_change() {
+        let mut contact = ContactIdentity::new(&[1u8; 32]);
+        assert!(!contact.present_key(&[1u8; 32]));
+        assert!(!contact.is_blocked(&[1u8; 32]));
+        contact.verify();
+        assert!(contact.verified);
+
+        // a new identity key is flagged and blocked until verified
+        assert!(contact.present_key(&[2u8; 32]));
+        assert!(!contact.verified);
+        assert!(contact.is_blocked(&[2u8; 32]));
+        assert_eq!(contact.pinned_key, vec![1u8; 32]);
+
+        // presenting the pinned key again doesn't clear the change
+        assert!(!contact.present_key(&[1u8; 32]));
+        assert!(contact.is_blocked(&[2u8; 32]));
+
+        contact.verify();
+        assert!(contact.verified);
+        assert!(!contact.is_blocked(&[2u8; 32]));
+        assert_eq!(contact.pinned_key, vec![2u8; 32]);
+        assert!(contact.changed_key.is_none());
+    }
+
+    #[test]
+    fn test_pin_contact_identity() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+
+            // contacts w/o a registered nickname are known by their identity key
+            let contact_id = client.get_contact_id("alice", &[1u8; 32]).await;
+            assert_eq!(contact_id, hex_string(&[1u8; 32]));
+            assert_eq!(client.get_contact_id("", &[1u8; 32]).await, contact_id);
+
+            assert!(client.pin_contact(&contact_id, &[1u8; 32]).is_some());
+            assert!(client.pin_contact(&contact_id, &[1u8; 32]).is_none());
+
+            // a bundle which claims alice's nickname doesn't flag alice
+            let impostor_id = client.get_contact_id("alice", &[2u8; 32]).await;
+            assert_ne!(impostor_id, contact_id);
+            client.pin_contact(&impostor_id, &[2u8; 32]);
+            assert!(client.contacts[&contact_id].changed_key.is_none());
+
+            // a changed key of a contact is flagged but not shown until verified
+            client.pin_contact("bob", &[3u8; 32]);
+            assert!(client.pin_contact("bob", &[4u8; 32]).is_some());
+            let resp = client.get_contact_safety_number("bob").unwrap();
+            assert_eq!(resp.contact_id.unwrap().get_id().unwrap(), &vec![3u8; 32]);
+            assert_eq!(
+                resp.safety_number,
+                safety_number(client.client_id.public.as_bytes(), &[3u8; 32])
+            );
+            assert!(resp.identity_changed);
+            assert!(!resp.verified);
+
+            assert!(client.get_contact_safety_number("carol").is_err());
+        });
+    }
+
+    /// Contacts w/o a registered nickname are pinned by their identity key so their key changes are not flagged
+    #[test]
+    fn test_unregistered_contact_key_change() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+
+            let contact_id = client.get_contact_id("dave", &[5u8; 32]).await;
+            client.pin_contact(&contact_id, &[5u8; 32]);
+
+            let new_contact_id = client.get_contact_id("dave", &[6u8; 32]).await;
+            assert_ne!(new_contact_id, contact_id);
+            assert!(client.pin_contact(&new_contact_id, &[6u8; 32]).is_some());
+
+            for (id, key) in [(&contact_id, [5u8; 32]), (&new_contact_id, [6u8; 32])] {
+                let resp = client.get_contact_safety_number(id).unwrap();
+                assert_eq!(resp.contact_id.unwrap().get_id().unwrap(), &key.to_vec());
+                assert!(!resp.identity_changed);
+            }
+        });
+    }
+}
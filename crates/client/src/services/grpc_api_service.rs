@@ -3,23 +3,33 @@
 //
 
 use crate::channels::channel_creator::CreateNewChannel;
+use crate::channels::channel_history_requester::GetChannelHistory;
 use crate::channels::channel_msg_publisher::PublishNewChannelMessage;
 use crate::channels::channel_subscriber::SubscribeToChannel;
 use crate::channels::channel_unsubscriber::UnsubscribeFromChannel;
+use crate::channels::group_admin::{GetGroupMembers, GroupAdmin};
 
 use crate::paid_content::item_buyer::BuyItem;
 use crate::paid_content::item_creator::CreatePaidItem;
 use crate::paid_content::list_items_sender::ListItems;
 use crate::services::add_other_client::AddOtherClientBundle;
+use crate::services::client_events::SubscribeEvents;
+use crate::services::contact_identities::{GetContactSafetyNumber, VerifyContact};
+use crate::services::identity_monitor::AuditIdentity;
+use crate::services::inbox::{GetConversationMessages, ListConversations};
 use crate::services::set_blockchain_service::SetBlockchainService;
 use crate::services::set_provider::SetProvider;
+use crate::services::switch_provider::SwitchProvider;
 use crate::simple_client::SimpleClient;
+use crate::user_to_user_messaging::attachments::SendAttachment;
+use crate::user_to_user_messaging::receipts::{
+    GetMessagesStatus, MarkMessagesRead, SetReadReceipts,
+};
 use crate::user_to_user_messaging::text_msg_sender::SendTextMessage;
 use anyhow::Result;
 use base::snp::snp_core_types::*;
 use base::snp::upsetter_simple_client::simple_client_user_service_server::SimpleClientUserService;
 use base::snp::upsetter_simple_client::*;
-use bytes::Bytes;
 use tonic::{Request, Response, Status};
 use xactor::*;
 
@@ -107,6 +117,33 @@ impl SimpleClientUserService for SimpleClientGrpcService {
         }
     }
 
+    /// Move this client from its current provider to another provider.
+    /// Client's messages are handed over to its new provider and its new bundle is published on-chain.
+    async fn user_switch_provider(
+        &self,
+        request: Request<UserSwitchProviderRequest>,
+    ) -> Result<Response<UserSwitchProviderResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        let dialup_info = request
+            .into_inner()
+            .dialup_info
+            .ok_or_else(|| Status::invalid_argument("missing dialup info"))?;
+
+        match client
+            .call(SwitchProvider { dialup_info })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(bundle) => Ok(Response::new(UserSwitchProviderResponse {
+                client_bundle: Some(bundle),
+            })),
+            Err(e) => Err(Status::internal(format!("internal error: {:?}", e))),
+        }
+    }
+
     // Set other client bundle on behalf of the client's user
     async fn user_add_other_client_bundle(
         &self,
@@ -138,18 +175,20 @@ impl SimpleClientUserService for SimpleClientGrpcService {
 
         let inner_req = request.into_inner();
 
-        let receiver_id: Vec<u8> = inner_req
+        let receiver = inner_req
             .other_client_id
-            .ok_or_else(|| Status::invalid_argument("missing receiver id"))?
-            .get_id()
-            .map_err(|_| Status::invalid_argument("missing pub key"))?
-            .clone();
+            .ok_or_else(|| Status::invalid_argument("missing receiver id"))?;
+
+        if receiver.get_id().is_err() && receiver.nickname.is_empty() {
+            return Err(Status::invalid_argument("missing pub key or nickname"));
+        }
 
         match client
             .call(SendTextMessage {
                 message: inner_req.user_text,
-                receiver_id: Bytes::from(receiver_id),
+                receiver,
                 reply_to: inner_req.reply_to,
+                ttl: inner_req.ttl,
             })
             .await
             .map_err(|e| Status::internal(format!("{:?}", e)))?
@@ -159,6 +198,278 @@ impl SimpleClientUserService for SimpleClientGrpcService {
         }
     }
 
+    /// Send a 1:1 message with an attachment compressed with the requested codec
+    async fn user_send_attachment(
+        &self,
+        request: Request<UserSendAttachmentRequest>,
+    ) -> Result<Response<UserSendAttachmentResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        let inner_req = request.into_inner();
+
+        let receiver = inner_req
+            .other_client_id
+            .ok_or_else(|| Status::invalid_argument("missing receiver id"))?;
+
+        if receiver.get_id().is_err() && receiver.nickname.is_empty() {
+            return Err(Status::invalid_argument("missing pub key or nickname"));
+        }
+
+        let mime_type = MimeType::from_i32(inner_req.mime_type)
+            .ok_or_else(|| Status::invalid_argument("unknown mime type"))?;
+
+        let compression = CompressionCodec::from_i32(inner_req.compression)
+            .ok_or_else(|| Status::invalid_argument("unknown compression codec"))?;
+
+        let attachment = MediaItem::new(
+            1,
+            inner_req.name,
+            mime_type,
+            compression,
+            &inner_req.content,
+        )
+        .map_err(|e| Status::invalid_argument(format!("{}", e)))?;
+
+        match client
+            .call(SendAttachment {
+                receiver,
+                text: inner_req.user_text,
+                attachment,
+                reply_to: inner_req.reply_to,
+                ttl: inner_req.ttl,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok((message_id, chunks_count)) => Ok(Response::new(UserSendAttachmentResponse {
+                message_id,
+                chunks_count,
+            })),
+            Err(_) => Err(Status::internal("internal error")),
+        }
+    }
+
+    /// Get the safety number of this client and a contact so the user can compare it with the contact out-of-band
+    async fn user_get_contact_safety_number(
+        &self,
+        request: Request<UserGetContactSafetyNumberRequest>,
+    ) -> Result<Response<UserGetContactSafetyNumberResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        match client
+            .call(GetContactSafetyNumber {
+                nickname: request.into_inner().nickname,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => Err(Status::not_found(format!("{:?}", e))),
+        }
+    }
+
+    /// Mark a contact as verified on behalf of the user after the user compared safety numbers with the contact
+    async fn user_verify_contact(
+        &self,
+        request: Request<UserVerifyContactRequest>,
+    ) -> Result<Response<UserVerifyContactResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        let req = request.into_inner();
+        match client
+            .call(VerifyContact {
+                nickname: req.nickname,
+                safety_number: req.safety_number,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(()) => Ok(Response::new(UserVerifyContactResponse {})),
+            Err(e) => Err(Status::failed_precondition(format!("{:?}", e))),
+        }
+    }
+
+    /// Audit this client's identity bundles history in the blockchain transparency log
+    async fn user_audit_identity(
+        &self,
+        _request: Request<UserAuditIdentityRequest>,
+    ) -> Result<Response<UserAuditIdentityResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        match client
+            .call(AuditIdentity)
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => Err(Status::internal(format!("{:?}", e))),
+        }
+    }
+
+    /// List the conversations this client received messages in
+    async fn user_list_conversations(
+        &self,
+        _request: Request<UserListConversationsRequest>,
+    ) -> Result<Response<UserListConversationsResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        match client
+            .call(ListConversations)
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => Err(Status::internal(format!("{:?}", e))),
+        }
+    }
+
+    /// Get a page of the messages this client received in a conversation
+    async fn user_get_conversation_messages(
+        &self,
+        request: Request<UserGetConversationMessagesRequest>,
+    ) -> Result<Response<UserGetConversationMessagesResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        let req = request.into_inner();
+        if req.conversation_id.is_empty() {
+            return Err(Status::invalid_argument("missing conversation id"));
+        }
+
+        match client
+            .call(GetConversationMessages {
+                conversation_id: req.conversation_id,
+                from_index: req.from_index,
+                max_results: req.max_results,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => Err(Status::internal(format!("{:?}", e))),
+        }
+    }
+
+    type SubscribeEventsStream =
+        tokio_stream::wrappers::ReceiverStream<Result<ClientEvent, Status>>;
+
+    /// Stream this client's events to the caller until it drops the stream
+    async fn subscribe_events(
+        &self,
+        _request: Request<SubscribeEventsRequest>,
+    ) -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        match client
+            .call(SubscribeEvents)
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(rx) => Ok(Response::new(tokio_stream::wrappers::ReceiverStream::new(
+                rx,
+            ))),
+            Err(e) => Err(Status::internal(format!("{:?}", e))),
+        }
+    }
+
+    /// Mark messages from another client as read and send it a read receipt for them
+    async fn user_mark_messages_read(
+        &self,
+        request: Request<UserMarkMessagesReadRequest>,
+    ) -> Result<Response<UserMarkMessagesReadResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        let req = request.into_inner();
+        let sender = req
+            .other_client_id
+            .ok_or_else(|| Status::invalid_argument("missing sender id"))?;
+
+        if sender.get_id().is_err() {
+            return Err(Status::invalid_argument("missing pub key"));
+        }
+
+        if req.message_ids.is_empty() {
+            return Err(Status::invalid_argument("missing message ids"));
+        }
+
+        match client
+            .call(MarkMessagesRead {
+                sender,
+                message_ids: req.message_ids,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(receipt_sent) => Ok(Response::new(UserMarkMessagesReadResponse { receipt_sent })),
+            Err(e) => Err(Status::internal(format!("{:?}", e))),
+        }
+    }
+
+    /// Get the delivery status of messages this client sent
+    async fn user_get_messages_status(
+        &self,
+        request: Request<UserGetMessagesStatusRequest>,
+    ) -> Result<Response<UserGetMessagesStatusResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        match client
+            .call(GetMessagesStatus(request.into_inner().message_ids))
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(messages) => Ok(Response::new(UserGetMessagesStatusResponse { messages })),
+            Err(e) => Err(Status::internal(format!("{:?}", e))),
+        }
+    }
+
+    /// Turn on or off read receipts to a contact
+    async fn user_set_read_receipts(
+        &self,
+        request: Request<UserSetReadReceiptsRequest>,
+    ) -> Result<Response<UserSetReadReceiptsResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        let req = request.into_inner();
+        let contact = req
+            .contact_id
+            .ok_or_else(|| Status::invalid_argument("missing contact id"))?;
+
+        if contact.get_id().is_err() {
+            return Err(Status::invalid_argument("missing pub key"));
+        }
+
+        match client
+            .call(SetReadReceipts {
+                contact,
+                enabled: req.enabled,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(()) => Ok(Response::new(UserSetReadReceiptsResponse {})),
+            Err(e) => Err(Status::internal(format!("{:?}", e))),
+        }
+    }
+
     // Create a new status update channel on behalf of the user
     async fn user_create_status_update_channel(
         &self,
@@ -168,11 +479,14 @@ impl SimpleClientUserService for SimpleClientGrpcService {
             .await
             .map_err(|e| Status::internal(format!("{:?}", e)))?;
 
+        let req = request.into_inner();
         match client
             .call(CreateNewChannel {
-                name: request.into_inner().channel_name,
+                name: req.channel_name,
                 channel_type: ChannelType::StatusFeed,
                 description: "My Upsetter Status Updates".to_string(),
+                subscription_fee: req.subscription_fee,
+                approve_members: false,
             })
             .await
             .map_err(|e| Status::internal(format!("{:?}", e)))?
@@ -240,6 +554,31 @@ impl SimpleClientUserService for SimpleClientGrpcService {
         }
     }
 
+    /// Request a page of the content of a channel we are subscribed to or of a group we are a member of.
+    /// The page is published in a channel history received event when the channel's creator sends it
+    async fn user_get_channel_history(
+        &self,
+        request: Request<UserGetChannelHistoryRequest>,
+    ) -> Result<Response<UserGetChannelHistoryResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+        let req = request.into_inner();
+
+        match client
+            .call(GetChannelHistory {
+                channel_id: req.channel_id,
+                before: req.before,
+                max_items: req.max_items,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(()) => Ok(Response::new(UserGetChannelHistoryResponse {})),
+            Err(e) => Err(Status::internal(format!("Internal error: {:?}", e))),
+        }
+    }
+
     /// Create a new group on behalf of this client's user
     async fn user_create_group(
         &self,
@@ -249,11 +588,14 @@ impl SimpleClientUserService for SimpleClientGrpcService {
             .await
             .map_err(|e| Status::internal(format!("{:?}", e)))?;
 
+        let req = request.into_inner();
         match client
             .call(CreateNewChannel {
-                name: request.into_inner().group_name,
+                name: req.group_name,
                 channel_type: ChannelType::Group,
                 description: "My Upsetter Group".to_string(),
+                subscription_fee: 0,
+                approve_members: req.approve_members,
             })
             .await
             .map_err(|e| Status::internal(format!("{:?}", e)))?
@@ -289,6 +631,53 @@ impl SimpleClientUserService for SimpleClientGrpcService {
         Ok(Response::new(UserLeaveGroupResponse {}))
     }
 
+    async fn user_group_admin(
+        &self,
+        request: Request<UserGroupAdminRequest>,
+    ) -> Result<Response<UserGroupAdminResponse>, Status> {
+        let req = request.into_inner();
+        let action = GroupAdminAction::from_i32(req.action)
+            .ok_or_else(|| Status::invalid_argument("unknown group admin action"))?;
+        let user = req
+            .user_id
+            .ok_or_else(|| Status::invalid_argument("missing user id"))?;
+
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        match client
+            .call(GroupAdmin {
+                channel_id: req.channel_id,
+                action,
+                user,
+            })
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(()) => Ok(Response::new(UserGroupAdminResponse {})),
+            Err(e) => Err(Status::internal(format!("Internal error: {:?}", e))),
+        }
+    }
+
+    async fn user_get_group_members(
+        &self,
+        request: Request<UserGetGroupMembersRequest>,
+    ) -> Result<Response<UserGetGroupMembersResponse>, Status> {
+        let client = SimpleClient::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        match client
+            .call(GetGroupMembers(request.into_inner().channel_id))
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?
+        {
+            Ok(resp) => Ok(Response::new(resp)),
+            Err(e) => Err(Status::internal(format!("Internal error: {:?}", e))),
+        }
+    }
+
     async fn user_create_paid_item(
         &self,
         request: Request<UserCreatePaidItemRequest>,
@@ -0,0 +1,274 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::Signed;
+use base::hex_utils::short_hex_string;
+use base::snp::snp_blockchain::{
+    GetIdentityBundlesHistoryRequest, GetIdentityBundlesHistoryResponse,
+    GetTransparencyLogConsistencyProofRequest, TransparencyLogEntry, TransparencyLogHead,
+};
+use base::snp::upsetter_simple_client::UserAuditIdentityResponse;
+use base::transparency_log::{verify_consistency, verify_inclusion};
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::time::sleep;
+use xactor::*;
+
+impl SimpleClient {
+    /// Periodically audit our identity bundles history in the blockchain transparency log
+    pub(crate) async fn monitor_identity(interval: Duration) {
+        loop {
+            sleep(interval).await;
+
+            let res = match SimpleClient::from_registry().await {
+                Ok(client) => client.call(AuditIdentity).await.and_then(|r| r),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = res {
+                warn!("failed to audit our identity history: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Verify that all entries of an identity history are bundles of the identity key and are included in the history's tree head.
+/// Returns the verified tree head
+fn verify_identity_history(
+    key: &[u8],
+    history: &GetIdentityBundlesHistoryResponse,
+) -> Result<TransparencyLogHead> {
+    let head = history
+        .head
+        .as_ref()
+        .ok_or_else(|| anyhow!("missing log head"))?;
+
+    head.verify_signature()
+        .map_err(|e| anyhow!("invalid log head signature: {}", e))?;
+
+    for entry_proof in &history.entries {
+        let entry = entry_proof
+            .entry
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing log entry"))?;
+
+        let entry_key = entry
+            .entity_id
+            .as_ref()
+            .and_then(|id| id.public_key.as_ref())
+            .ok_or_else(|| anyhow!("missing log entry entity"))?;
+
+        if entry_key.key != key {
+            bail!("log entry {} is of another identity", entry.index)
+        }
+
+        verify_inclusion(
+            &entry.leaf_hash(),
+            entry.index,
+            head.tree_size,
+            &entry_proof.inclusion_proof,
+            &head.root_hash,
+        )
+        .map_err(|e| anyhow!("log entry {}: {}", entry.index, e))?;
+    }
+
+    Ok(head.clone())
+}
+
+/// Returns the entries of an identity history with bundles which are not in the provided bundles hashes
+fn unknown_bundles(
+    history: &GetIdentityBundlesHistoryResponse,
+    published: &HashSet<Vec<u8>>,
+) -> Vec<TransparencyLogEntry> {
+    history
+        .entries
+        .iter()
+        .filter_map(|e| e.entry.as_ref())
+        .filter(|e| !published.contains(&e.bundle_hash))
+        .cloned()
+        .collect()
+}
+
+/// Audit our identity bundles history in the blockchain transparency log.
+/// Verifies that the log is consistent with the last log head we audited and alerts on our bundles we didn't publish
+#[message(result = "Result<UserAuditIdentityResponse>")]
+pub(crate) struct AuditIdentity;
+
+#[async_trait::async_trait]
+impl Handler<AuditIdentity> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: AuditIdentity,
+    ) -> Result<UserAuditIdentityResponse> {
+        let entity_id = self.get_client_entity()?;
+        let key = self.client_id.public.as_bytes().to_vec();
+
+        let blockchain = self
+            .blockchain_service_client
+            .as_mut()
+            .ok_or_else(|| anyhow!("no blockchain service set on this client"))?;
+
+        let history = blockchain
+            .get_identity_bundles_history(GetIdentityBundlesHistoryRequest {
+                entity_id: Some(entity_id),
+            })
+            .await?
+            .into_inner();
+
+        let head = verify_identity_history(&key, &history)?;
+
+        // a log head must extend the previous log head we audited. Otherwise, the log was forked or rewritten
+        if let Some(prev_head) = self.transparency_log_head.as_ref() {
+            if prev_head.get_log_key()? != head.get_log_key()? {
+                error!("ALERT: transparency log head is signed by another log key");
+                bail!("transparency log head is signed by another log key")
+            }
+
+            let proof = if prev_head.tree_size < head.tree_size {
+                blockchain
+                    .get_transparency_log_consistency_proof(
+                        GetTransparencyLogConsistencyProofRequest {
+                            first_tree_size: prev_head.tree_size,
+                            second_tree_size: head.tree_size,
+                        },
+                    )
+                    .await?
+                    .into_inner()
+                    .proof
+            } else {
+                vec![]
+            };
+
+            verify_consistency(
+                prev_head.tree_size,
+                &prev_head.root_hash,
+                head.tree_size,
+                &head.root_hash,
+                &proof,
+            )
+            .map_err(|e| {
+                error!(
+                    "ALERT: transparency log is not consistent with its previous head: {}",
+                    e
+                );
+                anyhow!(
+                    "transparency log is not consistent with its previous head: {}",
+                    e
+                )
+            })?;
+        }
+
+        self.transparency_log_head = Some(head.clone());
+        self.store_transparency_log_head().await?;
+
+        let unknown_bundles = unknown_bundles(&history, &self.published_bundles);
+        for entry in &unknown_bundles {
+            error!(
+                "ALERT: identity bundle {} in transparency log entry {} wasn't published by us",
+                short_hex_string(&entry.bundle_hash),
+                entry.index
+            );
+        }
+
+        debug!(
+            "audited {} identity bundles in transparency log of size {}",
+            history.entries.len(),
+            head.tree_size
+        );
+
+        Ok(UserAuditIdentityResponse {
+            head: Some(head),
+            bundles_count: history.entries.len() as u32,
+            unknown_bundles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::snp::snp_blockchain::{TransactionType, TransparencyLogEntryProof};
+    use base::snp::snp_core_types::{EntityId, PublicKey};
+    use base::transparency_log::{inclusion_proof, root_hash};
+    use crypto::utils::{create_key_pair, entity_from_ed25519_pub_key};
+    use ed25519_dalek::Keypair;
+
+    fn new_entry(index: u64, key: &[u8], bundle_hash: &[u8]) -> TransparencyLogEntry {
+        TransparencyLogEntry {
+            index,
+            entity_id: Some(EntityId {
+                public_key: Some(PublicKey { key: key.to_vec() }),
+                nickname: "".into(),
+            }),
+            transaction_type: TransactionType::SetClientBundle as i32,
+            bundle_hash: bundle_hash.to_vec(),
+            bundle_time_stamp: index,
+            block_id: index + 1,
+        }
+    }
+
+    fn new_history(
+        log_key: &Keypair,
+        entries: &[TransparencyLogEntry],
+        ours: &[usize],
+    ) -> GetIdentityBundlesHistoryResponse {
+        let leaves: Vec<Vec<u8>> = entries.iter().map(|e| e.leaf_hash()).collect();
+        let mut head = TransparencyLogHead {
+            tree_size: leaves.len() as u64,
+            root_hash: root_hash(&leaves),
+            log_id: Some(entity_from_ed25519_pub_key(&log_key.public, "".into())),
+            signature: None,
+        };
+        head.sign(log_key).unwrap();
+
+        GetIdentityBundlesHistoryResponse {
+            head: Some(head),
+            entries: ours
+                .iter()
+                .map(|i| TransparencyLogEntryProof {
+                    entry: Some(entries[*i].clone()),
+                    inclusion_proof: inclusion_proof(&leaves, *i).unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_audit_identity_history() {
+        let ours = [1u8; 32];
+        let other = [2u8; 32];
+        let entries = vec![
+            new_entry(0, &ours, &[10]),
+            new_entry(1, &other, &[11]),
+            new_entry(2, &ours, &[12]), // a bundle we didn't publish
+            new_entry(3, &other, &[13]),
+        ];
+
+        let log_key = create_key_pair();
+        let history = new_history(&log_key, &entries, &[0, 2]);
+        let head = verify_identity_history(&ours, &history).unwrap();
+        assert_eq!(head.tree_size, 4);
+
+        let published: HashSet<Vec<u8>> = vec![vec![10u8]].into_iter().collect();
+        let unknown = unknown_bundles(&history, &published);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].index, 2);
+
+        // entries of other identities are rejected
+        assert!(verify_identity_history(&ours, &new_history(&log_key, &entries, &[0, 1])).is_err());
+
+        // an entry which is not in the log head is rejected
+        let mut history = new_history(&log_key, &entries, &[0, 2]);
+        history.entries[1].entry.as_mut().unwrap().bundle_hash = vec![10];
+        assert!(verify_identity_history(&ours, &history).is_err());
+
+        // a log head which is not signed by its log is rejected
+        let mut history = new_history(&log_key, &entries, &[0, 2]);
+        history.head.as_mut().unwrap().tree_size = 3;
+        assert!(verify_identity_history(&ours, &history).is_err());
+    }
+}
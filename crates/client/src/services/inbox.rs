@@ -0,0 +1,276 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::consts::{CONVERSATIONS_CF, INBOX_CF};
+use crate::services::client_store::{delete_item, read_all_items, read_item, write_item};
+use crate::simple_client::SimpleClient;
+use anyhow::Result;
+use base::snp::snp_core_types::{ContentItem, EntityId, PublicKey};
+use base::snp::upsetter_simple_client::{
+    Conversation, ConversationType, InboxMessage, UserGetConversationMessagesResponse,
+    UserListConversationsResponse,
+};
+use base::time_utils::now;
+use prost::Message;
+use std::cmp::Reverse;
+use xactor::*;
+
+/// The inbox stores the messages this client received in the client db, by conversation.
+/// Messages of a conversation are indexed by the order they were received in
+fn inbox_message_key(conversation_id: &[u8], index: u64) -> Vec<u8> {
+    let mut key = conversation_id.to_vec();
+    key.extend_from_slice(&index.to_be_bytes());
+    key
+}
+
+/// Returns true when a stored message is a self-destructing message which expired by time_stamp
+fn is_expired(message: &InboxMessage, time_stamp: u64) -> bool {
+    message
+        .content_item
+        .as_ref()
+        .and_then(|item| item.expiration_time(message.received_time_stamp))
+        .is_some_and(|expiration| expiration <= time_stamp)
+}
+
+async fn read_conversation(conversation_id: &[u8]) -> Result<Option<Conversation>> {
+    match read_item(CONVERSATIONS_CF, conversation_id).await? {
+        Some(data) => Ok(Some(Conversation::decode(data.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
+impl SimpleClient {
+    /// Returns the entity of another client with its nickname when we know its bundle
+    pub(crate) fn get_other_client_entity(&self, key: &[u8]) -> EntityId {
+        self.other_clients
+            .get(key)
+            .and_then(|bundle| bundle.get_client_entity().ok())
+            .unwrap_or_else(|| EntityId {
+                public_key: Some(PublicKey { key: key.to_vec() }),
+                nickname: "".into(),
+            })
+    }
+
+    /// Store a message we received in a conversation. Returns the stored message
+    pub(crate) async fn store_inbox_message(
+        conversation_id: &[u8],
+        conversation_type: ConversationType,
+        sender_id: EntityId,
+        content_item: ContentItem,
+    ) -> Result<InboxMessage> {
+        let mut conversation = read_conversation(conversation_id)
+            .await?
+            .unwrap_or_else(|| Conversation {
+                conversation_id: conversation_id.to_vec(),
+                conversation_type: conversation_type as i32,
+                messages_count: 0,
+                last_message_time_stamp: 0,
+            });
+
+        let message = InboxMessage {
+            conversation_id: conversation_id.to_vec(),
+            index: conversation.messages_count,
+            sender_id: Some(sender_id),
+            received_time_stamp: now(),
+            content_item: Some(content_item),
+        };
+
+        write_item(
+            INBOX_CF,
+            &inbox_message_key(conversation_id, message.index),
+            message.encode_to_vec(),
+        )
+        .await?;
+
+        conversation.messages_count += 1;
+        conversation.last_message_time_stamp = message.received_time_stamp;
+        write_item(
+            CONVERSATIONS_CF,
+            conversation_id,
+            conversation.encode_to_vec(),
+        )
+        .await?;
+
+        Ok(message)
+    }
+
+    /// Returns all conversations, most recently active first
+    pub(crate) async fn list_conversations() -> Result<Vec<Conversation>> {
+        let mut conversations = vec![];
+        for (_, value) in read_all_items(CONVERSATIONS_CF).await? {
+            conversations.push(Conversation::decode(value.as_ref())?);
+        }
+        conversations.sort_by_key(|c| Reverse(c.last_message_time_stamp));
+        Ok(conversations)
+    }
+
+    /// Returns up to max_results messages of a conversation from from_index and the number of messages in it.
+    /// All messages from from_index are returned when max_results is 0
+    pub(crate) async fn get_conversation_messages(
+        conversation_id: &[u8],
+        from_index: u64,
+        max_results: u32,
+    ) -> Result<(Vec<InboxMessage>, u64)> {
+        let total_count = match read_conversation(conversation_id).await? {
+            Some(conversation) => conversation.messages_count,
+            None => return Ok((vec![], 0)),
+        };
+
+        let end_index = match max_results {
+            0 => total_count,
+            n => total_count.min(from_index.saturating_add(n as u64)),
+        };
+
+        // expired messages which weren't purged yet are not returned
+        let time_stamp = now();
+        let mut messages = vec![];
+        for index in from_index..end_index {
+            if let Some(data) =
+                read_item(INBOX_CF, &inbox_message_key(conversation_id, index)).await?
+            {
+                let message = InboxMessage::decode(data.as_ref())?;
+                if !is_expired(&message, time_stamp) {
+                    messages.push(message);
+                }
+            }
+        }
+
+        Ok((messages, total_count))
+    }
+
+    /// Delete self-destructing messages which expired by time_stamp from the inbox.
+    /// Conversations keep their messages count so the indexes of their other messages don't change.
+    /// Returns the number of deleted messages
+    pub(crate) async fn purge_expired_inbox_messages(time_stamp: u64) -> Result<usize> {
+        let mut count = 0;
+        for (key, value) in read_all_items(INBOX_CF).await? {
+            if is_expired(&InboxMessage::decode(value.as_ref())?, time_stamp) {
+                delete_item(INBOX_CF, key.as_ref()).await?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[message(result = "Result<UserListConversationsResponse>")]
+pub(crate) struct ListConversations;
+
+#[async_trait::async_trait]
+impl Handler<ListConversations> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: ListConversations,
+    ) -> Result<UserListConversationsResponse> {
+        Ok(UserListConversationsResponse {
+            conversations: SimpleClient::list_conversations().await?,
+        })
+    }
+}
+
+#[message(result = "Result<UserGetConversationMessagesResponse>")]
+pub(crate) struct GetConversationMessages {
+    pub(crate) conversation_id: Vec<u8>,
+    pub(crate) from_index: u64,
+    pub(crate) max_results: u32,
+}
+
+#[async_trait::async_trait]
+impl Handler<GetConversationMessages> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetConversationMessages,
+    ) -> Result<UserGetConversationMessagesResponse> {
+        let (messages, total_count) = SimpleClient::get_conversation_messages(
+            &msg.conversation_id,
+            msg.from_index,
+            msg.max_results,
+        )
+        .await?;
+
+        Ok(UserGetConversationMessagesResponse {
+            messages,
+            total_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+
+    #[test]
+    fn test_inbox_paging() {
+        run_with_test_db(async {
+            let first = vec![3u8; 32];
+            let second = vec![4u8; 32];
+            let sender = EntityId {
+                public_key: Some(PublicKey { key: first.clone() }),
+                nickname: "bob".into(),
+            };
+
+            for id in 0..5 {
+                let item = ContentItem {
+                    id,
+                    ..Default::default()
+                };
+                let msg = SimpleClient::store_inbox_message(
+                    &first,
+                    ConversationType::Direct,
+                    sender.clone(),
+                    item,
+                )
+                .await
+                .unwrap();
+                assert_eq!(msg.index, id);
+            }
+
+            SimpleClient::store_inbox_message(
+                &second,
+                ConversationType::Group,
+                sender.clone(),
+                ContentItem::default(),
+            )
+            .await
+            .unwrap();
+
+            let conversations = SimpleClient::list_conversations().await.unwrap();
+            let ids: Vec<&[u8]> = conversations
+                .iter()
+                .map(|c| c.conversation_id.as_slice())
+                .collect();
+            assert!(ids.contains(&first.as_slice()));
+            assert!(ids.contains(&second.as_slice()));
+
+            // most recent conversation first
+            let second_pos = ids.iter().position(|id| *id == second.as_slice()).unwrap();
+            let first_pos = ids.iter().position(|id| *id == first.as_slice()).unwrap();
+            assert!(second_pos < first_pos);
+
+            let (page, total) = SimpleClient::get_conversation_messages(&first, 1, 2)
+                .await
+                .unwrap();
+            assert_eq!(total, 5);
+            let ids: Vec<u64> = page
+                .iter()
+                .map(|m| m.content_item.as_ref().unwrap().id)
+                .collect();
+            assert_eq!(ids, vec![1, 2]);
+
+            let (rest, _) = SimpleClient::get_conversation_messages(&first, 3, 0)
+                .await
+                .unwrap();
+            assert_eq!(rest.len(), 2);
+
+            let (none, total) = SimpleClient::get_conversation_messages(&[9u8; 32], 0, 0)
+                .await
+                .unwrap();
+            assert!(none.is_empty());
+            assert_eq!(total, 0);
+        });
+    }
+}
@@ -5,5 +5,13 @@
 pub mod grpc_api_service;
 
 mod add_other_client;
+pub(crate) mod client_events;
+pub(crate) mod client_store;
+mod contact_discovery;
+pub(crate) mod contact_identities;
+pub(crate) mod identity_monitor;
+pub(crate) mod inbox;
+pub(crate) mod payments;
 mod set_blockchain_service;
 mod set_provider;
+mod switch_provider;
@@ -0,0 +1,227 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::Signed;
+use base::snp::snp_blockchain::transaction::Data;
+use base::snp::snp_blockchain::{
+    GetAccountRequest, GetTransactionRequest, PaymentTransactionData, SubmitTransactionRequest,
+    Transaction, TransactionFee, TransactionInfo, TransactionState,
+};
+use base::snp::snp_payments::{Address, Amount, CoinType, TransactionId};
+use common::network_salt::net_id;
+
+/// Verify that a transaction is a payment of at least amount coins from payer to payee with a payment id
+fn verify_payment(
+    tx_info: &TransactionInfo,
+    payee: &Address,
+    payer: &[u8],
+    amount: u64,
+    payment_id: u64,
+) -> Result<()> {
+    let state = tx_info.state;
+    if state != TransactionState::Confirmed as i32 && state != TransactionState::Final as i32 {
+        bail!("transaction is not confirmed. state: {}", state)
+    }
+
+    let tx = tx_info
+        .transaction
+        .as_ref()
+        .ok_or_else(|| anyhow!("missing transaction"))?;
+
+    if tx.sender_pub_key != payer {
+        bail!("transaction is not from the payer")
+    }
+
+    let payment = match tx.data.as_ref() {
+        Some(Data::PaymentTransaction(payment)) => payment,
+        _ => bail!("transaction is not a payment"),
+    };
+
+    if payment.receiver.as_ref() != Some(payee) {
+        bail!("payment is not to us")
+    }
+
+    if payment.id != payment_id {
+        bail!("payment is for something else")
+    }
+
+    let coins = payment
+        .coins
+        .as_ref()
+        .ok_or_else(|| anyhow!("missing payment amount"))?;
+
+    if coins.coin_type != CoinType::Core as i32 || coins.value < amount {
+        bail!("payment is less than {} coins", amount)
+    }
+
+    Ok(())
+}
+
+impl SimpleClient {
+    /// Submit a payment of amount coins to receiver on the blockchain.
+    /// The payment id tells the receiver what it pays for, e.g. a paid item id
+    pub(crate) async fn submit_payment(
+        &mut self,
+        receiver: Address,
+        amount: u64,
+        payment_id: u64,
+    ) -> Result<TransactionId> {
+        let payment_address = self.get_payment_address()?;
+
+        let client = self
+            .blockchain_service_client
+            .as_mut()
+            .ok_or_else(|| anyhow!("No blockchain service set on this client"))?;
+
+        let account = client
+            .get_account(GetAccountRequest {
+                address: Some(payment_address),
+            })
+            .await?
+            .into_inner()
+            .account
+            .ok_or_else(|| anyhow!("missing client account"))?;
+
+        let payment_tx_data = PaymentTransactionData {
+            receiver: Some(receiver),
+            coins: Some(Amount {
+                value: amount,
+                coin_type: CoinType::Core as i32,
+            }),
+            id: payment_id,
+        };
+
+        let tx_fee = TransactionFee {
+            amount: Some(Amount {
+                value: 1,
+                coin_type: CoinType::Core as i32,
+            }),
+            payer_public_key: vec![], // sender pays fee
+        };
+
+        let mut tx = Transaction {
+            sender_pub_key: self.client_id.public.to_bytes().to_vec(),
+            fee: Some(tx_fee),
+            counter: account.nonce + 1,
+            entity_id: None,
+            net_id: net_id(),
+            signature: vec![],
+            data: Some(Data::PaymentTransaction(payment_tx_data)),
+            fee_signature: vec![], // sender pays fee
+            signature_scheme_id: 0,
+            fee_signature_scheme_id: 0,
+        };
+
+        tx.sign(&self.client_id)?;
+
+        client
+            .submit_transaction(SubmitTransactionRequest {
+                transaction: Some(tx),
+            })
+            .await?
+            .into_inner()
+            .id
+            .ok_or_else(|| anyhow!("missing transaction id"))
+    }
+
+    /// Verify a payment to us on the blockchain and mark it as used.
+    /// A payment can only be used once so it can't pay for the same thing twice or for something else
+    pub(crate) async fn accept_payment(
+        &mut self,
+        transaction_id: Option<TransactionId>,
+        payer: &[u8],
+        amount: u64,
+        payment_id: u64,
+    ) -> Result<()> {
+        let transaction_id = transaction_id.ok_or_else(|| anyhow!("missing transaction id"))?;
+
+        if SimpleClient::is_used_payment(&transaction_id.id).await? {
+            bail!("payment was already used")
+        }
+
+        let payee = self.get_payment_address()?;
+        let tx_info = self
+            .blockchain_service_client
+            .as_mut()
+            .ok_or_else(|| anyhow!("No blockchain service set on this client"))?
+            .get_transaction(GetTransactionRequest {
+                id: Some(transaction_id.clone()),
+            })
+            .await?
+            .into_inner()
+            .transaction_info
+            .ok_or_else(|| anyhow!("unknown transaction"))?;
+
+        verify_payment(&tx_info, &payee, payer, amount, payment_id)?;
+
+        SimpleClient::store_used_payment(&transaction_id.id, payment_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+
+    #[test]
+    fn test_verify_payment() {
+        let payer = vec![1u8; 32];
+        let payee = Address { data: vec![2; 20] };
+
+        let payment = |receiver: &Address, value: u64, id: u64| TransactionInfo {
+            id: Some(TransactionId { id: vec![3; 32] }),
+            state: TransactionState::Confirmed as i32,
+            transaction: Some(Transaction {
+                sender_pub_key: payer.clone(),
+                data: Some(Data::PaymentTransaction(PaymentTransactionData {
+                    receiver: Some(receiver.clone()),
+                    coins: Some(Amount {
+                        value,
+                        coin_type: CoinType::Core as i32,
+                    }),
+                    id,
+                })),
+                ..Default::default()
+            }),
+            transaction_type: 0,
+            block_id: 1,
+        };
+
+        assert!(verify_payment(&payment(&payee, 10, 7), &payee, &payer, 10, 7).is_ok());
+        assert!(verify_payment(&payment(&payee, 11, 7), &payee, &payer, 10, 7).is_ok());
+
+        // too small, to someone else or for something else
+        assert!(verify_payment(&payment(&payee, 9, 7), &payee, &payer, 10, 7).is_err());
+        let other = Address { data: vec![4; 20] };
+        assert!(verify_payment(&payment(&other, 10, 7), &payee, &payer, 10, 7).is_err());
+        assert!(verify_payment(&payment(&payee, 10, 8), &payee, &payer, 10, 7).is_err());
+
+        // from someone else
+        assert!(verify_payment(&payment(&payee, 10, 7), &payee, &[5; 32], 10, 7).is_err());
+
+        // not confirmed
+        let mut rejected = payment(&payee, 10, 7);
+        rejected.state = TransactionState::RejectedInsufficientFunds as i32;
+        assert!(verify_payment(&rejected, &payee, &payer, 10, 7).is_err());
+    }
+
+    #[test]
+    fn test_used_payments() {
+        run_with_test_db(async {
+            let transaction_id = vec![46u8; 32];
+            assert!(!SimpleClient::is_used_payment(&transaction_id)
+                .await
+                .unwrap());
+
+            SimpleClient::store_used_payment(&transaction_id, 7)
+                .await
+                .unwrap();
+            assert!(SimpleClient::is_used_payment(&transaction_id)
+                .await
+                .unwrap());
+        });
+    }
+}
@@ -4,10 +4,14 @@
 
 use crate::simple_client::SimpleClient;
 use anyhow::Result;
+use base::api_types_extensions::NetworkScoped;
+use base::client_config_service::ClientConfigService;
+use base::server_config_service::TLS_CA_CERT_FILE_CONFIG_KEY;
 use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
 use base::snp::snp_blockchain::SetBalanceRequest;
 use base::snp::snp_core_types::DialupInfo;
 use base::snp::snp_payments::{Amount, CoinType};
+use common::network_salt::net_id;
 use xactor::*;
 
 #[message(result = "Result<()>")]
@@ -20,19 +24,17 @@ pub(crate) struct SetBlockchainService {
 impl Handler<SetBlockchainService> for SimpleClient {
     async fn handle(&mut self, _ctx: &mut Context<Self>, msg: SetBlockchainService) -> Result<()> {
         let dialup_info = msg.info;
+        dialup_info.verify_net_id(net_id())?;
 
         info!(
             "connecting to blockchain service... {} {}",
             dialup_info.ip_address, dialup_info.port
         );
 
-        self.blockchain_service_client = Some(
-            BlockchainServiceClient::connect(format!(
-                "http://{}:{}",
-                dialup_info.ip_address, dialup_info.port
-            ))
-            .await?,
-        );
+        let ca_cert_file = ClientConfigService::get(TLS_CA_CERT_FILE_CONFIG_KEY.into()).await?;
+        self.blockchain_service_client = Some(BlockchainServiceClient::new(
+            dialup_info.connect(ca_cert_file).await?,
+        ));
 
         info!(
             "Blockchain service set to {} {}",
@@ -2,8 +2,12 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::simple_client::{SimpleClient, SNP_PROTOCOL_VERSION};
+use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, bail, Result};
+use base::client_config_service::ClientConfigService;
+use base::protocol_version::{VersionRange, X2DH_HYBRID_VERSION, X2DH_VERSION};
+use base::request_rejection::status_error;
+use base::server_config_service::TLS_CA_CERT_FILE_CONFIG_KEY;
 use base::snp::snp_core_types::{
     ClientIdentityBundle, DialupInfo, EntityId, PreKey, ProviderSignedClientIdentityBundle,
     PublicKey,
@@ -15,12 +19,15 @@ use base::snp::snp_server_api::{
     StartServiceResponse,
 };
 
-use base::api_types_extensions::Signed;
+use base::api_types_extensions::{NetworkScoped, Signed};
 use base::snp::snp_blockchain::transaction::Data;
 use base::snp::snp_blockchain::{
-    ClientBundleTransactionData, SubmitTransactionRequest, Transaction, TransactionFee,
+    ClientBundleTransactionData, GetAccountRequest, SubmitTransactionRequest, Transaction,
+    TransactionFee,
 };
+use base::transparency_log::bundle_hash;
 use chrono::prelude::*;
+use common::network_salt::net_id;
 use xactor::*;
 
 #[message(result = "Result<ProviderSignedClientIdentityBundle>")]
@@ -36,18 +43,32 @@ impl Handler<SetProvider> for SimpleClient {
         &mut self,
         _ctx: &mut Context<Self>,
         msg: SetProvider,
+    ) -> Result<ProviderSignedClientIdentityBundle> {
+        self.start_service_with_provider(msg.dialup_info).await
+    }
+}
+
+impl SimpleClient {
+    /// Connect to a provider, start being served by it and set it as this client's provider.
+    /// Returns the client bundle signed by the provider.
+    pub(crate) async fn start_service_with_provider(
+        &mut self,
+        info: DialupInfo,
     ) -> Result<ProviderSignedClientIdentityBundle> {
         // Step 1 - connect to provider. store connection and get provider bundle and store it
 
-        let info = msg.dialup_info;
-        info!(
-            "Connecting to {}",
-            format!("http://{}:{}", info.ip_address, info.port)
-        );
+        info.verify_net_id(net_id())?;
+
+        // use the newest protocol version supported by both the provider and this client
+        let protocol_version = VersionRange::snp_protocol()
+            .negotiate(&VersionRange::from_dialup_info(&info)?)?
+            .to_string();
+
+        info!("Connecting to {}", info.url());
 
+        let ca_cert_file = ClientConfigService::get(TLS_CA_CERT_FILE_CONFIG_KEY.into()).await?;
         let mut provider_api_service =
-            ProviderCoreServiceClient::connect(format!("http://{}:{}", info.ip_address, info.port))
-                .await?;
+            ProviderCoreServiceClient::new(info.connect(ca_cert_file).await?);
 
         info!("Connected to {}", info.name);
 
@@ -80,18 +101,27 @@ impl Handler<SetProvider> for SimpleClient {
 
         let provider_bundle = provider_api_service
             .get_identity_bundle(GetIdentityBundleRequest {
-                protocol_version: SNP_PROTOCOL_VERSION.into(),
+                protocol_version: protocol_version.clone(),
             })
-            .await?
+            .await
+            .map_err(status_error)?
             .into_inner()
             .bundle
             .ok_or_else(|| anyhow!("missing provider bundle"))?;
 
+        provider_bundle.verify_net_id(net_id())?;
+        provider_bundle
+            .pre_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing provider pre-key"))?
+            .verify_x2dh_version()?;
+
         info!("got provider bundle");
 
         self.provider_bundle = Some(provider_bundle.clone());
         // Store the api client with our provider for later use
         self.provider_net_client = Some(provider_api_service);
+        self.provider_protocol_version = Some(protocol_version);
 
         // Step 2 - create client bundle, sign it and send StartService to provider
 
@@ -109,6 +139,12 @@ impl Handler<SetProvider> for SimpleClient {
             nickname: self.client_name.clone(),
         };
 
+        // hybrid pre-keys include our ml-kem encapsulation key
+        let (x2dh_version, kem_key) = match self.kem_pre_key.as_ref() {
+            Some(kem_pre_key) => (X2DH_HYBRID_VERSION, kem_pre_key.encapsulation_key.clone()),
+            None => (X2DH_VERSION, vec![]),
+        };
+
         let mut client_bundle = ClientIdentityBundle {
             time_stamp: Utc::now().timestamp_nanos() as u64,
             client_id: Some(client_entity.clone()),
@@ -116,20 +152,24 @@ impl Handler<SetProvider> for SimpleClient {
             address: Some(Address::new(&client_id_pub_key)),
             provider_bundle: Some(self.provider_bundle.as_ref().unwrap().clone()),
             pre_key: Some(PreKey {
-                x2dh_version: "".to_string(),
+                x2dh_version: x2dh_version.into(),
                 key: Some(client_pre_key_public),
                 key_id: 0,
+                kem_key,
             }),
             one_time_keys: vec![],
             profile_image: None,
             signature: None,
-            net_id: 0,
+            net_id: net_id(),
         };
 
         client_bundle.sign(&self.client_id)?;
 
         // Store our client bundle for future use
         self.client_bundle = Some(client_bundle.clone());
+        let published_bundle_hash = bundle_hash(&client_bundle);
+        SimpleClient::store_published_bundle(&published_bundle_hash).await?;
+        self.published_bundles.insert(published_bundle_hash);
 
         info!("requesting start service...");
 
@@ -139,6 +179,7 @@ impl Handler<SetProvider> for SimpleClient {
             payment: None,
             service_contract_id: contract.id,
             contract_options: 0, // monthly fixed or pay per usage
+            delivery_token: self.delivery_token.clone(), // same token with all our providers
         };
 
         use prost::Message;
@@ -164,7 +205,9 @@ impl Handler<SetProvider> for SimpleClient {
                 anyhow!("failed to decode client terms of service request: {:?}", e)
             })?;
 
-        // subscribe to messages for this client on the provider
+        // subscribe to messages for this client on the provider. Cursors are per provider.
+        self.messages_cursor = 0;
+        self.store_provider_data().await?;
         self.subscribe_to_provider_messages().await?;
 
         let client_bundle = start_service_resp
@@ -191,11 +234,24 @@ impl Handler<SetProvider> for SimpleClient {
 }
 
 impl SimpleClient {
-    async fn _publish_client_bundle(
+    /// Publish a provider signed client bundle on the blockchain so other clients can find this client
+    /// with its provider
+    pub(crate) async fn publish_client_bundle(
         &mut self,
         bundle: &ProviderSignedClientIdentityBundle,
     ) -> Result<()> {
+        let payment_address = self.get_payment_address()?;
+
         if let Some(client) = self.blockchain_service_client.as_mut() {
+            let account = client
+                .get_account(GetAccountRequest {
+                    address: Some(payment_address),
+                })
+                .await?
+                .into_inner()
+                .account
+                .ok_or_else(|| anyhow!("missing client account"))?;
+
             let bundle_tx_data = ClientBundleTransactionData {
                 client_bundle: Some(bundle.clone()),
             };
@@ -211,25 +267,26 @@ impl SimpleClient {
             let mut tx = Transaction {
                 sender_pub_key: self.client_id.public.to_bytes().to_vec(),
                 fee: Some(tx_fee),
-                counter: 1,
+                counter: account.nonce + 1,
                 entity_id: None,
-                net_id: 0,
+                net_id: net_id(),
                 signature: vec![],
                 data: Some(Data::ClientBundle(bundle_tx_data)),
                 fee_signature: vec![], // sender pays fee
+                signature_scheme_id: 0,
+                fee_signature_scheme_id: 0,
             };
 
-            tx.sign(&self.client_id).unwrap();
+            tx.sign(&self.client_id)?;
 
             let res = client
                 .submit_transaction(SubmitTransactionRequest {
                     transaction: Some(tx),
                 })
-                .await
-                .unwrap()
+                .await?
                 .into_inner();
 
-            let _tx_id = res.id.unwrap();
+            let _tx_id = res.id.ok_or_else(|| anyhow!("missing transaction id"))?;
 
             Ok(())
         } else {
@@ -0,0 +1,236 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::snp::snp_core_types::{
+    ClientIdentityBundle, DialupInfo, ProviderIdentityBundle, ProviderSignedClientIdentityBundle,
+    ServiceTermsBundle,
+};
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::{MessageType, StopServiceRequest};
+use std::time::Duration;
+use tokio::time::sleep;
+use tonic::transport::Channel;
+use xactor::*;
+
+/// Number of attempts to publish our new bundle and to stop service with a provider
+const SWITCH_PROVIDER_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry of a failed switch provider step. Doubled on each retry
+const SWITCH_PROVIDER_MIN_BACKOFF: Duration = Duration::from_millis(500);
+
+#[message(result = "Result<ProviderSignedClientIdentityBundle>")]
+pub(crate) struct SwitchProvider {
+    pub(crate) dialup_info: DialupInfo,
+}
+
+/// Our provider state before a switch so we can go back to it
+struct ProviderState {
+    provider_bundle: ProviderIdentityBundle,
+    provider_net_client: ProviderCoreServiceClient<Channel>,
+    provider_terms: Option<ServiceTermsBundle>,
+    provider_protocol_version: Option<String>,
+    client_bundle: Option<ClientIdentityBundle>,
+    messages_cursor: u64,
+}
+
+/// Move this client from its current provider to another provider.
+/// 1. Client starts being served by the new provider.
+/// 2. Client publishes its new provider signed bundle on-chain so other clients send messages via its new provider.
+///    If it can't, client asks the new provider to stop serving it and goes back to its current provider.
+/// 3. Client sends its current provider a signed stop service request with its new bundle.
+///    The old provider forwards pending and new messages to the new provider for a grace period.
+#[async_trait::async_trait]
+impl Handler<SwitchProvider> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: SwitchProvider,
+    ) -> Result<ProviderSignedClientIdentityBundle> {
+        // Step 1 - keep our current provider so we can go back to it or ask it to stop serving us
+        let mut old_state = ProviderState {
+            provider_bundle: self
+                .provider_bundle
+                .clone()
+                .ok_or_else(|| anyhow!("no provider to switch from - set a provider first"))?,
+            provider_net_client: self
+                .provider_net_client
+                .clone()
+                .ok_or_else(|| anyhow!("missing provider net client"))?,
+            provider_terms: self.provider_terms.clone(),
+            provider_protocol_version: self.provider_protocol_version.clone(),
+            client_bundle: self.client_bundle.clone(),
+            messages_cursor: self.messages_cursor,
+        };
+
+        // Step 2 - start service with the new provider
+        let signed_bundle = match self.start_service_with_provider(msg.dialup_info).await {
+            Ok(bundle) => bundle,
+            Err(e) => {
+                // stay with our current provider
+                self.restore_provider_state(old_state);
+                return Err(e);
+            }
+        };
+
+        // Step 3 - publish our new bundle so senders find us with our new provider.
+        // Old provider keeps serving us until our new bundle is published
+        info!(
+            "started service with new provider - publishing new client bundle to the blockchain..."
+        );
+        if let Err(e) = self
+            .publish_client_bundle_with_retries(&signed_bundle)
+            .await
+        {
+            warn!(
+                "failed to publish new client bundle: {:?}. going back to old provider...",
+                e
+            );
+            self.rollback_switch_provider(old_state).await?;
+            return Err(e);
+        }
+
+        // Step 4 - ask old provider to stop serving us and hand over our messages to the new provider
+        info!("published new client bundle - stopping service with old provider...");
+        let client_bundle = self.client_bundle.clone();
+        self.stop_service_with_retries(
+            &old_state.provider_bundle,
+            &mut old_state.provider_net_client,
+            client_bundle,
+        )
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "switched provider but failed to stop service with old provider: {:?}",
+                e
+            )
+        })?;
+
+        info!("switched provider");
+
+        Ok(signed_bundle)
+    }
+}
+
+impl SimpleClient {
+    /// Set our provider state back to a state saved before a provider switch
+    fn restore_provider_state(&mut self, state: ProviderState) {
+        self.provider_bundle = Some(state.provider_bundle);
+        self.provider_net_client = Some(state.provider_net_client);
+        self.provider_terms = state.provider_terms;
+        self.provider_protocol_version = state.provider_protocol_version;
+        self.client_bundle = state.client_bundle;
+        self.messages_cursor = state.messages_cursor;
+    }
+
+    /// Go back to our old provider after we started service with a new provider.
+    /// New provider is asked to stop serving us and to hand over messages it got for us to the old provider
+    async fn rollback_switch_provider(&mut self, old_state: ProviderState) -> Result<()> {
+        let new_provider_bundle = self
+            .provider_bundle
+            .clone()
+            .ok_or_else(|| anyhow!("missing new provider bundle"))?;
+
+        let mut new_provider_net_client = self
+            .provider_net_client
+            .clone()
+            .ok_or_else(|| anyhow!("missing new provider net client"))?;
+
+        let old_client_bundle = old_state.client_bundle.clone();
+        self.restore_provider_state(old_state);
+        self.store_provider_data().await?;
+        self.subscribe_to_provider_messages().await?;
+
+        if let Err(e) = self
+            .stop_service_with_retries(
+                &new_provider_bundle,
+                &mut new_provider_net_client,
+                old_client_bundle,
+            )
+            .await
+        {
+            // we are back with our old provider which is the one on-chain
+            warn!("failed to stop service with new provider: {:?}", e);
+        }
+
+        info!("back with old provider");
+
+        Ok(())
+    }
+
+    /// Publish a client bundle on the blockchain, retrying with backoff on failures
+    async fn publish_client_bundle_with_retries(
+        &mut self,
+        bundle: &ProviderSignedClientIdentityBundle,
+    ) -> Result<()> {
+        let mut backoff = SWITCH_PROVIDER_MIN_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            match self.publish_client_bundle(bundle).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < SWITCH_PROVIDER_ATTEMPTS => {
+                    warn!(
+                        "failed to publish client bundle: {:?}. retrying in {:?}",
+                        e, backoff
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+            sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// Ask a provider to stop serving us and to hand over our messages to the provider of client_bundle.
+    /// Retries with backoff on failures
+    async fn stop_service_with_retries(
+        &mut self,
+        provider_bundle: &ProviderIdentityBundle,
+        provider_api_service: &mut ProviderCoreServiceClient<Channel>,
+        client_bundle: Option<ClientIdentityBundle>,
+    ) -> Result<()> {
+        let stop_service_request = StopServiceRequest { client_bundle };
+
+        use prost::Message;
+        let mut buff = Vec::with_capacity(stop_service_request.encoded_len());
+        stop_service_request.encode(&mut buff)?;
+
+        let ikb = provider_bundle.get_provider_id_ed25519_public_key()?;
+
+        let mut backoff = SWITCH_PROVIDER_MIN_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            let res = self
+                .send_message_to_provider_with_client(
+                    ikb,
+                    provider_api_service,
+                    MessageType::StopServiceRequest,
+                    buff.clone(),
+                )
+                .await
+                .and_then(|resp| {
+                    if resp.msg_type != MessageType::StopServiceResponse as i32 {
+                        bail!("unexpected response message type {}", resp.msg_type)
+                    }
+                    Ok(())
+                });
+
+            match res {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < SWITCH_PROVIDER_ATTEMPTS => {
+                    warn!(
+                        "failed to stop service with provider: {:?}. retrying in {:?}",
+                        e, backoff
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+            sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
@@ -2,12 +2,23 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use crate::services::client_events::ClientEventsSender;
+use crate::services::client_store::{client_col_descriptors, ResumeProviderService};
+use crate::services::contact_identities::ContactIdentity;
 use crate::services::grpc_api_service::SimpleClientGrpcService;
+use crate::user_to_user_messaging::attachments::IncomingAttachment;
 use anyhow::{anyhow, Result};
-use base::client_config_service::ClientConfigService;
+use base::client_config_service::{
+    ClientConfigService, COVER_TRAFFIC_INTERVAL_CONFIG_KEY, IDENTITY_AUDIT_INTERVAL_CONFIG_KEY,
+    MESSAGES_PURGE_INTERVAL_CONFIG_KEY, SUBSCRIPTIONS_CHECK_INTERVAL_CONFIG_KEY,
+    X2DH_HYBRID_CONFIG_KEY,
+};
 use base::hex_utils::short_hex_string;
-use base::server_config_service::{DB_NAME_CONFIG_KEY, DROP_DB_CONFIG_KEY};
+use base::server_config_service::{
+    DB_NAME_CONFIG_KEY, DROP_DB_CONFIG_KEY, MESSAGE_PADDING_CONFIG_KEY, NET_ID_CONFIG_KEY,
+};
 use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
+use base::snp::snp_blockchain::TransparencyLogHead;
 use base::snp::snp_core_types::{
     ChannelBundle, ClientIdentityBundle, ContentItem, EntityId, ProviderIdentityBundle,
     ProviderSignedClientIdentityBundle, ServiceTermsBundle,
@@ -15,45 +26,77 @@ use base::snp::snp_core_types::{
 use base::snp::snp_payments::Address;
 use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
 use base::snp::upsetter_simple_client::simple_client_user_service_server::SimpleClientUserServiceServer;
+use common::{message_padding, network_salt};
+use crypto::ml_kem;
 use db::db_service::{Configure, DatabaseService, PROVIDER_COL_FAMILY, TESTS_COL_FAMILY};
 use ed25519_dalek::Keypair;
 use rand_core::OsRng;
 use rocksdb::{ColumnFamilyDescriptor, Options};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use tonic::transport::{Channel, Server};
 use x25519_dalek::StaticSecret;
 use xactor::*;
 
-pub const SNP_PROTOCOL_VERSION: &str = "0.1.0";
+pub use base::protocol_version::SNP_PROTOCOL_VERSION;
 
-/// A simple client creates a new id when it is running
-/// and has only one pre_key it uses
-/// Currently doesn't use a db or x2dh or dr services for more robust functionality
-/// todo: use a DR service to store DR sessions w provider and w other clients instead of hard-coded ones
+/// A simple client creates a new id on its first run and has only one pre_key it uses.
+/// Its identity, provider, contacts, channels and paid items are persisted in the client db
+/// so a restarted client resumes with the same identity and DR sessions
 pub struct SimpleClient {
     pub(crate) client_name: String,
     /// client long term ed25519 id
     pub(crate) client_id: Keypair,
     /// for now we assume only 1 pre-key for the client and we don't create new ones yet
     pub(crate) pre_key: StaticSecret,
+    /// our ml-kem pre-key. Set when we publish a hybrid x2dh pre-key
+    pub(crate) kem_pre_key: Option<ml_kem::KeyPair>,
     /// our provider bundle
     pub(crate) provider_bundle: Option<ProviderIdentityBundle>,
+    /// protocol version negotiated with our provider
+    pub(crate) provider_protocol_version: Option<String>,
     /// our provider terms of service
     pub(crate) provider_terms: Option<ServiceTermsBundle>,
     /// a connection with our provider
     pub(crate) provider_net_client: Option<ProviderCoreServiceClient<Channel>>,
+    /// cursor of the last messages metadata handled from our provider messages stream
+    pub(crate) messages_cursor: u64,
+    /// id of the current provider messages subscription. Ended subscriptions which were replaced don't reconnect
+    pub(crate) messages_subscription_id: u64,
     /// our client bundle with our provider
     pub(crate) client_bundle: Option<ClientIdentityBundle>,
+    /// hashes of all client bundles we signed. Bundles of our identity in the transparency log must be one of them
+    pub(crate) published_bundles: HashSet<Vec<u8>>,
+    /// last transparency log head we audited our identity in
+    pub(crate) transparency_log_head: Option<TransparencyLogHead>,
     /// other clients indexed by pub key
     pub(crate) other_clients: HashMap<Vec<u8>, ProviderSignedClientIdentityBundle>,
+    /// identities of other clients pinned on first use indexed by nickname
+    pub(crate) contacts: HashMap<String, ContactIdentity>,
+    /// token we give our providers and other clients. Authorizes delivery of sealed-sender messages to us
+    pub(crate) delivery_token: Vec<u8>,
+    /// delivery tokens other clients gave us indexed by pub key. Used to send sealed-sender messages to them
+    pub(crate) other_clients_delivery_tokens: HashMap<Vec<u8>, Vec<u8>>,
+    /// protocol versions negotiated with other clients indexed by pub key. Gate protocol features in messages to them
+    pub(crate) other_clients_protocol_versions: HashMap<Vec<u8>, String>,
     /// channels this client is subscribed to (groups and status updates)
     pub(crate) channels_subscriptions: HashMap<Vec<u8>, ChannelBundle>,
+    /// time our paid channels subscriptions are paid through (unix nanos) indexed by channel id
+    pub(crate) subscriptions_paid_through: HashMap<Vec<u8>, u64>,
     /// channels client requested to subscribe to but subscription not confirmed yet
     pub(crate) channels_subscriptions_requests: HashMap<Vec<u8>, ChannelBundle>,
     /// paid content items published by this client
     pub(crate) paid_items: HashMap<u64, ContentItem>,
     /// A name server client used to communicate with a name service
     pub(crate) blockchain_service_client: Option<BlockchainServiceClient<Channel>>,
+    /// streams of clients subscribed to our events
+    pub(crate) events_subscribers: Vec<ClientEventsSender>,
+    /// ids of messages we received and didn't send delivered receipts for yet, indexed by sender pub key
+    pub(crate) pending_delivery_receipts: HashMap<Vec<u8>, Vec<u64>>,
+    /// pub keys of contacts we don't send read receipts to
+    pub(crate) read_receipts_off: HashSet<Vec<u8>>,
+    /// messages with attachments we are receiving in chunks indexed by sender pub key and transfer id
+    pub(crate) incoming_attachments: HashMap<(Vec<u8>, u64), IncomingAttachment>,
 }
 
 impl SimpleClient {
@@ -88,19 +131,42 @@ impl Default for SimpleClient {
             client_name: "client???".into(),
             client_id,
             pre_key: StaticSecret::new(&mut OsRng),
+            kem_pre_key: None,
             provider_bundle: None,
             provider_net_client: None,
+            messages_cursor: 0,
+            messages_subscription_id: 0,
             client_bundle: None,
+            published_bundles: HashSet::new(),
+            transparency_log_head: None,
             channels_subscriptions: HashMap::new(),
+            subscriptions_paid_through: HashMap::new(),
             channels_subscriptions_requests: HashMap::new(),
             other_clients: HashMap::new(),
+            contacts: HashMap::new(),
+            delivery_token: new_delivery_token(),
+            other_clients_delivery_tokens: HashMap::new(),
+            other_clients_protocol_versions: HashMap::new(),
             paid_items: HashMap::new(),
             blockchain_service_client: None,
+            events_subscribers: vec![],
+            pending_delivery_receipts: HashMap::new(),
+            read_receipts_off: HashSet::new(),
+            incoming_attachments: HashMap::new(),
             provider_terms: None,
+            provider_protocol_version: None,
         }
     }
 }
 
+/// Returns a new random delivery token
+fn new_delivery_token() -> Vec<u8> {
+    use rand_core::RngCore;
+    let mut token = vec![0u8; 32];
+    OsRng.fill_bytes(&mut token);
+    token
+}
+
 impl Service for SimpleClient {}
 
 #[async_trait::async_trait]
@@ -108,6 +174,73 @@ impl Actor for SimpleClient {
     async fn started(&mut self, _ctx: &mut Context<Self>) -> Result<()> {
         // init here system services used by this client
 
+        let net_id = ClientConfigService::get_u64(NET_ID_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default() as u32;
+        network_salt::set_net_id(net_id);
+        info!("client network id: {}", net_id);
+
+        let padding = ClientConfigService::get_bool(MESSAGE_PADDING_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default();
+        message_padding::set_message_padding(padding);
+
+        if ClientConfigService::get_bool(X2DH_HYBRID_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default()
+        {
+            info!("using hybrid post-quantum x2dh pre-key");
+            self.kem_pre_key = Some(ml_kem::generate_key_pair());
+        }
+
+        let cover_traffic_interval =
+            ClientConfigService::get_u64(COVER_TRAFFIC_INTERVAL_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default();
+        if cover_traffic_interval > 0 {
+            info!(
+                "sending cover traffic every {} ms on average",
+                cover_traffic_interval
+            );
+            tokio::spawn(SimpleClient::send_cover_traffic(Duration::from_millis(
+                cover_traffic_interval,
+            )));
+        }
+
+        let identity_audit_interval =
+            ClientConfigService::get_u64(IDENTITY_AUDIT_INTERVAL_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default();
+        if identity_audit_interval > 0 {
+            info!(
+                "auditing our identity in the transparency log every {} ms",
+                identity_audit_interval
+            );
+            tokio::spawn(SimpleClient::monitor_identity(Duration::from_millis(
+                identity_audit_interval,
+            )));
+        }
+
+        let messages_purge_interval =
+            ClientConfigService::get_u64(MESSAGES_PURGE_INTERVAL_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default();
+        if messages_purge_interval > 0 {
+            tokio::spawn(SimpleClient::purge_expired_messages_periodically(
+                Duration::from_millis(messages_purge_interval),
+            ));
+        }
+
+        let subscriptions_check_interval =
+            ClientConfigService::get_u64(SUBSCRIPTIONS_CHECK_INTERVAL_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default();
+        if subscriptions_check_interval > 0 {
+            tokio::spawn(SimpleClient::send_payment_reminders_periodically(
+                Duration::from_millis(subscriptions_check_interval),
+            ));
+        }
+
         info!("initializing client db...");
         let db_name = ClientConfigService::get(DB_NAME_CONFIG_KEY.into())
             .await?
@@ -116,17 +249,34 @@ impl Actor for SimpleClient {
             .await?
             .unwrap();
 
-        // no column descriptors ????
+        let mut col_descriptors = vec![
+            ColumnFamilyDescriptor::new(PROVIDER_COL_FAMILY, Options::default()),
+            ColumnFamilyDescriptor::new(TESTS_COL_FAMILY, Options::default()),
+        ];
+        col_descriptors.extend(client_col_descriptors());
+
         DatabaseService::config_db(Configure {
             drop_on_exit,
             db_name,
-            col_descriptors: vec![
-                ColumnFamilyDescriptor::new(PROVIDER_COL_FAMILY, Options::default()),
-                ColumnFamilyDescriptor::new(TESTS_COL_FAMILY, Options::default()),
-            ],
+            col_descriptors,
         })
         .await?;
 
+        self.load_client_data().await?;
+
+        // resume service with the provider we had before we were restarted
+        if self.provider_bundle.is_some() {
+            tokio::spawn(async {
+                let res = match SimpleClient::from_registry().await {
+                    Ok(client) => client.call(ResumeProviderService).await.and_then(|r| r),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = res {
+                    warn!("failed to resume service with our provider: {:?}", e);
+                }
+            });
+        }
+
         info!("SimpleClient started");
         Ok(())
     }
@@ -0,0 +1,43 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+// test helper functions
+
+use crate::services::client_store::client_col_descriptors;
+use base::test_helpers::enable_logger;
+use db::db_service::{Configure, DatabaseService};
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::runtime::Runtime;
+use tokio::sync::OnceCell;
+
+/// Run a test on a runtime shared by all tests with a configured client db.
+/// Service actors run on the runtime they were started on so tests using them must share it.
+pub(crate) fn run_with_test_db<F: Future>(test: F) -> F::Output {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    static DB: OnceCell<()> = OnceCell::const_new();
+
+    enable_logger();
+    let runtime = RUNTIME.get_or_init(|| Runtime::new().unwrap());
+    runtime.block_on(async {
+        DB.get_or_init(|| async {
+            let db_name = std::env::temp_dir()
+                .join(format!("client_test_db_{}", std::process::id()))
+                .to_str()
+                .unwrap()
+                .to_string();
+
+            DatabaseService::config_db(Configure {
+                drop_on_exit: true,
+                db_name,
+                col_descriptors: client_col_descriptors(),
+            })
+            .await
+            .unwrap();
+        })
+        .await;
+
+        test.await
+    })
+}
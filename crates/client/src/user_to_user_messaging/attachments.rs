@@ -0,0 +1,518 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::Signed;
+use base::client_config_service::ClientConfigService;
+use base::hex_utils::short_hex_string;
+use base::server_config_service::TLS_CA_CERT_FILE_CONFIG_KEY;
+use base::snp::snp_client_to_client::AttachmentChunk;
+use base::snp::snp_core_types::{ContentItem, DialupInfo, EntityId, MediaItem};
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::{GetTermsOfServiceRequest, MessageType, TypedMessage};
+use base::time_utils::{expiration_time, now};
+use bytes::Bytes;
+use common::message_padding;
+use crypto::utils::entity_from_ed25519_pub_key;
+use prost::Message;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use xactor::*;
+
+/// Max chunk size. Used when the receiver's provider doesn't limit the size of files it routes
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Upper bound of the bytes a chunk's typed message adds to its data: chunk fields, sender, signature and protocol version
+const CHUNK_MESSAGE_OVERHEAD: usize = 1024;
+
+/// Upper bound of the bytes a dr message adds to its encrypted typed message:
+/// dr header, new session x2dh data, sealed sender envelope and AEAD tag
+const DR_MESSAGE_OVERHEAD: usize = 4096;
+
+/// Max size of a message with attachments we accept, compressed or decompressed
+pub(crate) const MAX_ATTACHMENTS_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Max number of chunks of a message with attachments we accept
+const MAX_CHUNKS_COUNT: u32 = 4096;
+
+/// Max number of messages with attachments we receive concurrently from a sender
+const MAX_INCOMING_TRANSFERS_PER_SENDER: usize = 4;
+
+/// Seconds after which we drop a message with attachments we didn't get a new chunk of
+const INCOMING_TRANSFER_TIMEOUT_SECS: u64 = 10 * 60;
+
+/// A 1:1 message with attachments we are receiving in chunks
+pub(crate) struct IncomingAttachment {
+    content_hash: Vec<u8>,
+    chunks: Vec<Option<Vec<u8>>>,
+    size: usize,
+    /// time we got the last chunk of this message (unix nanos)
+    last_chunk_time: u64,
+}
+
+impl SimpleClient {
+    /// Chunk size for messages to a receiver whose provider routes messages of up to max_file_size bytes, 0 for no limit.
+    /// A chunk's typed message envelope, padding and dr message must fit in max_file_size
+    fn attachment_chunk_size(max_file_size: usize) -> Result<usize> {
+        if max_file_size == 0 {
+            return Ok(MAX_CHUNK_SIZE);
+        }
+
+        let max_typed_msg_len = max_file_size.saturating_sub(DR_MESSAGE_OVERHEAD);
+        let max_typed_msg_len = if message_padding::message_padding() {
+            message_padding::max_unpadded_len(max_typed_msg_len)
+        } else {
+            max_typed_msg_len
+        };
+
+        match max_typed_msg_len.checked_sub(CHUNK_MESSAGE_OVERHEAD) {
+            Some(size) if size > 0 => Ok(size.min(MAX_CHUNK_SIZE)),
+            _ => bail!(
+                "receiver's provider max file size of {} bytes is too small for attachments",
+                max_file_size
+            ),
+        }
+    }
+
+    /// Returns the max size of messages a receiver's provider routes to it, 0 for no limit
+    async fn receiver_max_file_size(&self, key: &[u8]) -> Result<usize> {
+        let provider_bundle = self
+            .other_clients
+            .get(key)
+            .and_then(|c| c.client_bundle.as_ref())
+            .and_then(|b| b.provider_bundle.as_ref())
+            .ok_or_else(|| anyhow!("missing receiver provider bundle"))?;
+
+        let dialup_info = DialupInfo::best_grpc_endpoint(&provider_bundle.dial_up_info)
+            .ok_or_else(|| anyhow!("missing grpc dialup info from receiver provider bundle"))?;
+
+        let ca_cert_file = ClientConfigService::get(TLS_CA_CERT_FILE_CONFIG_KEY.into()).await?;
+        let terms = ProviderCoreServiceClient::new(dialup_info.connect(ca_cert_file).await?)
+            .get_terms_of_service(GetTermsOfServiceRequest {
+                promo_code: "".to_string(),
+            })
+            .await?
+            .into_inner()
+            .terms
+            .and_then(|t| t.service_terms)
+            .ok_or_else(|| anyhow!("missing receiver provider terms of service"))?;
+
+        Ok(terms.max_file_size as usize)
+    }
+
+    /// Drop messages with attachments we are receiving which we didn't get a new chunk of for a while
+    fn drop_stale_incoming_attachments(&mut self, time_stamp: u64) {
+        let timeout = INCOMING_TRANSFER_TIMEOUT_SECS * 1_000_000_000;
+        self.incoming_attachments
+            .retain(|(sender_id, transfer_id), transfer| {
+                let stale = time_stamp.saturating_sub(transfer.last_chunk_time) > timeout;
+                if stale {
+                    debug!(
+                        "dropping stale message with attachments {} from {}",
+                        transfer_id,
+                        short_hex_string(sender_id)
+                    );
+                }
+                !stale
+            });
+    }
+
+    /// A chunk of a message with attachments from another client
+    pub(crate) async fn handle_attachment_chunk(&mut self, msg: TypedMessage) -> Result<()> {
+        let sender_id = msg.get_ika()?.as_ref().to_vec();
+        let chunk = AttachmentChunk::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode attachment chunk {:?}", e))?;
+
+        if chunk.chunks_count == 0 || chunk.chunks_count > MAX_CHUNKS_COUNT {
+            bail!("invalid chunks count {}", chunk.chunks_count)
+        }
+
+        if chunk.chunk_index >= chunk.chunks_count {
+            bail!("invalid chunk index {}", chunk.chunk_index)
+        }
+
+        if Sha256::digest(&chunk.data).to_vec() != chunk.chunk_hash {
+            bail!("chunk hash mismatch")
+        }
+
+        let time_stamp = now();
+        self.drop_stale_incoming_attachments(time_stamp);
+
+        let transfer_key = (sender_id.clone(), chunk.transfer_id);
+        if !self.incoming_attachments.contains_key(&transfer_key)
+            && self
+                .incoming_attachments
+                .keys()
+                .filter(|(id, _)| *id == sender_id)
+                .count()
+                >= MAX_INCOMING_TRANSFERS_PER_SENDER
+        {
+            bail!("too many messages with attachments in transfer from sender")
+        }
+
+        let transfer = self
+            .incoming_attachments
+            .entry(transfer_key.clone())
+            .or_insert_with(|| IncomingAttachment {
+                content_hash: chunk.content_hash.clone(),
+                chunks: vec![None; chunk.chunks_count as usize],
+                size: 0,
+                last_chunk_time: time_stamp,
+            });
+
+        if transfer.content_hash != chunk.content_hash
+            || transfer.chunks.len() != chunk.chunks_count as usize
+        {
+            bail!("chunk doesn't match its transfer")
+        }
+
+        let index = chunk.chunk_index as usize;
+        if transfer.chunks[index].is_some() {
+            debug!("ignoring a duplicated chunk {}", index);
+            return Ok(());
+        }
+
+        transfer.size += chunk.data.len();
+        transfer.chunks[index] = Some(chunk.data);
+        transfer.last_chunk_time = time_stamp;
+
+        if transfer.size > MAX_ATTACHMENTS_MESSAGE_SIZE {
+            self.incoming_attachments.remove(&transfer_key);
+            bail!("message with attachments is too large")
+        }
+
+        if transfer.chunks.iter().any(|c| c.is_none()) {
+            return Ok(());
+        }
+
+        // we got all chunks
+        let transfer = self.incoming_attachments.remove(&transfer_key).unwrap();
+        let data: Vec<u8> = transfer.chunks.into_iter().flatten().flatten().collect();
+
+        if Sha256::digest(&data).to_vec() != transfer.content_hash {
+            bail!("content hash mismatch")
+        }
+
+        let content_item = ContentItem::decode(data.as_slice())
+            .map_err(|e| anyhow!("failed to decode message with attachments {:?}", e))?;
+
+        content_item.verify_signature()?;
+        let author = content_item
+            .author
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing author"))?;
+        if author.get_id()? != &sender_id {
+            bail!("message author is not its sender")
+        }
+
+        // reject attachments we can't decompress, e.g. with an unsupported codec
+        for item in content_item.media_item.iter() {
+            item.get_content(MAX_ATTACHMENTS_MESSAGE_SIZE)?;
+        }
+
+        info!(
+            "🎉 👋 incoming message with {} attachments from {}. message id: {}",
+            // the first media item is the message's text
+            content_item.media_item.len().saturating_sub(1),
+            short_hex_string(&sender_id),
+            content_item.id,
+        );
+
+        self.receive_direct_message(&sender_id, content_item).await
+    }
+}
+
+/// Send a 1:1 message with an attachment to another client.
+/// The message is sent in chunks smaller than the max file size of our provider.
+/// Returns the message id and the number of chunks it was sent in
+#[message(result = "Result<(u64, u32)>")]
+pub struct SendAttachment {
+    pub receiver: EntityId,
+    pub text: String,
+    pub attachment: MediaItem,
+    pub reply_to: u64,
+    /// seconds after which the message self-destructs. 0 for never
+    pub ttl: u64,
+}
+
+#[async_trait::async_trait]
+impl Handler<SendAttachment> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: SendAttachment,
+    ) -> Result<(u64, u32)> {
+        if self.provider_bundle.is_none() {
+            bail!("missing provider bundle")
+        }
+
+        let key = self.resolve_other_client(&msg.receiver).await?;
+        let ikb = self
+            .other_clients
+            .get(&key)
+            .ok_or_else(|| anyhow!("missing bundle"))?
+            .client_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client bundle"))?
+            .get_client_id_ed25519_public_key()?;
+
+        let my_entity = entity_from_ed25519_pub_key(&self.client_id.public, "".into());
+        let mut content_item = ContentItem::new_one_to_one_media_message(
+            msg.text,
+            vec![msg.attachment],
+            my_entity,
+            msg.reply_to,
+        );
+        content_item.ttl = msg.ttl;
+        content_item.sign(&self.client_id)?;
+
+        let data = content_item.encode_to_vec();
+        if data.len() > MAX_ATTACHMENTS_MESSAGE_SIZE {
+            bail!("message with attachments is too large")
+        }
+
+        let content_hash = Sha256::digest(&data).to_vec();
+        let transfer_id = OsRng.next_u64();
+        let chunk_size =
+            SimpleClient::attachment_chunk_size(self.receiver_max_file_size(&key).await?)?;
+        let chunks: Vec<&[u8]> = data.chunks(chunk_size).collect();
+        let chunks_count = chunks.len() as u32;
+        let expires = expiration_time(now(), msg.ttl).unwrap_or_default();
+
+        for (index, chunk_data) in chunks.into_iter().enumerate() {
+            let chunk = AttachmentChunk {
+                transfer_id,
+                chunk_index: index as u32,
+                chunks_count,
+                data: chunk_data.to_vec(),
+                chunk_hash: Sha256::digest(chunk_data).to_vec(),
+                content_hash: content_hash.clone(),
+            };
+
+            let typed_msg = self.create_typed_message(
+                MessageType::AttachmentChunk,
+                chunk.encode_to_vec(),
+                ikb,
+            )?;
+            self.send_expiring_typed_message(typed_msg, Bytes::from(key.clone()), expires)
+                .await?;
+        }
+
+        SimpleClient::store_sent_message(
+            self.get_other_client_entity(&key),
+            content_item.id,
+            msg.ttl,
+        )
+        .await?;
+
+        Ok((content_item.id, chunks_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+    use base::snp::snp_core_types::{CompressionCodec, MimeType, PublicKey, Signature};
+
+    fn chunk_message(sender: &[u8], chunk: &AttachmentChunk) -> TypedMessage {
+        TypedMessage {
+            time_stamp: 0,
+            msg_type: MessageType::AttachmentChunk as i32,
+            message: chunk.encode_to_vec(),
+            receiver: None,
+            sender: Some(EntityId {
+                public_key: Some(PublicKey {
+                    key: sender.to_vec(),
+                }),
+                nickname: "".into(),
+            }),
+            signature: Some(Signature::default()),
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
+        }
+    }
+
+    /// Returns the first of 3 chunks of a message with attachments
+    fn first_chunk(transfer_id: u64) -> AttachmentChunk {
+        let data = vec![1u8; 32];
+        AttachmentChunk {
+            transfer_id,
+            chunk_index: 0,
+            chunks_count: 3,
+            chunk_hash: Sha256::digest(&data).to_vec(),
+            data,
+            content_hash: vec![0; 32],
+        }
+    }
+
+    #[test]
+    fn test_incoming_attachments_limits() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+            let sender = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng)
+                .public
+                .to_bytes()
+                .to_vec();
+            let other_sender = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng)
+                .public
+                .to_bytes()
+                .to_vec();
+
+            for transfer_id in 0..MAX_INCOMING_TRANSFERS_PER_SENDER as u64 {
+                client
+                    .handle_attachment_chunk(chunk_message(&sender, &first_chunk(transfer_id)))
+                    .await
+                    .unwrap();
+            }
+
+            // a sender can't start more transfers than the cap
+            let transfer_id = MAX_INCOMING_TRANSFERS_PER_SENDER as u64;
+            assert!(client
+                .handle_attachment_chunk(chunk_message(&sender, &first_chunk(transfer_id)))
+                .await
+                .is_err());
+
+            // chunks of its ongoing transfers and transfers of other senders are accepted
+            let mut chunk = first_chunk(0);
+            chunk.chunk_index = 1;
+            client
+                .handle_attachment_chunk(chunk_message(&sender, &chunk))
+                .await
+                .unwrap();
+            client
+                .handle_attachment_chunk(chunk_message(&other_sender, &first_chunk(0)))
+                .await
+                .unwrap();
+
+            // stale transfers are dropped
+            let stale_time = now() - (INCOMING_TRANSFER_TIMEOUT_SECS + 1) * 1_000_000_000;
+            for ((id, _), transfer) in client.incoming_attachments.iter_mut() {
+                if *id == sender {
+                    transfer.last_chunk_time = stale_time;
+                }
+            }
+
+            client
+                .handle_attachment_chunk(chunk_message(&sender, &first_chunk(transfer_id)))
+                .await
+                .unwrap();
+            assert_eq!(client.incoming_attachments.len(), 2);
+            assert!(client
+                .incoming_attachments
+                .contains_key(&(sender.clone(), transfer_id)));
+            assert!(client
+                .incoming_attachments
+                .contains_key(&(other_sender.clone(), 0)));
+        });
+    }
+
+    #[test]
+    fn test_attachment_chunk_size() {
+        assert_eq!(
+            SimpleClient::attachment_chunk_size(0).unwrap(),
+            MAX_CHUNK_SIZE
+        );
+        assert_eq!(
+            SimpleClient::attachment_chunk_size(16 * 1024 * 1024).unwrap(),
+            MAX_CHUNK_SIZE
+        );
+
+        // a chunk's message must fit in the receiver provider's max file size
+        let max_file_size = 128 * 1024;
+        let chunk_size = SimpleClient::attachment_chunk_size(max_file_size).unwrap();
+        assert!(chunk_size + CHUNK_MESSAGE_OVERHEAD + DR_MESSAGE_OVERHEAD <= max_file_size);
+
+        let client = SimpleClient::default();
+        let receiver = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+        let data = vec![7u8; chunk_size];
+        let chunk = AttachmentChunk {
+            transfer_id: u64::MAX,
+            chunk_index: u32::MAX,
+            chunks_count: u32::MAX,
+            chunk_hash: Sha256::digest(&data).to_vec(),
+            data,
+            content_hash: vec![0; 32],
+        };
+        let typed_msg = client
+            .create_typed_message(
+                MessageType::AttachmentChunk,
+                chunk.encode_to_vec(),
+                receiver.public,
+            )
+            .unwrap();
+        assert!(typed_msg.encoded_len() <= chunk_size + CHUNK_MESSAGE_OVERHEAD);
+
+        assert!(
+            SimpleClient::attachment_chunk_size(DR_MESSAGE_OVERHEAD + CHUNK_MESSAGE_OVERHEAD)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_attachment_chunks() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+            let sender = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+            let sender_key = sender.public.to_bytes().to_vec();
+
+            let attachment = MediaItem::new(
+                1,
+                "file.txt".into(),
+                MimeType::TextUtf8,
+                CompressionCodec::Zlib030,
+                "some file content".repeat(100).as_bytes(),
+            )
+            .unwrap();
+            let mut content_item = ContentItem::new_one_to_one_media_message(
+                "".into(),
+                vec![attachment],
+                entity_from_ed25519_pub_key(&sender.public, "".into()),
+                0,
+            );
+            content_item.sign(&sender).unwrap();
+
+            let data = content_item.encode_to_vec();
+            let content_hash = Sha256::digest(&data).to_vec();
+            let chunks: Vec<AttachmentChunk> = data
+                .chunks(32)
+                .enumerate()
+                .map(|(index, chunk_data)| AttachmentChunk {
+                    transfer_id: 7,
+                    chunk_index: index as u32,
+                    chunks_count: (data.len() as u32).div_ceil(32),
+                    data: chunk_data.to_vec(),
+                    chunk_hash: Sha256::digest(chunk_data).to_vec(),
+                    content_hash: content_hash.clone(),
+                })
+                .collect();
+            assert!(chunks.len() > 2);
+
+            // a chunk with a wrong hash is rejected
+            let mut bad_chunk = chunks[0].clone();
+            bad_chunk.data[0] ^= 1;
+            assert!(client
+                .handle_attachment_chunk(chunk_message(&sender_key, &bad_chunk))
+                .await
+                .is_err());
+
+            // chunks may arrive out of order and more than once
+            let mut received: Vec<&AttachmentChunk> = chunks.iter().rev().collect();
+            received.insert(1, &chunks[chunks.len() - 1]);
+            for chunk in received {
+                client
+                    .handle_attachment_chunk(chunk_message(&sender_key, chunk))
+                    .await
+                    .unwrap();
+            }
+
+            assert!(client.incoming_attachments.is_empty());
+            assert_eq!(
+                client.pending_delivery_receipts.get(&sender_key),
+                Some(&vec![content_item.id])
+            );
+        });
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::simple_client::SimpleClient;
+use anyhow::Result;
+use base::time_utils::now;
+use std::time::Duration;
+use tokio::time::sleep;
+
+impl SimpleClient {
+    /// Periodically purge expired self-destructing messages from the client db
+    pub(crate) async fn purge_expired_messages_periodically(interval: Duration) {
+        loop {
+            sleep(interval).await;
+
+            if let Err(e) = SimpleClient::purge_expired_messages(now()).await {
+                warn!("failed to purge expired messages: {:?}", e);
+            }
+        }
+    }
+
+    /// Delete self-destructing messages we received and our copies of ones we sent which expired by time_stamp
+    pub(crate) async fn purge_expired_messages(time_stamp: u64) -> Result<()> {
+        let received = SimpleClient::purge_expired_inbox_messages(time_stamp).await?;
+        let sent = SimpleClient::purge_expired_sent_messages(time_stamp).await?;
+
+        if received + sent > 0 {
+            debug!(
+                "purged {} received and {} sent expired messages",
+                received, sent
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::SENT_MESSAGES_CF;
+    use crate::services::client_store::read_all_items;
+    use crate::test_helpers::run_with_test_db;
+    use base::snp::snp_core_types::{ContentItem, EntityId, PublicKey};
+    use base::snp::upsetter_simple_client::{ConversationType, SentMessage};
+    use prost::Message;
+
+    #[test]
+    fn test_purge_expired_messages() {
+        run_with_test_db(async {
+            let sender_key = vec![5u8; 32];
+            let sender = EntityId {
+                public_key: Some(PublicKey {
+                    key: sender_key.clone(),
+                }),
+                nickname: "".into(),
+            };
+
+            for (id, ttl) in [(1, 60), (2, 0), (3, 3600)] {
+                let item = ContentItem {
+                    id,
+                    created: now(),
+                    ttl,
+                    ..Default::default()
+                };
+                SimpleClient::store_inbox_message(
+                    &sender_key,
+                    ConversationType::Direct,
+                    sender.clone(),
+                    item,
+                )
+                .await
+                .unwrap();
+                SimpleClient::store_sent_message(sender.clone(), 100 + id, ttl)
+                    .await
+                    .unwrap();
+            }
+
+            let inbox_ids = || async {
+                SimpleClient::get_conversation_messages(&sender_key, 0, 0)
+                    .await
+                    .unwrap()
+                    .0
+                    .iter()
+                    .map(|m| m.content_item.as_ref().unwrap().id)
+                    .collect::<Vec<u64>>()
+            };
+
+            let sent_ids = || async {
+                read_all_items(SENT_MESSAGES_CF)
+                    .await
+                    .unwrap()
+                    .iter()
+                    .map(|(_, value)| SentMessage::decode(value.as_ref()).unwrap().message_id)
+                    .filter(|id| (101..=103).contains(id))
+                    .collect::<Vec<u64>>()
+            };
+
+            let time_stamp = now();
+            SimpleClient::purge_expired_messages(time_stamp)
+                .await
+                .unwrap();
+            assert_eq!(inbox_ids().await, vec![1, 2, 3]);
+            assert_eq!(sent_ids().await, vec![101, 102, 103]);
+
+            // a message expires after its ttl
+            let time_stamp = time_stamp + Duration::from_secs(61).as_nanos() as u64;
+            SimpleClient::purge_expired_messages(time_stamp)
+                .await
+                .unwrap();
+            assert_eq!(inbox_ids().await, vec![2, 3]);
+            assert_eq!(sent_ids().await, vec![102, 103]);
+
+            // a message with a longer ttl expires later and one without a ttl never expires
+            let time_stamp = time_stamp + Duration::from_secs(3600).as_nanos() as u64;
+            SimpleClient::purge_expired_messages(time_stamp)
+                .await
+                .unwrap();
+            assert_eq!(inbox_ids().await, vec![2]);
+            assert_eq!(sent_ids().await, vec![102]);
+
+            // the conversation keeps its messages count so indexes of other messages don't change
+            let (_, total_count) = SimpleClient::get_conversation_messages(&sender_key, 0, 0)
+                .await
+                .unwrap();
+            assert_eq!(total_count, 3);
+        });
+    }
+}
@@ -7,11 +7,14 @@ use anyhow::{anyhow, Result};
 use base::hex_utils::short_hex_string;
 use base::snp::snp_core_types::ContentItem;
 use base::snp::snp_server_api::TypedMessage;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::{ConversationType, TextMessageReceivedEvent};
+use base::time_utils::now;
 use prost::Message;
 
 impl SimpleClient {
     /// New text message from another client
-    pub(crate) async fn handle_text_message(&self, msg: TypedMessage) -> Result<()> {
+    pub(crate) async fn handle_text_message(&mut self, msg: TypedMessage) -> Result<()> {
         let sender_id = msg.get_ika()?;
         let text_message: ContentItem = ContentItem::decode(msg.message.as_slice())
             .map_err(|e| anyhow!("failed to decode text message {:?}", e))?;
@@ -24,6 +27,39 @@ impl SimpleClient {
             text_message.reply_to
         );
 
+        self.receive_direct_message(sender_id.as_ref(), text_message)
+            .await
+    }
+
+    /// Store a 1:1 message we received in our inbox and publish it to our events subscribers
+    pub(crate) async fn receive_direct_message(
+        &mut self,
+        sender_id: &[u8],
+        content_item: ContentItem,
+    ) -> Result<()> {
+        self.queue_delivery_receipt(sender_id, content_item.id);
+
+        // a self-destructing message may expire before it is delivered to us
+        let time_stamp = now();
+        if let Some(expiration) = content_item.expiration_time(time_stamp) {
+            if expiration <= time_stamp {
+                debug!("dropping expired message {}", content_item.id);
+                return Ok(());
+            }
+        }
+
+        let message = SimpleClient::store_inbox_message(
+            sender_id,
+            ConversationType::Direct,
+            self.get_other_client_entity(sender_id),
+            content_item,
+        )
+        .await?;
+
+        self.publish_event(Event::TextMessageReceived(TextMessageReceivedEvent {
+            message: Some(message),
+        }));
+
         Ok(())
     }
 }
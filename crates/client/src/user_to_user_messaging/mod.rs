@@ -2,6 +2,9 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+pub mod attachments;
+mod expired_msgs_purger;
 mod incoming_text_msgs_handler;
 mod new_text_msg;
+pub(crate) mod receipts;
 pub mod text_msg_sender;
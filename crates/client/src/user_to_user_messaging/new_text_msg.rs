@@ -11,16 +11,18 @@ use crypto::utils::entity_from_ed25519_pub_key;
 
 /// Implementation of creation of a new text message with content designated to an entity
 impl SimpleClient {
-    /// Create a new text message to a user and sign it using sender_keys
+    /// Create a new text message to a user and sign it using sender_keys. The message self-destructs after ttl seconds when ttl isn't 0.
     /// Returns a signed self-describing typed message with the text message as its content
     pub(crate) async fn new_text_message(
         &self,
         content: String,
         to: ed25519_dalek::PublicKey,
         reply_to: u64,
+        ttl: u64,
     ) -> Result<(TypedMessage, u64)> {
         let my_entity = entity_from_ed25519_pub_key(&self.client_id.public, "".into());
         let mut inner_msg = ContentItem::new_one_to_one_text_message(content, my_entity, reply_to);
+        inner_msg.ttl = ttl;
         inner_msg.sign(&self.client_id)?;
 
         use prost::Message;
@@ -0,0 +1,352 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::consts::SENT_MESSAGES_CF;
+use crate::services::client_store::{delete_item, read_all_items, read_item, write_item};
+use crate::simple_client::SimpleClient;
+use anyhow::{anyhow, Result};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_client_to_client::{MessageReceipts, ReceiptType};
+use base::snp::snp_core_types::{EntityId, PublicKey};
+use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::{MessageStatus, MessageStatusChangedEvent, SentMessage};
+use base::time_utils::{expiration_time, now};
+use bytes::Bytes;
+use chrono::prelude::*;
+use prost::Message;
+use xactor::*;
+
+/// Returns true when our copy of a self-destructing message we sent expired by time_stamp
+fn is_expired(message: &SentMessage, time_stamp: u64) -> bool {
+    message.expires_time_stamp != 0 && message.expires_time_stamp <= time_stamp
+}
+
+/// Sent messages are stored by their id so receipts for them can update their status.
+/// Self-destructing messages which expired but weren't purged yet are not returned
+async fn read_sent_message(message_id: u64) -> Result<Option<SentMessage>> {
+    match read_item(SENT_MESSAGES_CF, &message_id.to_be_bytes()).await? {
+        Some(data) => {
+            let message = SentMessage::decode(data.as_ref())?;
+            if is_expired(&message, now()) {
+                return Ok(None);
+            }
+            Ok(Some(message))
+        }
+        None => Ok(None),
+    }
+}
+
+async fn write_sent_message(message: &SentMessage) -> Result<()> {
+    write_item(
+        SENT_MESSAGES_CF,
+        &message.message_id.to_be_bytes(),
+        message.encode_to_vec(),
+    )
+    .await
+}
+
+impl SimpleClient {
+    /// Store a 1:1 message we sent so we can track its delivery status.
+    /// Our copy of a self-destructing message expires after its ttl in seconds, same as the receiver's copy
+    pub(crate) async fn store_sent_message(
+        receiver_id: EntityId,
+        message_id: u64,
+        ttl: u64,
+    ) -> Result<()> {
+        let sent_time_stamp = now();
+        write_sent_message(&SentMessage {
+            message_id,
+            receiver_id: Some(receiver_id),
+            status: MessageStatus::Sent as i32,
+            sent_time_stamp,
+            delivered_time_stamp: 0,
+            read_time_stamp: 0,
+            expires_time_stamp: expiration_time(sent_time_stamp, ttl).unwrap_or_default(),
+        })
+        .await
+    }
+
+    /// Delete our copies of self-destructing messages we sent which expired by time_stamp.
+    /// Returns the number of deleted messages
+    pub(crate) async fn purge_expired_sent_messages(time_stamp: u64) -> Result<usize> {
+        let mut count = 0;
+        for (key, value) in read_all_items(SENT_MESSAGES_CF).await? {
+            if is_expired(&SentMessage::decode(value.as_ref())?, time_stamp) {
+                delete_item(SENT_MESSAGES_CF, key.as_ref()).await?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Queue a delivered receipt for a message we received. Receipts are sent in batches per sender
+    pub(crate) fn queue_delivery_receipt(&mut self, sender_id: &[u8], message_id: u64) {
+        self.pending_delivery_receipts
+            .entry(sender_id.to_vec())
+            .or_default()
+            .push(message_id);
+    }
+
+    /// Send all queued delivered receipts - one receipts message per sender
+    pub(crate) async fn send_delivery_receipts(&mut self) {
+        let pending = std::mem::take(&mut self.pending_delivery_receipts);
+        for (sender_id, message_ids) in pending {
+            if let Err(e) = self
+                .send_receipts(ReceiptType::Delivered, &sender_id, message_ids)
+                .await
+            {
+                warn!(
+                    "failed to send delivered receipts to {}: {:?}",
+                    short_hex_string(&sender_id),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Send a batch of receipts to the sender of the messages over our DR session with it
+    async fn send_receipts(
+        &mut self,
+        receipt_type: ReceiptType,
+        sender_id: &[u8],
+        message_ids: Vec<u64>,
+    ) -> Result<()> {
+        let key = self
+            .resolve_other_client(&EntityId {
+                public_key: Some(PublicKey {
+                    key: sender_id.to_vec(),
+                }),
+                nickname: "".into(),
+            })
+            .await?;
+
+        let ikb = self
+            .other_clients
+            .get(&key)
+            .ok_or_else(|| anyhow!("missing bundle"))?
+            .client_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client bundle"))?
+            .get_client_id_ed25519_public_key()?;
+
+        let receipts = MessageReceipts {
+            receipt_type: receipt_type as i32,
+            message_ids,
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+        };
+
+        let typed_msg =
+            self.create_typed_message(MessageType::MessageReceipts, receipts.encode_to_vec(), ikb)?;
+
+        self.send_typed_message(typed_msg, Bytes::from(key)).await
+    }
+
+    /// Receipts from another client for messages we sent it
+    pub(crate) async fn handle_message_receipts(&mut self, msg: TypedMessage) -> Result<()> {
+        let sender_id = msg.get_ika()?;
+        let receipts = MessageReceipts::decode(msg.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode message receipts {:?}", e))?;
+
+        let status = match ReceiptType::from_i32(receipts.receipt_type) {
+            Some(ReceiptType::Delivered) => MessageStatus::Delivered,
+            Some(ReceiptType::Read) => MessageStatus::Read,
+            None => return Err(anyhow!("unknown receipt type")),
+        };
+
+        for message_id in receipts.message_ids {
+            let mut message = match read_sent_message(message_id).await? {
+                Some(message) => message,
+                None => {
+                    warn!("receipt for an unknown message {}", message_id);
+                    continue;
+                }
+            };
+
+            // only the receiver of a message may send receipts for it
+            let receiver_id = message.receiver_id.as_ref().and_then(|id| id.get_id().ok());
+            if receiver_id.map(|id| id.as_slice()) != Some(sender_id.as_ref()) {
+                warn!("receipt for message {} not from its receiver", message_id);
+                continue;
+            }
+
+            // a read message was also delivered. Its status never goes back
+            if message.status >= status as i32 {
+                continue;
+            }
+
+            if message.delivered_time_stamp == 0 {
+                message.delivered_time_stamp = receipts.time_stamp;
+            }
+            if status == MessageStatus::Read {
+                message.read_time_stamp = receipts.time_stamp;
+            }
+            message.status = status as i32;
+
+            write_sent_message(&message).await?;
+
+            self.publish_event(Event::MessageStatusChanged(MessageStatusChangedEvent {
+                message: Some(message),
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+/// User read messages another client sent us
+#[message(result = "Result<bool>")]
+pub(crate) struct MarkMessagesRead {
+    pub(crate) sender: EntityId,
+    pub(crate) message_ids: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Handler<MarkMessagesRead> for SimpleClient {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: MarkMessagesRead) -> Result<bool> {
+        let key = msg.sender.get_id()?.clone();
+        if self.read_receipts_off.contains(&key) {
+            return Ok(false);
+        }
+
+        self.send_receipts(ReceiptType::Read, &key, msg.message_ids)
+            .await?;
+
+        Ok(true)
+    }
+}
+
+/// Returns the status of the messages we sent with the provided ids
+#[message(result = "Result<Vec<SentMessage>>")]
+pub(crate) struct GetMessagesStatus(pub(crate) Vec<u64>);
+
+#[async_trait::async_trait]
+impl Handler<GetMessagesStatus> for SimpleClient {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetMessagesStatus,
+    ) -> Result<Vec<SentMessage>> {
+        let mut messages = vec![];
+        for message_id in msg.0 {
+            if let Some(message) = read_sent_message(message_id).await? {
+                messages.push(message);
+            }
+        }
+        Ok(messages)
+    }
+}
+
+/// Turn on or off read receipts to a contact
+#[message(result = "Result<()>")]
+pub(crate) struct SetReadReceipts {
+    pub(crate) contact: EntityId,
+    pub(crate) enabled: bool,
+}
+
+#[async_trait::async_trait]
+impl Handler<SetReadReceipts> for SimpleClient {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: SetReadReceipts) -> Result<()> {
+        let key = msg.contact.get_id()?.clone();
+        SimpleClient::store_read_receipts_setting(&key, msg.enabled).await?;
+        if msg.enabled {
+            self.read_receipts_off.remove(&key);
+        } else {
+            self.read_receipts_off.insert(key);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::run_with_test_db;
+    use base::snp::snp_core_types::Signature;
+
+    fn receipts_message(sender: &[u8], receipt_type: ReceiptType, ids: Vec<u64>) -> TypedMessage {
+        TypedMessage {
+            time_stamp: 0,
+            msg_type: MessageType::MessageReceipts as i32,
+            message: MessageReceipts {
+                receipt_type: receipt_type as i32,
+                message_ids: ids,
+                time_stamp: Utc::now().timestamp_nanos() as u64,
+            }
+            .encode_to_vec(),
+            receiver: None,
+            sender: Some(EntityId {
+                public_key: Some(PublicKey {
+                    key: sender.to_vec(),
+                }),
+                nickname: "".into(),
+            }),
+            signature: Some(Signature::default()),
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
+        }
+    }
+
+    #[test]
+    fn test_message_receipts() {
+        run_with_test_db(async {
+            let mut client = SimpleClient::default();
+            let receiver = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+            let receiver_key = receiver.public.to_bytes().to_vec();
+            let other = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+
+            let receiver_id = EntityId {
+                public_key: Some(PublicKey {
+                    key: receiver_key.clone(),
+                }),
+                nickname: "".into(),
+            };
+            SimpleClient::store_sent_message(receiver_id.clone(), 1001, 0)
+                .await
+                .unwrap();
+            SimpleClient::store_sent_message(receiver_id, 1002, 0)
+                .await
+                .unwrap();
+
+            // receipts from a client which isn't the receiver are ignored
+            client
+                .handle_message_receipts(receipts_message(
+                    other.public.as_ref(),
+                    ReceiptType::Read,
+                    vec![1001],
+                ))
+                .await
+                .unwrap();
+            let message = read_sent_message(1001).await.unwrap().unwrap();
+            assert_eq!(message.status, MessageStatus::Sent as i32);
+
+            client
+                .handle_message_receipts(receipts_message(
+                    &receiver_key,
+                    ReceiptType::Read,
+                    vec![1001],
+                ))
+                .await
+                .unwrap();
+            client
+                .handle_message_receipts(receipts_message(
+                    &receiver_key,
+                    ReceiptType::Delivered,
+                    vec![1001, 1002, 9999],
+                ))
+                .await
+                .unwrap();
+
+            // a read message stays read
+            let message = read_sent_message(1001).await.unwrap().unwrap();
+            assert_eq!(message.status, MessageStatus::Read as i32);
+            assert_ne!(message.delivered_time_stamp, 0);
+            assert_ne!(message.read_time_stamp, 0);
+
+            let message = read_sent_message(1002).await.unwrap().unwrap();
+            assert_eq!(message.status, MessageStatus::Delivered as i32);
+            assert_eq!(message.read_time_stamp, 0);
+        });
+    }
+}
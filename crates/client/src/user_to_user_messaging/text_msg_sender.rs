@@ -4,6 +4,8 @@
 
 use crate::simple_client::SimpleClient;
 use anyhow::{anyhow, bail, Result};
+use base::snp::snp_core_types::EntityId;
+use base::time_utils::{expiration_time, now};
 use bytes::Bytes;
 use xactor::*;
 
@@ -11,8 +13,10 @@ use xactor::*;
 #[message(result = "Result<u64>")]
 pub struct SendTextMessage {
     pub message: String,
-    pub receiver_id: Bytes,
+    pub receiver: EntityId,
     pub reply_to: u64,
+    /// seconds after which the message self-destructs. 0 for never
+    pub ttl: u64,
 }
 
 /// Handle a user api request to send a 1:1 text message to another client.
@@ -24,11 +28,11 @@ impl Handler<SendTextMessage> for SimpleClient {
             bail!("missing provider bundle")
         }
 
-        let key = msg.receiver_id.as_ref();
+        let key = self.resolve_other_client(&msg.receiver).await?;
 
         let sb_bundle = self
             .other_clients
-            .get(key)
+            .get(&key)
             .ok_or_else(|| anyhow!("missing bundle"))?
             .clone();
 
@@ -37,9 +41,15 @@ impl Handler<SendTextMessage> for SimpleClient {
 
         // The message Alice (A's user) sends Bob (B's user)
         let (text_message, message_id) = self
-            .new_text_message(msg.message, ikb, msg.reply_to)
+            .new_text_message(msg.message, ikb, msg.reply_to, msg.ttl)
             .await?;
-        self.send_typed_message(text_message, msg.receiver_id)
+
+        // the receiver's provider may drop a self-destructing message which expired before it was delivered
+        let expires = expiration_time(now(), msg.ttl).unwrap_or_default();
+        self.send_expiring_typed_message(text_message, Bytes::from(key.clone()), expires)
+            .await?;
+
+        SimpleClient::store_sent_message(self.get_other_client_entity(&key), message_id, msg.ttl)
             .await?;
 
         Ok(message_id)
@@ -2,7 +2,7 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::network_salt::NET_SALT;
+use crate::network_salt::net_salt;
 use anyhow::Result;
 use bytes::Bytes;
 use crypto::aead_cypher::AeadCipher;
@@ -15,7 +15,7 @@ impl AEAD {
     /// decrypts ciphertext to cleartext
     pub fn decrypt(ciphertext: &[u8], key: &[u8; 32], ad: &[u8]) -> Result<Bytes> {
         let cipher = AeadCipher::new(
-            Bytes::from(NET_SALT.to_vec()),
+            Bytes::from(net_salt().to_vec()),
             Bytes::from(key.to_vec()),
             Bytes::from(ad.to_vec()),
         );
@@ -26,7 +26,7 @@ impl AEAD {
     /// encrypt cleartext to ciphertext
     pub fn encrypt(plaintext: Bytes, key: &[u8; 32], ad: &[u8]) -> Result<Bytes> {
         let cipher = AeadCipher::new(
-            Bytes::from(NET_SALT.to_vec()),
+            Bytes::from(net_salt().to_vec()),
             Bytes::from(key.to_vec()),
             Bytes::from(ad.to_vec()),
         );
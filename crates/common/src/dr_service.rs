@@ -4,12 +4,16 @@
 
 use anyhow::{anyhow, Result};
 use base::hex_utils::short_hex_string;
+use byteorder::{BigEndian, ByteOrder};
 use bytes::Bytes;
 use db::db_service;
 use db::db_service::{DataItem, DatabaseService, ReadItem, WriteItem};
 use db::types::IntDbKey;
 use double_ratchet::dr::DoubleRatchet;
-use ed25519_dalek::PublicKey;
+use ed25519_dalek::{PublicKey, PUBLIC_KEY_LENGTH};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 use xactor::*;
 
 /// DRService is a system service for managing tow-party DR (double ratchet) sessions.
@@ -17,9 +21,36 @@ use xactor::*;
 /// to load these sessions from store and call the DR protocol on them.
 /// See double_ratchet::DoubleRatchet for more info.
 #[derive(Debug, Default)]
-pub struct DRService {}
+pub struct DRService {
+    // locks used to serialize loading, updating and saving of a session by concurrent tasks
+    session_locks: HashMap<u64, Arc<Mutex<()>>>,
+}
 impl Service for DRService {}
 
+/// Version of stored session records. A record is this version, the remote entity's public key and the
+/// session serialized with DoubleRatchet::to_bytes(). Records are stored by session id and an entity's
+/// key maps to its latest session id.
+/// The original layout stored the session by the entity's key and the entity's key by the session id.
+/// Later, unversioned records were stored by session id as a bincode (entity, session) tuple.
+/// Sessions in both layouts are migrated when they are loaded.
+const SESSION_RECORD_VERSION: u8 = 1;
+
+/// A session update lock held until it is dropped.
+/// The service forgets a session's lock once no task holds or waits for it.
+pub struct SessionLock {
+    session_id: u64,
+    guard: Option<OwnedMutexGuard<()>>,
+    dr_service: Addr<DRService>,
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        // release the lock before asking the service to remove it
+        self.guard.take();
+        let _ = self.dr_service.send(ReleaseSessionLock(self.session_id));
+    }
+}
+
 // TODO: add functionality to store and retrieve the key-pairs for public pre-keys so entities can create new pre-keys and use them in new identity bundles.
 
 impl DRService {
@@ -85,6 +116,25 @@ impl DRService {
 
         Ok(res)
     }
+
+    /// Lock a session for updates. Tasks which load a session, update it and save it should
+    /// hold the lock from loading to saving so they don't overwrite each other's updates
+    pub async fn lock_session(session_id: u64) -> Result<SessionLock> {
+        let dr_service = DRService::from_registry()
+            .await
+            .map_err(|e| anyhow!(format!("failed to get provider service: {:?}", e)))?;
+
+        let lock = dr_service
+            .call(GetSessionLock(session_id))
+            .await
+            .map_err(|e| anyhow!(format!("internal error - failed to call: {:?}", e)))?;
+
+        Ok(SessionLock {
+            session_id,
+            guard: Some(lock.lock_owned().await),
+            dr_service,
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -95,7 +145,166 @@ impl Actor for DRService {
     }
 }
 
-/// Get an existing DR session with an entity identified by an ed25519 public key
+/// Read a stored session and its remote entity by the session id
+async fn read_session(session_id: u64) -> Result<Option<(DoubleRatchet, PublicKey)>> {
+    let key: IntDbKey = session_id.into();
+    let read_item = ReadItem {
+        key: key.0,
+        cf: db_service::PROVIDER_COL_FAMILY,
+    };
+
+    let data = match DatabaseService::read(read_item).await? {
+        Some((data, _)) => data,
+        None => return Ok(None),
+    };
+
+    if data.len() == PUBLIC_KEY_LENGTH {
+        // original layout - the session id maps to the entity which maps to the session
+        let pub_id = PublicKey::from_bytes(data.as_ref())
+            .map_err(|e| anyhow!("invalid pub key data: {:?}", e))?;
+        return Ok(read_original_session(&pub_id).await?.map(|dr| (dr, pub_id)));
+    }
+
+    if let Some(res) = migrate_unversioned_record(data.as_ref()).await {
+        return res.map(Some);
+    }
+
+    match data.split_first() {
+        Some((&SESSION_RECORD_VERSION, record)) if record.len() > PUBLIC_KEY_LENGTH => {
+            let pub_id = PublicKey::from_bytes(&record[..PUBLIC_KEY_LENGTH])
+                .map_err(|e| anyhow!("invalid pub key data: {:?}", e))?;
+            let dr = DoubleRatchet::from_bytes(&record[PUBLIC_KEY_LENGTH..])?;
+            Ok(Some((dr, pub_id)))
+        }
+        _ => Err(anyhow!("unsupported stored dr session format")),
+    }
+}
+
+/// Read a session stored in the original layout by its entity and migrate it to the current layout
+async fn read_original_session(entity_id: &PublicKey) -> Result<Option<DoubleRatchet>> {
+    let read_item = ReadItem {
+        key: Bytes::from(entity_id.to_bytes().to_vec()),
+        cf: db_service::PROVIDER_COL_FAMILY,
+    };
+
+    match DatabaseService::read(read_item).await? {
+        Some((data, _)) => migrate_original_session(entity_id, data.as_ref())
+            .await
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Migrate a session stored in the original layout and format to the current ones
+async fn migrate_original_session(entity_id: &PublicKey, data: &[u8]) -> Result<DoubleRatchet> {
+    let dr = DoubleRatchet::from_unversioned_bytes(data, 0)?;
+    write_session(entity_id, &dr).await?;
+    debug!(
+        "migrated stored dr session {} with entity {}",
+        dr.session_id,
+        short_hex_string(entity_id.as_ref())
+    );
+    Ok(dr)
+}
+
+/// Migrate an unversioned (entity, session) record. Returns None when data isn't such a record.
+/// These records start with the entity key's length so they can't be confused with versioned records
+async fn migrate_unversioned_record(data: &[u8]) -> Option<Result<(DoubleRatchet, PublicKey)>> {
+    const ENTITY_LEN_PREFIX: [u8; 8] = (PUBLIC_KEY_LENGTH as u64).to_le_bytes();
+    if data.len() <= 8 + PUBLIC_KEY_LENGTH || data[..8] != ENTITY_LEN_PREFIX {
+        return None;
+    }
+
+    Some(
+        async {
+            let pub_id = PublicKey::from_bytes(&data[8..8 + PUBLIC_KEY_LENGTH])
+                .map_err(|e| anyhow!("invalid pub key data: {:?}", e))?;
+
+            // records were stored with session format version 1 and then with version 2
+            let session = &data[8 + PUBLIC_KEY_LENGTH..];
+            let dr = DoubleRatchet::from_unversioned_bytes(session, 2)
+                .or_else(|_| DoubleRatchet::from_unversioned_bytes(session, 1))?;
+
+            write_session(&pub_id, &dr).await?;
+            debug!("migrated unversioned dr session record {}", dr.session_id);
+            Ok((dr, pub_id))
+        }
+        .await,
+    )
+}
+
+/// Store a session with its entity by session id and make it the entity's latest session
+async fn write_session(entity_id: &PublicKey, dr: &DoubleRatchet) -> Result<()> {
+    // todo: these 2 db ops should be atomic - if 2nd fails, first one needs to be rolled back...
+
+    let key: IntDbKey = dr.session_id.into();
+
+    let mut record = vec![SESSION_RECORD_VERSION];
+    record.extend_from_slice(entity_id.as_ref());
+    record.extend(dr.to_bytes()?);
+
+    // store the session with its entity by session id
+    let write_req = WriteItem {
+        data: DataItem {
+            key: key.0.clone(),
+            value: Bytes::from(record),
+        },
+        cf: db_service::PROVIDER_COL_FAMILY,
+        ttl: 0, // todo: think about ttl for dr sessions with other peers
+    };
+
+    DatabaseService::write(write_req).await?;
+
+    debug!("Stored dr session in the db. Session id: {}", dr.session_id);
+
+    // store mapping from entity id to its latest session id
+    let write_req = WriteItem {
+        data: DataItem {
+            key: Bytes::from(entity_id.to_bytes().to_vec()),
+            value: key.0,
+        },
+        cf: db_service::PROVIDER_COL_FAMILY,
+        ttl: 0, // todo: think about ttl for dr sessions with other peers
+    };
+
+    DatabaseService::write(write_req).await?;
+
+    debug!(
+        "Stored latest session id for entity: {:?}",
+        short_hex_string(entity_id.as_ref())
+    );
+
+    Ok(())
+}
+
+/// Get the update lock of a session
+#[message(result = "Arc<Mutex<()>>")]
+pub struct GetSessionLock(pub u64);
+
+#[async_trait::async_trait]
+impl Handler<GetSessionLock> for DRService {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: GetSessionLock) -> Arc<Mutex<()>> {
+        self.session_locks.entry(msg.0).or_default().clone()
+    }
+}
+
+/// Remove a released session lock unless other tasks got it while it was held
+#[message]
+struct ReleaseSessionLock(u64);
+
+#[async_trait::async_trait]
+impl Handler<ReleaseSessionLock> for DRService {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: ReleaseSessionLock) {
+        if let Some(lock) = self.session_locks.get(&msg.0) {
+            // the service holds the only reference when no task holds or waits for the lock
+            if Arc::strong_count(lock) == 1 {
+                self.session_locks.remove(&msg.0);
+            }
+        }
+    }
+}
+
+/// Get the latest saved DR session with an entity identified by an ed25519 public key
 #[message(result = "Result<Option<DoubleRatchet>>")]
 pub struct GetSession(pub PublicKey);
 
@@ -111,18 +320,23 @@ impl Handler<GetSession> for DRService {
             cf: db_service::PROVIDER_COL_FAMILY,
         };
 
-        match DatabaseService::read(read_item).await? {
+        let session_id = match DatabaseService::read(read_item).await? {
+            Some((data, _)) if data.len() == 8 => BigEndian::read_u64(data.as_ref()),
+            // original layout - the entity maps to its session
             Some((data, _)) => {
-                let dr: DoubleRatchet = bincode::deserialize(&data).unwrap();
-                Ok(Some(dr))
+                return migrate_original_session(&msg.0, data.as_ref())
+                    .await
+                    .map(Some)
             }
-            _ => Ok(None),
-        }
+            None => return Ok(None),
+        };
+
+        Ok(read_session(session_id).await?.map(|(dr, _)| dr))
     }
 }
 
-/// Get a session's entity from a session id.
-/// Use the entity returned to call GetSession
+/// Get a session and its entity from a session id.
+/// An entity may have more than one session. e.g. a session each party started with the other one
 #[message(result = "Result<Option<(DoubleRatchet, PublicKey)>>")]
 pub struct GetSessionById(pub u64);
 
@@ -133,55 +347,23 @@ impl Handler<GetSessionById> for DRService {
         _ctx: &mut Context<Self>,
         msg: GetSessionById,
     ) -> Result<Option<(DoubleRatchet, PublicKey)>> {
-        // Get entity from the session id
-        let key: IntDbKey = msg.0.into();
         debug!("Looking for dr session id : {}", msg.0);
 
-        let read_item = ReadItem {
-            key: key.0,
-            cf: db_service::PROVIDER_COL_FAMILY,
-        };
-
-        let entity = DatabaseService::read(read_item).await?;
-
-        if entity.is_none() {
-            debug!("Did not find entity id by session id");
-            return Ok(None);
+        let res = read_session(msg.0).await?;
+        match res.as_ref() {
+            Some((_, pub_id)) => debug!(
+                "Found stored dr session in db for entity: {}",
+                base::hex_utils::short_hex_string(pub_id.to_bytes().as_ref())
+            ),
+            None => debug!("No stored DR session with this id"),
         }
 
-        let pub_id = PublicKey::from_bytes(entity.as_ref().unwrap().0.as_ref())
-            .map_err(|e| anyhow!("invalid pub key data: {:?}", e))?;
-
-        debug!(
-            "Entity id for session id: {}",
-            base::hex_utils::short_hex_string(pub_id.to_bytes().as_ref())
-        );
-
-        // Load the session for this entity
-        let session_read_item = ReadItem {
-            key: entity.unwrap().0,
-            cf: db_service::PROVIDER_COL_FAMILY,
-        };
-
-        match DatabaseService::read(session_read_item).await? {
-            Some((data, _)) => {
-                let dr: DoubleRatchet = bincode::deserialize(&data).unwrap();
-                debug!(
-                    "Found stored dr session in db. Session id: {}",
-                    dr.session_id
-                );
-
-                Ok(Some((dr, pub_id)))
-            }
-            _ => {
-                debug!("No stored DR session for entity.");
-                Ok(None)
-            }
-        }
+        Ok(res)
     }
 }
 
-/// Save an existing DR session so it can be loaded later via GetSession
+/// Save an existing DR session so it can be loaded later via GetSessionById.
+/// It becomes the entity's latest session returned by GetSession.
 /// Caller should only call this after verifying that entity_id is the other party
 /// of the dr session.
 #[message(result = "Result<()>")]
@@ -193,44 +375,163 @@ pub struct SaveSession {
 #[async_trait::async_trait]
 impl Handler<SaveSession> for DRService {
     async fn handle(&mut self, _ctx: &mut Context<Self>, msg: SaveSession) -> Result<()> {
-        // todo: these 2 db ops should be atomic - if 2nd fails, first one needs to be rolled back...
-
-        // store mapping from entity id to the dr session
-        let write_req = WriteItem {
-            data: DataItem {
-                key: Bytes::from(msg.entity_id.to_bytes().to_vec()),
-                value: Bytes::from(bincode::serialize(&msg.dr).unwrap()),
-            },
+        write_session(&msg.entity_id, &msg.dr).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::db_service::Configure;
+    use rocksdb::{ColumnFamilyDescriptor, Options};
+    use std::sync::Weak;
+
+    /// Id of a session stored by the original version in the original layout
+    const ORIGINAL_SESSION_ID: u64 = 10232337844106001548;
+
+    /// The session stored by the original version in the original format
+    const ORIGINAL_SESSION: [&str; 7] = [
+        "01010000002f4b0442d75161f5910da868f1dcb26b7ee3993ca50f4cff040675efdd8ad33e030303",
+        "0303030303030303030303030303030303030303030303030303030303010000000101000000ce82",
+        "25013ff4df984988c477c45c4d2432e9bad005d6f827ca5f426a7472f50901000000010000000000",
+        "0000000000000001904adc8682ba601462b09f6ad9c911403f29bb9fbe49cdafff14ca982b3c2c59",
+        "01400000000000000007070707070707070707070707070707070707070707070707070707070707",
+        "0707070707070707070707070707070707070707070707070707070707070707078c7cb9530e9100",
+        "8e",
+    ];
+
+    async fn write_item(key: Bytes, value: Bytes) {
+        DatabaseService::write(WriteItem {
+            data: DataItem { key, value },
             cf: db_service::PROVIDER_COL_FAMILY,
-            ttl: 0, // todo: think about ttl for dr sessions with other peers
-        };
+            ttl: 0,
+        })
+        .await
+        .unwrap();
+    }
 
-        DatabaseService::write(write_req).await?;
+    /// Service actors run on the runtime they were started on so all service tests run in one test
+    #[tokio::test]
+    async fn test_dr_service() {
+        base::test_helpers::enable_logger();
+
+        let db_name = std::env::temp_dir()
+            .join(format!("dr_service_test_db_{}", std::process::id()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        DatabaseService::config_db(Configure {
+            drop_on_exit: true,
+            db_name,
+            col_descriptors: vec![ColumnFamilyDescriptor::new(
+                db_service::PROVIDER_COL_FAMILY,
+                Options::default(),
+            )],
+        })
+        .await
+        .unwrap();
+
+        migrate_original_layout().await;
+        migrate_unversioned_record().await;
+        prune_released_locks().await;
+    }
 
-        debug!(
-            "Stored dr session in the db for entity: {:?}",
-            base::hex_utils::short_hex_string(msg.entity_id.as_ref())
+    /// Sessions stored in the original layout and format are loaded and migrated
+    async fn migrate_original_layout() {
+        let entity = ed25519_dalek::SecretKey::from_bytes(&[9u8; 32]).unwrap();
+        let entity: PublicKey = (&entity).into();
+        let session = hex::decode(ORIGINAL_SESSION.concat()).unwrap();
+
+        let session_key: IntDbKey = ORIGINAL_SESSION_ID.into();
+        write_item(
+            session_key.0.clone(),
+            Bytes::from(entity.to_bytes().to_vec()),
+        )
+        .await;
+        write_item(
+            Bytes::from(entity.to_bytes().to_vec()),
+            Bytes::from(session),
+        )
+        .await;
+
+        let (dr, pub_id) = DRService::get_dr_session_by_id(ORIGINAL_SESSION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(dr.session_id, ORIGINAL_SESSION_ID);
+        assert_eq!(pub_id, entity);
+
+        // the session was migrated to a versioned record and the entity maps to its id
+        let (record, _) = DatabaseService::read(ReadItem {
+            key: session_key.0,
+            cf: db_service::PROVIDER_COL_FAMILY,
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(record[0], SESSION_RECORD_VERSION);
+
+        let dr = DRService::get_dr_session(entity).await.unwrap().unwrap();
+        assert_eq!(dr.session_id, ORIGINAL_SESSION_ID);
+        assert_eq!(
+            DoubleRatchet::from_bytes(&record[1 + PUBLIC_KEY_LENGTH..])
+                .unwrap()
+                .get_ad()
+                .unwrap(),
+            dr.get_ad().unwrap()
         );
+    }
 
-        let key: IntDbKey = msg.dr.session_id.into();
+    /// Unversioned (entity, session) records stored by session id are loaded and migrated
+    async fn migrate_unversioned_record() {
+        let entity = ed25519_dalek::SecretKey::from_bytes(&[10u8; 32]).unwrap();
+        let entity: PublicKey = (&entity).into();
 
-        // store mappings from session id to entity id
-        let write_req = WriteItem {
-            data: DataItem {
-                key: key.0,
-                value: Bytes::from(msg.entity_id.to_bytes().to_vec()),
-            },
-            cf: db_service::PROVIDER_COL_FAMILY,
-            ttl: 0, // todo: think about ttl for dr sessions with other peers
-        };
+        let session = hex::decode(ORIGINAL_SESSION.concat()).unwrap();
+        let mut dr = DoubleRatchet::from_unversioned_bytes(&session, 0).unwrap();
+        dr.session_id = 42;
 
-        DatabaseService::write(write_req).await?;
+        let session_key: IntDbKey = dr.session_id.into();
+        write_item(
+            session_key.0.clone(),
+            Bytes::from(bincode::serialize(&(entity.to_bytes().to_vec(), &dr)).unwrap()),
+        )
+        .await;
 
-        debug!(
-            "Stored latest session id for entity in db: {}",
-            msg.dr.session_id
+        let (migrated_dr, pub_id) = DRService::get_dr_session_by_id(42).await.unwrap().unwrap();
+        assert_eq!(migrated_dr.session_id, 42);
+        assert_eq!(pub_id, entity);
+
+        let (record, _) = DatabaseService::read(ReadItem {
+            key: session_key.0,
+            cf: db_service::PROVIDER_COL_FAMILY,
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(record[0], SESSION_RECORD_VERSION);
+        assert_eq!(
+            DRService::get_dr_session(entity)
+                .await
+                .unwrap()
+                .unwrap()
+                .session_id,
+            42
         );
+    }
 
-        Ok(())
+    /// Session locks are removed once released
+    async fn prune_released_locks() {
+        let dr_service = DRService::from_registry().await.unwrap();
+        let lock = DRService::lock_session(7).await.unwrap();
+        let weak: Weak<Mutex<()>> =
+            Arc::downgrade(&dr_service.call(GetSessionLock(7)).await.unwrap());
+        assert!(weak.upgrade().is_some());
+
+        drop(lock);
+
+        // the service handles messages in order so the lock was released when this call returns
+        let _ = dr_service.call(GetSessionLock(8)).await.unwrap();
+        assert!(weak.upgrade().is_none());
     }
 }
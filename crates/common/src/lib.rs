@@ -13,7 +13,10 @@ extern crate db;
 pub mod aead;
 pub mod dr_service;
 pub mod edh;
+pub mod message_padding;
 pub mod network_salt;
+pub mod sealed_sender;
+pub mod sender_keys;
 pub mod typed_msg_extensions;
 pub mod wallet_service;
 pub mod x2dh_service;
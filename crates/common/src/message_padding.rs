@@ -0,0 +1,139 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use anyhow::{bail, Result};
+use rand::Rng;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Padded messages sizes. Messages longer than the largest bucket are padded to a multiple of it.
+/// So an observer of encrypted messages only learns a message size bucket and not its exact size.
+static PADDING_BUCKETS: [usize; 6] = [256, 512, 1024, 4096, 16384, 65536];
+
+/// Marks the end of message data in a padded message. Followed by zeros up to the bucket size (ISO/IEC 7816-4)
+const PADDING_MARKER: u8 = 0x80;
+
+/// True when this process's p2p network pads encrypted messages
+static MESSAGE_PADDING: AtomicBool = AtomicBool::new(false);
+
+/// Set whether this process's p2p network pads encrypted messages. Apps set it from their config on startup.
+pub fn set_message_padding(enabled: bool) {
+    MESSAGE_PADDING.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns true when this process's p2p network pads encrypted messages
+pub fn message_padding() -> bool {
+    MESSAGE_PADDING.load(Ordering::SeqCst)
+}
+
+/// Returns the padded size of a message of len bytes
+fn padded_len(len: usize) -> usize {
+    // room for the padding marker
+    let len = len + 1;
+    match PADDING_BUCKETS.iter().find(|b| **b >= len) {
+        Some(bucket) => *bucket,
+        None => {
+            let max = PADDING_BUCKETS[PADDING_BUCKETS.len() - 1];
+            len.div_ceil(max) * max
+        }
+    }
+}
+
+/// Returns the max length of a message whose padded size is at most max_padded_len bytes
+pub fn max_unpadded_len(max_padded_len: usize) -> usize {
+    let max = PADDING_BUCKETS[PADDING_BUCKETS.len() - 1];
+    let padded = if max_padded_len >= max {
+        max_padded_len / max * max
+    } else {
+        PADDING_BUCKETS
+            .iter()
+            .rev()
+            .find(|b| **b <= max_padded_len)
+            .copied()
+            .unwrap_or_default()
+    };
+
+    // room for the padding marker
+    padded.saturating_sub(1)
+}
+
+/// Returns a random message length which is padded to one of the padding buckets. All buckets are equally likely.
+/// Cover traffic messages use it so their padded sizes are spread over the buckets of real messages
+pub fn random_bucket_len<R: Rng>(rng: &mut R) -> usize {
+    let index = rng.gen_range(0, PADDING_BUCKETS.len());
+    let min = if index == 0 {
+        0
+    } else {
+        PADDING_BUCKETS[index - 1]
+    };
+
+    // room for the padding marker
+    rng.gen_range(min, PADDING_BUCKETS[index] - 1)
+}
+
+/// Pad message data to its size bucket
+pub fn pad(mut data: Vec<u8>) -> Vec<u8> {
+    let len = padded_len(data.len());
+    data.push(PADDING_MARKER);
+    data.resize(len, 0);
+    data
+}
+
+/// Strip padding added by pad() from message data
+pub fn unpad(mut data: Vec<u8>) -> Result<Vec<u8>> {
+    match data.iter().rposition(|b| *b != 0) {
+        Some(idx) if data[idx] == PADDING_MARKER => {
+            data.truncate(idx);
+            Ok(data)
+        }
+        _ => bail!("invalid message padding"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_unpad() {
+        for len in [0, 1, 255, 256, 1000, 65535, 65536, 200000] {
+            let data = vec![7u8; len];
+            let padded = pad(data.clone());
+            assert!(padded.len() > len);
+            assert!(PADDING_BUCKETS.contains(&padded.len()) || padded.len().is_multiple_of(65536));
+            assert_eq!(unpad(padded).unwrap(), data);
+        }
+
+        assert_eq!(pad(vec![1u8; 10]).len(), pad(vec![1u8; 200]).len());
+
+        // trailing zeros in message data are kept
+        let data = vec![1, 0, 0];
+        assert_eq!(unpad(pad(data.clone())).unwrap(), data);
+
+        assert!(unpad(vec![1, 2, 0, 0]).is_err());
+        assert!(unpad(vec![0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_max_unpadded_len() {
+        for max_padded_len in [256, 300, 1024, 5000, 65536, 100_000, 262_144, 1_000_000] {
+            let len = max_unpadded_len(max_padded_len);
+            assert!(padded_len(len) <= max_padded_len);
+            assert!(padded_len(len + 1) > max_padded_len);
+        }
+
+        assert_eq!(max_unpadded_len(255), 0);
+    }
+
+    #[test]
+    fn test_random_bucket_len() {
+        let mut rng = rand::thread_rng();
+        let mut buckets = std::collections::HashSet::new();
+        for _ in 0..1000 {
+            let len = random_bucket_len(&mut rng);
+            assert!(PADDING_BUCKETS.contains(&padded_len(len)));
+            buckets.insert(padded_len(len));
+        }
+        assert_eq!(buckets.len(), PADDING_BUCKETS.len());
+    }
+}
@@ -2,16 +2,74 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-//
-//
-// todo: make this a hash of the network id so it is unique per p2p network
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicU32, Ordering};
 
+/// The salt of network id 0 - the salt all networks used before salts were derived from network ids.
+/// Salts of other networks are a hash of it and the network id so they are unique per p2p network
 pub static NET_SALT: [u8; 32] = [
     0x4b, 0x66, 0xe9, 0xd4, 0xd1, 0xb4, 0x67, 0x3c, 0x5a, 0xd2, 0x26, 0x91, 0x95, 0x7d, 0x6a, 0xf5,
     0xc1, 0x1b, 0x64, 0x21, 0xe0, 0xea, 0x01, 0xd4, 0x2c, 0xa4, 0x16, 0x9e, 0x79, 0x18, 0xba, 0x0d,
 ];
 
+/// Id of the p2p network this process is part of
+static NET_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Set the id of the p2p network this process is part of. Apps set it from their config on startup.
+pub fn set_net_id(net_id: u32) {
+    NET_ID.store(net_id, Ordering::SeqCst);
+}
+
+/// Returns the id of the p2p network this process is part of
+pub fn net_id() -> u32 {
+    NET_ID.load(Ordering::SeqCst)
+}
+
+/// Returns the salt of this process's p2p network
+pub fn net_salt() -> [u8; 32] {
+    net_salt_for(net_id())
+}
+
+/// Returns the salt of a p2p network. Network id 0 keeps the legacy salt so its signatures and ids don't change
+pub fn net_salt_for(net_id: u32) -> [u8; 32] {
+    if net_id == 0 {
+        return NET_SALT;
+    }
+
+    let mut salt = [0u8; 32];
+    salt.copy_from_slice(
+        &Sha256::new()
+            .chain(NET_SALT)
+            .chain(net_id.to_le_bytes())
+            .finalize(),
+    );
+    salt
+}
+
 // IV for AES w/o hamc
 pub static AES_IV: [u8; 16] = [
     0x1e, 0xff, 0x01, 0x32, 0xad, 0xfa, 0x24, 0x55, 0xe1, 0x94, 0x8d, 0x57, 0x3c, 0xaa, 0xf5, 0x82,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_net_salt() {
+        assert_eq!(net_salt_for(1), net_salt_for(1));
+        assert_ne!(net_salt_for(0), net_salt_for(1));
+        assert_ne!(net_salt_for(1), net_salt_for(2));
+        assert_ne!(net_salt_for(1), NET_SALT);
+    }
+
+    /// Network 0 uses the salt it used before salts were derived from network ids
+    #[test]
+    fn test_legacy_net_salt() {
+        assert_eq!(
+            hex::encode(net_salt_for(0)),
+            "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"
+        );
+        assert_eq!(net_salt_for(0), NET_SALT);
+    }
+}
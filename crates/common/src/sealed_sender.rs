@@ -0,0 +1,156 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::aead::AEAD;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::Signed;
+use base::snp::snp_core_types::{ClientIdentityBundle, EntityId, PublicKey};
+use base::snp::snp_server_api::{DrMessage, SealedSenderContent, SealedSenderMessage};
+use bytes::Bytes;
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seal a dr message to a client so providers can't learn who sent it.
+/// The sender's id and signature are encrypted to the receiver using an eph-dh with the receiver's pre-key.
+pub fn seal_message(
+    sender: &ed25519_dalek::Keypair,
+    receiver_bundle: &ClientIdentityBundle,
+    dr_message: DrMessage,
+) -> Result<SealedSenderMessage> {
+    let mut content = SealedSenderContent {
+        time_stamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+        sender: Some(EntityId {
+            public_key: Some(PublicKey {
+                key: sender.public.as_ref().to_vec(),
+            }),
+            nickname: "".to_string(),
+        }),
+        receiver: Some(receiver_bundle.get_client_entity()?),
+        dr_message: Some(dr_message),
+        signature: None,
+    };
+    content.sign(sender)?;
+
+    use prost::Message;
+    let mut buf = Vec::with_capacity(content.encoded_len());
+    content.encode(&mut buf)?;
+
+    let pre_key = receiver_bundle.get_client_x25519_pre_key()?;
+    let eph_key = x25519_dalek::EphemeralSecret::new(OsRng);
+    let eph_pub = x25519_dalek::PublicKey::from(&eph_key);
+    let shared_secret = eph_key.diffie_hellman(&pre_key);
+    let ad = crate::edh::compute_ad(&eph_pub, &pre_key);
+    let enc_content = AEAD::encrypt(Bytes::from(buf), shared_secret.as_bytes(), &ad)?;
+
+    Ok(SealedSenderMessage {
+        sender_ephemeral_key: Some(PublicKey {
+            key: eph_pub.as_bytes().to_vec(),
+        }),
+        receiver_bundle_id: receiver_bundle.time_stamp,
+        enc_content: enc_content.to_vec(),
+    })
+}
+
+/// Open a sealed-sender message designated to a client using the client's pre-key.
+/// Returns the content after verifying the sender's signature and that the client is the designated receiver.
+pub fn unseal_message(
+    message: &SealedSenderMessage,
+    receiver: &ed25519_dalek::PublicKey,
+    pre_key: &x25519_dalek::StaticSecret,
+) -> Result<SealedSenderContent> {
+    let eph_pub = message
+        .sender_ephemeral_key
+        .as_ref()
+        .ok_or_else(|| anyhow!("missing sender ephemeral key"))?
+        .as_x25519_pub_key()?;
+
+    let shared_secret = pre_key.diffie_hellman(&eph_pub);
+    let ad = crate::edh::compute_ad(&eph_pub, &x25519_dalek::PublicKey::from(pre_key));
+    let content_bytes = AEAD::decrypt(
+        message.enc_content.as_ref(),
+        &shared_secret.to_bytes(),
+        ad.as_ref(),
+    )?;
+
+    use prost::Message;
+    let content = SealedSenderContent::decode(content_bytes.as_ref())?;
+    content.verify_signature()?;
+
+    if content.get_receiver_pub_key()? != *receiver {
+        bail!("sealed content is designated to another receiver")
+    }
+
+    Ok(content)
+}
+
+/// Returns true if a delivery token presented by a sender matches the token a client issued.
+/// Tokens are compared by their digests so the comparison doesn't leak a token prefix.
+pub fn is_valid_delivery_token(client_token: &[u8], token: &[u8]) -> bool {
+    !client_token.is_empty() && Sha256::digest(client_token) == Sha256::digest(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::snp::snp_core_types::PreKey;
+    use base::snp::snp_server_api::dr_message::Data;
+    use base::snp::snp_server_api::Message;
+
+    fn client_bundle(
+        id: &ed25519_dalek::Keypair,
+        pre_key: &x25519_dalek::StaticSecret,
+    ) -> ClientIdentityBundle {
+        ClientIdentityBundle {
+            time_stamp: 7,
+            client_id: Some(EntityId {
+                public_key: Some(PublicKey {
+                    key: id.public.as_ref().to_vec(),
+                }),
+                nickname: "".to_string(),
+            }),
+            pre_key: Some(PreKey {
+                key: Some(PublicKey {
+                    key: x25519_dalek::PublicKey::from(pre_key).as_bytes().to_vec(),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_seal_message() {
+        let alice = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let bob = ed25519_dalek::Keypair::generate(&mut OsRng);
+        let bob_pre_key = x25519_dalek::StaticSecret::new(OsRng);
+        let bob_bundle = client_bundle(&bob, &bob_pre_key);
+
+        let dr_message = DrMessage {
+            data: Some(Data::Message(Message {
+                header: None,
+                enc_typed_msg: vec![1, 2, 3],
+            })),
+        };
+
+        let sealed = seal_message(&alice, &bob_bundle, dr_message.clone()).unwrap();
+        assert_eq!(sealed.receiver_bundle_id, 7);
+
+        let content = unseal_message(&sealed, &bob.public, &bob_pre_key).unwrap();
+        assert_eq!(content.get_sender_pub_key().unwrap(), alice.public);
+        assert_eq!(content.dr_message, Some(dr_message));
+
+        // only the designated receiver can open the message
+        let eve_pre_key = x25519_dalek::StaticSecret::new(OsRng);
+        assert!(unseal_message(&sealed, &bob.public, &eve_pre_key).is_err());
+        assert!(unseal_message(&sealed, &alice.public, &bob_pre_key).is_err());
+    }
+
+    #[test]
+    fn test_delivery_token() {
+        assert!(is_valid_delivery_token(b"token", b"token"));
+        assert!(!is_valid_delivery_token(b"token", b"other"));
+        assert!(!is_valid_delivery_token(b"", b""));
+    }
+}
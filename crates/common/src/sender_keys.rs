@@ -0,0 +1,278 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::aead::AEAD;
+use crate::message_padding;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::Signed;
+use base::snp::snp_client_to_client::GroupSenderKey;
+use base::snp::snp_server_api::{GroupMessage, TypedMessage};
+use bytes::Bytes;
+use crypto::hmacer::Hmacer;
+use ed25519_dalek::{Keypair, Signer, Verifier};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// Max number of message keys a receiver keeps for messages which it didn't get yet, e.g. messages it gets out of order
+const MAX_SKIPPED_KEYS: usize = 1000;
+
+/// A group sender key. Its owner encrypts each group message once with a message key derived by a symmetric ratchet
+/// and signs it with the key's signing key. Group members who got the key from the owner derive the same message keys
+/// to decrypt the message. The ratchet only moves forward so a member can't decrypt messages sent before it got the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SenderKey {
+    pub key_id: Vec<u8>,
+    pub channel_id: Vec<u8>,
+    chain_key: Vec<u8>,
+    iteration: u32,
+    signing_key: Vec<u8>,
+    // only the key owner has the signing key pair and can encrypt messages with the key
+    signing_key_pair: Option<Vec<u8>>,
+    // message keys of iterations before the current one that a receiver didn't use yet
+    skipped_keys: HashMap<u32, Vec<u8>>,
+}
+
+impl SenderKey {
+    /// Create a new sender key for a group
+    pub fn new(channel_id: &[u8]) -> Self {
+        let mut key_id = vec![0u8; 16];
+        OsRng.fill_bytes(&mut key_id);
+        let mut chain_key = vec![0u8; 32];
+        OsRng.fill_bytes(&mut chain_key);
+        let signing_key_pair = Keypair::generate(&mut OsRng);
+
+        SenderKey {
+            key_id,
+            channel_id: channel_id.to_vec(),
+            chain_key,
+            iteration: 0,
+            signing_key: signing_key_pair.public.as_ref().to_vec(),
+            signing_key_pair: Some(signing_key_pair.to_bytes().to_vec()),
+            skipped_keys: HashMap::new(),
+        }
+    }
+
+    /// A sender key that its owner sent us. It can only decrypt the owner's messages
+    pub fn from_distribution(key: &GroupSenderKey) -> Result<Self> {
+        if key.chain_key.len() != 32 {
+            bail!("invalid chain key")
+        }
+        ed25519_dalek::PublicKey::from_bytes(&key.signing_key)?;
+
+        Ok(SenderKey {
+            key_id: key.key_id.clone(),
+            channel_id: key.channel_id.clone(),
+            chain_key: key.chain_key.clone(),
+            iteration: key.iteration,
+            signing_key: key.signing_key.clone(),
+            signing_key_pair: None,
+            skipped_keys: HashMap::new(),
+        })
+    }
+
+    /// Returns the key's current state to distribute to group members.
+    /// Members can decrypt messages encrypted with the key from now on
+    pub fn distribution(&self) -> GroupSenderKey {
+        GroupSenderKey {
+            channel_id: self.channel_id.clone(),
+            key_id: self.key_id.clone(),
+            chain_key: self.chain_key.clone(),
+            iteration: self.iteration,
+            signing_key: self.signing_key.clone(),
+        }
+    }
+
+    /// Encrypt and sign a message to all group members with the next message key
+    pub fn encrypt(&mut self, message: TypedMessage) -> Result<GroupMessage> {
+        let signing_key_pair = Keypair::from_bytes(
+            self.signing_key_pair
+                .as_ref()
+                .ok_or_else(|| anyhow!("only the sender key owner can encrypt with it"))?,
+        )?;
+
+        use prost::Message;
+        let mut buff = Vec::with_capacity(message.encoded_len());
+        message.encode(&mut buff)?;
+        if message_padding::message_padding() {
+            buff = message_padding::pad(buff);
+        }
+
+        let iteration = self.iteration;
+        let message_key = self.next_message_key()?;
+        let enc_typed_msg = AEAD::encrypt(
+            Bytes::from(buff),
+            &message_key,
+            &compute_ad(&self.key_id, iteration),
+        )?;
+
+        let mut group_message = GroupMessage {
+            key_id: self.key_id.clone(),
+            iteration,
+            enc_typed_msg: enc_typed_msg.to_vec(),
+            signature: vec![],
+        };
+        group_message.signature = signing_key_pair
+            .sign(&signed_data(&group_message))
+            .to_bytes()
+            .to_vec();
+
+        Ok(group_message)
+    }
+
+    /// Verify a group message was signed by the key owner and decrypt it.
+    /// Returns the message after verifying its sender's signature
+    pub fn decrypt(&mut self, message: &GroupMessage) -> Result<TypedMessage> {
+        if message.key_id != self.key_id {
+            bail!("message is encrypted with another sender key")
+        }
+
+        let signing_key = ed25519_dalek::PublicKey::from_bytes(&self.signing_key)?;
+        let signature = ed25519_dalek::Signature::try_from(message.signature.as_slice())?;
+        signing_key
+            .verify(&signed_data(message), &signature)
+            .map_err(|_| anyhow!("invalid group message signature"))?;
+
+        let message_key = self.get_message_key(message.iteration)?;
+        let mut clear_text = AEAD::decrypt(
+            &message.enc_typed_msg,
+            &message_key,
+            &compute_ad(&self.key_id, message.iteration),
+        )?
+        .to_vec();
+        if message_padding::message_padding() {
+            clear_text = message_padding::unpad(clear_text)?;
+        }
+
+        use prost::Message;
+        let typed_message = TypedMessage::decode(clear_text.as_slice())?;
+        typed_message.verify_signature()?;
+        Ok(typed_message)
+    }
+
+    /// Returns the message key of the current iteration and ratchets the chain key forward
+    fn next_message_key(&mut self) -> Result<[u8; 32]> {
+        let (chain_key, message_key) = ratchet(&self.chain_key)?;
+        self.chain_key = chain_key;
+        self.iteration += 1;
+        Ok(message_key)
+    }
+
+    /// Returns the message key of an iteration. Keys of skipped iterations are kept until their messages arrive
+    fn get_message_key(&mut self, iteration: u32) -> Result<[u8; 32]> {
+        if iteration < self.iteration {
+            let key = self
+                .skipped_keys
+                .remove(&iteration)
+                .ok_or_else(|| anyhow!("message key was already used or is unknown"))?;
+            return Ok(<[u8; 32]>::try_from(key.as_slice())?);
+        }
+
+        if (iteration - self.iteration) as usize > MAX_SKIPPED_KEYS {
+            bail!("message is too far ahead of the sender key")
+        }
+
+        while self.iteration < iteration {
+            let skipped = self.iteration;
+            let key = self.next_message_key()?;
+            self.skipped_keys.insert(skipped, key.to_vec());
+        }
+
+        // drop the oldest skipped keys
+        while self.skipped_keys.len() > MAX_SKIPPED_KEYS {
+            let oldest = *self.skipped_keys.keys().min().unwrap();
+            self.skipped_keys.remove(&oldest);
+        }
+
+        self.next_message_key()
+    }
+}
+
+/// Derive the next chain key and a message key from a chain key
+fn ratchet(chain_key: &[u8]) -> Result<(Vec<u8>, [u8; 32])> {
+    let next_chain_key = Hmacer::hmac_sha512(chain_key, &[0x02])?;
+    let message_key = Hmacer::hmac_sha512(chain_key, &[0x01])?;
+
+    Ok((
+        next_chain_key.unprotected_as_bytes()[..32].to_vec(),
+        <[u8; 32]>::try_from(&message_key.unprotected_as_bytes()[..32])?,
+    ))
+}
+
+fn compute_ad(key_id: &[u8], iteration: u32) -> Vec<u8> {
+    let mut ad = key_id.to_vec();
+    ad.extend_from_slice(&iteration.to_be_bytes());
+    ad
+}
+
+/// Signed data of a group message - all fields besides its signature
+fn signed_data(message: &GroupMessage) -> Vec<u8> {
+    let mut data = compute_ad(&message.key_id, message.iteration);
+    data.extend_from_slice(&message.enc_typed_msg);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::snp::snp_core_types::{EntityId, PublicKey};
+    use base::snp::snp_server_api::MessageType;
+
+    fn typed_message(sender: &Keypair, text: &str) -> TypedMessage {
+        let mut message = TypedMessage {
+            time_stamp: 1,
+            msg_type: MessageType::ChannelMessage as i32,
+            message: text.as_bytes().to_vec(),
+            receiver: None,
+            sender: Some(EntityId {
+                public_key: Some(PublicKey {
+                    key: sender.public.as_ref().to_vec(),
+                }),
+                nickname: "".to_string(),
+            }),
+            signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
+        };
+        message.sign(sender).unwrap();
+        message
+    }
+
+    #[test]
+    fn test_sender_key() {
+        let sender = Keypair::generate(&mut OsRng);
+        let mut key = SenderKey::new(&[7; 32]);
+        let mut member_key = SenderKey::from_distribution(&key.distribution()).unwrap();
+
+        let first = key.encrypt(typed_message(&sender, "first")).unwrap();
+        let second = key.encrypt(typed_message(&sender, "second")).unwrap();
+        let third = key.encrypt(typed_message(&sender, "third")).unwrap();
+
+        // messages can be decrypted out of order but only once
+        assert_eq!(member_key.decrypt(&second).unwrap().message, b"second");
+        assert_eq!(member_key.decrypt(&first).unwrap().message, b"first");
+        assert!(member_key.decrypt(&first).is_err());
+        assert_eq!(member_key.decrypt(&third).unwrap().message, b"third");
+
+        // a member who got the key later can't decrypt earlier messages
+        let mut late_member_key = SenderKey::from_distribution(&key.distribution()).unwrap();
+        assert!(late_member_key.decrypt(&third).is_err());
+        let fourth = key.encrypt(typed_message(&sender, "fourth")).unwrap();
+        assert_eq!(late_member_key.decrypt(&fourth).unwrap().message, b"fourth");
+
+        // only the key owner can encrypt and sign messages with the key
+        assert!(member_key
+            .encrypt(typed_message(&sender, "forged"))
+            .is_err());
+        let mut forged = key.encrypt(typed_message(&sender, "fifth")).unwrap();
+        forged.enc_typed_msg[0] ^= 1;
+        assert!(member_key.decrypt(&forged).is_err());
+
+        // a new key can't decrypt messages encrypted with the old one
+        let mut new_member_key =
+            SenderKey::from_distribution(&SenderKey::new(&[7; 32]).distribution()).unwrap();
+        assert!(new_member_key.decrypt(&fourth).is_err());
+    }
+}
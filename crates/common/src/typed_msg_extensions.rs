@@ -2,7 +2,8 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::network_salt::NET_SALT;
+use crate::message_padding;
+use crate::network_salt::net_salt;
 use anyhow::Result;
 use base::api_types_extensions::Signed;
 use base::snp::snp_server_api::TypedMessage;
@@ -18,12 +19,15 @@ pub struct TypedMessageExtensions;
 impl TypedMessageExtensions {
     pub fn decrypt_msg(enc_message: &[u8], key: &MessageKey, ad: &[u8]) -> Result<TypedMessage> {
         let cipher = AeadCipher::new(
-            Bytes::from(NET_SALT.to_vec()),
+            Bytes::from(net_salt().to_vec()),
             Bytes::from(key.to_vec()),
             Bytes::from(ad.to_vec()),
         );
 
-        let clear_text = cipher.decrypt(enc_message)?;
+        let mut clear_text = cipher.decrypt(enc_message)?;
+        if message_padding::message_padding() {
+            clear_text = message_padding::unpad(clear_text)?;
+        }
 
         use prost::Message;
         let typed_message = TypedMessage::decode(clear_text.as_slice())?;
@@ -34,13 +38,17 @@ impl TypedMessageExtensions {
     /// Encrypt a TypedMessage into an EncryptedTypedMessage using an encryption key and ad
     /// is aware of base and the crypto crates
     /// currently used by integration test by client
+    /// Message is padded to its size bucket when the network pads messages
     pub fn encrypt_msg(message: TypedMessage, key: &MessageKey, ad: &[u8]) -> Result<Bytes> {
         use prost::Message;
         let mut buff = Vec::with_capacity(message.encoded_len());
         message.encode(&mut buff)?;
+        if message_padding::message_padding() {
+            buff = message_padding::pad(buff);
+        }
 
         let cipher = AeadCipher::new(
-            Bytes::from(NET_SALT.to_vec()),
+            Bytes::from(net_salt().to_vec()),
             Bytes::from(key.to_vec()),
             Bytes::from(ad.to_vec()),
         );
@@ -53,7 +53,7 @@ impl Handler<ExecuteProtocolAsAlice> for X2DHService {
         debug!("bob and alice protocol output not in db for alice - executing");
 
         // step 2 - execute the X2DH protocol
-        let output = x2dh::execute_alice(&msg.0);
+        let output = x2dh::execute_alice(&msg.0)?;
 
         // step 3 - store the output in the db
         let data: Bytes = Bytes::from(bincode::serialize(&output).unwrap());
@@ -100,7 +100,7 @@ impl Handler<ExecuteProtocolAsBob> for X2DHService {
         debug!("No stored X2DH session output for parties - executing X2DH...");
 
         // step 2 - execute the x2dh protocol
-        let output = x2dh::execute_bob(&msg.0);
+        let output = x2dh::execute_bob(&msg.0)?;
 
         // step 3 - store the output in the db with ttl of 2 weeks or so
         let data: Bytes = Bytes::from(bincode::serialize(&output).unwrap());
@@ -133,6 +133,9 @@ impl X2DHService {
         let mut hasher = Sha512::new();
         hasher.update(input.ikb.as_bytes().to_vec());
         hasher.update(input.pkb.as_bytes().to_vec());
+        if let Some(kem_pkb) = input.kem_pkb.as_ref() {
+            hasher.update(kem_pkb);
+        }
 
         let mut buf = [0; 8];
         BigEndian::write_u64(&mut buf, input.b_bundle_id);
@@ -146,6 +149,9 @@ impl X2DHService {
     fn compute_bob_output_db_key(input: &ProtocolInputBob) -> Bytes {
         let mut hasher = Sha512::new();
         hasher.update(input.eka.as_bytes().to_vec());
+        if let Some(kem_ct) = input.kem_ct.as_ref() {
+            hasher.update(kem_ct);
+        }
         hasher.update(input.ikb_pair.public.as_bytes().to_vec());
         hasher.update(input.pkb_private.to_bytes().to_vec());
 
@@ -106,6 +106,6 @@ mod tests {
         let cipher = AeadCipher::new(info_bytes, key, ad_bytes);
         let cipher_text = cipher.encrypt(plaintext_bytes).unwrap();
         let round_tripped = cipher.decrypt(&cipher_text).unwrap();
-        assert_eq!(plaintext.as_ref().to_vec(), round_tripped);
+        assert_eq!(plaintext.to_vec(), round_tripped);
     }
 }
@@ -21,5 +21,6 @@ pub mod aes_cypher;
 pub mod hasher;
 pub mod hmacer;
 pub mod kdfer;
+pub mod ml_kem;
 pub mod utils;
 pub mod x2dh;
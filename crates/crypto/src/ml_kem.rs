@@ -0,0 +1,169 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+//! ML-KEM-768 key encapsulation mechanism (FIPS 203).
+//! Used by hybrid x2dh to mix a post-quantum shared secret into the x2dh shared secret.
+//! The KEM is provided by the ml-kem crate. Keys and ciphertexts use the standard's encodings.
+
+use anyhow::{anyhow, bail, Result};
+use kem::{Decapsulate, Encapsulate};
+use ml_kem::{Ciphertext, EncodedSizeUser, KemCore, MlKem768};
+use rand_core_06::OsRng;
+use std::convert::TryFrom;
+use tiny_keccak::{Hasher, Sha3};
+
+type EncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+type DecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+type Encoded<T> = ml_kem::Encoded<T>;
+
+pub const ENCAPSULATION_KEY_LEN: usize = 1184;
+pub const DECAPSULATION_KEY_LEN: usize = 2400;
+pub const CIPHERTEXT_LEN: usize = 1088;
+pub const SHARED_SECRET_LEN: usize = 32;
+
+/// An ML-KEM-768 key pair
+pub struct KeyPair {
+    pub encapsulation_key: Vec<u8>,
+    pub decapsulation_key: Vec<u8>,
+}
+
+/// Create a new random key pair
+pub fn generate_key_pair() -> KeyPair {
+    let (dk, ek) = MlKem768::generate(&mut OsRng);
+    KeyPair {
+        encapsulation_key: ek.as_bytes().to_vec(),
+        decapsulation_key: dk.as_bytes().to_vec(),
+    }
+}
+
+/// Create the key pair of a seed (ML-KEM.KeyGen_internal)
+pub fn generate_key_pair_from_seed(d: &[u8; 32], z: &[u8; 32]) -> KeyPair {
+    let (dk, ek) = MlKem768::generate_deterministic(&(*d).into(), &(*z).into());
+    KeyPair {
+        encapsulation_key: ek.as_bytes().to_vec(),
+        decapsulation_key: dk.as_bytes().to_vec(),
+    }
+}
+
+/// Create a shared secret and its ciphertext for the owner of an encapsulation key.
+/// Returns (ciphertext, shared secret)
+pub fn encapsulate(ek: &[u8]) -> Result<(Vec<u8>, [u8; SHARED_SECRET_LEN])> {
+    let ek = decode_encapsulation_key(ek)?;
+    let (ct, shared_secret) = ek
+        .encapsulate(&mut OsRng)
+        .map_err(|_| anyhow!("ml-kem encapsulation failed"))?;
+    Ok((ct.to_vec(), shared_secret.into()))
+}
+
+/// Returns the shared secret of a ciphertext created for our encapsulation key.
+/// Invalid ciphertexts implicitly yield a pseudo-random shared secret per the standard.
+pub fn decapsulate(dk: &[u8], ct: &[u8]) -> Result<[u8; SHARED_SECRET_LEN]> {
+    let dk_data = Encoded::<DecapsulationKey>::try_from(dk)
+        .map_err(|_| anyhow!("invalid ml-kem decapsulation key length"))?;
+    let ct = Ciphertext::<MlKem768>::try_from(ct)
+        .map_err(|_| anyhow!("invalid ml-kem ciphertext length"))?;
+
+    // decapsulation key input check (FIPS 203 7.3) - the embedded encapsulation key hash must match
+    let ek = &dk[384 * 3..768 * 3 + 32];
+    let hash = &dk[768 * 3 + 32..768 * 3 + 64];
+    if sha3_256(ek) != hash {
+        bail!("invalid ml-kem decapsulation key")
+    }
+
+    let shared_secret = DecapsulationKey::from_bytes(&dk_data)
+        .decapsulate(&ct)
+        .map_err(|_| anyhow!("ml-kem decapsulation failed"))?;
+    Ok(shared_secret.into())
+}
+
+/// Decode an encapsulation key and run the standard's modulus input check on it (FIPS 203 7.2).
+/// Decoding reduces coefficients mod q so a key with unreduced coefficients doesn't encode back to itself
+fn decode_encapsulation_key(ek: &[u8]) -> Result<EncapsulationKey> {
+    let data = Encoded::<EncapsulationKey>::try_from(ek)
+        .map_err(|_| anyhow!("invalid ml-kem encapsulation key length"))?;
+    let key = EncapsulationKey::from_bytes(&data);
+    if key.as_bytes() != data {
+        bail!("invalid ml-kem encapsulation key")
+    }
+    Ok(key)
+}
+
+fn sha3_256(data: &[u8]) -> [u8; 32] {
+    let mut sha3 = Sha3::v256();
+    sha3.update(data);
+    let mut out = [0u8; 32];
+    sha3.finalize(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ml_kem::EncapsulateDeterministic;
+
+    #[test]
+    fn test_encapsulate_decapsulate() {
+        let key_pair = generate_key_pair();
+        assert_eq!(key_pair.encapsulation_key.len(), ENCAPSULATION_KEY_LEN);
+        assert_eq!(key_pair.decapsulation_key.len(), DECAPSULATION_KEY_LEN);
+
+        let (ct, shared_secret) = encapsulate(&key_pair.encapsulation_key).unwrap();
+        assert_eq!(ct.len(), CIPHERTEXT_LEN);
+        assert_eq!(
+            decapsulate(&key_pair.decapsulation_key, &ct).unwrap(),
+            shared_secret
+        );
+
+        // a modified ciphertext implicitly yields a different shared secret
+        let mut bad_ct = ct.clone();
+        bad_ct[0] ^= 1;
+        assert_ne!(
+            decapsulate(&key_pair.decapsulation_key, &bad_ct).unwrap(),
+            shared_secret
+        );
+
+        assert!(decapsulate(&key_pair.decapsulation_key, &ct[1..]).is_err());
+        assert!(encapsulate(&key_pair.encapsulation_key[1..]).is_err());
+
+        // coefficients must be reduced mod q
+        let mut bad_ek = key_pair.encapsulation_key;
+        bad_ek[0] = 0xff;
+        bad_ek[1] |= 0x0f;
+        assert!(encapsulate(&bad_ek).is_err());
+    }
+
+    /// Known answer from a deterministic seed and encapsulation randomness, cross-checked with OpenSSL's ML-KEM-768
+    #[test]
+    fn test_known_answer() {
+        let mut d = [0u8; 32];
+        let mut z = [0u8; 32];
+        for i in 0..32 {
+            d[i] = i as u8;
+            z[i] = 32 + i as u8;
+        }
+        let key_pair = generate_key_pair_from_seed(&d, &z);
+        assert_eq!(
+            hex::encode(sha3_256(&key_pair.encapsulation_key)),
+            "a24e16d8f8f9383a95b77050f4d9fd2f5733eec1d63ef3c23ebf9918173669a7"
+        );
+
+        let (ct, shared_secret) = EncapsulationKey::from_bytes(
+            &Encoded::<EncapsulationKey>::try_from(key_pair.encapsulation_key.as_slice()).unwrap(),
+        )
+        .encapsulate_deterministic(&[7u8; 32].into())
+        .unwrap();
+        assert_eq!(
+            hex::encode(sha3_256(&ct)),
+            "a2ccffc801ffd1202ecf6b9a7fb3235a6efa4d4963cc84dc6c69239223b54a5c"
+        );
+        assert_eq!(
+            hex::encode(shared_secret),
+            "f3409cb545c0757aab3d7c7b9e8be4225b4aac1107f6663f1f19dc676a69de60"
+        );
+        assert_eq!(
+            decapsulate(&key_pair.decapsulation_key, &ct).unwrap(),
+            shared_secret.as_slice()
+        );
+    }
+}
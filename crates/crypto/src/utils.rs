@@ -23,7 +23,7 @@ pub fn sha512(data: &[u8]) -> Vec<u8> {
 /// Return a new EntityId for a x25519 public key
 pub fn entity_from_x25519_pub_key(pub_key: x25519_dalek::PublicKey, nickname: String) -> EntityId {
     let k = base::snp::snp_core_types::PublicKey {
-        key: pub_key.to_bytes().as_ref().to_vec(),
+        key: pub_key.to_bytes().to_vec(),
     };
 
     EntityId {
@@ -3,7 +3,9 @@
 //
 
 use crate::kdfer::Kdfer;
+use crate::ml_kem;
 use crate::utils::{PublicKeyWrapper, StaticSecretWrapper};
+use anyhow::{bail, Result};
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha512};
@@ -20,12 +22,14 @@ pub struct ProtocolInputAlice {
     pub ikb: ed25519_dalek::PublicKey, // Bob's public identity key
     pub pkb: x25519_dalek::PublicKey,  // Bob's public pre-key
     pub b_bundle_id: u64, // the id of bob's identity bundle used to get bob's public keys
+    pub kem_pkb: Option<Vec<u8>>, // Bob's ml-kem encapsulation key. Set when Bob's pre-key is hybrid
 }
 
 /// Alice's protocol execution output
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProtocolOutputAlice {
     pub eka: PublicKey,          // the public key of party a ephemeral key
+    pub kem_ct: Option<Vec<u8>>, // ml-kem ciphertext for bob. Set in hybrid mode
     pub shared_secret: [u8; 32], // the shared secret created between a nd b
     pub ad: Bytes,               // pub associated_date: [u8],   // AD in the X2DH protocol
 }
@@ -33,9 +37,11 @@ pub struct ProtocolOutputAlice {
 /// Bob's X2DH protocol execution input
 pub struct ProtocolInputBob {
     pub eka: PublicKey,                          // Alice ephemeral x25519 public key
+    pub kem_ct: Option<Vec<u8>>,                 // Alice ml-kem ciphertext. Set in hybrid mode
     pub ikb_pair: ed25519_dalek::Keypair,        // Bob's id key pair
     pub pkb_private: x25519_dalek::StaticSecret, // Bob's pre-key private key (pub extractable)
     pub b_bundle_id: u64, // the id of bob's identity bundle used to get bob's public keys
+    pub kem_pkb_private: Option<Vec<u8>>, // Bob's ml-kem decapsulation key. Set when Bob's pre-key is hybrid
 }
 
 /// Bob's protocol output
@@ -58,7 +64,8 @@ pub fn compute_ad(eka: PublicKey, ikb: ed25519_dalek::PublicKey) -> Bytes {
 
 /// Execute the X2DH protocol (see X2DH spec).
 /// Alice is the protocol initiator. She calls this method to execute the protocol with Bob.
-pub fn execute_alice(input: &ProtocolInputAlice) -> ProtocolOutputAlice {
+/// Executes the hybrid protocol when Bob's pre-key includes an ml-kem encapsulation key.
+pub fn execute_alice(input: &ProtocolInputAlice) -> Result<ProtocolOutputAlice> {
     // ephemeral secret - we use StaticSecret as we need to diffie hellman more than once with it
     let ea_secret = x25519_dalek::StaticSecret::new(&mut rand_core::OsRng);
     // ephemeral public key
@@ -71,22 +78,38 @@ pub fn execute_alice(input: &ProtocolInputAlice) -> ProtocolOutputAlice {
     //DH2 = DH(EKA, PKB)
     let dh2 = ea_secret.diffie_hellman(&input.pkb);
 
-    // SK = KDF(DH2 || DH3 )
-    let shared_secret = Kdfer::kdf(dh1.as_bytes(), dh2.as_bytes(), None, None).unwrap();
+    // SS = ML-KEM-ENCAPS(PQPKB)
+    let (kem_ct, kem_ss) = match input.kem_pkb.as_ref() {
+        Some(kem_pkb) => {
+            let (ct, ss) = ml_kem::encapsulate(kem_pkb)?;
+            (Some(ct), Some(ss))
+        }
+        None => (None, None),
+    };
+
+    // SK = KDF(DH1 || DH2 [|| SS])
+    let shared_secret = Kdfer::kdf(
+        dh1.as_bytes(),
+        dh2.as_bytes(),
+        kem_ss.as_ref().map(|ss| ss.as_ref()),
+        None,
+    )?;
 
     // AD = Encode(IKA) || Encode(IKB)
     let ad = compute_ad(eka, input.ikb);
 
-    ProtocolOutputAlice {
+    Ok(ProtocolOutputAlice {
         eka,
+        kem_ct,
         shared_secret,
         ad,
-    }
+    })
 }
 
 /// Bob's is the receiver of an X2DH protocol request from Alice.
 /// He executes the protocol to device the same shared secret output
-pub fn execute_bob(input: &ProtocolInputBob) -> ProtocolOutputBob {
+/// Returns an error when the request's mode doesn't match Bob's pre-key, e.g. a classic request for a hybrid pre-key.
+pub fn execute_bob(input: &ProtocolInputBob) -> Result<ProtocolOutputBob> {
     // DH1 = DH(IKB, EKA)
     let ikb_secret: StaticSecretWrapper = (&input.ikb_pair.secret).into();
     let dh1 = ikb_secret.0.diffie_hellman(&input.eka);
@@ -94,13 +117,29 @@ pub fn execute_bob(input: &ProtocolInputBob) -> ProtocolOutputBob {
     // DH2 = DH(PKB, EKA)
     let dh2 = input.pkb_private.diffie_hellman(&input.eka);
 
-    // SK = KDF(DH2 || DH3 )
-    let shared_secret = Kdfer::kdf(dh1.as_bytes(), dh2.as_bytes(), None, None).unwrap();
+    // SS = ML-KEM-DECAPS(PQPKB, CT)
+    let kem_ss = match (input.kem_pkb_private.as_ref(), input.kem_ct.as_ref()) {
+        (Some(kem_pkb_private), Some(kem_ct)) => {
+            Some(ml_kem::decapsulate(kem_pkb_private, kem_ct)?)
+        }
+        (None, None) => None,
+        // don't let an attacker downgrade a hybrid pre-key to classic x2dh
+        (Some(_), None) => bail!("missing ml-kem ciphertext for a hybrid pre-key"),
+        (None, Some(_)) => bail!("unexpected ml-kem ciphertext for a classic pre-key"),
+    };
+
+    // SK = KDF(DH1 || DH2 [|| SS])
+    let shared_secret = Kdfer::kdf(
+        dh1.as_bytes(),
+        dh2.as_bytes(),
+        kem_ss.as_ref().map(|ss| ss.as_ref()),
+        None,
+    )?;
 
     // AD = Encode(IKA) || Encode(IKB)
     let ad = compute_ad(input.eka, input.ikb_pair.public);
 
-    ProtocolOutputBob { shared_secret, ad }
+    Ok(ProtocolOutputBob { shared_secret, ad })
 }
 
 #[cfg(test)]
@@ -125,19 +164,23 @@ mod tests {
             ikb: bob_id_key_pair.public,
             pkb: bob_pre_key_public,
             b_bundle_id: 0,
+            kem_pkb: None,
         };
 
-        let output_alice = execute_alice(&input_alice);
+        let output_alice = execute_alice(&input_alice).unwrap();
+        assert!(output_alice.kem_ct.is_none());
 
         // Bob's execution
         let input_bob = ProtocolInputBob {
             eka: output_alice.eka,
+            kem_ct: None,
             ikb_pair: bob_id_key_pair,
             pkb_private: bob_pre_key_private,
             b_bundle_id: 0,
+            kem_pkb_private: None,
         };
 
-        let output_bob = execute_bob(&input_bob);
+        let output_bob = execute_bob(&input_bob).unwrap();
 
         debug!(
             "Alice's shared secret: {:?}",
@@ -161,4 +204,73 @@ mod tests {
             "dh failed - different AD computed"
         );
     }
+
+    #[test]
+    fn test_hybrid_x2dh_protocol() {
+        let bob_id_key_pair = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+        let bob_pre_key_private = x25519_dalek::StaticSecret::new(rand_core::OsRng);
+        let bob_kem_key_pair = ml_kem::generate_key_pair();
+
+        let input_alice = ProtocolInputAlice {
+            ikb: bob_id_key_pair.public,
+            pkb: (&bob_pre_key_private).into(),
+            b_bundle_id: 0,
+            kem_pkb: Some(bob_kem_key_pair.encapsulation_key.clone()),
+        };
+        let output_alice = execute_alice(&input_alice).unwrap();
+
+        let mut input_bob = ProtocolInputBob {
+            eka: output_alice.eka,
+            kem_ct: output_alice.kem_ct.clone(),
+            ikb_pair: bob_id_key_pair,
+            pkb_private: bob_pre_key_private,
+            b_bundle_id: 0,
+            kem_pkb_private: Some(bob_kem_key_pair.decapsulation_key),
+        };
+        let output_bob = execute_bob(&input_bob).unwrap();
+
+        assert_eq!(output_bob.shared_secret, output_alice.shared_secret);
+        assert_eq!(output_bob.ad.to_vec(), output_alice.ad.to_vec());
+
+        // the kem shared secret is mixed into the shared secret - a tampered ciphertext results in a different one
+        let mut kem_ct = output_alice.kem_ct.unwrap();
+        kem_ct[0] ^= 1;
+        input_bob.kem_ct = Some(kem_ct);
+        assert_ne!(
+            execute_bob(&input_bob).unwrap().shared_secret,
+            output_alice.shared_secret
+        );
+    }
+
+    #[test]
+    fn test_hybrid_downgrade_rejected() {
+        let bob_id_key_pair = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
+        let bob_pre_key_private = x25519_dalek::StaticSecret::new(rand_core::OsRng);
+        let bob_kem_key_pair = ml_kem::generate_key_pair();
+
+        // alice ignores bob's kem key and executes classic x2dh
+        let input_alice = ProtocolInputAlice {
+            ikb: bob_id_key_pair.public,
+            pkb: (&bob_pre_key_private).into(),
+            b_bundle_id: 0,
+            kem_pkb: None,
+        };
+        let output_alice = execute_alice(&input_alice).unwrap();
+
+        let mut input_bob = ProtocolInputBob {
+            eka: output_alice.eka,
+            kem_ct: None,
+            ikb_pair: bob_id_key_pair,
+            pkb_private: bob_pre_key_private,
+            b_bundle_id: 0,
+            kem_pkb_private: Some(bob_kem_key_pair.decapsulation_key),
+        };
+        assert!(execute_bob(&input_bob).is_err());
+
+        // a kem ciphertext for a classic pre-key is rejected as well
+        let (kem_ct, _) = ml_kem::encapsulate(&bob_kem_key_pair.encapsulation_key).unwrap();
+        input_bob.kem_pkb_private = None;
+        input_bob.kem_ct = Some(kem_ct);
+        assert!(execute_bob(&input_bob).is_err());
+    }
 }
@@ -8,11 +8,13 @@ use crate::features::api_service::CryptoMailGrpcService;
 use crate::model::api::cryptomail_admin_api_service_server::CryptomailAdminApiServiceServer;
 use crate::model::api::cryptomail_api_service_server::CryptomailApiServiceServer;
 use anyhow::Result;
+use base::grpc_tls::server_tls_config;
 use base::server_config_service::{
     ServerConfigService, GRPC_ADMIN_PORT_CONFIG_KEY, GRPC_HOST_CONFIG_KEY,
     GRPC_SERVER_PORT_CONFIG_KEY, PEER_NAME_CONFIG_KEY, START_GRPC_SERVER_ADMIN_SERVICE_CONFIG_KEY,
+    TLS_CERT_FILE_CONFIG_KEY, TLS_KEY_FILE_CONFIG_KEY,
 };
-use tonic::transport::Server;
+use tonic::transport::{Server, ServerTlsConfig};
 use xactor::*;
 
 #[message(result = "Result<()>")]
@@ -45,6 +47,12 @@ impl Handler<StartGrpcServerMessage> for CryptoMailService {
             server_name, grpc_server_address
         );
 
+        let mut builder = Server::builder();
+        if let Some(tls_config) = CryptoMailService::tls_config().await? {
+            info!("api grpc server using tls");
+            builder = builder.tls_config(tls_config)?;
+        }
+
         // todo: add health service
 
         tokio::task::spawn(async move {
@@ -64,7 +72,7 @@ impl Handler<StartGrpcServerMessage> for CryptoMailService {
                 //.allow_origins(vec!["127.0.0.1"]) // e.g. can limit to a reverse proxy that adds ssl!
                 .enable(service);
 
-            let res = Server::builder()
+            let res = builder
                 .accept_http1(true)
                 .add_service(service)
                 .add_service(health_service)
@@ -96,6 +104,12 @@ impl Handler<StartGrpcServerMessage> for CryptoMailService {
                 server_name, grpc_admin_address
             );
 
+            let mut builder = Server::builder();
+            if let Some(tls_config) = CryptoMailService::tls_config().await? {
+                info!("admin grpc server using tls");
+                builder = builder.tls_config(tls_config)?;
+            }
+
             // todo: add health service
 
             tokio::task::spawn(async move {
@@ -110,7 +124,7 @@ impl Handler<StartGrpcServerMessage> for CryptoMailService {
                     .allow_origins(vec!["127.0.0.1"]) // allow only local origin for admin api over http
                     .enable(service);
 
-                let res = Server::builder()
+                let res = builder
                     .accept_http1(true)
                     .add_service(service)
                     .serve(grpc_admin_address)
@@ -130,3 +144,17 @@ impl Handler<StartGrpcServerMessage> for CryptoMailService {
         Ok(())
     }
 }
+
+impl CryptoMailService {
+    /// Returns the grpc servers tls config. None when tls cert and key files are not configured.
+    async fn tls_config() -> Result<Option<ServerTlsConfig>> {
+        let cert_file = ServerConfigService::get(TLS_CERT_FILE_CONFIG_KEY.into()).await?;
+        let key_file = ServerConfigService::get(TLS_KEY_FILE_CONFIG_KEY.into()).await?;
+        match cert_file.zip(key_file) {
+            Some((cert_file, key_file)) => {
+                Ok(Some(server_tls_config(&cert_file, &key_file).await?))
+            }
+            None => Ok(None),
+        }
+    }
+}
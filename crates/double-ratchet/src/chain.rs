@@ -39,6 +39,10 @@ impl<K: Kdf> Chain<K> {
         }
     }
 
+    pub(crate) fn is_running(&self) -> bool {
+        matches!(self.state, Some(ChainState::Run(_)))
+    }
+
     // Advance a chain in the running state
     pub(crate) fn advance(&mut self, input: K::Input) -> Result<(u32, K::Output)> {
         match self.state {
@@ -11,7 +11,19 @@ use anyhow::{anyhow, bail, Result};
 use crate::kdf::{ChainKdf, RootKdf};
 use base::hex_utils::short_hex_string;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+
+/// Max number of previous receiving chains kept. The other party may ratchet several times before
+/// messages it sent in an older sending chain arrive, e.g. when it pushes messages while responding to requests
+const MAX_PREV_RECEIVING_CHAINS: usize = 5;
+
+/// A previous receiving chain with its skipped keys and the other party's public ratchet key of the chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PrevReceivingChain {
+    peer_key: [u8; 32],
+    chain: Chain<ChainKdf>,
+    keys: HashMap<u32, MessageKey>,
+}
 
 /// Chains is the main data structure used by the DR algorithm with another party.
 /// Chains includes 3 chains - Root, Sending and Receiving.
@@ -22,6 +34,91 @@ pub(crate) struct Chains {
     receiving: Chain<ChainKdf>,
     // a set of receiving key that were skipped in the current receiving chain
     receiving_keys: HashMap<u32, MessageKey>,
+    // previous receiving chains, newest first - for messages the other party sent before it
+    // received our last ratchet keys, which arrive after messages from its newer sending chains
+    prev_receiving: VecDeque<PrevReceivingChain>,
+}
+
+/// Chains as stored by the original session format (format version 0)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ChainsV0 {
+    root: Chain<RootKdf>,
+    sending: Chain<ChainKdf>,
+    receiving: Chain<ChainKdf>,
+    receiving_keys: HashMap<u32, MessageKey>,
+}
+
+/// Chains as stored by format version 1 - with one previous receiving chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ChainsV1 {
+    root: Chain<RootKdf>,
+    sending: Chain<ChainKdf>,
+    receiving: Chain<ChainKdf>,
+    receiving_keys: HashMap<u32, MessageKey>,
+    prev_receiving: Option<Chain<ChainKdf>>,
+    prev_receiving_keys: HashMap<u32, MessageKey>,
+}
+
+impl ChainsV1 {
+    /// Returns the chains in the current format. prev_peer_key is the other party's ratchet key
+    /// of the previous receiving chain
+    pub(crate) fn into_chains(self, prev_peer_key: Option<[u8; 32]>) -> Chains {
+        let mut prev_receiving = VecDeque::new();
+        if let (Some(peer_key), Some(chain)) = (prev_peer_key, self.prev_receiving) {
+            prev_receiving.push_back(PrevReceivingChain {
+                peer_key,
+                chain,
+                keys: self.prev_receiving_keys,
+            });
+        }
+
+        Chains {
+            root: self.root,
+            sending: self.sending,
+            receiving: self.receiving,
+            receiving_keys: self.receiving_keys,
+            prev_receiving,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Chains {
+    /// Returns the chains in the original session format, without the previous receiving chains
+    pub(crate) fn to_v0(&self) -> ChainsV0 {
+        ChainsV0 {
+            root: self.root.clone(),
+            sending: self.sending,
+            receiving: self.receiving,
+            receiving_keys: self.receiving_keys.clone(),
+        }
+    }
+
+    /// Returns the chains in format version 1 with the newest previous receiving chain and its peer key
+    pub(crate) fn to_v1(&self) -> (ChainsV1, Option<[u8; 32]>) {
+        let prev = self.prev_receiving.front();
+        let chains = ChainsV1 {
+            root: self.root.clone(),
+            sending: self.sending,
+            receiving: self.receiving,
+            receiving_keys: self.receiving_keys.clone(),
+            prev_receiving: prev.map(|p| p.chain),
+            prev_receiving_keys: prev.map(|p| p.keys.clone()).unwrap_or_default(),
+        };
+        (chains, prev.map(|p| p.peer_key))
+    }
+}
+
+impl From<ChainsV0> for Chains {
+    fn from(chains: ChainsV0) -> Self {
+        Chains {
+            root: chains.root,
+            sending: chains.sending,
+            receiving: chains.receiving,
+            receiving_keys: chains.receiving_keys,
+            prev_receiving: VecDeque::new(),
+        }
+    }
 }
 
 impl Chains {
@@ -42,6 +139,7 @@ impl Chains {
             sending,
             receiving,
             receiving_keys: HashMap::new(),
+            prev_receiving: VecDeque::new(),
         }
     }
 
@@ -60,11 +158,28 @@ impl Chains {
     }
 
     /// Advance the receiving chain and the root chain.
-    /// pn is PN in the DR paper - the number of keys in the previous sending chain
-    pub fn next_receiving_chain(&mut self, key: SessionKey, pn: u32) -> Result<()> {
+    /// pn is PN in the DR paper - the number of keys in the previous sending chain.
+    /// peer_key is the other party's public ratchet key of the current receiving chain, if any
+    pub fn next_receiving_chain(
+        &mut self,
+        key: SessionKey,
+        pn: u32,
+        peer_key: Option<[u8; 32]>,
+    ) -> Result<()> {
         // this will store any skipped keys in the previous chain (see section 2.6 in the DR paper)
         let _ = self.get_receiving_key(pn);
 
+        // keep the previous chain as pn is not always provided by the other party
+        let keys = std::mem::take(&mut self.receiving_keys);
+        if let Some(peer_key) = peer_key {
+            self.prev_receiving.push_front(PrevReceivingChain {
+                peer_key,
+                chain: self.receiving,
+                keys,
+            });
+            self.prev_receiving.truncate(MAX_PREV_RECEIVING_CHAINS);
+        }
+
         // Advance the root chain...
         let key = self
             .root
@@ -78,6 +193,11 @@ impl Chains {
         Ok(())
     }
 
+    /// Returns true when we have a receiving chain - after the first ratchet step
+    pub fn has_receiving_chain(&self) -> bool {
+        self.receiving.is_running()
+    }
+
     /// Get a next sending key from the current sending chain.
     /// Calling this advances the sending chain but not the root chain.
     pub fn next_sending_key(&mut self) -> Result<(u32, MessageKey)> {
@@ -93,20 +213,40 @@ impl Chains {
     /// Get receiving key at a specific index - store all skipped keys if any in this session
     /// todo: this needs to be heavily tested as this code is what enables out of order messages decryption
     pub fn get_receiving_key(&mut self, index: u32) -> Result<MessageKey> {
+        Chains::chain_receiving_key(&mut self.receiving, &mut self.receiving_keys, index)
+
+        // todo: figure out when it is okay to remove old keys from the hash
+        // currently, it grows with the session - there should be a way to remove old unused keys
+        // maybe in a chain previous to the previous chain as these keys can't be used anymore?
+    }
+
+    /// Get receiving key at a specific index of the previous receiving chain of the other party's ratchet key.
+    /// Returns None when we don't have a previous receiving chain for the key
+    pub fn get_prev_receiving_key(
+        &mut self,
+        peer_key: &[u8; 32],
+        index: u32,
+    ) -> Option<Result<MessageKey>> {
+        self.prev_receiving
+            .iter_mut()
+            .find(|prev| prev.peer_key == *peer_key)
+            .map(|prev| Chains::chain_receiving_key(&mut prev.chain, &mut prev.keys, index))
+    }
+
+    /// Get receiving key at a specific index of a receiving chain - store all skipped keys in the chain's keys
+    fn chain_receiving_key(
+        chain: &mut Chain<ChainKdf>,
+        keys: &mut HashMap<u32, MessageKey>,
+        index: u32,
+    ) -> Result<MessageKey> {
+        // query is for a skipped key we should have - return a clone of it as it may be queried again
+        if let Some(skipped_key) = keys.get(&index) {
+            return Ok(*skipped_key);
+        }
+
         loop {
             // get next receiving key and compare with index
-            let next_key = self.receiving.advance(())?;
-
-            if index < next_key.0 {
-                // query is for a skipped key we should have - it should be in our store
-                if let Some(skipped_key) = self.receiving_keys.get(&index) {
-                    // return a clone of store key as it may be queried again
-                    return Ok(*skipped_key);
-                } else {
-                    // we should have this key but we don't
-                    bail!("could not find old receiving key")
-                }
-            }
+            let next_key = chain.advance(())?;
 
             if next_key.0 == index {
                 // we have the requested key and it is going to be used for decryption
@@ -115,11 +255,98 @@ impl Chains {
 
             // save the key in this session so it can be used later in a data structure
             // before getting next key in a new iteration of this loop
-            self.receiving_keys.insert(next_key.0, next_key.1);
+            keys.insert(next_key.0, next_key.1);
+
+            if index < next_key.0 {
+                // we should have this key but we don't
+                bail!("could not find old receiving key")
+            }
         }
+    }
+}
 
-        // todo: figure out when it is okay to remove old keys from the hash
-        // currently, it grows with the session - there should be a way to remove old unused keys
-        // maybe in a chain previous to the previous chain as these keys can't be used anymore?
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Chains of a sender and a receiver which advance their root chains together
+    fn new_chains() -> (Chains, Chains) {
+        let chains = Chains::init(
+            SessionKey::from([1u8; 32].as_ref()),
+            ChainKey::from([2u8; 32].as_ref()),
+        );
+        (chains.clone(), chains)
+    }
+
+    #[test]
+    fn test_prev_receiving_chains() {
+        let (mut sender, mut receiver) = new_chains();
+
+        // the sender sends 2 messages in each sending chain - the receiver only gets the first one
+        // before the next ratchet step
+        let mut late_keys = vec![];
+        let mut peer_key = None;
+        for i in 0..(MAX_PREV_RECEIVING_CHAINS + 2) as u8 {
+            let key = SessionKey::from([10 + i; 32].as_ref());
+            sender.next_sending_chain(key).unwrap();
+            receiver.next_receiving_chain(key, 0, peer_key).unwrap();
+            peer_key = Some([100 + i; 32]);
+
+            let first = sender.next_sending_key().unwrap();
+            let late = sender.next_sending_key().unwrap();
+            let receiving_key = receiver.get_receiving_key(first.0).unwrap();
+            assert_eq!(receiving_key.as_bytes(), first.1.as_bytes());
+            late_keys.push((peer_key.unwrap(), late));
+        }
+
+        assert_eq!(receiver.prev_receiving.len(), MAX_PREV_RECEIVING_CHAINS);
+
+        // the current receiving chain isn't a previous chain
+        let (current, prev) = late_keys.split_last().unwrap();
+        assert!(receiver
+            .get_prev_receiving_key(&current.0, current.1 .0)
+            .is_none());
+
+        for (i, (peer_key, late)) in prev.iter().enumerate() {
+            match receiver.get_prev_receiving_key(peer_key, late.0) {
+                // the oldest chain was dropped
+                None => assert_eq!(i, 0),
+                Some(key) => assert_eq!(key.unwrap().as_bytes(), late.1.as_bytes()),
+            }
+        }
+
+        // a late message's key may be queried again
+        let (peer_key, late) = &prev[1];
+        let key = receiver.get_prev_receiving_key(peer_key, late.0).unwrap();
+        assert_eq!(key.unwrap().as_bytes(), late.1.as_bytes());
+    }
+
+    #[test]
+    fn test_prev_receiving_chains_skipped_keys() {
+        let (mut sender, mut receiver) = new_chains();
+
+        let key = SessionKey::from([10u8; 32].as_ref());
+        sender.next_sending_chain(key).unwrap();
+        receiver.next_receiving_chain(key, 0, None).unwrap();
+        let sent: Vec<_> = (0..4).map(|_| sender.next_sending_key().unwrap()).collect();
+
+        // the receiver only gets the last message before it ratchets
+        let receiving_key = receiver.get_receiving_key(sent[3].0).unwrap();
+        assert_eq!(receiving_key.as_bytes(), sent[3].1.as_bytes());
+
+        let key = SessionKey::from([11u8; 32].as_ref());
+        sender.next_sending_chain(key).unwrap();
+        receiver
+            .next_receiving_chain(key, 0, Some([1u8; 32]))
+            .unwrap();
+
+        // skipped messages of the previous chain arrive out of order after the ratchet step
+        for (index, sent_key) in sent[..3].iter().rev() {
+            let receiving_key = receiver
+                .get_prev_receiving_key(&[1u8; 32], *index)
+                .unwrap()
+                .unwrap();
+            assert_eq!(receiving_key.as_bytes(), sent_key.as_bytes());
+        }
     }
 }
@@ -3,11 +3,11 @@
 //
 
 use crate::chain_key::ChainKey;
-use crate::chains::Chains;
+use crate::chains::{Chains, ChainsV0, ChainsV1};
 use crate::message_key::MessageKey;
 use crate::session_key::SessionKey;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use bytes::Bytes;
 use rand_core::{CryptoRng, OsRng, RngCore};
 
@@ -16,6 +16,15 @@ use std::fmt;
 use std::fmt::{Debug, Formatter};
 use x25519_dalek::{PublicKey, StaticSecret};
 
+/// Version of the serialized session format written by DoubleRatchet::to_bytes().
+/// - 0: the original format.
+/// - 1: the other party's current and previous ratchet keys with one previous receiving chain.
+/// - 2: previous receiving chains by the other party's ratchet key.
+///
+/// Sessions stored in versions 0 to 2 before the format was versioned are loaded with
+/// DoubleRatchet::from_unversioned_bytes()
+pub const FORMAT_VERSION: u8 = 2;
+
 /// Implementation of the DR protocol between 2 parties.
 /// Initialized externally. Has no persistence.
 /// The methods are not concurrency safe. They are designed to use by a client
@@ -166,6 +175,52 @@ pub struct DoubleRatchet {
     key: Option<StaticSecret>, // local side ratchet key. Public can be extracted from private
     pub ad: Option<Bytes>, // AD - see DR algo and X2DH - we need to store the ad generated between alice and bob in the key exchange phase that generated this session
     pub session_id: u64, // unique session id created by the session initiator and stored by the 2 parties.
+    peer_key: Option<[u8; 32]>, // the other party's current public ratchet key
+}
+
+/// A session stored in the original format (format version 0)
+#[derive(Deserialize)]
+struct DoubleRatchetV0 {
+    chains: ChainsV0,
+    key: Option<StaticSecret>,
+    ad: Option<Bytes>,
+    session_id: u64,
+}
+
+impl From<DoubleRatchetV0> for DoubleRatchet {
+    fn from(dr: DoubleRatchetV0) -> Self {
+        // the original format doesn't have the other party's ratchet key - it is learned from its next message
+        DoubleRatchet {
+            chains: dr.chains.into(),
+            key: dr.key,
+            ad: dr.ad,
+            session_id: dr.session_id,
+            peer_key: None,
+        }
+    }
+}
+
+/// A session stored in format version 1 - with one previous receiving chain
+#[derive(Deserialize)]
+struct DoubleRatchetV1 {
+    chains: ChainsV1,
+    key: Option<StaticSecret>,
+    ad: Option<Bytes>,
+    session_id: u64,
+    peer_key: Option<[u8; 32]>,
+    prev_peer_key: Option<[u8; 32]>,
+}
+
+impl From<DoubleRatchetV1> for DoubleRatchet {
+    fn from(dr: DoubleRatchetV1) -> Self {
+        DoubleRatchet {
+            chains: dr.chains.into_chains(dr.prev_peer_key),
+            key: dr.key,
+            ad: dr.ad,
+            session_id: dr.session_id,
+            peer_key: dr.peer_key,
+        }
+    }
 }
 
 impl Debug for DoubleRatchet {
@@ -191,6 +246,7 @@ impl DoubleRatchet {
             key: None,
             ad: Some(ad),
             session_id: OsRng.next_u64(),
+            peer_key: None,
         };
 
         // Initialize the dr session by doing a half-ratchet
@@ -215,9 +271,49 @@ impl DoubleRatchet {
             key: Some(key),
             ad: Some(ad),
             session_id,
+            peer_key: None,
         }
     }
 
+    /// Serialize the session to the current format version
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut data = vec![FORMAT_VERSION];
+        bincode::serialize_into(&mut data, self)
+            .map_err(|e| anyhow!("failed to serialize dr session: {:?}", e))?;
+        Ok(data)
+    }
+
+    /// Deserialize a session serialized with to_bytes()
+    pub fn from_bytes(data: &[u8]) -> Result<DoubleRatchet> {
+        match data.split_first() {
+            Some((&FORMAT_VERSION, session)) => bincode::deserialize(session)
+                .map_err(|e| anyhow!("invalid dr session data: {:?}", e)),
+            Some((version, _)) => bail!("unsupported dr session format version {}", version),
+            None => bail!("empty dr session data"),
+        }
+    }
+
+    /// Deserialize a session which was stored without a format version, migrating it to the current format.
+    /// Trailing data is rejected so callers may try the versions a record may have been stored in
+    pub fn from_unversioned_bytes(data: &[u8], version: u8) -> Result<DoubleRatchet> {
+        use bincode::Options;
+        let options = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .reject_trailing_bytes();
+
+        let res = match version {
+            0 => options
+                .deserialize::<DoubleRatchetV0>(data)
+                .map(|dr| dr.into()),
+            1 => options
+                .deserialize::<DoubleRatchetV1>(data)
+                .map(|dr| dr.into()),
+            2 => options.deserialize::<DoubleRatchet>(data),
+            _ => bail!("unsupported unversioned dr session format {}", version),
+        };
+        res.map_err(|e| anyhow!("invalid dr session data: {:?}", e))
+    }
+
     pub fn get_ad(&self) -> Result<&[u8]> {
         let ad = self.ad.as_ref().ok_or_else(|| anyhow!("missing ad"))?;
         Ok(ad.as_ref())
@@ -256,7 +352,8 @@ impl DoubleRatchet {
         }
 
         let sk = self.diffie_hellman(peer_pub_ratchet_key);
-        self.chains.next_receiving_chain(sk, pn)?;
+        self.chains.next_receiving_chain(sk, pn, self.peer_key)?;
+        self.peer_key = Some(peer_pub_ratchet_key.to_bytes());
 
         self.generate_keypair(csprng);
 
@@ -275,6 +372,54 @@ impl DoubleRatchet {
         self.chains.get_receiving_key(index)
     }
 
+    /// Get the receiving key of a message from the other party by the public ratchet key, previous
+    /// sending chain count and index in the message's header.
+    /// Performs a full ratchet step when the message has a new ratchet key. Supports out of order
+    /// messages sent in the other party's previous sending chains.
+    /// A message's header is only authenticated by decrypting the message, so the key is derived on a
+    /// copy of this session which is returned with it. Callers should replace this session with the copy
+    /// only after they decrypted the message with the key, so a forged header can't change the session.
+    pub fn get_message_receiving_key<R: CryptoRng + RngCore>(
+        &self,
+        csprng: &mut R,
+        peer_pub_ratchet_key: &PublicKey,
+        pn: u32,
+        index: u32,
+    ) -> Result<(DoubleRatchet, MessageKey)> {
+        let mut dr = self.clone();
+        let key = dr.next_message_receiving_key(csprng, peer_pub_ratchet_key, pn, index)?;
+        Ok((dr, key))
+    }
+
+    fn next_message_receiving_key<R: CryptoRng + RngCore>(
+        &mut self,
+        csprng: &mut R,
+        peer_pub_ratchet_key: &PublicKey,
+        pn: u32,
+        index: u32,
+    ) -> Result<MessageKey> {
+        let key = peer_pub_ratchet_key.to_bytes();
+        if self.peer_key == Some(key) {
+            return self.chains.get_receiving_key(index);
+        }
+
+        if self.peer_key.is_none() && self.chains.has_receiving_chain() && index != 0 {
+            // a session migrated from the original format which doesn't have the other party's
+            // ratchet key. Sessions in that format ratchet on the first message of each sending chain
+            // so this message is from the current receiving chain. The key in its header isn't used
+            // for the message so it isn't authenticated by it - the other party's key is learned
+            // from its next ratchet step
+            return self.chains.get_receiving_key(index);
+        }
+
+        if let Some(receiving_key) = self.chains.get_prev_receiving_key(&key, index) {
+            return receiving_key;
+        }
+
+        self.ratchet(csprng, peer_pub_ratchet_key, pn)?;
+        self.chains.get_receiving_key(index)
+    }
+
     /// Performs diffie hellman using a peer's ratchet public key and our ratchet private key
     /// and return the shared secret output.
     fn diffie_hellman(&self, peer_pub_key: &PublicKey) -> SessionKey {
@@ -305,3 +450,272 @@ impl DoubleRatchet {
         self.chains.next_sending_chain(sk)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get a message's receiving key and keep the session's updates as the message was decrypted with it
+    fn receive(dr: &mut DoubleRatchet, peer_key: &PublicKey, pn: u32, index: u32) -> MessageKey {
+        let (next_dr, key) = dr
+            .get_message_receiving_key(&mut OsRng, peer_key, pn, index)
+            .unwrap();
+        *dr = next_dr;
+        key
+    }
+
+    /// Returns a new session between alice and bob after alice's first message to bob
+    fn new_sessions() -> (DoubleRatchet, DoubleRatchet) {
+        let mut shared_secret = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_secret);
+        let root_chain_key = ChainKey::from(shared_secret.as_ref());
+        let ad = Bytes::from(vec![7u8; 64]);
+        let session_key = SessionKey::from([3u8; 32].as_ref());
+
+        let bob_dr_private_key = StaticSecret::new(OsRng);
+        let bob_dr_public_key: PublicKey = (&bob_dr_private_key).into();
+
+        let mut alice = DoubleRatchet::new_with_peer(
+            session_key,
+            root_chain_key,
+            &mut OsRng,
+            &bob_dr_public_key,
+            ad.clone(),
+        )
+        .unwrap();
+
+        let mut bob = DoubleRatchet::new_with_keys(
+            session_key,
+            root_chain_key,
+            bob_dr_private_key,
+            ad,
+            alice.session_id,
+        );
+
+        let alice_send_key = alice.next_sending_key().unwrap();
+        let bob_receive_key = receive(
+            &mut bob,
+            &alice.get_public_key().unwrap(),
+            0,
+            alice_send_key.0,
+        );
+        assert_eq!(alice_send_key.1.as_bytes(), bob_receive_key.as_bytes());
+
+        (alice, bob)
+    }
+
+    #[test]
+    fn test_versioned_round_trip() {
+        let (alice, mut bob) = new_sessions();
+        let data = alice.to_bytes().unwrap();
+        assert_eq!(data[0], FORMAT_VERSION);
+
+        let mut alice = DoubleRatchet::from_bytes(&data).unwrap();
+        assert_eq!(alice.session_id, bob.session_id);
+
+        let bob_send_key = bob.next_sending_key().unwrap();
+        let alice_receive_key = receive(
+            &mut alice,
+            &bob.get_public_key().unwrap(),
+            0,
+            bob_send_key.0,
+        );
+        assert_eq!(bob_send_key.1.as_bytes(), alice_receive_key.as_bytes());
+
+        let mut unknown_version = data;
+        unknown_version[0] = FORMAT_VERSION + 1;
+        assert!(DoubleRatchet::from_bytes(&unknown_version).is_err());
+        assert!(DoubleRatchet::from_bytes(&[]).is_err());
+    }
+
+    /// A session stored in the original format is migrated and keeps working in the current receiving chain
+    /// and across the next ratchet steps
+    #[test]
+    fn test_migrate_v0_session() {
+        let (mut alice, mut bob) = new_sessions();
+
+        // bob sends 2 messages in his first sending chain - alice only gets the first one before she's restarted
+        let bob_pub_key = bob.get_public_key().unwrap();
+        let bob_first_key = bob.next_sending_key().unwrap();
+        let bob_second_key = bob.next_sending_key().unwrap();
+        let alice_receive_key = receive(&mut alice, &bob_pub_key, 0, bob_first_key.0);
+        assert_eq!(bob_first_key.1.as_bytes(), alice_receive_key.as_bytes());
+
+        let v0_data = bincode::serialize(&(
+            alice.chains.to_v0(),
+            &alice.key,
+            &alice.ad,
+            alice.session_id,
+        ))
+        .unwrap();
+        let mut alice = DoubleRatchet::from_unversioned_bytes(&v0_data, 0).unwrap();
+        assert!(alice.peer_key.is_none());
+        assert!(DoubleRatchet::from_unversioned_bytes(&v0_data, 1).is_err());
+        assert!(DoubleRatchet::from_unversioned_bytes(&v0_data, 7).is_err());
+
+        // bob's next message in the same chain is decrypted in alice's current receiving chain.
+        // Its ratchet key isn't authenticated by the message so alice doesn't learn it
+        let alice_receive_key = receive(&mut alice, &bob_pub_key, 0, bob_second_key.0);
+        assert_eq!(bob_second_key.1.as_bytes(), alice_receive_key.as_bytes());
+        assert!(alice.peer_key.is_none());
+
+        // alice replies and both parties ratchet
+        let alice_send_key = alice.next_sending_key().unwrap();
+        let bob_receive_key = receive(
+            &mut bob,
+            &alice.get_public_key().unwrap(),
+            bob_second_key.0 + 1,
+            alice_send_key.0,
+        );
+        assert_eq!(alice_send_key.1.as_bytes(), bob_receive_key.as_bytes());
+
+        let bob_send_key = bob.next_sending_key().unwrap();
+        let alice_receive_key = receive(
+            &mut alice,
+            &bob.get_public_key().unwrap(),
+            0,
+            bob_send_key.0,
+        );
+        assert_eq!(bob_send_key.1.as_bytes(), alice_receive_key.as_bytes());
+
+        // alice learned bob's ratchet key from his ratchet step
+        assert_eq!(
+            alice.peer_key,
+            Some(bob.get_public_key().unwrap().to_bytes())
+        );
+    }
+
+    /// Sessions stored in format version 1 with one previous receiving chain, and in version 2 before
+    /// the format was versioned, are migrated and keep decrypting late messages from the previous chain
+    #[test]
+    fn test_migrate_v1_and_unversioned_v2_sessions() {
+        let (mut alice, mut bob) = new_sessions();
+
+        // bob sends 2 messages. Alice only gets the first one before she replies
+        let bob_pub_key = bob.get_public_key().unwrap();
+        let bob_first_key = bob.next_sending_key().unwrap();
+        let bob_late_key = bob.next_sending_key().unwrap();
+        receive(&mut alice, &bob_pub_key, 0, bob_first_key.0);
+
+        let alice_send_key = alice.next_sending_key().unwrap();
+        receive(
+            &mut bob,
+            &alice.get_public_key().unwrap(),
+            0,
+            alice_send_key.0,
+        );
+
+        // bob's message in his new chain moves alice's receiving chain with his late message to her previous chains
+        let bob_new_key = bob.next_sending_key().unwrap();
+        receive(&mut alice, &bob.get_public_key().unwrap(), 0, bob_new_key.0);
+
+        let (chains_v1, prev_peer_key) = alice.chains.to_v1();
+        assert_eq!(prev_peer_key, Some(bob_pub_key.to_bytes()));
+        let v1_data = bincode::serialize(&(
+            chains_v1,
+            &alice.key,
+            &alice.ad,
+            alice.session_id,
+            alice.peer_key,
+            prev_peer_key,
+        ))
+        .unwrap();
+        let v2_data = bincode::serialize(&alice).unwrap();
+
+        // a record is only loaded in the version it was stored in
+        assert!(DoubleRatchet::from_unversioned_bytes(&v1_data, 2).is_err());
+        assert!(DoubleRatchet::from_unversioned_bytes(&v2_data, 1).is_err());
+
+        for (data, version) in [(v1_data, 1), (v2_data, 2)] {
+            let mut alice = DoubleRatchet::from_unversioned_bytes(&data, version).unwrap();
+            let alice_receive_key = receive(&mut alice, &bob_pub_key, 0, bob_late_key.0);
+            assert_eq!(bob_late_key.1.as_bytes(), alice_receive_key.as_bytes());
+        }
+    }
+
+    /// A message with a forged header fails decryption with the key derived from it.
+    /// Dropping the session copy returned with the key leaves the session usable
+    #[test]
+    fn test_forged_header() {
+        let (mut alice, mut bob) = new_sessions();
+        let bob_pub_key = bob.get_public_key().unwrap();
+        let bob_first_key = bob.next_sending_key().unwrap();
+        let bob_second_key = bob.next_sending_key().unwrap();
+        receive(&mut alice, &bob_pub_key, 0, bob_first_key.0);
+
+        let forged_key: PublicKey = (&StaticSecret::new(OsRng)).into();
+        let session = alice.to_bytes().unwrap();
+
+        // a new ratchet key, a previous chain count and indexes in the current and in a new chain
+        for (pn, index) in [(0, 0), (1000, 0), (0, bob_second_key.0), (0, 7)] {
+            let (_, key) = alice
+                .get_message_receiving_key(&mut OsRng, &forged_key, pn, index)
+                .unwrap();
+            assert_ne!(key.as_bytes(), bob_second_key.1.as_bytes());
+            assert_eq!(alice.to_bytes().unwrap(), session);
+        }
+
+        // the forged messages didn't change the session
+        let alice_receive_key = receive(&mut alice, &bob_pub_key, 0, bob_second_key.0);
+        assert_eq!(bob_second_key.1.as_bytes(), alice_receive_key.as_bytes());
+
+        let alice_send_key = alice.next_sending_key().unwrap();
+        let bob_receive_key = receive(
+            &mut bob,
+            &alice.get_public_key().unwrap(),
+            bob_second_key.0 + 1,
+            alice_send_key.0,
+        );
+        assert_eq!(alice_send_key.1.as_bytes(), bob_receive_key.as_bytes());
+    }
+
+    /// A forged ratchet key in the header of a message in the current receiving chain of a session migrated from
+    /// the original format isn't taken as the other party's ratchet key
+    #[test]
+    fn test_forged_header_migrated_session() {
+        let (mut alice, mut bob) = new_sessions();
+        let bob_pub_key = bob.get_public_key().unwrap();
+        let bob_first_key = bob.next_sending_key().unwrap();
+        let bob_second_key = bob.next_sending_key().unwrap();
+        let bob_third_key = bob.next_sending_key().unwrap();
+        receive(&mut alice, &bob_pub_key, 0, bob_first_key.0);
+
+        let v0_data = bincode::serialize(&(
+            alice.chains.to_v0(),
+            &alice.key,
+            &alice.ad,
+            alice.session_id,
+        ))
+        .unwrap();
+        let mut alice = DoubleRatchet::from_unversioned_bytes(&v0_data, 0).unwrap();
+
+        // bob's message with a replaced ratchet key still decrypts in the current chain...
+        let forged_key: PublicKey = (&StaticSecret::new(OsRng)).into();
+        let alice_receive_key = receive(&mut alice, &forged_key, 0, bob_second_key.0);
+        assert_eq!(bob_second_key.1.as_bytes(), alice_receive_key.as_bytes());
+
+        // ...without making the forged key the other party's key
+        assert!(alice.peer_key.is_none());
+        let alice_receive_key = receive(&mut alice, &bob_pub_key, 0, bob_third_key.0);
+        assert_eq!(bob_third_key.1.as_bytes(), alice_receive_key.as_bytes());
+
+        // both parties keep ratcheting
+        let alice_send_key = alice.next_sending_key().unwrap();
+        let bob_receive_key = receive(
+            &mut bob,
+            &alice.get_public_key().unwrap(),
+            bob_third_key.0 + 1,
+            alice_send_key.0,
+        );
+        assert_eq!(alice_send_key.1.as_bytes(), bob_receive_key.as_bytes());
+
+        let bob_send_key = bob.next_sending_key().unwrap();
+        let alice_receive_key = receive(
+            &mut alice,
+            &bob.get_public_key().unwrap(),
+            0,
+            bob_send_key.0,
+        );
+        assert_eq!(bob_send_key.1.as_bytes(), alice_receive_key.as_bytes());
+    }
+}
@@ -7,10 +7,20 @@ use base::test_helpers::enable_logger;
 use bytes::Bytes;
 use double_ratchet::chain_key::ChainKey;
 use double_ratchet::dr::DoubleRatchet;
+use double_ratchet::message_key::MessageKey;
 use double_ratchet::session_key::SessionKey;
 use rand_core::{OsRng, RngCore};
 use x25519_dalek::{PublicKey, StaticSecret};
 
+/// Get a message's receiving key and keep the session's updates as the message was decrypted with it
+fn receive(dr: &mut DoubleRatchet, peer_key: &PublicKey, pn: u32, index: u32) -> MessageKey {
+    let (next_dr, key) = dr
+        .get_message_receiving_key(&mut OsRng, peer_key, pn, index)
+        .unwrap();
+    *dr = next_dr;
+    key
+}
+
 #[test]
 fn test_dr() {
     enable_logger();
@@ -98,3 +108,129 @@ fn test_dr() {
         "expected same message key"
     );
 }
+
+#[test]
+fn test_dr_out_of_order_messages() {
+    enable_logger();
+
+    let mut shared_secret = [0u8; 32];
+    OsRng.fill_bytes(&mut shared_secret);
+    let root_chain_key = ChainKey::from(shared_secret.as_ref());
+    let ad = Bytes::from(vec![7u8; 64]);
+    let mut shared_info = [0u8; 32];
+    OsRng.fill_bytes(&mut shared_info);
+    let session_key = SessionKey::from(shared_info.as_ref());
+
+    let bob_dr_private_key = StaticSecret::new(OsRng);
+    let bob_dr_public_key: PublicKey = (&bob_dr_private_key).into();
+
+    let mut alice_dr = DoubleRatchet::new_with_peer(
+        session_key,
+        root_chain_key,
+        &mut OsRng,
+        &bob_dr_public_key,
+        ad.clone(),
+    )
+    .unwrap();
+
+    let mut bob_dr = DoubleRatchet::new_with_keys(
+        session_key,
+        root_chain_key,
+        bob_dr_private_key,
+        ad,
+        alice_dr.session_id,
+    );
+
+    // alice's first message - bob ratchets with her new key
+    let alice_pub_dr_key = alice_dr.get_public_key().unwrap();
+    let alice_send_key = alice_dr.next_sending_key().unwrap();
+    let bob_receive_key = receive(&mut bob_dr, &alice_pub_dr_key, 0, alice_send_key.0);
+    assert_eq!(alice_send_key.1.as_bytes(), bob_receive_key.as_bytes());
+
+    // bob sends 2 messages. Alice only gets the first one before she sends a new message
+    let bob_pub_dr_key = bob_dr.get_public_key().unwrap();
+    let bob_first_key = bob_dr.next_sending_key().unwrap();
+    let bob_late_key = bob_dr.next_sending_key().unwrap();
+
+    let alice_receive_key = receive(&mut alice_dr, &bob_pub_dr_key, 0, bob_first_key.0);
+    assert_eq!(bob_first_key.1.as_bytes(), alice_receive_key.as_bytes());
+
+    // alice's message has her new ratchet key - bob ratchets and sends 2 messages in his new chain
+    let alice_pub_dr_key = alice_dr.get_public_key().unwrap();
+    let alice_send_key = alice_dr.next_sending_key().unwrap();
+    let bob_receive_key = receive(&mut bob_dr, &alice_pub_dr_key, 0, alice_send_key.0);
+    assert_eq!(alice_send_key.1.as_bytes(), bob_receive_key.as_bytes());
+
+    let bob_new_pub_dr_key = bob_dr.get_public_key().unwrap();
+    assert_ne!(bob_new_pub_dr_key.as_bytes(), bob_pub_dr_key.as_bytes());
+    let bob_new_keys = [
+        bob_dr.next_sending_key().unwrap(),
+        bob_dr.next_sending_key().unwrap(),
+    ];
+
+    // alice gets bob's messages in his new chain in reverse order...
+    for bob_key in bob_new_keys.iter().rev() {
+        let alice_receive_key = receive(&mut alice_dr, &bob_new_pub_dr_key, 0, bob_key.0);
+        assert_eq!(bob_key.1.as_bytes(), alice_receive_key.as_bytes());
+    }
+
+    // ...and only then his late message from his previous chain
+    let alice_receive_key = receive(&mut alice_dr, &bob_pub_dr_key, 0, bob_late_key.0);
+    assert_eq!(bob_late_key.1.as_bytes(), alice_receive_key.as_bytes());
+}
+
+#[test]
+fn test_dr_messages_late_by_several_ratchet_steps() {
+    enable_logger();
+
+    let mut shared_secret = [0u8; 32];
+    OsRng.fill_bytes(&mut shared_secret);
+    let root_chain_key = ChainKey::from(shared_secret.as_ref());
+    let ad = Bytes::from(vec![7u8; 64]);
+    let mut shared_info = [0u8; 32];
+    OsRng.fill_bytes(&mut shared_info);
+    let session_key = SessionKey::from(shared_info.as_ref());
+
+    let bob_dr_private_key = StaticSecret::new(OsRng);
+    let bob_dr_public_key: PublicKey = (&bob_dr_private_key).into();
+
+    let mut alice_dr = DoubleRatchet::new_with_peer(
+        session_key,
+        root_chain_key,
+        &mut OsRng,
+        &bob_dr_public_key,
+        ad.clone(),
+    )
+    .unwrap();
+
+    let mut bob_dr = DoubleRatchet::new_with_keys(
+        session_key,
+        root_chain_key,
+        bob_dr_private_key,
+        ad,
+        alice_dr.session_id,
+    );
+
+    // bob pushes a message to alice in each of his sending chains while alice's requests make him ratchet.
+    // Alice gets all of his pushed messages only after she got his responses
+    let mut late_messages = vec![];
+    for _ in 0..3 {
+        let alice_pub_dr_key = alice_dr.get_public_key().unwrap();
+        let alice_send_key = alice_dr.next_sending_key().unwrap();
+        let bob_receive_key = receive(&mut bob_dr, &alice_pub_dr_key, 0, alice_send_key.0);
+        assert_eq!(alice_send_key.1.as_bytes(), bob_receive_key.as_bytes());
+
+        let bob_pub_dr_key = bob_dr.get_public_key().unwrap();
+        let bob_late_key = bob_dr.next_sending_key().unwrap();
+        late_messages.push((bob_pub_dr_key, bob_late_key));
+
+        let bob_send_key = bob_dr.next_sending_key().unwrap();
+        let alice_receive_key = receive(&mut alice_dr, &bob_pub_dr_key, 0, bob_send_key.0);
+        assert_eq!(bob_send_key.1.as_bytes(), alice_receive_key.as_bytes());
+    }
+
+    for (bob_pub_dr_key, bob_late_key) in late_messages.iter() {
+        let alice_receive_key = receive(&mut alice_dr, bob_pub_dr_key, 0, bob_late_key.0);
+        assert_eq!(bob_late_key.1.as_bytes(), alice_receive_key.as_bytes());
+    }
+}
@@ -38,6 +38,7 @@ impl Playground {
             port,
             net_id,
             name: name.into(),
+            min_api_version: "".to_string(),
         };
 
         sleep(Duration::from_millis(2000)).await; // Wait for the grpc service to startup
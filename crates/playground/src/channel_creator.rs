@@ -22,6 +22,7 @@ impl Playground {
             .unwrap()
             .user_create_status_update_channel(UserCreateStatusUpdateChannelRequest {
                 channel_name: channel_name.to_string(),
+                subscription_fee: 0,
             })
             .await
             .unwrap()
@@ -0,0 +1,39 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::playground::Playground;
+use anyhow::{anyhow, Result};
+use base::snp::upsetter_simple_client::UserGetChannelHistoryRequest;
+
+impl Playground {
+    pub(crate) async fn channel_history(
+        &mut self,
+        client_name: &str,
+        channel_name: &str,
+        max_items: u32,
+    ) -> Result<()> {
+        let channel_bundle = self
+            .channels
+            .get(channel_name)
+            .ok_or_else(|| anyhow!("channel bundle not found"))?;
+        let channel_id = channel_bundle.get_channel_id()?;
+
+        let client = self
+            .clients
+            .get_mut(client_name)
+            .ok_or_else(|| anyhow!("unknown client"))?;
+
+        client
+            .user_get_channel_history(UserGetChannelHistoryRequest {
+                channel_id,
+                before: 0,
+                max_items,
+            })
+            .await
+            .map_err(|e| anyhow!(format!("error getting channel history: {:?}", e)))?;
+
+        println!("🖖 requested channel {} history", channel_name);
+        Ok(())
+    }
+}
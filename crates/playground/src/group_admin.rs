@@ -0,0 +1,124 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::playground::Playground;
+use anyhow::{anyhow, Result};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_core_types::GroupAdminAction;
+use base::snp::upsetter_simple_client::{UserGetGroupMembersRequest, UserGroupAdminRequest};
+
+impl Playground {
+    pub(crate) async fn group_admin(
+        &mut self,
+        client_name: &str,
+        channel_name: &str,
+        action: &str,
+        user_name: &str,
+    ) -> Result<()> {
+        let action = match action {
+            "remove" => GroupAdminAction::RemoveMember,
+            "ban" => GroupAdminAction::BanMember,
+            "unban" => GroupAdminAction::UnbanMember,
+            "add-admin" => GroupAdminAction::AddAdmin,
+            "remove-admin" => GroupAdminAction::RemoveAdmin,
+            "approve" => GroupAdminAction::ApproveMember,
+            "deny" => GroupAdminAction::DenyMember,
+            "block-replier" => GroupAdminAction::BlockReplier,
+            "unblock-replier" => GroupAdminAction::UnblockReplier,
+            _ => return Err(anyhow!("unknown group admin action")),
+        };
+
+        let user = self
+            .clients_bundles
+            .get(user_name)
+            .ok_or_else(|| anyhow!("unknown user client"))?
+            .get_client_entity()?;
+
+        let channel_bundle = self
+            .channels
+            .get(channel_name)
+            .ok_or_else(|| anyhow!("group bundle not found"))?;
+        let channel_id = channel_bundle.get_channel_id()?;
+
+        let client = self
+            .clients
+            .get_mut(client_name)
+            .ok_or_else(|| anyhow!("unknown client"))?;
+
+        client
+            .user_group_admin(UserGroupAdminRequest {
+                channel_id,
+                action: action as i32,
+                user_id: Some(user),
+            })
+            .await
+            .map_err(|e| anyhow!(format!("error changing group membership: {:?}", e)))?;
+
+        println!("🖖 {:?} {} in group {}", action, user_name, channel_name);
+        Ok(())
+    }
+
+    pub(crate) async fn group_members(
+        &mut self,
+        client_name: &str,
+        channel_name: &str,
+    ) -> Result<()> {
+        let channel_bundle = self
+            .channels
+            .get(channel_name)
+            .ok_or_else(|| anyhow!("group bundle not found"))?;
+        let channel_id = channel_bundle.get_channel_id()?;
+
+        let client = self
+            .clients
+            .get_mut(client_name)
+            .ok_or_else(|| anyhow!("unknown client"))?;
+
+        let resp = client
+            .user_get_group_members(UserGetGroupMembersRequest { channel_id })
+            .await
+            .map_err(|e| anyhow!(format!("error getting group members: {:?}", e)))?
+            .into_inner();
+
+        let members = resp
+            .members
+            .ok_or_else(|| anyhow!("no members bundle for this group"))?;
+
+        let name = |id: &[u8]| {
+            self.clients_bundles
+                .iter()
+                .find(|(_, b)| {
+                    b.get_client_entity()
+                        .map(|e| e.get_id().map(|i| i.as_slice() == id).unwrap_or(false))
+                        .unwrap_or(false)
+                })
+                .map(|(n, _)| n.clone())
+                .unwrap_or_else(|| short_hex_string(id))
+        };
+
+        for member in members.members.iter() {
+            let id = member.get_member_id()?;
+            let role = if members.is_creator(&id) {
+                " (creator)"
+            } else if members.is_admin(&id) {
+                " (admin)"
+            } else {
+                ""
+            };
+            println!("🖖 member: {}{}", name(&id), role);
+        }
+
+        for request in resp.pending_requests.iter() {
+            if let Some(user) = request.user.as_ref() {
+                println!("🖖 pending request: {}", name(user.get_id()?));
+            }
+        }
+
+        for replier in resp.blocked_repliers.iter() {
+            println!("🖖 blocked replier: {}", name(replier.get_id()?));
+        }
+
+        Ok(())
+    }
+}
@@ -7,7 +7,12 @@ use anyhow::{anyhow, Result};
 use base::snp::upsetter_simple_client::UserCreateGroupRequest;
 
 impl Playground {
-    pub(crate) async fn create_group(&mut self, client_name: &str, group_name: &str) -> Result<()> {
+    pub(crate) async fn create_group(
+        &mut self,
+        client_name: &str,
+        group_name: &str,
+        approve_members: bool,
+    ) -> Result<()> {
         let client = self.clients.get_mut(client_name);
 
         if client.is_none() {
@@ -18,6 +23,7 @@ impl Playground {
             .unwrap()
             .user_create_group(UserCreateGroupRequest {
                 group_name: group_name.to_string(),
+                approve_members,
             })
             .await
             .unwrap()
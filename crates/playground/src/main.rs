@@ -8,9 +8,11 @@ extern crate log;
 mod abc_magic_command;
 mod blockchain_service_commands;
 mod channel_creator;
+mod channel_history;
 mod channel_subscriber;
 mod channel_unsubscriber;
 mod client_commands;
+mod group_admin;
 mod group_creator;
 mod group_joiner;
 mod group_leaver;
@@ -29,6 +29,7 @@ impl Playground {
                 other_client_id: Some(other_entity),
                 user_text: text,
                 reply_to,
+                ttl: 0,
             })
             .await
         {
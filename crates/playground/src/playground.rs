@@ -183,9 +183,30 @@ impl Playground {
                             Err(anyhow!("expected reply id"))
                         }
                     }
-                    "group-create" => self.create_group(name, tokens[2]).await,
+                    "history" => {
+                        let max_items = match tokens.get(3) {
+                            Some(max_items) => max_items.parse::<u32>()?,
+                            None => 0,
+                        };
+                        self.channel_history(name, tokens[2], max_items).await
+                    }
+                    "group-create" => {
+                        let approve_members = tokens.get(3) == Some(&"approve-members");
+                        self.create_group(name, tokens[2], approve_members).await
+                    }
                     "group-join" => self.join_group(name, tokens[2]).await,
                     "group-leave" => self.leave_group(name, tokens[2]).await,
+                    "group-admin" => {
+                        if tokens.len() < 5 {
+                            Err(anyhow!(
+                                "missing arguments. Expected group name, action and client name."
+                            ))
+                        } else {
+                            self.group_admin(name, tokens[2], tokens[3], tokens[4])
+                                .await
+                        }
+                    }
+                    "group-members" => self.group_members(name, tokens[2]).await,
                     "group-message" => {
                         if tokens.len() < 3 {
                             Err(anyhow!("missing arguments. Expected group name and text."))
@@ -4,6 +4,7 @@
 
 use crate::playground::{ChildGuard, Playground};
 use anyhow::{anyhow, Result};
+use base::server_config_service::DEFAULT_GRPC_ADMIN_PORT;
 use base::snp::upsetter_server_admin::server_admin_service_client::ServerAdminServiceClient;
 use std::process::Command;
 use std::time::Duration;
@@ -46,12 +47,15 @@ impl Playground {
 
         sleep(Duration::from_millis(1000)).await;
 
-        let port = config_data["grpc_server_port"].as_u64().unwrap() as i32;
+        let admin_port = config_data["grpc_admin_port"]
+            .as_u64()
+            .unwrap_or(DEFAULT_GRPC_ADMIN_PORT as u64) as i32;
         let host_name = config_data["host_name"].as_str().unwrap();
 
         // get and store admin client to these providers
         let admin_client =
-            ServerAdminServiceClient::connect(format!("http://{}:{}", host_name, port)).await?;
+            ServerAdminServiceClient::connect(format!("http://{}:{}", host_name, admin_port))
+                .await?;
 
         self.providers_admin_clients
             .insert(provider_name.clone(), admin_client);
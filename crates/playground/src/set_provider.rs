@@ -31,6 +31,7 @@ impl Playground {
             ip_address: data["host_name"].as_str().unwrap().into(),
             port: data["grpc_server_port"].as_u64().unwrap() as u32,
             name: data["peer_name"].as_str().unwrap().into(),
+            min_api_version: "".to_string(),
             net_id: data["net_id"].as_u64().unwrap() as u32,
         };
 
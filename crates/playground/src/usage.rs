@@ -22,11 +22,14 @@ impl Playground {
         println!("    👉 <client> status <channel> <text>");
         println!("    👉 <client> status-reply <channel> <reply_to> <text>");
         println!("    👉 <client> status-unsubscribe <channel>");
-        println!("    👉 <client> group-create <group>");
+        println!("    👉 <client> history <channel|group> [max_items]");
+        println!("    👉 <client> group-create <group> [approve-members]");
         println!("    👉 <client> group-join <group>");
         println!("    👉 <client> group-message <group> <text>");
         println!("    👉 <client> group-message-reply <group> <reply_to> <text>");
         println!("    👉 <client> group-leave <group>");
+        println!("    👉 <client> group-admin <group> <remove|ban|unban|add-admin|remove-admin|approve|deny|block-replier|unblock-replier> <client>");
+        println!("    👉 <client> group-members <group>");
         println!("    👉 <client> create-item <price> <name> <text>");
         println!("    👉 <client> buy-item <seller> <item-id> <price>");
         println!("    👉 <client> list-items <seller>");
@@ -8,7 +8,7 @@ use base::hex_utils::short_hex_string;
 use base::snp::snp_core_types::ClientServiceData;
 use bytes::{BufMut, Bytes, BytesMut};
 use db::db_service;
-use db::db_service::{DataItem, DatabaseService, ReadItem, WriteItem};
+use db::db_service::{DataItem, DatabaseService, DeleteItem, ReadItem, WriteItem};
 use ed25519_dalek::PublicKey;
 use serde::{Deserialize, Serialize};
 use std::convert::From;
@@ -100,15 +100,21 @@ impl Handler<GetAllClientIds> for ClientsDataService {
 
 #[message(result = "Result<Vec<ClientServiceData>>")]
 pub(crate) struct GetClientsServiceData(pub(crate) Vec<PublicKey>);
-/// Get service data for one or more clients
+/// Get service data for one or more clients. Unknown clients are skipped.
 #[async_trait::async_trait]
 impl Handler<GetClientsServiceData> for ClientsDataService {
     async fn handle(
         &mut self,
         _ctx: &mut Context<Self>,
-        _msg: GetClientsServiceData,
+        msg: GetClientsServiceData,
     ) -> Result<Vec<ClientServiceData>> {
-        unimplemented!()
+        let mut res = vec![];
+        for id in msg.0.iter() {
+            if let Some(data) = read_client_service_data(id).await? {
+                res.push(data)
+            }
+        }
+        Ok(res)
     }
 }
 
@@ -129,28 +135,36 @@ impl Handler<GetClientServiceData> for ClientsDataService {
         msg: GetClientServiceData,
     ) -> Result<Option<ClientServiceData>> {
         debug!("Get client data for: {}", short_hex_string(msg.0.as_ref()));
+        read_client_service_data(&msg.0).await
+    }
+}
 
-        // we need to create a key unique to client data to avoid conflicts with other code
-        // that uses user ids as keys
-        let mut key = BytesMut::with_capacity(1024);
-        key.put(CD_KEY_SUFFIX.as_bytes());
-        key.put(msg.0.as_ref().to_vec().as_slice());
+/// Returns the db key of a client's service data
+fn client_data_key(client_id: &PublicKey) -> Bytes {
+    // we need to create a key unique to client data to avoid conflicts with other code
+    // that uses user ids as keys
+    let mut key = BytesMut::with_capacity(1024);
+    key.put(CD_KEY_SUFFIX.as_bytes());
+    key.put(client_id.as_ref());
+    key.freeze()
+}
 
-        let read_item = ReadItem {
-            key: key.freeze(),
-            cf: db_service::PROVIDER_COL_FAMILY,
-        };
+/// Read client service data from the db
+async fn read_client_service_data(client_id: &PublicKey) -> Result<Option<ClientServiceData>> {
+    let read_item = ReadItem {
+        key: client_data_key(client_id),
+        cf: db_service::PROVIDER_COL_FAMILY,
+    };
 
-        if let Some(res) = DatabaseService::read(read_item).await? {
-            debug!("Decoding client data...");
-            use prost::Message;
-            let client_data = ClientServiceData::decode(res.0.to_vec().as_slice())?;
-            debug!("Returning client data");
-            Ok(Some(client_data))
-        } else {
-            debug!("No client data found");
-            Ok(None)
-        }
+    if let Some(res) = DatabaseService::read(read_item).await? {
+        debug!("Decoding client data...");
+        use prost::Message;
+        let client_data = ClientServiceData::decode(res.0.to_vec().as_slice())?;
+        debug!("Returning client data");
+        Ok(Some(client_data))
+    } else {
+        debug!("No client data found");
+        Ok(None)
     }
 }
 
@@ -213,3 +227,53 @@ impl Handler<UpsertClientServiceData> for ClientsDataService {
 }
 
 ////////////////////
+
+#[message(result = "Result<()>")]
+pub(crate) struct DeleteClientServiceData(pub(crate) PublicKey);
+
+/// Delete a client's service data and remove it from the serviced client ids
+#[async_trait::async_trait]
+impl Handler<DeleteClientServiceData> for ClientsDataService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: DeleteClientServiceData,
+    ) -> Result<()> {
+        DatabaseService::delete(DeleteItem {
+            key: client_data_key(&msg.0),
+            cf: db_service::PROVIDER_COL_FAMILY,
+        })
+        .await?;
+
+        let read_item = ReadItem {
+            key: ALL_CLIENT_IDS_KEY.into(),
+            cf: db_service::PROVIDER_COL_FAMILY,
+        };
+
+        let mut ids: Vec<PublicKey> = match DatabaseService::read(read_item).await? {
+            Some(data) => bincode::deserialize::<ClientsIds>(&data.0)?.ids,
+            None => return Ok(()),
+        };
+
+        ids.retain(|id| *id != msg.0);
+
+        let data: Bytes = Bytes::from(bincode::serialize(&ClientsIds { ids })?);
+        let write_item = WriteItem {
+            data: DataItem {
+                key: ALL_CLIENT_IDS_KEY.into(),
+                value: data,
+            },
+            cf: db_service::PROVIDER_COL_FAMILY,
+            ttl: 0, // this data should never expire
+        };
+
+        debug!(
+            "Deleted data of client id: {}",
+            short_hex_string(msg.0.as_ref())
+        );
+
+        DatabaseService::write(write_item).await
+    }
+}
+
+////////////////////
@@ -4,9 +4,9 @@
 
 use crate::clients_data::service::ClientsDataService;
 use anyhow::Result;
-use base::snp::snp_server_api::{ClientMessageMetadata, DrMessage};
+use base::snp::snp_server_api::{ClientMessageMetadata, ClientMessagesMetadata, DrMessage};
+use base::time_utils::{expiration_time, now};
 use bytes::{BufMut, Bytes, BytesMut};
-use chrono::prelude::*;
 use db::db_service;
 use db::db_service::{DataItem, DatabaseService, DeleteItem, ReadItem, WriteItem};
 use ed25519_dalek::PublicKey;
@@ -24,6 +24,21 @@ use xactor::*;
 // suffix for a client message
 const MSG_KEY_SUFFIX: &str = "cm"; // key := msg_id.string().bytes() || cm
 
+const A_BILLY: u64 = 1_000_000_000;
+
+/// Returns the ids of pending messages which carried an expiry hint that passed by time_stamp
+pub(crate) fn expired_messages_ids(metadata: &ClientMessagesMetadata, time_stamp: u64) -> Vec<u64> {
+    metadata
+        .messages_metadata
+        .iter()
+        .filter(|m| {
+            expiration_time(m.received_date, m.ttl)
+                .is_some_and(|expiration| expiration <= time_stamp)
+        })
+        .map(|m| m.id)
+        .collect()
+}
+
 ///////////////////////////
 
 #[message(result = "Result<()>")]
@@ -114,6 +129,8 @@ impl Handler<LoadMessagesFromStore> for ClientsDataService {
 pub(crate) struct StoreMessageForClient {
     pub(crate) id: PublicKey,
     pub(crate) message: DrMessage,
+    /// time (unix nanos) after which the message should be dropped if not delivered. 0 for none
+    pub(crate) expires: u64,
 }
 
 /// Store message and meta-data that should be forwarded to a client
@@ -128,14 +145,26 @@ impl Handler<StoreMessageForClient> for ClientsDataService {
         let mut client_msgs_metadata =
             ClientsDataService::get_client_pending_messages(&msg.id).await?;
 
-        // Create ClientMessageMetadata for the message with unique id
+        // A message with an expiry hint is held until it expires. Its ttl is rounded up to whole seconds
+        let received_date = now();
+        let ttl = match msg.expires {
+            0 => 0, // todo: expire this per service terms - e.g. 2 months...
+            expires => expires
+                .saturating_sub(received_date)
+                .div_ceil(A_BILLY)
+                .max(1),
+        };
+
+        // Create ClientMessageMetadata for the message with unique id and the next cursor in the client's stream
         let meta_data_id = OsRng.next_u64();
+        client_msgs_metadata.last_cursor += 1;
         let meta_data = ClientMessageMetadata {
             id: meta_data_id, // this allow client to request the message indexed by provider by id
-            received_date: Utc::now().timestamp_nanos() as u64,
+            received_date,
             price: 1, // todo: compute this based on pricing policy in terms and message size
             size: 10, // todo: compute this based on message size
-            ttl: 0,   // todo: expire this per service terms - e.g. 2 months...
+            ttl,
+            cursor: client_msgs_metadata.last_cursor,
         };
 
         // update meta data and store to db
@@ -162,7 +191,7 @@ impl Handler<StoreMessageForClient> for ClientsDataService {
         let write_item = WriteItem {
             data,
             cf: db_service::PROVIDER_COL_FAMILY,
-            ttl: 0, // todo: this should expire per service terms. e.g. 2 months...
+            ttl, // todo: this should expire per service terms. e.g. 2 months...
         };
 
         DatabaseService::write(write_item).await?;
@@ -171,3 +200,43 @@ impl Handler<StoreMessageForClient> for ClientsDataService {
     }
 }
 ///////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn metadata(id: u64, received_date: u64, ttl: u64) -> ClientMessageMetadata {
+        ClientMessageMetadata {
+            id,
+            received_date,
+            price: 0,
+            size: 0,
+            ttl,
+            cursor: id,
+        }
+    }
+
+    #[test]
+    fn test_expired_messages_ids() {
+        let received_date = now();
+        let pending = ClientMessagesMetadata {
+            messages_metadata: vec![
+                metadata(1, received_date, 60),
+                metadata(2, received_date, 0),
+                metadata(3, received_date, 3600),
+            ],
+            last_cursor: 3,
+        };
+
+        assert!(expired_messages_ids(&pending, received_date).is_empty());
+
+        // messages with an expiry hint are dropped once it passes
+        let time_stamp = received_date + Duration::from_secs(61).as_nanos() as u64;
+        assert_eq!(expired_messages_ids(&pending, time_stamp), vec![1]);
+
+        // messages without one are held
+        let time_stamp = time_stamp + Duration::from_secs(3600).as_nanos() as u64;
+        assert_eq!(expired_messages_ids(&pending, time_stamp), vec![1, 3]);
+    }
+}
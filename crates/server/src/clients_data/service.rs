@@ -3,18 +3,21 @@
 //
 
 use crate::clients_data::clients::{
-    AddClientId, GetAllClientIds, GetClientServiceData, GetClientsServiceData,
-    UpsertClientServiceData,
+    AddClientId, DeleteClientServiceData, GetAllClientIds, GetClientServiceData,
+    GetClientsServiceData, UpsertClientServiceData,
 };
 use crate::clients_data::clients_msgs::{
-    DeleteMessages, LoadMessagesFromStore, StoreMessageForClient,
+    expired_messages_ids, DeleteMessages, LoadMessagesFromStore, StoreMessageForClient,
 };
 use anyhow::{anyhow, Result};
+use base::server_config_service::{ServerConfigService, HANDOVER_GRACE_PERIOD_CONFIG_KEY};
 use base::snp::snp_core_types::ClientServiceData;
 use base::snp::snp_server_api::{ClientMessageMetadata, ClientMessagesMetadata, DrMessage};
+use base::time_utils::now;
 use bytes::{BufMut, Bytes, BytesMut};
+use chrono::prelude::*;
 use db::db_service;
-use db::db_service::{DataItem, DatabaseService, ReadItem, WriteItem};
+use db::db_service::{DataItem, DatabaseService, DeleteItem, ReadItem, WriteItem};
 use ed25519_dalek::PublicKey;
 use std::convert::From;
 use xactor::*;
@@ -37,7 +40,7 @@ impl ClientsDataService {
     }
 
     /// Returns the service data for all clients serviced by this provider
-    pub(crate) async fn _get_all_clients_service_data() -> Result<Vec<ClientServiceData>> {
+    pub(crate) async fn get_all_clients_service_data() -> Result<Vec<ClientServiceData>> {
         let service = ClientsDataService::from_registry().await?;
         let all_ids: Vec<PublicKey> = service.call(GetAllClientIds {}).await??;
         service.call(GetClientsServiceData(all_ids)).await?
@@ -65,15 +68,68 @@ impl ClientsDataService {
         service.call(AddClientId(id)).await?
     }
 
+    /// Delete a client's service data and its pending messages metadata.
+    /// Callers should delete the client's pending messages first via delete_client_messages.
+    /// The client is no longer known to this provider after this call.
+    pub(crate) async fn delete_client_data(client_id: &PublicKey) -> Result<()> {
+        let mut key = BytesMut::with_capacity(1024);
+        key.put(client_id.as_ref());
+        key.put(MSGS_METADATA_KEY_SUFFIX.as_bytes());
+
+        DatabaseService::delete(DeleteItem {
+            key: key.freeze(),
+            cf: db_service::PROVIDER_COL_FAMILY,
+        })
+        .await?;
+
+        let service = ClientsDataService::from_registry().await?;
+        service.call(DeleteClientServiceData(*client_id)).await?
+    }
+
+    /// Returns true if a client stopped being serviced by this provider and the grace period
+    /// in which this provider handles messages designated to it is over
+    pub(crate) async fn handover_grace_period_expired(data: &ClientServiceData) -> Result<bool> {
+        if data.service_ended == 0 {
+            return Ok(false);
+        }
+
+        let grace_period = ServerConfigService::get_u64(HANDOVER_GRACE_PERIOD_CONFIG_KEY.into())
+            .await?
+            .unwrap_or(0);
+
+        let now = Utc::now().timestamp_nanos() as u64;
+        Ok(now > data.service_ended + grace_period * 1_000_000_000)
+    }
+
     /// Store a new message that should be delivered to a client
-    /// This will create an indexed message metadata that can be sent to client
+    /// This will create an indexed message metadata that can be sent to client.
+    /// The message is dropped if it wasn't delivered by expires (unix nanos) when it isn't 0.
     /// Returns the message's metadata
     pub(crate) async fn store_new_message_for_client(
         id: PublicKey,
         message: DrMessage,
+        expires: u64,
     ) -> Result<ClientMessageMetadata> {
         let service = ClientsDataService::from_registry().await?;
-        service.call(StoreMessageForClient { id, message }).await?
+        service
+            .call(StoreMessageForClient {
+                id,
+                message,
+                expires,
+            })
+            .await?
+    }
+
+    /// Drop messages pending delivery to a client which carried an expiry hint that passed
+    pub(crate) async fn delete_expired_messages(client_id: &PublicKey) -> Result<()> {
+        let pending = ClientsDataService::get_client_pending_messages(client_id).await?;
+        let ids = expired_messages_ids(&pending, now());
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        debug!("dropping {} expired pending message(s)", ids.len());
+        ClientsDataService::delete_client_messages(client_id, ids).await
     }
 
     // Load client messages from store based on id
@@ -111,6 +167,7 @@ impl ClientsDataService {
             Some(data) => Ok(ClientMessagesMetadata::decode(data.0.as_ref())?),
             None => Ok(ClientMessagesMetadata {
                 messages_metadata: vec![],
+                last_cursor: 0,
             }),
         }
     }
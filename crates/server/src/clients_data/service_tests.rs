@@ -8,16 +8,15 @@ mod test {
     use crate::clients_data::service::ClientsDataService;
 
     use crate::clients_data::clients::{GetClientServiceData, UpsertClientServiceData};
+    use crate::test_helpers::run_with_test_db;
     use base::snp::snp_core_types::{ClientIdentityBundle, ClientServiceData, PreKey, PublicKey};
-    use base::test_helpers::enable_logger;
+    use base::snp::snp_server_api::DrMessage;
     use chrono::prelude::*;
     use crypto::utils::entity_from_pub_key;
     use xactor::Service;
 
-    #[tokio::test]
-    async fn test_add_client() {
-        enable_logger();
-        let clients_data_service = ClientsDataService::from_registry().await.unwrap();
+    /// Returns a new client id and its service data
+    fn new_client_data() -> (ed25519_dalek::PublicKey, ClientServiceData) {
         let client_id_key_pair = ed25519_dalek::Keypair::generate(&mut rand_core::OsRng);
         let client_id_pub_key = PublicKey {
             key: client_id_key_pair.public.as_ref().to_vec(),
@@ -44,6 +43,7 @@ mod test {
                 x2dh_version: "".to_string(),
                 key: Some(pre_key_public),
                 key_id: 0,
+                kem_key: vec![],
             }),
             one_time_keys: vec![],
             profile_image: None,
@@ -55,22 +55,104 @@ mod test {
             service_started: 0,
             service_ended: 0,
             client_identity_bundle: Some(client_bundle),
+            next_client_bundle: None,
+            service_suspended: false,
+            service_terms: None,
+            delivery_token: vec![],
+            protocol_version: "".into(),
         };
 
-        clients_data_service
-            .call(UpsertClientServiceData(client_data))
+        (client_id_pub, client_data)
+    }
+
+    #[test]
+    fn test_add_client() {
+        run_with_test_db(async {
+            let clients_data_service = ClientsDataService::from_registry().await.unwrap();
+            let (client_id_pub, client_data) = new_client_data();
+            let time_stamp = client_data
+                .client_identity_bundle
+                .as_ref()
+                .unwrap()
+                .time_stamp;
+
+            clients_data_service
+                .call(UpsertClientServiceData(client_data))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let res: ClientServiceData = clients_data_service
+                .call(GetClientServiceData(client_id_pub))
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+
+            assert!(res.client_identity_bundle.is_some());
+            assert_eq!(res.client_identity_bundle.unwrap().time_stamp, time_stamp);
+        })
+    }
+
+    #[test]
+    fn test_delete_client() {
+        run_with_test_db(async {
+            let (client_id_pub, client_data) = new_client_data();
+
+            ClientsDataService::upsert_client_data(client_data)
+                .await
+                .unwrap();
+
+            let metadata = ClientsDataService::store_new_message_for_client(
+                client_id_pub,
+                DrMessage { data: None },
+                0,
+            )
             .await
-            .unwrap()
             .unwrap();
 
-        let res: ClientServiceData = clients_data_service
-            .call(GetClientServiceData(client_id_pub))
+            assert!(is_known_client(&client_id_pub).await);
+
+            ClientsDataService::delete_client_messages(&client_id_pub, vec![metadata.id])
+                .await
+                .unwrap();
+
+            assert!(ClientsDataService::load_client_messages(vec![metadata.id])
+                .await
+                .unwrap()
+                .is_empty());
+
+            ClientsDataService::delete_client_data(&client_id_pub)
+                .await
+                .unwrap();
+
+            assert!(ClientsDataService::get_client_service_data(&client_id_pub)
+                .await
+                .unwrap()
+                .is_none());
+
+            let pending = ClientsDataService::get_client_pending_messages(&client_id_pub)
+                .await
+                .unwrap();
+            assert!(pending.messages_metadata.is_empty());
+
+            assert!(!is_known_client(&client_id_pub).await);
+        })
+    }
+
+    /// Returns true if client is in the provider's serviced clients
+    async fn is_known_client(client_id: &ed25519_dalek::PublicKey) -> bool {
+        ClientsDataService::get_all_clients_service_data()
             .await
             .unwrap()
-            .unwrap()
-            .unwrap();
-
-        assert!(res.client_identity_bundle.is_some());
-        assert_eq!(res.client_identity_bundle.unwrap().time_stamp, time_stamp);
+            .iter()
+            .any(|d| {
+                d.client_identity_bundle
+                    .as_ref()
+                    .unwrap()
+                    .get_client_id_ed25519_public_key()
+                    .unwrap()
+                    == *client_id
+            })
     }
 }
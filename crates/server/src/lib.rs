@@ -12,3 +12,6 @@ pub use services::server_service;
 
 mod clients_data;
 mod services;
+
+#[cfg(test)]
+mod test_helpers;
@@ -2,14 +2,30 @@
 //  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use crate::clients_data::service::ClientsDataService;
 use crate::services::blockchain_service::BlockchainService;
+use crate::services::clients_service::ClientsService;
+use crate::services::provider_id::ProviderIdService;
+use crate::services::provider_id_service::RotateIdentityBundle;
+use crate::services::server_to_server::server_to_server_service::{
+    GetPeers, ServerToServerService,
+};
 use anyhow::Result;
-use base::snp::snp_core_types::DialupInfo;
+use base::server_config_service::{ServerConfigService, ADMIN_KEY_CONFIG_KEY};
+use base::snp::snp_core_types::{DialupInfo, EntityId, PrivateProviderIdentityBundle, PublicKey};
 use base::snp::upsetter_server_admin::server_admin_service_server::ServerAdminService;
-use base::snp::upsetter_server_admin::GetClientsResponse;
+use base::snp::upsetter_server_admin::{
+    ClientInfo, EvictClientRequest, GetClientsResponse, GetConfigResponse,
+    GetPeersSessionsResponse, PeerSession, RotatePreKeyResponse, SuspendClientRequest,
+};
+use common::dr_service::DRService;
+use subtle::ConstantTimeEq;
 use tonic::{Request, Response, Status};
 use xactor::*;
 
+/// Metadata key admin service callers provide the server's admin key in
+pub(crate) const ADMIN_KEY_METADATA_KEY: &str = "x-admin-key";
+
 /// AdminService is a system service that provides access to provider server persisted data as well as an interface to admin the provider's server. It provides a GRPC admin service defined in ServerAdminService. This service is designed to be used by provider admin clients.
 #[derive(Debug, Clone)]
 pub(crate) struct AdminService {}
@@ -31,6 +47,25 @@ impl Actor for AdminService {
 
 impl Service for AdminService {}
 
+/// Returns an interceptor which rejects admin requests that don't include the admin key.
+/// Requests are not checked when admin_key is None.
+#[allow(clippy::result_large_err)]
+pub(crate) fn admin_key_interceptor(
+    admin_key: Option<String>,
+) -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Clone {
+    move |request: Request<()>| {
+        let admin_key = match admin_key.as_ref() {
+            Some(key) => key,
+            None => return Ok(request),
+        };
+
+        match request.metadata().get(ADMIN_KEY_METADATA_KEY) {
+            Some(key) if bool::from(key.as_bytes().ct_eq(admin_key.as_bytes())) => Ok(request),
+            _ => Err(Status::unauthenticated("invalid admin key")),
+        }
+    }
+}
+
 /// AdminService implements the ServerAdminService trait which defines the grpc methods
 /// it provides for clients over the network
 #[tonic::async_trait]
@@ -50,6 +85,197 @@ impl ServerAdminService for AdminService {
         &self,
         _request: Request<()>,
     ) -> Result<Response<GetClientsResponse>, Status> {
-        todo!()
+        let clients_data = ClientsDataService::get_all_clients_service_data()
+            .await
+            .map_err(|e| Status::internal(format!("failed to get clients data: {:?}", e)))?;
+
+        let mut clients = vec![];
+        for service_data in clients_data {
+            let bundle = service_data
+                .client_identity_bundle
+                .as_ref()
+                .ok_or_else(|| Status::internal("missing client bundle"))?;
+
+            let client_id = bundle
+                .get_client_id_ed25519_public_key()
+                .map_err(|e| Status::internal(format!("invalid client id: {:?}", e)))?;
+
+            let pending = ClientsDataService::get_client_pending_messages(&client_id)
+                .await
+                .map_err(|e| {
+                    Status::internal(format!("failed to get client pending messages: {:?}", e))
+                })?;
+
+            // balances are informative - a missing account should not fail the listing
+            let balances = match bundle.address.as_ref() {
+                Some(address) => match BlockchainService::get_account(address.clone()).await {
+                    Ok(account) => account.map(|a| a.balances).unwrap_or_default(),
+                    Err(e) => {
+                        warn!("failed to get client account: {:?}", e);
+                        vec![]
+                    }
+                },
+                None => vec![],
+            };
+
+            clients.push(ClientInfo {
+                service_data: Some(service_data),
+                pending_messages: pending.messages_metadata.len() as u32,
+                balances,
+            });
+        }
+
+        Ok(Response::new(GetClientsResponse { clients }))
+    }
+
+    async fn get_peers_sessions(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<GetPeersSessionsResponse>, Status> {
+        let peers = ServerToServerService::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?
+            .call(GetPeers)
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?
+            .map_err(|e| Status::internal(format!("failed to get peers: {:?}", e)))?;
+
+        let mut sessions = vec![];
+        for (peer_id, dialup_info) in peers {
+            let dr_session = DRService::get_dr_session(peer_id)
+                .await
+                .map_err(|e| Status::internal(format!("failed to get dr session: {:?}", e)))?;
+
+            if let Some(dr) = dr_session {
+                sessions.push(PeerSession {
+                    provider_id: Some(EntityId {
+                        public_key: Some(PublicKey {
+                            key: peer_id.as_ref().to_vec(),
+                        }),
+                        nickname: dialup_info.name.clone(),
+                    }),
+                    dialup_info: Some(dialup_info),
+                    session_id: dr.session_id,
+                })
+            }
+        }
+
+        Ok(Response::new(GetPeersSessionsResponse { sessions }))
+    }
+
+    async fn evict_client(
+        &self,
+        request: Request<EvictClientRequest>,
+    ) -> Result<Response<()>, Status> {
+        let client_id = request
+            .into_inner()
+            .client_id
+            .ok_or_else(|| Status::invalid_argument("missing client id"))?
+            .get_ed_pub_key()
+            .map_err(|_| Status::invalid_argument("invalid client id"))?;
+
+        if ClientsDataService::get_client_service_data(&client_id)
+            .await
+            .map_err(|e| Status::internal(format!("failed to get client data: {:?}", e)))?
+            .is_none()
+        {
+            return Err(Status::not_found("unrecognized client"));
+        }
+
+        ClientsService::remove_client_message_sender(client_id)
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?;
+
+        let pending = ClientsDataService::get_client_pending_messages(&client_id)
+            .await
+            .map_err(|e| {
+                Status::internal(format!("failed to get client pending messages: {:?}", e))
+            })?;
+        let ids = pending.messages_metadata.iter().map(|m| m.id).collect();
+
+        ClientsDataService::delete_client_messages(&client_id, ids)
+            .await
+            .map_err(|e| Status::internal(format!("failed to delete client messages: {:?}", e)))?;
+
+        ClientsDataService::delete_client_data(&client_id)
+            .await
+            .map_err(|e| Status::internal(format!("failed to delete client data: {:?}", e)))?;
+
+        info!("evicted client");
+
+        Ok(Response::new(()))
+    }
+
+    async fn suspend_client(
+        &self,
+        request: Request<SuspendClientRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req = request.into_inner();
+        let client_id = req
+            .client_id
+            .ok_or_else(|| Status::invalid_argument("missing client id"))?
+            .get_ed_pub_key()
+            .map_err(|_| Status::invalid_argument("invalid client id"))?;
+
+        let mut client_data = ClientsDataService::get_client_service_data(&client_id)
+            .await
+            .map_err(|e| Status::internal(format!("failed to get client data: {:?}", e)))?
+            .ok_or_else(|| Status::not_found("unrecognized client"))?;
+
+        client_data.service_suspended = req.suspend;
+        ClientsDataService::upsert_client_data(client_data)
+            .await
+            .map_err(|e| Status::internal(format!("failed to update client data: {:?}", e)))?;
+
+        // a suspended client's messages stream is closed. It may subscribe again once resumed.
+        if req.suspend {
+            ClientsService::remove_client_message_sender(client_id)
+                .await
+                .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?;
+        }
+
+        info!("client service suspended: {}", req.suspend);
+
+        Ok(Response::new(()))
+    }
+
+    async fn rotate_pre_key(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<RotatePreKeyResponse>, Status> {
+        let provider_id_service = ProviderIdService::from_registry()
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?;
+
+        let bundle: PrivateProviderIdentityBundle = provider_id_service
+            .call(RotateIdentityBundle)
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?
+            .map_err(|e| Status::internal(format!("failed to create new bundle: {:?}", e)))?;
+
+        info!("provider pre-key rotated - publishing new provider bundle...");
+
+        BlockchainService::publish_provider_bundle()
+            .await
+            .map_err(|e| Status::internal(format!("failed to publish new bundle: {:?}", e)))?;
+
+        Ok(Response::new(RotatePreKeyResponse {
+            provider_bundle: bundle.public_bundle,
+        }))
+    }
+
+    async fn get_config(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<GetConfigResponse>, Status> {
+        let mut values = ServerConfigService::get_all()
+            .await
+            .map_err(|e| Status::internal(format!("failed to get config: {:?}", e)))?;
+
+        if let Some(admin_key) = values.get_mut(ADMIN_KEY_CONFIG_KEY) {
+            *admin_key = "****".into();
+        }
+
+        Ok(Response::new(GetConfigResponse { values }))
     }
 }
@@ -5,18 +5,20 @@
 use crate::services::provider_id::ProviderIdService;
 use crate::services::provider_id_service::{GetCurrentIdentityBundle, GetPaymentAccountKeypair};
 use anyhow::Result;
-use base::api_types_extensions::Signed;
+use base::api_types_extensions::{NetworkScoped, Signed};
 use base::hex_utils::hex_string;
+use base::server_config_service::{ServerConfigService, TLS_CA_CERT_FILE_CONFIG_KEY};
 use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
 use base::snp::snp_blockchain::transaction::Data;
 use base::snp::snp_blockchain::{
-    ClientBundleTransactionData, ProviderBundleTransactionData, SetBalanceRequest,
-    SubmitTransactionRequest, Transaction, TransactionFee,
+    Account, ClientBundleTransactionData, GetAccountRequest, ProviderBundleTransactionData,
+    SetBalanceRequest, SubmitTransactionRequest, Transaction, TransactionFee,
 };
 use base::snp::snp_core_types::{
     DialupInfo, PrivateProviderIdentityBundle, ProviderSignedClientIdentityBundle,
 };
-use base::snp::snp_payments::{Amount, CoinType};
+use base::snp::snp_payments::{Address, Amount, CoinType};
+use common::network_salt::net_id;
 use ed25519_dalek::Keypair;
 use tonic::transport::Channel;
 use xactor::*;
@@ -24,7 +26,7 @@ use xactor::*;
 // Blockchain service client api
 impl BlockchainService {
     /// Publish this provider current bundle to the blockchain. Call me when provider bundle changes
-    pub(crate) async fn _publish_provider_bundle() -> Result<()> {
+    pub(crate) async fn publish_provider_bundle() -> Result<()> {
         let service = BlockchainService::from_registry().await?;
         service.call(PublishProviderBundleMessage {}).await?
     }
@@ -35,6 +37,12 @@ impl BlockchainService {
         service.call(msg).await?
     }
 
+    /// Returns an account from the blockchain service or None if no blockchain service was set
+    pub(crate) async fn get_account(address: Address) -> Result<Option<Account>> {
+        let service = BlockchainService::from_registry().await?;
+        service.call(GetAccount { address }).await?
+    }
+
     /// Set the remote blockchain service for this server
     pub(crate) async fn setup_blockchain_service(dialup_info: DialupInfo) -> Result<()> {
         let service = BlockchainService::from_registry().await?;
@@ -54,16 +62,15 @@ pub(crate) struct SetBlockchainService {
 impl Handler<SetBlockchainService> for BlockchainService {
     async fn handle(&mut self, _ctx: &mut Context<Self>, msg: SetBlockchainService) -> Result<()> {
         let dialup_info = msg.dialup_info;
+        dialup_info.verify_net_id(net_id())?;
+
         info!(
             "Connecting to blockchain-service set to {} {}",
             dialup_info.ip_address, dialup_info.port
         );
 
-        let mut client = BlockchainServiceClient::connect(format!(
-            "http://{}:{}",
-            dialup_info.ip_address, dialup_info.port
-        ))
-        .await?;
+        let ca_cert_file = ServerConfigService::get(TLS_CA_CERT_FILE_CONFIG_KEY.into()).await?;
+        let mut client = BlockchainServiceClient::new(dialup_info.connect(ca_cert_file).await?);
 
         info!(
             "Blockchain service set to {} {}",
@@ -105,7 +112,8 @@ impl Handler<SetBlockchainService> for BlockchainService {
 #[message(result = "Result<()>")]
 pub(crate) struct PublishProviderBundleMessage {}
 
-/// Publish provider bundle to the blockchain service
+/// Publish provider bundle to the blockchain service.
+/// When no blockchain service is set, the current bundle is published once one is set.
 #[async_trait::async_trait]
 impl Handler<PublishProviderBundleMessage> for BlockchainService {
     async fn handle(
@@ -113,10 +121,45 @@ impl Handler<PublishProviderBundleMessage> for BlockchainService {
         _ctx: &mut Context<Self>,
         _msg: PublishProviderBundleMessage,
     ) -> Result<()> {
+        if self.blockchain_service_client.is_none() {
+            info!("blockchain service not set - provider bundle not published");
+            return Ok(());
+        }
+
         self.publish_provider_bundle_to_blockchain().await
     }
 }
 
+////////////
+
+#[message(result = "Result<Option<Account>>")]
+pub(crate) struct GetAccount {
+    pub(crate) address: Address,
+}
+
+/// Get an account from the blockchain service
+#[async_trait::async_trait]
+impl Handler<GetAccount> for BlockchainService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: GetAccount,
+    ) -> Result<Option<Account>> {
+        let client = match self.blockchain_service_client.as_mut() {
+            Some(client) => client,
+            None => return Ok(None),
+        };
+
+        Ok(client
+            .get_account(GetAccountRequest {
+                address: Some(msg.address),
+            })
+            .await?
+            .into_inner()
+            .account)
+    }
+}
+
 impl BlockchainService {
     /// Private helper function
     async fn publish_provider_bundle_to_blockchain(&mut self) -> Result<()> {
@@ -151,10 +194,12 @@ impl BlockchainService {
             fee: Some(tx_fee),
             counter,
             entity_id: None,
-            net_id: 0,
+            net_id: net_id(),
             signature: vec![],
             data: Some(Data::ProviderBundle(bundle_data)),
             fee_signature: vec![], // sender pays fee
+            signature_scheme_id: 0,
+            fee_signature_scheme_id: 0,
         };
 
         tx.sign(&payment_keypair).unwrap();
@@ -244,10 +289,12 @@ impl Handler<PublishClientBundleMessage> for BlockchainService {
             fee: Some(tx_fee),
             counter,
             entity_id: None,
-            net_id: 0,
+            net_id: net_id(),
             signature: vec![],
             data: Some(Data::ClientBundle(bundle_tx_data)),
             fee_signature: vec![], // sender pays fee
+            signature_scheme_id: 0,
+            fee_signature_scheme_id: 0,
         };
 
         tx.sign(&payment_keypair).unwrap();
@@ -5,12 +5,13 @@
 use crate::clients_data::service::ClientsDataService;
 use crate::services::messaging::new_outgoing_message::new_outgoing_message;
 use anyhow::{anyhow, Result};
+use base::hex_utils::short_hex_string;
 use base::snp::snp_server_api::*;
 use bytes::Bytes;
 use common::dr_service::DRService;
 use std::collections::HashMap;
 use std::convert::From;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tonic::Status;
 use xactor::*;
 
@@ -18,18 +19,22 @@ use xactor::*;
 /// It maintains a list of all current provider's clients and knows how to return
 /// client data based on a public client id.
 /// This includes the current ClientIdentityBundle provided by the client and additional info such as balance, L2
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ClientsService {
-    /// map from client id to Sender used for sending remote clients messages from other entities
-    client_messages_streams: HashMap<Bytes, mpsc::Sender<Result<DrMessage, Status>>>,
+    /// map from client id to the client's subscription to messages designated to it
+    client_messages_streams: HashMap<Bytes, ClientMessagesStream>,
+    /// id of the next client messages subscription
+    next_stream_id: u64,
 }
 
-impl Default for ClientsService {
-    fn default() -> Self {
-        ClientsService {
-            client_messages_streams: HashMap::new(),
-        }
-    }
+/// A client subscription to metadata of messages designated to it. Metadata is sent over the stream by a task
+/// which reads it from the store.
+#[derive(Debug)]
+struct ClientMessagesStream {
+    /// unique subscription id - a task only cleans up its own subscription
+    id: u64,
+    /// notifies the stream task about new messages stored for the client. The stream ends when it is dropped.
+    notifier: watch::Sender<()>,
 }
 
 impl Service for ClientsService {}
@@ -49,23 +54,21 @@ impl ClientsService {
         service.call(sender).await?
     }
 
-    pub async fn _get_client_message_sender(
-        client_id: ed25519_dalek::PublicKey,
-    ) -> Result<Option<mpsc::Sender<Result<DrMessage, Status>>>> {
-        let service = ClientsService::from_registry().await?;
-        service.call(GetClientMessagesSender { client_id }).await?
-    }
-
-    pub async fn _remove_client_message_sender(client_id: ed25519_dalek::PublicKey) -> Result<()> {
+    /// Ends a client's messages stream
+    pub async fn remove_client_message_sender(client_id: ed25519_dalek::PublicKey) -> Result<()> {
         let service = ClientsService::from_registry().await?;
         service
-            .call(RemoveClientMessagesSender { client_id })
+            .call(RemoveClientMessagesSender {
+                client_id,
+                stream_id: None,
+            })
             .await?
     }
 
-    pub async fn send_message_to_client(data: SendMessageToClient) -> Result<()> {
+    /// Notify a client's messages stream that a new message was stored for the client
+    pub async fn notify_new_client_message(client_id: ed25519_dalek::PublicKey) -> Result<()> {
         let service = ClientsService::from_registry().await?;
-        service.call(data).await?
+        service.call(NotifyNewClientMessage { client_id }).await?
     }
 }
 
@@ -73,9 +76,12 @@ impl ClientsService {
 pub struct SetClientMessagesSender {
     pub client_id: ed25519_dalek::PublicKey,
     pub sender: mpsc::Sender<Result<DrMessage, Status>>,
+    /// metadata of pending messages stored after this cursor is replayed on the stream
+    pub resume_cursor: u64,
 }
 
-/// SetClientMessagesSender sets a Sender that is able to send messages designated to a client over a stream from this provider
+/// SetClientMessagesSender sets a Sender that is able to send messages designated to a client over a stream from this provider.
+/// A previous stream of the client ends.
 #[async_trait::async_trait]
 impl Handler<SetClientMessagesSender> for ClientsService {
     async fn handle(
@@ -83,64 +89,188 @@ impl Handler<SetClientMessagesSender> for ClientsService {
         _ctx: &mut Context<Self>,
         msg: SetClientMessagesSender,
     ) -> Result<()> {
-        self.client_messages_streams
-            .insert(Bytes::from(msg.client_id.as_bytes().to_vec()), msg.sender);
-
-        // Send any pending messages metadata over the stream to the client
-        let msgs_metadata = ClientsDataService::get_client_pending_messages(&msg.client_id).await?;
-
-        if !msgs_metadata.messages_metadata.is_empty() {
-            use prost::Message;
-            let mut buff: Vec<u8> = Vec::with_capacity(msgs_metadata.encoded_len());
-            msgs_metadata.encode(&mut buff)?;
-            self.send_message_to_client_over_stream(
-                &msg.client_id,
-                Bytes::from(buff),
-                MessageType::ClientMessagesMetadata,
-            )
-            .await?;
-        }
+        let id = self.next_stream_id;
+        self.next_stream_id += 1;
+
+        let (notifier, new_messages) = watch::channel(());
+        self.client_messages_streams.insert(
+            Bytes::from(msg.client_id.as_bytes().to_vec()),
+            ClientMessagesStream { id, notifier },
+        );
+
+        tokio::spawn(ClientsService::stream_client_messages(
+            msg.client_id,
+            id,
+            msg.sender,
+            new_messages,
+            msg.resume_cursor,
+        ));
 
         Ok(())
     }
 }
 
 impl ClientsService {
-    async fn send_message_to_client_over_stream(
-        &mut self,
+    /// Stream task of a client subscription. Removes the subscription when the stream ends.
+    async fn stream_client_messages(
+        client_id: ed25519_dalek::PublicKey,
+        stream_id: u64,
+        sender: mpsc::Sender<Result<DrMessage, Status>>,
+        mut new_messages: watch::Receiver<()>,
+        resume_cursor: u64,
+    ) {
+        if let Err(e) = ClientsService::send_client_messages(
+            &client_id,
+            &sender,
+            &mut new_messages,
+            resume_cursor,
+        )
+        .await
+        {
+            warn!("client messages stream failed: {:?}", e);
+        }
+
+        debug!(
+            "client messages stream ended: {}",
+            short_hex_string(client_id.as_ref())
+        );
+
+        // clean up the stale subscription unless the client subscribed again
+        if let Ok(service) = ClientsService::from_registry().await {
+            let _ = service
+                .call(RemoveClientMessagesSender {
+                    client_id,
+                    stream_id: Some(stream_id),
+                })
+                .await;
+        }
+    }
+
+    /// Sends metadata of pending messages stored after the resume cursor and then metadata of new messages
+    /// as they are stored. Metadata is read from the store, so when the client is slow to consume the stream
+    /// new messages wait in the store until the stream has capacity instead of being dropped.
+    /// Returns when the client disconnects or the subscription is removed.
+    async fn send_client_messages(
         client_id: &ed25519_dalek::PublicKey,
-        message: Bytes,
-        message_type: MessageType,
+        sender: &mpsc::Sender<Result<DrMessage, Status>>,
+        new_messages: &mut watch::Receiver<()>,
+        resume_cursor: u64,
     ) -> Result<()> {
-        if let Some(sender) = self.client_messages_streams.get_mut(client_id.as_ref()) {
-            let mut dr = DRService::get_dr_session(*client_id)
-                .await?
-                .ok_or_else(|| anyhow!("no dr session with client"))?;
-
-            debug!("preparing new message to client...");
-            let out_msg = new_outgoing_message(message_type, message, &mut dr, *client_id).await?;
-
-            debug!("sending new message to client over stream...");
-            sender
-                .send(Ok(DrMessage {
-                    data: Some(dr_message::Data::Message(out_msg)),
-                }))
-                .await?;
-        } else {
-            warn!("didn't find a remote client subscription on message stream");
+        let mut cursor = resume_cursor;
+        let mut replay = true;
+
+        loop {
+            // mark notifications as seen before reading the store so no new message is missed
+            new_messages.borrow_and_update();
+
+            // the client never learns about messages which expired before it got their metadata
+            ClientsDataService::delete_expired_messages(client_id).await?;
+            let pending = ClientsDataService::get_client_pending_messages(client_id).await?;
+            let metadata = messages_after(pending, cursor, replay);
+            replay = false;
+
+            if let Some(last_cursor) = metadata.messages_metadata.iter().map(|m| m.cursor).max() {
+                let message = ClientsService::new_metadata_message(client_id, metadata).await?;
+
+                tokio::select! {
+                    res = sender.send(Ok(message)) => {
+                        if res.is_err() {
+                            // client disconnected
+                            return Ok(());
+                        }
+                    },
+                    _ = subscription_removed(new_messages) => return Ok(()),
+                }
+
+                cursor = cursor.max(last_cursor);
+                continue;
+            }
+
+            tokio::select! {
+                res = new_messages.changed() => {
+                    if res.is_err() {
+                        // subscription removed
+                        return Ok(());
+                    }
+                },
+                _ = sender.closed() => return Ok(()),
+            }
         }
+    }
 
-        Ok(())
+    /// Returns a message to a client with messages metadata, encrypted in the dr session with the client
+    async fn new_metadata_message(
+        client_id: &ed25519_dalek::PublicKey,
+        metadata: ClientMessagesMetadata,
+    ) -> Result<DrMessage> {
+        use prost::Message;
+        let mut buff: Vec<u8> = Vec::with_capacity(metadata.encoded_len());
+        metadata.encode(&mut buff)?;
+
+        let session_id = DRService::get_dr_session(*client_id)
+            .await?
+            .ok_or_else(|| anyhow!("no dr session with client"))?
+            .session_id;
+
+        // the client may be calling us in this session at the same time
+        let _session_lock = DRService::lock_session(session_id).await?;
+        let (mut dr, _) = DRService::get_dr_session_by_id(session_id)
+            .await?
+            .ok_or_else(|| anyhow!("no dr session with client"))?;
+
+        debug!("preparing new message to client...");
+        let out_msg = new_outgoing_message(
+            MessageType::ClientMessagesMetadata,
+            Bytes::from(buff),
+            &mut dr,
+            *client_id,
+        )
+        .await?;
+
+        Ok(DrMessage {
+            data: Some(dr_message::Data::Message(out_msg)),
+        })
+    }
+}
+
+/// Returns when a subscription's notifier was dropped
+async fn subscription_removed(new_messages: &mut watch::Receiver<()>) {
+    while new_messages.changed().await.is_ok() {}
+}
+
+/// Returns metadata of pending messages with a cursor greater than a cursor.
+/// Messages stored before cursors were assigned have no cursor and are included only when replaying.
+/// A cursor which is ahead of the store is from another store, e.g. before the client's data was deleted, and is ignored.
+fn messages_after(
+    pending: ClientMessagesMetadata,
+    cursor: u64,
+    replay: bool,
+) -> ClientMessagesMetadata {
+    let cursor = if cursor > pending.last_cursor {
+        0
+    } else {
+        cursor
+    };
+
+    ClientMessagesMetadata {
+        messages_metadata: pending
+            .messages_metadata
+            .into_iter()
+            .filter(|m| m.cursor > cursor || (replay && m.cursor == 0))
+            .collect(),
+        last_cursor: pending.last_cursor,
     }
 }
 
 #[message(result = "Result<()>")]
 pub struct RemoveClientMessagesSender {
     pub client_id: ed25519_dalek::PublicKey,
+    /// when set, the client's subscription is only removed if it has this id
+    pub stream_id: Option<u64>,
 }
 
-/// RemoveClientMessagesSender removes a message sender for a client.
-/// This should be called when client disconnects streaming connection with this server
+/// RemoveClientMessagesSender removes a message sender for a client and ends its stream.
+/// This is called when client disconnects streaming connection with this server
 #[async_trait::async_trait]
 impl Handler<RemoveClientMessagesSender> for ClientsService {
     async fn handle(
@@ -148,52 +278,90 @@ impl Handler<RemoveClientMessagesSender> for ClientsService {
         _ctx: &mut Context<Self>,
         msg: RemoveClientMessagesSender,
     ) -> Result<()> {
-        self.client_messages_streams.remove(msg.client_id.as_ref());
+        let key = msg.client_id.as_ref();
+        match (msg.stream_id, self.client_messages_streams.get(key)) {
+            (Some(id), Some(stream)) if stream.id != id => {}
+            _ => {
+                self.client_messages_streams.remove(key);
+            }
+        }
         Ok(())
     }
 }
 
-/// Request service to send a typed message to a client
-/// Message is a typed message to be sent
+/// Notify a client's messages stream about a new message stored for the client
 #[message(result = "Result<()>")]
-pub struct SendMessageToClient {
+pub struct NotifyNewClientMessage {
     pub client_id: ed25519_dalek::PublicKey,
-    pub message_type: MessageType, // Protobuf message type
-    pub message: Bytes,            // Protobuf serialized data
 }
 
-/// SendMessageToClient is used for sending a typed message to a client.
-/// Currently, the message is sent if this service has a Sender for a streaming connection of GetClientMessageResponses with the client. The message will be encrypted in the current dr session between the provider and the client
+/// Metadata of the new message is sent over the client's stream if it is subscribed.
+/// Otherwise, it is sent next time the client subscribes.
 #[async_trait::async_trait]
-impl Handler<SendMessageToClient> for ClientsService {
-    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: SendMessageToClient) -> Result<()> {
-        // use the help method tha tis used by other flows
-        self.send_message_to_client_over_stream(&msg.client_id, msg.message, msg.message_type)
-            .await
+impl Handler<NotifyNewClientMessage> for ClientsService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: NotifyNewClientMessage,
+    ) -> Result<()> {
+        match self.client_messages_streams.get(msg.client_id.as_ref()) {
+            Some(stream) => {
+                // stream task might have ended - it cleans up its subscription
+                let _ = stream.notifier.send(());
+            }
+            None => debug!("client is not subscribed to messages stream"),
+        }
+        Ok(())
     }
 }
 
-#[message(result = "Result<Option<mpsc::Sender<Result<DrMessage, Status>>>>")]
-pub struct GetClientMessagesSender {
-    pub client_id: ed25519_dalek::PublicKey,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-/// GetClientMessagesSender gets a message sender for a client. Sender is able to send client messages
-/// over a stream. This should be called when client disconnects a streaming connection with this server
-/// Returns a Sender for this client or None if no Sender is currently available for the client
-#[async_trait::async_trait]
-impl Handler<GetClientMessagesSender> for ClientsService {
-    async fn handle(
-        &mut self,
-        _ctx: &mut Context<Self>,
-        msg: GetClientMessagesSender,
-    ) -> Result<Option<mpsc::Sender<Result<DrMessage, Status>>>> {
-        match self
-            .client_messages_streams
-            .get(msg.client_id.as_bytes().to_vec().as_slice())
-        {
-            Some(res) => Ok(Some(res.clone())),
-            None => Ok(None),
+    fn metadata(cursors: &[u64], last_cursor: u64) -> ClientMessagesMetadata {
+        ClientMessagesMetadata {
+            messages_metadata: cursors
+                .iter()
+                .map(|c| ClientMessageMetadata {
+                    id: *c + 100,
+                    cursor: *c,
+                    ..Default::default()
+                })
+                .collect(),
+            last_cursor,
         }
     }
+
+    fn cursors(metadata: &ClientMessagesMetadata) -> Vec<u64> {
+        metadata
+            .messages_metadata
+            .iter()
+            .map(|m| m.cursor)
+            .collect()
+    }
+
+    #[test]
+    fn test_messages_after() {
+        let pending = metadata(&[0, 2, 3, 5], 5);
+
+        assert_eq!(
+            cursors(&messages_after(pending.clone(), 0, true)),
+            vec![0, 2, 3, 5]
+        );
+        assert_eq!(
+            cursors(&messages_after(pending.clone(), 2, true)),
+            vec![0, 3, 5]
+        );
+        assert_eq!(
+            cursors(&messages_after(pending.clone(), 2, false)),
+            vec![3, 5]
+        );
+        assert!(messages_after(pending.clone(), 5, false)
+            .messages_metadata
+            .is_empty());
+
+        // cursor from another store replays all pending messages
+        assert_eq!(cursors(&messages_after(pending, 9, false)), vec![2, 3, 5]);
+    }
 }
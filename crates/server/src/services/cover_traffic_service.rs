@@ -0,0 +1,70 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use anyhow::{anyhow, Result};
+use base::snp::snp_server_api::{MessageType, TypedMessage};
+use base::typed_msgs_dispatcher::{
+    Subscribe, TypedMessageHandler, TypedMessagesDispatcher, Unsubscribe,
+};
+use chrono::prelude::*;
+use xactor::*;
+
+/// CoverTrafficService handles cover traffic sent by served clients to mask their real messaging patterns.
+/// Cover messages are discarded and answered with an empty response.
+#[derive(Debug, Default)]
+pub(crate) struct CoverTrafficService {}
+impl Service for CoverTrafficService {}
+
+#[async_trait::async_trait]
+impl Actor for CoverTrafficService {
+    async fn started(&mut self, ctx: &mut Context<Self>) -> Result<()> {
+        let subscribe_msg = Subscribe {
+            message_type: MessageType::CoverTrafficRequest as i32,
+            subscriber: ctx.address().caller(),
+        };
+
+        TypedMessagesDispatcher::from_registry()
+            .await?
+            .call(subscribe_msg)
+            .await??;
+
+        debug!("CoverTrafficService started and subscribed to handle CoverTrafficRequest");
+        Ok(())
+    }
+
+    async fn stopped(&mut self, _ctx: &mut Context<Self>) {
+        // Unsubscribe from the dispatcher
+        let dispatcher = TypedMessagesDispatcher::from_registry().await.unwrap();
+        let _res = dispatcher
+            .call(Unsubscribe {
+                id: MessageType::CoverTrafficRequest as i32,
+            })
+            .await;
+    }
+}
+
+/// Discard an incoming cover traffic message from a served client
+#[async_trait::async_trait]
+impl Handler<TypedMessageHandler> for CoverTrafficService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: TypedMessageHandler,
+    ) -> Result<TypedMessage> {
+        if msg.0.msg_type != (MessageType::CoverTrafficRequest as i32) {
+            return Err(anyhow!("Unexpected message type {}", msg.0.msg_type));
+        };
+
+        Ok(TypedMessage {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            msg_type: MessageType::CoverTrafficResponse as i32,
+            message: vec![],
+            receiver: None,
+            sender: None,
+            signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
+        })
+    }
+}
@@ -0,0 +1,342 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+//! A json over http(s) gateway to the provider's public and client apis.
+//! Each route maps to a ProviderCoreService grpc method. Requests and responses are the json encoding
+//! of the grpc method's request and response messages. An empty request body is treated as a default request.
+//! Client messages subscriptions are streamed as newline delimited json DrMessages.
+
+// grpc Status is the gateway's error type as it is mapped to http responses
+#![allow(clippy::result_large_err)]
+
+use crate::services::messaging::messaging_service::ServerMessagingService;
+use crate::services::rate_limiter::RateLimiterService;
+use anyhow::{anyhow, Result};
+use base::grpc_tls::read_tls_files;
+use base::snp::snp_server_api::provider_core_service_server::ProviderCoreService;
+use bytes::{Bytes, BytesMut};
+use hyper::body::HttpBody;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+use tokio_rustls::rustls::{NoClientAuth, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::StreamExt;
+use tonic::{Code, Status};
+
+pub(crate) const IDENTITY_BUNDLE_PATH: &str = "/v1/identity_bundle";
+pub(crate) const TERMS_OF_SERVICE_PATH: &str = "/v1/terms_of_service";
+pub(crate) const NEW_SESSION_PATH: &str = "/v1/new_session";
+pub(crate) const MESSAGE_PATH: &str = "/v1/message";
+pub(crate) const SUBSCRIBE_TO_CLIENT_MESSAGES_PATH: &str = "/v1/subscribe_to_client_messages";
+
+/// Max size of a json request body. Larger requests are rejected with a 413 before they are read in full
+const MAX_REQUEST_BODY_SIZE: usize = 8 * 1024 * 1024;
+
+/// Start the json gateway on addr. Connections are tls secured when cert_file and key_file are provided.
+pub(crate) async fn start_json_gateway(
+    addr: String,
+    tls_files: Option<(String, String)>,
+) -> Result<()> {
+    let acceptor = match tls_files {
+        Some((cert_file, key_file)) => Some(tls_acceptor(&cert_file, &key_file).await?),
+        None => None,
+    };
+
+    let listener = TcpListener::bind(addr.as_str()).await?;
+    info!(
+        "json gateway listening on: {} (tls: {})",
+        addr,
+        acceptor.is_some()
+    );
+
+    tokio::task::spawn(async move {
+        loop {
+            let (stream, remote_addr) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("json gateway failed to accept connection: {:?}", e);
+                    continue;
+                }
+            };
+
+            let acceptor = acceptor.clone();
+            tokio::task::spawn(async move {
+                match acceptor {
+                    Some(acceptor) => match acceptor.accept(stream).await {
+                        Ok(tls_stream) => serve_connection(tls_stream, remote_addr).await,
+                        Err(e) => debug!("json gateway tls handshake failed: {:?}", e),
+                    },
+                    None => serve_connection(stream, remote_addr).await,
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+async fn serve_connection<S>(stream: S, remote_addr: SocketAddr)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    if let Err(e) = Http::new()
+        .serve_connection(
+            stream,
+            service_fn(move |req| handle_request(req, remote_addr)),
+        )
+        .await
+    {
+        debug!("json gateway connection error: {:?}", e);
+    }
+}
+
+/// Returns a tls acceptor configured with the pem cert chain and private key (pkcs8 or rsa) files
+async fn tls_acceptor(cert_file: &str, key_file: &str) -> Result<TlsAcceptor> {
+    let (cert_pem, key_pem) = read_tls_files(cert_file, key_file).await?;
+
+    let cert_chain = certs(&mut cert_pem.as_slice())
+        .map_err(|_| anyhow!("invalid tls cert file {}", cert_file))?;
+
+    let mut keys = pkcs8_private_keys(&mut key_pem.as_slice())
+        .map_err(|_| anyhow!("invalid tls key file {}", key_file))?;
+    if keys.is_empty() {
+        keys = rsa_private_keys(&mut key_pem.as_slice())
+            .map_err(|_| anyhow!("invalid tls key file {}", key_file))?;
+    }
+    let key = keys
+        .pop()
+        .ok_or_else(|| anyhow!("no private key in tls key file {}", key_file))?;
+
+    let mut config = ServerConfig::new(NoClientAuth::new());
+    config.set_single_cert(cert_chain, key)?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+async fn handle_request(
+    req: Request<Body>,
+    remote_addr: SocketAddr,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::POST {
+        return Ok(error_response(Status::unimplemented(
+            "only POST is supported",
+        )));
+    }
+
+    let path = req.uri().path().to_string();
+
+    // the grpc api rate limits these requests by the caller's ip address. Gateway calls have no grpc remote address
+    if path == NEW_SESSION_PATH || path == MESSAGE_PATH {
+        if let Err(status) = RateLimiterService::check_ip(remote_addr.ip()).await {
+            return Ok(error_response(status));
+        }
+    }
+
+    let body = match read_body(req.into_body(), MAX_REQUEST_BODY_SIZE).await {
+        Ok(Some(body)) => body,
+        Ok(None) => {
+            return Ok(error_response_with_code(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Status::resource_exhausted(format!(
+                    "request body is larger than {} bytes",
+                    MAX_REQUEST_BODY_SIZE
+                )),
+            ))
+        }
+        Err(e) => {
+            return Ok(error_response(Status::invalid_argument(format!(
+                "failed to read request body: {:?}",
+                e
+            ))))
+        }
+    };
+
+    let service = ServerMessagingService::default();
+
+    let res = match path.as_str() {
+        IDENTITY_BUNDLE_PATH => match decode_request(&body) {
+            Ok(r) => json_response(service.get_identity_bundle(r).await),
+            Err(s) => Err(s),
+        },
+        TERMS_OF_SERVICE_PATH => match decode_request(&body) {
+            Ok(r) => json_response(service.get_terms_of_service(r).await),
+            Err(s) => Err(s),
+        },
+        NEW_SESSION_PATH => match decode_request(&body) {
+            Ok(r) => json_response(service.new_session(r).await),
+            Err(s) => Err(s),
+        },
+        MESSAGE_PATH => match decode_request(&body) {
+            Ok(r) => json_response(service.message(r).await),
+            Err(s) => Err(s),
+        },
+        SUBSCRIBE_TO_CLIENT_MESSAGES_PATH => match decode_request(&body) {
+            Ok(r) => match service.subscribe_to_client_messages(r).await {
+                Ok(resp) => {
+                    // each message is sent as a json line as soon as it is available
+                    let stream = resp.into_inner().map(|item| {
+                        item.and_then(|msg| {
+                            serde_json::to_vec(&msg)
+                                .map(|mut line| {
+                                    line.push(b'\n');
+                                    Bytes::from(line)
+                                })
+                                .map_err(|e| Status::internal(format!("{:?}", e)))
+                        })
+                    });
+                    Ok(Response::builder()
+                        .header("content-type", "application/x-ndjson")
+                        .body(Body::wrap_stream(stream))
+                        .unwrap())
+                }
+                Err(s) => Err(s),
+            },
+            Err(s) => Err(s),
+        },
+        _ => Err(Status::not_found(format!("unknown path {}", path))),
+    };
+
+    Ok(res.unwrap_or_else(error_response))
+}
+
+/// Read a request body. Returns None as soon as the body is larger than max_size
+async fn read_body(mut body: Body, max_size: usize) -> Result<Option<Bytes>, hyper::Error> {
+    if body.size_hint().lower() > max_size as u64 {
+        return Ok(None);
+    }
+
+    let mut buf = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if buf.len() + chunk.len() > max_size {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    Ok(Some(buf.freeze()))
+}
+
+/// Decode a json request body. An empty body is decoded as a default request.
+fn decode_request<T: DeserializeOwned + Default>(body: &[u8]) -> Result<tonic::Request<T>, Status> {
+    if body.is_empty() {
+        return Ok(tonic::Request::new(T::default()));
+    }
+
+    serde_json::from_slice(body)
+        .map(tonic::Request::new)
+        .map_err(|e| Status::invalid_argument(format!("invalid json request: {:?}", e)))
+}
+
+fn json_response<T: Serialize>(
+    res: Result<tonic::Response<T>, Status>,
+) -> Result<Response<Body>, Status> {
+    let body = serde_json::to_vec(res?.get_ref())
+        .map_err(|e| Status::internal(format!("failed to encode response: {:?}", e)))?;
+
+    Ok(Response::builder()
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap())
+}
+
+fn error_response(status: Status) -> Response<Body> {
+    error_response_with_code(http_status_code(status.code()), status)
+}
+
+fn error_response_with_code(code: StatusCode, status: Status) -> Response<Body> {
+    let body = serde_json::json!({
+        "code": status.code() as i32,
+        "message": status.message(),
+    });
+
+    Response::builder()
+        .status(code)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// Maps a grpc status code to an http status code
+fn http_status_code(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::snp::snp_server_api::GetIdentityBundleRequest;
+
+    #[test]
+    fn test_http_status_code() {
+        assert_eq!(http_status_code(Code::NotFound), StatusCode::NOT_FOUND);
+        assert_eq!(
+            http_status_code(Code::InvalidArgument),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            http_status_code(Code::Internal),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn test_decode_request() {
+        let req: tonic::Request<GetIdentityBundleRequest> = decode_request(b"").unwrap();
+        assert_eq!(req.get_ref(), &GetIdentityBundleRequest::default());
+
+        let req: tonic::Request<GetIdentityBundleRequest> =
+            decode_request(br#"{"protocol_version":"0.1.0"}"#).unwrap();
+        assert_eq!(req.get_ref().protocol_version, "0.1.0");
+
+        assert!(decode_request::<GetIdentityBundleRequest>(b"{").is_err());
+    }
+
+    #[test]
+    fn test_max_request_body_size() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let body = read_body(Body::from(vec![1u8; 10]), 10).await.unwrap();
+            assert_eq!(body.unwrap().len(), 10);
+            assert!(read_body(Body::from(vec![1u8; 11]), 10)
+                .await
+                .unwrap()
+                .is_none());
+
+            // streamed bodies without a known size are cut off once they are too large
+            let chunks: Vec<Result<_, std::io::Error>> = vec![Ok(vec![1u8; 6]), Ok(vec![1u8; 6])];
+            let body = Body::wrap_stream(tokio_stream::iter(chunks));
+            assert!(read_body(body, 10).await.unwrap().is_none());
+
+            let req = Request::post(IDENTITY_BUNDLE_PATH)
+                .body(Body::from(vec![b' '; MAX_REQUEST_BODY_SIZE + 1]))
+                .unwrap();
+            let res = handle_request(req, "127.0.0.1:1234".parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        });
+    }
+}
@@ -70,11 +70,10 @@ impl Handler<TypedMessageHandler> for ClientMessagesDeliveryService {
             .get_ika()
             .map_err(|_| anyhow!("missing sender from msg"))?;
 
-        if ClientsDataService::get_client_service_data(&ika)
-            .await?
-            .is_none()
-        {
-            bail!("unrecognized client - not served by this provider")
+        match ClientsDataService::get_client_service_data(&ika).await? {
+            None => bail!("unrecognized client - not served by this provider"),
+            Some(data) if data.service_suspended => bail!("client service is suspended"),
+            _ => {}
         }
 
         // Step 3 - verify that the request is a DeliverClientMessagesRequest
@@ -85,8 +84,10 @@ impl Handler<TypedMessageHandler> for ClientMessagesDeliveryService {
         // Step 4 - todo: verify the payment - implement me
         let payment = req.payment.ok_or_else(|| anyhow!("missing payment data"))?;
 
-        // Step 5: load messages from store deliver the messages to the client in a response
+        // Step 5: load messages from store deliver the messages to the client in a response.
+        // Messages which expired before they were delivered are dropped and not returned
 
+        ClientsDataService::delete_expired_messages(&ika).await?;
         let messages = ClientsDataService::load_client_messages(payment.item_ids.clone()).await?;
 
         // Step 6 - delete the messages and messages meta-data from store - note that responding may fail.
@@ -111,6 +112,8 @@ impl Handler<TypedMessageHandler> for ClientMessagesDeliveryService {
             receiver: None,
             sender: None,
             signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
         })
     }
 }
@@ -8,7 +8,9 @@ use crate::services::messaging::messaging_service::ServerMessagingService;
 use anyhow::{anyhow, bail, Result};
 use base::api_types_extensions::Signed;
 use base::snp::snp_server_api::provider_core_service_server::ProviderCoreService;
-use base::snp::snp_server_api::SubscribeToClientMessagesRequest;
+use base::snp::snp_server_api::{
+    SubscribeToClientMessagesRequest, SubscribeToClientMessagesRequestPayload,
+};
 use tokio::sync::mpsc;
 
 impl ServerMessagingService {
@@ -32,25 +34,33 @@ impl ServerMessagingService {
         // verify that client signed on this request before serving
         context.msg.verify_signature()?;
 
-        // so the typed message is a GetClientMessagesRequestPayload but we don't decode it as it is currently empty
         // we used DR to hide the identity of the client over the network for this use case....
+        use prost::Message;
+        let payload =
+            SubscribeToClientMessagesRequestPayload::decode(context.msg.message.as_slice())?;
 
         // step 5 - verify that this provider is serving the designated receiver
         let ika = context.msg.get_ika()?;
-        if ClientsDataService::get_client_service_data(&ika)
+        let client_data = ClientsDataService::get_client_service_data(&ika)
             .await?
-            .is_none()
-        {
-            bail!("unrecognized client - not served by this provider")
+            .ok_or_else(|| anyhow!("unrecognized client - not served by this provider"))?;
+
+        // a client who asked to stop being served may only get its held messages during the grace period
+        if ClientsDataService::handover_grace_period_expired(&client_data).await? {
+            bail!("client is no longer served by this provider")
         }
 
-        // todo: check that service didn't expire past its grace period for client who asked to stop being served
+        if client_data.service_suspended {
+            bail!("client service is suspended")
+        }
 
+        // when a slow client fills the stream buffer, its stream task waits until the client catches up
         let (tx, rx) = mpsc::channel(32);
 
         let msg = SetClientMessagesSender {
             client_id: context.ika,
             sender: tx,
+            resume_cursor: payload.resume_cursor,
         };
 
         ClientsService::set_client_message_sender(msg).await?;
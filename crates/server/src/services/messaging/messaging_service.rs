@@ -2,6 +2,8 @@
 //  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use crate::services::protocol_versions::negotiate_protocol_version;
+use crate::services::rate_limiter::RateLimiterService;
 use crate::services::terms_service::{GetCurrentTerms, TermsService};
 use anyhow::Result;
 use base::snp::snp_server_api::provider_core_service_server::ProviderCoreService;
@@ -35,6 +37,10 @@ impl ProviderCoreService for ServerMessagingService {
         &self,
         request: Request<NewSessionRequest>,
     ) -> Result<Response<NewSessionResponse>, Status> {
+        if let Some(addr) = request.remote_addr() {
+            RateLimiterService::check_ip(addr.ip()).await?;
+        }
+
         let res = self.new_session_handler(request).await;
         if res.is_err() {
             // let's log the error before returning it to remote host
@@ -53,6 +59,10 @@ impl ProviderCoreService for ServerMessagingService {
         &self,
         request: Request<MessageRequest>,
     ) -> Result<Response<MessageResponse>, Status> {
+        if let Some(addr) = request.remote_addr() {
+            RateLimiterService::check_ip(addr.ip()).await?;
+        }
+
         // validate and decode the incoming message
 
         // parse input
@@ -108,10 +118,12 @@ impl ProviderCoreService for ServerMessagingService {
     // rpc GetIdentityBundle(google.protobuf.Empty) returns (GetIdentityBundleResponse);
     async fn get_identity_bundle(
         &self,
-        _request: Request<GetIdentityBundleRequest>,
+        request: Request<GetIdentityBundleRequest>,
     ) -> Result<Response<GetIdentityBundleResponse>, Status> {
         debug!("Got GetIdentityBundle request",);
 
+        negotiate_protocol_version(&request.get_ref().protocol_version).await?;
+
         let bundle = ServerMessagingService::get_curr_provider_id_bundle().await?;
 
         debug!(
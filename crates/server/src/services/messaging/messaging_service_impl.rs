@@ -12,7 +12,7 @@ use base::hex_utils::short_hex_string;
 use base::snp::snp_core_types::{EntityId, PrivateProviderIdentityBundle, PublicKey};
 use base::snp::snp_server_api;
 use base::snp::snp_server_api::{DrSessionHeader, Message, MessageType};
-use base::typed_msgs_dispatcher::{Publish, TypedMessagesDispatcher};
+use base::typed_msgs_dispatcher::TypedMessagesDispatcher;
 use common::dr_service::DRService;
 use common::typed_msg_extensions::TypedMessageExtensions;
 use tonic::Status;
@@ -36,16 +36,8 @@ impl ServerMessagingService {
             msg_type
         );
 
-        let mut resp_msg = TypedMessagesDispatcher::from_registry()
+        let mut resp_msg = TypedMessagesDispatcher::publish(context.msg)
             .await
-            .map_err(|e| {
-                Status::internal(format!("internal error - failed to find dispatcher: {}", e))
-            })?
-            .call(Publish(context.msg))
-            .await
-            .map_err(|e| {
-                Status::internal(format!("internal error - failed to call dispatcher: {}", e))
-            })?
             .map_err(|e| {
                 Status::internal(format!(
                     "internal error - failed to get typed msg response: {}",
@@ -72,6 +64,13 @@ impl ServerMessagingService {
 
         resp_msg.sign(&context.ikb_pair)?;
 
+        // Messages may have been sent to alice in this session while her message was processed,
+        // e.g. new messages metadata pushed to her, so use the latest stored session state
+        let _session_lock = DRService::lock_session(context.dr.session_id).await?;
+        if let Some((dr, _)) = DRService::get_dr_session_by_id(context.dr.session_id).await? {
+            context.dr = dr;
+        }
+
         // The key to encrypt response message to Alice
         let bob_send_key = context.dr.next_sending_key().unwrap();
 
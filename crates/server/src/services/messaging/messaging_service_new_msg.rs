@@ -3,6 +3,7 @@
 //
 
 use crate::services::messaging::messaging_service::ServerMessagingService;
+use crate::services::rate_limiter::RateLimiterService;
 use anyhow::Result;
 use base::hex_utils::short_hex_string;
 use base::snp::snp_server_api::{Message, TypedMessage};
@@ -42,6 +43,11 @@ impl ServerMessagingService {
         // Alice the caller requesting to use an existing dr session with it.
         // We try to load it from storage by id to get Alice's id we stored wth it last time we used it
 
+        // hold the session's lock until it is saved so concurrent updates are not lost
+        let _session_lock = DRService::lock_session(header.session_id)
+            .await
+            .map_err(|_| Status::internal("failed to lock dr session"))?;
+
         let dr_service = DRService::from_registry()
             .await
             .map_err(|_| Status::internal("failed to get provider service"))?;
@@ -58,7 +64,10 @@ impl ServerMessagingService {
                 ))
             })?;
 
-        let mut dr: DoubleRatchet = dr_session.0;
+        // limit the rate of messages from the session's creator before doing any crypto work
+        RateLimiterService::check_client(&dr_session.1).await?;
+
+        let dr: DoubleRatchet = dr_session.0;
 
         let alice_pub_dr_key = message
             .get_sender_dr_pub_key()
@@ -69,22 +78,16 @@ impl ServerMessagingService {
             short_hex_string(alice_pub_dr_key.as_bytes())
         );
 
-        // Bob should only perform full ratchet if sending key count is 0
+        // Bob should only perform full ratchet if alice's pub dr key is new
         // otherwise he already did a ratchet with alice and should just advance is receiving key
         let header = message.header.unwrap();
         let index = header.count;
         debug!("sending key index: {}", index);
-        if index == 0 {
-            // Bob performs a full ratchet step with Alice's pub dr key per the protocol
-            dr.ratchet(&mut OsRng, &alice_pub_dr_key, header.prev_count)
-                .map_err(|e| Status::internal(format!("ratchet failed: {:?}", e)))?;
-        } else {
-            debug!("@@@ not doing ratchet index != 0");
-        }
 
-        // The requested message decryption key (compare counter with message)
-        let bob_receive_key = dr
-            .get_receiving_key(index)
+        // The requested message decryption key (compare counter with message).
+        // The updated session is saved once the message was decrypted and authenticated
+        let (dr, bob_receive_key) = dr
+            .get_message_receiving_key(&mut OsRng, &alice_pub_dr_key, header.prev_count, index)
             .map_err(|e| Status::internal(format!("failed to get DR receiving key: {:?}", e)))?;
 
         debug!(
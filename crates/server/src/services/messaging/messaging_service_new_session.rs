@@ -4,12 +4,16 @@
 
 use crate::services::messaging::messaging_service::ServerMessagingService;
 use crate::services::messaging::messaging_service_new_msg::IncomingMessageContext;
+use crate::services::protocol_versions::negotiate_protocol_version;
+use crate::services::rate_limiter::RateLimiterService;
 use anyhow::Result;
-use base::api_types_extensions::SignedWithExternalVerifier;
+use base::api_types_extensions::{NetworkScoped, SignedWithExternalVerifier};
 use base::hex_utils::short_hex_string;
-use base::snp::snp_server_api::{NewSessionRequest, NewSessionResponse};
+use base::server_config_service::{ServerConfigService, NEW_SESSION_POW_DIFFICULTY_CONFIG_KEY};
+use base::snp::snp_server_api::{NewSessionRequest, NewSessionResponse, RequestRejection};
 use bytes::Bytes;
-use common::network_salt;
+use chrono::Utc;
+use common::network_salt::{net_id, net_salt};
 use common::typed_msg_extensions::TypedMessageExtensions;
 use common::x2dh_service::{ExecuteProtocolAsBob, X2DHService};
 use crypto::utils::StaticSecretWrapper;
@@ -22,6 +26,9 @@ use std::convert::TryInto;
 use tonic::{Request, Response, Status};
 use xactor::Service;
 
+/// Max age of a new session request which includes a proof of work
+const POW_REQUEST_MAX_AGE_SECS: i64 = 60 * 5;
+
 /// MyMessagingService new_session api method implementation
 impl ServerMessagingService {
     /// Handles a remote request for a new DR session with this provider
@@ -32,6 +39,16 @@ impl ServerMessagingService {
         //
         // Step 1 - Check that the sender used a valid receiver provider data (provider id, pre-key)
         let req_data = request.into_inner();
+
+        req_data
+            .verify_net_id(net_id())
+            .map_err(|e| Status::invalid_argument(format!("{}", e)))?;
+
+        negotiate_protocol_version(&req_data.protocol_version).await?;
+
+        // Unauthenticated callers may be required to include a proof of work before we do any work
+        ServerMessagingService::verify_new_session_pow(&req_data).await?;
+
         let bundle =
             ServerMessagingService::get_provider_id_bundle(req_data.receiver_bundle_id).await?;
 
@@ -86,11 +103,14 @@ impl ServerMessagingService {
 
         // debug!("Bundle from local: {:?}", bundle.public_bundle);
 
+        // provider pre-keys are classic, so requests with a kem ciphertext are rejected by x2dh
         let input_bob = ProtocolInputBob {
             eka,
+            kem_ct: req_data.get_kem_ct(),
             ikb_pair,
             pkb_private: pkb_private.clone(),
             b_bundle_id: req_data.receiver_bundle_id,
+            kem_pkb_private: None,
         };
 
         // step 3 - call X2DHService to execute x2dh with Alice (obtain AD and shared secret)
@@ -118,7 +138,7 @@ impl ServerMessagingService {
         let root_chain_key = ChainKey::from(x2dh_output_bob.shared_secret);
 
         // Shared info between all nodes on the same p2p network - salt
-        let session_key = SessionKey::from(network_salt::NET_SALT.to_vec().as_slice());
+        let session_key = SessionKey::from(net_salt().to_vec().as_slice());
 
         let session_id = req_data
             .get_dr_session_id()
@@ -172,9 +192,11 @@ impl ServerMessagingService {
 
         // verify the whole request
         req_data
-            .verify_signature(&ika)
+            .verify_signature(ika.as_bytes())
             .map_err(|_| Status::invalid_argument("Failed to authenticate message"))?;
 
+        RateLimiterService::check_client(&ika).await?;
+
         // step 5 - process message
         // this step is common with how we handle message in an exiting dr session
         // so we use a helper function to generate the response
@@ -194,4 +216,35 @@ impl ServerMessagingService {
             message: Some(resp_msg),
         }))
     }
+
+    /// Verifies that a new session request includes a fresh and unused proof of work of the difficulty
+    /// required by the provider's terms of service
+    async fn verify_new_session_pow(req_data: &NewSessionRequest) -> Result<(), Status> {
+        let difficulty = ServerConfigService::get_u64(NEW_SESSION_POW_DIFFICULTY_CONFIG_KEY.into())
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?
+            .unwrap_or_default() as u32;
+
+        if difficulty == 0 {
+            return Ok(());
+        }
+
+        // a proof of work can't be reused once its request is stale
+        let age_secs = (Utc::now().timestamp_nanos() - req_data.time_stamp as i64) / 1_000_000_000;
+        if age_secs.abs() > POW_REQUEST_MAX_AGE_SECS {
+            return Err(Status::invalid_argument("stale new session request"));
+        }
+
+        req_data.verify_pow(difficulty).map_err(|_| {
+            RequestRejection::proof_of_work_required(difficulty)
+                .into_status("new session request requires a proof of work")
+        })?;
+
+        // a proof of work may be used only once while its request is fresh
+        let eka = req_data
+            .get_eka()
+            .map_err(|_| Status::invalid_argument("invalid eka"))?;
+        let expires = req_data.time_stamp as i64 + POW_REQUEST_MAX_AGE_SECS * 1_000_000_000;
+        RateLimiterService::use_pow(eka.to_bytes(), req_data.pow_nonce, expires).await
+    }
 }
@@ -8,6 +8,8 @@ pub(crate) mod messaging_service;
 mod messaging_service_impl;
 mod messaging_service_new_msg;
 mod messaging_service_new_session;
+pub(crate) mod msg_fan_out_service;
 pub(crate) mod msg_forwarding_service;
+mod msg_handover;
 pub(crate) mod msg_routing_service;
 pub(crate) mod new_outgoing_message;
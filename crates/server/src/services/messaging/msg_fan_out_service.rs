@@ -0,0 +1,165 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use anyhow::{anyhow, bail, Result};
+use base::typed_msgs_dispatcher::{
+    Subscribe, TypedMessageHandler, TypedMessagesDispatcher, Unsubscribe,
+};
+use chrono::prelude::*;
+use prost::Message;
+use xactor::*;
+
+use crate::clients_data::service::ClientsDataService;
+use crate::services::messaging::msg_forwarding_service::MessageForwardingService;
+use crate::services::rate_limiter::RateLimiterService;
+use base::hex_utils::short_hex_string;
+use base::server_config_service::{ServerConfigService, FAN_OUT_MAX_RECEIVERS_CONFIG_KEY};
+use base::snp::snp_core_types::ClientServiceData;
+use base::snp::snp_server_api::dr_message::Data;
+use base::snp::snp_server_api::{
+    DrMessage, FanOutMessageRequest, FanOutMessageResponse, MessageType, TypedMessage,
+};
+
+/// MessageFanOutService is a service which handles FanOutMessageRequest requests.
+/// A served client sends a group message it encrypted once with its group sender key to this provider,
+/// and this provider forwards it to each receiver via the receiver's provider.
+/// This provider learns that its client sent a message to the receivers but not the message's group.
+/// The number of receivers per request is capped by the client's service terms, and each receiver counts towards
+/// the client's messages rate limit.
+#[derive(Debug, Default)]
+pub struct MessageFanOutService {}
+impl Service for MessageFanOutService {}
+
+#[async_trait::async_trait]
+impl Actor for MessageFanOutService {
+    async fn started(&mut self, ctx: &mut Context<Self>) -> Result<()> {
+        // subscribe to FanOutMessageRequest incoming messages
+        let subscribe_msg = Subscribe {
+            message_type: MessageType::FanOutMessageRequest as i32,
+            subscriber: ctx.address().caller(),
+        };
+
+        let dispatcher = TypedMessagesDispatcher::from_registry().await.unwrap();
+        dispatcher.call(subscribe_msg).await??;
+        debug!("MessageFanOutService started and subscribed to handle FanOutMessageRequests");
+        Ok(())
+    }
+
+    async fn stopped(&mut self, _ctx: &mut Context<Self>) {
+        // Unsubscribe from the dispatcher
+        let dispatcher = TypedMessagesDispatcher::from_registry().await.unwrap();
+        let _res = dispatcher
+            .call(Unsubscribe {
+                id: MessageType::FanOutMessageRequest as i32,
+            })
+            .await;
+    }
+}
+
+/// Handle a FanOutMessageRequest from a served client
+#[async_trait::async_trait]
+impl Handler<TypedMessageHandler> for MessageFanOutService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: TypedMessageHandler,
+    ) -> Result<TypedMessage> {
+        // Step 1 - verify we know how to handle the message
+        if msg.0.msg_type != (MessageType::FanOutMessageRequest as i32) {
+            debug!("Unexpected message type");
+            return Err(anyhow!("Unexpected message type {}", msg.0.msg_type));
+        };
+
+        // Step 2 - Verify that we are serving this client before processing the message
+        let ika = msg
+            .0
+            .get_ika()
+            .map_err(|_| anyhow!("missing sender from msg"))?;
+
+        let service_data = match ClientsDataService::get_client_service_data(&ika).await? {
+            None => bail!("unrecognized client - not served by this provider"),
+            Some(data) if data.service_ended != 0 => {
+                bail!("client stopped being served by this provider")
+            }
+            Some(data) if data.service_suspended => bail!("client service is suspended"),
+            Some(data) => data,
+        };
+
+        let req: FanOutMessageRequest = FanOutMessageRequest::decode(msg.0.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode FanOutMessageRequest: {:?}", e))?;
+
+        // Step 3 - enforce the client's receivers limit and rate limit
+        let max_receivers = MessageFanOutService::max_receivers(&service_data).await?;
+        if max_receivers != 0 && req.receivers.len() > max_receivers as usize {
+            bail!(
+                "too many receivers - max {} receivers per request",
+                max_receivers
+            )
+        }
+
+        RateLimiterService::check_client_messages(&ika, req.receivers.len() as u32)
+            .await
+            .map_err(|status| anyhow!("fan out rejected: {}", status.message()))?;
+
+        let dr_message = DrMessage {
+            data: Some(Data::GroupMessage(
+                req.group_message
+                    .ok_or_else(|| anyhow!("missing group message"))?,
+            )),
+        };
+
+        debug!(
+            "fanning out a group message from client {} to {} receivers",
+            short_hex_string(ika.as_ref()),
+            req.receivers.len()
+        );
+
+        // Step 4 - forward the message to each receiver's provider. Receivers we fail to forward it to
+        // are returned to the client
+        let mut failed_receivers = vec![];
+        for receiver in req.receivers.iter() {
+            if let Err(e) = MessageForwardingService::forward_to_client_provider(
+                receiver,
+                dr_message.clone(),
+                &[],
+                0,
+            )
+            .await
+            {
+                warn!("failed to forward group message to receiver: {:?}", e);
+                failed_receivers.push(receiver.get_client_entity()?);
+            }
+        }
+
+        let resp = FanOutMessageResponse { failed_receivers };
+        let mut buff = Vec::with_capacity(resp.encoded_len());
+        resp.encode(&mut buff)?;
+
+        Ok(TypedMessage {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            msg_type: MessageType::FanOutMessageResponse as i32,
+            message: buff,
+            receiver: None,
+            sender: None,
+            signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
+        })
+    }
+}
+
+impl MessageFanOutService {
+    /// Returns the max number of receivers per request for a client. Clients are limited by their service terms
+    async fn max_receivers(service_data: &ClientServiceData) -> Result<u32> {
+        if let Some(terms) = service_data.service_terms.as_ref() {
+            return Ok(terms.max_fan_out_receivers);
+        }
+
+        Ok(
+            ServerConfigService::get_u64(FAN_OUT_MAX_RECEIVERS_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default() as u32,
+        )
+    }
+}
@@ -3,22 +3,25 @@
 //
 
 use crate::clients_data::service::ClientsDataService;
-use crate::services::clients_service::{ClientsService, SendMessageToClient};
+use crate::services::clients_service::ClientsService;
 use crate::services::provider_id::ProviderIdService;
 use crate::services::provider_id_service::GetIdentityBundle;
 use anyhow::{anyhow, bail, Result};
 use base::hex_utils::short_hex_string;
-use base::snp::snp_core_types::PrivateProviderIdentityBundle;
+use base::protocol_version::{parse_version, ProtocolFeature};
+use base::server_config_service::{ServerConfigService, MAX_FILE_SIZE_CONFIG_KEY};
+use base::snp::snp_core_types::{ClientServiceData, PrivateProviderIdentityBundle};
+use base::snp::snp_server_api::dr_message::Data;
 use base::snp::snp_server_api::{
-    ClientMessagesMetadata, ForwardMessagePayload, ForwardMessageRequest, ForwardMessageResponse,
-    MessageType, TypedMessage,
+    ForwardMessagePayload, ForwardMessageRequest, ForwardMessageResponse, MessageType, TypedMessage,
 };
+use base::time_utils::now;
 use base::typed_msgs_dispatcher::{
     Subscribe, TypedMessageHandler, TypedMessagesDispatcher, Unsubscribe,
 };
-use bytes::Bytes;
 use chrono::prelude::*;
 use common::aead::AEAD;
+use common::sealed_sender::is_valid_delivery_token;
 use xactor::*;
 
 /// MessageForwardingService is a system service which handles ForwardMessageRequests.
@@ -131,40 +134,65 @@ impl Handler<TypedMessageHandler> for MessageForwardingService {
 
         // step 5 - verify that this provider is serving the designated receiver
         let ika = payload.get_receiver_pub_key()?;
-        if ClientsDataService::get_client_service_data(&ika)
+        let client_data = ClientsDataService::get_client_service_data(&ika)
             .await?
-            .is_none()
-        {
-            bail!("unrecognized client - not served by this provider")
-        }
-
-        // Step 6 - Store message and message metadata for client
+            .ok_or_else(|| anyhow!("unrecognized client - not served by this provider"))?;
 
         let data = payload
             .dr_message
             .ok_or_else(|| anyhow!("missing payload data"))?;
 
-        debug!(
-            "Storing a message to client: {:?}",
-            short_hex_string(ika.as_ref())
-        );
+        // the receiver's terms limit the size of messages we route to it
+        let max_file_size = MessageForwardingService::max_file_size(&client_data).await?;
+        if max_file_size != 0 && data.encoded_len() as u64 > max_file_size {
+            bail!(
+                "message is larger than the max file size of {} bytes",
+                max_file_size
+            )
+        }
 
-        let msg_meta_data = ClientsDataService::store_new_message_for_client(ika, data).await?;
+        // a sealed-sender message doesn't identify its sender to us. It is delivered only to clients whose
+        // protocol version supports it and only when authorized by the delivery token that the receiver issued.
+        if let Some(Data::SealedSenderMessage(_)) = data.data {
+            if !ProtocolFeature::SealedSender
+                .is_enabled(&parse_version(&client_data.protocol_version)?)
+            {
+                bail!("sealed-sender messages are not supported by the client's protocol version")
+            }
+            if !is_valid_delivery_token(&client_data.delivery_token, &payload.delivery_token) {
+                bail!("unauthorized sealed-sender message - invalid delivery token")
+            }
+        }
 
-        let forwarded_msg = ClientMessagesMetadata {
-            messages_metadata: vec![msg_meta_data],
-        };
-        let mut buff: Vec<u8> = Vec::with_capacity(forwarded_msg.encoded_len());
-        forwarded_msg.encode(&mut buff)?;
-
-        // Attempt to send the metadata to the client but don't fail on error.
-        // In case there is not connection with client the meta-data about the message will be sent to the client next time he connects.
-        let _ = ClientsService::send_message_to_client(SendMessageToClient {
-            client_id: ika,
-            message_type: MessageType::ClientMessagesMetadata,
-            message: Bytes::from(buff),
-        })
-        .await;
+        // the sender asked us to drop the message if it isn't delivered by its expiry time
+        if payload.expires != 0 && payload.expires <= now() {
+            debug!("dropping an expired message to client");
+        } else if client_data.service_ended != 0 {
+            // Client moved to another provider - hand over the message or reject it
+            MessageForwardingService::handle_former_client_message(
+                &ika,
+                &client_data,
+                data,
+                payload.expires,
+            )
+            .await?;
+        } else {
+            // Step 6 - Store message and message metadata for client
+
+            debug!(
+                "Storing a message to client: {:?}",
+                short_hex_string(ika.as_ref())
+            );
+
+            let _ = ClientsDataService::store_new_message_for_client(ika, data, payload.expires)
+                .await?;
+
+            // Attempt to push the metadata to the client but don't fail on error.
+            // In case there is no connection with client the meta-data about the message will be sent to the client next time he connects.
+            if let Err(e) = ClientsService::notify_new_client_message(ika).await {
+                warn!("failed to notify client messages stream: {:?}", e);
+            }
+        }
 
         // Step 7 - create and return response to the forwarding provider to ack we got it and going to forward to the designated client
         let resp = ForwardMessageResponse {};
@@ -178,6 +206,23 @@ impl Handler<TypedMessageHandler> for MessageForwardingService {
             receiver: None,
             sender: None,
             signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
         })
     }
 }
+
+impl MessageForwardingService {
+    /// Returns the max size of a message routed to a client. Clients are limited by their service terms
+    async fn max_file_size(service_data: &ClientServiceData) -> Result<u64> {
+        if let Some(terms) = service_data.service_terms.as_ref() {
+            return Ok(terms.max_file_size);
+        }
+
+        Ok(
+            ServerConfigService::get_u64(MAX_FILE_SIZE_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default(),
+        )
+    }
+}
@@ -0,0 +1,169 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::clients_data::service::ClientsDataService;
+use crate::services::messaging::msg_forwarding_service::MessageForwardingService;
+use crate::services::server_to_server::server_to_server_service::{
+    SendMessageToServer, ServerToServerService,
+};
+use anyhow::{anyhow, bail, Result};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_core_types::{ClientIdentityBundle, ClientServiceData, DialupInfo, PublicKey};
+use base::snp::snp_server_api::{
+    DrMessage, ForwardMessagePayload, ForwardMessageRequest, MessageType,
+};
+use base::time_utils::expiration_time;
+use bytes::Bytes;
+use common::aead::AEAD;
+use rand_core::OsRng;
+use xactor::*;
+
+/// Messages handover to the new provider of a client that stopped being served by this provider.
+/// Within the handover grace period this provider forwards messages designated to such a client to its new provider.
+/// When the client didn't tell us who its new provider is, messages are held here for it.
+impl MessageForwardingService {
+    /// Handle a message designated to a client that stopped being served by this provider.
+    /// Returns an error once the handover grace period is over so the message sender can obtain the
+    /// client's current bundle and retry with its new provider.
+    pub(crate) async fn handle_former_client_message(
+        client_id: &ed25519_dalek::PublicKey,
+        client_data: &ClientServiceData,
+        message: DrMessage,
+        expires: u64,
+    ) -> Result<()> {
+        if ClientsDataService::handover_grace_period_expired(client_data).await? {
+            bail!("client is no longer served by this provider")
+        }
+
+        if let Some(next_bundle) = client_data.next_client_bundle.as_ref() {
+            match MessageForwardingService::forward_to_client_provider(
+                next_bundle,
+                message.clone(),
+                &client_data.delivery_token,
+                expires,
+            )
+            .await
+            {
+                Ok(()) => return Ok(()),
+                Err(e) => warn!(
+                    "failed to forward message to client's new provider: {:?}",
+                    e
+                ),
+            }
+        }
+
+        debug!(
+            "holding a message for former client: {:?}",
+            short_hex_string(client_id.as_ref())
+        );
+
+        let _ =
+            ClientsDataService::store_new_message_for_client(*client_id, message, expires).await?;
+        Ok(())
+    }
+
+    /// Forward all messages pending delivery to a client to its new provider.
+    /// Messages which could not be forwarded are kept in store for the client.
+    pub(crate) async fn handover_pending_messages(
+        client_id: &ed25519_dalek::PublicKey,
+        next_bundle: &ClientIdentityBundle,
+        delivery_token: &[u8],
+    ) -> Result<()> {
+        ClientsDataService::delete_expired_messages(client_id).await?;
+        let meta_data = ClientsDataService::get_client_pending_messages(client_id).await?;
+        let mut forwarded_ids: Vec<u64> = vec![];
+
+        for meta in meta_data.messages_metadata.iter() {
+            let id = meta.id;
+            let expires = expiration_time(meta.received_date, meta.ttl).unwrap_or_default();
+            for message in ClientsDataService::load_client_messages(vec![id]).await? {
+                match MessageForwardingService::forward_to_client_provider(
+                    next_bundle,
+                    message,
+                    delivery_token,
+                    expires,
+                )
+                .await
+                {
+                    Ok(()) => forwarded_ids.push(id),
+                    Err(e) => warn!("failed to forward pending message {}: {:?}", id, e),
+                }
+            }
+        }
+
+        debug!(
+            "forwarded {} pending message(s) to client's new provider",
+            forwarded_ids.len()
+        );
+
+        ClientsDataService::delete_client_messages(client_id, forwarded_ids).await
+    }
+
+    /// Send a message designated to a client to the provider in the client's bundle in a ForwardMessageRequest.
+    /// The request payload is encrypted to the provider's pre-key, same as a client routing a message via its
+    /// provider does. On handover, the client's delivery token authorizes delivery of sealed-sender messages by
+    /// the new provider as the client gives the same token to all its providers.
+    pub(crate) async fn forward_to_client_provider(
+        client_bundle: &ClientIdentityBundle,
+        message: DrMessage,
+        delivery_token: &[u8],
+        expires: u64,
+    ) -> Result<()> {
+        let provider_bundle = client_bundle
+            .provider_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing client provider bundle"))?;
+
+        let dialup_info = DialupInfo::best_grpc_endpoint(&provider_bundle.dial_up_info)
+            .ok_or_else(|| anyhow!("missing client provider dialup info"))?
+            .clone();
+
+        let payload = ForwardMessagePayload {
+            receiver: Some(client_bundle.get_client_entity()?),
+            dr_message: Some(message),
+            delivery_token: delivery_token.to_vec(),
+            expires,
+        };
+
+        // eph-dh with the provider pre-key so only it can decrypt the payload
+        let pre_key = provider_bundle.get_provider_x25519_pre_key()?;
+        let eph_key = x25519_dalek::EphemeralSecret::new(OsRng);
+        let eph_pub = x25519_dalek::PublicKey::from(&eph_key);
+        let shared_secret = eph_key.diffie_hellman(&pre_key);
+        let ad = common::edh::compute_ad(&eph_pub, &pre_key);
+
+        use prost::Message;
+        let mut buf = Vec::with_capacity(payload.encoded_len());
+        payload.encode(&mut buf)?;
+        let enc_payload = AEAD::encrypt(Bytes::from(buf), shared_secret.as_bytes(), &ad)?;
+
+        let forward_req = ForwardMessageRequest {
+            receiver: provider_bundle.provider_id.clone(),
+            receiver_bundle_id: provider_bundle.time_stamp,
+            sender_ephemeral_key: Some(PublicKey {
+                key: eph_pub.as_bytes().to_vec(),
+            }),
+            enc_payload: enc_payload.to_vec(),
+        };
+
+        let mut buff: Vec<u8> = Vec::with_capacity(forward_req.encoded_len());
+        forward_req.encode(&mut buff)?;
+
+        let resp = ServerToServerService::from_registry()
+            .await?
+            .call(SendMessageToServer {
+                dialup_info,
+                receiver_id: provider_bundle.get_provider_id_ed25519_public_key()?,
+                message_type: MessageType::ForwardMessageRequest,
+                message: Bytes::from(buff),
+            })
+            .await??;
+
+        if resp.msg_type != MessageType::ForwardMessageResponse as i32 {
+            bail!("unexpected response from client provider")
+        }
+
+        Ok(())
+    }
+}
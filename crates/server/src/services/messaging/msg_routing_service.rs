@@ -23,6 +23,9 @@ use base::snp::snp_server_api::{
 /// MessageRoutingService is a service which handles RouteMessageRequest requests.
 /// A client send to this provider a RouteMessageRequest that it wants to route to another provider.
 /// This is use in the core client-to-client messaging core algorithm of SNP.
+/// The routed request payload is encrypted to the other provider, so this provider only learns that its client sent
+/// a message to a client of the other provider. When the message is a sealed-sender message, the other provider
+/// doesn't learn who sent it either.
 /// Note that is not an internal messages router / dispatcher. It is designed for handling remote route requests.
 #[derive(Debug, Default)]
 pub struct MessageRoutingService {}
@@ -74,11 +77,13 @@ impl Handler<TypedMessageHandler> for MessageRoutingService {
             .get_ika()
             .map_err(|_| anyhow!("missing sender from msg"))?;
 
-        if ClientsDataService::get_client_service_data(&ika)
-            .await?
-            .is_none()
-        {
-            bail!("unrecognized client - not served by this provider")
+        match ClientsDataService::get_client_service_data(&ika).await? {
+            None => bail!("unrecognized client - not served by this provider"),
+            Some(data) if data.service_ended != 0 => {
+                bail!("client stopped being served by this provider")
+            }
+            Some(data) if data.service_suspended => bail!("client service is suspended"),
+            _ => {}
         }
 
         // Step 3 - verify that the request is a ForwardMessageRequest
@@ -151,6 +156,8 @@ impl Handler<TypedMessageHandler> for MessageRoutingService {
             receiver: None,
             sender: None,
             signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
         })
     }
 }
@@ -45,6 +45,8 @@ pub async fn new_outgoing_message(
         }),
         sender: Some(bundle.get_provider_id_entity()?.clone()),
         signature: None,
+        sender_delivery_token: vec![],
+        sender_protocol_version: "".into(),
     };
 
     let key_pair = bundle
@@ -7,10 +7,15 @@
 mod admin_service;
 mod blockchain_service;
 mod clients_service;
+mod cover_traffic_service;
+mod json_gateway;
 mod messaging;
+mod protocol_versions;
 mod provider_id;
 mod provider_id_service;
 mod public_service;
+mod rate_limiter;
+mod stop_service;
 mod terms_service;
 
 pub mod server_service;
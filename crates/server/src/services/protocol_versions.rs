@@ -0,0 +1,41 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+// grpc Status is returned to callers of the negotiation helpers
+#![allow(clippy::result_large_err)]
+
+use anyhow::Result;
+use base::protocol_version::{VersionRange, MIN_SNP_PROTOCOL_VERSION, SNP_PROTOCOL_VERSION};
+use base::server_config_service::{ServerConfigService, MIN_API_VERSION_CONFIG_KEY};
+use semver::Version;
+use tonic::Status;
+
+/// Returns the protocol versions this provider accepts from callers and advertises in its dialup info.
+/// Providers may raise the min version in config to stop serving old peers once a fleet was upgraded.
+pub(crate) async fn supported_protocol_versions() -> Result<VersionRange> {
+    let min_version = ServerConfigService::get(MIN_API_VERSION_CONFIG_KEY.into())
+        .await?
+        .unwrap_or_else(|| MIN_SNP_PROTOCOL_VERSION.into());
+
+    let range = VersionRange::new(&min_version, SNP_PROTOCOL_VERSION)?;
+
+    // versions older than the ones this build supports are never accepted
+    Ok(VersionRange {
+        min: std::cmp::max(range.min, VersionRange::snp_protocol().min),
+        max: range.max,
+    })
+}
+
+/// Negotiate a protocol version with a caller which implements a version.
+/// Callers with incompatible versions are rejected with a typed rejection which includes the supported versions.
+pub(crate) async fn negotiate_protocol_version(version: &str) -> Result<Version, Status> {
+    supported_protocol_versions()
+        .await
+        .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?
+        .negotiate_with(version)
+        .map_err(|e| {
+            debug!("rejecting caller: {}", e);
+            e.into_status()
+        })
+}
@@ -2,13 +2,19 @@
 //  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use crate::services::protocol_versions::supported_protocol_versions;
 use crate::services::server_service::SNP_PROTOCOL_VERSION;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use base::api_types_extensions::Signed;
 use base::hex_utils::short_hex_string;
-use base::server_config_service::{GetValue, ServerConfigService};
+use base::server_config_service::{
+    GetValue, ServerConfigService, API_VERSION_CONFIG_KEY, GRPC_HOST_CONFIG_KEY,
+    GRPC_SERVER_PORT_CONFIG_KEY, JSON_HTTP_PORT_CONFIG_KEY, NET_ID_CONFIG_KEY,
+    PEER_NAME_CONFIG_KEY, PUBLIC_HOST_CONFIG_KEY, PUBLIC_PORT_CONFIG_KEY, TLS_CERT_FILE_CONFIG_KEY,
+    TLS_KEY_FILE_CONFIG_KEY,
+};
 use base::snp::snp_core_types::{
-    ApiEndPoint, DialupInfo, PrivateProviderIdentityBundle, ProviderNetInfo,
+    ApiEndPoint, DialupInfo, EntityId, PrivateProviderIdentityBundle, ProviderNetInfo, PublicKey,
 };
 use base::snp::snp_payments::Address;
 use byteorder::{BigEndian, ByteOrder};
@@ -24,6 +30,7 @@ use xactor::*;
 const ACCOUNT_KEYPAIR_KEY: &str = "p_account_keypair_key";
 const CURR_ID_BUNDLE_KEY: &str = "p_curr_id_bundle_key";
 const ID_KEYPAIR_KEY: &str = "p_id_keypair_key";
+const CURR_NET_INFO_KEY: &str = "p_curr_net_info_key";
 
 /// ProviderIdService maintains provider id data and provides data service to clients via
 /// a system service interface.
@@ -67,51 +74,203 @@ impl ProviderIdService {
         Ok(())
     }
 
-    /// Returns signed provider current ProviderNetInfo
+    /// Returns signed provider current ProviderNetInfo.
+    /// The net info is persisted and is only computed from config when none was saved before.
     pub async fn get_provider_net_info(&mut self) -> Result<ProviderNetInfo> {
-        // todo: this should also be persisted and not computed on every startup
+        match self.load_net_info().await? {
+            Some(info) => Ok(info),
+            None => {
+                let info = self.new_net_info(self.get_dialup_info().await?)?;
+                self.save_net_info(&info).await?;
+                Ok(info)
+            }
+        }
+    }
+
+    /// Update the provider persisted net info from the configured endpoints.
+    /// When the endpoints changed, the net info is re-signed and a new identity bundle which advertises
+    /// the new endpoints is created. Returns true if a new bundle was created and should be published.
+    pub async fn refresh_net_info(&mut self) -> Result<bool> {
         let dialup_info = self.get_dialup_info().await?;
-        let id_bundle = self.get_identity_bundle(false).await?;
-        let entity = id_bundle.get_provider_id_entity()?;
+
+        let net_info_changed = match self.load_net_info().await? {
+            Some(info) => info.dial_up_info != dialup_info,
+            None => true,
+        };
+
+        if net_info_changed {
+            let info = self.new_net_info(dialup_info.clone())?;
+            self.save_net_info(&info).await?;
+            info!("provider net info updated");
+        }
+
+        let bundle = self.get_identity_bundle(false).await?;
+        let bundle_dialup_info = &bundle
+            .public_bundle
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing public bundle"))?
+            .dial_up_info;
+
+        if *bundle_dialup_info == dialup_info {
+            return Ok(false);
+        }
+
+        info!("provider endpoints changed - creating a new identity bundle...");
+        self.get_identity_bundle(true).await?;
+        Ok(true)
+    }
+
+    /// Returns a new signed net info for the provided endpoints
+    fn new_net_info(&self, dialup_info: Vec<DialupInfo>) -> Result<ProviderNetInfo> {
+        let key_pair = self
+            .provider_id
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing provider id"))?;
+
         let mut info = ProviderNetInfo {
-            provider_id: Some(entity.clone()),
-            dial_up_info: Some(dialup_info),
+            provider_id: Some(EntityId {
+                public_key: Some(PublicKey {
+                    key: key_pair.public.as_ref().to_vec(),
+                }),
+                nickname: dialup_info
+                    .first()
+                    .map(|d| d.name.clone())
+                    .unwrap_or_default(),
+            }),
+            dial_up_info: dialup_info,
             signature: None,
         };
-        info.sign(self.provider_id.as_ref().unwrap())?;
+        info.sign(key_pair)?;
 
         Ok(info)
     }
 
-    /// get_dialup_info gets the provider current dialup info
-    pub async fn get_dialup_info(&self) -> Result<DialupInfo> {
-        let config = ServerConfigService::from_registry().await?;
+    /// Load the provider's persisted net info. Returns None if it was not saved before.
+    async fn load_net_info(&self) -> Result<Option<ProviderNetInfo>> {
+        let read_item = ReadItem {
+            key: CURR_NET_INFO_KEY.into(),
+            cf: db_service::PROVIDER_COL_FAMILY,
+        };
 
-        let name = config
-            .call(GetValue("peer_name".into()))
+        match DatabaseService::read(read_item).await? {
+            Some(data) => Ok(Some(
+                ProviderNetInfo::decode(data.0.as_ref())
+                    .map_err(|e| anyhow!("failed to decode net info: {:?}", e))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Persist the provider's net info
+    async fn save_net_info(&self, info: &ProviderNetInfo) -> Result<()> {
+        let mut buf = Vec::with_capacity(info.encoded_len());
+        info.encode(&mut buf)?;
+
+        DatabaseService::write(WriteItem {
+            data: DataItem {
+                key: CURR_NET_INFO_KEY.into(),
+                value: Bytes::from(buf),
+            },
+            cf: db_service::PROVIDER_COL_FAMILY,
+            ttl: 0, // we store this forever
+        })
+        .await
+    }
+
+    /// get_dialup_info gets the provider's configured api endpoints.
+    /// When an endpoints list is configured, it is advertised as is. Otherwise, the grpc api endpoint is
+    /// advertised on the public host and port. It is tls secured when a tls cert and key are configured.
+    /// The json/http gateway endpoint is advertised when a gateway port is configured.
+    pub async fn get_dialup_info(&self) -> Result<Vec<DialupInfo>> {
+        let name = ServerConfigService::get(PEER_NAME_CONFIG_KEY.into())
             .await?
             .ok_or_else(|| anyhow!("expected value in config"))?;
 
-        let grpc_server_port = config
-            .call(GetValue("grpc_server_port".into()))
+        let net_id = ServerConfigService::get_u64(NET_ID_CONFIG_KEY.into())
             .await?
-            .ok_or_else(|| anyhow!("expected value in config"))?
-            .parse::<u32>()
-            .map_err(|e| anyhow!("invalid port number in config: {:?}", e))?;
-
-        // todo: get supported grpc api version from config
-
-        // todo: take provider ip address from config in the future. For now, localhost only.
-        let address = "[::1]";
-
-        Ok(DialupInfo {
-            end_point: ApiEndPoint::GrpcWeb2 as i32,
-            api_version: SNP_PROTOCOL_VERSION.into(),
-            ip_address: address.into(),
-            port: grpc_server_port,
-            net_id: 0,
-            name,
-        })
+            .unwrap_or_default() as u32;
+
+        let api_version = ServerConfigService::get(API_VERSION_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_else(|| SNP_PROTOCOL_VERSION.into());
+
+        // oldest version accepted from callers
+        let min_api_version = supported_protocol_versions().await?.min.to_string();
+
+        if let Some(endpoints) = ServerConfigService::get_endpoints().await? {
+            if endpoints.is_empty() {
+                bail!("endpoints config must include at least one endpoint")
+            }
+
+            return endpoints
+                .iter()
+                .map(|e| {
+                    DialupInfo::from_endpoint_config(
+                        e,
+                        &api_version,
+                        &min_api_version,
+                        net_id,
+                        &name,
+                    )
+                })
+                .collect();
+        }
+
+        let port = match ServerConfigService::get_u64(PUBLIC_PORT_CONFIG_KEY.into()).await? {
+            Some(port) => port,
+            None => ServerConfigService::get_u64(GRPC_SERVER_PORT_CONFIG_KEY.into())
+                .await?
+                .ok_or_else(|| anyhow!("expected value in config"))?,
+        } as u32;
+
+        let address = match ServerConfigService::get(PUBLIC_HOST_CONFIG_KEY.into()).await? {
+            Some(host) => host,
+            None => ServerConfigService::get(GRPC_HOST_CONFIG_KEY.into())
+                .await?
+                .ok_or_else(|| anyhow!("expected value in config"))?,
+        };
+
+        let tls = ServerConfigService::get(TLS_CERT_FILE_CONFIG_KEY.into())
+            .await?
+            .is_some()
+            && ServerConfigService::get(TLS_KEY_FILE_CONFIG_KEY.into())
+                .await?
+                .is_some();
+
+        let mut endpoints = vec![DialupInfo {
+            end_point: if tls {
+                ApiEndPoint::GrpcWeb2s as i32
+            } else {
+                ApiEndPoint::GrpcWeb2 as i32
+            },
+            api_version: api_version.clone(),
+            ip_address: address.clone(),
+            port,
+            net_id,
+            name: name.clone(),
+            min_api_version: min_api_version.clone(),
+        }];
+
+        if let Some(json_http_port) = ServerConfigService::get_u64(JSON_HTTP_PORT_CONFIG_KEY.into())
+            .await?
+            .filter(|p| *p != 0)
+        {
+            endpoints.push(DialupInfo {
+                end_point: if tls {
+                    ApiEndPoint::JsonHttps as i32
+                } else {
+                    ApiEndPoint::JsonHttp as i32
+                },
+                api_version,
+                ip_address: address,
+                port: json_http_port as u32,
+                net_id,
+                name,
+                min_api_version,
+            });
+        }
+
+        Ok(endpoints)
     }
 
     /// Get an id bundle from the db by a unique bundle id.
@@ -216,7 +375,9 @@ impl ProviderIdService {
 
         let config = ServerConfigService::from_registry().await?;
 
-        // todo: read net_id from config
+        let net_id = ServerConfigService::get_u64(NET_ID_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default() as u32;
 
         let nickname = config
             .call(GetValue("peer_name".into()))
@@ -240,7 +401,7 @@ impl ProviderIdService {
             &dialup_info,
             nickname,
             &payment_address,
-            0,
+            net_id,
         )?;
 
         self.save_bundle(&bundle).await?;
@@ -259,8 +420,13 @@ impl ProviderIdService {
             return self.create_new_bundle().await;
         }
 
+        let net_id = ServerConfigService::get_u64(NET_ID_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default() as u32;
+
+        // a new bundle is needed when the provider moved to another network
         match self.load_latest_bundle().await? {
-            Some(b) => Ok(b),
+            Some(b) if b.public_bundle.as_ref().is_some_and(|p| p.net_id == net_id) => Ok(b),
             _ => Ok(self.create_new_bundle().await?),
         }
     }
@@ -346,89 +512,125 @@ impl ProviderIdService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use db::db_service::Destroy;
+    use crate::test_helpers::run_with_test_db;
     use ed25519_dalek::ed25519::signature::Signature;
     use ed25519_dalek::Verifier;
 
-    // helper
-    async fn delete_db() {
-        let db = DatabaseService::from_registry().await.unwrap();
-        let _ = db.call(Destroy).await.unwrap();
+    #[test]
+    fn test_dialup_info() {
+        run_with_test_db(async {
+            let mut p = ProviderIdService::default();
+            p.init().await.unwrap();
+            p.get_dialup_info().await.unwrap();
+        })
     }
 
-    #[tokio::test]
-    async fn test_dialup_info() {
-        let mut p = ProviderIdService::default();
-        p.init().await.unwrap();
-        p.get_dialup_info().await.unwrap();
-        delete_db().await;
+    #[test]
+    fn test_refresh_net_info() {
+        run_with_test_db(async {
+            let mut p = ProviderIdService::default();
+            p.init().await.unwrap();
+
+            // net info is persisted on first refresh and the initial bundle already advertises the endpoints
+            assert!(!p.refresh_net_info().await.unwrap());
+            let info = p.get_provider_net_info().await.unwrap();
+            info.verify_signature().unwrap();
+            assert_eq!(info.dial_up_info, p.get_dialup_info().await.unwrap());
+
+            // changed endpoints are re-signed and advertised in a new bundle
+            let bundle = p.get_identity_bundle(false).await.unwrap();
+            ServerConfigService::set(PUBLIC_HOST_CONFIG_KEY.into(), "provider.example.com".into())
+                .await
+                .unwrap();
+            assert!(p.refresh_net_info().await.unwrap());
+
+            let info = p.get_provider_net_info().await.unwrap();
+            info.verify_signature().unwrap();
+            assert_eq!(
+                info.get_dialup_info().unwrap().ip_address,
+                "provider.example.com"
+            );
+
+            let new_bundle = p
+                .get_identity_bundle(false)
+                .await
+                .unwrap()
+                .public_bundle
+                .unwrap();
+            assert_ne!(
+                new_bundle.time_stamp,
+                bundle.public_bundle.unwrap().time_stamp
+            );
+            assert_eq!(new_bundle.dial_up_info, info.dial_up_info);
+        })
     }
 
-    #[tokio::test]
-    async fn test_load_bundle_new_provider() {
-        let mut p = ProviderIdService::default();
-        p.init().await.unwrap();
-        let res = p.load_latest_bundle().await.unwrap();
-        assert!(
-            res.is_some(),
-            "expected latest bundle when provided is loaded"
-        );
-        delete_db().await;
+    #[test]
+    fn test_load_bundle_new_provider() {
+        run_with_test_db(async {
+            let mut p = ProviderIdService::default();
+            p.init().await.unwrap();
+            let res = p.load_latest_bundle().await.unwrap();
+            assert!(
+                res.is_some(),
+                "expected latest bundle when provided is loaded"
+            );
+        })
     }
 
-    #[tokio::test]
-    async fn test_new_bundle() {
-        let mut p = ProviderIdService::default();
-        p.init().await.unwrap();
-
-        let bundle = p.create_new_bundle().await.unwrap();
-        let bundle1 = p.load_latest_bundle().await.unwrap().unwrap();
-        assert_eq!(
-            bundle.public_bundle.unwrap().time_stamp,
-            bundle1.public_bundle.unwrap().time_stamp,
-            "expected latest bundle to be the newly created one"
-        );
-
-        delete_db().await;
+    #[test]
+    fn test_new_bundle() {
+        run_with_test_db(async {
+            let mut p = ProviderIdService::default();
+            p.init().await.unwrap();
+
+            let bundle = p.create_new_bundle().await.unwrap();
+            let bundle1 = p.load_latest_bundle().await.unwrap().unwrap();
+            assert_eq!(
+                bundle.public_bundle.unwrap().time_stamp,
+                bundle1.public_bundle.unwrap().time_stamp,
+                "expected latest bundle to be the newly created one"
+            );
+        })
     }
 
-    #[tokio::test]
-    async fn test_signature_verification() {
-        let mut p = ProviderIdService::default();
-        p.init().await.unwrap();
+    #[test]
+    fn test_signature_verification() {
+        run_with_test_db(async {
+            let mut p = ProviderIdService::default();
+            p.init().await.unwrap();
 
-        let mut bundle = p.create_new_bundle().await.unwrap().public_bundle.unwrap();
+            let mut bundle = p.create_new_bundle().await.unwrap().public_bundle.unwrap();
 
-        // get the signature
-        let signature = ed25519_dalek::Signature::from_bytes(
-            bundle.provider_signature.unwrap().signature.as_slice(),
-        )
-        .expect("failed to create signature from data");
+            // get the signature
+            let signature = ed25519_dalek::Signature::from_bytes(
+                bundle.provider_signature.unwrap().signature.as_slice(),
+            )
+            .expect("failed to create signature from data");
 
-        // remove signature and get binary data of all other data
-        bundle.provider_signature = None;
-        let mut buf = Vec::with_capacity(bundle.encoded_len());
-        bundle
-            .encode(&mut buf)
-            .expect("failed to get bundle to binary data");
-
-        // restore public key from data
-        let public_key = ed25519_dalek::PublicKey::from_bytes(
+            // remove signature and get binary data of all other data
+            bundle.provider_signature = None;
+            let mut buf = Vec::with_capacity(bundle.encoded_len());
             bundle
-                .provider_id
-                .unwrap()
-                .public_key
-                .unwrap()
-                .key
-                .as_slice(),
-        )
-        .expect("failed to create public key");
-
-        assert!(
-            public_key.verify(buf.as_slice(), &signature).is_ok(),
-            "sig verification failure"
-        );
-
-        delete_db().await;
+                .encode(&mut buf)
+                .expect("failed to get bundle to binary data");
+
+            // restore public key from data
+            let public_key = ed25519_dalek::PublicKey::from_bytes(
+                bundle
+                    .provider_id
+                    .unwrap()
+                    .public_key
+                    .unwrap()
+                    .key
+                    .as_slice(),
+            )
+            .expect("failed to create public key");
+
+            assert!(
+                public_key.verify(buf.as_slice(), &signature).is_ok(),
+                "sig verification failure"
+            );
+        })
     }
 }
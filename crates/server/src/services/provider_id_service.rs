@@ -96,6 +96,20 @@ impl Handler<GetProviderNetInfo> for ProviderIdService {
 
 /////////////////////
 
+/// Update the provider's persisted net info from config.
+/// Returns true if the provider's endpoints changed and a new identity bundle was created.
+#[message(result = "Result<bool>")]
+pub struct RefreshNetInfo;
+
+#[async_trait::async_trait]
+impl Handler<RefreshNetInfo> for ProviderIdService {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, _msg: RefreshNetInfo) -> Result<bool> {
+        self.refresh_net_info().await
+    }
+}
+
+/////////////////////
+
 /// Get the provider's current identity bundle
 #[message(result = "Result<PrivateProviderIdentityBundle>")]
 pub struct GetCurrentIdentityBundle();
@@ -112,27 +126,38 @@ impl Handler<GetCurrentIdentityBundle> for ProviderIdService {
 }
 
 /////////////////////
+#[message(result = "Result<PrivateProviderIdentityBundle>")]
+pub struct RotateIdentityBundle;
+
+/// Create a new provider identity bundle with a new pre-key and make it the current bundle.
+/// Previous bundles remain available by id so messages sent using them can still be handled.
+#[async_trait::async_trait]
+impl Handler<RotateIdentityBundle> for ProviderIdService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: RotateIdentityBundle,
+    ) -> Result<PrivateProviderIdentityBundle> {
+        self.get_identity_bundle(true).await
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use base::test_helpers::enable_logger;
-    use db::db_service::{DatabaseService, Destroy};
-
-    #[tokio::test]
-    async fn test_provider_init_first_run() {
-        enable_logger();
-
-        let db = DatabaseService::from_registry().await.unwrap();
-        let provider = ProviderIdService::from_registry().await.unwrap();
-        let bundle: PrivateProviderIdentityBundle = provider
-            .call(GetCurrentIdentityBundle {})
-            .await
-            .unwrap()
-            .unwrap();
-
-        debug!("bundle id: {}", bundle.public_bundle.unwrap().time_stamp);
-
-        let _ = db.call(Destroy).await.expect("failed to delete the db");
+    use crate::test_helpers::run_with_test_db;
+
+    #[test]
+    fn test_provider_init_first_run() {
+        run_with_test_db(async {
+            let provider = ProviderIdService::from_registry().await.unwrap();
+            let bundle: PrivateProviderIdentityBundle = provider
+                .call(GetCurrentIdentityBundle {})
+                .await
+                .unwrap()
+                .unwrap();
+
+            debug!("bundle id: {}", bundle.public_bundle.unwrap().time_stamp);
+        })
     }
 }
@@ -4,10 +4,12 @@
 
 use crate::clients_data::service::ClientsDataService;
 use crate::services::blockchain_service::{BlockchainService, PublishClientBundleMessage};
+use crate::services::protocol_versions::supported_protocol_versions;
 use crate::services::provider_id::ProviderIdService;
 use crate::services::provider_id_service::GetCurrentIdentityBundle;
+use crate::services::terms_service::{GetCurrentTerms, TermsService};
 use anyhow::{anyhow, Result};
-use base::api_types_extensions::Signed;
+use base::api_types_extensions::{NetworkScoped, Signed};
 use base::snp::snp_core_types::{
     ClientServiceData, PrivateProviderIdentityBundle, ProviderSignedClientIdentityBundle,
 };
@@ -18,6 +20,7 @@ use base::typed_msgs_dispatcher::{
     Subscribe, TypedMessageHandler, TypedMessagesDispatcher, Unsubscribe,
 };
 use chrono::prelude::*;
+use common::network_salt::net_id;
 use xactor::*;
 
 /// PublicService is an app-level networking protocol handler that is responsible
@@ -82,21 +85,44 @@ impl Handler<TypedMessageHandler> for PublicService {
             .bundle
             .ok_or_else(|| anyhow!("missing bundle for req"))?;
 
+        // only clients on this provider's network may be served
+        client_bundle.verify_net_id(net_id())?;
+
         let client_id = client_bundle.get_client_id_ed25519_public_key()?;
 
-        if ClientsDataService::get_client_service_data(&client_id)
-            .await?
-            .is_some()
-        {
-            return Err(anyhow!("client is already serviced by this provider"));
+        // a client who previously stopped being serviced by this provider may come back
+        if let Some(data) = ClientsDataService::get_client_service_data(&client_id).await? {
+            if data.service_ended == 0 {
+                return Err(anyhow!("client is already serviced by this provider"));
+            }
+            if data.service_suspended {
+                return Err(anyhow!("client service is suspended"));
+            }
         }
 
+        // features of the protocol version negotiated with the client are used in messages to it
+        let protocol_version = supported_protocol_versions()
+            .await?
+            .negotiate_with(&msg.0.sender_protocol_version)?;
+
         let mut signed_client_bundle = client_bundle.clone();
 
+        // the client is serviced under the provider's current terms
+        let service_terms = TermsService::from_registry()
+            .await?
+            .call(GetCurrentTerms)
+            .await??
+            .service_terms;
+
         let client_data = ClientServiceData {
             service_started: 0,
             service_ended: 0,
             client_identity_bundle: Some(client_bundle),
+            next_client_bundle: None,
+            service_suspended: false,
+            service_terms,
+            delivery_token: req.delivery_token,
+            protocol_version: protocol_version.to_string(),
         };
 
         // todo: save the signed client service request data in client data - evidence client agreed to terms of service plus how to charge him - fixed monthly, or pay per use?
@@ -150,6 +176,8 @@ impl Handler<TypedMessageHandler> for PublicService {
             receiver: None,
             sender: None,
             signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
         })
     }
 }
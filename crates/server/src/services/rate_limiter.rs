@@ -0,0 +1,483 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+// grpc Status is returned to callers of the rate limiter helpers
+#![allow(clippy::result_large_err)]
+
+use crate::clients_data::service::ClientsDataService;
+use anyhow::Result;
+use base::hex_utils::short_hex_string;
+use base::server_config_service::{
+    ServerConfigService, CLIENT_MESSAGES_PER_MINUTE_CONFIG_KEY, IP_REQUESTS_PER_MINUTE_CONFIG_KEY,
+};
+use base::snp::snp_core_types::ClientServiceData;
+use base::snp::snp_server_api::RequestRejection;
+use chrono::Utc;
+use ed25519_dalek::PublicKey;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tonic::Status;
+use xactor::*;
+
+/// Full buckets are pruned when more than this number of buckets are tracked.
+/// When none is full the least recently used buckets are evicted
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Expired proofs of work are pruned when more than this number of proofs of work are tracked.
+/// When none expired the proofs of work which expire first are evicted
+const MAX_TRACKED_POWS: usize = 10_000;
+
+/// Fraction of a full map evicted at once so floods of distinct keys don't scan the map on every request
+const EVICTION_FRACTION: usize = 10;
+
+/// A token bucket which holds up to a minute worth of tokens and is refilled at a per minute rate
+#[derive(Debug, Clone)]
+pub(crate) struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+    last_used: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(per_minute: u32, now: Instant) -> Self {
+        TokenBucket {
+            tokens: per_minute as f64,
+            last_refill: now,
+            last_used: now,
+        }
+    }
+
+    /// Take count tokens from the bucket. Returns the time until count tokens are available when the bucket
+    /// doesn't hold them. count must not be larger than per_minute.
+    pub(crate) fn take(&mut self, count: u32, per_minute: u32, now: Instant) -> Option<Duration> {
+        self.refill(per_minute, now);
+        self.last_used = now;
+        if self.tokens >= count as f64 {
+            self.tokens -= count as f64;
+            return None;
+        }
+
+        let per_ms = per_minute as f64 / 60_000.0;
+        let wait_ms = ((count as f64 - self.tokens) / per_ms).ceil() as u64;
+        Some(Duration::from_millis(wait_ms.max(1)))
+    }
+
+    /// Returns true when the bucket was fully refilled - it may be discarded
+    pub(crate) fn is_full(&mut self, per_minute: u32, now: Instant) -> bool {
+        self.refill(per_minute, now);
+        self.tokens >= per_minute as f64
+    }
+
+    fn refill(&mut self, per_minute: u32, now: Instant) {
+        let elapsed_ms = now.saturating_duration_since(self.last_refill).as_millis() as f64;
+        self.tokens =
+            (self.tokens + elapsed_ms * per_minute as f64 / 60_000.0).min(per_minute as f64);
+        self.last_refill = now;
+    }
+}
+
+/// RateLimiterService limits the rate of requests providers accept per client and per remote ip address.
+/// Clients limits are set by their service terms. Limits of 0 disable rate limiting.
+/// It also remembers the proofs of work of new session requests until they expire so each may only be used once.
+/// Tracked buckets and proofs of work are bounded so floods of distinct ips, clients or proofs of work can't exhaust memory.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiterService {
+    clients: HashMap<[u8; 32], TokenBucket>,
+    ips: HashMap<IpAddr, TokenBucket>,
+    /// expiry time (unix nanos) of used proofs of work indexed by request ephemeral key and nonce
+    used_pows: HashMap<([u8; 32], u64), i64>,
+}
+
+impl Service for RateLimiterService {}
+
+#[async_trait::async_trait]
+impl Actor for RateLimiterService {
+    async fn started(&mut self, _ctx: &mut Context<Self>) -> Result<()> {
+        debug!("RateLimiterService started");
+        Ok(())
+    }
+}
+
+impl RateLimiterService {
+    /// Take a token for a request from a remote ip address
+    pub(crate) async fn check_ip(ip: IpAddr) -> Result<(), Status> {
+        let per_minute = ServerConfigService::get_u64(IP_REQUESTS_PER_MINUTE_CONFIG_KEY.into())
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?
+            .unwrap_or_default() as u32;
+
+        if per_minute == 0 {
+            return Ok(());
+        }
+
+        let retry_after = RateLimiterService::from_registry()
+            .await
+            .map_err(|_| Status::internal("failed to get rate limiter service"))?
+            .call(TakeIpToken { ip, per_minute })
+            .await
+            .map_err(|_| Status::internal("internal error - failed to call"))?;
+
+        match retry_after {
+            None => Ok(()),
+            Some(retry_after) => {
+                debug!("rate limited requests from ip: {}", ip);
+                Err(
+                    RequestRejection::ip_rate_limited(retry_after.as_millis() as u64)
+                        .into_status("too many requests from ip address"),
+                )
+            }
+        }
+    }
+
+    /// Take a token for a request from a client. Served clients are limited by their service terms.
+    /// Other entities are limited by the provider's current terms.
+    pub(crate) async fn check_client(client_id: &PublicKey) -> Result<(), Status> {
+        RateLimiterService::check_client_messages(client_id, 1).await
+    }
+
+    /// Take a token for each of the messages a client request makes this provider send, such as a group message
+    /// fan out to its receivers
+    pub(crate) async fn check_client_messages(
+        client_id: &PublicKey,
+        count: u32,
+    ) -> Result<(), Status> {
+        let per_minute = RateLimiterService::client_limit(client_id)
+            .await
+            .map_err(|e| Status::internal(format!("internal error: {:?}", e)))?;
+
+        if per_minute == 0 {
+            return Ok(());
+        }
+
+        if count > per_minute {
+            return Err(Status::invalid_argument(format!(
+                "request exceeds the limit of {} messages per minute",
+                per_minute
+            )));
+        }
+
+        let retry_after = RateLimiterService::from_registry()
+            .await
+            .map_err(|_| Status::internal("failed to get rate limiter service"))?
+            .call(TakeClientToken {
+                client_id: client_id.to_bytes(),
+                count,
+                per_minute,
+            })
+            .await
+            .map_err(|_| Status::internal("internal error - failed to call"))?;
+
+        match retry_after {
+            None => Ok(()),
+            Some(retry_after) => {
+                debug!(
+                    "rate limited requests from client: {}",
+                    short_hex_string(client_id.as_ref())
+                );
+                Err(
+                    RequestRejection::client_rate_limited(retry_after.as_millis() as u64)
+                        .into_status("too many requests from client"),
+                )
+            }
+        }
+    }
+
+    /// Returns the max number of requests per minute allowed for a client
+    async fn client_limit(client_id: &PublicKey) -> Result<u32> {
+        match ClientsDataService::get_client_service_data(client_id).await {
+            Ok(Some(ClientServiceData {
+                service_terms: Some(terms),
+                ..
+            })) => return Ok(terms.max_messages_per_minute),
+            Ok(_) => {}
+            Err(e) => warn!("failed to get client service data: {:?}", e),
+        }
+
+        Ok(
+            ServerConfigService::get_u64(CLIENT_MESSAGES_PER_MINUTE_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default() as u32,
+        )
+    }
+
+    /// Use a proof of work of a request with an ephemeral key. It may not be used again until it expires (unix nanos).
+    pub(crate) async fn use_pow(eka: [u8; 32], nonce: u64, expires: i64) -> Result<(), Status> {
+        let fresh = RateLimiterService::from_registry()
+            .await
+            .map_err(|_| Status::internal("failed to get rate limiter service"))?
+            .call(UsePow {
+                eka,
+                nonce,
+                expires,
+            })
+            .await
+            .map_err(|_| Status::internal("internal error - failed to call"))?;
+
+        if !fresh {
+            debug!("rejecting a replayed proof of work");
+            return Err(Status::invalid_argument("proof of work was already used"));
+        }
+        Ok(())
+    }
+
+    /// Returns false when the proof of work was already used and didn't expire yet
+    fn use_pow_at(
+        used_pows: &mut HashMap<([u8; 32], u64), i64>,
+        key: ([u8; 32], u64),
+        expires: i64,
+        now: i64,
+    ) -> bool {
+        if used_pows.len() >= MAX_TRACKED_POWS {
+            used_pows.retain(|_, expires| *expires > now);
+        }
+
+        // an evicted proof of work may be replayed but evicting one takes computing many fresh proofs of work
+        if used_pows.len() >= MAX_TRACKED_POWS && !used_pows.contains_key(&key) {
+            RateLimiterService::evict(used_pows, MAX_TRACKED_POWS / EVICTION_FRACTION, |e| *e);
+        }
+
+        match used_pows.get(&key) {
+            Some(used_expires) if *used_expires > now => false,
+            _ => {
+                used_pows.insert(key, expires);
+                true
+            }
+        }
+    }
+
+    fn take<K: Hash + Eq + Clone>(
+        buckets: &mut HashMap<K, TokenBucket>,
+        key: K,
+        count: u32,
+        per_minute: u32,
+        now: Instant,
+    ) -> Option<Duration> {
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            buckets.retain(|_, bucket| !bucket.is_full(per_minute, now));
+        }
+
+        if buckets.len() >= MAX_TRACKED_BUCKETS && !buckets.contains_key(&key) {
+            RateLimiterService::evict(buckets, MAX_TRACKED_BUCKETS / EVICTION_FRACTION, |b| {
+                b.last_used
+            });
+        }
+
+        buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(per_minute, now))
+            .take(count, per_minute, now)
+    }
+
+    /// Remove count entries with the lowest order from a map, e.g. the least recently used ones
+    fn evict<K: Hash + Eq + Clone, V, O: Ord>(
+        map: &mut HashMap<K, V>,
+        count: usize,
+        order: impl Fn(&V) -> O,
+    ) {
+        let mut entries: Vec<(O, K)> = map.iter().map(|(k, v)| (order(v), k.clone())).collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        for (_, key) in entries.into_iter().take(count) {
+            map.remove(&key);
+        }
+    }
+}
+
+/// Take tokens for a client request. Returns the time to wait before retrying when rate limited.
+#[message(result = "Option<Duration>")]
+pub(crate) struct TakeClientToken {
+    pub(crate) client_id: [u8; 32],
+    pub(crate) count: u32,
+    pub(crate) per_minute: u32,
+}
+
+#[async_trait::async_trait]
+impl Handler<TakeClientToken> for RateLimiterService {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: TakeClientToken) -> Option<Duration> {
+        RateLimiterService::take(
+            &mut self.clients,
+            msg.client_id,
+            msg.count,
+            msg.per_minute,
+            Instant::now(),
+        )
+    }
+}
+
+/// Take a token for a request from an ip address. Returns the time to wait before retrying when rate limited.
+#[message(result = "Option<Duration>")]
+pub(crate) struct TakeIpToken {
+    pub(crate) ip: IpAddr,
+    pub(crate) per_minute: u32,
+}
+
+#[async_trait::async_trait]
+impl Handler<TakeIpToken> for RateLimiterService {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: TakeIpToken) -> Option<Duration> {
+        RateLimiterService::take(&mut self.ips, msg.ip, 1, msg.per_minute, Instant::now())
+    }
+}
+
+/// Use a new session request proof of work. Returns false when it was already used.
+#[message(result = "bool")]
+pub(crate) struct UsePow {
+    pub(crate) eka: [u8; 32],
+    pub(crate) nonce: u64,
+    pub(crate) expires: i64,
+}
+
+#[async_trait::async_trait]
+impl Handler<UsePow> for RateLimiterService {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: UsePow) -> bool {
+        RateLimiterService::use_pow_at(
+            &mut self.used_pows,
+            (msg.eka, msg.nonce),
+            msg.expires,
+            Utc::now().timestamp_nanos(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(60, start);
+
+        // a minute worth of requests may be sent in a burst
+        for _ in 0..60 {
+            assert!(bucket.take(1, 60, start).is_none());
+        }
+
+        // bucket is refilled at a rate of one token per second
+        assert_eq!(bucket.take(1, 60, start), Some(Duration::from_secs(1)));
+        assert!(bucket
+            .take(1, 60, start + Duration::from_millis(500))
+            .is_some());
+        assert!(bucket.take(1, 60, start + Duration::from_secs(1)).is_none());
+        assert!(!bucket.is_full(60, start + Duration::from_secs(2)));
+        assert!(bucket.is_full(60, start + Duration::from_secs(120)));
+
+        // a request which sends several messages takes a token per message
+        let later = start + Duration::from_secs(120);
+        assert!(bucket.take(50, 60, later).is_none());
+        assert_eq!(bucket.take(20, 60, later), Some(Duration::from_secs(10)));
+        assert!(bucket.take(10, 60, later).is_none());
+    }
+
+    #[test]
+    fn test_pruning() {
+        let mut buckets = HashMap::new();
+        for i in 0..MAX_TRACKED_BUCKETS {
+            buckets.insert(i, TokenBucket::new(10, Instant::now()));
+        }
+
+        assert!(
+            RateLimiterService::take(&mut buckets, MAX_TRACKED_BUCKETS, 1, 10, Instant::now())
+                .is_none()
+        );
+        assert_eq!(buckets.len(), 1);
+    }
+
+    /// A flood of requests from distinct keys evicts the least recently used buckets
+    #[test]
+    fn test_buckets_flood() {
+        let start = Instant::now();
+        let mut buckets = HashMap::new();
+        let at = |i: usize| start + Duration::from_micros(i as u64);
+
+        for i in 0..MAX_TRACKED_BUCKETS {
+            assert!(RateLimiterService::take(&mut buckets, i, 1, 10, at(i)).is_none());
+        }
+
+        // a key which used all its tokens is used again during the flood and keeps its bucket
+        for _ in 0..9 {
+            RateLimiterService::take(&mut buckets, 0, 1, 10, at(MAX_TRACKED_BUCKETS));
+        }
+
+        for i in MAX_TRACKED_BUCKETS..MAX_TRACKED_BUCKETS * 3 {
+            assert!(RateLimiterService::take(&mut buckets, i, 1, 10, at(i)).is_none());
+            assert!(buckets.len() <= MAX_TRACKED_BUCKETS);
+            if i < MAX_TRACKED_BUCKETS * 3 / 2 {
+                assert!(buckets.contains_key(&0));
+            }
+        }
+
+        assert!(!buckets.contains_key(&1));
+        assert!(buckets.contains_key(&(MAX_TRACKED_BUCKETS * 3 - 1)));
+    }
+
+    #[test]
+    fn test_pow_replay() {
+        let mut used_pows = HashMap::new();
+        let key = ([1u8; 32], 42);
+
+        // a proof of work may only be used once until it expires
+        assert!(RateLimiterService::use_pow_at(&mut used_pows, key, 100, 0));
+        assert!(!RateLimiterService::use_pow_at(
+            &mut used_pows,
+            key,
+            100,
+            50
+        ));
+        assert!(RateLimiterService::use_pow_at(
+            &mut used_pows,
+            ([1u8; 32], 43),
+            100,
+            50
+        ));
+        assert!(RateLimiterService::use_pow_at(
+            &mut used_pows,
+            ([2u8; 32], 42),
+            100,
+            50
+        ));
+        assert!(RateLimiterService::use_pow_at(
+            &mut used_pows,
+            key,
+            200,
+            100
+        ));
+
+        // expired proofs of work are pruned
+        for i in 0..MAX_TRACKED_POWS as u64 {
+            used_pows.insert(([3u8; 32], i), 150);
+        }
+        assert!(RateLimiterService::use_pow_at(
+            &mut used_pows,
+            ([4u8; 32], 0),
+            300,
+            160
+        ));
+        assert_eq!(used_pows.len(), 2);
+    }
+
+    /// A flood of distinct unexpired proofs of work evicts the ones which expire first
+    #[test]
+    fn test_pows_flood() {
+        let mut used_pows = HashMap::new();
+        for i in 0..MAX_TRACKED_POWS as u64 * 3 {
+            assert!(RateLimiterService::use_pow_at(
+                &mut used_pows,
+                ([5u8; 32], i),
+                1000 + i as i64,
+                0
+            ));
+            assert!(used_pows.len() <= MAX_TRACKED_POWS);
+        }
+
+        // recent proofs of work are still rejected
+        let last = MAX_TRACKED_POWS as u64 * 3 - 1;
+        assert!(!RateLimiterService::use_pow_at(
+            &mut used_pows,
+            ([5u8; 32], last),
+            1000 + last as i64,
+            0
+        ));
+    }
+}
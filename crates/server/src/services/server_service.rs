@@ -2,30 +2,55 @@
 //  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
+use crate::services::blockchain_service::BlockchainService;
+use crate::services::cover_traffic_service::CoverTrafficService;
 use crate::services::messaging::client_msgs_delivery_service::ClientMessagesDeliveryService;
 use crate::services::messaging::messaging_service::ServerMessagingService;
+use crate::services::messaging::msg_fan_out_service::MessageFanOutService;
 use crate::services::messaging::msg_forwarding_service::MessageForwardingService;
 use crate::services::messaging::msg_routing_service::MessageRoutingService;
+use crate::services::provider_id::ProviderIdService;
+use crate::services::provider_id_service::RefreshNetInfo;
 use crate::services::public_service::PublicService;
+use crate::services::stop_service::StopService;
 use crate::services::terms_service::TermsService;
 use anyhow::Result;
 use base::snp::snp_server_api::provider_core_service_server::ProviderCoreServiceServer;
 use rocksdb::{ColumnFamilyDescriptor, Options};
 
-use crate::services::admin_service::AdminService;
+use crate::services::admin_service::{admin_key_interceptor, AdminService};
+use crate::services::json_gateway::start_json_gateway;
+use base::grpc_tls::server_tls_config;
 use base::server_config_service::{
-    ServerConfigService, DB_NAME_CONFIG_KEY, DROP_DB_CONFIG_KEY, GRPC_HOST_CONFIG_KEY,
-    GRPC_SERVER_PORT_CONFIG_KEY, PEER_NAME_CONFIG_KEY,
+    ServerConfigService, ADMIN_KEY_CONFIG_KEY, DB_NAME_CONFIG_KEY, DROP_DB_CONFIG_KEY,
+    GRPC_ADMIN_HOST_CONFIG_KEY, GRPC_ADMIN_PORT_CONFIG_KEY, GRPC_HOST_CONFIG_KEY,
+    GRPC_SERVER_PORT_CONFIG_KEY, JSON_HTTP_PORT_CONFIG_KEY, MESSAGE_PADDING_CONFIG_KEY,
+    NET_ID_CONFIG_KEY, PEER_NAME_CONFIG_KEY, START_GRPC_SERVER_ADMIN_SERVICE_CONFIG_KEY,
+    TLS_CERT_FILE_CONFIG_KEY, TLS_KEY_FILE_CONFIG_KEY,
 };
 use base::snp::upsetter_server_admin::server_admin_service_server::ServerAdminServiceServer;
+use common::{message_padding, network_salt};
 use db::db_service::{
     DatabaseService, Destroy, PROVIDER_COL_FAMILY, PROVIDER_DISTRIBUTED_DATA_COL_FAMILY,
     PROVIDER_USER_DATA_COL_FAMILY, TESTS_COL_FAMILY,
 };
-use tonic::transport::Server;
+use tonic::transport::{Server, ServerTlsConfig};
 use xactor::*;
 
-pub const SNP_PROTOCOL_VERSION: &str = "0.1.0";
+pub use base::protocol_version::SNP_PROTOCOL_VERSION;
+
+/// Column families of a provider's db
+pub(crate) fn provider_col_descriptors() -> Vec<ColumnFamilyDescriptor> {
+    vec![
+        PROVIDER_COL_FAMILY,
+        PROVIDER_USER_DATA_COL_FAMILY,
+        PROVIDER_DISTRIBUTED_DATA_COL_FAMILY,
+        TESTS_COL_FAMILY,
+    ]
+    .into_iter()
+    .map(|cf| ColumnFamilyDescriptor::new(cf, Options::default()))
+    .collect()
+}
 
 /// ServerService is a full node p2p network server
 /// todo: ServerService should maintain node id identity (for protocol purposes)
@@ -45,10 +70,13 @@ impl Actor for ServerService {
         /////////////
 
         MessageRoutingService::from_registry().await?;
+        MessageFanOutService::from_registry().await?;
         MessageForwardingService::from_registry().await?;
         ClientMessagesDeliveryService::from_registry().await?;
         PublicService::from_registry().await?;
+        StopService::from_registry().await?;
         TermsService::from_registry().await?;
+        CoverTrafficService::from_registry().await?;
 
         info!("ServerService started");
         Ok(())
@@ -94,6 +122,18 @@ impl Handler<Startup> for ServerService {
             .await?
             .unwrap() as u32;
 
+        // all network data and messages are scoped to the configured network
+        let net_id = ServerConfigService::get_u64(NET_ID_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default() as u32;
+        network_salt::set_net_id(net_id);
+        info!("network id: {}", net_id);
+
+        let padding = ServerConfigService::get_bool(MESSAGE_PADDING_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default();
+        message_padding::set_message_padding(padding);
+
         let db_name = ServerConfigService::get(DB_NAME_CONFIG_KEY.into())
             .await?
             .unwrap();
@@ -105,19 +145,42 @@ impl Handler<Startup> for ServerService {
         DatabaseService::config_db(db::db_service::Configure {
             drop_on_exit,
             db_name,
-            col_descriptors: vec![
-                ColumnFamilyDescriptor::new(PROVIDER_COL_FAMILY, Options::default()),
-                ColumnFamilyDescriptor::new(PROVIDER_USER_DATA_COL_FAMILY, Options::default()),
-                ColumnFamilyDescriptor::new(
-                    PROVIDER_DISTRIBUTED_DATA_COL_FAMILY,
-                    Options::default(),
-                ),
-                ColumnFamilyDescriptor::new(TESTS_COL_FAMILY, Options::default()),
-            ],
+            col_descriptors: provider_col_descriptors(),
         })
         .await?;
 
-        self.start_grpc_server(port, host, peer_name).await?;
+        // advertise the configured endpoints - publish a new bundle if they changed since last run
+        let endpoints_changed = ProviderIdService::from_registry()
+            .await?
+            .call(RefreshNetInfo)
+            .await??;
+
+        if endpoints_changed {
+            BlockchainService::publish_provider_bundle().await?;
+        }
+
+        self.start_grpc_server(port, host.clone(), peer_name)
+            .await?;
+
+        if let Some(json_http_port) = ServerConfigService::get_u64(JSON_HTTP_PORT_CONFIG_KEY.into())
+            .await?
+            .filter(|p| *p != 0)
+        {
+            start_json_gateway(
+                format!("{}:{}", host, json_http_port),
+                ServerService::tls_files().await?,
+            )
+            .await?;
+        }
+
+        let start_admin_service =
+            ServerConfigService::get_bool(START_GRPC_SERVER_ADMIN_SERVICE_CONFIG_KEY.into())
+                .await?
+                .unwrap();
+
+        if start_admin_service {
+            self.start_grpc_admin_server().await?;
+        }
 
         info!("services started");
 
@@ -126,6 +189,23 @@ impl Handler<Startup> for ServerService {
 }
 
 impl ServerService {
+    /// Returns the configured tls cert and key files. None when tls is not configured.
+    async fn tls_files() -> Result<Option<(String, String)>> {
+        let cert_file = ServerConfigService::get(TLS_CERT_FILE_CONFIG_KEY.into()).await?;
+        let key_file = ServerConfigService::get(TLS_KEY_FILE_CONFIG_KEY.into()).await?;
+        Ok(cert_file.zip(key_file))
+    }
+
+    /// Returns the grpc servers tls config. None when tls is not configured.
+    async fn tls_config() -> Result<Option<ServerTlsConfig>> {
+        match ServerService::tls_files().await? {
+            Some((cert_file, key_file)) => {
+                Ok(Some(server_tls_config(&cert_file, &key_file).await?))
+            }
+            None => Ok(None),
+        }
+    }
+
     async fn start_grpc_server(&self, port: u32, host: String, peer_name: String) -> Result<()> {
         // setup grpc server and services
         let grpc_server_addr = format!("{}:{}", host, port).parse().unwrap();
@@ -142,13 +222,16 @@ impl ServerService {
             .set_serving::<ProviderCoreServiceServer<ServerMessagingService>>()
             .await;
 
-        // todo: server admin service should be deployed on different server with different port for security and only be available inside provider network or only locally.
+        let mut builder = Server::builder();
+        if let Some(tls_config) = ServerService::tls_config().await? {
+            info!("grpc server using tls");
+            builder = builder.tls_config(tls_config)?;
+        }
 
         tokio::task::spawn(async move {
             // all services that should be started must be added below
-            let res = Server::builder()
+            let res = builder
                 .add_service(ProviderCoreServiceServer::new(messaging_service))
-                .add_service(ServerAdminServiceServer::new(AdminService::default()))
                 .add_service(health_service)
                 .serve(grpc_server_addr)
                 .await;
@@ -160,6 +243,43 @@ impl ServerService {
             }
         });
 
+        Ok(())
+    }
+    /// Start the grpc admin server on its own port so it is only reachable where the provider exposes
+    /// the admin port. It binds to localhost by default and requires an admin key when one is configured.
+    async fn start_grpc_admin_server(&self) -> Result<()> {
+        let host = ServerConfigService::get(GRPC_ADMIN_HOST_CONFIG_KEY.into())
+            .await?
+            .unwrap();
+        let port = ServerConfigService::get_u64(GRPC_ADMIN_PORT_CONFIG_KEY.into())
+            .await?
+            .unwrap() as u32;
+        let admin_key = ServerConfigService::get(ADMIN_KEY_CONFIG_KEY.into()).await?;
+
+        let grpc_admin_server_addr = format!("{}:{}", host, port).parse().unwrap();
+        info!("starting grpc admin server on: {}", grpc_admin_server_addr);
+
+        let mut builder = Server::builder();
+        if let Some(tls_config) = ServerService::tls_config().await? {
+            builder = builder.tls_config(tls_config)?;
+        }
+
+        tokio::task::spawn(async move {
+            let res = builder
+                .add_service(ServerAdminServiceServer::with_interceptor(
+                    AdminService::default(),
+                    admin_key_interceptor(admin_key),
+                ))
+                .serve(grpc_admin_server_addr)
+                .await;
+
+            if res.is_err() {
+                info!("grpc admin server stopped due to: {:?}", res.err().unwrap());
+            } else {
+                info!("grpc admin server stopped");
+            }
+        });
+
         Ok(())
     }
 }
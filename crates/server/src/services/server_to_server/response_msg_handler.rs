@@ -29,16 +29,14 @@ impl ServerToServerService {
 
         let index = resp_dr_header.count;
 
-        if index == 0 {
-            dr_session.ratchet(
-                &mut OsRng,
-                &bob_dr_key_wrapper.0.clone(),
-                resp_dr_header.prev_count,
-            )?;
-        }
-
-        let alice_receive_key = dr_session.get_receiving_key(index)?;
-        let ad = dr_session
+        // the updated session replaces ours once the response was decrypted and authenticated
+        let (next_dr_session, alice_receive_key) = dr_session.get_message_receiving_key(
+            &mut OsRng,
+            &bob_dr_key_wrapper.0,
+            resp_dr_header.prev_count,
+            index,
+        )?;
+        let ad = next_dr_session
             .ad
             .as_ref()
             .ok_or_else(|| anyhow!("missing ad"))?;
@@ -58,6 +56,7 @@ impl ServerToServerService {
         }
 
         // Save the updated dr session using the dr server
+        *dr_session = next_dr_session;
         DRService::save_dr_session(ikb, dr_session.clone()).await?;
 
         Ok(resp_msg)
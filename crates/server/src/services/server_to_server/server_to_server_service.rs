@@ -2,12 +2,15 @@
 // This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
 //
 
-use crate::server_service::SNP_PROTOCOL_VERSION;
+use crate::services::protocol_versions::supported_protocol_versions;
 use crate::services::provider_id::ProviderIdService;
 use crate::services::provider_id_service::GetCurrentIdentityBundle;
 use anyhow::{anyhow, Result};
-use base::api_types_extensions::{Signed, SignedWithExternalVerifier};
+use base::api_types_extensions::{NetworkScoped, Signed, SignedWithExternalVerifier};
 use base::hex_utils::short_hex_string;
+use base::protocol_version::VersionRange;
+use base::request_rejection::status_error;
+use base::server_config_service::{ServerConfigService, TLS_CA_CERT_FILE_CONFIG_KEY};
 use base::snp::snp_core_types::{DialupInfo, PrivateProviderIdentityBundle};
 use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
 use base::snp::snp_server_api::{
@@ -16,7 +19,7 @@ use base::snp::snp_server_api::{
 use bytes::Bytes;
 use chrono::prelude::*;
 use common::dr_service::DRService;
-use common::network_salt::NET_SALT;
+use common::network_salt::{net_id, net_salt};
 use common::typed_msg_extensions::TypedMessageExtensions;
 use crypto::x2dh;
 use crypto::x2dh::ProtocolInputAlice;
@@ -34,12 +37,18 @@ use xactor::*;
 #[derive(Debug)]
 pub struct ServerToServerService {
     servers_net_clients: HashMap<Vec<u8>, ProviderCoreServiceClient<Channel>>,
+    servers_dialup_info: HashMap<Vec<u8>, DialupInfo>, // dialup info of servers we sent messages to
+    // ids of dr sessions we started with servers. We only send requests in sessions we started as a server may
+    // send us requests in a session it started at the same time
+    outgoing_sessions: HashMap<Vec<u8>, u64>,
 }
 
 impl Default for ServerToServerService {
     fn default() -> Self {
         ServerToServerService {
             servers_net_clients: HashMap::new(),
+            servers_dialup_info: HashMap::new(),
+            outgoing_sessions: HashMap::new(),
         }
     }
 }
@@ -54,6 +63,25 @@ impl Actor for ServerToServerService {
     }
 }
 
+/// Returns the ids and dialup info of all servers this server sent messages to
+#[message(result = "Result<Vec<(PublicKey, DialupInfo)>>")]
+pub struct GetPeers;
+
+#[async_trait::async_trait]
+impl Handler<GetPeers> for ServerToServerService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: GetPeers,
+    ) -> Result<Vec<(PublicKey, DialupInfo)>> {
+        let mut res = vec![];
+        for (id, dialup_info) in self.servers_dialup_info.iter() {
+            res.push((PublicKey::from_bytes(id.as_ref())?, dialup_info.clone()));
+        }
+        Ok(res)
+    }
+}
+
 /// Send a message to another provider based on his public key and dialup info
 #[message(result = "Result<(TypedMessage)>")]
 pub struct SendMessageToServer {
@@ -80,7 +108,21 @@ impl Handler<SendMessageToServer> for ServerToServerService {
             short_hex_string(msg.receiver_id.to_bytes().as_ref())
         );
 
-        let dr_session = DRService::get_dr_session(msg.receiver_id).await?;
+        // only talk with providers on this network
+        msg.dialup_info.verify_net_id(net_id())?;
+
+        // use the newest protocol version supported by both providers
+        let protocol_version = supported_protocol_versions()
+            .await?
+            .negotiate(&VersionRange::from_dialup_info(&msg.dialup_info)?)?
+            .to_string();
+
+        let dr_session = match self.outgoing_sessions.get(msg.receiver_id.as_ref()) {
+            Some(session_id) => DRService::get_dr_session_by_id(*session_id)
+                .await?
+                .map(|(dr, _)| dr),
+            None => None,
+        };
 
         // step 2: check if we have a net client with the remote provider and if not then connect to it and store
         // client for future messages
@@ -94,19 +136,23 @@ impl Handler<SendMessageToServer> for ServerToServerService {
                 c.clone()
             }
             None => {
-                let address = format!(
-                    "http://{}:{}",
-                    msg.dialup_info.ip_address, msg.dialup_info.port
+                debug!(
+                    "Connecting to a remote provider at {} ...",
+                    msg.dialup_info.url()
                 );
-
-                debug!("Connecting to a remote provider at {} ...", address);
-                let new_client = ProviderCoreServiceClient::connect(address).await?;
+                let ca_cert_file =
+                    ServerConfigService::get(TLS_CA_CERT_FILE_CONFIG_KEY.into()).await?;
+                let channel = msg.dialup_info.connect(ca_cert_file).await?;
+                let new_client = ProviderCoreServiceClient::new(channel);
                 self.servers_net_clients
                     .insert(msg.receiver_id.as_ref().to_vec(), new_client.clone());
                 new_client
             }
         };
 
+        self.servers_dialup_info
+            .insert(msg.receiver_id.as_ref().to_vec(), msg.dialup_info.clone());
+
         // Get our current PrivateIdentityBundle as we need it for X2DH and DR execution
         let alice_provider_data = ProviderIdService::from_registry().await.unwrap();
         let alice_provider_bundle: PrivateProviderIdentityBundle = alice_provider_data
@@ -133,13 +179,21 @@ impl Handler<SendMessageToServer> for ServerToServerService {
 
         let bob_provider_bundle = bob_api_service
             .get_identity_bundle(GetIdentityBundleRequest {
-                protocol_version: SNP_PROTOCOL_VERSION.into(),
+                protocol_version: protocol_version.clone(),
             })
-            .await?
+            .await
+            .map_err(status_error)?
             .into_inner()
             .bundle
             .ok_or_else(|| anyhow!("missing provider bundle from response message"))?;
 
+        bob_provider_bundle.verify_net_id(net_id())?;
+        let kem_pkb = bob_provider_bundle
+            .pre_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing provider pre-key"))?
+            .get_kem_key()?;
+
         // step 4: execute x2dh to create a new dr session with bob
 
         let alice_id_key_pair = alice_provider_bundle
@@ -160,13 +214,14 @@ impl Handler<SendMessageToServer> for ServerToServerService {
             ikb,
             pkb,
             b_bundle_id: bob_provider_bundle.time_stamp,
+            kem_pkb,
         };
 
         // Alice executes x2dh with bob and get the output
-        let output_alice = x2dh::execute_alice(&input_alice);
+        let output_alice = x2dh::execute_alice(&input_alice)?;
 
         // Alice creates a DR session with bob and using it to get the enc key for her first message with bob
-        let input = SessionKey::from(NET_SALT.as_ref());
+        let input = SessionKey::from(net_salt().as_ref());
         let dr_root_chain_key = ChainKey::from(output_alice.shared_secret.as_ref());
         let mut alice_dr = DoubleRatchet::new_with_peer(
             input,
@@ -196,6 +251,8 @@ impl Handler<SendMessageToServer> for ServerToServerService {
             receiver: Some(bob_identity.clone()),
             sender: Some(alice_entity.clone()),
             signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
         };
 
         let ika_pair = alice_id_key_pair.to_ed2559_kaypair();
@@ -241,18 +298,17 @@ impl Handler<SendMessageToServer> for ServerToServerService {
             message: Some(message),
             sender_signature: None,
             receiver_bundle_id: bob_provider_bundle.time_stamp,
-            net_id: 0,
-            protocol_version: SNP_PROTOCOL_VERSION.into(),
+            net_id: net_id(),
+            protocol_version,
+            pow_nonce: 0,
+            sender_kem_ciphertext: output_alice.kem_ct.unwrap_or_default(),
         };
 
         new_session_request.sign(&ika_pair).unwrap();
 
         debug!("sending new_session request to remote provider...");
 
-        let response = bob_api_service
-            .new_session(tonic::Request::new(new_session_request))
-            .await?
-            .into_inner();
+        let response = new_session_request.send(&mut bob_api_service).await?;
 
         debug!("processing new_session response from remote provider...");
 
@@ -265,6 +321,10 @@ impl Handler<SendMessageToServer> for ServerToServerService {
             .handle_server_response_message(message, &mut alice_dr, ikb)
             .await?;
 
+        // remote server accepted the session - use it for our next messages to it
+        self.outgoing_sessions
+            .insert(ikb.as_ref().to_vec(), alice_dr.session_id);
+
         Ok(resp_msg)
     }
 }
@@ -0,0 +1,162 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+use crate::clients_data::service::ClientsDataService;
+use crate::services::clients_service::ClientsService;
+use crate::services::messaging::msg_forwarding_service::MessageForwardingService;
+use crate::services::provider_id::ProviderIdService;
+use crate::services::provider_id_service::GetCurrentIdentityBundle;
+use anyhow::{anyhow, bail, Result};
+use base::api_types_extensions::{NetworkScoped, Signed};
+use base::hex_utils::short_hex_string;
+use base::snp::snp_core_types::{DialupInfo, PrivateProviderIdentityBundle};
+use base::snp::snp_server_api::{
+    MessageType, StopServiceRequest, StopServiceResponse, TypedMessage,
+};
+use base::typed_msgs_dispatcher::{
+    Subscribe, TypedMessageHandler, TypedMessagesDispatcher, Unsubscribe,
+};
+use chrono::prelude::*;
+use common::network_salt::net_id;
+use xactor::*;
+
+/// StopService handles requests from served clients to stop being served by this provider.
+/// A client moving to another provider includes its bundle with the new provider in the request.
+/// This provider hands over messages pending delivery to the client to its new provider and keeps
+/// forwarding new messages designated to the client during the handover grace period.
+#[derive(Debug, Default)]
+pub(crate) struct StopService {}
+impl Service for StopService {}
+
+#[async_trait::async_trait]
+impl Actor for StopService {
+    async fn started(&mut self, ctx: &mut Context<Self>) -> Result<()> {
+        let subscribe_msg = Subscribe {
+            message_type: MessageType::StopServiceRequest as i32,
+            subscriber: ctx.address().caller(),
+        };
+
+        TypedMessagesDispatcher::from_registry()
+            .await?
+            .call(subscribe_msg)
+            .await??;
+
+        debug!("StopService started and subscribed to handle StopServiceRequest");
+        Ok(())
+    }
+
+    async fn stopped(&mut self, _ctx: &mut Context<Self>) {
+        // Unsubscribe from the dispatcher
+        let dispatcher = TypedMessagesDispatcher::from_registry().await.unwrap();
+        let _res = dispatcher
+            .call(Unsubscribe {
+                id: MessageType::StopServiceRequest as i32,
+            })
+            .await;
+    }
+}
+
+/// Handle an incoming StopServiceRequest from a served client
+#[async_trait::async_trait]
+impl Handler<TypedMessageHandler> for StopService {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: TypedMessageHandler,
+    ) -> Result<TypedMessage> {
+        info!("stop service request...");
+
+        // step 1 - verify we know how to handle the message
+        if msg.0.msg_type != (MessageType::StopServiceRequest as i32) {
+            return Err(anyhow!("Unexpected message type {}", msg.0.msg_type));
+        };
+
+        // step 2 - only the client may ask to stop its service
+        msg.0.verify_signature()?;
+        let ika = msg.0.get_ika()?;
+
+        let mut client_data = ClientsDataService::get_client_service_data(&ika)
+            .await?
+            .ok_or_else(|| anyhow!("unrecognized client - not served by this provider"))?;
+
+        if client_data.service_ended != 0 {
+            bail!("client already stopped being served by this provider")
+        }
+
+        use prost::Message;
+        let req: StopServiceRequest = StopServiceRequest::decode(msg.0.message.as_slice())
+            .map_err(|e| anyhow!("failed to decode client stop service request: {:?}", e))?;
+
+        // step 3 - validate the client's bundle with its new provider when provided
+        if let Some(bundle) = req.client_bundle.as_ref() {
+            bundle.verify_signature()?;
+
+            if bundle.get_client_id_ed25519_public_key()? != ika {
+                bail!("new client bundle is for another client")
+            }
+
+            let new_provider_bundle = bundle
+                .provider_bundle
+                .as_ref()
+                .ok_or_else(|| anyhow!("missing new provider bundle"))?;
+
+            // clients may only move to providers on this network
+            bundle.verify_net_id(net_id())?;
+
+            if DialupInfo::best_grpc_endpoint(&new_provider_bundle.dial_up_info).is_none() {
+                bail!("missing new provider grpc dialup info")
+            }
+
+            let provider = ProviderIdService::from_registry().await?;
+            let our_bundle: PrivateProviderIdentityBundle =
+                provider.call(GetCurrentIdentityBundle {}).await??;
+
+            if new_provider_bundle.get_provider_id_ed25519_public_key()?
+                == our_bundle
+                    .public_bundle
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing public bundle"))?
+                    .get_provider_id_ed25519_public_key()?
+            {
+                bail!("new client bundle must be with another provider")
+            }
+        }
+
+        // step 4 - end service and remember where client moved to for the handover grace period
+        client_data.service_ended = Utc::now().timestamp_nanos() as u64;
+        client_data.next_client_bundle = req.client_bundle.clone();
+        let delivery_token = client_data.delivery_token.clone();
+        ClientsDataService::upsert_client_data(client_data).await?;
+
+        // client no longer gets messages pushed to it from this provider
+        ClientsService::remove_client_message_sender(ika).await?;
+
+        info!("stopped serving client {}", short_hex_string(ika.as_ref()));
+
+        // step 5 - hand over pending messages. Ones we failed to forward are held for the grace period.
+        if let Some(bundle) = req.client_bundle.as_ref() {
+            if let Err(e) =
+                MessageForwardingService::handover_pending_messages(&ika, bundle, &delivery_token)
+                    .await
+            {
+                warn!("failed to hand over pending client messages: {:?}", e);
+            }
+        }
+
+        let resp = StopServiceResponse {};
+        let mut buff = Vec::with_capacity(resp.encoded_len());
+        resp.encode(&mut buff)?;
+
+        Ok(TypedMessage {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            msg_type: MessageType::StopServiceResponse as i32,
+            message: buff,
+            receiver: None,
+            sender: None,
+            signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
+        })
+    }
+}
@@ -5,6 +5,10 @@
 use crate::services::messaging::messaging_service::ServerMessagingService;
 use anyhow::{anyhow, Result};
 use base::api_types_extensions::Signed;
+use base::server_config_service::{
+    ServerConfigService, CLIENT_MESSAGES_PER_MINUTE_CONFIG_KEY, FAN_OUT_MAX_RECEIVERS_CONFIG_KEY,
+    MAX_FILE_SIZE_CONFIG_KEY, NEW_SESSION_POW_DIFFICULTY_CONFIG_KEY,
+};
 use base::snp::snp_core_types::ServiceTermsBundle;
 use base::snp::snp_payments::ServiceTerms;
 use base::snp::snp_server_api::{
@@ -61,6 +65,22 @@ impl TermsService {
             .provider_id
             .ok_or_else(|| anyhow!("missing provider id"))?;
 
+        let max_messages_per_minute =
+            ServerConfigService::get_u64(CLIENT_MESSAGES_PER_MINUTE_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default() as u32;
+        let new_session_pow_difficulty =
+            ServerConfigService::get_u64(NEW_SESSION_POW_DIFFICULTY_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default() as u32;
+        let max_fan_out_receivers =
+            ServerConfigService::get_u64(FAN_OUT_MAX_RECEIVERS_CONFIG_KEY.into())
+                .await?
+                .unwrap_or_default() as u32;
+        let max_file_size = ServerConfigService::get_u64(MAX_FILE_SIZE_CONFIG_KEY.into())
+            .await?
+            .unwrap_or_default();
+
         // todo: generate ServiceTermsBundle when new provider identity is created, store it in db and return stored terms and don't generate and sign new terms per request.
 
         let mut terms = ServiceTermsBundle {
@@ -82,8 +102,11 @@ impl TermsService {
                 registration_fee: None,
                 monthly_fixed_fee: None,
                 max_user_storage_space: 0,
-                max_file_size: 0,
+                max_file_size,
                 payable_account: None,
+                max_messages_per_minute,
+                new_session_pow_difficulty,
+                max_fan_out_receivers,
             }),
         };
         let key_pair = private_bundle
@@ -151,6 +174,8 @@ impl Handler<TypedMessageHandler> for TermsService {
             receiver: None,
             sender: None,
             signature: None,
+            sender_delivery_token: vec![],
+            sender_protocol_version: "".into(),
         })
     }
 }
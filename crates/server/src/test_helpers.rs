@@ -0,0 +1,56 @@
+//  Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+//  This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+// test helper functions
+
+use crate::services::server_service::provider_col_descriptors;
+use base::test_helpers::enable_logger;
+use db::db_service::{Configure, DatabaseService, Destroy};
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+use tokio::runtime::Runtime;
+use tokio::sync::Mutex;
+use xactor::Service;
+
+/// Run a test with a new provider db on a runtime shared by all tests.
+/// Service actors run on the runtime they were started on so tests using them must share it.
+/// Tests share the provider's services and their db keys so they run one at a time.
+pub(crate) fn run_with_test_db<F: Future>(test: F) -> F::Output {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    static TEST_LOCK: Mutex<()> = Mutex::const_new(());
+    static DB_COUNT: AtomicU32 = AtomicU32::new(0);
+
+    enable_logger();
+    let runtime = RUNTIME.get_or_init(|| Runtime::new().unwrap());
+    runtime.block_on(async {
+        let _test_lock = TEST_LOCK.lock().await;
+
+        // drop the db of a previous test which failed before dropping it
+        let db_service = DatabaseService::from_registry().await.unwrap();
+        db_service.call(Destroy).await.unwrap().unwrap();
+
+        let db_name = std::env::temp_dir()
+            .join(format!(
+                "server_test_db_{}_{}",
+                std::process::id(),
+                DB_COUNT.fetch_add(1, Ordering::Relaxed)
+            ))
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        DatabaseService::config_db(Configure {
+            drop_on_exit: true,
+            db_name,
+            col_descriptors: provider_col_descriptors(),
+        })
+        .await
+        .unwrap();
+
+        let res = test.await;
+        db_service.call(Destroy).await.unwrap().unwrap();
+        res
+    })
+}
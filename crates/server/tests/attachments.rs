@@ -0,0 +1,175 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::{CompressionCodec, MimeType};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use test_helpers::{
+    blockchain_dialup_info, next_event, provider_dialup_info, set_providers_blockchain_service,
+    start_blockchain, start_client, start_provider, start_provider_with_env, wait_for_services,
+};
+
+/*
+In this test client B sends client A an image attachment which is larger than the max file size of A's provider,
+compressed with zlib. B sends it in chunks which fit in that size. A reassembles the chunks and gets the message
+with the attachment. Attachments compressed with rar are rejected. A's provider rejects larger messages to A.
+*/
+
+#[tokio::test]
+async fn attachments() {
+    enable_logger();
+
+    let bc_guard = start_blockchain("tests/blockchain_service2.json");
+
+    // client a's provider routes messages of up to 128 KiB
+    let spc_guard = start_provider_with_env(
+        "tests/spc_conf.json",
+        &[("UPSETTER_MAX_FILE_SIZE", "131072")],
+    );
+    let spd_guard = start_provider("tests/spd_conf.json");
+    let client_guards = [
+        start_client("tests/client_a_conf.json"),
+        start_client("tests/client_b_conf.json"),
+    ];
+
+    wait_for_services().await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_a_entity = bundles[0].get_client_entity().unwrap();
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+
+    client_a
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut a_events = client_a
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    // pseudo random image data which zlib can't compress much
+    let mut seed = 42u32;
+    let image: Vec<u8> = (0..600 * 1024)
+        .map(|_| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as u8
+        })
+        .collect();
+
+    let response = client_b
+        .user_send_attachment(UserSendAttachmentRequest {
+            other_client_id: Some(client_a_entity.clone()),
+            user_text: "A picture of my cat".into(),
+            name: "cat.png".into(),
+            mime_type: MimeType::ImagePng as i32,
+            compression: CompressionCodec::Zlib030 as i32,
+            content: image.clone(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send attachment to a")
+        .into_inner();
+
+    assert!(response.chunks_count >= 5);
+
+    let message = match next_event(&mut a_events).await {
+        Event::TextMessageReceived(e) => e.message.unwrap(),
+        e => panic!("unexpected event: {:?}", e),
+    };
+
+    assert_eq!(message.sender_id.unwrap(), client_b_entity);
+    let content_item = message.content_item.unwrap();
+    assert_eq!(content_item.id, response.message_id);
+    assert_eq!(
+        content_item.get_simple_text_content().unwrap(),
+        "A picture of my cat"
+    );
+
+    let attachment = &content_item.media_item[1];
+    assert_eq!(attachment.name, "cat.png");
+    assert_eq!(attachment.mime_type, MimeType::ImagePng as i32);
+    assert_eq!(attachment.compression, CompressionCodec::Zlib030 as i32);
+    assert_eq!(attachment.get_content(image.len()).unwrap(), image);
+
+    info!("b sends an attachment compressed with rar...");
+    let status = client_b
+        .user_send_attachment(UserSendAttachmentRequest {
+            other_client_id: Some(client_a_entity.clone()),
+            user_text: "".into(),
+            name: "cat.rar".into(),
+            mime_type: MimeType::ImagePng as i32,
+            compression: CompressionCodec::Rar010 as i32,
+            content: image,
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    info!("b sends a message which is larger than a's provider max file size...");
+    let status = client_b
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_a_entity.clone()),
+            user_text: "a".repeat(200 * 1024),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Internal);
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", bc_guard.0.id());
+    debug!("{}", spc_guard.0.id());
+    debug!("{}", spd_guard.0.id());
+    debug!("{}", client_guards.len());
+}
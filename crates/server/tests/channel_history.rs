@@ -0,0 +1,245 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::api_types_extensions::SignedWithExternalVerifier;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, provider_dialup_info, set_providers_blockchain_service, start_network,
+};
+use tokio::time::{sleep, timeout};
+use tonic::Streaming;
+
+/*
+In this test client A creates a status updates channel and a group and posts to both of them. Client B then
+subscribes to the channel and joins the group and gets their content which was published before it subscribed or
+joined from A in pages of channel history. Each history item is signed by A as the channel's creator.
+*/
+
+/// Returns the next channel history page received on an events stream
+async fn next_history_page(events: &mut Streaming<ClientEvent>) -> ChannelHistoryReceivedEvent {
+    loop {
+        let event = timeout(Duration::from_secs(20), events.message())
+            .await
+            .expect("timed out waiting for an event")
+            .expect("events stream failed")
+            .expect("events stream ended")
+            .event
+            .expect("missing event");
+
+        if let Event::ChannelHistoryReceived(e) = event {
+            return e;
+        }
+    }
+}
+
+/// Returns the texts of the items of a channel history page
+fn page_texts(page: &ChannelHistoryReceivedEvent) -> Vec<String> {
+    page.content_items
+        .iter()
+        .map(|i| {
+            i.content_item
+                .as_ref()
+                .unwrap()
+                .get_simple_text_content()
+                .unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn channel_history() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &["tests/client_a_conf.json", "tests/client_b_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_a_id = bundles[0].get_client_entity().unwrap();
+
+    client_a
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    info!("a creates a status updates channel and a group...");
+    let channel_bundle = client_a
+        .user_create_status_update_channel(UserCreateStatusUpdateChannelRequest {
+            channel_name: "A Status Updates".into(),
+            subscription_fee: 0,
+        })
+        .await
+        .expect("failed to create channel")
+        .into_inner()
+        .channel_bundle
+        .unwrap();
+    let channel_id = channel_bundle.get_channel_id().unwrap();
+
+    let group_bundle = client_a
+        .user_create_group(UserCreateGroupRequest {
+            group_name: "A Group".into(),
+            approve_members: false,
+        })
+        .await
+        .expect("failed to create group")
+        .into_inner()
+        .channel_bundle
+        .unwrap();
+    let group_id = group_bundle.get_channel_id().unwrap();
+
+    info!("a posts to its channel and group before b subscribes...");
+    for text in ["first", "second", "third"] {
+        client_a
+            .user_new_post(UserNewPostRequest {
+                channel_id: channel_bundle.channel_id.clone(),
+                reply_to: 0,
+                text: text.into(),
+            })
+            .await
+            .expect("failed to post");
+    }
+
+    client_a
+        .user_new_post(UserNewPostRequest {
+            channel_id: group_bundle.channel_id.clone(),
+            reply_to: 0,
+            text: "welcome to the group".into(),
+        })
+        .await
+        .expect("failed to post");
+
+    client_b
+        .user_subscribe_to_status_updates(UserSubscribeRequest {
+            channel_bundle: Some(channel_bundle.clone()),
+        })
+        .await
+        .expect("failed to subscribe");
+
+    client_b
+        .user_join_group(UserJoinGroupRequest {
+            channel_bundle: Some(group_bundle.clone()),
+        })
+        .await
+        .expect("failed to join group");
+
+    // wait for b to get a's responses to its subscription requests
+    sleep(Duration::from_millis(2000)).await;
+
+    info!("b gets the latest page of the channel's history...");
+    client_b
+        .user_get_channel_history(UserGetChannelHistoryRequest {
+            channel_id: channel_id.clone(),
+            before: 0,
+            max_items: 2,
+        })
+        .await
+        .expect("failed to get channel history");
+
+    let page = next_history_page(&mut b_events).await;
+    assert!(page.error.is_empty());
+    assert_eq!(page.channel_id, channel_id);
+    assert_eq!(page_texts(&page), vec!["third", "second"]);
+    assert!(page.has_more);
+
+    // pages are signed by the channel's creator
+    for item in page.content_items.iter() {
+        item.verify_signature(client_a_id.get_id().unwrap())
+            .expect("invalid history item signature");
+    }
+
+    info!("b gets the next page of the channel's history...");
+    let before = page
+        .content_items
+        .last()
+        .unwrap()
+        .content_item
+        .as_ref()
+        .unwrap()
+        .created;
+    client_b
+        .user_get_channel_history(UserGetChannelHistoryRequest {
+            channel_id: channel_id.clone(),
+            before,
+            max_items: 2,
+        })
+        .await
+        .expect("failed to get channel history");
+
+    let page = next_history_page(&mut b_events).await;
+    assert_eq!(page_texts(&page), vec!["first"]);
+    assert!(!page.has_more);
+
+    info!("b gets the group's history...");
+    client_b
+        .user_get_channel_history(UserGetChannelHistoryRequest {
+            channel_id: group_id.clone(),
+            before: 0,
+            max_items: 0,
+        })
+        .await
+        .expect("failed to get group history");
+
+    let page = next_history_page(&mut b_events).await;
+    assert_eq!(page.channel_id, group_id);
+    assert_eq!(page_texts(&page), vec!["welcome to the group"]);
+    assert!(!page.has_more);
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
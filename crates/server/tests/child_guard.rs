@@ -2,10 +2,16 @@ use log::*;
 use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
 use std::process::Child;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
 pub struct ChildGuard(pub Child);
 
+/// How long to wait for a child process to shut down gracefully before killing it
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// A guard of a child os process, sends a ctrl-c SIGINT to child process when it is dropped.
+/// Waits for the process to exit so the next test can reuse its ports and db.
 impl Drop for ChildGuard {
     fn drop(&mut self) {
         let pid = self.0.id() as i32;
@@ -14,5 +20,16 @@ impl Drop for ChildGuard {
             Err(e) => debug!("could not kill child process id {}: {}", pid, e),
             Ok(_) => debug!("killed child process id {}", pid),
         }
+
+        let start = Instant::now();
+        while let Ok(None) = self.0.try_wait() {
+            if start.elapsed() > SHUTDOWN_TIMEOUT {
+                warn!("child process id {} didn't shut down - killing it", pid);
+                let _ = self.0.kill();
+                let _ = self.0.wait();
+                return;
+            }
+            sleep(Duration::from_millis(50));
+        }
     }
 }
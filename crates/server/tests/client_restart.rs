@@ -0,0 +1,228 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::EntityId;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::fs;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, provider_dialup_info, set_providers_blockchain_service,
+    start_blockchain, start_client, start_provider, wait_for_services,
+};
+use tokio::time::{sleep, timeout};
+use tonic::transport::Channel;
+use tonic::Streaming;
+
+/*
+In this test client G, which keeps its db when it exits, exchanges messages with client B in a DR session.
+G is then restarted. It resumes service with its provider with the same identity and keeps messaging with B in the
+existing DR session - B sends G a message in the session B had with G before the restart, and G replies in it.
+*/
+
+/// db of client g. Removed before and after the test so each run starts with a new identity
+const CLIENT_G_DB: &str = "client_g_db";
+
+/// Returns the next text message received on an events stream
+async fn next_text_message(events: &mut Streaming<ClientEvent>) -> InboxMessage {
+    loop {
+        let event = timeout(Duration::from_secs(20), events.message())
+            .await
+            .expect("timed out waiting for an event")
+            .expect("events stream failed")
+            .expect("events stream ended")
+            .event
+            .expect("missing event");
+
+        if let Event::TextMessageReceived(e) = event {
+            return e.message.unwrap();
+        }
+    }
+}
+
+/// Send a text message and verify the receiver got it from the sender
+async fn send_text(
+    sender: &mut SimpleClientUserServiceClient<Channel>,
+    sender_id: &EntityId,
+    receiver_id: &EntityId,
+    receiver_events: &mut Streaming<ClientEvent>,
+    text: &str,
+) {
+    sender
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(receiver_id.clone()),
+            user_text: text.into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message");
+
+    let message = next_text_message(receiver_events).await;
+    assert_eq!(message.sender_id.as_ref(), Some(sender_id));
+    assert_eq!(
+        message
+            .content_item
+            .unwrap()
+            .get_simple_text_content()
+            .unwrap(),
+        text
+    );
+}
+
+#[tokio::test]
+async fn client_restart() {
+    enable_logger();
+
+    let _ = fs::remove_dir_all(CLIENT_G_DB);
+
+    let bc_guard = start_blockchain("tests/blockchain_service2.json");
+    let provider_guards = [
+        start_provider("tests/spc_conf.json"),
+        start_provider("tests/spd_conf.json"),
+    ];
+    let g_guard = start_client("tests/client_g_conf.json");
+    let b_guard = start_client("tests/client_b_conf.json");
+
+    wait_for_services().await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_g = SimpleClientUserServiceClient::connect("http://[::1]:3039")
+        .await
+        .expect("failed to connect to client g");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_g, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_g_entity = bundles[0].get_client_entity().unwrap();
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+
+    client_g
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut g_events = client_g
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    info!("g and b start a dr session...");
+    send_text(
+        &mut client_g,
+        &client_g_entity,
+        &client_b_entity,
+        &mut b_events,
+        "Hi B, this is G",
+    )
+    .await;
+    send_text(
+        &mut client_b,
+        &client_b_entity,
+        &client_g_entity,
+        &mut g_events,
+        "Hi G",
+    )
+    .await;
+
+    info!("restarting g...");
+    drop(g_events);
+    drop(client_g);
+    drop(g_guard);
+    sleep(Duration::from_millis(1000)).await;
+    let g_guard = start_client("tests/client_g_conf.json");
+    sleep(Duration::from_millis(3000)).await; // Wait for g to resume service with its provider
+
+    let mut client_g = SimpleClientUserServiceClient::connect("http://[::1]:3039")
+        .await
+        .expect("failed to connect to restarted client g");
+
+    let mut g_events = client_g
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    // b doesn't know g restarted. G can only read b's message if it resumed with its identity and dr session
+    send_text(
+        &mut client_b,
+        &client_b_entity,
+        &client_g_entity,
+        &mut g_events,
+        "Are you still there?",
+    )
+    .await;
+
+    // g replies with its stored bundle of b in its existing dr session
+    send_text(
+        &mut client_g,
+        &client_g_entity,
+        &client_b_entity,
+        &mut b_events,
+        "Still here after a restart",
+    )
+    .await;
+
+    // the restarted client keeps its conversation with b
+    let conversations = client_g
+        .user_list_conversations(UserListConversationsRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .conversations;
+    assert_eq!(conversations.len(), 1);
+
+    drop(client_g);
+    drop(g_guard);
+    let _ = fs::remove_dir_all(CLIENT_G_DB);
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", bc_guard.0.id());
+    debug!("{}", provider_guards.len());
+    debug!("{}", b_guard.0.id());
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::EntityId;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, provider_dialup_info, set_providers_blockchain_service, start_network,
+};
+use tokio::time::{sleep, timeout};
+use tonic::Streaming;
+
+/*
+In this test clients A and B, served by providers C and D, message each other without adding each other's bundles.
+B sends A a message by A's id and A replies to B by B's nickname. Both bundles are looked up on the blockchain,
+where they were published by the clients' providers when they started serving them.
+*/
+
+/// Returns the text of the next text message received event of an events stream
+async fn next_text_message(events: &mut Streaming<ClientEvent>) -> (EntityId, String) {
+    loop {
+        let event = timeout(Duration::from_secs(20), events.message())
+            .await
+            .expect("timed out waiting for an event")
+            .expect("events stream failed")
+            .expect("events stream ended")
+            .event
+            .expect("missing event");
+
+        match event {
+            Event::TextMessageReceived(e) => {
+                let message = e.message.unwrap();
+                return (
+                    message.sender_id.unwrap(),
+                    message
+                        .content_item
+                        .unwrap()
+                        .get_simple_text_content()
+                        .unwrap(),
+                );
+            }
+            // receipts for messages we sent
+            Event::MessageStatusChanged(_) => continue,
+            e => panic!("unexpected event: {:?}", e),
+        }
+    }
+}
+
+#[tokio::test]
+async fn contact_discovery() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &["tests/client_a_conf.json", "tests/client_b_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_a_entity = bundles[0].get_client_entity().unwrap();
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+
+    // wait for the providers' bundle transactions of a and b to be added to the blockchain
+    sleep(Duration::from_millis(5000)).await;
+
+    let mut a_events = client_a
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    info!("b sends a message to a by its id...");
+    client_b
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(EntityId {
+                public_key: client_a_entity.public_key.clone(),
+                nickname: "".into(),
+            }),
+            user_text: "Hi A, this is B".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to a by id");
+
+    // a doesn't know b's bundle yet so it only knows b by its id
+    let (sender, text) = next_text_message(&mut a_events).await;
+    assert_eq!(sender.public_key, client_b_entity.public_key);
+    assert_eq!(text, "Hi A, this is B");
+
+    info!("a replies to b by its nickname...");
+    client_a
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(EntityId {
+                public_key: None,
+                nickname: client_b_entity.nickname.clone(),
+            }),
+            user_text: "Hi B".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to b by nickname");
+
+    let (sender, text) = next_text_message(&mut b_events).await;
+    assert_eq!(sender, client_a_entity);
+    assert_eq!(text, "Hi B");
+
+    // clients which are not on the blockchain can't be messaged
+    assert!(client_a
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(EntityId {
+                public_key: None,
+                nickname: "nobody".into(),
+            }),
+            user_text: "Hello?".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .is_err());
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -0,0 +1,200 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::hex_utils::hex_string;
+use base::snp::snp_core_types::{DialupInfo, ProviderSignedClientIdentityBundle};
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, provider_dialup_info, set_providers_blockchain_service,
+    start_blockchain, start_client, start_provider, wait_for_services,
+};
+use tokio::time::sleep;
+use tonic::transport::Channel;
+
+/*
+In this test client A pins the identity of its contact B on first use and both clients compare their safety numbers.
+B's nickname is registered to its identity key on the blockchain. B then comes back with a new identity key and the
+same nickname. A doesn't take the new identity for its contact B and keeps showing B's pinned identity. The new
+identity is a new contact which is known by its identity key.
+*/
+
+/// Connect a client to a blockchain service and a provider and return its provider signed bundle
+async fn set_provider(
+    client: &mut SimpleClientUserServiceClient<Channel>,
+    bc_dialup_info: &DialupInfo,
+    port: u32,
+    name: &str,
+) -> ProviderSignedClientIdentityBundle {
+    client
+        .set_blockchain_service(SetBlockchainServiceRequest {
+            dialup_info: Some(bc_dialup_info.clone()),
+        })
+        .await
+        .unwrap();
+
+    client
+        .user_set_provider(UserSetProviderRequest {
+            dialup_info: Some(provider_dialup_info(port, name)),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .client_bundle
+        .unwrap()
+}
+
+async fn get_safety_number(
+    client: &mut SimpleClientUserServiceClient<Channel>,
+    nickname: &str,
+) -> UserGetContactSafetyNumberResponse {
+    client
+        .user_get_contact_safety_number(UserGetContactSafetyNumberRequest {
+            nickname: nickname.into(),
+        })
+        .await
+        .expect("failed to get contact safety number")
+        .into_inner()
+}
+
+#[tokio::test]
+async fn contact_verification() {
+    enable_logger();
+
+    let bc_guard = start_blockchain("tests/blockchain_service1.json");
+    let provider_guards = [
+        start_provider("tests/spa_conf.json"),
+        start_provider("tests/spb_conf.json"),
+    ];
+    let a_guard = start_client("tests/client_a_conf.json");
+    let b_guard = start_client("tests/client_b_conf.json");
+
+    wait_for_services().await;
+
+    let bc_dialup_info = blockchain_dialup_info(5555);
+    set_providers_blockchain_service(&[9082, 9083], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let a_bundle = set_provider(&mut client_a, &bc_dialup_info, 8082, "ServiceProviderA").await;
+    let b_bundle = set_provider(&mut client_b, &bc_dialup_info, 8083, "ServiceProviderB").await;
+
+    client_a
+        .user_add_other_client_bundle(b_bundle.clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(a_bundle.clone())
+        .await
+        .unwrap();
+
+    // both clients compute the same safety number
+    let a_view = get_safety_number(&mut client_a, "B").await;
+    let b_view = get_safety_number(&mut client_b, "A").await;
+    assert_eq!(a_view.safety_number.len(), 60);
+    assert_eq!(a_view.safety_number, b_view.safety_number);
+    assert!(!a_view.verified);
+    assert!(!a_view.identity_changed);
+
+    client_a
+        .user_verify_contact(UserVerifyContactRequest {
+            nickname: "B".into(),
+            safety_number: "0".repeat(60),
+        })
+        .await
+        .expect_err("expected wrong safety number to be rejected");
+
+    client_a
+        .user_verify_contact(UserVerifyContactRequest {
+            nickname: "B".into(),
+            safety_number: a_view.safety_number.clone(),
+        })
+        .await
+        .expect("failed to verify contact");
+    assert!(get_safety_number(&mut client_a, "B").await.verified);
+
+    info!("client b comes back with a new identity...");
+
+    drop(client_b);
+    drop(b_guard);
+    sleep(Duration::from_millis(2000)).await;
+    let b_guard = start_client("tests/client_b_conf.json");
+    sleep(Duration::from_millis(3000)).await;
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let new_b_bundle = set_provider(&mut client_b, &bc_dialup_info, 8083, "ServiceProviderB").await;
+    let new_b_entity = new_b_bundle.get_client_entity().unwrap();
+    assert_ne!(new_b_entity, b_bundle.get_client_entity().unwrap());
+
+    client_a
+        .user_add_other_client_bundle(new_b_bundle.clone())
+        .await
+        .unwrap();
+
+    // a bundle with b's nickname which is not registered to its key doesn't change a's contact b
+    let a_new_view = get_safety_number(&mut client_a, "B").await;
+    assert!(!a_new_view.identity_changed);
+    assert!(a_new_view.verified);
+    assert_eq!(a_new_view.safety_number, a_view.safety_number);
+    assert_eq!(
+        a_new_view.contact_id.unwrap().public_key,
+        b_bundle.get_client_entity().unwrap().public_key
+    );
+
+    // b's new identity is a new contact known by its identity key
+    let new_b_id = hex_string(new_b_entity.get_id().unwrap());
+    let new_b_view = get_safety_number(&mut client_a, &new_b_id).await;
+    assert!(!new_b_view.verified);
+    assert!(!new_b_view.identity_changed);
+    assert_ne!(new_b_view.safety_number, a_view.safety_number);
+
+    client_b
+        .user_add_other_client_bundle(a_bundle.clone())
+        .await
+        .unwrap();
+    let b_view = get_safety_number(&mut client_b, "A").await;
+    assert_eq!(new_b_view.safety_number, b_view.safety_number);
+
+    client_a
+        .user_verify_contact(UserVerifyContactRequest {
+            nickname: new_b_id.clone(),
+            safety_number: b_view.safety_number.clone(),
+        })
+        .await
+        .expect("failed to verify contact");
+    assert!(get_safety_number(&mut client_a, &new_b_id).await.verified);
+
+    client_a
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(new_b_entity.clone()),
+            user_text: "Hi B".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to verified contact");
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", bc_guard.0.id());
+    debug!("{}", provider_guards.len());
+    debug!("{}", a_guard.0.id());
+    debug!("{}", b_guard.0.id());
+}
@@ -0,0 +1,317 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::{ChannelType, GroupAdminAction};
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, next_event, provider_dialup_info, set_providers_blockchain_service,
+    start_network,
+};
+use tokio::time::sleep;
+use tonic::Streaming;
+
+/*
+In this test client A creates a group which requires approving its members and clients B and C ask to join it.
+A approves B and appoints it as a co-admin. B approves C and A blocks C from replying to posts.
+C's reply isn't published to the group while its post is. B then bans C, which is removed from the group and
+can't join it again.
+*/
+
+/// Returns the next client event
+/// Returns the text of the next group post received on an events stream
+async fn next_group_post(events: &mut Streaming<ClientEvent>) -> String {
+    loop {
+        if let Event::ChannelPostReceived(e) = next_event(events).await {
+            assert_eq!(e.channel_type, ChannelType::Group as i32);
+            let item = e.message.unwrap().content_item.unwrap();
+            return item.get_simple_text_content().unwrap();
+        }
+    }
+}
+
+/// Returns the next group members update received on an events stream
+async fn next_members_update(events: &mut Streaming<ClientEvent>) -> GroupMembersUpdatedEvent {
+    loop {
+        if let Event::GroupMembersUpdated(e) = next_event(events).await {
+            e.members.as_ref().unwrap().verify().unwrap();
+            return e;
+        }
+    }
+}
+
+#[tokio::test]
+async fn group_admin() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &[
+            "tests/client_a_conf.json",
+            "tests/client_b_conf.json",
+            "tests/client_c_conf.json",
+        ],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let mut client_c = SimpleClientUserServiceClient::connect("http://[::1]:3035")
+        .await
+        .expect("failed to connect to client c");
+
+    // a is served by provider c. b and c are served by provider d
+    let providers = [
+        (8084, "ServiceProviderC"),
+        (8085, "ServiceProviderD"),
+        (8085, "ServiceProviderD"),
+    ];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b, &mut client_c]
+        .iter_mut()
+        .zip(providers)
+    {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    for bundle in &bundles[1..] {
+        client_a
+            .user_add_other_client_bundle(bundle.clone())
+            .await
+            .unwrap();
+    }
+    for client in [&mut client_b, &mut client_c] {
+        client
+            .user_add_other_client_bundle(bundles[0].clone())
+            .await
+            .unwrap();
+    }
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut c_events = client_c
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+    let client_c_entity = bundles[2].get_client_entity().unwrap();
+
+    info!("a creates a group and b and c ask to join it...");
+    let channel_bundle = client_a
+        .user_create_group(UserCreateGroupRequest {
+            group_name: "A Private Group".into(),
+            approve_members: true,
+        })
+        .await
+        .expect("failed to create group")
+        .into_inner()
+        .channel_bundle
+        .unwrap();
+    let channel_id = channel_bundle.channel_id.as_ref().unwrap().clone();
+    let group_id = channel_bundle.get_channel_id().unwrap();
+
+    for client in [&mut client_b, &mut client_c] {
+        client
+            .user_join_group(UserJoinGroupRequest {
+                channel_bundle: Some(channel_bundle.clone()),
+            })
+            .await
+            .expect("failed to join group");
+    }
+
+    // wait for a to get the requests
+    sleep(Duration::from_millis(2000)).await;
+
+    let members = client_a
+        .user_get_group_members(UserGetGroupMembersRequest {
+            channel_id: group_id.clone(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(members.members.unwrap().members.len(), 1);
+    assert_eq!(members.pending_requests.len(), 2);
+
+    info!("a approves b and appoints it as a co-admin...");
+    for action in [GroupAdminAction::ApproveMember, GroupAdminAction::AddAdmin] {
+        client_a
+            .user_group_admin(UserGroupAdminRequest {
+                channel_id: group_id.clone(),
+                action: action as i32,
+                user_id: Some(client_b_entity.clone()),
+            })
+            .await
+            .expect("failed to change group membership");
+
+        let update = next_members_update(&mut b_events).await;
+        assert!(!update.removed);
+        let members = update.members.unwrap();
+        assert!(members
+            .get_member(client_b_entity.get_id().unwrap())
+            .is_some());
+        assert_eq!(members.last_change.unwrap().action, action as i32);
+    }
+
+    // wait for b to get a's response to its request to join the group
+    sleep(Duration::from_millis(1000)).await;
+
+    info!("co-admin b approves c...");
+    client_b
+        .user_group_admin(UserGroupAdminRequest {
+            channel_id: group_id.clone(),
+            action: GroupAdminAction::ApproveMember as i32,
+            user_id: Some(client_c_entity.clone()),
+        })
+        .await
+        .expect("failed to change group membership");
+
+    let members = next_members_update(&mut c_events).await.members.unwrap();
+    assert_eq!(members.members.len(), 3);
+    assert!(members.is_admin(client_b_entity.get_id().unwrap()));
+    assert_eq!(
+        members.last_change.unwrap().admin_id.unwrap(),
+        client_b_entity
+    );
+
+    info!("co-admin b may not appoint co-admins...");
+    client_b
+        .user_group_admin(UserGroupAdminRequest {
+            channel_id: group_id.clone(),
+            action: GroupAdminAction::AddAdmin as i32,
+            user_id: Some(client_c_entity.clone()),
+        })
+        .await
+        .expect("failed to send group admin request");
+
+    sleep(Duration::from_millis(1000)).await;
+
+    let members = client_a
+        .user_get_group_members(UserGetGroupMembersRequest {
+            channel_id: group_id.clone(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        members.members.unwrap().admins,
+        vec![client_b_entity.clone()]
+    );
+    assert!(members.pending_requests.is_empty());
+
+    info!("a blocks c from replying...");
+    client_a
+        .user_group_admin(UserGroupAdminRequest {
+            channel_id: group_id.clone(),
+            action: GroupAdminAction::BlockReplier as i32,
+            user_id: Some(client_c_entity.clone()),
+        })
+        .await
+        .expect("failed to change group membership");
+
+    let post_id = client_a
+        .user_new_post(UserNewPostRequest {
+            channel_id: Some(channel_id.clone()),
+            reply_to: 0,
+            text: "hello members".into(),
+        })
+        .await
+        .expect("failed to post")
+        .into_inner()
+        .post_id;
+
+    assert_eq!(next_group_post(&mut b_events).await, "hello members");
+    assert_eq!(next_group_post(&mut c_events).await, "hello members");
+
+    for (reply_to, text) in [(post_id, "a blocked reply"), (0, "a post from c")] {
+        client_c
+            .user_new_post(UserNewPostRequest {
+                channel_id: Some(channel_id.clone()),
+                reply_to,
+                text: text.into(),
+            })
+            .await
+            .expect("failed to post");
+    }
+
+    // c's reply is dropped by a
+    assert_eq!(next_group_post(&mut b_events).await, "a post from c");
+
+    info!("co-admin b bans c...");
+    client_b
+        .user_group_admin(UserGroupAdminRequest {
+            channel_id: group_id.clone(),
+            action: GroupAdminAction::BanMember as i32,
+            user_id: Some(client_c_entity.clone()),
+        })
+        .await
+        .expect("failed to change group membership");
+
+    let update = next_members_update(&mut c_events).await;
+    assert!(update.removed);
+    assert_eq!(update.members.unwrap().members.len(), 2);
+
+    info!("banned c asks to join the group again...");
+    client_c
+        .user_join_group(UserJoinGroupRequest {
+            channel_bundle: Some(channel_bundle.clone()),
+        })
+        .await
+        .expect("failed to join group");
+
+    sleep(Duration::from_millis(1000)).await;
+
+    let members = client_a
+        .user_get_group_members(UserGetGroupMembersRequest {
+            channel_id: group_id.clone(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(members.members.unwrap().members.len(), 2);
+    assert!(members.pending_requests.is_empty());
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -0,0 +1,198 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::ChannelType;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, provider_dialup_info, set_providers_blockchain_service, start_network,
+};
+use tokio::time::{sleep, timeout};
+use tonic::Streaming;
+
+/*
+In this test client A creates a group and clients B and C join it. A encrypts each group message once with its
+group sender key and its provider forwards the message to the providers of B and C.
+B and C receive A's posts and C receives B's post which A publishes to the group.
+*/
+
+/// Returns the text of the next group post received on an events stream
+async fn next_group_post(events: &mut Streaming<ClientEvent>) -> String {
+    loop {
+        let event = timeout(Duration::from_secs(20), events.message())
+            .await
+            .expect("timed out waiting for an event")
+            .expect("events stream failed")
+            .expect("events stream ended")
+            .event
+            .expect("missing event");
+
+        if let Event::ChannelPostReceived(e) = event {
+            assert_eq!(e.channel_type, ChannelType::Group as i32);
+            let item = e.message.unwrap().content_item.unwrap();
+            return item.get_simple_text_content().unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn group_messages() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &[
+            "tests/client_a_conf.json",
+            "tests/client_b_conf.json",
+            "tests/client_c_conf.json",
+        ],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let mut client_c = SimpleClientUserServiceClient::connect("http://[::1]:3035")
+        .await
+        .expect("failed to connect to client c");
+
+    // a is served by provider c. b and c are served by provider d
+    let providers = [
+        (8084, "ServiceProviderC"),
+        (8085, "ServiceProviderD"),
+        (8085, "ServiceProviderD"),
+    ];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b, &mut client_c]
+        .iter_mut()
+        .zip(providers)
+    {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    for bundle in &bundles[1..] {
+        client_a
+            .user_add_other_client_bundle(bundle.clone())
+            .await
+            .unwrap();
+    }
+    for client in [&mut client_b, &mut client_c] {
+        client
+            .user_add_other_client_bundle(bundles[0].clone())
+            .await
+            .unwrap();
+    }
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut c_events = client_c
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    info!("a creates a group and b and c join it...");
+    let channel_bundle = client_a
+        .user_create_group(UserCreateGroupRequest {
+            group_name: "A Group".into(),
+            approve_members: false,
+        })
+        .await
+        .expect("failed to create group")
+        .into_inner()
+        .channel_bundle
+        .unwrap();
+    let channel_id = channel_bundle.channel_id.as_ref().unwrap().clone();
+
+    for client in [&mut client_b, &mut client_c] {
+        client
+            .user_join_group(UserJoinGroupRequest {
+                channel_bundle: Some(channel_bundle.clone()),
+            })
+            .await
+            .expect("failed to join group");
+    }
+
+    // wait for b and c to get a's responses to their requests to join the group
+    sleep(Duration::from_millis(2000)).await;
+
+    info!("a posts to its group...");
+    client_a
+        .user_new_post(UserNewPostRequest {
+            channel_id: Some(channel_id.clone()),
+            reply_to: 0,
+            text: "hello group".into(),
+        })
+        .await
+        .expect("failed to post");
+
+    assert_eq!(next_group_post(&mut b_events).await, "hello group");
+    assert_eq!(next_group_post(&mut c_events).await, "hello group");
+
+    info!("b posts to a's group...");
+    client_b
+        .user_new_post(UserNewPostRequest {
+            channel_id: Some(channel_id.clone()),
+            reply_to: 0,
+            text: "hello from b".into(),
+        })
+        .await
+        .expect("failed to post");
+
+    assert_eq!(next_group_post(&mut c_events).await, "hello from b");
+
+    info!("a posts again with the same sender key...");
+    client_a
+        .user_new_post(UserNewPostRequest {
+            channel_id: Some(channel_id),
+            reply_to: 0,
+            text: "hello again".into(),
+        })
+        .await
+        .expect("failed to post");
+
+    assert_eq!(next_group_post(&mut b_events).await, "hello again");
+    assert_eq!(next_group_post(&mut c_events).await, "hello again");
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::api_types_extensions::Signed;
+use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
+use base::snp::snp_blockchain::{
+    GetTransparencyLogConsistencyProofRequest, GetTransparencyLogHeadRequest,
+};
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use base::transparency_log::verify_consistency;
+use test_helpers::{
+    blockchain_dialup_info, provider_dialup_info, set_providers_blockchain_service, start_network,
+};
+
+/*
+In this test client C audits its identity bundles history in the blockchain transparency log.
+C's bundles are logged when it starts service with provider SC and when it switches to provider SD.
+C finds all its logged bundles were published by it and that the log only grew between its audits.
+*/
+
+#[tokio::test]
+async fn identity_transparency() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &["tests/client_c_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_c = SimpleClientUserServiceClient::connect("http://[::1]:3035")
+        .await
+        .expect("failed to connect to client c");
+
+    client_c
+        .set_blockchain_service(SetBlockchainServiceRequest {
+            dialup_info: Some(bc_dialup_info.clone()),
+        })
+        .await
+        .unwrap();
+
+    client_c
+        .user_set_provider(UserSetProviderRequest {
+            dialup_info: Some(provider_dialup_info(8084, "ServiceProviderC")),
+        })
+        .await
+        .expect("failed to set provider");
+
+    let first_audit = client_c
+        .user_audit_identity(UserAuditIdentityRequest {})
+        .await
+        .expect("failed to audit identity")
+        .into_inner();
+
+    assert!(first_audit.bundles_count >= 1);
+    assert!(first_audit.unknown_bundles.is_empty());
+    let first_head = first_audit.head.unwrap();
+
+    info!("switching c's provider...");
+
+    client_c
+        .user_switch_provider(UserSwitchProviderRequest {
+            dialup_info: Some(provider_dialup_info(8085, "ServiceProviderD")),
+        })
+        .await
+        .expect("failed to switch provider");
+
+    // c verifies the new log head is consistent with the head of its first audit
+    let second_audit = client_c
+        .user_audit_identity(UserAuditIdentityRequest {})
+        .await
+        .expect("failed to audit identity")
+        .into_inner();
+
+    assert!(second_audit.bundles_count > first_audit.bundles_count);
+    assert!(second_audit.unknown_bundles.is_empty());
+    let second_head = second_audit.head.unwrap();
+    assert!(second_head.tree_size > first_head.tree_size);
+
+    // anyone can verify the log is append-only between the two heads
+    let mut bc_client = BlockchainServiceClient::connect("http://[::1]:5556")
+        .await
+        .expect("failed to connect to blockchain service");
+
+    let current_head = bc_client
+        .get_transparency_log_head(GetTransparencyLogHeadRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .head
+        .unwrap();
+    assert!(current_head.tree_size >= second_head.tree_size);
+
+    // all heads are signed by the log's key
+    for head in [&first_head, &second_head, &current_head] {
+        head.verify_signature().expect("invalid log head signature");
+        assert_eq!(
+            head.get_log_key().unwrap(),
+            current_head.get_log_key().unwrap()
+        );
+    }
+
+    let proof = bc_client
+        .get_transparency_log_consistency_proof(GetTransparencyLogConsistencyProofRequest {
+            first_tree_size: first_head.tree_size,
+            second_tree_size: second_head.tree_size,
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .proof;
+
+    verify_consistency(
+        first_head.tree_size,
+        &first_head.root_hash,
+        second_head.tree_size,
+        &second_head.root_hash,
+        &proof,
+    )
+    .expect("log heads are not consistent");
+
+    // a head with a rewritten history is rejected
+    assert!(verify_consistency(
+        first_head.tree_size,
+        &[0u8; 32],
+        second_head.tree_size,
+        &second_head.root_hash,
+        &proof,
+    )
+    .is_err());
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
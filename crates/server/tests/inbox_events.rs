@@ -0,0 +1,193 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, next_event, provider_dialup_info, set_providers_blockchain_service,
+    start_blockchain, start_client, start_provider, wait_for_services,
+};
+use tokio::time::sleep;
+
+/*
+In this test client A, which is served by provider C, subscribes to its events and receives text messages from
+client B, which is served by provider D. A reads the messages from its inbox conversation with B page by page.
+When provider D goes down, A gets a delivery failure event for its message to B.
+*/
+
+#[tokio::test]
+async fn inbox_events() {
+    enable_logger();
+
+    let bc_guard = start_blockchain("tests/blockchain_service2.json");
+    let spc_guard = start_provider("tests/spc_conf.json");
+    let spd_guard = start_provider("tests/spd_conf.json");
+    let client_guards = [
+        start_client("tests/client_a_conf.json"),
+        start_client("tests/client_b_conf.json"),
+    ];
+
+    wait_for_services().await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_a_entity = bundles[0].get_client_entity().unwrap();
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+
+    client_a
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut events = client_a
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let texts = ["Hi A, this is B", "How are you?", "Talk soon"];
+    for text in texts {
+        client_b
+            .user_send_text_message(UserSendTextMessageRequest {
+                other_client_id: Some(client_a_entity.clone()),
+                user_text: text.into(),
+                reply_to: 0,
+                ttl: 0,
+            })
+            .await
+            .expect("failed to send message to a");
+
+        match next_event(&mut events).await {
+            Event::TextMessageReceived(e) => {
+                let message = e.message.unwrap();
+                assert_eq!(message.sender_id.unwrap(), client_b_entity);
+                assert_eq!(
+                    message
+                        .content_item
+                        .unwrap()
+                        .get_simple_text_content()
+                        .unwrap(),
+                    text
+                );
+            }
+            e => panic!("unexpected event: {:?}", e),
+        }
+    }
+
+    // a reads its conversation with b from its inbox
+    let conversations = client_a
+        .user_list_conversations(UserListConversationsRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .conversations;
+    assert_eq!(conversations.len(), 1);
+    let conversation = &conversations[0];
+    assert_eq!(
+        conversation.conversation_type,
+        ConversationType::Direct as i32
+    );
+    assert_eq!(conversation.messages_count, texts.len() as u64);
+
+    let mut received = vec![];
+    let mut from_index = 0;
+    loop {
+        let page = client_a
+            .user_get_conversation_messages(UserGetConversationMessagesRequest {
+                conversation_id: conversation.conversation_id.clone(),
+                from_index,
+                max_results: 2,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(page.total_count, texts.len() as u64);
+        if page.messages.is_empty() {
+            break;
+        }
+        for message in page.messages {
+            assert_eq!(message.index, from_index);
+            from_index += 1;
+            received.push(
+                message
+                    .content_item
+                    .unwrap()
+                    .get_simple_text_content()
+                    .unwrap(),
+            );
+        }
+    }
+    assert_eq!(received, texts);
+
+    info!("stopping b's provider...");
+    drop(spd_guard);
+    sleep(Duration::from_millis(500)).await;
+
+    assert!(client_a
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_b_entity.clone()),
+            user_text: "Are you there?".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .is_err());
+
+    match next_event(&mut events).await {
+        Event::DeliveryFailure(e) => {
+            assert_eq!(e.receiver_id.unwrap(), client_b_entity);
+            assert!(!e.error.is_empty());
+        }
+        e => panic!("unexpected event: {:?}", e),
+    }
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", bc_guard.0.id());
+    debug!("{}", spc_guard.0.id());
+    debug!("{}", client_guards.len());
+}
@@ -0,0 +1,198 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use test_helpers::{
+    blockchain_dialup_info, next_event, provider_dialup_info, set_providers_blockchain_service,
+    start_network,
+};
+use tonic::Streaming;
+
+/*
+In this test client B sends text messages to client A. B gets a delivered receipt when A receives a message and
+a read receipt when A's user marks it as read. After A turns off read receipts to B, B only gets delivered receipts.
+*/
+
+/// Returns the sent message of the next message status changed event of an events stream
+async fn next_status_change(events: &mut Streaming<ClientEvent>) -> SentMessage {
+    match next_event(events).await {
+        Event::MessageStatusChanged(e) => e.message.unwrap(),
+        e => panic!("unexpected event: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn message_receipts() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &["tests/client_a_conf.json", "tests/client_b_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_a_entity = bundles[0].get_client_entity().unwrap();
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+
+    client_a
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut a_events = client_a
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let message_id = client_b
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_a_entity.clone()),
+            user_text: "Hi A, this is B".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to a")
+        .into_inner()
+        .message_id;
+
+    match next_event(&mut a_events).await {
+        Event::TextMessageReceived(_) => {}
+        e => panic!("unexpected event: {:?}", e),
+    }
+
+    let message = next_status_change(&mut b_events).await;
+    assert_eq!(message.message_id, message_id);
+    assert_eq!(message.status, MessageStatus::Delivered as i32);
+    assert_eq!(message.receiver_id.unwrap(), client_a_entity);
+
+    info!("a reads b's message...");
+    let receipt_sent = client_a
+        .user_mark_messages_read(UserMarkMessagesReadRequest {
+            other_client_id: Some(client_b_entity.clone()),
+            message_ids: vec![message_id],
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .receipt_sent;
+    assert!(receipt_sent);
+
+    let message = next_status_change(&mut b_events).await;
+    assert_eq!(message.message_id, message_id);
+    assert_eq!(message.status, MessageStatus::Read as i32);
+
+    info!("a turns off read receipts to b...");
+    client_a
+        .user_set_read_receipts(UserSetReadReceiptsRequest {
+            contact_id: Some(client_b_entity.clone()),
+            enabled: false,
+        })
+        .await
+        .unwrap();
+
+    let second_message_id = client_b
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_a_entity.clone()),
+            user_text: "Did you get this?".into(),
+            reply_to: message_id,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to a")
+        .into_inner()
+        .message_id;
+
+    let message = next_status_change(&mut b_events).await;
+    assert_eq!(message.message_id, second_message_id);
+    assert_eq!(message.status, MessageStatus::Delivered as i32);
+
+    let receipt_sent = client_a
+        .user_mark_messages_read(UserMarkMessagesReadRequest {
+            other_client_id: Some(client_b_entity.clone()),
+            message_ids: vec![second_message_id],
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .receipt_sent;
+    assert!(!receipt_sent);
+
+    let statuses: Vec<(u64, i32)> = client_b
+        .user_get_messages_status(UserGetMessagesStatusRequest {
+            message_ids: vec![message_id, second_message_id, 42],
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .messages
+        .into_iter()
+        .map(|m| (m.message_id, m.status))
+        .collect();
+
+    assert_eq!(
+        statuses,
+        vec![
+            (message_id, MessageStatus::Read as i32),
+            (second_message_id, MessageStatus::Delivered as i32)
+        ]
+    );
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::{GetIdentityBundleRequest, NewSessionRequest};
+use base::test_helpers::enable_logger;
+use chrono::prelude::*;
+use server::server_service::SNP_PROTOCOL_VERSION;
+use test_helpers::{start_provider, wait_for_services};
+use tonic::transport::Channel;
+use tonic::Code;
+
+/*
+In this test provider L is part of network 1 and provider M is part of network 2. Both run at the same time.
+Each provider stamps its network id on its identity bundle and rejects new sessions from other networks.
+*/
+
+async fn verify_bundle_net_id(client: &mut ProviderCoreServiceClient<Channel>, net_id: u32) {
+    let bundle = client
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .bundle
+        .expect("expected provider bundle");
+
+    assert_eq!(bundle.net_id, net_id);
+    assert!(!bundle.dial_up_info.is_empty());
+    for info in bundle.dial_up_info.iter() {
+        assert_eq!(info.net_id, net_id);
+    }
+}
+
+fn new_session_request(net_id: u32) -> NewSessionRequest {
+    NewSessionRequest {
+        time_stamp: Utc::now().timestamp_nanos() as u64,
+        protocol_version: SNP_PROTOCOL_VERSION.into(),
+        net_id,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn network_isolation() {
+    enable_logger();
+
+    let provider_l_guard = start_provider("tests/spl_conf.json");
+    let provider_m_guard = start_provider("tests/spm_conf.json");
+
+    wait_for_services().await;
+
+    let mut client_l = ProviderCoreServiceClient::connect("http://[::1]:8093")
+        .await
+        .expect("failed to connect to spl");
+    let mut client_m = ProviderCoreServiceClient::connect("http://[::1]:8094")
+        .await
+        .expect("failed to connect to spm");
+
+    info!("verifying providers' bundles are stamped with their network id...");
+
+    verify_bundle_net_id(&mut client_l, 1).await;
+    verify_bundle_net_id(&mut client_m, 2).await;
+
+    info!("sending a new session request from network 1 to both providers...");
+
+    let status = client_m
+        .new_session(new_session_request(1))
+        .await
+        .expect_err("expected new session from another network to be rejected");
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(status.message().contains("network"));
+
+    // provider L accepts the network of the request and rejects it for other reasons
+    let status = client_l
+        .new_session(new_session_request(1))
+        .await
+        .expect_err("expected unsigned new session request to be rejected");
+    assert!(!status.message().contains("network"));
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!(
+        "{:?} {:?}",
+        provider_l_guard.0.id(),
+        provider_m_guard.0.id()
+    );
+}
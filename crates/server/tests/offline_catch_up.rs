@@ -0,0 +1,174 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::{ApiEndPoint, DialupInfo};
+use base::snp::upsetter_server_admin::server_admin_service_client::ServerAdminServiceClient;
+use base::snp::upsetter_server_admin::SuspendClientRequest;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, pending_messages, set_providers_blockchain_service, start_network,
+};
+use tokio::time::sleep;
+use tonic::transport::Channel;
+use tonic::Request;
+
+/*
+In this test client D's messages stream with its provider O is closed while C, which is served by provider B,
+sends it a message.
+The message is stored by the provider for D. D reconnects to its provider with backoff and the provider
+replays the metadata of the message stored while D was offline so D gets the message.
+*/
+
+#[tokio::test]
+#[allow(clippy::result_large_err)]
+async fn offline_catch_up() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service1.json",
+        &["tests/spo_conf.json", "tests/spb_conf.json"],
+        &["tests/client_c_conf.json", "tests/client_d_conf.json"],
+    )
+    .await;
+
+    let channel = Channel::from_static("http://[::1]:9096")
+        .connect()
+        .await
+        .expect("failed to connect to spo admin service");
+    let mut admin_client =
+        ServerAdminServiceClient::with_interceptor(channel, |mut req: Request<()>| {
+            req.metadata_mut()
+                .insert("x-admin-key", "spo-admin-key".parse().unwrap());
+            Ok(req)
+        });
+
+    let bc_dialup_info = blockchain_dialup_info(5555);
+
+    admin_client
+        .set_blockchain_service(bc_dialup_info.clone())
+        .await
+        .expect("failed to set blockchain service");
+
+    set_providers_blockchain_service(&[9083], &bc_dialup_info).await;
+
+    let mut client_c = SimpleClientUserServiceClient::connect("http://[::1]:3035")
+        .await
+        .expect("failed to connect to client c");
+
+    let mut client_d = SimpleClientUserServiceClient::connect("http://[::1]:3036")
+        .await
+        .expect("failed to connect to client d");
+
+    let providers = [(8083, "ServiceProviderB"), (8096, "ServiceProviderO")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_c, &mut client_d].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(DialupInfo {
+                    end_point: ApiEndPoint::GrpcWeb2 as i32,
+                    api_version: "0.1.0".into(),
+                    ip_address: "[::1]".into(),
+                    port,
+                    net_id: 0,
+                    name: name.into(),
+                    min_api_version: "".to_string(),
+                }),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_d_entity = bundles[1].get_client_entity().unwrap();
+
+    client_c
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_d
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    info!("closing d's messages stream...");
+
+    // a suspended client's messages stream is closed and it may not subscribe again
+    admin_client
+        .suspend_client(SuspendClientRequest {
+            client_id: Some(client_d_entity.clone()),
+            suspend: true,
+        })
+        .await
+        .expect("failed to suspend client");
+
+    client_c
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_d_entity.clone()),
+            user_text: "Hi D, this is C while you are offline".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to d");
+
+    let clients = admin_client
+        .get_clients(())
+        .await
+        .expect("failed to get clients")
+        .into_inner()
+        .clients;
+    assert_eq!(pending_messages(&clients, &client_d_entity), 1);
+
+    info!("resuming d's service...");
+
+    admin_client
+        .suspend_client(SuspendClientRequest {
+            client_id: Some(client_d_entity.clone()),
+            suspend: false,
+        })
+        .await
+        .expect("failed to resume client");
+
+    // d reconnects with backoff, gets the metadata of the stored message and fetches the message
+    let mut pending = 1;
+    for _ in 0..40 {
+        sleep(Duration::from_millis(500)).await;
+        let clients = admin_client
+            .get_clients(())
+            .await
+            .expect("failed to get clients")
+            .into_inner()
+            .clients;
+        pending = pending_messages(&clients, &client_d_entity);
+        if pending == 0 {
+            break;
+        }
+    }
+    assert_eq!(
+        pending, 0,
+        "expected d to get the message stored while offline"
+    );
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -9,7 +9,7 @@ use base::snp::snp_server_api::{
 };
 use base::test_helpers::enable_logger;
 use chrono::prelude::*;
-use common::network_salt::NET_SALT;
+use common::network_salt::net_salt;
 use common::typed_msg_extensions::TypedMessageExtensions;
 use crypto::utils::X25519PublicKeyWrapper;
 use crypto::x2dh;
@@ -118,15 +118,16 @@ async fn p2p_messaging() {
         ikb,
         pkb,
         b_bundle_id: bob_provider_bundle.time_stamp,
+        kem_pkb: None,
     };
 
     // Alice executes x2dh with bob and get the output
-    let output_alice = x2dh::execute_alice(&input_alice);
+    let output_alice = x2dh::execute_alice(&input_alice).unwrap();
 
     // debug!("Alice X2DH output: {:?}", output_alice);
 
     // Alice creates a DR session with bob and using it to get the enc key for her first message with bob
-    let input = SessionKey::from(NET_SALT.as_ref());
+    let input = SessionKey::from(net_salt().as_ref());
     let dr_root_chain_key = ChainKey::from(output_alice.shared_secret.as_ref());
 
     let mut alice_dr = DoubleRatchet::new_with_peer(
@@ -168,6 +169,8 @@ async fn p2p_messaging() {
         receiver: Some(ikb_identity.clone()),
         sender: Some(alice_entity.clone()),
         signature: None,
+        sender_delivery_token: vec![],
+        sender_protocol_version: "".into(),
     };
 
     typed_msg.sign(&alice_id_key_pair_clone).unwrap();
@@ -209,6 +212,8 @@ async fn p2p_messaging() {
         receiver_bundle_id: bob_provider_bundle.time_stamp,
         net_id: 0,
         protocol_version: SNP_PROTOCOL_VERSION.into(),
+        pow_nonce: 0,
+        sender_kem_ciphertext: vec![],
     };
 
     //debug!("new session request: {:?}", new_session_request);
@@ -0,0 +1,143 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::{ApiEndPoint, DialupInfo};
+use base::snp::upsetter_server_admin::server_admin_service_client::ServerAdminServiceClient;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{blockchain_dialup_info, start_network, wait_for_delivery};
+use tokio::time::sleep;
+
+/*
+In this test providers P and Q and their clients E and F are on a network which pads encrypted messages to size buckets.
+E and F also send cover traffic to their providers, which providers discard, while they exchange messages.
+F publishes a hybrid post-quantum x2dh pre-key, so E's new session with F uses hybrid x2dh.
+*/
+
+#[tokio::test]
+async fn padded_messaging() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service1.json",
+        &["tests/spq_conf.json", "tests/spp_conf.json"],
+        &["tests/client_e_conf.json", "tests/client_f_conf.json"],
+    )
+    .await;
+
+    let mut spq_admin_client = ServerAdminServiceClient::connect("http://[::1]:9098")
+        .await
+        .expect("failed to connect to spq admin service");
+
+    let bc_dialup_info = blockchain_dialup_info(5555);
+
+    spq_admin_client
+        .set_blockchain_service(bc_dialup_info.clone())
+        .await
+        .expect("failed to set blockchain service");
+
+    let mut spp_admin_client = ServerAdminServiceClient::connect("http://[::1]:9097")
+        .await
+        .expect("failed to connect to spp admin service");
+
+    spp_admin_client
+        .set_blockchain_service(bc_dialup_info.clone())
+        .await
+        .expect("failed to set blockchain service");
+
+    let mut client_e = SimpleClientUserServiceClient::connect("http://[::1]:3037")
+        .await
+        .expect("failed to connect to client e");
+
+    let mut client_f = SimpleClientUserServiceClient::connect("http://[::1]:3038")
+        .await
+        .expect("failed to connect to client f");
+
+    let providers = [(8097, "ServiceProviderP"), (8098, "ServiceProviderQ")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_e, &mut client_f].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(DialupInfo {
+                    end_point: ApiEndPoint::GrpcWeb2 as i32,
+                    api_version: "0.1.0".into(),
+                    ip_address: "[::1]".into(),
+                    port,
+                    net_id: 0,
+                    name: name.into(),
+                    min_api_version: "".to_string(),
+                }),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_f_entity = bundles[1].get_client_entity().unwrap();
+
+    client_e
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_f
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let client_e_entity = bundles[0].get_client_entity().unwrap();
+
+    info!("padded message e >> f...");
+
+    client_e
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_f_entity.clone()),
+            user_text: "Hi F, this is E".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to f");
+
+    wait_for_delivery(&mut spq_admin_client, &client_f_entity).await;
+
+    info!("padded message f >> e...");
+
+    client_f
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_e_entity.clone()),
+            user_text: "Hi E, this is F. ".repeat(100),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to e");
+
+    wait_for_delivery(&mut spp_admin_client, &client_e_entity).await;
+
+    // cover traffic is discarded by providers and is not delivered to clients
+    sleep(Duration::from_millis(1000)).await;
+    wait_for_delivery(&mut spp_admin_client, &client_e_entity).await;
+    wait_for_delivery(&mut spq_admin_client, &client_f_entity).await;
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -0,0 +1,173 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
+use base::snp::snp_core_types::ChannelType;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use test_helpers::{
+    blockchain_dialup_info, core_balance, next_event, provider_dialup_info,
+    set_providers_blockchain_service, start_network,
+};
+
+/*
+In this test client A creates a status updates channel with a monthly subscription fee and client B subscribes to it.
+B pays the first month's fee with a payment transaction on the blockchain and A only accepts B as a subscriber
+after verifying the payment. B then receives A's posts to the channel.
+*/
+
+#[tokio::test]
+async fn paid_channels() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &["tests/client_a_conf.json", "tests/client_b_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+
+    client_a
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut a_events = client_a
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut bc_client = BlockchainServiceClient::connect("http://[::1]:5556")
+        .await
+        .expect("failed to connect to blockchain service");
+
+    let seller_address = bundles[0]
+        .client_bundle
+        .as_ref()
+        .unwrap()
+        .address
+        .clone()
+        .unwrap();
+
+    info!("creating a paid status updates channel for a...");
+    let channel_bundle = client_a
+        .user_create_status_update_channel(UserCreateStatusUpdateChannelRequest {
+            channel_name: "A Paid Status Updates".into(),
+            subscription_fee: 20,
+        })
+        .await
+        .expect("failed to create channel")
+        .into_inner()
+        .channel_bundle
+        .unwrap();
+    let channel_id = channel_bundle.channel_id.as_ref().unwrap().clone();
+
+    assert_eq!(channel_bundle.get_monthly_fee().unwrap().value, 20);
+    assert_eq!(
+        channel_bundle.payable_address.as_ref(),
+        Some(&seller_address)
+    );
+
+    let seller_balance = core_balance(&mut bc_client, &seller_address).await;
+
+    info!("b subscribes to a's paid channel...");
+    client_b
+        .user_subscribe_to_status_updates(UserSubscribeRequest {
+            channel_bundle: Some(channel_bundle.clone()),
+        })
+        .await
+        .expect("failed to subscribe");
+
+    match next_event(&mut a_events).await {
+        Event::SubscriptionRequest(e) => {
+            assert_eq!(e.subscriber_id.unwrap(), client_b_entity);
+            assert_eq!(e.channel_id, channel_bundle.get_channel_id().unwrap());
+        }
+        e => panic!("unexpected event: {:?}", e),
+    }
+
+    assert_eq!(
+        core_balance(&mut bc_client, &seller_address).await,
+        seller_balance + 20
+    );
+
+    info!("a posts to its paid channel...");
+    client_a
+        .user_new_post(UserNewPostRequest {
+            channel_id: Some(channel_id),
+            reply_to: 0,
+            text: "for paying subscribers only".into(),
+        })
+        .await
+        .expect("failed to post");
+
+    match next_event(&mut b_events).await {
+        Event::ChannelPostReceived(e) => {
+            assert_eq!(e.channel_type, ChannelType::StatusFeed as i32);
+            let item = e.message.unwrap().content_item.unwrap();
+            assert_eq!(
+                item.get_simple_text_content().unwrap(),
+                "for paying subscribers only"
+            );
+        }
+        e => panic!("unexpected event: {:?}", e),
+    }
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -0,0 +1,190 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use test_helpers::{
+    blockchain_dialup_info, core_balance, next_event, provider_dialup_info,
+    set_providers_blockchain_service, start_network,
+};
+use tonic::Streaming;
+
+/*
+In this test client A sells a paid content item to client B. B pays for it with a payment transaction on the
+blockchain and A only sends B the item after verifying the payment. A payment that is too small or for an item
+A doesn't sell doesn't buy an item.
+*/
+
+/// Returns the purchased item event of the next event of an events stream
+async fn next_purchase(events: &mut Streaming<ClientEvent>) -> ItemPurchasedEvent {
+    match next_event(events).await {
+        Event::ItemPurchased(e) => e,
+        e => panic!("unexpected event: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn paid_content() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &["tests/client_a_conf.json", "tests/client_b_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_a_entity = bundles[0].get_client_entity().unwrap();
+    let client_b_entity = bundles[1].get_client_entity().unwrap();
+
+    client_a
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut a_events = client_a
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut b_events = client_b
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    let mut bc_client = BlockchainServiceClient::connect("http://[::1]:5556")
+        .await
+        .expect("failed to connect to blockchain service");
+
+    let seller_address = bundles[0]
+        .client_bundle
+        .as_ref()
+        .unwrap()
+        .address
+        .clone()
+        .unwrap();
+
+    let item_id = client_a
+        .user_create_paid_item(UserCreatePaidItemRequest {
+            price: 10,
+            name: "song".into(),
+            content: "la la la".into(),
+        })
+        .await
+        .expect("failed to create paid item")
+        .into_inner()
+        .item_id;
+
+    let seller_balance = core_balance(&mut bc_client, &seller_address).await;
+
+    info!("b pays too little for a's item...");
+    client_b
+        .user_buy_paid_item(UserBuyPaidItemRequest {
+            seller_client_id: Some(client_a_entity.clone()),
+            item_id,
+            price: 5,
+        })
+        .await
+        .expect("failed to buy item");
+
+    info!("b pays for a's item...");
+    client_b
+        .user_buy_paid_item(UserBuyPaidItemRequest {
+            seller_client_id: Some(client_a_entity.clone()),
+            item_id,
+            price: 10,
+        })
+        .await
+        .expect("failed to buy item");
+
+    // only the second payment buys the item
+    let purchase = next_purchase(&mut b_events).await;
+    assert_eq!(purchase.item_id, item_id);
+    assert_eq!(purchase.seller_id.unwrap(), client_a_entity);
+    let item = purchase.item.unwrap();
+    assert_eq!(item.get_simple_text_content().unwrap(), "la la la");
+
+    let purchase = next_purchase(&mut a_events).await;
+    assert_eq!(purchase.item_id, item_id);
+    assert_eq!(purchase.buyer_id.unwrap(), client_b_entity);
+
+    assert_eq!(
+        core_balance(&mut bc_client, &seller_address).await,
+        seller_balance + 15
+    );
+
+    info!("b buys an item a doesn't sell...");
+    client_b
+        .user_buy_paid_item(UserBuyPaidItemRequest {
+            seller_client_id: Some(client_a_entity.clone()),
+            item_id: item_id + 1,
+            price: 10,
+        })
+        .await
+        .expect("failed to buy item");
+
+    info!("b buys a's item again...");
+    client_b
+        .user_buy_paid_item(UserBuyPaidItemRequest {
+            seller_client_id: Some(client_a_entity.clone()),
+            item_id,
+            price: 10,
+        })
+        .await
+        .expect("failed to buy item");
+
+    assert_eq!(next_purchase(&mut b_events).await.item_id, item_id);
+    assert_eq!(next_purchase(&mut a_events).await.item_id, item_id);
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
@@ -0,0 +1,103 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::protocol_version::{IncompatibleVersionError, VersionRange};
+use base::request_rejection::RequestRejectedError;
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::{GetIdentityBundleRequest, NewSessionRequest, RejectionReason};
+use base::test_helpers::enable_logger;
+use chrono::prelude::*;
+use server::server_service::SNP_PROTOCOL_VERSION;
+use test_helpers::{start_provider, wait_for_services};
+use tonic::Code;
+
+/*
+In this test provider N advertises the range of protocol versions it supports in its dialup info.
+Callers implementing older versions are rejected with a typed rejection which includes the supported versions.
+Callers implementing newer versions are served using the newest version both sides support.
+*/
+
+#[tokio::test]
+async fn protocol_versions() {
+    enable_logger();
+
+    let provider_guard = start_provider("tests/spn_conf.json");
+
+    wait_for_services().await;
+
+    let mut client = ProviderCoreServiceClient::connect("http://[::1]:8095")
+        .await
+        .expect("failed to connect to spn");
+
+    info!("getting provider bundle with a newer protocol version...");
+
+    let bundle = client
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: "9.0.0".into(),
+        })
+        .await
+        .expect("expected newer callers to be served")
+        .into_inner()
+        .bundle
+        .unwrap();
+
+    for info in bundle.dial_up_info.iter() {
+        let range = VersionRange::from_dialup_info(info).unwrap();
+        assert_eq!(range, VersionRange::snp_protocol());
+        assert_eq!(
+            VersionRange::snp_protocol().negotiate(&range).unwrap(),
+            range.max
+        );
+    }
+
+    info!("getting provider bundle with an old protocol version...");
+
+    let status = client
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: "0.0.1".into(),
+        })
+        .await
+        .expect_err("expected old protocol version to be rejected");
+    assert_eq!(status.code(), Code::FailedPrecondition);
+
+    let err = RequestRejectedError::from_status(&status).expect("expected a rejection");
+    assert_eq!(
+        err.rejection.reason,
+        RejectionReason::IncompatibleProtocolVersion as i32
+    );
+    let err = IncompatibleVersionError::from_rejection(&err.rejection, "0.0.1").unwrap();
+    assert_eq!(err.supported, VersionRange::snp_protocol());
+
+    info!("sending a new session request with an unparsable protocol version...");
+
+    let status = client
+        .new_session(NewSessionRequest {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            protocol_version: "latest".into(),
+            ..Default::default()
+        })
+        .await
+        .expect_err("expected invalid protocol version to be rejected");
+    assert!(RequestRejectedError::from_status(&status).is_some());
+
+    // a compatible new session request passes version negotiation and is rejected for other reasons
+    let status = client
+        .new_session(NewSessionRequest {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+            ..Default::default()
+        })
+        .await
+        .expect_err("expected unsigned new session request to be rejected");
+    assert!(RequestRejectedError::from_status(&status).is_none());
+
+    // we need to keep a ref to the guard so it is not dropped before we get here in case there's no panic
+    debug!("{:?}", provider_guard.0.id());
+}
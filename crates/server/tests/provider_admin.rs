@@ -0,0 +1,221 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::{ApiEndPoint, DialupInfo};
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::GetIdentityBundleRequest;
+use base::snp::upsetter_server_admin::server_admin_service_client::ServerAdminServiceClient;
+use base::snp::upsetter_server_admin::{EvictClientRequest, SuspendClientRequest};
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use server::server_service::SNP_PROTOCOL_VERSION;
+use test_helpers::{blockchain_dialup_info, start_network};
+use tonic::transport::Channel;
+use tonic::{Code, Request};
+
+/*
+In this test a provider admin uses the provider's admin service to manage a client served by the provider.
+The admin service is only available on the admin port and requires the provider's admin key.
+*/
+
+#[tokio::test]
+#[allow(clippy::result_large_err)]
+async fn provider_admin() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service1.json",
+        &["tests/spf_conf.json"],
+        &["tests/client_a_conf.json"],
+    )
+    .await;
+
+    // admin service is not available on the provider's public port
+    let mut public_port_admin_client = ServerAdminServiceClient::connect("http://[::1]:8087")
+        .await
+        .expect("failed to connect to spf");
+    assert!(public_port_admin_client.get_clients(()).await.is_err());
+
+    // admin requests without the admin key are rejected
+    let channel = Channel::from_static("http://[::1]:9087")
+        .connect()
+        .await
+        .expect("failed to connect to spf admin service");
+
+    let mut no_key_admin_client = ServerAdminServiceClient::new(channel.clone());
+    let err = no_key_admin_client.get_clients(()).await.unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let mut admin_client =
+        ServerAdminServiceClient::with_interceptor(channel, |mut req: Request<()>| {
+            req.metadata_mut()
+                .insert("x-admin-key", "spf-admin-key".parse().unwrap());
+            Ok(req)
+        });
+
+    let bc_dialup_info = blockchain_dialup_info(5555);
+
+    admin_client
+        .set_blockchain_service(bc_dialup_info.clone())
+        .await
+        .expect("failed to set blockchain service");
+
+    // config dump masks the admin key
+    let config = admin_client
+        .get_config(())
+        .await
+        .expect("failed to get config")
+        .into_inner()
+        .values;
+    assert_eq!(config.get("grpc_server_port").unwrap(), "8087");
+    assert_eq!(config.get("admin_key").unwrap(), "****");
+
+    // serve a client
+    let mut client = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client");
+
+    client
+        .set_blockchain_service(SetBlockchainServiceRequest {
+            dialup_info: Some(bc_dialup_info),
+        })
+        .await
+        .unwrap();
+
+    let client_bundle = client
+        .user_set_provider(UserSetProviderRequest {
+            dialup_info: Some(DialupInfo {
+                end_point: ApiEndPoint::GrpcWeb2 as i32,
+                api_version: "0.1.0".into(),
+                ip_address: "[::1]".into(),
+                port: 8087,
+                net_id: 0,
+                name: "ServiceProviderF".into(),
+                min_api_version: "".to_string(),
+            }),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .client_bundle
+        .unwrap();
+
+    let client_entity = client_bundle.get_client_entity().unwrap();
+
+    let clients = admin_client
+        .get_clients(())
+        .await
+        .expect("failed to get clients")
+        .into_inner()
+        .clients;
+
+    assert_eq!(clients.len(), 1);
+    let service_data = clients[0].service_data.as_ref().unwrap();
+    assert_eq!(
+        service_data
+            .client_identity_bundle
+            .as_ref()
+            .unwrap()
+            .client_id
+            .as_ref()
+            .unwrap(),
+        &client_entity
+    );
+    assert!(!service_data.service_suspended);
+    assert!(!clients[0].balances.is_empty());
+
+    // no other providers were messaged
+    let sessions = admin_client
+        .get_peers_sessions(())
+        .await
+        .expect("failed to get peers sessions")
+        .into_inner()
+        .sessions;
+    assert!(sessions.is_empty());
+
+    // suspend and resume the client
+    for suspend in [true, false] {
+        admin_client
+            .suspend_client(SuspendClientRequest {
+                client_id: Some(client_entity.clone()),
+                suspend,
+            })
+            .await
+            .expect("failed to suspend client");
+
+        let clients = admin_client
+            .get_clients(())
+            .await
+            .unwrap()
+            .into_inner()
+            .clients;
+        assert_eq!(
+            clients[0].service_data.as_ref().unwrap().service_suspended,
+            suspend
+        );
+    }
+
+    // rotate provider pre-key
+    let new_bundle = admin_client
+        .rotate_pre_key(())
+        .await
+        .expect("failed to rotate pre-key")
+        .into_inner()
+        .provider_bundle
+        .unwrap();
+
+    assert_ne!(
+        new_bundle.pre_key,
+        client_bundle
+            .client_bundle
+            .as_ref()
+            .unwrap()
+            .provider_bundle
+            .as_ref()
+            .unwrap()
+            .pre_key
+    );
+
+    let mut spf_client = ProviderCoreServiceClient::connect("http://[::1]:8087")
+        .await
+        .expect("failed to connect to spf");
+
+    let current_bundle = spf_client
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .bundle
+        .unwrap();
+
+    assert_eq!(current_bundle, new_bundle);
+
+    // evict the client
+    admin_client
+        .evict_client(EvictClientRequest {
+            client_id: Some(client_entity),
+        })
+        .await
+        .expect("failed to evict client");
+
+    let clients = admin_client
+        .get_clients(())
+        .await
+        .unwrap()
+        .into_inner()
+        .clients;
+    assert!(clients.is_empty());
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
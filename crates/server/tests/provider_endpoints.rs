@@ -0,0 +1,91 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::protocol_version::MIN_SNP_PROTOCOL_VERSION;
+use base::snp::snp_core_types::{ApiEndPoint, DialupInfo, ProviderIdentityBundle};
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::GetIdentityBundleRequest;
+use base::test_helpers::enable_logger;
+use server::server_service::SNP_PROTOCOL_VERSION;
+use test_helpers::{start_provider, wait_for_services};
+
+/*
+In this test providers run behind a NAT or a load balancer and advertise configured public endpoints.
+Provider I configures its public host, port and api version. Provider J configures a list of endpoints.
+Providers advertise the oldest api version they support unless an endpoint configures its own.
+*/
+
+async fn get_bundle(port: u32) -> ProviderIdentityBundle {
+    ProviderCoreServiceClient::connect(format!("http://[::1]:{}", port))
+        .await
+        .expect("failed to connect to provider")
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .bundle
+        .unwrap()
+}
+
+#[tokio::test]
+async fn provider_endpoints() {
+    enable_logger();
+
+    let provider_guards = [
+        start_provider("tests/spi_conf.json"),
+        start_provider("tests/spj_conf.json"),
+    ];
+
+    wait_for_services().await;
+
+    let spi_bundle = get_bundle(8090).await;
+    assert_eq!(
+        spi_bundle.dial_up_info,
+        vec![DialupInfo {
+            end_point: ApiEndPoint::GrpcWeb2 as i32,
+            api_version: "0.2.0".into(),
+            ip_address: "provider-i.example.com".into(),
+            port: 443,
+            net_id: 1,
+            name: "ServiceProviderI".into(),
+            min_api_version: MIN_SNP_PROTOCOL_VERSION.into(),
+        }]
+    );
+
+    let spj_bundle = get_bundle(8091).await;
+    assert_eq!(
+        spj_bundle.dial_up_info,
+        vec![
+            DialupInfo {
+                end_point: ApiEndPoint::GrpcWeb2s as i32,
+                api_version: SNP_PROTOCOL_VERSION.into(),
+                ip_address: "provider-j.example.com".into(),
+                port: 443,
+                net_id: 0,
+                name: "ServiceProviderJ".into(),
+                min_api_version: MIN_SNP_PROTOCOL_VERSION.into(),
+            },
+            DialupInfo {
+                end_point: ApiEndPoint::JsonHttps as i32,
+                api_version: "0.1.1".into(),
+                ip_address: "api.provider-j.example.com".into(),
+                port: 443,
+                net_id: 0,
+                name: "ServiceProviderJ".into(),
+                min_api_version: "0.1.1".into(),
+            }
+        ]
+    );
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", provider_guards.len());
+}
@@ -0,0 +1,116 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::request_rejection::RequestRejectedError;
+use base::snp::snp_core_types::PublicKey;
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::{
+    GetTermsOfServiceRequest, MessageRequest, NewSessionRequest, RejectionReason,
+};
+use base::test_helpers::enable_logger;
+use chrono::prelude::*;
+use server::server_service::SNP_PROTOCOL_VERSION;
+use test_helpers::{start_provider, wait_for_services};
+use tonic::Code;
+
+/*
+In this test provider K requires a proof of work for new sessions and limits the rate of requests per ip address.
+Its terms of service advertise its limits and rejected requests carry typed rejections callers can act on.
+A proof of work may only be used once.
+*/
+
+#[tokio::test]
+async fn rate_limits() {
+    enable_logger();
+
+    let provider_guard = start_provider("tests/spk_conf.json");
+
+    wait_for_services().await;
+
+    let mut client = ProviderCoreServiceClient::connect("http://[::1]:8092")
+        .await
+        .expect("failed to connect to spk");
+
+    let terms = client
+        .get_terms_of_service(GetTermsOfServiceRequest {
+            promo_code: "".into(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .terms
+        .unwrap()
+        .service_terms
+        .unwrap();
+    assert_eq!(terms.max_messages_per_minute, 30);
+    assert_eq!(terms.new_session_pow_difficulty, 8);
+
+    info!("sending a new session request without a proof of work...");
+
+    let status = client
+        .new_session(NewSessionRequest {
+            time_stamp: Utc::now().timestamp_nanos() as u64,
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+            ..Default::default()
+        })
+        .await
+        .expect_err("expected proof of work to be required");
+    let err = RequestRejectedError::from_status(&status).expect("expected a rejection");
+    assert_eq!(
+        err.rejection.reason,
+        RejectionReason::ProofOfWorkRequired as i32
+    );
+    assert_eq!(err.rejection.pow_difficulty, 8);
+
+    info!("replaying a new session request proof of work...");
+
+    let mut request = NewSessionRequest {
+        time_stamp: Utc::now().timestamp_nanos() as u64,
+        protocol_version: SNP_PROTOCOL_VERSION.into(),
+        sender_ephemeral_key: Some(PublicKey { key: vec![7; 32] }),
+        ..Default::default()
+    };
+    request.solve_pow(8).unwrap();
+
+    // the proof of work is accepted but the request is not a valid new session request
+    let status = client
+        .new_session(request.clone())
+        .await
+        .expect_err("expected invalid request");
+    assert_ne!(status.message(), "proof of work was already used");
+
+    let status = client
+        .new_session(request)
+        .await
+        .expect_err("expected proof of work replay to be rejected");
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(status.message(), "proof of work was already used");
+
+    info!("sending requests until the ip address is rate limited...");
+
+    for _ in 0..3 {
+        let status = client
+            .message(MessageRequest { message: None })
+            .await
+            .expect_err("expected invalid request");
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    let status = client
+        .message(MessageRequest { message: None })
+        .await
+        .expect_err("expected request to be rate limited");
+    let err = RequestRejectedError::from_status(&status).expect("expected a rejection");
+    assert_eq!(err.rejection.reason, RejectionReason::IpRateLimited as i32);
+    assert!(err.rejection.retry_after_ms > 0);
+
+    // we need to keep a ref to the guard so it is not dropped before we get here in case there's no panic
+    debug!("{:?}", provider_guard.0.id());
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::{ApiEndPoint, DialupInfo};
+use base::snp::upsetter_server_admin::server_admin_service_client::ServerAdminServiceClient;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use test_helpers::{blockchain_dialup_info, start_network, wait_for_delivery};
+use tonic::transport::Channel;
+use tonic::Request;
+
+/*
+In this test client C, which is served by provider B, and client D, which is served by provider O, exchange messages.
+C's first message to D is a first contact so it is not sealed. Clients give each other their delivery tokens in their
+messages, so D's reply to C and C's next message to D are sealed-sender messages which providers deliver when
+authorized by the receiver's delivery token.
+*/
+
+#[tokio::test]
+#[allow(clippy::result_large_err)]
+async fn sealed_sender() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service1.json",
+        &["tests/spo_conf.json", "tests/spb_conf.json"],
+        &["tests/client_c_conf.json", "tests/client_d_conf.json"],
+    )
+    .await;
+
+    let channel = Channel::from_static("http://[::1]:9096")
+        .connect()
+        .await
+        .expect("failed to connect to spo admin service");
+    let mut spo_admin_client =
+        ServerAdminServiceClient::with_interceptor(channel, |mut req: Request<()>| {
+            req.metadata_mut()
+                .insert("x-admin-key", "spo-admin-key".parse().unwrap());
+            Ok(req)
+        });
+
+    let bc_dialup_info = blockchain_dialup_info(5555);
+
+    spo_admin_client
+        .set_blockchain_service(bc_dialup_info.clone())
+        .await
+        .expect("failed to set blockchain service");
+
+    let mut spb_admin_client = ServerAdminServiceClient::connect("http://[::1]:9083")
+        .await
+        .expect("failed to connect to spb admin service");
+
+    spb_admin_client
+        .set_blockchain_service(bc_dialup_info.clone())
+        .await
+        .expect("failed to set blockchain service");
+
+    let mut client_c = SimpleClientUserServiceClient::connect("http://[::1]:3035")
+        .await
+        .expect("failed to connect to client c");
+
+    let mut client_d = SimpleClientUserServiceClient::connect("http://[::1]:3036")
+        .await
+        .expect("failed to connect to client d");
+
+    let providers = [(8083, "ServiceProviderB"), (8096, "ServiceProviderO")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_c, &mut client_d].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(DialupInfo {
+                    end_point: ApiEndPoint::GrpcWeb2 as i32,
+                    api_version: "0.1.0".into(),
+                    ip_address: "[::1]".into(),
+                    port,
+                    net_id: 0,
+                    name: name.into(),
+                    min_api_version: "".to_string(),
+                }),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_d_entity = bundles[1].get_client_entity().unwrap();
+
+    client_c
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_d
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    info!("first contact c >> d...");
+
+    let client_c_entity = bundles[0].get_client_entity().unwrap();
+
+    client_c
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_d_entity.clone()),
+            user_text: "Hi D, this is C".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to d");
+
+    wait_for_delivery(&mut spo_admin_client, &client_d_entity).await;
+
+    info!("sealed-sender d >> c...");
+
+    // d got c's delivery token in c's message
+    client_d
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_c_entity.clone()),
+            user_text: "Hi C, this is D".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send sealed-sender message to c");
+
+    wait_for_delivery(&mut spb_admin_client, &client_c_entity).await;
+
+    info!("sealed-sender c >> d...");
+
+    client_c
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_d_entity.clone()),
+            user_text: "Hi again D".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send sealed-sender message to d");
+
+    wait_for_delivery(&mut spo_admin_client, &client_d_entity).await;
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
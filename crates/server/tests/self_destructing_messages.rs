@@ -0,0 +1,155 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, next_event, provider_dialup_info, set_providers_blockchain_service,
+    start_network,
+};
+use tokio::time::sleep;
+
+/*
+In this test client B sends client A a self-destructing message with a ttl. A receives it and both clients drop
+their copy of it once the ttl passes.
+*/
+
+#[tokio::test]
+async fn self_destructing_messages() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &["tests/spc_conf.json", "tests/spd_conf.json"],
+        &["tests/client_a_conf.json", "tests/client_b_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+    set_providers_blockchain_service(&[9084, 9085], &bc_dialup_info).await;
+
+    let mut client_a = SimpleClientUserServiceClient::connect("http://[::1]:3033")
+        .await
+        .expect("failed to connect to client a");
+
+    let mut client_b = SimpleClientUserServiceClient::connect("http://[::1]:3034")
+        .await
+        .expect("failed to connect to client b");
+
+    let providers = [(8084, "ServiceProviderC"), (8085, "ServiceProviderD")];
+    let mut bundles = vec![];
+    for (client, (port, name)) in [&mut client_a, &mut client_b].iter_mut().zip(providers) {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+
+        let bundle = client
+            .user_set_provider(UserSetProviderRequest {
+                dialup_info: Some(provider_dialup_info(port, name)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .client_bundle
+            .unwrap();
+        bundles.push(bundle);
+    }
+
+    let client_a_entity = bundles[0].get_client_entity().unwrap();
+
+    client_a
+        .user_add_other_client_bundle(bundles[1].clone())
+        .await
+        .unwrap();
+    client_b
+        .user_add_other_client_bundle(bundles[0].clone())
+        .await
+        .unwrap();
+
+    let mut a_events = client_a
+        .subscribe_events(SubscribeEventsRequest {})
+        .await
+        .expect("failed to subscribe to events")
+        .into_inner();
+
+    info!("b sends a with a self-destructing message...");
+    let message_id = client_b
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_a_entity.clone()),
+            user_text: "This message will self-destruct in 2 seconds".into(),
+            reply_to: 0,
+            ttl: 2,
+        })
+        .await
+        .expect("failed to send message to a")
+        .into_inner()
+        .message_id;
+
+    let message = match next_event(&mut a_events).await {
+        Event::TextMessageReceived(e) => e.message.unwrap(),
+        e => panic!("unexpected event: {:?}", e),
+    };
+    let content_item = message.content_item.unwrap();
+    assert_eq!(content_item.id, message_id);
+    assert_eq!(content_item.ttl, 2);
+
+    let conversation_id = message.conversation_id;
+    let get_messages = UserGetConversationMessagesRequest {
+        conversation_id: conversation_id.clone(),
+        from_index: 0,
+        max_results: 0,
+    };
+    let get_status = UserGetMessagesStatusRequest {
+        message_ids: vec![message_id],
+    };
+
+    let response = client_a
+        .user_get_conversation_messages(get_messages.clone())
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.messages.len(), 1);
+
+    let statuses = client_b
+        .user_get_messages_status(get_status.clone())
+        .await
+        .unwrap()
+        .into_inner()
+        .messages;
+    assert_eq!(statuses.len(), 1);
+
+    sleep(Duration::from_millis(2500)).await;
+
+    info!("a's message and b's copy of it expired...");
+    let response = client_a
+        .user_get_conversation_messages(get_messages)
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.messages.is_empty());
+    assert_eq!(response.total_count, 1);
+
+    let statuses = client_b
+        .user_get_messages_status(get_status)
+        .await
+        .unwrap()
+        .into_inner()
+        .messages;
+    assert!(statuses.is_empty());
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
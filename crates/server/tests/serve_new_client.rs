@@ -8,7 +8,8 @@ mod child_guard;
 
 use base::api_types_extensions::{Signed, SignedWithExternalVerifier};
 use base::server_config_service::{
-    ServerConfigService, GRPC_HOST_CONFIG_KEY, GRPC_SERVER_PORT_CONFIG_KEY,
+    ServerConfigService, GRPC_ADMIN_PORT_CONFIG_KEY, GRPC_HOST_CONFIG_KEY,
+    GRPC_SERVER_PORT_CONFIG_KEY,
 };
 use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
 use base::snp::snp_blockchain::SetBalanceRequest;
@@ -26,7 +27,7 @@ use base::snp::upsetter_server_admin::server_admin_service_client::ServerAdminSe
 use base::test_helpers::enable_logger;
 use child_guard::ChildGuard;
 use chrono::prelude::*;
-use common::network_salt::NET_SALT;
+use common::network_salt::net_salt;
 use common::typed_msg_extensions::TypedMessageExtensions;
 use crypto::utils::X25519PublicKeyWrapper;
 use crypto::x2dh;
@@ -75,6 +76,11 @@ async fn serve_new_client() {
         .unwrap()
         .unwrap();
 
+    let grpc_admin_port = ServerConfigService::get_u64(GRPC_ADMIN_PORT_CONFIG_KEY.into())
+        .await
+        .unwrap()
+        .unwrap();
+
     sleep(Duration::from_millis(2000)).await;
 
     debug!("Connecting...");
@@ -82,7 +88,7 @@ async fn serve_new_client() {
     // admin service client to configure blockchain service on the server
 
     let mut server_admin_client =
-        ServerAdminServiceClient::connect(format!("http://{}:{}", grpc_host, grpc_port))
+        ServerAdminServiceClient::connect(format!("http://{}:{}", grpc_host, grpc_admin_port))
             .await
             .expect("failed to connect to provider admin service ");
 
@@ -94,6 +100,7 @@ async fn serve_new_client() {
             port: 5555,
             net_id: 0,
             name: "Blockchain Service".to_string(),
+            min_api_version: "".to_string(),
         })
         .await
         .expect("failed to set blockchain service");
@@ -164,6 +171,7 @@ async fn serve_new_client() {
             x2dh_version: "".to_string(),
             key: Some(pre_key_public),
             key_id: 0,
+            kem_key: vec![],
         }),
         one_time_keys: vec![],
         profile_image: None,
@@ -186,6 +194,7 @@ async fn serve_new_client() {
         payment: None,
         service_contract_id: 0,
         contract_options: 0,
+        delivery_token: vec![],
     };
 
     // start session we provider and set StartServiceRequest as the message
@@ -201,15 +210,16 @@ async fn serve_new_client() {
         ikb,
         pkb,
         b_bundle_id: bob_provider_bundle.time_stamp,
+        kem_pkb: None,
     };
 
     // Alice executes x2dh with bob and get the output
-    let output_alice = x2dh::execute_alice(&input_alice);
+    let output_alice = x2dh::execute_alice(&input_alice).unwrap();
 
     debug!("Alice X2DH output: {:?}", output_alice);
 
     // Alice creates a DR session with bob and using it to get the enc key for her first message with bob
-    let input = SessionKey::from(NET_SALT.as_ref());
+    let input = SessionKey::from(net_salt().as_ref());
     let dr_root_chain_key = ChainKey::from(output_alice.shared_secret.as_ref());
 
     let mut alice_dr = DoubleRatchet::new_with_peer(
@@ -241,6 +251,8 @@ async fn serve_new_client() {
         receiver: Some(ikb_identity.clone()),
         sender: Some(alice_entity.clone()),
         signature: None,
+        sender_delivery_token: vec![],
+        sender_protocol_version: "".into(),
     };
 
     // Sign and add signature to typed_msg as alice
@@ -282,6 +294,8 @@ async fn serve_new_client() {
         receiver_bundle_id: bob_provider_bundle.time_stamp,
         net_id: 0,
         protocol_version: SNP_PROTOCOL_VERSION.into(),
+        pow_nonce: 0,
+        sender_kem_ciphertext: vec![],
     };
 
     new_session_request.sign(&alice_id_key_pair).unwrap();
@@ -0,0 +1,199 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
+use base::snp::snp_blockchain::GetClientIdentityBundleRequest;
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::GetIdentityBundleRequest;
+use base::snp::upsetter_simple_client::simple_client_user_service_client::SimpleClientUserServiceClient;
+use base::snp::upsetter_simple_client::*;
+use base::test_helpers::enable_logger;
+use server::server_service::SNP_PROTOCOL_VERSION;
+use std::time::Duration;
+use test_helpers::{
+    blockchain_dialup_info, provider_dialup_info, set_providers_blockchain_service, start_network,
+};
+use tokio::time::sleep;
+
+/*
+In this test client D moves from provider SD to provider SE while client C, served by SC, is sending it messages.
+
+1. C and D are served by SC and SD. C sends D a message via SC and SD.
+2. D starts service with SE, sends SD a signed stop service request with its new bundle and publishes
+   its SE signed bundle on-chain.
+3. C sends D a message using D's old bundle. SD is within its handover grace period and forwards it to SE.
+4. SD grace period is over. C sends D a message using D's old bundle. SD rejects it,
+   C gets D's current bundle from the blockchain and retries via SE.
+*/
+
+#[tokio::test]
+async fn switch_provider() {
+    enable_logger();
+
+    let guards = start_network(
+        "tests/blockchain_service2.json",
+        &[
+            "tests/spc_conf.json",
+            "tests/spd_conf.json",
+            "tests/spe_conf.json",
+        ],
+        &["tests/client_c_conf.json", "tests/client_d_conf.json"],
+    )
+    .await;
+
+    let bc_dialup_info = blockchain_dialup_info(5556);
+
+    set_providers_blockchain_service(&[9084, 9085, 9086], &bc_dialup_info).await;
+
+    let mut client_c = SimpleClientUserServiceClient::connect("http://[::1]:3035")
+        .await
+        .expect("failed to connect to client c");
+
+    let mut client_d = SimpleClientUserServiceClient::connect("http://[::1]:3036")
+        .await
+        .expect("failed to connect to client d");
+
+    for client in [&mut client_c, &mut client_d].iter_mut() {
+        client
+            .set_blockchain_service(SetBlockchainServiceRequest {
+                dialup_info: Some(bc_dialup_info.clone()),
+            })
+            .await
+            .unwrap();
+    }
+
+    let client_c_bundle = client_c
+        .user_set_provider(UserSetProviderRequest {
+            dialup_info: Some(provider_dialup_info(8084, "ServiceProviderC")),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .client_bundle
+        .unwrap();
+
+    let client_d_bundle = client_d
+        .user_set_provider(UserSetProviderRequest {
+            dialup_info: Some(provider_dialup_info(8085, "ServiceProviderD")),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .client_bundle
+        .unwrap();
+
+    let client_d_entity = client_d_bundle.get_client_entity().unwrap();
+
+    // Let the clients have each other's bundles with their current providers
+    client_c
+        .user_add_other_client_bundle(client_d_bundle)
+        .await
+        .unwrap();
+
+    client_d
+        .user_add_other_client_bundle(client_c_bundle)
+        .await
+        .unwrap();
+
+    info!("testing c to d messaging...");
+
+    client_c
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_d_entity.clone()),
+            user_text: "Hi D, this is C".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("failed to send message to d via its provider");
+
+    info!("switching d's provider...");
+
+    let new_bundle = client_d
+        .user_switch_provider(UserSwitchProviderRequest {
+            dialup_info: Some(provider_dialup_info(8086, "ServiceProviderE")),
+        })
+        .await
+        .expect("failed to switch provider")
+        .into_inner()
+        .client_bundle
+        .unwrap();
+
+    // d's published bundle is its bundle with its new provider
+    let mut spe_client = ProviderCoreServiceClient::connect("http://[::1]:8086")
+        .await
+        .expect("failed to connect to spe");
+
+    let spe_bundle = spe_client
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .bundle
+        .unwrap();
+
+    let mut bc_client = BlockchainServiceClient::connect("http://[::1]:5556")
+        .await
+        .expect("failed to connect to blockchain service");
+
+    let published_bundle = bc_client
+        .get_client_identity_bundle(GetClientIdentityBundleRequest {
+            entity_id: Some(client_d_entity.clone()),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .client_bundle
+        .expect("expected client bundle on-chain");
+
+    assert_eq!(published_bundle, new_bundle);
+    assert_eq!(
+        published_bundle
+            .client_bundle
+            .unwrap()
+            .provider_bundle
+            .unwrap()
+            .provider_id,
+        spe_bundle.provider_id,
+        "expected d's bundle with its new provider on-chain"
+    );
+
+    info!("testing c to d messaging during handover grace period...");
+
+    client_c
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_d_entity.clone()),
+            user_text: "Hi D, this is C again".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("expected old provider to hand over message to d's new provider");
+
+    // wait for spd grace period to be over
+    sleep(Duration::from_millis(4000)).await;
+
+    info!("testing c to d messaging after handover grace period...");
+
+    client_c
+        .user_send_text_message(UserSendTextMessageRequest {
+            other_client_id: Some(client_d_entity),
+            user_text: "Hi D, this is C with your new provider".into(),
+            reply_to: 0,
+            ttl: 0,
+        })
+        .await
+        .expect("expected c to retry sending message via d's new provider");
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", guards.len());
+}
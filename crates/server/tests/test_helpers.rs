@@ -0,0 +1,190 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+// helpers shared by integration tests. Each test uses some of them.
+#![allow(dead_code)]
+
+#[path = "child_guard.rs"]
+mod child_guard;
+
+pub use child_guard::ChildGuard;
+
+use base::snp::snp_blockchain::blockchain_service_client::BlockchainServiceClient;
+use base::snp::snp_blockchain::GetAccountRequest;
+use base::snp::snp_core_types::{ApiEndPoint, DialupInfo, EntityId};
+use base::snp::snp_payments::{Address, CoinType};
+use base::snp::upsetter_server_admin::server_admin_service_client::ServerAdminServiceClient;
+use base::snp::upsetter_server_admin::ClientInfo;
+use base::snp::upsetter_simple_client::client_event::Event;
+use base::snp::upsetter_simple_client::ClientEvent;
+use std::env;
+use std::process::Command;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+use tonic::codegen::{Body, StdError};
+use tonic::Streaming;
+
+/// Start an app built in the target dir with a config file path relative to the crate dir.
+/// The app is killed when its guard is dropped
+fn start_app(app: &str, conf: &str, envs: &[(&str, &str)]) -> ChildGuard {
+    let path = env::current_dir().unwrap().join(conf);
+    let child = Command::new(format!("../../target/debug/{}", app))
+        .args(["-c", path.to_str().unwrap()])
+        .envs(envs.iter().copied())
+        .spawn()
+        .unwrap();
+    ChildGuard(child)
+}
+
+/// Start a blockchain service with a config file, e.g. tests/blockchain_service1.json
+pub fn start_blockchain(conf: &str) -> ChildGuard {
+    start_app("blockchain-app", conf, &[])
+}
+
+/// Start a provider with a config file, e.g. tests/spa_conf.json
+pub fn start_provider(conf: &str) -> ChildGuard {
+    start_app("server-app", conf, &[])
+}
+
+/// Start a provider with a config file and config values overridden by env vars, e.g. UPSETTER_MAX_FILE_SIZE
+pub fn start_provider_with_env(conf: &str, envs: &[(&str, &str)]) -> ChildGuard {
+    start_app("server-app", conf, envs)
+}
+
+/// Start a client with a config file, e.g. tests/client_a_conf.json
+pub fn start_client(conf: &str) -> ChildGuard {
+    start_app("client-app", conf, &[])
+}
+
+/// Wait for the grpc services of started apps to start
+pub async fn wait_for_services() {
+    sleep(Duration::from_millis(3000)).await;
+}
+
+/// Start a blockchain service, providers and clients with config files and wait for their grpc services to start
+pub async fn start_network(
+    blockchain_conf: &str,
+    provider_confs: &[&str],
+    client_confs: &[&str],
+) -> Vec<ChildGuard> {
+    let mut guards = vec![start_blockchain(blockchain_conf)];
+    guards.extend(provider_confs.iter().map(|conf| start_provider(conf)));
+    guards.extend(client_confs.iter().map(|conf| start_client(conf)));
+    wait_for_services().await;
+    guards
+}
+
+/// Dialup info of a local test blockchain service's grpc endpoint
+pub fn blockchain_dialup_info(port: u32) -> DialupInfo {
+    DialupInfo {
+        end_point: ApiEndPoint::GrpcWeb2 as i32,
+        api_version: "0.1.0".to_string(),
+        ip_address: "[::1]".to_string(),
+        port,
+        net_id: 0,
+        name: "Blockchain Service".to_string(),
+        min_api_version: "".to_string(),
+    }
+}
+
+/// Set the blockchain service of local test providers by their admin service ports
+pub async fn set_providers_blockchain_service(admin_ports: &[u32], bc_dialup_info: &DialupInfo) {
+    for admin_port in admin_ports {
+        ServerAdminServiceClient::connect(format!("http://[::1]:{}", admin_port))
+            .await
+            .expect("failed to connect to provider admin service")
+            .set_blockchain_service(bc_dialup_info.clone())
+            .await
+            .expect("failed to set blockchain service");
+    }
+}
+
+/// Dialup info of a local test provider's grpc endpoint
+pub fn provider_dialup_info(port: u32, name: &str) -> DialupInfo {
+    DialupInfo {
+        end_point: ApiEndPoint::GrpcWeb2 as i32,
+        api_version: "0.1.0".into(),
+        ip_address: "[::1]".into(),
+        port,
+        net_id: 0,
+        name: name.into(),
+        min_api_version: "".to_string(),
+    }
+}
+
+/// Returns the next event from a client's events stream. Panics if no event arrives within 20 seconds
+pub async fn next_event(events: &mut Streaming<ClientEvent>) -> Event {
+    timeout(Duration::from_secs(20), events.message())
+        .await
+        .expect("timed out waiting for an event")
+        .expect("events stream failed")
+        .expect("events stream ended")
+        .event
+        .expect("missing event")
+}
+
+/// Returns the number of messages a provider stores for one of the clients it serves
+pub fn pending_messages(clients: &[ClientInfo], client: &EntityId) -> u32 {
+    clients
+        .iter()
+        .find(|c| {
+            c.service_data
+                .as_ref()
+                .unwrap()
+                .client_identity_bundle
+                .as_ref()
+                .unwrap()
+                .client_id
+                .as_ref()
+                == Some(client)
+        })
+        .expect("expected client to be served")
+        .pending_messages
+}
+
+/// Wait for a provider to deliver all messages pending delivery to a client
+pub async fn wait_for_delivery<T>(admin_client: &mut ServerAdminServiceClient<T>, client: &EntityId)
+where
+    T: tonic::client::GrpcService<tonic::body::BoxBody>,
+    T::ResponseBody: Body + Send + Sync + 'static,
+    T::Error: Into<StdError>,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    for _ in 0..20 {
+        let clients = admin_client
+            .get_clients(())
+            .await
+            .expect("failed to get clients")
+            .into_inner()
+            .clients;
+        if pending_messages(&clients, client) == 0 {
+            // give client time to process the delivered messages
+            sleep(Duration::from_millis(500)).await;
+            return;
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+    panic!("expected messages to be delivered to client");
+}
+
+/// Returns the core coins balance of a blockchain account
+pub async fn core_balance(
+    bc_client: &mut BlockchainServiceClient<tonic::transport::Channel>,
+    address: &Address,
+) -> u64 {
+    bc_client
+        .get_account(GetAccountRequest {
+            address: Some(address.clone()),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .account
+        .unwrap()
+        .balances
+        .iter()
+        .find(|b| b.coin_type == CoinType::Core as i32)
+        .map(|b| b.value)
+        .unwrap_or_default()
+}
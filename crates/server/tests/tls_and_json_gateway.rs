@@ -0,0 +1,213 @@
+// Copyright (c) 2021, Subnet Authors. cmdev2@proton.me.
+// This work is licensed under the Subnet v0.1.0 license published in the LICENSE file of this repo.
+//
+
+#[macro_use]
+extern crate log;
+extern crate nix;
+
+mod test_helpers;
+
+use base::snp::snp_core_types::{ApiEndPoint, DialupInfo};
+use base::snp::snp_server_api::provider_core_service_client::ProviderCoreServiceClient;
+use base::snp::snp_server_api::{GetIdentityBundleRequest, GetIdentityBundleResponse};
+use base::test_helpers::enable_logger;
+use hyper::{Body, Client, Method, Request, StatusCode};
+use server::server_service::SNP_PROTOCOL_VERSION;
+use std::sync::Arc;
+use test_helpers::{start_provider, wait_for_services};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::ClientConfig;
+use tokio_rustls::webpki::DNSNameRef;
+use tokio_rustls::TlsConnector;
+
+/*
+In this test provider G serves its grpc api and its json gateway over tls using a self-signed certificate,
+and provider H serves them without tls. Both advertise their endpoints in their identity bundles.
+*/
+
+const CA_CERT_FILE: &str = "tests/certs/ca.pem";
+
+#[tokio::test]
+async fn tls_and_json_gateway() {
+    enable_logger();
+
+    let provider_guards = [
+        start_provider("tests/spg_conf.json"),
+        start_provider("tests/sph_conf.json"),
+    ];
+
+    wait_for_services().await;
+
+    info!("testing provider grpc api over tls...");
+
+    let spg_grpc = DialupInfo {
+        end_point: ApiEndPoint::GrpcWeb2s as i32,
+        api_version: SNP_PROTOCOL_VERSION.into(),
+        ip_address: "localhost".into(),
+        port: 8088,
+        net_id: 0,
+        name: "ServiceProviderG".into(),
+        min_api_version: "".to_string(),
+    };
+
+    // an insecure client can't talk with a tls provider
+    let mut insecure_client = ProviderCoreServiceClient::connect("http://127.0.0.1:8088")
+        .await
+        .expect("failed to connect to spg");
+    assert!(insecure_client
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+        })
+        .await
+        .is_err());
+
+    let channel = spg_grpc
+        .connect(Some(CA_CERT_FILE.into()))
+        .await
+        .expect("failed to connect to spg over tls");
+
+    let spg_bundle = ProviderCoreServiceClient::new(channel)
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+        })
+        .await
+        .expect("failed to get spg bundle over tls")
+        .into_inner()
+        .bundle
+        .unwrap();
+
+    let grpc_endpoint = DialupInfo::best_grpc_endpoint(&spg_bundle.dial_up_info)
+        .expect("expected grpc endpoint in bundle");
+    assert_eq!(grpc_endpoint.end_point, ApiEndPoint::GrpcWeb2s as i32);
+    assert_eq!(grpc_endpoint.ip_address, "localhost");
+    assert_eq!(grpc_endpoint.port, 8088);
+
+    let json_endpoint = spg_bundle
+        .dial_up_info
+        .iter()
+        .find(|e| e.is_json_http())
+        .expect("expected json endpoint in bundle");
+    assert_eq!(json_endpoint.end_point, ApiEndPoint::JsonHttps as i32);
+    assert_eq!(json_endpoint.port, 8188);
+
+    info!("testing json gateway over tls...");
+
+    let mut roots = ClientConfig::new();
+    let ca_pem = std::fs::read(CA_CERT_FILE).unwrap();
+    roots
+        .root_store
+        .add_pem_file(&mut ca_pem.as_slice())
+        .expect("invalid ca cert");
+
+    let tcp = TcpStream::connect("localhost:8188").await.unwrap();
+    let tls_stream = TlsConnector::from(Arc::new(roots))
+        .connect(DNSNameRef::try_from_ascii_str("localhost").unwrap(), tcp)
+        .await
+        .expect("json gateway tls handshake failed");
+
+    let (mut sender, conn) = hyper::client::conn::handshake(tls_stream).await.unwrap();
+    tokio::spawn(conn);
+
+    let resp = sender
+        .send_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/v1/identity_bundle")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    let json_resp: GetIdentityBundleResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_resp.bundle.unwrap(), spg_bundle);
+
+    info!("testing json gateway without tls...");
+
+    let sph_bundle = ProviderCoreServiceClient::connect("http://[::1]:8089")
+        .await
+        .expect("failed to connect to sph")
+        .get_identity_bundle(GetIdentityBundleRequest {
+            protocol_version: SNP_PROTOCOL_VERSION.into(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .bundle
+        .unwrap();
+
+    let sph_json_endpoint = sph_bundle
+        .dial_up_info
+        .iter()
+        .find(|e| e.is_json_http())
+        .expect("expected json endpoint in bundle");
+    assert_eq!(sph_json_endpoint.end_point, ApiEndPoint::JsonHttp as i32);
+    assert_eq!(
+        DialupInfo::best_grpc_endpoint(&sph_bundle.dial_up_info)
+            .unwrap()
+            .end_point,
+        ApiEndPoint::GrpcWeb2 as i32
+    );
+
+    let client = Client::new();
+    let resp = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://[::1]:8189/v1/identity_bundle")
+                .body(Body::from(format!(
+                    r#"{{"protocol_version":"{}"}}"#,
+                    SNP_PROTOCOL_VERSION
+                )))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    let json_resp: GetIdentityBundleResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_resp.bundle.unwrap(), sph_bundle);
+
+    let resp = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://[::1]:8189/v1/terms_of_service")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://[::1]:8189/v1/unknown")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://[::1]:8189/v1/message")
+                .body(Body::from("not json"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // we need to keep a ref to the guards so they are not dropped before we get here in case there's no panic
+    debug!("{}", provider_guards.len());
+}
@@ -111,7 +111,7 @@ async fn user_to_user() {
     // todo: figure out why this is needed - we get errors if we remove this....
     sleep(Duration::from_millis(3000)).await; // Wait for the grpc service to start
 
-    let mut spa_admin_client = ServerAdminServiceClient::connect("http://[::1]:8082")
+    let mut spa_admin_client = ServerAdminServiceClient::connect("http://[::1]:9082")
         .await
         .expect("failed to connect to spa admin service ");
 
@@ -123,11 +123,12 @@ async fn user_to_user() {
             port: 5555,
             net_id: 0,
             name: "Blockchain Service".to_string(),
+            min_api_version: "".to_string(),
         })
         .await
         .expect("failed to set blockchain service");
 
-    let mut spb_admin_client = ServerAdminServiceClient::connect("http://[::1]:8083")
+    let mut spb_admin_client = ServerAdminServiceClient::connect("http://[::1]:9083")
         .await
         .expect("failed to connect to spa admin service ");
 
@@ -139,6 +140,7 @@ async fn user_to_user() {
             port: 5555,
             net_id: 0,
             name: "Blockchain Service".to_string(),
+            min_api_version: "".to_string(),
         })
         .await
         .expect("failed to set blockchain service");
@@ -159,6 +161,7 @@ async fn user_to_user() {
                 port: 5555,
                 net_id: 0,
                 name: "Blockchain Service".to_string(),
+                min_api_version: "".to_string(),
             }),
         })
         .await
@@ -173,6 +176,7 @@ async fn user_to_user() {
                 port: 8082,
                 net_id: 0,
                 name: "ServiceProviderA".to_string(),
+                min_api_version: "".to_string(),
             }),
         })
         .await
@@ -197,6 +201,7 @@ async fn user_to_user() {
                 port: 5555,
                 net_id: 0,
                 name: "Blockchain Service".to_string(),
+                min_api_version: "".to_string(),
             }),
         })
         .await
@@ -211,6 +216,7 @@ async fn user_to_user() {
                 port: 8083,
                 net_id: 0,
                 name: "ServiceProviderB".to_string(),
+                min_api_version: "".to_string(),
             }),
         })
         .await
@@ -242,6 +248,7 @@ async fn user_to_user() {
             other_client_id: Some(client_b_entity.clone()),
             user_text: "Hi Bob, this is Alice sending you an upsetter instant message".into(),
             reply_to: 0,
+            ttl: 0,
         })
         .await
     {
@@ -256,6 +263,7 @@ async fn user_to_user() {
                     other_client_id: Some(client_a_entity.clone()),
                     user_text: "Hi Alice, this is Bob. Got your message!".into(),
                     reply_to: message_id,
+                    ttl: 0,
                 })
                 .await
             {
@@ -273,6 +281,7 @@ async fn user_to_user() {
     let resp = client_a
         .user_create_status_update_channel(UserCreateStatusUpdateChannelRequest {
             channel_name: "A Status Updates Channel".to_string(),
+            subscription_fee: 0,
         })
         .await
         .unwrap()
@@ -338,6 +347,7 @@ async fn user_to_user() {
     let resp = client_a
         .user_create_group(UserCreateGroupRequest {
             group_name: "My Upsetter Group".to_string(),
+            approve_members: false,
         })
         .await
         .unwrap()